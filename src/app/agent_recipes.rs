@@ -10,20 +10,32 @@
 //! auto_start: false
 //! trigger_events: VariableUpdate
 //! trigger_variables: deploy.request,ci.*
+//! trigger_paths: src/**/*.rs
 //! tools: local
+//! depends_on: repo-collector
+//! pipe_output_to: repo-publisher
+//! model: gpt-4o-mini
+//! thinking: off
+//! schema: digest
+//! output_var: repo.digest
+//! env: GITHUB_TOKEN,NPM_TOKEN
+//! writes: propose
+//! notify: team
+//! deliver: email:team@example.com
+//! max_tool_loops: 10
+//! checkpoints: before writing files, before pushing
 //! persona: You are a repo digest agent.
 //! ---
 //! Summarize recent repository changes and propose next actions.
 //! ```
 
-use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow, bail};
-use directories::BaseDirs;
 
 use crate::constants::DEFAULT_AGENT_INTERVAL_SECS;
+use crate::util::memini_home;
 
 #[derive(Clone, Debug)]
 pub struct AgentRecipe {
@@ -33,7 +45,35 @@ pub struct AgentRecipe {
     pub auto_start: bool,
     pub trigger_events: Vec<String>,
     pub trigger_variables: Vec<String>,
+    pub trigger_paths: Vec<String>,
     pub tools: Vec<String>,
+    /// Upstream recipes whose output this one waits for.
+    pub depends_on: Vec<String>,
+    /// Downstream recipes that run with this one's output.
+    pub pipe_output_to: Vec<String>,
+    /// Model for this recipe's runs, overriding the `daemon` route.
+    pub model: Option<String>,
+    /// Reasoning effort (`off`, `low`, `medium`, `high`), overriding the
+    /// global `/model thinking` setting.
+    pub thinking: Option<String>,
+    /// `$MEMINI_HOME/schemas/<name>.json` the final answer must match.
+    pub schema: Option<String>,
+    /// Rice variable that receives the parsed JSON of a `schema:` run.
+    pub output_var: Option<String>,
+    /// Secret names exported to this recipe's `workspace_run_command` runs.
+    pub env: Vec<String>,
+    /// `writes: propose` — file writes are queued for `/review` instead of
+    /// touching the workspace.
+    pub propose_writes: bool,
+    /// `[sinks.<name>]` that receive this recipe's results (`none` for no
+    /// sink); empty means every sink taking `daemon` events.
+    pub notify: Vec<String>,
+    /// Where else each run's output goes: `email:<address>` targets.
+    pub deliver: Vec<String>,
+    /// Tool-call rounds per run, overriding `[tool_loops] max_rounds`.
+    pub max_tool_loops: Option<usize>,
+    /// Points where a run waits for `/reply` (`before writing files`, ...).
+    pub checkpoints: Vec<String>,
     pub persona: String,
     pub instructions: String,
     pub path: PathBuf,
@@ -67,7 +107,10 @@ impl AgentRecipe {
 
     pub fn trigger_summary(&self) -> Option<String> {
         if !self.has_trigger() {
-            return None;
+            if self.trigger_paths.is_empty() {
+                return None;
+            }
+            return Some(format!("files:{}", self.trigger_paths.join(",")));
         }
         let events = if self.trigger_events.is_empty() {
             "VariableUpdate".to_string()
@@ -239,7 +282,44 @@ pub fn agents_dir() -> PathBuf {
     memini_home().join("agents")
 }
 
-fn parse_recipe_file(path: &Path, raw: &str) -> Result<AgentRecipe> {
+/// Every front-matter key [`parse_recipe_file`] reads, aliases included.
+pub(crate) const FRONT_MATTER_KEYS: &[&str] = &[
+    "name",
+    "description",
+    "interval_secs",
+    "interval",
+    "auto_start",
+    "autostart",
+    "trigger_events",
+    "events",
+    "trigger_variables",
+    "trigger_vars",
+    "trigger_keys",
+    "trigger_paths",
+    "trigger_globs",
+    "watch",
+    "tools",
+    "depends_on",
+    "after",
+    "pipe_output_to",
+    "pipe_to",
+    "model",
+    "thinking",
+    "reasoning",
+    "schema",
+    "output_var",
+    "env",
+    "writes",
+    "notify",
+    "deliver",
+    "max_tool_loops",
+    "checkpoints",
+    "persona",
+    "instructions",
+    "prompt",
+];
+
+pub(crate) fn parse_recipe_file(path: &Path, raw: &str) -> Result<AgentRecipe> {
     let (front_matter, body) = split_front_matter(raw);
 
     let fallback_name = path
@@ -282,11 +362,116 @@ fn parse_recipe_file(path: &Path, raw: &str) -> Result<AgentRecipe> {
         .map(|value| parse_csv(value))
         .unwrap_or_default();
 
+    let trigger_paths = front_matter
+        .get("trigger_paths")
+        .or_else(|| front_matter.get("trigger_globs"))
+        .or_else(|| front_matter.get("watch"))
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+
     let tools = front_matter
         .get("tools")
         .map(|value| parse_csv(value))
         .unwrap_or_default();
 
+    let depends_on = front_matter
+        .get("depends_on")
+        .or_else(|| front_matter.get("after"))
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+
+    let pipe_output_to = front_matter
+        .get("pipe_output_to")
+        .or_else(|| front_matter.get("pipe_to"))
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+
+    let model = front_matter
+        .get("model")
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    let thinking = match front_matter
+        .get("thinking")
+        .or_else(|| front_matter.get("reasoning"))
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    {
+        Some(value) => match crate::openai::parse_reasoning_setting(value) {
+            Some(effort) => Some(effort.unwrap_or_else(|| "off".to_string())),
+            None => bail!("thinking: expected off, low, medium or high, got '{value}'"),
+        },
+        None => None,
+    };
+
+    let schema = match front_matter
+        .get("schema")
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    {
+        Some(value) => Some(sanitize_name(value).context("schema")?),
+        None => None,
+    };
+
+    let output_var = front_matter
+        .get("output_var")
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+    if output_var.is_some() && schema.is_none() {
+        bail!("output_var needs a schema");
+    }
+
+    let env = front_matter
+        .get("env")
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+    for name in &env {
+        super::secrets::validate_name(name).context("env")?;
+    }
+
+    let propose_writes = match front_matter
+        .get("writes")
+        .map(|value| value.trim().to_ascii_lowercase())
+        .as_deref()
+    {
+        None | Some("" | "direct") => false,
+        Some("propose" | "review") => true,
+        Some(other) => bail!("writes: expected direct or propose, got '{other}'"),
+    };
+
+    let notify = front_matter
+        .get("notify")
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+
+    let deliver = front_matter
+        .get("deliver")
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+    for target in &deliver {
+        super::mail::validate_target(target).context("deliver")?;
+    }
+
+    let max_tool_loops = match front_matter
+        .get("max_tool_loops")
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+    {
+        Some(value) => match value.parse::<usize>() {
+            Ok(rounds) if rounds > 0 => Some(rounds),
+            _ => bail!("max_tool_loops: expected a positive number, got '{value}'"),
+        },
+        None => None,
+    };
+
+    let checkpoints = front_matter
+        .get("checkpoints")
+        .map(|value| parse_csv(value))
+        .unwrap_or_default();
+    for checkpoint in &checkpoints {
+        super::checkpoints::Checkpoint::parse(checkpoint).context("checkpoints")?;
+    }
+
     let persona = front_matter.get("persona").cloned().unwrap_or_else(|| {
         format!(
             "You are a background autonomous agent named '{name}'. \
@@ -318,14 +503,27 @@ fn parse_recipe_file(path: &Path, raw: &str) -> Result<AgentRecipe> {
         auto_start,
         trigger_events,
         trigger_variables,
+        trigger_paths,
         tools,
+        depends_on,
+        pipe_output_to,
+        model,
+        thinking,
+        schema,
+        output_var,
+        env,
+        propose_writes,
+        notify,
+        deliver,
+        max_tool_loops,
+        checkpoints,
         persona,
         instructions,
         path: path.to_path_buf(),
     })
 }
 
-fn split_front_matter(raw: &str) -> (std::collections::HashMap<String, String>, String) {
+pub(crate) fn split_front_matter(raw: &str) -> (std::collections::HashMap<String, String>, String) {
     let mut map = std::collections::HashMap::new();
     let mut lines = raw.lines();
     let Some(first) = lines.next() else {
@@ -357,6 +555,19 @@ fn split_front_matter(raw: &str) -> (std::collections::HashMap<String, String>,
     (std::collections::HashMap::new(), raw.to_string())
 }
 
+/// The file [`write_recipe_file`] would write for `spec`.
+pub fn render_new_recipe(spec: &NewAgentRecipe) -> String {
+    render_recipe_markdown(
+        &spec.name,
+        &spec.description,
+        spec.interval_secs,
+        spec.auto_start,
+        &spec.tools,
+        &spec.persona,
+        &spec.instructions,
+    )
+}
+
 fn render_recipe_markdown(
     name: &str,
     description: &str,
@@ -386,7 +597,7 @@ fn render_recipe_markdown(
     )
 }
 
-fn parse_bool(raw: &str) -> Option<bool> {
+pub(crate) fn parse_bool(raw: &str) -> Option<bool> {
     match raw.trim().to_ascii_lowercase().as_str() {
         "true" | "1" | "yes" | "on" => Some(true),
         "false" | "0" | "no" | "off" => Some(false),
@@ -394,7 +605,7 @@ fn parse_bool(raw: &str) -> Option<bool> {
     }
 }
 
-fn parse_csv(raw: &str) -> Vec<String> {
+pub(crate) fn parse_csv(raw: &str) -> Vec<String> {
     raw.split(',')
         .map(|item| item.trim())
         .filter(|item| !item.is_empty())
@@ -414,11 +625,11 @@ fn strip_quotes(raw: &str) -> &str {
     trimmed
 }
 
-fn yaml_quote(value: &str) -> String {
+pub(crate) fn yaml_quote(value: &str) -> String {
     format!("\"{}\"", value.replace('\\', "\\\\").replace('\"', "\\\""))
 }
 
-fn sanitize_name(raw: &str) -> Result<String> {
+pub(crate) fn sanitize_name(raw: &str) -> Result<String> {
     let candidate = raw
         .trim()
         .to_ascii_lowercase()
@@ -439,22 +650,15 @@ fn sanitize_name(raw: &str) -> Result<String> {
     Ok(candidate)
 }
 
-fn memini_home() -> PathBuf {
-    if let Ok(value) = env::var("MEMINI_HOME") {
-        if !value.trim().is_empty() {
-            return PathBuf::from(value);
-        }
-    }
-    if let Some(base_dirs) = BaseDirs::new() {
-        return base_dirs.home_dir().join("Memini");
-    }
-    PathBuf::from("Memini")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parse_fields(front_matter: &str) -> Result<AgentRecipe> {
+        let raw = format!("---\nname: field-check\n{front_matter}\n---\nCheck the repo.\n");
+        parse_recipe_file(Path::new("field-check.md"), &raw)
+    }
+
     #[test]
     fn parse_recipe_front_matter_and_body() {
         let raw = r#"---
@@ -511,4 +715,95 @@ React to deployment-related updates.
         assert!(!parsed.matches_trigger("Commit", Some("deploy.request")));
         assert!(!parsed.matches_trigger("VariableUpdate", Some("other.key")));
     }
+
+    #[test]
+    fn parse_trigger_paths() {
+        let parsed = parse_fields("trigger_paths: src/**/*.rs, Cargo.toml").unwrap();
+        assert_eq!(parsed.trigger_paths, vec!["src/**/*.rs", "Cargo.toml"]);
+        let parsed = parse_fields("watch: docs/*.md").unwrap();
+        assert_eq!(parsed.trigger_paths, vec!["docs/*.md"]);
+    }
+
+    #[test]
+    fn parse_depends_on_and_pipe_output_to() {
+        let parsed =
+            parse_fields("depends_on: repo-collector\npipe_output_to: repo-publisher, slack-post")
+                .unwrap();
+        assert_eq!(parsed.depends_on, vec!["repo-collector"]);
+        assert_eq!(parsed.pipe_output_to, vec!["repo-publisher", "slack-post"]);
+    }
+
+    #[test]
+    fn parse_model() {
+        let parsed = parse_fields("model: gpt-4o-mini").unwrap();
+        assert_eq!(parsed.model.as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(parse_fields("model:").unwrap().model, None);
+    }
+
+    #[test]
+    fn parse_thinking() {
+        let parsed = parse_fields("thinking: on").unwrap();
+        assert_eq!(parsed.thinking.as_deref(), Some("medium"));
+        let parsed = parse_fields("reasoning: off").unwrap();
+        assert_eq!(parsed.thinking.as_deref(), Some("off"));
+        assert_eq!(parse_fields("").unwrap().thinking, None);
+        assert!(parse_fields("thinking: extreme").is_err());
+    }
+
+    #[test]
+    fn parse_schema_and_output_var() {
+        let parsed = parse_fields("schema: triage\noutput_var: triage.latest").unwrap();
+        assert_eq!(parsed.schema.as_deref(), Some("triage"));
+        assert_eq!(parsed.output_var.as_deref(), Some("triage.latest"));
+        assert_eq!(parse_fields("").unwrap().schema, None);
+        assert!(parse_fields("output_var: triage.latest").is_err());
+    }
+
+    #[test]
+    fn parse_env() {
+        let parsed = parse_fields("env: GITHUB_TOKEN, NPM_TOKEN").unwrap();
+        assert_eq!(parsed.env, vec!["GITHUB_TOKEN", "NPM_TOKEN"]);
+        assert!(parse_fields("env: not a name").is_err());
+    }
+
+    #[test]
+    fn parse_writes() {
+        assert!(parse_fields("writes: propose").unwrap().propose_writes);
+        assert!(parse_fields("writes: review").unwrap().propose_writes);
+        assert!(!parse_fields("writes: direct").unwrap().propose_writes);
+        assert!(!parse_fields("").unwrap().propose_writes);
+        assert!(parse_fields("writes: sometimes").is_err());
+    }
+
+    #[test]
+    fn parse_notify() {
+        let parsed = parse_fields("notify: team, ops").unwrap();
+        assert_eq!(parsed.notify, vec!["team", "ops"]);
+    }
+
+    #[test]
+    fn parse_deliver() {
+        let parsed = parse_fields("deliver: email:ops@example.com").unwrap();
+        assert_eq!(parsed.deliver, vec!["email:ops@example.com"]);
+        assert!(parse_fields("deliver: carrier-pigeon").is_err());
+    }
+
+    #[test]
+    fn parse_max_tool_loops() {
+        let parsed = parse_fields("max_tool_loops: 10").unwrap();
+        assert_eq!(parsed.max_tool_loops, Some(10));
+        assert_eq!(parse_fields("").unwrap().max_tool_loops, None);
+        assert!(parse_fields("max_tool_loops: 0").is_err());
+        assert!(parse_fields("max_tool_loops: many").is_err());
+    }
+
+    #[test]
+    fn parse_checkpoints() {
+        let parsed = parse_fields("checkpoints: before writing files, before pushing").unwrap();
+        assert_eq!(
+            parsed.checkpoints,
+            vec!["before writing files", "before pushing"]
+        );
+        assert!(parse_fields("checkpoints: whenever").is_err());
+    }
 }
@@ -4,8 +4,34 @@
 //! injected into the system prompt.  Users can create custom agents on the
 //! fly with `/agent create <name> <description>` and switch between them
 //! with `/agent use <name>`.
+//!
+//! `tools` and `mcp_servers` are allowlists: a persona with `tools:
+//! workspace_read_file` cannot run shell commands, and one with
+//! `mcp_servers: github` sees no other server.  Empty lists allow
+//! everything.  Sub-agents spawned by a persona inherit its restrictions.
+//!
+//! Agents can be shared with `/agent export` / `/agent import` as JSON or as
+//! Markdown with front matter, where the body is the persona:
+//!
+//! ```text
+//! ---
+//! name: docs-writer
+//! description: Writes and edits project documentation
+//! model: gpt-5-mini
+//! tools: workspace_read_file,workspace_write_file
+//! mcp_servers: github
+//! ---
+//! You are a meticulous technical writer...
+//! ```
 
+use anyhow::{Context, Result, bail};
+use globset::GlobBuilder;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::mcp;
+
+use super::agent_recipes::split_front_matter;
 
 /// An agent persona that shapes how the LLM responds.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -16,6 +42,102 @@ pub struct Agent {
     pub description: String,
     /// The personality/instructions injected into the system prompt.
     pub persona: String,
+    /// Preferred model for chats with this agent (beats `/route`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Allowed tools: names or globs (`workspace_read_*`, `mcp:github.*`),
+    /// or `local` for every workspace tool.  Empty allows all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    /// Allowed MCP server ids.  Empty allows all.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mcp_servers: Vec<String>,
+}
+
+/// Tool and MCP server allowlists carried into a persona's chat turns and
+/// the sub-agents it spawns.  Built-in coordination and Rice memory tools
+/// are never restricted.
+#[derive(Clone, Debug, Default)]
+pub struct ToolRestrictions {
+    pub tools: Vec<String>,
+    pub mcp_servers: Vec<String>,
+}
+
+impl ToolRestrictions {
+    pub fn is_unrestricted(&self) -> bool {
+        self.tools.is_empty() && self.mcp_servers.is_empty()
+    }
+
+    pub fn allows_server(&self, server_id: &str) -> bool {
+        self.mcp_servers.is_empty()
+            || self
+                .mcp_servers
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(server_id))
+    }
+
+    pub fn allows_tool(&self, tool_name: &str) -> bool {
+        if let Some((server, tool)) = mcp::split_namespaced_tool_name(tool_name) {
+            if !self.allows_server(server) {
+                return false;
+            }
+            // MCP tools are only narrowed further by `mcp:` patterns.
+            let mcp_patterns: Vec<&String> = self
+                .tools
+                .iter()
+                .filter(|pattern| pattern.starts_with("mcp:"))
+                .collect();
+            return mcp_patterns.is_empty()
+                || mcp_patterns
+                    .iter()
+                    .any(|pattern| glob_matches(pattern, &format!("mcp:{server}.{tool}")));
+        }
+        let is_local = super::shell_sessions::SHELL_TOOLS.contains(&tool_name)
+            || super::processes::PROCESS_TOOLS.contains(&tool_name)
+            || crate::local_tools::tool_defs()
+                .iter()
+                .any(|def| def.get("name").and_then(Value::as_str) == Some(tool_name));
+        if !is_local || self.tools.is_empty() {
+            return true;
+        }
+        self.tools.iter().any(|pattern| {
+            matches!(
+                pattern.to_ascii_lowercase().as_str(),
+                "local" | "workspace" | "all" | "*"
+            ) || glob_matches(pattern, tool_name)
+        })
+    }
+
+    /// Drop OpenAI tool definitions this persona may not use.
+    pub fn retain_tools(&self, tools: Vec<Value>) -> Vec<Value> {
+        if self.is_unrestricted() {
+            return tools;
+        }
+        tools
+            .into_iter()
+            .filter(|tool| {
+                tool.get("name")
+                    .and_then(Value::as_str)
+                    .is_none_or(|name| self.allows_tool(name))
+            })
+            .collect()
+    }
+
+    /// Error returned to the model for a call outside the allowlist.
+    pub fn denied_output(tool_name: &str) -> String {
+        serde_json::json!({
+            "error": format!("Tool '{tool_name}' is not allowed for this persona")
+        })
+        .to_string()
+    }
+}
+
+fn glob_matches(pattern: &str, name: &str) -> bool {
+    GlobBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map(|glob| glob.compile_matcher().is_match(name))
+        .unwrap_or(false)
 }
 
 impl Default for Agent {
@@ -24,6 +146,145 @@ impl Default for Agent {
             name: "memini".to_string(),
             description: "Your personal CLI assistant with long-term memory".to_string(),
             persona: crate::prompts::default_memini_persona().to_string(),
+            model: None,
+            tools: Vec::new(),
+            mcp_servers: Vec::new(),
         }
     }
 }
+
+impl Agent {
+    pub fn restrictions(&self) -> ToolRestrictions {
+        ToolRestrictions {
+            tools: self.tools.clone(),
+            mcp_servers: self.mcp_servers.clone(),
+        }
+    }
+
+    /// Portable Markdown form written by `/agent export`.
+    pub fn to_markdown(&self) -> String {
+        // Values are written bare: front matter splits on the first ':'.
+        let mut front = format!(
+            "name: {}\ndescription: {}\n",
+            self.name,
+            self.description.replace('\n', " ")
+        );
+        if let Some(model) = &self.model {
+            front.push_str(&format!("model: {model}\n"));
+        }
+        if !self.tools.is_empty() {
+            front.push_str(&format!("tools: {}\n", self.tools.join(",")));
+        }
+        if !self.mcp_servers.is_empty() {
+            front.push_str(&format!("mcp_servers: {}\n", self.mcp_servers.join(",")));
+        }
+        format!("---\n{front}---\n{}\n", self.persona.trim())
+    }
+
+    /// Parse an exported agent, either JSON or Markdown with front matter.
+    /// `fallback_name` is used when Markdown front matter has no `name`.
+    pub fn from_portable(raw: &str, fallback_name: &str) -> Result<Self> {
+        let agent = if raw.trim_start().starts_with('{') {
+            serde_json::from_str::<Agent>(raw).context("parse agent JSON")?
+        } else {
+            let (front_matter, body) = split_front_matter(raw);
+            let field = |key: &str| {
+                front_matter
+                    .get(key)
+                    .map(|value| value.trim().to_string())
+                    .filter(|value| !value.is_empty())
+            };
+            let list = |key: &str| -> Vec<String> {
+                field(key)
+                    .map(|items| {
+                        items
+                            .split(',')
+                            .map(|item| item.trim().to_string())
+                            .filter(|item| !item.is_empty())
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            };
+            Agent {
+                name: field("name").unwrap_or_else(|| fallback_name.to_string()),
+                description: field("description").unwrap_or_default(),
+                persona: body.trim().to_string(),
+                model: field("model"),
+                tools: list("tools"),
+                mcp_servers: list("mcp_servers"),
+            }
+        };
+        if agent.name.trim().is_empty() || agent.name.contains(char::is_whitespace) {
+            bail!("agent name must be a single word");
+        }
+        if agent.persona.trim().is_empty() {
+            bail!("agent '{}' has no persona", agent.name);
+        }
+        Ok(agent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_markdown_and_json() {
+        let agent = Agent {
+            name: "docs-writer".to_string(),
+            description: "Writes \"great\" docs".to_string(),
+            persona: "You are a technical writer.\nBe precise.".to_string(),
+            model: Some("gpt-5-mini".to_string()),
+            tools: vec!["workspace_read_file".to_string()],
+            mcp_servers: vec!["github".to_string()],
+        };
+        let parsed = Agent::from_portable(&agent.to_markdown(), "x").unwrap();
+        assert_eq!(parsed.name, agent.name);
+        assert_eq!(parsed.description, agent.description);
+        assert_eq!(parsed.persona, agent.persona);
+        assert_eq!(parsed.model, agent.model);
+        assert_eq!(parsed.tools, agent.tools);
+        assert_eq!(parsed.mcp_servers, agent.mcp_servers);
+
+        let json = serde_json::to_string(&agent).unwrap();
+        assert_eq!(
+            Agent::from_portable(&json, "x").unwrap().persona,
+            agent.persona
+        );
+
+        let bare = Agent::from_portable("You review pull requests.", "reviewer").unwrap();
+        assert_eq!(bare.name, "reviewer");
+        assert!(Agent::from_portable("---\nname: empty\n---\n", "x").is_err());
+    }
+
+    #[test]
+    fn restrictions_filter_local_and_mcp_tools() {
+        let docs = ToolRestrictions {
+            tools: vec![
+                "workspace_read_*".to_string(),
+                "workspace_list_files".to_string(),
+            ],
+            mcp_servers: vec!["github".to_string()],
+        };
+        assert!(docs.allows_tool("workspace_read_file"));
+        assert!(!docs.allows_tool("workspace_run_command"));
+        assert!(docs.allows_tool("github__search_issues"));
+        assert!(!docs.allows_tool("linear__create_issue"));
+        assert!(docs.allows_tool("spawn_agent"));
+        assert!(docs.allows_tool("rice_memories"));
+
+        let read_only_github = ToolRestrictions {
+            tools: vec!["local".to_string(), "mcp:github.search_*".to_string()],
+            mcp_servers: Vec::new(),
+        };
+        assert!(read_only_github.allows_tool("workspace_run_command"));
+        assert!(read_only_github.allows_tool("github__search_issues"));
+        assert!(!read_only_github.allows_tool("github__create_issue"));
+
+        let defs = vec![
+            serde_json::json!({"name": "workspace_read_file"}),
+            serde_json::json!({"name": "workspace_run_command"}),
+        ];
+        assert_eq!(docs.retain_tools(defs).len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+//! Files produced by background work, kept under
+//! `$MEMINI_HOME/artifacts/<task>/<YYYYmmdd-HHMMSS>/`.
+//!
+//! Every daemon run and agent window gets an [`ArtifactRun`].  The model
+//! writes files into it with the `save_artifact` tool, and the final output
+//! is captured as `output.md`.  The run directory is only created once
+//! something is saved.  `/artifacts list|open` browses the results.
+
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use serde_json::{Value, json};
+
+use super::agent_recipes;
+use crate::constants::MAX_ARTIFACT_BYTES;
+use crate::openai::ToolCall;
+
+pub(crate) const SAVE_ARTIFACT_TOOL: &str = "save_artifact";
+/// File the final output of a run is saved as.
+pub(crate) const OUTPUT_FILE: &str = "output.md";
+
+/// `$MEMINI_HOME/artifacts`.
+pub(crate) fn artifacts_root() -> PathBuf {
+    crate::util::memini_home().join("artifacts")
+}
+
+/// Where one run's artifacts go.
+#[derive(Clone, Debug)]
+pub(crate) struct ArtifactRun {
+    dir: PathBuf,
+}
+
+impl ArtifactRun {
+    /// A run for `owner` (task name or `agent-<id>`), stamped now.
+    pub fn start(owner: &str) -> Self {
+        let owner = agent_recipes::sanitize_name(owner).unwrap_or_else(|_| "task".to_string());
+        let stamp = Local::now().format("%Y%m%d-%H%M%S").to_string();
+        ArtifactRun {
+            dir: artifacts_root().join(owner).join(stamp),
+        }
+    }
+
+    /// Write `content` to `name` (a relative path) inside the run.
+    pub fn save(&self, name: &str, content: &str) -> Result<PathBuf> {
+        self.save_bytes(name, content.as_bytes())
+    }
+
+    /// [`Self::save`] for binary content (e.g. images from MCP tools).
+    pub fn save_bytes(&self, name: &str, content: &[u8]) -> Result<PathBuf> {
+        let relative = Path::new(name.trim());
+        let valid = relative.components().next().is_some()
+            && relative
+                .components()
+                .all(|part| matches!(part, Component::Normal(_)));
+        if !valid {
+            bail!("artifact name must be a relative path without `..`: {name}");
+        }
+        if content.len() > MAX_ARTIFACT_BYTES {
+            bail!(
+                "artifact is {} bytes; the limit is {MAX_ARTIFACT_BYTES}",
+                content.len()
+            );
+        }
+        let path = self.dir.join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Create {}", parent.display()))?;
+        }
+        fs::write(&path, content).with_context(|| format!("Write {}", path.display()))?;
+        Ok(path)
+    }
+
+    /// Capture a run's final output; empty outputs are skipped.
+    pub fn save_output(&self, output: &str) -> Option<PathBuf> {
+        let output = output.trim();
+        if output.is_empty() || output == "(no output)" {
+            return None;
+        }
+        self.save(OUTPUT_FILE, output).ok()
+    }
+}
+
+/// Tool definition offered to daemons and agent windows.
+pub(crate) fn tool_def() -> Value {
+    json!({
+        "type": "function",
+        "name": SAVE_ARTIFACT_TOOL,
+        "description": "Save a deliverable (report, data file, patch, script) as a named artifact the user can open later. Use this for results worth keeping outside the chat; your final answer is saved automatically as output.md.",
+        "parameters": {
+            "type": "object",
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "File name with extension, optionally in a subfolder (e.g. 'report.md', 'data/prices.csv')."
+                },
+                "content": {
+                    "type": "string",
+                    "description": "Complete file content."
+                }
+            },
+            "required": ["name", "content"]
+        }
+    })
+}
+
+/// Handle a `save_artifact` call.
+pub(crate) fn handle_tool_call(call: &ToolCall, run: &ArtifactRun) -> String {
+    let name = call.arguments.get("name").and_then(Value::as_str);
+    let content = call.arguments.get("content").and_then(Value::as_str);
+    let result = match (name, content) {
+        (Some(name), Some(content)) => run.save(name, content),
+        _ => Err(anyhow::anyhow!("name and content are required")),
+    };
+    match result {
+        Ok(path) => json!({"status": "saved", "path": path.display().to_string()}),
+        Err(err) => json!({"error": format!("{err:#}")}),
+    }
+    .to_string()
+}
+
+/// One run directory on disk.
+#[derive(Clone, Debug)]
+pub(crate) struct RunListing {
+    pub owner: String,
+    pub stamp: String,
+    pub path: PathBuf,
+    /// Paths relative to the run directory, sorted.
+    pub files: Vec<String>,
+}
+
+/// Runs under `artifacts_root()`, newest first; only `owner`'s if given.
+pub(crate) fn list_runs(owner: Option<&str>) -> Vec<RunListing> {
+    list_runs_in(&artifacts_root(), owner)
+}
+
+fn list_runs_in(root: &Path, owner: Option<&str>) -> Vec<RunListing> {
+    let mut runs = Vec::new();
+    for owner_dir in sorted_dirs(root) {
+        let owner_name = file_name(&owner_dir);
+        if owner.is_some_and(|wanted| !wanted.eq_ignore_ascii_case(&owner_name)) {
+            continue;
+        }
+        for run_dir in sorted_dirs(&owner_dir) {
+            let mut files = Vec::new();
+            collect_files(&run_dir, &run_dir, &mut files);
+            files.sort();
+            runs.push(RunListing {
+                owner: owner_name.clone(),
+                stamp: file_name(&run_dir),
+                path: run_dir,
+                files,
+            });
+        }
+    }
+    runs.sort_by(|a, b| b.stamp.cmp(&a.stamp).then_with(|| a.owner.cmp(&b.owner)));
+    runs
+}
+
+fn sorted_dirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            collect_files(base, &path, out);
+        } else if let Ok(relative) = path.strip_prefix(base) {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(tag: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("memini-artifacts-{tag}-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    #[test]
+    fn saves_inside_the_run_and_rejects_escapes() {
+        let root = temp_root("save");
+        let run = ArtifactRun {
+            dir: root.join("digest").join("20260101-090000"),
+        };
+        let path = run.save("data/prices.csv", "a,b\n").expect("save");
+        assert!(path.ends_with("digest/20260101-090000/data/prices.csv"));
+        assert!(run.save("../escape.txt", "x").is_err());
+        assert!(run.save("/etc/passwd", "x").is_err());
+        assert!(run.save_output("(no output)").is_none());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn lists_runs_newest_first() {
+        let root = temp_root("list");
+        for (owner, stamp) in [
+            ("digest", "20260101-090000"),
+            ("briefing", "20260102-090000"),
+        ] {
+            let run = ArtifactRun {
+                dir: root.join(owner).join(stamp),
+            };
+            run.save_output("done").expect("save");
+        }
+        let runs = list_runs_in(&root, None);
+        let order: Vec<&str> = runs.iter().map(|run| run.owner.as_str()).collect();
+        assert_eq!(order, vec!["briefing", "digest"]);
+        assert_eq!(runs[0].files, vec![OUTPUT_FILE]);
+        assert_eq!(list_runs_in(&root, Some("DIGEST")).len(), 1);
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,204 @@
+//! Image attachments for the next chat turn (`/attach`).
+//!
+//! Local files are inlined as base64 `data:` URLs; `http(s)` and `data:`
+//! URLs are passed through.  Either way the image is sent as a Responses
+//! API `input_image` content part next to the message text.  Images can
+//! also arrive by paste: a pasted image path, or raw clipboard image data
+//! (encoded to PNG here).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+
+/// Largest image file the Responses API accepts.
+pub const MAX_IMAGE_BYTES: u64 = 20 * 1024 * 1024;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ImageAttachment {
+    /// File name or URL, for the activity log and thread.
+    pub label: String,
+    /// `https://…` or `data:image/…;base64,…`.
+    pub image_url: String,
+    /// Size of a local file, when known.
+    pub bytes: Option<u64>,
+}
+
+impl ImageAttachment {
+    /// Placeholder chip shown in the activity log and kept in the thread
+    /// instead of the image data.
+    pub fn chip(&self) -> String {
+        match self.bytes {
+            Some(bytes) => format!("[image: {} · {}]", self.label, human_size(bytes)),
+            None => format!("[image: {}]", self.label),
+        }
+    }
+}
+
+/// Resolve a path or URL into an attachment.
+pub fn load_image(source: &str) -> Result<ImageAttachment> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Ok(ImageAttachment {
+            label: source.to_string(),
+            image_url: source.to_string(),
+            bytes: None,
+        });
+    }
+    if source.starts_with("data:image/") {
+        return Ok(ImageAttachment {
+            label: "inline image".to_string(),
+            image_url: source.to_string(),
+            bytes: None,
+        });
+    }
+
+    let path = Path::new(source);
+    let mime = image_mime(path)
+        .with_context(|| format!("{} is not a PNG, JPEG, GIF or WebP image", path.display()))?;
+    let size = fs::metadata(path)
+        .with_context(|| format!("Read {}", path.display()))?
+        .len();
+    if size > MAX_IMAGE_BYTES {
+        bail!(
+            "{} is {}; images must be under {}",
+            path.display(),
+            human_size(size),
+            human_size(MAX_IMAGE_BYTES)
+        );
+    }
+    let data = fs::read(path).with_context(|| format!("Read {}", path.display()))?;
+    Ok(ImageAttachment {
+        label: path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or(source)
+            .to_string(),
+        image_url: format!("data:{mime};base64,{}", STANDARD.encode(data)),
+        bytes: Some(size),
+    })
+}
+
+/// Content of the user message: plain text, or text plus `input_image`
+/// parts when images are attached.
+pub fn user_content(message: &str, images: &[ImageAttachment]) -> Value {
+    if images.is_empty() {
+        return Value::String(message.to_string());
+    }
+    let mut parts = vec![json!({"type": "input_text", "text": message})];
+    parts.extend(images.iter().map(|image| {
+        json!({
+            "type": "input_image",
+            "image_url": image.image_url,
+            "detail": "auto",
+        })
+    }));
+    Value::Array(parts)
+}
+
+/// The clipboard's image, PNG-encoded, if it holds one.
+pub fn clipboard_image() -> Result<Option<ImageAttachment>> {
+    let mut clipboard = arboard::Clipboard::new().context("open clipboard")?;
+    let image = match clipboard.get_image() {
+        Ok(image) => image,
+        Err(arboard::Error::ContentNotAvailable) => return Ok(None),
+        Err(err) => return Err(err).context("read clipboard image"),
+    };
+    let png = encode_png(image.width as u32, image.height as u32, &image.bytes)?;
+    Ok(Some(ImageAttachment {
+        label: format!("clipboard {}×{}", image.width, image.height),
+        bytes: Some(png.len() as u64),
+        image_url: format!("data:image/png;base64,{}", STANDARD.encode(png)),
+    }))
+}
+
+/// A paste that is just the path of an existing image file, as terminals
+/// produce when a file is dragged onto them (quoted or `\ `-escaped).
+pub fn pasted_image_path(text: &str) -> Option<PathBuf> {
+    let text = text.trim();
+    if text.contains('\n') {
+        return None;
+    }
+    let unquoted = text
+        .strip_prefix('\'')
+        .and_then(|rest| rest.strip_suffix('\''))
+        .or_else(|| {
+            text.strip_prefix('"')
+                .and_then(|rest| rest.strip_suffix('"'))
+        })
+        .unwrap_or(text);
+    let raw = unquoted.strip_prefix("file://").unwrap_or(unquoted);
+    let path = PathBuf::from(raw.replace("\\ ", " "));
+    (image_mime(&path).is_some() && path.is_file()).then_some(path)
+}
+
+fn encode_png(width: u32, height: u32, rgba: &[u8]) -> Result<Vec<u8>> {
+    let mut out = Vec::new();
+    let mut encoder = png::Encoder::new(&mut out, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().context("encode clipboard image")?;
+    writer
+        .write_image_data(rgba)
+        .context("encode clipboard image")?;
+    writer.finish().context("encode clipboard image")?;
+    Ok(out)
+}
+
+fn image_mime(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "gif" => Some("image/gif"),
+        "webp" => Some("image/webp"),
+        _ => None,
+    }
+}
+
+fn human_size(bytes: u64) -> String {
+    if bytes >= 1024 * 1024 {
+        format!("{:.1} MB", bytes as f64 / (1024.0 * 1024.0))
+    } else {
+        format!("{} KB", bytes.div_ceil(1024))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_local_images_as_input_image_parts() {
+        let path = std::env::temp_dir().join(format!("memini-attach-{}.png", std::process::id()));
+        fs::write(&path, [0x89, b'P', b'N', b'G']).unwrap();
+        let image = load_image(path.to_str().unwrap()).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(image.image_url, "data:image/png;base64,iVBORw==");
+        assert!(image.chip().ends_with("· 1 KB]"));
+
+        let content = user_content("what's wrong here?", &[image]);
+        assert_eq!(content[0]["type"], "input_text");
+        assert_eq!(content[1]["type"], "input_image");
+        assert_eq!(user_content("hi", &[]), Value::String("hi".to_string()));
+        assert!(load_image("notes.txt").is_err());
+
+        let path = std::env::temp_dir().join(format!("memini paste {}.jpg", std::process::id()));
+        fs::write(&path, [0xff, 0xd8]).unwrap();
+        let escaped = path.to_str().unwrap().replace(' ', "\\ ");
+        assert_eq!(
+            pasted_image_path(&format!("{escaped}\n")),
+            Some(path.clone())
+        );
+        assert_eq!(
+            pasted_image_path(&format!("'{}'", path.display())),
+            Some(path.clone())
+        );
+        fs::remove_file(&path).unwrap();
+        assert_eq!(pasted_image_path("just some text"), None);
+    }
+}
@@ -9,9 +9,13 @@ use crate::constants::OPENAI_KEY_VAR;
 use crate::mcp;
 
 use super::App;
-use super::daemon;
+use super::agents::Agent;
+use super::attachments::ImageAttachment;
+use super::context_files;
+use super::daemon::{self, AgentEvent, ChatLogLevel};
 use super::log_src;
 use super::logging::{LogLevel, mask_key};
+use super::pins;
 
 impl App {
     /// Launch a non-blocking chat turn.
@@ -20,9 +24,21 @@ impl App {
     /// runs on a background tokio task via `daemon::spawn_chat_task`.
     /// The function returns immediately so the TUI draw loop keeps running.
     pub(crate) fn handle_chat_message(&mut self, message: &str, _require_mcp: bool) {
+        let agent = self.active_agent.clone();
+        self.handle_chat_message_as(message, agent, None);
+    }
+
+    /// Launch a chat turn with a one-off persona and model (`/prompt run`).
+    pub(crate) fn handle_chat_message_as(
+        &mut self,
+        message: &str,
+        agent: Agent,
+        model: Option<String>,
+    ) {
         let key = match self.ensure_openai_key() {
             Ok(k) => k,
             Err(err) => {
+                self.chat_busy = false;
                 log_src!(self, LogLevel::Error, format!("OpenAI key missing: {err}"));
                 self.log(
                     LogLevel::Info,
@@ -32,26 +48,94 @@ impl App {
             }
         };
 
+        let chips: Vec<String> = self.pending_images.iter().map(|i| i.chip()).collect();
+        for chip in chips {
+            self.log(LogLevel::Info, format!("  📎 {chip}"));
+        }
+        let images = std::mem::take(&mut self.pending_images);
+        self.launch_chat_task(key, message, agent, model, images, false);
+    }
+
+    /// Assemble a turn for the active agent — recall, skills, tool schemas —
+    /// and report it as a `ChatMarkdown` event instead of calling the model
+    /// (`/dry`, `memini --dry-run`).  Attached images stay queued and no key
+    /// is needed.
+    pub(crate) fn dry_run_chat_message(&mut self, message: &str) {
+        let key = self.ensure_openai_key().unwrap_or_default();
+        let agent = self.active_agent.clone();
+        let images = self.pending_images.clone();
+        self.launch_chat_task(key, message, agent, None, images, true);
+    }
+
+    /// `memini --dry-run <message>`: the dry-run report without a TUI.
+    /// Warnings go to stderr.
+    pub fn dry_run_report(&mut self, message: &str) -> Result<String> {
+        self.dry_run_chat_message(message);
+        let mut report = String::new();
+        while let Some(event) = self.daemon_rx.blocking_recv() {
+            match event {
+                AgentEvent::ChatMarkdown { body, .. } => report = body,
+                AgentEvent::ChatProgress {
+                    line,
+                    level: ChatLogLevel::Warn | ChatLogLevel::Error,
+                } => eprintln!("{line}"),
+                AgentEvent::ChatFinished { .. } if report.is_empty() => {
+                    return Err(anyhow!("dry run failed before the request was built"));
+                }
+                AgentEvent::ChatFinished { .. } => return Ok(report),
+                _ => {}
+            }
+        }
+        Err(anyhow!("chat task ended without a report"))
+    }
+
+    fn launch_chat_task(
+        &mut self,
+        key: String,
+        message: &str,
+        agent: Agent,
+        model: Option<String>,
+        images: Vec<ImageAttachment>,
+        dry_run: bool,
+    ) {
+        let model = model.or_else(|| agent.model.clone());
+
         // Snapshot everything the background task needs (all Clone / Send).
-        let mcp_snapshots = self.build_mcp_snapshots(None);
+        let restrictions = agent.restrictions();
+        let mcp_snapshots: Vec<daemon::McpServerSnapshot> = self
+            .build_mcp_snapshots(None)
+            .into_iter()
+            .filter(|snap| restrictions.allows_server(&snap.server.id))
+            .map(|mut snap| {
+                snap.openai_tools = restrictions.retain_tools(snap.openai_tools);
+                snap
+            })
+            .collect();
         let builtin_tools = Self::builtin_tool_defs();
 
         let params = daemon::ChatTaskParams {
             key,
             message: message.to_string(),
-            persona: self.active_agent.persona.clone(),
-            agent_name: self.active_agent.name.clone(),
+            persona: agent.persona,
+            agent_name: agent.name,
+            model,
             skill_context: self.skills_prompt_context(message),
+            pinned_context: pins::load_pins().render().content,
+            project_context: context_files::load().content,
             memory_limit: self.memory_limit,
             conversation_thread: self.conversation_thread.clone(),
             mcp_snapshots,
             builtin_tools,
             next_window_id: self.next_window_id.clone(),
+            sub_agents: self.sub_agents.clone(),
+            restrictions,
+            images,
+            dry_run,
         };
 
         let tx = self.daemon_tx.clone();
         let openai = self.openai.clone();
-        let rice_handle = self.runtime.spawn(crate::rice::RiceStore::connect());
+        let rice_handle = self.spawn_rice_handle();
         let rt = self.runtime.handle().clone();
 
         daemon::spawn_chat_task(params, tx, openai, rice_handle, rt);
@@ -62,7 +146,7 @@ impl App {
         let spawn_tool = json!({
             "type": "function",
             "name": "spawn_agent",
-            "description": "Spawn an independent execution agent in its own grid window. Each agent gets its own memory context, workspace tools (file read/write + shell command), and full tool loop; MCP tools are added when available. Use this to run sub-tasks in parallel. For code/document tasks, instruct the worker to create or edit files directly and run commands for verification. Pass a coordination_key so you can later collect results with collect_results. Never claim a sub-agent is running unless you called this tool successfully in the current turn.",
+            "description": "Spawn an independent execution agent in its own grid window. Each agent gets its own memory context, workspace tools (file read/write + shell command), and full tool loop; MCP tools are added when available. Use this to run sub-tasks in parallel. For code/document tasks, instruct the worker to create or edit files directly and run commands for verification. Call wait_for_agent with the returned window_id to get its result, or pass a coordination_key so you can later collect results with collect_results. Never claim a sub-agent is running unless you called this tool successfully in the current turn.",
             "parameters": {
                 "type": "object",
                 "properties": {
@@ -101,6 +185,25 @@ impl App {
                 "required": ["coordination_key"]
             }
         });
+        let wait_tool = json!({
+            "type": "function",
+            "name": "wait_for_agent",
+            "description": "Wait for an agent started with spawn_agent to finish and return its final output. Use this to delegate work and then collect the outcome in the same turn. Returns status 'done', 'needs_input' (with the agent's question as output), or 'running' with timed_out=true if it is still working. Results you do not wait for are added to the conversation automatically when the agent finishes.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "window_id": {
+                        "type": "integer",
+                        "description": "The window_id returned by spawn_agent."
+                    },
+                    "timeout_secs": {
+                        "type": "integer",
+                        "description": "Optional. Seconds to wait before giving up (default 120, max 600)."
+                    }
+                },
+                "required": ["window_id"]
+            }
+        });
         let rice_memories_tool = json!({
             "type": "function",
             "name": "rice_memories",
@@ -138,6 +241,7 @@ impl App {
             rice_memories_tool,
             rice_state_get_tool,
             spawn_tool,
+            wait_tool,
             collect_tool,
         ]
     }
@@ -175,6 +279,7 @@ impl App {
             snapshots.push(daemon::McpServerSnapshot {
                 server: conn.server.clone(),
                 bearer,
+                backend: self.backends.mcp.clone(),
                 openai_tools,
             });
         }
@@ -182,7 +287,7 @@ impl App {
     }
 
     /// Ensure an OpenAI API key is available, loading from Rice or env if needed.
-    fn ensure_openai_key(&mut self) -> Result<String> {
+    pub(crate) fn ensure_openai_key(&mut self) -> Result<String> {
         if let Some(key) = &self.openai_key {
             return Ok(key.clone());
         }
@@ -0,0 +1,145 @@
+//! Chat messages typed while a turn is in flight.
+//!
+//! Instead of being rejected, plain input sent while the chat is busy is
+//! queued and dispatched one message per finished turn, oldest first.  The
+//! prompt shows how many are waiting, and `/queue` lists, edits or cancels
+//! them before they are sent.
+
+use std::collections::VecDeque;
+
+use super::App;
+use super::logging::LogLevel;
+
+/// A message waiting for the current chat turn to finish.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct QueuedMessage {
+    /// Stable id for `/queue edit|cancel` (not reused within a session).
+    pub id: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ChatQueue {
+    messages: VecDeque<QueuedMessage>,
+    next_id: usize,
+}
+
+impl ChatQueue {
+    /// Queue `text` and return its id.
+    pub fn push(&mut self, text: &str) -> usize {
+        self.next_id += 1;
+        self.messages.push_back(QueuedMessage {
+            id: self.next_id,
+            text: text.to_string(),
+        });
+        self.next_id
+    }
+
+    pub fn pop(&mut self) -> Option<QueuedMessage> {
+        self.messages.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &QueuedMessage> {
+        self.messages.iter()
+    }
+
+    /// Replace the text of message `id`.  Returns `false` if it is gone.
+    pub fn edit(&mut self, id: usize, text: &str) -> bool {
+        match self.messages.iter_mut().find(|message| message.id == id) {
+            Some(message) => {
+                message.text = text.to_string();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<QueuedMessage> {
+        let index = self.messages.iter().position(|message| message.id == id)?;
+        self.messages.remove(index)
+    }
+
+    /// Drop every queued message and return how many there were.
+    pub fn clear(&mut self) -> usize {
+        let count = self.messages.len();
+        self.messages.clear();
+        count
+    }
+}
+
+impl App {
+    /// Queue a chat message typed while a turn is in flight.
+    pub(crate) fn queue_chat_message(&mut self, line: &str) {
+        let id = self.chat_queue.push(line);
+        self.log(
+            LogLevel::Info,
+            format!(
+                "⧗ Queued #{id} ({} waiting) — sent when the current turn finishes. /queue to edit or cancel.",
+                self.chat_queue.len()
+            ),
+        );
+    }
+
+    /// Send the oldest queued message once the chat is idle (every tick).
+    pub(crate) fn dispatch_queued_chat(&mut self) {
+        if self.chat_busy {
+            return;
+        }
+        let Some(message) = self.chat_queue.pop() else {
+            return;
+        };
+        self.log(LogLevel::Info, format!("› {}", message.text));
+        self.chat_busy = true;
+        self.handle_chat_message(&message.text, false);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::harness::Harness;
+
+    #[test]
+    fn queue_edits_and_cancels_by_stable_id() {
+        let mut queue = ChatQueue::default();
+        let first = queue.push("summarize the diff");
+        let second = queue.push("then open a PR");
+        assert!(queue.edit(second, "then draft the PR text"));
+        assert_eq!(
+            queue.remove(first).map(|m| m.text).as_deref(),
+            Some("summarize the diff")
+        );
+        // Ids are not reused after a removal.
+        let third = queue.push("and ping the team");
+        assert_eq!(third, 3);
+        let texts: Vec<&str> = queue.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["then draft the PR text", "and ping the team"]);
+        assert!(!queue.edit(first, "gone"));
+    }
+
+    #[test]
+    fn messages_typed_while_busy_are_sent_after_the_turn() {
+        let mut harness = Harness::new();
+        harness.mocks.llm.reply_text("First answer.");
+        harness.mocks.llm.reply_text("Second answer.");
+        harness.app.input = "first question".to_string();
+        harness.app.submit_input().expect("submit");
+        harness.app.input = "second question".to_string();
+        harness.app.submit_input().expect("submit");
+        assert_eq!(harness.app.chat_queue.len(), 1);
+
+        harness.settle();
+        harness.app.dispatch_queued_chat();
+        harness.settle();
+        assert!(harness.app.chat_queue.is_empty());
+        assert_eq!(harness.mocks.llm.requests("responses").len(), 2);
+    }
+}
@@ -0,0 +1,453 @@
+//! Human checkpoints in daemon recipes — `checkpoints:` front matter that
+//! stops a scheduled run before it acts and waits for `/reply`.
+//!
+//! ```text
+//! checkpoints: before writing files, before pushing
+//! ```
+//!
+//! Forms: `before writing files` (write, move, copy, delete, mkdir),
+//! `before running commands`, `before pushing` (a command containing
+//! `git push`), `before running <text>` (a command containing the text)
+//! and `before calling <tool-glob>`.
+//!
+//! When a round of tool calls hits a checkpoint, the run opens a window in
+//! WaitingForInput that lists what it was about to do, gives back its
+//! concurrency slot and waits.  `/reply <id> <message>` resumes it: the
+//! calls run and the message is passed to the model.  `/reply <id> stop`
+//! (or `no`) refuses them instead, along with every later call the same
+//! checkpoint covers in that run.  Each checkpoint asks once per run.
+//! There is no timeout; `/kill`, `/halt` and quitting count as `stop`.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Result, bail};
+use globset::GlobBuilder;
+use serde_json::json;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::local_tools::COMMAND_TOOLS;
+use crate::openai::ToolCall;
+
+use super::App;
+use super::daemon::{AgentEvent, AgentWindow, AgentWindowStatus};
+use super::log_src;
+use super::logging::LogLevel;
+use super::notifications::NotifyEvent;
+use super::shutdown;
+use super::sinks::SinkEvent;
+
+/// Tools `before writing files` covers.
+const WRITE_TOOLS: &[&str] = &[
+    "workspace_write_file",
+    "workspace_move",
+    "workspace_copy",
+    "workspace_delete",
+    "workspace_mkdir",
+];
+
+/// Replies that refuse the gated calls.
+const STOP_REPLIES: &[&str] = &["stop", "no", "deny", "cancel", "abort"];
+
+/// Longest argument preview in the intended-actions summary.
+const ARGUMENT_PREVIEW_CHARS: usize = 200;
+
+/// One `checkpoints:` entry.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Checkpoint {
+    /// The entry as written, for the window and the log.
+    pub label: String,
+    gate: Gate,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Gate {
+    WriteFiles,
+    Commands,
+    /// Commands containing this text (lowercase).
+    Command(String),
+    /// Tool names matching this glob.
+    Tool(String),
+}
+
+impl Checkpoint {
+    pub fn parse(raw: &str) -> Result<Self> {
+        let label = raw.trim().to_string();
+        let lower = label.to_ascii_lowercase();
+        let rest = lower.strip_prefix("before ").unwrap_or(&lower).trim();
+        let gate = match rest {
+            "writing files" | "writing" | "writes" | "file writes" => Gate::WriteFiles,
+            "running commands" | "commands" => Gate::Commands,
+            "pushing" | "push" => Gate::Command("git push".to_string()),
+            _ => {
+                if let Some(text) = rest.strip_prefix("running ") {
+                    Gate::Command(text.trim().trim_matches('`').to_string())
+                } else if let Some(pattern) = rest.strip_prefix("calling ") {
+                    let pattern = pattern.trim().trim_matches('`').to_string();
+                    if let Err(err) = GlobBuilder::new(&pattern).build() {
+                        bail!("checkpoint '{label}': invalid tool glob: {err}");
+                    }
+                    Gate::Tool(pattern)
+                } else {
+                    bail!(
+                        "checkpoint '{label}': expected before writing files, before running \
+                         commands, before pushing, before running <text> or before calling <tool>"
+                    );
+                }
+            }
+        };
+        if matches!(&gate, Gate::Command(text) | Gate::Tool(text) if text.is_empty()) {
+            bail!("checkpoint '{label}' names nothing to wait for");
+        }
+        Ok(Checkpoint { label, gate })
+    }
+
+    /// Whether `call` may only run once this checkpoint is passed.
+    pub fn covers(&self, call: &ToolCall) -> bool {
+        match &self.gate {
+            Gate::WriteFiles => WRITE_TOOLS.contains(&call.name.as_str()),
+            Gate::Commands => COMMAND_TOOLS.contains(&call.name.as_str()),
+            Gate::Command(text) => {
+                COMMAND_TOOLS.contains(&call.name.as_str())
+                    && call
+                        .arguments
+                        .get("command")
+                        .and_then(|value| value.as_str())
+                        .is_some_and(|command| command.to_ascii_lowercase().contains(text))
+            }
+            Gate::Tool(pattern) => GlobBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+                .is_ok_and(|glob| glob.compile_matcher().is_match(&call.name)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GateState {
+    Ahead,
+    Passed,
+    Stopped,
+}
+
+/// A run's checkpoints and which of them it has passed.
+#[derive(Debug)]
+pub(crate) struct RunGates {
+    checkpoints: Vec<Checkpoint>,
+    states: Vec<GateState>,
+}
+
+impl RunGates {
+    /// Gates for a task's `checkpoints:` (entries that do not parse were
+    /// rejected when the recipe loaded and are skipped here).
+    pub fn new(raw: &[String]) -> Self {
+        let checkpoints: Vec<Checkpoint> = raw
+            .iter()
+            .filter_map(|entry| Checkpoint::parse(entry).ok())
+            .collect();
+        let states = vec![GateState::Ahead; checkpoints.len()];
+        RunGates {
+            checkpoints,
+            states,
+        }
+    }
+
+    /// The first checkpoint not yet reached that covers one of `calls`.
+    pub fn reached(&self, calls: &[ToolCall]) -> Option<usize> {
+        self.checkpoints
+            .iter()
+            .zip(&self.states)
+            .position(|(checkpoint, state)| {
+                *state == GateState::Ahead && calls.iter().any(|call| checkpoint.covers(call))
+            })
+    }
+
+    pub fn label(&self, index: usize) -> &str {
+        &self.checkpoints[index].label
+    }
+
+    pub fn pass(&mut self, index: usize) {
+        self.states[index] = GateState::Passed;
+    }
+
+    pub fn stop(&mut self, index: usize) {
+        self.states[index] = GateState::Stopped;
+    }
+
+    /// Error output for a call behind a checkpoint the user stopped at.
+    pub fn refused(&self, call: &ToolCall) -> Option<String> {
+        let (checkpoint, _) = self
+            .checkpoints
+            .iter()
+            .zip(&self.states)
+            .find(|(checkpoint, state)| **state == GateState::Stopped && checkpoint.covers(call))?;
+        Some(
+            json!({
+                "error": format!(
+                    "The user stopped this run at checkpoint '{}'; do not retry. Report what you would have done instead.",
+                    checkpoint.label
+                )
+            })
+            .to_string(),
+        )
+    }
+}
+
+/// Whether a `/reply` to a checkpoint refuses the gated calls.
+pub(crate) fn is_stop(reply: &str) -> bool {
+    let word = reply
+        .trim()
+        .trim_end_matches(['.', '!'])
+        .to_ascii_lowercase();
+    STOP_REPLIES.contains(&word.as_str())
+}
+
+/// Markdown list of what a round was about to do: the model's words, then
+/// each tool call with a short argument preview.
+pub(crate) fn intended_actions(output_text: &str, calls: &[ToolCall]) -> String {
+    let mut lines = Vec::new();
+    if !output_text.trim().is_empty() {
+        lines.push(output_text.trim().to_string());
+        lines.push(String::new());
+    }
+    for call in calls {
+        let detail = match call.arguments.get("command").and_then(|v| v.as_str()) {
+            Some(command) => format!("`{command}`"),
+            None => match crate::local_tools::preview_write(call) {
+                Some(change) => {
+                    let (added, removed) = change.stats();
+                    format!("{} (+{added} -{removed})", change.path)
+                }
+                None => {
+                    let preview: String = call
+                        .arguments
+                        .to_string()
+                        .chars()
+                        .take(ARGUMENT_PREVIEW_CHARS)
+                        .collect();
+                    format!("`{preview}`")
+                }
+            },
+        };
+        lines.push(format!("- {}: {detail}", call.name));
+    }
+    lines.join("\n")
+}
+
+// ── Waiting for /reply ───────────────────────────────────────────────
+
+static NEXT_CHECKPOINT_ID: AtomicUsize = AtomicUsize::new(1);
+
+fn pending() -> &'static Mutex<HashMap<usize, oneshot::Sender<String>>> {
+    static PENDING: OnceLock<Mutex<HashMap<usize, oneshot::Sender<String>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Announce that task `task_name` reached `checkpoint` and wait for the
+/// reply.  `None` when the checkpoint was cancelled or memini is quitting.
+pub(crate) async fn wait(
+    task_name: &str,
+    checkpoint: &str,
+    actions: String,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Option<String> {
+    let checkpoint_id = NEXT_CHECKPOINT_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = oneshot::channel();
+    if let Ok(mut pending) = pending().lock() {
+        pending.insert(checkpoint_id, sender);
+    }
+    let _ = tx.send(AgentEvent::CheckpointReached {
+        checkpoint_id,
+        task_name: task_name.to_string(),
+        checkpoint: checkpoint.to_string(),
+        actions,
+    });
+    let reply = tokio::select! {
+        reply = receiver => reply.ok(),
+        _ = shutdown::wait() => None,
+    };
+    if let Ok(mut pending) = pending().lock() {
+        pending.remove(&checkpoint_id);
+    }
+    reply
+}
+
+/// Hand `reply` to the run waiting at `checkpoint_id`; `false` when it is
+/// no longer waiting.
+fn resolve(checkpoint_id: usize, reply: &str) -> bool {
+    let sender = pending()
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.remove(&checkpoint_id));
+    match sender {
+        Some(sender) => sender.send(reply.to_string()).is_ok(),
+        None => false,
+    }
+}
+
+/// Drop a pending checkpoint, which the waiting run treats as `stop`.
+fn cancel(checkpoint_id: usize) {
+    if let Ok(mut pending) = pending().lock() {
+        pending.remove(&checkpoint_id);
+    }
+}
+
+impl App {
+    /// Open a waiting window for a daemon run that reached a checkpoint.
+    pub(crate) fn open_checkpoint_window(
+        &mut self,
+        checkpoint_id: usize,
+        task_name: String,
+        checkpoint: String,
+        actions: String,
+    ) {
+        let window_id = self.next_window_id.fetch_add(1, Ordering::SeqCst);
+        let question = format!(
+            "Checkpoint '{checkpoint}' reached. /reply {window_id} <message> to continue, /reply {window_id} stop to refuse."
+        );
+        let mut output_lines = vec![format!("-- {task_name} paused: {checkpoint} --")];
+        output_lines.extend(actions.lines().map(str::to_string));
+        output_lines.push(format!(">> Waiting for your input: {question}"));
+        self.agent_windows.push(AgentWindow {
+            output_lines,
+            pending_question: Some(question),
+            ..AgentWindow::new(
+                window_id,
+                format!("{task_name} checkpoint"),
+                actions.clone(),
+                AgentWindowStatus::WaitingForInput,
+            )
+        });
+        self.checkpoint_windows.insert(window_id, checkpoint_id);
+        self.enqueue_waiting_window(window_id);
+        self.log(
+            LogLevel::Warn,
+            format!("⏸ Daemon {task_name} reached checkpoint '{checkpoint}' (Agent #{window_id})."),
+        );
+        self.log_markdown(format!("{task_name} intends to"), actions);
+        self.log(
+            LogLevel::Info,
+            format!("   /reply {window_id} <message> to continue  or  /reply {window_id} stop"),
+        );
+        self.notify(
+            NotifyEvent::Waiting,
+            format!("{task_name} reached a checkpoint"),
+            checkpoint.clone(),
+        );
+        self.post_to_sinks(
+            SinkEvent::Waiting,
+            Vec::new(),
+            format!("{task_name} reached a checkpoint"),
+            checkpoint,
+        );
+    }
+
+    /// `/reply` to a checkpoint window; `None` when `window_id` is not one.
+    pub(crate) fn reply_to_checkpoint(&mut self, window_id: usize, reply: &str) -> Option<bool> {
+        let checkpoint_id = *self.checkpoint_windows.get(&window_id)?;
+        let waiting = self
+            .agent_windows
+            .iter()
+            .any(|w| w.id == window_id && w.status == AgentWindowStatus::WaitingForInput);
+        if !waiting {
+            return Some(false);
+        }
+        let resumed = resolve(checkpoint_id, reply);
+        let outcome = match (resumed, is_stop(reply)) {
+            (false, _) => "the run ended before the reply",
+            (true, true) => "stopped",
+            (true, false) => "resumed",
+        };
+        if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+            win.output_lines.push(format!(">> You: {reply}"));
+            win.output_lines.push(format!("-- {outcome} --"));
+            win.status = AgentWindowStatus::Done;
+            win.pending_question = None;
+        }
+        self.dequeue_waiting_window(window_id);
+        if resumed {
+            self.log(
+                LogLevel::Info,
+                format!("Checkpoint #{window_id} {outcome}: {reply}"),
+            );
+        } else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Checkpoint #{window_id}: {outcome}.")
+            );
+        }
+        Some(true)
+    }
+
+    /// A checkpoint window was killed or halted: stop the waiting run.
+    pub(crate) fn checkpoint_window_stopped(&mut self, window_id: usize) {
+        if let Some(checkpoint_id) = self.checkpoint_windows.get(&window_id) {
+            cancel(*checkpoint_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn call(name: &str, arguments: serde_json::Value) -> ToolCall {
+        ToolCall {
+            call_id: "call-1".to_string(),
+            name: name.to_string(),
+            arguments,
+        }
+    }
+
+    #[test]
+    fn parses_checkpoints_and_gates_calls_once_per_run() {
+        let push = call(
+            "workspace_run_command",
+            json!({"command": "git push origin main"}),
+        );
+        let test = call("workspace_run_command", json!({"command": "cargo test"}));
+        let write = call(
+            "workspace_write_file",
+            json!({"path": "notes.md", "content": "hi"}),
+        );
+
+        assert!(
+            Checkpoint::parse("before writing files")
+                .unwrap()
+                .covers(&write)
+        );
+        assert!(Checkpoint::parse("Before pushing").unwrap().covers(&push));
+        assert!(!Checkpoint::parse("before pushing").unwrap().covers(&test));
+        assert!(
+            Checkpoint::parse("before running cargo")
+                .unwrap()
+                .covers(&test)
+        );
+        assert!(
+            Checkpoint::parse("before calling workspace_*")
+                .unwrap()
+                .covers(&write)
+        );
+        assert!(Checkpoint::parse("before lunch").is_err());
+        assert!(Checkpoint::parse("before running ").is_err());
+
+        let mut gates = RunGates::new(&[
+            "before writing files".to_string(),
+            "before pushing".to_string(),
+        ]);
+        assert_eq!(gates.reached(std::slice::from_ref(&test)), None);
+        assert_eq!(gates.reached(&[test.clone(), push.clone()]), Some(1));
+        gates.pass(1);
+        assert_eq!(gates.reached(std::slice::from_ref(&push)), None);
+        assert_eq!(gates.reached(std::slice::from_ref(&write)), Some(0));
+        gates.stop(0);
+        assert_eq!(gates.reached(std::slice::from_ref(&write)), None);
+        assert!(gates.refused(&write).is_some());
+        assert!(gates.refused(&push).is_none());
+
+        assert!(is_stop(" Stop."));
+        assert!(!is_stop("go ahead, but skip the tag"));
+    }
+}
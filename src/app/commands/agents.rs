@@ -1,5 +1,17 @@
 //! `/agent`, `/thread`, and `/memory` command handlers — persona
-//! management, conversation threads, and memory search.
+//! management and sharing, conversation threads, and memory search and
+//! compaction.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use rice::rice_state::proto::Trace;
+
+use crate::constants::MAX_MEMORY_EXPORT_TRACES;
+use crate::importance::{self, TraceMeta};
+use crate::provenance;
 
 use super::super::App;
 use super::super::agents::Agent;
@@ -47,6 +59,35 @@ impl App {
                 }
             }
             "info" => self.show_agent_info(),
+            "restrict" => match (args.get(1), args.get(2)) {
+                (Some(name), Some(kind)) if matches!(*kind, "tools" | "mcp") => {
+                    self.restrict_agent(name, kind, &args[3..]);
+                }
+                _ => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /agent restrict <name> tools|mcp <a,b,...|all>".to_string()
+                ),
+            },
+            "export" => match (args.get(1), args.get(2)) {
+                (Some(name), Some(path)) => self.export_agent(name, path),
+                _ => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /agent export <name> <path.md|path.json>".to_string()
+                ),
+            },
+            "import" => {
+                let force = args[1..].contains(&"--force");
+                match args[1..].iter().find(|arg| !arg.starts_with("--")) {
+                    Some(source) => self.import_agent(source, force),
+                    None => log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /agent import <path|url> [--force]".to_string()
+                    ),
+                }
+            }
             _ => self.list_agents(),
         }
     }
@@ -146,21 +187,12 @@ impl App {
             name: name.to_string(),
             description: description.to_string(),
             persona,
+            model: None,
+            tools: Vec::new(),
+            mcp_servers: Vec::new(),
         };
         self.custom_agents.push(agent);
-
-        let agents_json =
-            serde_json::to_value(&self.custom_agents).unwrap_or(serde_json::Value::Array(vec![]));
-        if let Err(err) = self
-            .runtime
-            .block_on(self.rice.save_custom_agents(agents_json))
-        {
-            log_src!(
-                self,
-                LogLevel::Warn,
-                format!("Failed to save agents: {err:#}")
-            );
-        }
+        self.persist_custom_agents();
 
         self.log(
             LogLevel::Info,
@@ -195,6 +227,11 @@ impl App {
                 .block_on(self.rice.save_active_agent_name("memini"));
         }
 
+        self.persist_custom_agents();
+        self.log(LogLevel::Info, format!("Persona '{name}' deleted."));
+    }
+
+    fn persist_custom_agents(&mut self) {
         let agents_json =
             serde_json::to_value(&self.custom_agents).unwrap_or(serde_json::Value::Array(vec![]));
         if let Err(err) = self
@@ -207,8 +244,162 @@ impl App {
                 format!("Failed to save agents: {err:#}")
             );
         }
+    }
 
-        self.log(LogLevel::Info, format!("Persona '{name}' deleted."));
+    /// Set a custom agent's tool or MCP server allowlist (`all` clears it).
+    fn restrict_agent(&mut self, name: &str, kind: &str, values: &[&str]) {
+        let list: Vec<String> = values
+            .iter()
+            .flat_map(|value| value.split(','))
+            .map(|item| item.trim().to_string())
+            .filter(|item| !item.is_empty() && item != "all")
+            .collect();
+        let Some(agent) = self.custom_agents.iter_mut().find(|a| a.name == name) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Agent '{name}' not found (the built-in 'memini' agent is unrestricted).")
+            );
+            return;
+        };
+        if kind == "tools" {
+            agent.tools = list.clone();
+        } else {
+            agent.mcp_servers = list.clone();
+        }
+        let updated = agent.clone();
+        if self.active_agent.name == name {
+            self.active_agent = updated;
+        }
+        self.persist_custom_agents();
+        let what = if kind == "tools" {
+            "tools"
+        } else {
+            "MCP servers"
+        };
+        let summary = if list.is_empty() {
+            "all".to_string()
+        } else {
+            list.join(", ")
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Agent '{name}' may now use {what}: {summary}."),
+        );
+    }
+
+    /// Write an agent to `path`: JSON for `.json`, Markdown otherwise.
+    fn export_agent(&mut self, name: &str, path: &str) {
+        let agent = if name == "memini" {
+            Some(Agent::default())
+        } else {
+            self.custom_agents.iter().find(|a| a.name == name).cloned()
+        };
+        let Some(agent) = agent else {
+            log_src!(self, LogLevel::Warn, format!("Agent '{name}' not found."));
+            return;
+        };
+        let is_json = Path::new(path)
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+        let contents = if is_json {
+            serde_json::to_string_pretty(&agent).map_err(anyhow::Error::from)
+        } else {
+            Ok(agent.to_markdown())
+        };
+        match contents
+            .and_then(|contents| fs::write(path, contents).with_context(|| format!("write {path}")))
+        {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Exported agent '{name}' to {path}."),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Agent export failed: {err:#}")
+            ),
+        }
+    }
+
+    /// Add an agent from a local file or an http(s) URL.
+    fn import_agent(&mut self, source: &str, force: bool) {
+        let agent = match self.read_portable_agent(source) {
+            Ok(agent) => agent,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Agent import failed: {err:#}")
+                );
+                return;
+            }
+        };
+        if agent.name == "memini" {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Cannot override the built-in 'memini' agent.".to_string()
+            );
+            return;
+        }
+        let existing = self.custom_agents.iter().position(|a| a.name == agent.name);
+        match existing {
+            Some(_) if !force => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!(
+                        "Agent '{}' already exists. Re-run with --force to replace it.",
+                        agent.name
+                    )
+                );
+                return;
+            }
+            Some(index) => self.custom_agents[index] = agent.clone(),
+            None => self.custom_agents.push(agent.clone()),
+        }
+        if self.active_agent.name == agent.name {
+            self.active_agent = agent.clone();
+        }
+        self.persist_custom_agents();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Imported agent '{}'{}. Use /agent use {} to switch.",
+                agent.name,
+                if existing.is_some() {
+                    " (replaced)"
+                } else {
+                    ""
+                },
+                agent.name
+            ),
+        );
+    }
+
+    fn read_portable_agent(&mut self, source: &str) -> Result<Agent> {
+        let fallback_name = source
+            .rsplit('/')
+            .next()
+            .and_then(|file| file.split('.').next())
+            .filter(|stem| !stem.is_empty())
+            .unwrap_or("imported")
+            .to_string();
+        let raw = if source.starts_with("http://") || source.starts_with("https://") {
+            self.runtime.block_on(async {
+                let response = reqwest::get(source)
+                    .await
+                    .with_context(|| format!("fetch {source}"))?;
+                if !response.status().is_success() {
+                    bail!("fetch {source}: HTTP {}", response.status());
+                }
+                response.text().await.context("read response body")
+            })?
+        } else {
+            fs::read_to_string(source).with_context(|| format!("read {source}"))?
+        };
+        Agent::from_portable(&raw, &fallback_name)
     }
 
     fn show_agent_info(&mut self) {
@@ -217,6 +408,28 @@ impl App {
         let thread_len = self.conversation_thread.len();
         self.log(LogLevel::Info, format!("Active persona: {name}"));
         self.log(LogLevel::Info, format!("   Description: {description}"));
+        if let Some(model) = self.active_agent.model.clone() {
+            self.log(LogLevel::Info, format!("   Model: {model}"));
+        }
+        let restrictions = self.active_agent.restrictions();
+        let allowed = |list: &[String]| {
+            if list.is_empty() {
+                "all".to_string()
+            } else {
+                list.join(", ")
+            }
+        };
+        self.log(
+            LogLevel::Info,
+            format!("   Allowed tools: {}", allowed(&restrictions.tools)),
+        );
+        self.log(
+            LogLevel::Info,
+            format!(
+                "   Allowed MCP servers: {}",
+                allowed(&restrictions.mcp_servers)
+            ),
+        );
         self.log(LogLevel::Info, format!("   Thread: {thread_len} messages"));
     }
 }
@@ -270,12 +483,50 @@ impl App {
 
 impl App {
     pub(crate) fn handle_memory_command(&mut self, args: Vec<&str>) {
-        if args.is_empty() {
-            self.log(LogLevel::Info, "Usage: /memory <search query>".to_string());
-            return;
+        match args.as_slice() {
+            [] => {
+                self.log(LogLevel::Info, "Usage: /memory <search query>".to_string());
+            }
+            ["export", rest @ ..] => {
+                let include_secrets = rest.contains(&"--include-secrets");
+                let path: Vec<&str> = rest
+                    .iter()
+                    .copied()
+                    .filter(|arg| *arg != "--include-secrets")
+                    .collect();
+                if path.is_empty() {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /memory export <path> [--include-secrets]".to_string()
+                    );
+                } else {
+                    self.export_memory(&path.join(" "), include_secrets);
+                }
+            }
+            ["import", rest @ ..] => {
+                if rest.is_empty() {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /memory import <path>".to_string()
+                    );
+                } else {
+                    self.import_memory(&rest.join(" "));
+                }
+            }
+            ["compact", rest @ ..] => self.compact_memory(rest.contains(&"--dry-run")),
+            ["list", rest @ ..] => {
+                let limit = rest.first().and_then(|raw| raw.parse().ok());
+                self.list_memories(limit.unwrap_or(self.memory_limit));
+            }
+            ["facts", rest @ ..] => self.handle_memory_facts_command(rest),
+            ["search", rest @ ..] if !rest.is_empty() => self.search_memory(&rest.join(" ")),
+            _ => {
+                let query = args.join(" ");
+                self.search_memory(&query);
+            }
         }
-        let query = args.join(" ");
-        self.search_memory(&query);
     }
 
     fn search_memory(&mut self, query: &str) {
@@ -304,7 +555,44 @@ impl App {
             LogLevel::Info,
             format!("Found {} memory(ies):", memories.len()),
         );
-        for trace in &memories {
+        self.log_memories(&memories);
+    }
+
+    /// `/memory list [n]` — the newest memories with how each was produced.
+    fn list_memories(&mut self, limit: u64) {
+        let mut memories =
+            match self
+                .runtime
+                .block_on(self.rice.reminisce(vec![], MAX_MEMORY_EXPORT_TRACES, ""))
+            {
+                Ok(traces) => traces,
+                Err(err) => {
+                    log_src!(self, LogLevel::Warn, format!("Memory list failed: {err:#}"));
+                    return;
+                }
+            };
+        if memories.is_empty() {
+            self.log(LogLevel::Info, "No memories yet.".to_string());
+            return;
+        }
+        let committed_at = |trace: &Trace| {
+            TraceMeta::parse(&trace.reasoning)
+                .map(|meta| meta.committed_at)
+                .unwrap_or_default()
+        };
+        memories.sort_by_key(|trace| std::cmp::Reverse(committed_at(trace)));
+        memories.truncate(limit as usize);
+        self.log(
+            LogLevel::Info,
+            format!("{} most recent memory(ies):", memories.len()),
+        );
+        self.log_memories(&memories);
+    }
+
+    /// One line per trace, plus its model, tools, tokens and duration when
+    /// they were recorded.
+    fn log_memories(&mut self, memories: &[Trace]) {
+        for trace in memories {
             let input = trace.input.trim();
             let outcome = trace.outcome.trim();
             let action = trace.action.trim();
@@ -322,6 +610,78 @@ impl App {
                     format!("  \u{21B3} [{action}] {input} \u{2192} {outcome}"),
                 );
             }
+            if let Some(via) =
+                TraceMeta::parse(&trace.reasoning).and_then(|meta| provenance::summary(&meta))
+            {
+                self.log(LogLevel::Info, format!("      via {via}"));
+            }
+        }
+    }
+
+    /// `/memory compact [--dry-run]` — merge repeated traces and hide ones
+    /// whose importance has decayed below the prune threshold.
+    fn compact_memory(&mut self, dry_run: bool) {
+        let traces =
+            match self
+                .runtime
+                .block_on(self.rice.reminisce(vec![], MAX_MEMORY_EXPORT_TRACES, ""))
+            {
+                Ok(traces) => traces,
+                Err(err) => {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Memory compaction failed: {err:#}")
+                    );
+                    return;
+                }
+            };
+        let plan = importance::plan_compaction(&traces, Local::now().fixed_offset());
+        let merged: usize = plan.merges.iter().map(|merge| merge.sources).sum();
+        let summary = format!(
+            "{} trace(s): merge {merged} into {}, drop {} duplicate(s), prune {} low-value, keep {}.",
+            traces.len(),
+            plan.merges.len(),
+            plan.duplicates,
+            plan.pruned,
+            plan.kept,
+        );
+        if dry_run || plan.removed.is_empty() {
+            let prefix = if dry_run {
+                "Dry run"
+            } else {
+                "Nothing to compact"
+            };
+            self.log(LogLevel::Info, format!("{prefix}: {summary}"));
+            return;
+        }
+
+        for merge in &plan.merges {
+            if let Err(err) = self.runtime.block_on(self.rice.commit_scored_trace(
+                &merge.input,
+                &merge.outcome,
+                &merge.action,
+                vec![],
+                &merge.agent_id,
+                &merge.meta,
+            )) {
+                // Leave the sources visible rather than lose them.
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Memory compaction stopped while merging: {err:#}")
+                );
+                return;
+            }
+        }
+        if let Err(err) = self.runtime.block_on(self.rice.prune_traces(&plan.removed)) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Memory compaction could not hide old traces: {err:#}")
+            );
+            return;
         }
+        self.log(LogLevel::Info, format!("Compacted memory: {summary}"));
     }
 }
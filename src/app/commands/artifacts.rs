@@ -0,0 +1,118 @@
+//! `/artifacts` command handler — browse files saved by daemons and agent
+//! windows (see [`super::super::artifacts`]).
+
+use super::super::App;
+use super::super::artifacts::{self, RunListing};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+/// Runs shown by `/artifacts list`.
+const LIST_LIMIT: usize = 20;
+
+// ── /artifacts ───────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_artifacts_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_artifacts(None),
+            ["list", task] => self.list_artifacts(Some(task)),
+            ["open"] => self.open_artifact_path(artifacts::artifacts_root()),
+            ["open", task, rest @ ..] if rest.len() <= 2 => {
+                self.open_artifact(task, rest.first().copied(), rest.get(1).copied())
+            }
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /artifacts [list [task]] | /artifacts open [task [run|latest] [file]]"
+                    .to_string()
+            ),
+        }
+    }
+
+    fn list_artifacts(&mut self, task: Option<&str>) {
+        let runs = artifacts::list_runs(task);
+        if runs.is_empty() {
+            let scope = task.map_or_else(String::new, |task| format!(" for {task}"));
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No artifacts{scope} yet. Daemon and agent results are saved under {}.",
+                    artifacts::artifacts_root().display()
+                ),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Artifacts ({}):", artifacts::artifacts_root().display()),
+        );
+        for run in runs.iter().take(LIST_LIMIT) {
+            self.log(
+                LogLevel::Info,
+                format!("  {} {} — {}", run.owner, run.stamp, run.files.join(", ")),
+            );
+        }
+        if runs.len() > LIST_LIMIT {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "  … {} older run(s). /artifacts list <task> narrows the list.",
+                    runs.len() - LIST_LIMIT
+                ),
+            );
+        }
+    }
+
+    fn open_artifact(&mut self, task: &str, run: Option<&str>, file: Option<&str>) {
+        let runs = artifacts::list_runs(Some(task));
+        let found: Option<&RunListing> = match run {
+            None | Some("latest") => runs.first(),
+            Some(stamp) => runs.iter().find(|listing| listing.stamp == stamp),
+        };
+        let Some(found) = found else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No artifacts for {task}. See /artifacts list.")
+            );
+            return;
+        };
+        let path = match file {
+            Some(file) if found.files.iter().any(|name| name == file) => found.path.join(file),
+            Some(file) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!(
+                        "{} {} has no {file} (files: {}).",
+                        found.owner,
+                        found.stamp,
+                        found.files.join(", ")
+                    )
+                );
+                return;
+            }
+            None => found.path.clone(),
+        };
+        self.open_artifact_path(path);
+    }
+
+    fn open_artifact_path(&mut self, path: std::path::PathBuf) {
+        if !path.exists() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("{} does not exist yet.", path.display())
+            );
+            return;
+        }
+        match open::that(&path) {
+            Ok(()) => self.log(LogLevel::Info, format!("Opened {}", path.display())),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Could not open {}: {err}", path.display())
+            ),
+        }
+    }
+}
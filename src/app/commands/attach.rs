@@ -0,0 +1,60 @@
+//! `/attach` command handler — queue images for the next chat turn (see
+//! [`super::super::attachments`]).
+
+use super::super::App;
+use super::super::attachments;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /attach ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_attach_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => {
+                if self.pending_images.is_empty() {
+                    self.log(
+                        LogLevel::Info,
+                        "No images attached. Usage: /attach <path-or-url>".to_string(),
+                    );
+                    return;
+                }
+                self.log(LogLevel::Info, "Attached to your next message:".to_string());
+                let chips: Vec<String> = self.pending_images.iter().map(|i| i.chip()).collect();
+                for chip in chips {
+                    self.log(LogLevel::Info, format!("  {chip}"));
+                }
+            }
+            ["clear"] => {
+                let count = self.pending_images.len();
+                self.pending_images.clear();
+                self.log(
+                    LogLevel::Info,
+                    format!("Removed {count} attached image(s)."),
+                );
+            }
+            // Paths may contain spaces.
+            _ => self.attach_image(&args.join(" ")),
+        }
+    }
+
+    /// Load an image from a path or URL and queue it for the next turn.
+    pub(crate) fn attach_image(&mut self, source: &str) {
+        match attachments::load_image(source) {
+            Ok(image) => self.queue_image(image),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Cannot attach image: {err:#}")
+            ),
+        }
+    }
+
+    pub(crate) fn queue_image(&mut self, image: attachments::ImageAttachment) {
+        self.log(
+            LogLevel::Info,
+            format!("📎 {} — sent with your next message.", image.chip()),
+        );
+        self.pending_images.push(image);
+    }
+}
@@ -0,0 +1,141 @@
+//! `/branch` and `/rewind` — fork the conversation thread or go back to an
+//! earlier turn, keeping the abandoned branch as a named session.
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::sessions;
+
+/// Turns shown by a bare `/rewind`.
+const REWIND_LIST_LIMIT: usize = 10;
+
+// ── /branch ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_branch_command(&mut self, args: Vec<&str>) {
+        let name = match args.as_slice() {
+            [] => sessions::branch_name(),
+            [name] => name.to_string(),
+            _ => {
+                log_src!(self, LogLevel::Warn, "Usage: /branch [name]".to_string());
+                return;
+            }
+        };
+        if self.conversation_thread.is_empty() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Nothing to branch yet; the conversation is empty.".to_string()
+            );
+            return;
+        }
+        if self.store_session(&name) {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Branched: this conversation is saved as '{name}'. Keep going here, or /session load {name} to come back."
+                ),
+            );
+        }
+    }
+}
+
+// ── /rewind ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_rewind_command(&mut self, args: Vec<&str>) {
+        let Some(first) = args.first() else {
+            self.list_rewind_points();
+            return;
+        };
+        let Ok(turns) = first.parse::<usize>() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /rewind [<n> [new message]]".to_string()
+            );
+            return;
+        };
+        let replacement = args[1..].join(" ");
+        self.rewind_turns(turns, &replacement);
+    }
+
+    fn list_rewind_points(&mut self) {
+        let lines: Vec<String> = sessions::user_turns(&self.conversation_thread)
+            .iter()
+            .take(REWIND_LIST_LIMIT)
+            .enumerate()
+            .map(|(i, (_, text))| format!("  {:>2}  {}", i + 1, preview(text)))
+            .collect();
+        if lines.is_empty() {
+            self.log(LogLevel::Info, "No turns to rewind to yet.".to_string());
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            "Your recent messages (/rewind <n> goes back to before message n):".to_string(),
+        );
+        for line in lines {
+            self.log(LogLevel::Info, line);
+        }
+    }
+
+    /// Save the current branch, drop the last `turns` turns, then either
+    /// send `replacement` or put the dropped message back in the input box.
+    fn rewind_turns(&mut self, turns: usize, replacement: &str) {
+        if self.chat_busy {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Wait for the current reply before rewinding.".to_string()
+            );
+            return;
+        }
+        let available = sessions::user_turns(&self.conversation_thread).len();
+        if turns == 0 || turns > available {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Can rewind 1..{available} turn(s); see /rewind.")
+            );
+            return;
+        }
+
+        let name = sessions::branch_name();
+        if !self.store_session(&name) {
+            return;
+        }
+        let Some(original) = sessions::rewind(&mut self.conversation_thread, turns) else {
+            return;
+        };
+        self.save_conversation_thread();
+        self.log(
+            LogLevel::Info,
+            format!("Rewound {turns} turn(s); the previous branch is saved as '{name}'."),
+        );
+
+        if replacement.trim().is_empty() {
+            self.input.clear();
+            self.cursor = 0;
+            self.insert_str(&original);
+            self.log(
+                LogLevel::Info,
+                "Edit the message in the input box and press Enter to regenerate.".to_string(),
+            );
+        } else {
+            self.log(LogLevel::Info, format!("› {replacement}"));
+            self.chat_busy = true;
+            self.handle_chat_message(replacement, false);
+        }
+    }
+}
+
+/// First line of `text`, shortened for the turn list.
+fn preview(text: &str) -> String {
+    let line = text.lines().next().unwrap_or("");
+    if line.chars().count() > 72 {
+        format!("{}…", line.chars().take(71).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
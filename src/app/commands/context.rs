@@ -0,0 +1,61 @@
+//! `/context files` command handler — which workspace context files
+//! (`MEMINI.md`, `AGENTS.md`, `CLAUDE.md`) go into prompts.
+
+use crate::local_tools::workspace_root;
+
+use super::super::App;
+use super::super::context_files::{self, CONTEXT_FILE_NAMES, Inclusion};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+impl App {
+    pub(crate) fn handle_context_command(&mut self, args: Vec<&str>) {
+        if !matches!(args.as_slice(), [] | ["files"]) {
+            log_src!(self, LogLevel::Warn, "Usage: /context files".to_string());
+            return;
+        }
+        let config = context_files::config();
+        if !config.enabled {
+            self.log(
+                LogLevel::Info,
+                "Context files are off ([context_files] enabled = false in memini.toml)."
+                    .to_string(),
+            );
+            return;
+        }
+        let loaded = context_files::load();
+        if loaded.files.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No context files in {} (looked for {}).",
+                    workspace_root().display(),
+                    CONTEXT_FILE_NAMES.join(", ")
+                ),
+            );
+            return;
+        }
+        let rows: Vec<String> = loaded
+            .files
+            .iter()
+            .map(|file| {
+                let status = match file.inclusion {
+                    Inclusion::Full => "loaded",
+                    Inclusion::Truncated => "truncated",
+                    Inclusion::Skipped => "skipped (over budget)",
+                };
+                format!(
+                    "| `{}` | ~{} | {status} |",
+                    file.path.display(),
+                    file.tokens
+                )
+            })
+            .collect();
+        let body = format!(
+            "| File | Tokens | In prompts |\n|---|---|---|\n{}\n\nBudget: {} tokens per chat turn and agent run.",
+            rows.join("\n"),
+            config.max_tokens
+        );
+        self.log_markdown("Context files".to_string(), body);
+    }
+}
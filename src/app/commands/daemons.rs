@@ -1,13 +1,21 @@
-//! `/daemon` (`/auto`) and `/spawn` command handlers — background task
-//! management and live agent window creation.
+//! `/daemon` (`/auto`), `/spawn`, `/kill` and `/cancel` command handlers — background
+//! task management and live agent window creation.
 
 use std::collections::HashSet;
 
+use crate::i18n::t;
+
 use super::super::App;
 use super::super::agent_recipes;
+use super::super::agents::Agent;
+use super::super::concurrency;
 use super::super::daemon;
 use super::super::log_src;
 use super::super::logging::LogLevel;
+use super::super::recipe_lint::{self, Severity};
+use super::super::sinks;
+use super::super::window_control;
+use super::super::worktrees;
 
 // ── /daemon ──────────────────────────────────────────────────────────
 
@@ -57,6 +65,17 @@ impl App {
                     );
                 }
             }
+            "pause" | "resume" => {
+                if let Some(name) = args.get(1) {
+                    self.set_daemon_paused(name, args[0] == "pause");
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Usage: /daemon {} <name>", args[0])
+                    );
+                }
+            }
             "stop" => {
                 if let Some(name) = args.get(1) {
                     self.stop_daemon(name);
@@ -85,6 +104,8 @@ impl App {
                     );
                 }
             }
+            "generate" => self.handle_daemon_generate(&args[1..]),
+            "lint" => self.lint_daemon_recipes(args.get(1).copied()),
             "remove" => {
                 if let Some(name) = args.get(1) {
                     self.remove_daemon_task(name);
@@ -100,6 +121,7 @@ impl App {
                 let filter = args.get(1).copied();
                 self.show_daemon_results(filter);
             }
+            "queue" => self.show_work_queue(),
             other => {
                 log_src!(
                     self,
@@ -110,6 +132,37 @@ impl App {
         }
     }
 
+    /// Work holding or waiting for one of the `max_concurrent_agents` slots.
+    fn show_work_queue(&mut self) {
+        let entries = concurrency::snapshot();
+        let running = entries.iter().filter(|e| e.started_at.is_some()).count();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Agent slots: {running}/{} busy, {} queued.",
+                concurrency::limit(),
+                entries.len() - running
+            ),
+        );
+        for entry in entries {
+            let line = match entry.started_at {
+                Some(started) => format!(
+                    "  ▶ {:<7} {} — running {}s",
+                    entry.kind.label(),
+                    entry.label,
+                    started.elapsed().as_secs()
+                ),
+                None => format!(
+                    "  ⏳ {:<7} {} — waiting {}s",
+                    entry.kind.label(),
+                    entry.label,
+                    entry.queued_at.elapsed().as_secs()
+                ),
+            };
+            self.log(LogLevel::Info, line);
+        }
+    }
+
     fn show_daemon_recipe_dir(&mut self) {
         match agent_recipes::ensure_agents_dir() {
             Ok(dir) => {
@@ -144,6 +197,7 @@ impl App {
             LogLevel::Info,
             format!("Loaded {} recipe-based auto-agent(s).", recipes.len()),
         );
+        self.ensure_file_watcher();
     }
 
     fn list_daemon_recipe_templates(&mut self) {
@@ -212,10 +266,8 @@ impl App {
                     persona: spec.persona,
                     prompt: spec.instructions,
                     interval_secs: spec.interval_secs,
-                    trigger_events: Vec::new(),
-                    trigger_variables: Vec::new(),
                     tools: spec.tools,
-                    paused: false,
+                    ..Default::default()
                 };
                 self.spawn_daemon_task(def);
             }
@@ -229,6 +281,98 @@ impl App {
         }
     }
 
+    /// `/daemon lint [name]`: check recipe files without loading them.
+    fn lint_daemon_recipes(&mut self, only: Option<&str>) {
+        let dir = agent_recipes::agents_dir();
+        let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .filter(|path| {
+                        path.extension()
+                            .and_then(|ext| ext.to_str())
+                            .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        files.sort();
+
+        let mut tasks: Vec<String> = daemon::builtin_tasks()
+            .into_iter()
+            .map(|task| task.name)
+            .collect();
+        tasks.extend(files.iter().filter_map(|path| {
+            path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .map(str::to_string)
+        }));
+        let context = recipe_lint::LintContext {
+            tools: crate::local_tools::tool_defs()
+                .iter()
+                .filter_map(|tool| tool.get("name").and_then(|name| name.as_str()))
+                .map(str::to_ascii_lowercase)
+                .collect(),
+            tasks,
+            sinks: sinks::config().sinks.keys().cloned().collect(),
+        };
+
+        let mut checked = 0;
+        let (mut errors, mut warnings) = (0, 0);
+        for path in &files {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or_default();
+            let raw = match std::fs::read_to_string(path) {
+                Ok(raw) => raw,
+                Err(err) => {
+                    log_src!(self, LogLevel::Error, format!("{}: {err}", path.display()));
+                    continue;
+                }
+            };
+            if let Some(name) = only {
+                let declared = agent_recipes::split_front_matter(&raw).0.remove("name");
+                if !stem.eq_ignore_ascii_case(name)
+                    && !declared.is_some_and(|declared| declared.eq_ignore_ascii_case(name))
+                {
+                    continue;
+                }
+            }
+            checked += 1;
+            let issues = recipe_lint::lint_recipe(path, &raw, &context);
+            if issues.is_empty() {
+                self.log(LogLevel::Info, format!("✓ {stem}"));
+                continue;
+            }
+            for issue in issues {
+                let level = match issue.severity {
+                    Severity::Error => {
+                        errors += 1;
+                        LogLevel::Error
+                    }
+                    Severity::Warning => {
+                        warnings += 1;
+                        LogLevel::Warn
+                    }
+                };
+                self.log(level, format!("{}:{issue}", path.display()));
+            }
+        }
+
+        match only {
+            Some(name) if checked == 0 => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No recipe named '{name}' in {}.", dir.display())
+            ),
+            _ => self.log(
+                LogLevel::Info,
+                format!("Linted {checked} recipe(s): {errors} error(s), {warnings} warning(s)."),
+            ),
+        }
+    }
+
     fn load_daemon_recipes(&mut self) -> Vec<agent_recipes::AgentRecipe> {
         match agent_recipes::load_agent_recipes() {
             Ok(recipes) => recipes,
@@ -243,7 +387,7 @@ impl App {
         }
     }
 
-    fn daemon_def_from_recipe(
+    pub(crate) fn daemon_def_from_recipe(
         recipe: &agent_recipes::AgentRecipe,
         paused: bool,
     ) -> daemon::DaemonTaskDef {
@@ -254,7 +398,20 @@ impl App {
             interval_secs: recipe.interval_secs,
             trigger_events: recipe.trigger_events.clone(),
             trigger_variables: recipe.trigger_variables.clone(),
+            trigger_paths: recipe.trigger_paths.clone(),
             tools: recipe.tools.clone(),
+            depends_on: recipe.depends_on.clone(),
+            pipe_output_to: recipe.pipe_output_to.clone(),
+            model: recipe.model.clone(),
+            thinking: recipe.thinking.clone(),
+            schema: recipe.schema.clone(),
+            output_var: recipe.output_var.clone(),
+            env: recipe.env.clone(),
+            propose_writes: recipe.propose_writes,
+            notify: recipe.notify.clone(),
+            deliver: recipe.deliver.clone(),
+            max_tool_loops: recipe.max_tool_loops,
+            checkpoints: recipe.checkpoints.clone(),
             paused,
         }
     }
@@ -318,15 +475,12 @@ impl App {
 
         for builtin in &builtins {
             known_names.insert(builtin.name.to_ascii_lowercase());
-            let running = self
-                .daemon_handles
-                .iter()
-                .any(|h| h.def.name.eq_ignore_ascii_case(&builtin.name));
-            let status = if running { "running" } else { "available" };
+            let status = self.daemon_status(&builtin.name);
+            let model = builtin.model_label(&self.openai);
             self.log(
                 LogLevel::Info,
                 format!(
-                    "  {} -- {} [{}s, {}, builtin]",
+                    "  {} -- {} [{}s, {}, {model}, builtin]",
                     builtin.name, builtin.prompt, builtin.interval_secs, status
                 ),
             );
@@ -345,11 +499,7 @@ impl App {
                 continue;
             }
             known_names.insert(recipe.name.to_ascii_lowercase());
-            let running = self
-                .daemon_handles
-                .iter()
-                .any(|h| h.def.name.eq_ignore_ascii_case(&recipe.name));
-            let status = if running { "running" } else { "available" };
+            let status = self.daemon_status(&recipe.name);
             let preview: String = if recipe.description.trim().is_empty() {
                 recipe.instructions.chars().take(72).collect()
             } else {
@@ -359,10 +509,11 @@ impl App {
                 .trigger_summary()
                 .map(|summary| format!(", trigger:{summary}"))
                 .unwrap_or_default();
+            let model = Self::daemon_def_from_recipe(recipe, false).model_label(&self.openai);
             self.log(
                 LogLevel::Info,
                 format!(
-                    "  {} -- {} [{}s, {}, file:{}{}]",
+                    "  {} -- {} [{}s, {}, {model}, file:{}{}]",
                     recipe.name,
                     preview,
                     recipe.interval_secs,
@@ -373,7 +524,7 @@ impl App {
             );
         }
 
-        let runtime_only: Vec<(String, String, u64)> = self
+        let runtime_only: Vec<(String, String, u64, String)> = self
             .daemon_handles
             .iter()
             .filter(|handle| !known_names.contains(&handle.def.name.to_ascii_lowercase()))
@@ -382,20 +533,44 @@ impl App {
                     handle.def.name.clone(),
                     handle.def.prompt.clone(),
                     handle.def.interval_secs,
+                    handle.def.model_label(&self.openai),
                 )
             })
             .collect();
-        for (name, prompt, interval) in runtime_only {
+        for (name, prompt, interval, model) in runtime_only {
+            let status = self.daemon_status(&name);
             self.log(
                 LogLevel::Info,
-                format!("  {name} -- {prompt} [{interval}s, running, runtime-only]"),
+                format!("  {name} -- {prompt} [{interval}s, {status}, {model}, runtime-only]"),
             );
         }
+
+        let pipelines = self.pipeline_lines();
+        if !pipelines.is_empty() {
+            self.log(LogLevel::Info, "Pipelines:".to_string());
+            for line in pipelines {
+                self.log(LogLevel::Info, line);
+            }
+        }
     }
 
-    fn run_daemon_now(&mut self, name: &str) {
+    pub(crate) fn run_daemon_now(&mut self, name: &str) {
+        if self.refuse_while_halted() {
+            return;
+        }
+        if self.run_pipeline_for(name) {
+            return;
+        }
+
         for handle in &self.daemon_handles {
             if handle.def.name.eq_ignore_ascii_case(name) {
+                if handle.schedule.is_paused() {
+                    // Run once without releasing the paused schedule.
+                    let mut def = handle.def.clone();
+                    def.paused = false;
+                    self.run_daemon_oneshot(def);
+                    return;
+                }
                 handle.wake.notify_one();
                 self.log(
                     LogLevel::Info,
@@ -427,7 +602,7 @@ impl App {
         log_src!(self, LogLevel::Warn, format!("Unknown daemon task: {name}"));
     }
 
-    fn start_daemon(&mut self, name: &str) {
+    pub(crate) fn start_daemon(&mut self, name: &str) {
         if self
             .daemon_handles
             .iter()
@@ -462,6 +637,58 @@ impl App {
         log_src!(self, LogLevel::Warn, format!("Unknown daemon task: {name}"));
     }
 
+    /// `running`, `paused (next run in …)` or `available`.
+    fn daemon_status(&self, name: &str) -> String {
+        match self
+            .daemon_handles
+            .iter()
+            .find(|handle| handle.def.name.eq_ignore_ascii_case(name))
+        {
+            Some(handle) if handle.schedule.is_paused() => format!(
+                "paused, {} left",
+                window_control::format_duration(handle.schedule.remaining())
+            ),
+            Some(_) => "running".to_string(),
+            None => "available".to_string(),
+        }
+    }
+
+    /// Hold or release a running task's schedule without stopping it.
+    fn set_daemon_paused(&mut self, name: &str, pause: bool) {
+        let Some(handle) = self
+            .daemon_handles
+            .iter_mut()
+            .find(|handle| handle.def.name.eq_ignore_ascii_case(name))
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No running daemon named '{name}'.")
+            );
+            return;
+        };
+        let task = handle.def.name.clone();
+        let changed = if pause {
+            handle.schedule.pause()
+        } else {
+            handle.schedule.resume()
+        };
+        handle.def.paused = handle.schedule.is_paused();
+        let message = match (changed, pause) {
+            (Some(left), true) => format!(
+                "Task '{task}' paused with {} until its next run. /daemon resume {task} continues it.",
+                window_control::format_duration(left)
+            ),
+            (Some(left), false) => format!(
+                "Task '{task}' resumed; next run in {}.",
+                window_control::format_duration(left)
+            ),
+            (None, true) => format!("Task '{task}' is already paused."),
+            (None, false) => format!("Task '{task}' is not paused."),
+        };
+        self.log(LogLevel::Info, message);
+    }
+
     fn stop_daemon(&mut self, name: &str) {
         if let Some(pos) = self
             .daemon_handles
@@ -536,10 +763,8 @@ impl App {
                     persona: spec.persona,
                     prompt: spec.instructions,
                     interval_secs: spec.interval_secs,
-                    trigger_events: Vec::new(),
-                    trigger_variables: Vec::new(),
                     tools: spec.tools,
-                    paused: false,
+                    ..Default::default()
                 };
                 self.spawn_daemon_task(def);
                 self.log(
@@ -698,7 +923,7 @@ impl App {
             log_src!(
                 self,
                 LogLevel::Warn,
-                format!("Agent #{window_id} is not waiting for input.")
+                format!("Agent #{window_id} is still working or was cancelled.")
             );
         }
     }
@@ -735,7 +960,7 @@ impl App {
         if args.is_empty() {
             self.log(
                 LogLevel::Info,
-                "Usage: /spawn <prompt>  or  /spawn list".to_string(),
+                "Usage: /spawn [--isolated] [--timeout 10m] <prompt>  or  /spawn list".to_string(),
             );
             self.log(
                 LogLevel::Info,
@@ -743,7 +968,7 @@ impl App {
             );
             self.log(
                 LogLevel::Info,
-                "Use Tab/Shift-Tab or PgUp/PgDn on dashboard to scroll live agents; Enter opens selected.".to_string(),
+                "Use Tab/Shift-Tab on dashboard to select live agents, Ctrl+PgUp/PgDn to page; Enter opens selected.".to_string(),
             );
             self.log(
                 LogLevel::Info,
@@ -757,33 +982,149 @@ impl App {
         }
 
         if args[0] == "list" {
-            self.list_spawned_agents();
+            self.list_spawned_agents("id");
             return;
         }
+        if self.refuse_while_halted() {
+            return;
+        }
+        if args == ["resume"] {
+            self.resume_saved_windows();
+            return;
+        }
+
+        let mut timeout = None;
+        let mut isolated = false;
+        let mut rest = args.as_slice();
+        loop {
+            match rest {
+                ["--isolated", tail @ ..] => {
+                    isolated = true;
+                    rest = tail;
+                }
+                ["--timeout", raw, tail @ ..] => match window_control::parse_duration(raw) {
+                    Ok(limit) => {
+                        timeout = Some(limit);
+                        rest = tail;
+                    }
+                    Err(err) => {
+                        log_src!(self, LogLevel::Warn, format!("{err:#}"));
+                        return;
+                    }
+                },
+                _ => break,
+            }
+        }
+        // Everything else is the prompt.
+        let prompt = rest.join(" ");
+        if prompt.trim().is_empty() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /spawn [--isolated] [--timeout 10m] <prompt>".to_string()
+            );
+            return;
+        }
+        self.spawn_agent_window_cmd(&prompt, timeout, isolated);
+    }
+
+    fn spawn_agent_window_cmd(
+        &mut self,
+        prompt: &str,
+        timeout: Option<std::time::Duration>,
+        isolated: bool,
+    ) {
+        use std::sync::atomic::Ordering;
+        let window_id = self.next_window_id.fetch_add(1, Ordering::SeqCst);
+        if isolated {
+            match worktrees::create(window_id) {
+                Ok(summary) => self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Agent #{window_id} works in an isolated {} at {}.",
+                        summary.method,
+                        summary.root.display()
+                    ),
+                ),
+                Err(err) => {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Could not isolate the workspace: {err:#}")
+                    );
+                    return;
+                }
+            }
+        }
+        let agent = self.active_agent.clone();
+        self.open_prompt_window(
+            window_id,
+            format!("Agent #{window_id}"),
+            prompt,
+            timeout,
+            &agent,
+        );
+        let limit = window_control::format_duration(window_control::timeout_for(window_id));
+        self.log(
+            LogLevel::Info,
+            format!("Spawned Agent #{window_id} (times out after {limit}) — opening session."),
+        );
 
-        // Everything after /spawn is the prompt.
-        let prompt = args.join(" ");
-        self.spawn_agent_window_cmd(&prompt);
+        // Auto-navigate into the agent session.
+        self.focused_window = Some(window_id);
+        self.view_mode = super::super::ViewMode::AgentSession(window_id);
+        // Also select this cell in the grid for when we come back.
+        let idx = self.agent_windows.len().saturating_sub(1);
+        self.grid_selected = idx;
     }
 
-    fn spawn_agent_window_cmd(&mut self, prompt: &str) {
+    /// Open an agent window on `prompt` with the active persona and start
+    /// it in the background; `timeout` overrides the default maximum
+    /// runtime.  Returns the window id.
+    pub(crate) fn spawn_prompt_window(
+        &mut self,
+        prompt: &str,
+        timeout: Option<std::time::Duration>,
+    ) -> usize {
         use std::sync::atomic::Ordering;
         let window_id = self.next_window_id.fetch_add(1, Ordering::SeqCst);
-        let label = format!("Agent #{window_id}");
+        let agent = self.active_agent.clone();
+        self.open_prompt_window(
+            window_id,
+            format!("Agent #{window_id}"),
+            prompt,
+            timeout,
+            &agent,
+        );
+        window_id
+    }
+
+    /// [`App::spawn_prompt_window`] for an id already taken (so an
+    /// isolated workspace can be made before the run starts), with its own
+    /// label and persona.
+    pub(crate) fn open_prompt_window(
+        &mut self,
+        window_id: usize,
+        label: String,
+        prompt: &str,
+        timeout: Option<std::time::Duration>,
+        agent: &Agent,
+    ) {
+        if let Some(timeout) = timeout {
+            window_control::set_timeout(window_id, timeout);
+        }
 
         // Create the window in Thinking state.
         let window = daemon::AgentWindow {
-            id: window_id,
-            label: label.clone(),
-            prompt: prompt.to_string(),
-            status: daemon::AgentWindowStatus::Thinking,
-            output_lines: Vec::new(),
-            pending_question: None,
-            scroll: 0,
-            persona: self.active_agent.persona.clone(),
+            persona: agent.persona.clone(),
             skill_context: self.skills_prompt_context(prompt),
-            mcp_snapshots: Vec::new(),
-            coordination_key: String::new(),
+            restrictions: agent.restrictions(),
+            ..daemon::AgentWindow::new(
+                window_id,
+                label,
+                prompt.to_string(),
+                daemon::AgentWindowStatus::Thinking,
+            )
         };
         self.agent_windows.push(window);
 
@@ -791,36 +1132,174 @@ impl App {
         let tx = self.daemon_tx.clone();
         let openai = self.openai.clone();
         let key = self.openai_key.clone();
-        let rice_handle = self.runtime.spawn(crate::rice::RiceStore::connect());
-        let persona = self.active_agent.persona.clone();
+        let rice_handle = self.spawn_rice_handle();
         let skill_context = self.skills_prompt_context(prompt);
 
         daemon::spawn_agent_window(
             window_id,
-            persona,
+            agent.persona.clone(),
             prompt.to_string(),
+            Vec::new(),
             skill_context,
+            agent.restrictions(),
             tx,
             openai,
             key,
             rice_handle,
             self.runtime.handle().clone(),
         );
+    }
 
-        self.log(
-            LogLevel::Info,
-            format!("Spawned {label} — opening session."),
-        );
+    /// `/kill <id>` — cancel an agent window's running work, or close a
+    /// window that is waiting for input.
+    pub(crate) fn handle_kill_command(&mut self, args: Vec<&str>) {
+        let Some(window_id) = args
+            .first()
+            .and_then(|raw| raw.trim_start_matches('#').parse::<usize>().ok())
+        else {
+            log_src!(self, LogLevel::Warn, "Usage: /kill <window-id>".to_string());
+            return;
+        };
+        let Some(status) = self
+            .agent_windows
+            .iter()
+            .find(|window| window.id == window_id)
+            .map(|window| window.status.clone())
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No agent window #{window_id}. Use /agents.")
+            );
+            return;
+        };
+        if super::super::shell_sessions::close_window(window_id) {
+            self.log(
+                LogLevel::Info,
+                format!("Closed Agent #{window_id}'s shell session."),
+            );
+        }
+        let killed = super::super::processes::close_window(window_id);
+        if killed > 0 {
+            self.log(
+                LogLevel::Info,
+                format!("Killed Agent #{window_id}'s {killed} background process(es)."),
+            );
+        }
+        let in_flight = self
+            .window_active_runs
+            .get(&window_id)
+            .copied()
+            .unwrap_or(0);
+        if in_flight > 0 {
+            window_control::kill(window_id);
+            self.log(LogLevel::Info, format!("Killing Agent #{window_id}…"));
+        } else if status == daemon::AgentWindowStatus::WaitingForInput {
+            self.stop_window_run(
+                window_id,
+                daemon::AgentWindowStatus::Killed,
+                "killed".to_string(),
+            );
+        } else {
+            self.log(
+                LogLevel::Info,
+                format!("Agent #{window_id} is not running."),
+            );
+        }
+    }
 
-        // Auto-navigate into the agent session.
-        self.focused_window = Some(window_id);
-        self.view_mode = super::super::ViewMode::AgentSession(window_id);
-        // Also select this cell in the grid for when we come back.
-        let idx = self.agent_windows.len().saturating_sub(1);
-        self.grid_selected = idx;
+    /// `/cancel <id>` — stop the command an agent window is running; the
+    /// agent gets what it printed so far and carries on.
+    pub(crate) fn handle_cancel_command(&mut self, args: Vec<&str>) {
+        let Some(window_id) = args
+            .first()
+            .and_then(|raw| raw.trim_start_matches('#').parse::<usize>().ok())
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /cancel <window-id>".to_string()
+            );
+            return;
+        };
+        let Some(window) = self
+            .agent_windows
+            .iter()
+            .find(|window| window.id == window_id)
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No agent window #{window_id}. Use /agents.")
+            );
+            return;
+        };
+        match &window.running_command {
+            Some(running) => {
+                let command = running.command.clone();
+                window_control::cancel_command(window_id);
+                self.log(
+                    LogLevel::Info,
+                    format!("Cancelling Agent #{window_id}'s command `{command}`…"),
+                );
+            }
+            None => self.log(
+                LogLevel::Info,
+                format!("Agent #{window_id} is not running a command."),
+            ),
+        }
     }
 
-    fn list_spawned_agents(&mut self) {
+    /// `/split [id|off]` — stream an agent window beside the activity log.
+    pub(crate) fn handle_split_command(&mut self, args: Vec<&str>) {
+        match args.first().copied() {
+            Some("off") | Some("close") => {
+                self.split_window = None;
+                self.log(LogLevel::Info, "Split view closed.".to_string());
+            }
+            Some(raw) => {
+                let Ok(id) = raw.trim_start_matches('#').parse::<usize>() else {
+                    log_src!(self, LogLevel::Warn, "Usage: /split [id|off]".to_string());
+                    return;
+                };
+                let Some(idx) = self.agent_windows.iter().position(|w| w.id == id) else {
+                    log_src!(self, LogLevel::Warn, format!("Agent #{id} not found."));
+                    return;
+                };
+                self.grid_selected = idx;
+                self.split_window = Some(id);
+                self.view_mode = super::super::ViewMode::Dashboard;
+                self.focused_window = None;
+            }
+            None => {
+                let selected = self.agent_windows.get(self.grid_selected).map(|w| w.id);
+                match (self.split_window, selected) {
+                    (Some(_), _) => self.split_window = None,
+                    (None, Some(id)) => self.split_window = Some(id),
+                    (None, None) => log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "No agent windows to split. Use /spawn <prompt> first.".to_string()
+                    ),
+                }
+            }
+        }
+    }
+
+    /// `/agents [status|id|name]` — overview of every live window.
+    pub(crate) fn handle_agents_command(&mut self, args: Vec<&str>) {
+        let sort = args.first().copied().unwrap_or("status");
+        match sort {
+            "status" | "id" | "name" => self.list_spawned_agents(sort),
+            other => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown /agents sort: {other} (use status, id, or name)")
+            ),
+        }
+    }
+
+    fn list_spawned_agents(&mut self, sort: &str) {
         if self.agent_windows.is_empty() {
             self.log(
                 LogLevel::Info,
@@ -831,16 +1310,23 @@ impl App {
 
         self.log(
             LogLevel::Info,
-            format!("Agent windows ({}):", self.agent_windows.len()),
+            format!("Agent windows ({}, by {sort}):", self.agent_windows.len()),
         );
-        let windows: Vec<_> = self
-            .agent_windows
-            .iter()
+        let mut sorted: Vec<&daemon::AgentWindow> = self.agent_windows.iter().collect();
+        match sort {
+            "status" => sorted.sort_by_key(|w| (w.status.sort_rank(), w.id)),
+            "name" => sorted.sort_by(|a, b| a.label.cmp(&b.label)),
+            _ => sorted.sort_by_key(|w| w.id),
+        }
+        let windows: Vec<_> = sorted
+            .into_iter()
             .map(|w| {
                 let status = match w.status {
-                    daemon::AgentWindowStatus::Thinking => "thinking",
-                    daemon::AgentWindowStatus::Done => "done",
-                    daemon::AgentWindowStatus::WaitingForInput => "WAITING FOR INPUT",
+                    daemon::AgentWindowStatus::Thinking => t("thinking"),
+                    daemon::AgentWindowStatus::Done => t("done"),
+                    daemon::AgentWindowStatus::WaitingForInput => t("WAITING FOR INPUT"),
+                    daemon::AgentWindowStatus::TimedOut => t("timed out"),
+                    daemon::AgentWindowStatus::Killed => t("killed"),
                 };
                 (w.id, w.label.clone(), w.prompt.clone(), status)
             })
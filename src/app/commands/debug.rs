@@ -0,0 +1,56 @@
+//! `/debug http` command handler — capture OpenAI and MCP payloads for
+//! diagnosis (see [`crate::capture`]).
+
+use std::path::PathBuf;
+
+use crate::capture;
+use crate::constants::DEBUG_HTTP_CAPTURES;
+
+use super::super::App;
+use super::super::http_inspector::HttpInspector;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+const USAGE: &str = "Usage: /debug http [on|off|show|clear|dump [path]]";
+
+impl App {
+    pub(crate) fn handle_debug_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            ["http"] => {
+                let state = if capture::enabled() { "on" } else { "off" };
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "HTTP capture is {state}; {} exchange(s) held (last {DEBUG_HTTP_CAPTURES} kept).",
+                        capture::snapshot().len()
+                    ),
+                );
+            }
+            ["http", "on"] => {
+                capture::set_enabled(true);
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Capturing the last {DEBUG_HTTP_CAPTURES} OpenAI and MCP payloads (secrets masked). /debug http show to browse."
+                    ),
+                );
+            }
+            ["http", "off"] => {
+                capture::set_enabled(false);
+                self.log(
+                    LogLevel::Info,
+                    "HTTP capture off; captured payloads are kept until /debug http clear."
+                        .to_string(),
+                );
+            }
+            ["http", "show"] => self.http_inspector = Some(HttpInspector::load()),
+            ["http", "clear"] => {
+                capture::clear();
+                self.log(LogLevel::Info, "HTTP capture cleared.".to_string());
+            }
+            ["http", "dump"] => self.dump_http_capture(None),
+            ["http", "dump", path] => self.dump_http_capture(Some(PathBuf::from(path))),
+            _ => log_src!(self, LogLevel::Warn, USAGE.to_string()),
+        }
+    }
+}
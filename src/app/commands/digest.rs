@@ -0,0 +1,79 @@
+//! `/digest` command handler — write today's or this week's digest now
+//! (see [`super::super::digest`]).
+
+use crate::routing::TaskKind;
+
+use super::super::App;
+use super::super::daemon::{AgentEvent, ChatLogLevel};
+use super::super::digest::{self, Period};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::sinks::{self, SinkEvent};
+
+impl App {
+    pub(crate) fn handle_digest_command(&mut self, args: Vec<&str>) {
+        let Some(period) = Period::parse(args.first().copied().unwrap_or_default()) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /digest [today|week]".to_string()
+            );
+            return;
+        };
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err}"));
+                return;
+            }
+        };
+        let model = self.openai.model_for(TaskKind::Summary);
+        self.log(
+            LogLevel::Info,
+            format!(
+                "📰 Writing the {} digest with {model}…",
+                match period {
+                    Period::Today => "daily",
+                    Period::Week => "weekly",
+                }
+            ),
+        );
+        let openai = self.openai.clone();
+        let tx = self.daemon_tx.clone();
+        let rice_handle = self.spawn_rice_handle();
+        self.runtime.spawn(async move {
+            let Ok(mut rice) = rice_handle.await else {
+                return;
+            };
+            let result = match digest::generate(period, &openai, &key, &model, &mut rice).await {
+                Ok(digest) => digest::publish(&digest, period, &mut rice)
+                    .await
+                    .map(|path| (digest, path)),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok((digest, path)) => {
+                    for line in
+                        sinks::publish(SinkEvent::Digest, &[], &digest.title, &digest.markdown)
+                            .await
+                    {
+                        let _ = tx.send(AgentEvent::ChatProgress {
+                            line,
+                            level: ChatLogLevel::Warn,
+                        });
+                    }
+                    let _ = tx.send(AgentEvent::ChatMarkdown {
+                        label: digest.title.clone(),
+                        body: format!("{}\n\n_Saved to `{}`._", digest.markdown, path.display()),
+                    });
+                }
+                Err(err) => {
+                    let _ = tx.send(AgentEvent::ChatProgress {
+                        line: format!("Digest failed: {err:#}"),
+                        level: ChatLogLevel::Warn,
+                    });
+                }
+            }
+        });
+    }
+}
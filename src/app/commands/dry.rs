@@ -0,0 +1,29 @@
+//! `/dry` command handler — assemble a chat turn and show it without
+//! calling the model (see [`super::super::prompt_inspect`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /dry ─────────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_dry_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /dry <message> — show the request without sending it".to_string()
+            );
+            return;
+        }
+        if self.chat_busy {
+            self.log(LogLevel::Info, "Still thinking… please wait.".to_string());
+            return;
+        }
+        let message = args.join(" ");
+        self.log(LogLevel::Info, format!("› (dry run) {message}"));
+        self.chat_busy = true;
+        self.dry_run_chat_message(&message);
+    }
+}
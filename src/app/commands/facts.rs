@@ -0,0 +1,111 @@
+//! `/memory facts` command handlers — review and edit the semantic memory
+//! lane (see [`crate::facts`]).
+
+use crate::facts::{self, Fact};
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /memory facts ────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_memory_facts_command(&mut self, args: &[&str]) {
+        let Some(facts) = self.load_semantic_facts() else {
+            return;
+        };
+        match args {
+            [] | ["list"] => self.list_semantic_facts(&facts),
+            ["add", text @ ..] if !text.is_empty() => {
+                let mut facts = facts;
+                facts.push(Fact::manual(&text.join(" ")));
+                self.save_semantic_facts(&facts, "Added fact.");
+            }
+            ["edit", index, text @ ..] if !text.is_empty() => {
+                let mut facts = facts;
+                let Some(slot) = self.fact_index(index, facts.len()) else {
+                    return;
+                };
+                facts[slot] = Fact::manual(&text.join(" "));
+                self.save_semantic_facts(&facts, &format!("Updated fact {}.", slot + 1));
+            }
+            ["remove" | "rm", index] => {
+                let mut facts = facts;
+                let Some(slot) = self.fact_index(index, facts.len()) else {
+                    return;
+                };
+                let removed = facts.remove(slot);
+                self.save_semantic_facts(&facts, &format!("Removed fact: {}", removed.text));
+            }
+            ["clear"] => self.save_semantic_facts(&[], "Cleared all semantic facts."),
+            ["distill"] => self.run_daemon_now(facts::FACTS_TASK),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /memory facts [list|add <text>|edit <n> <text>|remove <n>|clear|distill]"
+                    .to_string()
+            ),
+        }
+    }
+
+    fn list_semantic_facts(&mut self, facts: &[Fact]) {
+        if facts.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No semantic facts yet. Add one with /memory facts add <text>, or distill them with /memory facts distill (/daemon start {} keeps them fresh).",
+                    facts::FACTS_TASK
+                ),
+            );
+            return;
+        }
+        self.log(LogLevel::Info, format!("Semantic facts ({}):", facts.len()));
+        for (index, fact) in facts.iter().enumerate() {
+            let marker = if fact.is_manual() { " (manual)" } else { "" };
+            self.log(
+                LogLevel::Info,
+                format!("  {:>2}. {}{marker}", index + 1, fact.text),
+            );
+        }
+    }
+
+    fn load_semantic_facts(&mut self) -> Option<Vec<Fact>> {
+        match self.runtime.block_on(self.rice.load_facts()) {
+            Ok(facts) => Some(facts),
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Semantic facts unavailable: {err:#}")
+                );
+                None
+            }
+        }
+    }
+
+    fn save_semantic_facts(&mut self, facts: &[Fact], done: &str) {
+        match self.runtime.block_on(self.rice.save_facts(facts)) {
+            Ok(()) => self.log(LogLevel::Info, done.to_string()),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Semantic facts not saved: {err:#}")
+            ),
+        }
+    }
+
+    /// Parse a 1-based fact number from the `/memory facts` listing.
+    fn fact_index(&mut self, raw: &str, len: usize) -> Option<usize> {
+        match raw.parse::<usize>() {
+            Ok(number) if (1..=len).contains(&number) => Some(number - 1),
+            _ => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("No fact #{raw}. See /memory facts.")
+                );
+                None
+            }
+        }
+    }
+}
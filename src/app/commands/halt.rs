@@ -0,0 +1,93 @@
+//! `/halt` and `/resume-auto` command handlers — the kill switch for
+//! daemons, agent windows and tool loops (see [`super::super::halt`]).
+
+use super::super::App;
+use super::super::daemon::AgentWindowStatus;
+use super::super::halt;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /halt ────────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_halt_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] => self.halt_autonomy(),
+            ["status"] => self.show_halt_status(),
+            _ => log_src!(self, LogLevel::Warn, "Usage: /halt [status]".to_string()),
+        }
+    }
+
+    pub(crate) fn handle_resume_auto_command(&mut self, _args: Vec<&str>) {
+        if !halt::release() {
+            self.log(LogLevel::Info, "Autonomous work is not halted.".to_string());
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            "▶ Autonomous work resumed: daemon tasks run on their schedule and agents can be spawned again."
+                .to_string(),
+        );
+    }
+
+    /// Cancel every daemon run, agent window and chat tool loop, and block
+    /// new autonomous work until `/resume-auto` (also bound to Ctrl+X).
+    pub(crate) fn halt_autonomy(&mut self) {
+        if !halt::engage() {
+            self.show_halt_status();
+            return;
+        }
+        // Running windows are cancelled by their supervisors; windows
+        // waiting for a reply have no run to cancel, so close them here.
+        let mut running = 0;
+        let mut waiting = Vec::new();
+        for window in self.agent_windows.iter().filter(|w| w.status.is_open()) {
+            if self
+                .window_active_runs
+                .get(&window.id)
+                .copied()
+                .unwrap_or(0)
+                > 0
+            {
+                running += 1;
+            } else if window.status == AgentWindowStatus::WaitingForInput {
+                waiting.push(window.id);
+            }
+        }
+        let closed = waiting.len();
+        for window_id in waiting {
+            self.stop_window_run(window_id, AgentWindowStatus::Killed, "halted".to_string());
+        }
+        let daemons = self.daemon_handles.len();
+        self.log(
+            LogLevel::Warn,
+            format!(
+                "⏹ Halted: cancelled {running} running and {closed} waiting agent window(s) \
+                 and any in-flight tool loops; {daemons} daemon task(s) keep their schedule but \
+                 skip runs. /resume-auto re-enables autonomous work."
+            ),
+        );
+    }
+
+    fn show_halt_status(&mut self) {
+        let line = if halt::active() {
+            "⏹ Autonomous work is halted. /resume-auto re-enables it."
+        } else {
+            "Autonomous work is running. /halt (or Ctrl+X) stops it."
+        };
+        self.log(LogLevel::Info, line.to_string());
+    }
+
+    /// Warn and return `true` when `/halt` blocks starting autonomous work.
+    pub(crate) fn refuse_while_halted(&mut self) -> bool {
+        if !halt::active() {
+            return false;
+        }
+        log_src!(
+            self,
+            LogLevel::Warn,
+            "Autonomous work is halted. /resume-auto re-enables it.".to_string()
+        );
+        true
+    }
+}
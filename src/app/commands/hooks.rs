@@ -0,0 +1,129 @@
+//! `/hooks` command handler — list, generate, and revoke webhook URLs.
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::webhooks;
+
+// ── /hooks ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_hooks_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            self.list_webhooks();
+            return;
+        }
+
+        match args[0] {
+            "list" => self.list_webhooks(),
+            "new" | "generate" => {
+                if let Some(name) = args.get(1) {
+                    self.generate_webhook(name);
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /hooks new <task-name>".to_string()
+                    );
+                }
+            }
+            "revoke" | "remove" => {
+                if let Some(name) = args.get(1) {
+                    self.revoke_webhook(name);
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /hooks revoke <task-name>".to_string()
+                    );
+                }
+            }
+            other => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown /hooks command: {other}")
+            ),
+        }
+    }
+
+    fn list_webhooks(&mut self) {
+        match self.webhook_port {
+            Some(port) => self.log(
+                LogLevel::Info,
+                format!("Webhook listener: 127.0.0.1:{port}"),
+            ),
+            None => self.log(
+                LogLevel::Info,
+                "Webhook listener is off. Set MEMINI_WEBHOOK_PORT and restart to enable it."
+                    .to_string(),
+            ),
+        }
+
+        let store = webhooks::load_webhook_store();
+        if store.secrets.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No webhooks configured. Use /hooks new <task-name>.".to_string(),
+            );
+            return;
+        }
+
+        let mut entries: Vec<(String, String)> = store.secrets.into_iter().collect();
+        entries.sort();
+        for (name, secret) in entries {
+            let line = match self.webhook_port {
+                Some(port) => webhooks::hook_url(port, &name, &secret),
+                None => format!("/hook/{name} (listener off)"),
+            };
+            self.log(LogLevel::Info, format!("  {name} -- POST {line}"));
+        }
+    }
+
+    fn generate_webhook(&mut self, name: &str) {
+        let mut store = webhooks::load_webhook_store();
+        let secret = webhooks::generate_secret();
+        let key = name.to_ascii_lowercase();
+        store.secrets.insert(key.clone(), secret.clone());
+        if let Err(err) = webhooks::persist_webhook_store(&store) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save webhook: {err:#}")
+            );
+            return;
+        }
+
+        let port = self.webhook_port.unwrap_or(0);
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Webhook for '{name}': POST {}",
+                webhooks::hook_url(port, &key, &secret)
+            ),
+        );
+        if self.webhook_port.is_none() {
+            self.log(
+                LogLevel::Warn,
+                "Listener is off — set MEMINI_WEBHOOK_PORT and restart before using this URL."
+                    .to_string(),
+            );
+        }
+    }
+
+    fn revoke_webhook(&mut self, name: &str) {
+        let mut store = webhooks::load_webhook_store();
+        if store.secrets.remove(&name.to_ascii_lowercase()).is_none() {
+            log_src!(self, LogLevel::Warn, format!("No webhook for '{name}'."));
+            return;
+        }
+        if let Err(err) = webhooks::persist_webhook_store(&store) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save webhooks: {err:#}")
+            );
+            return;
+        }
+        self.log(LogLevel::Info, format!("Webhook for '{name}' revoked."));
+    }
+}
@@ -0,0 +1,66 @@
+//! `/incognito` command handler — stop writing chat turns, traces and
+//! focus to Rice for the rest of the session (see [`crate::rice::incognito`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::rice;
+
+// ── /incognito ───────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_incognito_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["status"] => self.show_incognito_status(),
+            ["on"] => self.set_incognito(true),
+            ["off"] => self.set_incognito(false),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /incognito [on|off|status]".to_string()
+            ),
+        }
+    }
+
+    fn show_incognito_status(&mut self) {
+        let line = if rice::incognito() {
+            "Incognito is on: nothing from this session is committed to memory."
+        } else {
+            "Incognito is off: turns are remembered as usual."
+        };
+        self.log(LogLevel::Info, line.to_string());
+    }
+
+    fn set_incognito(&mut self, on: bool) {
+        if rice::incognito() == on {
+            self.show_incognito_status();
+            return;
+        }
+        if on {
+            rice::set_incognito(true);
+            self.log(
+                LogLevel::Info,
+                "🕶 Incognito on — traces, focus and the conversation thread are no longer saved. \
+                 Turns stay in context until /incognito off."
+                    .to_string(),
+            );
+            return;
+        }
+        rice::set_incognito(false);
+        // Turns from the incognito stretch were never saved; go back to
+        // the stored thread so a later save cannot persist them.  Without
+        // Rice nothing is ever stored, so the thread is kept.
+        let dropped = match self.runtime.block_on(self.rice.load_thread()) {
+            Ok(thread) => {
+                let dropped = self.conversation_thread.len().saturating_sub(thread.len()) / 2;
+                self.conversation_thread = thread;
+                dropped
+            }
+            _ => 0,
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Incognito off — memory writes resumed; dropped {dropped} incognito turn(s) from the thread."),
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! `/index` command handler and the background indexer behind
+//! `workspace_semantic_search` (see [`crate::code_index`]).
+
+use std::time::Duration;
+
+use tokio::sync::mpsc;
+
+use super::super::App;
+use super::super::daemon::{AgentEvent, ChatLogLevel};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::code_index::{self, BuildSummary, IndexState};
+use crate::openai::OpenAiClient;
+
+// ── /index ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_index_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["status"] => self.show_index_status(),
+            ["rebuild"] => self.rebuild_index(false),
+            ["rebuild", "--full"] => self.rebuild_index(true),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /index [status] | /index rebuild [--full]".to_string()
+            ),
+        }
+    }
+
+    fn show_index_status(&mut self) {
+        let status = code_index::status();
+        let state = match &status.state {
+            IndexState::Idle => "idle".to_string(),
+            IndexState::Building { done, total } => format!("indexing {done}/{total} file(s)"),
+            IndexState::Failed(err) => format!("last pass failed: {err}"),
+        };
+        let updated = status
+            .updated
+            .map(|at| at.format("%H:%M:%S").to_string())
+            .unwrap_or_else(|| "not this session".to_string());
+        let config = code_index::config();
+        let refresh = if config.auto {
+            format!("every {} min", config.refresh_minutes.max(1))
+        } else {
+            "off ([index] auto = false)".to_string()
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Workspace index for {}:", status.root.display()),
+        );
+        self.log(
+            LogLevel::Info,
+            format!(
+                "  {} file(s), {} chunk(s) — {state}",
+                status.files, status.chunks
+            ),
+        );
+        self.log(
+            LogLevel::Info,
+            format!("  last pass: {updated}; background refresh: {refresh}"),
+        );
+    }
+
+    /// Run one pass now; `full` re-embeds every file.
+    fn rebuild_index(&mut self, full: bool) {
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("Cannot index: {err}"));
+                return;
+            }
+        };
+        if matches!(code_index::status().state, IndexState::Building { .. }) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Indexing is already running; see /index status.".to_string()
+            );
+            return;
+        }
+        let tx = self.daemon_tx.clone();
+        let openai = self.openai.clone();
+        self.log(
+            LogLevel::Info,
+            "Indexing the workspace in the background…".to_string(),
+        );
+        self.runtime.spawn(async move {
+            let result = code_index::build(&openai, &key, full).await;
+            report_pass(&tx, result, true);
+        });
+    }
+
+    /// Start the indexer: one pass now, then incremental passes every
+    /// `[index] refresh_minutes`.  Skipped with `auto = false` or no key.
+    pub(crate) fn start_code_indexer(&mut self) {
+        let config = code_index::config();
+        if !config.auto {
+            return;
+        }
+        let Ok(key) = self.ensure_openai_key() else {
+            return;
+        };
+        let interval = Duration::from_secs(config.refresh_minutes.max(1) * 60);
+        let tx = self.daemon_tx.clone();
+        let openai = self.openai.clone();
+        self.runtime.spawn(run_indexer(openai, key, interval, tx));
+    }
+}
+
+async fn run_indexer(
+    openai: OpenAiClient,
+    key: String,
+    interval: Duration,
+    tx: mpsc::UnboundedSender<AgentEvent>,
+) {
+    let mut first = true;
+    loop {
+        let result = code_index::build(&openai, &key, false).await;
+        report_pass(&tx, result, first);
+        first = false;
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Log a finished pass.  Quiet passes that changed nothing stay silent
+/// unless `verbose`.
+fn report_pass(
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    result: anyhow::Result<BuildSummary>,
+    verbose: bool,
+) {
+    let (line, level) = match result {
+        Ok(summary) if verbose || summary.embedded > 0 || summary.removed > 0 => (
+            format!(
+                "Workspace index: {} file(s), {} chunk(s) ({} re-embedded, {} removed).",
+                summary.files, summary.chunks, summary.embedded, summary.removed
+            ),
+            ChatLogLevel::Info,
+        ),
+        Ok(_) => return,
+        Err(err) => (
+            format!("Workspace indexing failed: {err:#}"),
+            ChatLogLevel::Warn,
+        ),
+    };
+    let _ = tx.send(AgentEvent::ChatProgress { line, level });
+}
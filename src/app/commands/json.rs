@@ -0,0 +1,139 @@
+//! `/json` command handler — one-shot structured output validated against
+//! a schema from `$MEMINI_HOME/schemas` (see [`super::super::schemas`]).
+
+use serde_json::{Value, json};
+
+use super::super::App;
+use super::super::artifacts::ArtifactRun;
+use super::super::daemon::{AgentEvent, ChatLogLevel};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::schemas;
+use crate::routing::TaskKind;
+
+// ── /json ────────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_json_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_schemas(),
+            [name, prompt @ ..] if !prompt.is_empty() => {
+                self.run_structured(name, &prompt.join(" "))
+            }
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /json list | /json <schema> <prompt>".to_string()
+            ),
+        }
+    }
+
+    fn list_schemas(&mut self) {
+        let dir = schemas::schemas_dir();
+        match schemas::load_schemas() {
+            Ok(list) if list.is_empty() => self.log(
+                LogLevel::Info,
+                format!(
+                    "No schemas yet. Add <name>.json files to {}.",
+                    dir.display()
+                ),
+            ),
+            Ok(list) => {
+                self.log(LogLevel::Info, format!("Schemas in {}:", dir.display()));
+                for schema in list {
+                    let mode = if schema.text_format()["strict"] == Value::Bool(true) {
+                        "strict"
+                    } else {
+                        "validated"
+                    };
+                    self.log(LogLevel::Info, format!("  {} ({mode})", schema.name));
+                }
+            }
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to load schemas: {err:#}")
+            ),
+        }
+    }
+
+    /// Ask for JSON matching `name`, then save it as an artifact and as the
+    /// Rice variable `json.<name>`.
+    fn run_structured(&mut self, name: &str, prompt: &str) {
+        let schema = match schemas::find_schema(name) {
+            Ok(schema) => schema,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err:#}"));
+                return;
+            }
+        };
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("Cannot run /json: {err}"));
+                return;
+            }
+        };
+        let model = self
+            .active_agent
+            .model
+            .clone()
+            .unwrap_or_else(|| self.openai.model_for(TaskKind::for_message(prompt)));
+        let input = vec![
+            json!({"role": "system", "content": self.active_agent.persona.clone()}),
+            json!({"role": "user", "content": prompt}),
+        ];
+        let tx = self.daemon_tx.clone();
+        let openai = self.openai.clone();
+        let rice_handle = self.spawn_rice_handle();
+        self.log(
+            LogLevel::Info,
+            format!("Asking {model} for `{}` JSON…", schema.name),
+        );
+        self.runtime.spawn(async move {
+            let value = match schemas::generate(&openai, &key, &model, input, &schema).await {
+                Ok(value) => value,
+                Err(err) => {
+                    let _ = tx.send(AgentEvent::ChatProgress {
+                        line: format!("/json {}: {err:#}", schema.name),
+                        level: ChatLogLevel::Error,
+                    });
+                    return;
+                }
+            };
+            let pretty = serde_json::to_string_pretty(&value).unwrap_or_default();
+            let _ = tx.send(AgentEvent::ChatMarkdown {
+                label: format!("json:{}", schema.name),
+                body: format!("```json\n{pretty}\n```"),
+            });
+            let mut saved = Vec::new();
+            match ArtifactRun::start("json").save(&format!("{}.json", schema.name), &pretty) {
+                Ok(path) => saved.push(path.display().to_string()),
+                Err(err) => {
+                    let _ = tx.send(AgentEvent::ChatProgress {
+                        line: format!("Failed to save /json artifact: {err:#}"),
+                        level: ChatLogLevel::Warn,
+                    });
+                }
+            }
+            let var = format!("json.{}", schema.name);
+            if let Ok(mut rice) = rice_handle.await {
+                match rice.set_variable(&var, value, "json").await {
+                    Ok(()) => saved.push(format!("Rice variable {var}")),
+                    Err(err) => {
+                        let _ = tx.send(AgentEvent::ChatProgress {
+                            line: format!("Failed to store {var}: {err:#}"),
+                            level: ChatLogLevel::Warn,
+                        });
+                    }
+                }
+            }
+            if !saved.is_empty() {
+                let _ = tx.send(AgentEvent::ChatProgress {
+                    line: format!("Saved to {}.", saved.join(" and ")),
+                    level: ChatLogLevel::Info,
+                });
+            }
+        });
+    }
+}
@@ -0,0 +1,54 @@
+//! `/lang` command handler — list UI languages or switch to one for this
+//! session (see [`crate::i18n`]).
+
+use crate::i18n;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /lang ────────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_lang_command(&mut self, args: Vec<&str>) {
+        match args.first().copied() {
+            None | Some("list") => self.show_languages(),
+            Some(lang) => self.apply_language(lang),
+        }
+    }
+
+    fn show_languages(&mut self) {
+        let active = i18n::current();
+        self.log(
+            LogLevel::Info,
+            format!("Language: {} ({})", active.name, active.lang),
+        );
+        for code in i18n::available() {
+            let marker = if code == active.lang { "●" } else { " " };
+            self.log(LogLevel::Info, format!("  {marker} {code}"));
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Add or override strings in {}/<code>.toml",
+                i18n::locales_dir().display()
+            ),
+        );
+    }
+
+    fn apply_language(&mut self, lang: &str) {
+        match i18n::load(lang) {
+            Ok(catalog) => {
+                let summary = format!(
+                    "Language set to {} ({}, {} translated strings).",
+                    catalog.name,
+                    catalog.lang,
+                    catalog.translated()
+                );
+                i18n::set(catalog);
+                self.log(LogLevel::Info, summary);
+            }
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+}
@@ -1,21 +1,28 @@
 //! `/mcp` command handlers — connect, disconnect, auth, tools, and token
 //! management for MCP (Model Context Protocol) servers.
 
+use std::collections::HashMap;
 use std::env;
+use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{Result, anyhow};
-use serde_json::{Value, json};
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Value, json};
 
-use crate::constants::ACTIVE_MCP_VAR;
-use crate::mcp;
+use crate::constants::{ACTIVE_MCP_VAR, CONNECTED_MCPS_VAR};
 use crate::mcp::config::{McpAuth, McpConfig, McpServer};
-use crate::openai::format_json;
+use crate::mcp::import::{self, ImportSource};
+use crate::mcp::oauth::OAuthToken;
+use crate::mcp::{self, McpConnection};
 
 use super::super::App;
+use super::super::artifacts::ArtifactRun;
+use super::super::daemon::AgentEvent;
 use super::super::log_src;
 use super::super::logging::{LogLevel, mask_key};
+use super::super::progress;
 use super::super::store::persist_local_mcp_store;
+use super::super::tool_form::{self, ToolForm};
 
 // ── MCP command dispatch ─────────────────────────────────────────────
 
@@ -57,10 +64,16 @@ impl App {
                 }
             }
             "auth" => {
-                if let Some(target) = args.get(1) {
-                    self.authenticate_mcp(target);
-                } else {
-                    log_src!(self, LogLevel::Warn, "Usage: /mcp auth <id>".to_string());
+                let device = args[1..].contains(&"--device");
+                let target = args[1..].iter().find(|arg| !arg.starts_with("--"));
+                match target {
+                    Some(target) if device => self.authenticate_mcp_device(target),
+                    Some(target) => self.authenticate_mcp(target),
+                    None => log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /mcp auth <id> [--device]".to_string()
+                    ),
                 }
             }
             "auth-code" => {
@@ -100,7 +113,28 @@ impl App {
                     );
                 }
             }
+            "add" => self.add_mcp_server(&args[1..]),
+            "remove" | "rm" => {
+                if let Some(target) = args.get(1) {
+                    self.remove_mcp_server(target);
+                } else {
+                    log_src!(self, LogLevel::Warn, "Usage: /mcp remove <id>".to_string());
+                }
+            }
+            "import" => {
+                if let Some(source) = args.get(1) {
+                    let path = (args.len() > 2).then(|| args[2..].join(" "));
+                    self.import_mcp_servers(source, path.as_deref());
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /mcp import claude|cursor [path]".to_string()
+                    );
+                }
+            }
             "reload" => self.reload_mcp_config(),
+            "roots" => self.handle_mcp_roots(&args[1..]),
             other => {
                 log_src!(
                     self,
@@ -112,6 +146,90 @@ impl App {
     }
 }
 
+// ── Roots ────────────────────────────────────────────────────────────
+
+impl App {
+    /// `/mcp roots [list] | add <path> | remove <path>`.
+    fn handle_mcp_roots(&mut self, args: &[&str]) {
+        let changed = match args {
+            [] | ["list"] => {
+                self.log(LogLevel::Info, "Roots exposed to MCP servers:".to_string());
+                for root in mcp::roots::current() {
+                    self.log(LogLevel::Info, format!("  {}", root.display()));
+                }
+                return;
+            }
+            ["add", path @ ..] if !path.is_empty() => match mcp::roots::add(&path.join(" ")) {
+                Ok(Some(dir)) => {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Exposing {} to MCP servers.", dir.display()),
+                    );
+                    true
+                }
+                Ok(None) => {
+                    self.log(LogLevel::Info, "Already exposed.".to_string());
+                    false
+                }
+                Err(err) => {
+                    log_src!(self, LogLevel::Warn, format!("Cannot add root: {err:#}"));
+                    false
+                }
+            },
+            ["remove" | "rm", path @ ..] if !path.is_empty() => {
+                match mcp::roots::remove(&path.join(" ")) {
+                    Some(dir) => {
+                        self.log(
+                            LogLevel::Info,
+                            format!("No longer exposing {}.", dir.display()),
+                        );
+                        true
+                    }
+                    None => {
+                        log_src!(
+                            self,
+                            LogLevel::Warn,
+                            format!("{} is not an exposed root. See /mcp roots.", path.join(" "))
+                        );
+                        false
+                    }
+                }
+            }
+            _ => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /mcp roots [list] | add <path> | remove <path>".to_string()
+                );
+                false
+            }
+        };
+        if changed {
+            self.notify_mcp_roots_changed();
+        }
+    }
+
+    /// Send `notifications/roots/list_changed` to every connected server.
+    fn notify_mcp_roots_changed(&mut self) {
+        let results: Vec<(String, Result<()>)> = self.runtime.block_on(async {
+            let mut results = Vec::new();
+            for (id, connection) in &self.mcp_connections {
+                results.push((id.clone(), connection.client.notify_roots_changed().await));
+            }
+            results
+        });
+        for (id, result) in results {
+            if let Err(err) = result {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Could not notify {id} of the new roots: {err:#}")
+                );
+            }
+        }
+    }
+}
+
 // ── Server listing ───────────────────────────────────────────────────
 
 impl App {
@@ -139,7 +257,7 @@ impl App {
                 format!(
                     "- {} ({}) [transport: {transport}, auth: {auth}]",
                     server.display_name(),
-                    server.url
+                    server.endpoint()
                 ),
             );
         }
@@ -149,7 +267,7 @@ impl App {
 // ── Connect / disconnect ─────────────────────────────────────────────
 
 impl App {
-    fn connect_mcp(&mut self, target: &str) {
+    pub(crate) fn connect_mcp(&mut self, target: &str) {
         let Some(server) = self.mcp_config.find_by_id_or_name(target) else {
             log_src!(
                 self,
@@ -208,14 +326,18 @@ impl App {
             }
         }
 
-        let connect_result = self
-            .runtime
-            .block_on(mcp::connect_http(&server, bearer.clone()));
+        self.warn_insecure_mcp(&server);
+        let connect_result = progress::block_on(
+            &self.runtime,
+            format!("Connecting {}", server.display_name()),
+            self.backends.mcp.connect(&server, bearer.clone()),
+        );
 
         match connect_result {
             Ok(connection) => {
                 self.active_mcp = Some(server.clone());
                 self.mcp_connections.insert(server.id.clone(), connection);
+                self.remember_connected_mcps();
 
                 let store_result = self.runtime.block_on(self.rice.set_variable(
                     ACTIVE_MCP_VAR,
@@ -254,6 +376,22 @@ impl App {
         }
     }
 
+    /// `insecure_skip_verify` turns off certificate checks, so say so on
+    /// every connect.
+    fn warn_insecure_mcp(&mut self, server: &McpServer) {
+        if server.insecure_skip_verify {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "⚠ TLS certificate verification is OFF for {} (insecure_skip_verify). \
+                     Anyone on the network path can read and alter this connection.",
+                    server.display_name()
+                )
+            );
+        }
+    }
+
     fn disconnect_mcp(&mut self, target: Option<&str>) {
         if self.mcp_connections.is_empty() {
             self.log(LogLevel::Info, "No MCP connections to close.".to_string());
@@ -263,6 +401,7 @@ impl App {
         if matches!(target, Some("all")) {
             let count = self.mcp_connections.len();
             self.mcp_connections.clear();
+            self.remember_connected_mcps();
             self.log(LogLevel::Info, format!("Closed {count} MCP connection(s)."));
             return;
         }
@@ -279,6 +418,7 @@ impl App {
         };
 
         if self.mcp_connections.remove(&id).is_some() {
+            self.remember_connected_mcps();
             self.log(LogLevel::Info, format!("Closed MCP connection '{id}'."));
         } else {
             self.log(
@@ -291,9 +431,17 @@ impl App {
 
 // ── Startup auto-connect ─────────────────────────────────────────────
 
+/// Outcome of one startup connect attempt.
+enum Reconnect {
+    Connected(McpConnection, Result<usize>),
+    Failed(String),
+}
+
 impl App {
-    /// Auto-connect to every configured MCP server that already has usable
-    /// credentials stored (or requires no auth). This runs during startup.
+    /// Auto-connect at startup, in parallel: every configured server that
+    /// has usable credentials stored (or requires no auth), plus — with
+    /// `"reconnect_on_startup": true` in `mcp.json` — the servers that were
+    /// connected when memini last ran.  Failures keep the saved state.
     pub(crate) fn autoconnect_saved_mcps(&mut self) {
         let enabled = match env::var("MEMINI_MCP_AUTOCONNECT") {
             Ok(value) => {
@@ -310,11 +458,24 @@ impl App {
             return;
         }
 
+        let mut previous: Vec<String> = Vec::new();
+        if self.mcp_config.reconnect_on_startup {
+            previous = self.load_connected_mcps_from_rice();
+            if let Some(active) = &self.active_mcp {
+                if !previous.contains(&active.id) {
+                    previous.push(active.id.clone());
+                }
+            }
+        }
+
         let mut connect_plan: Vec<(McpServer, Option<String>)> = Vec::new();
         for server in self.mcp_config.servers.clone() {
             if self.mcp_connections.contains_key(&server.id) {
                 continue;
             }
+            if server.transport.as_deref().unwrap_or("http") != "http" {
+                continue;
+            }
 
             let bearer = self.resolve_mcp_token(&server);
             let has_token = bearer
@@ -328,7 +489,7 @@ impl App {
                 None => true,
             };
 
-            if should_connect {
+            if should_connect || previous.contains(&server.id) {
                 connect_plan.push((server, bearer));
             }
         }
@@ -337,73 +498,98 @@ impl App {
             return;
         }
 
+        let names: Vec<String> = connect_plan
+            .iter()
+            .map(|(server, _)| server.display_name())
+            .collect();
         self.log(
             LogLevel::Info,
-            format!("Auto-connecting {} MCP server(s)…", connect_plan.len()),
+            format!(
+                "Auto-connecting {} MCP server(s): {}…",
+                connect_plan.len(),
+                names.join(", ")
+            ),
         );
 
+        for (server, _) in &connect_plan {
+            self.warn_insecure_mcp(server);
+        }
         let connect_timeout = Duration::from_secs(10);
         let tools_timeout = Duration::from_secs(10);
 
-        for (server, bearer) in connect_plan {
-            let id = server.id.clone();
-            let label = server.display_name();
-
-            let connect_result = self.runtime.block_on(async {
-                tokio::time::timeout(connect_timeout, mcp::connect_http(&server, bearer.clone()))
+        let handles: Vec<_> = connect_plan
+            .into_iter()
+            .map(|(server, bearer)| {
+                let backend = self.backends.mcp.clone();
+                let task = async move {
+                    let outcome = match tokio::time::timeout(
+                        connect_timeout,
+                        backend.connect(&server, bearer),
+                    )
                     .await
-            });
-
-            let connection = match connect_result {
-                Ok(Ok(connection)) => connection,
-                Ok(Err(err)) => {
-                    log_src!(
-                        self,
-                        LogLevel::Warn,
-                        format!("Auto-connect failed for {label}: {err:#}")
-                    );
-                    continue;
-                }
-                Err(_) => {
-                    log_src!(
-                        self,
-                        LogLevel::Warn,
-                        format!("Auto-connect timed out for {label}.")
-                    );
-                    continue;
-                }
-            };
-
-            self.mcp_connections.insert(id.clone(), connection);
-
-            let tools_result = {
-                let Some(conn) = self.mcp_connections.get_mut(&id) else {
-                    continue;
+                    {
+                        Ok(Ok(mut connection)) => {
+                            let tools = match tokio::time::timeout(
+                                tools_timeout,
+                                mcp::refresh_tools(&mut connection),
+                            )
+                            .await
+                            {
+                                Ok(Ok(tools)) => Ok(tools.len()),
+                                Ok(Err(err)) => Err(err),
+                                Err(_) => Err(anyhow!("tool list timed out")),
+                            };
+                            Reconnect::Connected(connection, tools)
+                        }
+                        Ok(Err(err)) => Reconnect::Failed(format!("{err:#}")),
+                        Err(_) => Reconnect::Failed("timed out".to_string()),
+                    };
+                    (server, outcome)
                 };
-                self.runtime.block_on(async {
-                    tokio::time::timeout(tools_timeout, mcp::refresh_tools(conn)).await
-                })
-            };
-
-            match tools_result {
-                Ok(Ok(tools)) => {
-                    self.log(
-                        LogLevel::Info,
-                        format!("Connected MCP: {label} ({} tools).", tools.len()),
-                    );
+                self.runtime.spawn(task)
+            })
+            .collect();
+        let total = handles.len();
+        let connecting = progress::start_blocking(format!("Connecting {total} MCP server(s)"));
+        let results: Vec<_> = self.runtime.block_on(async {
+            let mut results = Vec::with_capacity(total);
+            for (done, handle) in handles.into_iter().enumerate() {
+                connecting.set_detail(format!("{done}/{total}"));
+                if let Ok(result) = handle.await {
+                    results.push(result);
                 }
-                Ok(Err(err)) => {
-                    log_src!(
-                        self,
-                        LogLevel::Warn,
-                        format!("Connected {label}, but tool list failed: {err:#}")
-                    );
+            }
+            results
+        });
+        drop(connecting);
+
+        for (server, outcome) in results {
+            let label = server.display_name();
+            match outcome {
+                Reconnect::Connected(connection, tools) => {
+                    self.mcp_connections.insert(server.id.clone(), connection);
+                    match tools {
+                        Ok(count) => self.log(
+                            LogLevel::Info,
+                            format!("Connected MCP: {label} ({count} tools)."),
+                        ),
+                        Err(err) => log_src!(
+                            self,
+                            LogLevel::Warn,
+                            format!("Connected {label}, but tool list failed: {err:#}")
+                        ),
+                    }
                 }
-                Err(_) => {
+                Reconnect::Failed(err) => {
+                    let hint = if previous.contains(&server.id) {
+                        format!(" Kept as saved; /mcp connect {} to retry.", server.id)
+                    } else {
+                        String::new()
+                    };
                     log_src!(
                         self,
                         LogLevel::Warn,
-                        format!("Connected {label}, but tool list timed out.")
+                        format!("Auto-connect failed for {label}: {err}.{hint}")
                     );
                 }
             }
@@ -415,11 +601,77 @@ impl App {
             }
         }
     }
+
+    /// Ids of the servers connected when memini last ran.
+    fn load_connected_mcps_from_rice(&mut self) -> Vec<String> {
+        match self
+            .runtime
+            .block_on(self.rice.get_variable(CONNECTED_MCPS_VAR))
+        {
+            Ok(Some(value)) => serde_json::from_value(value).unwrap_or_default(),
+            Ok(None) => Vec::new(),
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Saved MCP connections unavailable: {err:#}")
+                );
+                Vec::new()
+            }
+        }
+    }
+
+    /// Record the connected server ids for `reconnect_on_startup`.
+    fn remember_connected_mcps(&mut self) {
+        let mut ids: Vec<String> = self.mcp_connections.keys().cloned().collect();
+        ids.sort();
+        let result = self.runtime.block_on(self.rice.set_variable(
+            CONNECTED_MCPS_VAR,
+            json!(ids),
+            "explicit",
+        ));
+        if let Err(err) = result {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Failed to persist MCP connections: {err:#}")
+            );
+        }
+    }
 }
 
 // ── Tool listing & invocation ────────────────────────────────────────
 
 impl App {
+    /// Swap in tool lists that servers re-published through
+    /// `notifications/tools/list_changed` (called every tick).
+    pub(crate) fn apply_mcp_tool_changes(&mut self) {
+        for (server_id, tools) in mcp::tool_changes::take() {
+            let Some(connection) = self.mcp_connections.get_mut(&server_id) else {
+                continue;
+            };
+            let change = mcp::tool_changes::diff(&connection.tool_cache, &tools);
+            connection.tool_cache = tools;
+            if change.is_empty() {
+                continue;
+            }
+            let mut parts = Vec::new();
+            for (label, names) in [
+                ("added", &change.added),
+                ("removed", &change.removed),
+                ("changed", &change.changed),
+            ] {
+                if !names.is_empty() {
+                    parts.push(format!("{label} {}", names.join(", ")));
+                }
+            }
+            self.log(
+                LogLevel::Info,
+                format!("🔄 {server_id} tools updated: {}", parts.join("; ")),
+            );
+        }
+    }
+
     /// Refresh and display the tool list from the active MCP connection.
     pub(crate) fn list_mcp_tools(&mut self, target: Option<&str>) {
         if self.mcp_connections.is_empty() {
@@ -455,7 +707,11 @@ impl App {
                 continue;
             };
 
-            let tools_result = self.runtime.block_on(mcp::refresh_tools(connection));
+            let tools_result = progress::block_on(
+                &self.runtime,
+                format!("Listing {id} tools"),
+                mcp::refresh_tools(connection),
+            );
 
             match tools_result {
                 Ok(tools) => {
@@ -488,7 +744,7 @@ impl App {
             log_src!(
                 self,
                 LogLevel::Warn,
-                "Usage: /mcp call <tool> <json>".to_string()
+                "Usage: /mcp call <tool> [json]".to_string()
             );
             return;
         };
@@ -515,32 +771,60 @@ impl App {
             .get(&server_id)
             .map(|connection| connection.tool_cache.clone())
             .unwrap_or_default();
+        let cached = tool_cache.iter().find(|t| t.name == tool_name);
 
-        if !tool_cache.is_empty() && !tool_cache.iter().any(|t| t.name == tool_name) {
+        if !tool_cache.is_empty() && cached.is_none() {
             log_src!(
                 self,
                 LogLevel::Warn,
                 format!("Tool '{tool}' not in cached list; attempting anyway.")
             );
         }
+        let schema = cached
+            .map(|t| t.input_schema.as_ref().clone())
+            .unwrap_or_default();
+        let namespaced = mcp::namespaced_tool_name(&server_id, tool_name);
 
-        let arg_value = if args.is_empty() {
-            json!({})
-        } else {
-            let raw = args.join(" ");
-            match serde_json::from_str::<Value>(&raw) {
-                Ok(value) => value,
-                Err(err) => {
-                    log_src!(self, LogLevel::Error, format!("Invalid JSON args: {err}"));
-                    return;
-                }
+        // No JSON given: prompt for required fields from the schema.
+        if args.is_empty() {
+            let form = ToolForm::new(&namespaced, &schema);
+            if form.fields.is_empty() {
+                self.execute_mcp_call(&namespaced, &schema, json!({}));
+            } else {
+                self.start_tool_form(form);
             }
-        };
+            return;
+        }
 
-        let namespaced = mcp::namespaced_tool_name(&server_id, tool_name);
-        match self.call_mcp_tool_value(&namespaced, arg_value) {
+        let raw = args.join(" ");
+        match serde_json::from_str::<Value>(&raw) {
+            Ok(value) => self.execute_mcp_call(&namespaced, &schema, value),
+            Err(err) => log_src!(self, LogLevel::Error, format!("Invalid JSON args: {err}")),
+        }
+    }
+
+    /// Validate `args` against the tool schema, then call and log the result.
+    pub(crate) fn execute_mcp_call(
+        &mut self,
+        tool: &str,
+        schema: &Map<String, Value>,
+        args: Value,
+    ) {
+        if let Err(err) = tool_form::validate(schema, &args) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Invalid arguments for {tool}: {err:#}")
+            );
+            return;
+        }
+        match self.call_mcp_tool_value(tool, args) {
             Ok(value) => {
-                let rendered = format_json(value);
+                // Images and blobs go to an artifact run instead of the log.
+                let artifacts = ArtifactRun::start("mcp-call");
+                let rendered = mcp::content::render_result(&value, |name, bytes| {
+                    artifacts.save_bytes(name, bytes)
+                });
                 self.log(LogLevel::Info, format!("Tool {tool} result:"));
                 self.log(LogLevel::Info, rendered);
             }
@@ -569,23 +853,27 @@ impl App {
                 LogLevel::Info,
                 format!("Connected MCP servers: {}", self.mcp_connections.len()),
             );
-            let mut entries: Vec<(String, String, usize)> = self
-                .mcp_connections
-                .values()
-                .map(|conn| {
-                    (
-                        conn.server.display_name(),
-                        conn.server.id.clone(),
-                        conn.tool_cache.len(),
-                    )
-                })
-                .collect();
+            let mut entries: Vec<(String, String, usize, Option<mcp::rate_limit::RateLimit>)> =
+                self.mcp_connections
+                    .values()
+                    .map(|conn| {
+                        (
+                            conn.server.display_name(),
+                            conn.server.id.clone(),
+                            conn.tool_cache.len(),
+                            conn.server.rate_limit,
+                        )
+                    })
+                    .collect();
             entries.sort_by(|a, b| a.1.cmp(&b.1));
-            for (name, id, tool_count) in entries {
+            for (name, id, tool_count, limit) in entries {
                 self.log(
                     LogLevel::Info,
                     format!("- {} ({}) [{} tools]", name, id, tool_count),
                 );
+                if let Some(limit) = limit {
+                    self.log(LogLevel::Info, format!("    {}", throttle_line(&id, limit)));
+                }
             }
             if let Some(server) = &self.active_mcp {
                 self.log(
@@ -599,7 +887,7 @@ impl App {
                 format!(
                     "Active MCP (saved): {} ({})",
                     server.display_name(),
-                    server.url
+                    server.endpoint()
                 ),
             );
         } else {
@@ -608,6 +896,32 @@ impl App {
     }
 }
 
+/// One-line rate limit summary for `/mcp status`.
+fn throttle_line(server_id: &str, limit: mcp::rate_limit::RateLimit) -> String {
+    let shape = format!(
+        "rate limit {}/min, burst {}",
+        limit.requests_per_minute,
+        limit.burst.unwrap_or(1)
+    );
+    let Some(status) = mcp::rate_limit::status(server_id) else {
+        return format!("{shape} — no calls yet");
+    };
+    let mut line = format!(
+        "{shape} — {} available, {} calls, {} throttled",
+        status.available, status.calls, status.throttled
+    );
+    if status.throttled > 0 {
+        line.push_str(&format!(
+            " (waited {:.1}s total)",
+            status.total_wait.as_secs_f64()
+        ));
+    }
+    if status.queued > 0 {
+        line.push_str(&format!(", {} queued", status.queued));
+    }
+    line
+}
+
 impl App {
     fn resolve_tool_target<'a>(&self, tool: &'a str) -> Result<(String, &'a str)> {
         if let Some((server_id, tool_name)) = mcp::split_namespaced_tool_name(tool) {
@@ -654,11 +968,15 @@ impl App {
             return;
         };
 
+        if auth.auth_type == "oauth_device" {
+            self.authenticate_mcp_device(target);
+            return;
+        }
         if auth.auth_type != "oauth_browser" {
             log_src!(
                 self,
                 LogLevel::Warn,
-                "Auth flow only supports oauth_browser.".to_string()
+                "Auth flow only supports oauth_browser and oauth_device.".to_string()
             );
             return;
         }
@@ -667,7 +985,13 @@ impl App {
         let client_secret = self.resolve_mcp_client_secret(auth);
         let mut oauth_logs = Vec::new();
 
-        let http_client = reqwest::Client::new();
+        let http_client = match crate::http::mcp_client(&server) {
+            Ok(client) => client,
+            Err(err) => {
+                log_src!(self, LogLevel::Error, format!("{err:#}"));
+                return;
+            }
+        };
 
         let prepare_result = self.runtime.block_on(mcp::oauth::prepare_auth(
             &http_client,
@@ -718,9 +1042,11 @@ impl App {
             ),
         );
 
-        let wait_result = self.runtime.block_on(async {
-            mcp::oauth::wait_for_oauth_callback(&pending, Duration::from_secs(120)).await
-        });
+        let wait_result = progress::block_on(
+            &self.runtime,
+            format!("Waiting for {} sign-in", server.display_name()),
+            mcp::oauth::wait_for_oauth_callback(&pending, Duration::from_secs(120)),
+        );
 
         match wait_result {
             Ok(token) => {
@@ -778,7 +1104,7 @@ impl App {
         }
 
         let pending = pending.clone();
-        let http_client = reqwest::Client::new();
+        let http_client = crate::http::client_or_default(crate::http::Interface::Mcp);
 
         let result = self
             .runtime
@@ -818,6 +1144,137 @@ impl App {
     }
 }
 
+impl App {
+    /// Device authorization grant: show a user code to enter on any device
+    /// and poll for the token in the background.  Works over SSH, where
+    /// the browser flow's localhost callback cannot be reached.
+    fn authenticate_mcp_device(&mut self, target: &str) {
+        let Some(server) = self.mcp_config.find_by_id_or_name(target) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown MCP server: {target}")
+            );
+            return;
+        };
+        let Some(auth) = server.auth.clone() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "No auth config for server.".to_string()
+            );
+            return;
+        };
+        if !matches!(auth.auth_type.as_str(), "oauth_browser" | "oauth_device") {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Device flow only supports OAuth servers.".to_string()
+            );
+            return;
+        }
+
+        let client_id = self.resolve_mcp_client_id(&server, &auth);
+        let client_secret = self.resolve_mcp_client_secret(&auth);
+        let mut oauth_logs = Vec::new();
+        let http_client = match crate::http::mcp_client(&server) {
+            Ok(client) => client,
+            Err(err) => {
+                log_src!(self, LogLevel::Error, format!("{err:#}"));
+                return;
+            }
+        };
+
+        let start_result = self.runtime.block_on(mcp::oauth::start_device_auth(
+            &http_client,
+            &server,
+            &auth,
+            client_id,
+            client_secret,
+            |line| oauth_logs.push(line),
+        ));
+        for line in oauth_logs.drain(..) {
+            self.log(LogLevel::Info, line);
+        }
+        let device = match start_result {
+            Ok(device) => device,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Device authorization failed: {err:#}")
+                );
+                return;
+            }
+        };
+
+        self.log(
+            LogLevel::Info,
+            format!(
+                "To sign in to {}, open {} on any device and enter the code:",
+                server.display_name(),
+                device.verification_uri
+            ),
+        );
+        self.log(LogLevel::Info, format!("    {}", device.user_code));
+        if let Some(complete) = &device.verification_uri_complete {
+            self.log(LogLevel::Info, format!("Or open: {complete}"));
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Waiting for approval (code expires in {}s)…",
+                device.expires_in
+            ),
+        );
+
+        let tx = self.daemon_tx.clone();
+        let server_id = server.id.clone();
+        self.runtime.spawn(async move {
+            let result = mcp::oauth::poll_device_token(&http_client, &device)
+                .await
+                .map_err(|err| format!("{err:#}"));
+            let _ = tx.send(AgentEvent::McpDeviceAuthFinished { server_id, result });
+        });
+    }
+
+    /// Store the token from a finished device flow and connect.
+    pub(crate) fn finish_mcp_device_auth(
+        &mut self,
+        server_id: String,
+        result: Result<OAuthToken, String>,
+    ) {
+        let token = match result {
+            Ok(token) => token,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Device sign-in for {server_id} failed: {err}")
+                );
+                return;
+            }
+        };
+        self.store_mcp_token(&server_id, &token.access_token);
+        if let Some(refresh) = &token.refresh_token {
+            self.store_mcp_refresh_token(&server_id, refresh);
+        }
+        if let (Some(client_id), Some(server)) = (
+            &token.client_id,
+            self.mcp_config.find_by_id_or_name(&server_id),
+        ) {
+            if let Some(auth) = &server.auth {
+                self.store_mcp_client_id(&server_id, client_id, auth);
+            }
+        }
+        self.log(
+            LogLevel::Info,
+            "OAuth complete (device). Token stored.".to_string(),
+        );
+        self.connect_mcp(&server_id);
+    }
+}
+
 // ── Token / credential helpers ───────────────────────────────────────
 
 impl App {
@@ -1008,3 +1465,296 @@ impl App {
         }
     }
 }
+
+// ── Adding / removing servers ────────────────────────────────────────
+
+const MCP_ADD_USAGE: &str =
+    "Usage: /mcp add <id> <url> [--transport http|stdio] [--header k=v] [--auth bearer|oauth]";
+
+/// Build a server entry from `/mcp add` arguments.  For `stdio` servers
+/// `<url>` is the command to launch and further words (or everything
+/// after `--`) are its arguments.
+fn parse_mcp_add_args(args: &[&str]) -> Result<McpServer> {
+    let mut positional = Vec::new();
+    let mut transport = None;
+    let mut headers = HashMap::new();
+    let mut auth_kind = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        // Everything after `--` belongs to the stdio command.
+        if *arg == "--" {
+            positional.extend(iter.by_ref().map(|rest| rest.to_string()));
+            break;
+        }
+        let (flag, inline) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag, Some(value)),
+            _ => (*arg, None),
+        };
+        if !flag.starts_with("--") {
+            positional.push(arg.to_string());
+            continue;
+        }
+        let Some(value) = inline.or_else(|| iter.next().copied()) else {
+            bail!("{flag} needs a value");
+        };
+        match flag {
+            "--transport" => match value {
+                "http" | "stdio" => transport = Some(value.to_string()),
+                other => bail!("Unknown transport '{other}' (expected http or stdio)"),
+            },
+            "--header" => {
+                let Some((key, val)) = value.split_once('=') else {
+                    bail!("Headers are written key=value, got '{value}'");
+                };
+                if key.trim().is_empty() {
+                    bail!("Header name is empty in '{value}'");
+                }
+                headers.insert(key.trim().to_string(), val.trim().to_string());
+            }
+            "--auth" => match value {
+                "bearer" | "oauth" | "none" => auth_kind = Some(value.to_string()),
+                other => bail!("Unknown auth '{other}' (expected bearer or oauth)"),
+            },
+            other => bail!("Unknown flag {other}"),
+        }
+    }
+
+    if positional.len() < 2 {
+        bail!("{MCP_ADD_USAGE}");
+    }
+    let id = positional.remove(0);
+    let target = positional.remove(0);
+    let stdio = transport.as_deref() == Some("stdio");
+    if !stdio && !positional.is_empty() {
+        bail!("Unexpected argument(s): {}", positional.join(" "));
+    }
+
+    let auth = match auth_kind.as_deref() {
+        Some("bearer") => Some(McpAuth {
+            auth_type: "bearer".to_string(),
+            bearer_env: Some(format!(
+                "{}_MCP_TOKEN",
+                id.to_ascii_uppercase().replace('-', "_")
+            )),
+            ..McpAuth::default()
+        }),
+        Some("oauth") => Some(McpAuth {
+            auth_type: "oauth_browser".to_string(),
+            ..McpAuth::default()
+        }),
+        _ => None,
+    };
+
+    let server = McpServer {
+        id,
+        name: None,
+        url: if stdio { String::new() } else { target.clone() },
+        sse_url: None,
+        transport: Some(transport.unwrap_or_else(|| "http".to_string())),
+        headers: (!headers.is_empty()).then_some(headers),
+        command: stdio.then_some(target),
+        args: positional,
+        env: None,
+        auth,
+        rate_limit: None,
+        sampling: None,
+        ca_cert: None,
+        insecure_skip_verify: false,
+        proxy: None,
+    };
+    server.validate()?;
+    Ok(server)
+}
+
+impl App {
+    fn add_mcp_server(&mut self, args: &[&str]) {
+        let server = match parse_mcp_add_args(args) {
+            Ok(server) => server,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err:#}"));
+                return;
+            }
+        };
+        let mut config = self.mcp_config.clone();
+        if let Err(err) = config.add_server(server.clone()) {
+            log_src!(self, LogLevel::Warn, format!("{err:#}"));
+            return;
+        }
+        if !self.save_mcp_config(&config) {
+            return;
+        }
+
+        self.log(
+            LogLevel::Info,
+            format!("Added MCP server '{}' ({}).", server.id, server.endpoint()),
+        );
+        match server.auth.as_ref().map(|auth| auth.auth_type.as_str()) {
+            Some("oauth_browser") => self.log(
+                LogLevel::Info,
+                format!("Run /mcp auth {} to sign in.", server.id),
+            ),
+            Some(_) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Set {} or run /mcp token {} <token>, then /mcp connect {}.",
+                    server
+                        .auth
+                        .as_ref()
+                        .and_then(|auth| auth.bearer_env.as_deref())
+                        .unwrap_or_default(),
+                    server.id,
+                    server.id
+                ),
+            ),
+            None => self.log(
+                LogLevel::Info,
+                format!("Run /mcp connect {} to use it.", server.id),
+            ),
+        }
+    }
+
+    fn remove_mcp_server(&mut self, target: &str) {
+        let mut config = self.mcp_config.clone();
+        let Some(removed) = config.remove_server(target) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown MCP server: {target}")
+            );
+            return;
+        };
+        if !self.save_mcp_config(&config) {
+            return;
+        }
+
+        if self.mcp_connections.remove(&removed.id).is_some() {
+            self.remember_connected_mcps();
+            self.log(
+                LogLevel::Info,
+                format!("Closed MCP connection '{}'.", removed.id),
+            );
+        }
+        if self
+            .active_mcp
+            .as_ref()
+            .is_some_and(|active| active.id == removed.id)
+        {
+            self.active_mcp = self
+                .mcp_connections
+                .values()
+                .next()
+                .map(|connection| connection.server.clone());
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Removed MCP server '{}'.", removed.id),
+        );
+    }
+
+    fn import_mcp_servers(&mut self, name: &str, path: Option<&str>) {
+        let Some(source) = ImportSource::parse(name) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown import source '{name}' (expected claude or cursor)")
+            );
+            return;
+        };
+        let path = match path {
+            Some(path) => Some(PathBuf::from(path)),
+            None => source.find_config(),
+        };
+        let Some(path) = path else {
+            let searched: Vec<String> = source
+                .default_paths()
+                .iter()
+                .map(|path| path.display().to_string())
+                .collect();
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "No {} config found (looked in {}). Pass a path: /mcp import {name} <path>",
+                    source.label(),
+                    searched.join(", ")
+                )
+            );
+            return;
+        };
+
+        let servers = match import::load_external(&path) {
+            Ok(servers) => servers,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Failed to import MCP servers: {err:#}")
+                );
+                return;
+            }
+        };
+
+        let mut config = self.mcp_config.clone();
+        let mut added = Vec::new();
+        let mut skipped = Vec::new();
+        for server in servers {
+            let id = server.id.clone();
+            match config.add_server(server) {
+                Ok(()) => added.push(id),
+                Err(err) => skipped.push(format!("{id} ({err:#})")),
+            }
+        }
+        if !skipped.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Skipped {} server(s): {}",
+                    skipped.len(),
+                    skipped.join("; ")
+                ),
+            );
+        }
+        if added.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!("No new MCP servers to import from {}.", path.display()),
+            );
+            return;
+        }
+        if self.save_mcp_config(&config) {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Imported {} MCP server(s) from {}: {}",
+                    added.len(),
+                    source.label(),
+                    added.join(", ")
+                ),
+            );
+        }
+    }
+
+    /// Persist `config` to the active mcp.json (or a config-dir copy of the
+    /// embedded defaults) and reload it.  Returns `false` on failure.
+    fn save_mcp_config(&mut self, config: &McpConfig) -> bool {
+        let Some(path) = McpConfig::writable_path(&self.mcp_source) else {
+            log_src!(
+                self,
+                LogLevel::Error,
+                "No writable location for mcp.json.".to_string()
+            );
+            return false;
+        };
+        if let Err(err) = config.save_to(&path) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save MCP config: {err:#}")
+            );
+            return false;
+        }
+        self.reload_mcp_config();
+        true
+    }
+}
@@ -0,0 +1,94 @@
+//! `/mission` command handler — multi-agent runs from
+//! `$MEMINI_HOME/missions/*.yaml` (see [`super::super::missions`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::missions::{self, AgentState};
+use super::super::window_control;
+
+// ── /mission ─────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_mission_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_missions(),
+            ["run", name] => self.run_mission(name),
+            ["status"] => self.show_mission_status(),
+            ["stop", name] => self.stop_mission(name),
+            ["dir"] => self.log(
+                LogLevel::Info,
+                format!("Missions dir: {}", missions::missions_dir().display()),
+            ),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /mission [list|status|dir]  |  /mission run|stop <name>".to_string()
+            ),
+        }
+    }
+
+    fn list_missions(&mut self) {
+        let loaded = missions::load_missions();
+        if loaded.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No missions. Describe one in {}/<name>.yaml (see docs/agents.md).",
+                    missions::missions_dir().display()
+                ),
+            );
+            return;
+        }
+        for mission in loaded {
+            match mission {
+                Ok(mission) => self.log(
+                    LogLevel::Info,
+                    format!(
+                        "  {} — {} ({} agent(s), {} success criteria)",
+                        mission.name,
+                        mission.goal,
+                        mission.agents.len(),
+                        mission.success.len()
+                    ),
+                ),
+                Err(err) => log_src!(self, LogLevel::Warn, format!("  {err:#}")),
+            }
+        }
+        self.log(
+            LogLevel::Info,
+            "/mission run <name> to start one.".to_string(),
+        );
+    }
+
+    fn show_mission_status(&mut self) {
+        if self.missions.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No missions run yet. /mission list shows what can run.".to_string(),
+            );
+            return;
+        }
+        let mut lines = Vec::new();
+        for run in &self.missions {
+            lines.push(format!(
+                "◎ {} ({} ago)",
+                run.summary(),
+                window_control::format_duration(run.started.elapsed())
+            ));
+            for (agent, state) in run.mission.agents.iter().zip(&run.states) {
+                let state = match state {
+                    AgentState::Waiting => "waiting for its inputs".to_string(),
+                    AgentState::Running(window_id) => format!("running as Agent #{window_id}"),
+                    AgentState::Done(window_id) => format!("done (Agent #{window_id})"),
+                    AgentState::Failed(reason) => format!("failed: {reason}"),
+                    AgentState::Skipped => "skipped".to_string(),
+                };
+                lines.push(format!("    {}: {state}", agent.id));
+            }
+        }
+        for line in lines {
+            self.log(LogLevel::Info, line);
+        }
+    }
+}
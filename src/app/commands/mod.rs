@@ -8,16 +8,92 @@
 //! | `mcp`     | `/mcp` – connect, auth, tools, call   |
 //! | `openai`  | `/openai`, `/model`, `/key`, `/rice`, bootstrap |
 //! | `agents`  | `/agent`, `/thread`, `/memory`        |
-//! | `daemons` | `/daemon`, `/auto`, `/spawn`, `/reply`|
-//! | `share`   | `/share`                              |
+//! | `artifacts` | `/artifacts` — saved task outputs   |
+//! | `attach`  | `/attach` — images for the next chat  |
+//! | `branch`  | `/branch`, `/rewind` — fork the thread |
+//! | `debug`   | `/debug http` — captured API payloads |
+//! | `digest`  | `/digest` — summary of today or the week |
+//! | `dry`     | `/dry` — show a turn without sending  |
+//! | `facts`   | `/memory facts` — semantic memory     |
+//! | `sessions`| `/session` — save/load named sessions |
+//! | `context` | `/context files` — AGENTS.md & co.    |
+//! | `daemons` | `/daemon`, `/auto`, `/spawn`, `/kill`, `/cancel`, `/reply`, `/agents`, `/split` |
+//! | `output`  | `/copy`, `/save-output` — agent output |
+//! | `pins`    | `/pin`, `/pins` — always-on context   |
+//! | `queue`   | `/queue` — messages typed mid-turn    |
+//! | `share`   | `/share`, `/workspace`                |
 //! | `skills`  | `/skills` — import/list skill packs   |
+//! | `snapshot`| `/snapshot` — whole-app state to a file |
+//! | `halt`    | `/halt`, `/resume-auto` — kill switch  |
+//! | `hooks`   | `/hooks` — webhook URLs for daemons   |
+//! | `incognito` | `/incognito` — pause memory writes  |
+//! | `index`   | `/index` — semantic workspace index   |
+//! | `json`    | `/json` — schema-validated JSON output |
+//! | `lang`    | `/lang` — UI language catalogs        |
+//! | `mission` | `/mission` — multi-agent mission files |
+//! | `notify`  | `/notify` — desktop/bell alerts, sinks |
+//! | `redact`  | `/redact` — secret masking rules      |
+//! | `review`  | `/review` — daemon-proposed file writes |
+//! | `route`   | `/route` — per-task model routing     |
+//! | `run`     | `/run` — replay a `.mm` command script |
+//! | `secrets` | `/secret` — values for recipe `env:`  |
+//! | `prompt`  | `/prompt` — reusable prompt templates |
+//! | `provider`| `/provider` — OpenAI or Azure OpenAI  |
+//! | `plugins` | `/plugins` — `$MEMINI_HOME/tools` plugins |
+//! | `policy`  | `/policy` — tool rules & approvals    |
+//! | `stats`   | `/stats` — request counts & latencies |
+//! | `status`  | `/status` — one health report         |
+//! | `theme`   | `/theme` — color schemes              |
+//! | `trace`   | `/trace last` — timeline of a turn    |
+//! | `update`  | `/update` — install the latest release |
+//! | `voice`   | `/voice` — dictate into the composer  |
+//! | `worktree` | `/worktree` — isolated agent workspaces |
 
 mod agents;
+mod artifacts;
+mod attach;
+mod branch;
+mod context;
 mod daemons;
+mod debug;
+mod digest;
+mod dry;
+mod facts;
+mod halt;
+mod hooks;
+mod incognito;
+mod index;
+mod json;
+mod lang;
 mod mcp;
+mod mission;
+mod notify;
 mod openai;
+mod output;
+mod pins;
+mod plugins;
+mod policy;
+mod prompt;
+mod provider;
+mod queue;
+mod redact;
+mod review;
+mod route;
+mod run;
+mod secrets;
+mod sessions;
 mod share;
 mod skills;
+mod snapshot;
+mod stats;
+mod status;
+mod theme;
+mod trace;
+mod update;
+mod voice;
+mod worktree;
+
+use crate::i18n::t;
 
 use super::App;
 use super::log_src;
@@ -38,16 +114,61 @@ impl App {
             "/mcp" => self.handle_mcp_command(parts.collect()),
             "/openai" => self.handle_openai_command(parts.collect()),
             "/model" => self.handle_model_command(parts.collect()),
+            "/route" => self.handle_route_command(parts.collect()),
+            "/provider" => self.handle_provider_command(parts.collect()),
+            "/prompt" => self.handle_prompt_command(parts.collect()),
             "/key" => self.handle_key_command(parts.collect()),
             "/rice" => self.handle_rice_command(parts.collect()),
             "/agent" => self.handle_agent_command(parts.collect()),
             "/thread" => self.handle_thread_command(parts.collect()),
+            "/session" | "/sessions" => self.handle_session_command(parts.collect()),
+            "/snapshot" => self.handle_snapshot_command(parts.collect()),
+            "/branch" => self.handle_branch_command(parts.collect()),
+            "/rewind" => self.handle_rewind_command(parts.collect()),
+            "/pin" => self.handle_pin_command(parts.collect()),
+            "/pins" => self.handle_pins_command(parts.collect()),
+            "/context" => self.handle_context_command(parts.collect()),
+            "/unpin" => self.handle_pins_command(["remove"].into_iter().chain(parts).collect()),
             "/memory" | "/mem" => self.handle_memory_command(parts.collect()),
+            "/attach" => self.handle_attach_command(parts.collect()),
+            "/voice" => self.handle_voice_command(parts.collect()),
+            "/dry" => self.handle_dry_command(parts.collect()),
+            "/digest" => self.handle_digest_command(parts.collect()),
+            "/queue" => self.handle_queue_command(parts.collect()),
+            "/stats" => self.handle_stats_command(parts.collect()),
+            "/status" => self.handle_status_command(parts.collect()),
+            "/trace" => self.handle_trace_command(parts.collect()),
+            "/debug" => self.handle_debug_command(parts.collect()),
+            "/run" => self.handle_run_command(parts.collect()),
+            "/review" => self.handle_review_command(parts.collect()),
+            "/worktree" => self.handle_worktree_command(parts.collect()),
+            "/mission" => self.handle_mission_command(parts.collect()),
             "/daemon" | "/d" | "/auto" => self.handle_daemon_command(parts.collect()),
             "/spawn" => self.handle_spawn_command(parts.collect()),
+            "/agents" => self.handle_agents_command(parts.collect()),
+            "/split" => self.handle_split_command(parts.collect()),
+            "/kill" => self.handle_kill_command(parts.collect()),
+            "/cancel" => self.handle_cancel_command(parts.collect()),
+            "/halt" => self.handle_halt_command(parts.collect()),
+            "/resume-auto" => self.handle_resume_auto_command(parts.collect()),
             "/reply" | "/r" => self.handle_reply_command(parts.collect()),
-            "/share" => self.handle_share_command(parts.collect()),
+            "/copy" => self.handle_copy_command(parts.collect()),
+            "/save-output" => self.handle_save_output_command(parts.collect()),
+            "/artifacts" => self.handle_artifacts_command(parts.collect()),
+            "/incognito" => self.handle_incognito_command(parts.collect()),
+            "/index" => self.handle_index_command(parts.collect()),
+            "/plugins" => self.handle_plugins_command(parts.collect()),
+            "/json" => self.handle_json_command(parts.collect()),
+            "/share" | "/workspace" => self.handle_share_command(parts.collect()),
             "/skills" => self.handle_skills_command(parts.collect()),
+            "/hooks" => self.handle_hooks_command(parts.collect()),
+            "/secret" | "/secrets" => self.handle_secret_command(parts.collect()),
+            "/redact" => self.handle_redact_command(parts.collect()),
+            "/notify" => self.handle_notify_command(parts.collect()),
+            "/policy" => self.handle_policy_command(parts.collect()),
+            "/theme" => self.handle_theme_command(parts.collect()),
+            "/lang" => self.handle_lang_command(parts.collect()),
+            "/update" => self.handle_update_command(parts.collect()),
             "/panel" => {
                 self.show_side_panel = !self.show_side_panel;
                 let state = if self.show_side_panel {
@@ -79,8 +200,30 @@ impl App {
             "Chat & Memory",
             "  (just type)             Talk to your AI — it recalls past chats",
             "  /memory <query>         Search your saved memories",
+            "  /memory list [n]        Newest memories + model/tools/tokens behind each",
+            "  /memory export <path>   Dump traces + variables to JSONL (--include-secrets)",
+            "  /memory import <path>   Replay a JSONL dump into the current run",
+            "  /memory compact         Merge repeats, prune low-value traces (--dry-run)",
+            "  /memory facts           Review/edit distilled facts (add, edit, remove, distill)",
+            "  /attach <path|url>      Send an image with your next message (list, clear)",
+            "  /voice [stop|cancel]    Dictate a message (transcribed into the input box)",
+            "  /dry <message>          Show the full request and token estimate; nothing is sent",
+            "  /digest [today|week]    Summarize today's or the week's activity (saved as an artifact)",
+            "  /queue [list]           Messages typed while a turn runs (edit <id> [text], cancel <id|all>)",
             "  /thread                 Show current conversation info",
             "  /thread clear           Start a fresh conversation",
+            "  /incognito on|off       Stop/resume saving turns, traces and focus to memory",
+            "  /session save <name>    Save thread, persona + MCP set as a named session",
+            "  /session list           List saved sessions",
+            "  /session load <name>    Resume a saved session",
+            "  /session delete <name>  Delete a saved session",
+            "  /snapshot save <file>   Write thread, windows, tasks + settings to a file",
+            "  /snapshot load <file>   Restore a snapshot (tasks paused, nothing re-run)",
+            "  /branch [name]          Save this conversation as a session and keep going",
+            "  /rewind [n] [message]   Go back n turns (old branch saved); edit or resend",
+            "  /pin <text>             Keep text in every chat turn (/pin file <path> for a file)",
+            "  /pins [remove <n|all>]  List or unpin pinned context",
+            "  /context files          Show which MEMINI.md / AGENTS.md / CLAUDE.md are in prompts",
             "",
             "Personas",
             "  /agent                  See available personas",
@@ -88,49 +231,107 @@ impl App {
             "  /agent create <n> <d>   Create a custom persona",
             "  /agent delete <name>    Remove a custom persona",
             "  /agent info             Current persona details",
+            "  /agent restrict <n> tools|mcp <a,b|all> Limit a persona's tools or MCP servers",
+            "  /agent export <n> <p>   Save a persona to a .md/.json file",
+            "  /agent import <p|url>   Add a persona from a file or URL (--force replaces)",
             "",
             "Autopilot (Background Tasks)",
             "  /auto                   See available background tasks",
             "  /auto run <name>        Run a task right now",
             "  /auto start <name>      Start a recurring task",
             "  /auto stop <name>       Stop a running task",
+            "  /auto pause <name>      Hold a task's schedule (keeps its place)",
+            "  /auto resume <name>     Continue a paused task",
             "  /auto create <n> <s> <p> Create + start recipe task (saved to ~/Memini/agents)",
             "  /auto add <n> <s> <p>   Alias for /auto create",
             "  /auto templates         List built-in recipe templates",
             "  /auto scaffold <t> [n]  Create + start from a template",
+            "  /auto generate <desc>   Draft a recipe with the model (show|set|save [--start]|discard)",
+            "  /auto lint [name]       Check recipe files: keys, triggers, tools, intervals",
             "  /auto dir               Show recipe directory path",
             "  /auto reload            Reload recipe files from disk",
             "  /auto remove <name>     Remove a task",
             "  /auto results [name]    See recent task outputs",
+            "  /auto queue             Running and queued agent work (max_concurrent_agents)",
             "  recipe triggers         front matter: trigger_events + trigger_variables",
+            "  /hooks                  List webhook URLs (needs MEMINI_WEBHOOK_PORT)",
+            "  /hooks new <name>       Generate a secret POST URL that wakes a task",
+            "  /hooks revoke <name>    Revoke a task's webhook URL",
+            "  /secret set <NAME> <v>  Store a secret for recipe env: (list, remove)",
+            "  /redact [test <text>]   Redaction rules and session count, or preview masking",
             "",
             "Agents (Multi-Instance)",
             "  /spawn <prompt>         Spin up a live agent window",
+            "  /spawn --timeout 10m <prompt>  ...with its own max runtime per run",
+            "  /spawn --isolated <prompt>  ...in its own git worktree (or copy) of the workspace",
+            "  /kill <id>              Cancel an agent window's run",
+            "  /cancel <id>            Stop the command an agent window is running",
+            "  /halt                   Stop ALL daemons, agents and tool loops (Ctrl+X)",
+            "  /resume-auto            Allow autonomous work again after /halt",
             "  /spawn list             Show all agent windows + status",
+            "  /spawn resume           Restart agent windows left unfinished at last exit",
+            "  /agents [status|id|name] Overview of agent windows, sorted",
+            "  /mission [list]         Multi-agent missions in $MEMINI_HOME/missions/*.yaml",
+            "  /mission run <name>     Spawn a mission's agents in dependency order",
+            "  /mission status|stop    Progress of each agent, or stop a running mission",
+            "  /split [id|off]         Stream an agent beside the chat (Ctrl+S toggles)",
             "  /reply list             Show agents waiting for input",
             "  /reply <id|next> <msg>  Reply from the main input box",
             "  #<id> <msg>             Inline reply shortcut",
             "  (plain text)            Sends to oldest waiting agent first (FIFO)",
             "  Tab                     Cycle through agents on dashboard",
+            "  Tab (after /)           Complete commands, servers, tools, agents, tasks",
+            "  Ctrl+PgUp / Ctrl+PgDn   Page through the agent grid",
             "  Enter (empty input)     Open selected agent session",
             "  Enter (with input)      Send message/command",
             "  Alt+Enter / Ctrl+J      Insert newline in input",
             "  Esc                     Return to dashboard from agent session",
             "  Ctrl+1..9               Jump to agent session by index",
+            "  Ctrl+X                  Panic button: same as /halt",
+            "  Ctrl+O                  Output mode in a session (j/k, v select, / search, y copy)",
+            "  /copy [id]              Copy selection or agent output to clipboard",
+            "  /save-output <id> <p>   Write an agent's raw output to a file",
+            "  /artifacts [list [task]] Saved daemon/agent outputs ($MEMINI_HOME/artifacts)",
+            "  /review [list]          File writes proposed by `writes: propose` tasks",
+            "  /review diff|apply|discard <id>  Inspect, write or drop a proposed change",
+            "  /worktree [list]        Isolated workspaces of /spawn --isolated agents",
+            "  /worktree diff|merge|discard <id>  Inspect, merge back or drop an agent's changes",
+            "  /artifacts open <task> [run] [file]  Open a run folder or file",
+            "  /index [status]         Workspace index for workspace_semantic_search",
+            "  /index rebuild [--full] Re-index changed files now (--full re-embeds all)",
+            "  /json <schema> <prompt> JSON matching a schema → artifact + Rice json.<schema>",
+            "  /json list              Schemas in $MEMINI_HOME/schemas",
+            "  /plugins [list|dir]     Plugin tools from $MEMINI_HOME/tools manifests",
             "",
             "Integrations",
             "  /mcp                    List available tools (MCP servers)",
             "  /mcp connect <id>       Connect to a tool (adds it to session)",
             "  /mcp auth <id>          Authenticate via browser (OAuth)",
+            "  /mcp auth <id> --device Authenticate with a device code (SSH/headless)",
             "  /mcp auth-code <id> <x> Finish OAuth with URL/code",
             "  /mcp ask <prompt>       Chat using connected tools",
             "  /mcp tools [id|all]     List MCP tools (namespaced: id__tool)",
+            "  /mcp call <tool> [json] Call a tool (no JSON: prompts for each field)",
             "  /mcp disconnect [id|all] Disconnect MCP server(s)",
+            "  /mcp add <id> <url> [--transport http|stdio] [--header k=v] [--auth bearer|oauth]",
+            "                          Add a server to mcp.json",
+            "  /mcp remove <id>        Remove a server from mcp.json",
+            "  /mcp import claude|cursor [path] Import servers from Claude Desktop / Cursor",
+            "  /mcp roots [add|remove <path>] Folders exposed to MCP servers",
             "",
             "Shared Workspaces (Team Memory)",
             "  /share                  Show current workspace status",
             "  /share join <name>      Join a shared workspace (team members use same name)",
             "  /share leave            Return to your private memory",
+            "  /workspace feed [n]     Show teammates' recent activity (alias of /share)",
+            "",
+            "Prompt Templates",
+            "  /prompt [list]          List templates in ~/Memini/prompts",
+            "  /prompt save <name> [text] Save text (or your last message) as a template",
+            "                          Flags: --model <m> --persona <agent|text> --force",
+            "  /prompt run <name> k=v  Fill {{k}} placeholders and send",
+            "  /prompt show|remove <name> Show or delete a template",
+            "  /prompt show            Inspect everything the last chat turn sent",
             "",
             "Skills (Agent Workflows)",
             "  /skills                 List imported skills",
@@ -144,14 +345,53 @@ impl App {
             "  /model list             Show model picking guidance",
             "  /model set <name>       Set active model",
             "  /model thinking <mode>  Set thinking: on|off|low|medium|high",
+            "  /route                  Show which model handles each task kind",
+            "  /route <task> <model>   Route chat|code|agent|daemon|summary|embedding (default clears)",
+            "  /route reset            Restore routes from memini.toml",
+            "  /provider               Show where model requests go (OpenAI or Azure)",
+            "  /provider azure <endpoint> [--api-version v] [--deploy model=name]",
+            "  /provider openai [url] | reset  Switch back to OpenAI / the environment",
             "  /key <key>              Quick set OpenAI key",
             "  /rice                   Show Rice memory connection status",
             "  /rice setup             Interactive Rice environment wizard",
+            "  /notify                 Show notification settings",
+            "  /notify <event> <mode>  event: waiting|agent|daemon|all, mode: off|bell|desktop",
+            "  /notify test            Send a test notification",
+            "  /notify sinks [test]    Slack/Discord webhooks from memini.toml (test posts to each)",
+            "  /policy                 Show tool policy rules (memini.toml [policy])",
+            "  /policy reload          Re-read memini.toml",
+            "  /policy approve <id>    Allow a tool call waiting on require-approval",
+            "  /policy deny <id>       Reject a tool call waiting on require-approval",
+            "  /policy audit [n]       Show recent policy denials/approvals",
+            "  /theme [name]           List or switch themes: dark|light|high-contrast|basic|custom",
+            "  /theme reload           Re-read ~/Memini/theme.toml",
+            "  /lang [code]            List or switch the UI language (MEMINI_LANG at startup)",
+            "  /stats [reset]          Request counts + p50/p95 latency per subsystem",
+            "  /status                 Health report: MCP, model, Rice, daemons, windows, usage",
+            "  /trace last [all|n]     Last chat turn as a timeline of model and tool calls",
+            "  /debug http on|off|show Capture OpenAI/MCP payloads (masked) and browse them",
+            "  /debug http dump [path] Write the captured payloads to a JSON file",
+            "  /update [check]         Install the latest GitHub release (check: only report)",
+            "  /run <file.mm>          Replay commands and prompts from a script (/run stop)",
             "  /clear                  Clear the screen",
             "  /quit                   Exit Memini by AG\\I",
         ];
         for line in lines {
-            self.log(LogLevel::Info, line.to_string());
+            self.log(LogLevel::Info, translate_help_line(line));
         }
     }
 }
+
+/// `line` in the active language: headings whole, command lines by their
+/// description column so the commands themselves stay as typed.
+fn translate_help_line(line: &str) -> String {
+    let Some(indented) = line.strip_prefix("  ") else {
+        return t(line).to_string();
+    };
+    let Some(gap) = indented.find("  ") else {
+        return line.to_string();
+    };
+    let description = indented[gap..].trim_start();
+    let prefix = &line[..line.len() - description.len()];
+    format!("{prefix}{}", t(description))
+}
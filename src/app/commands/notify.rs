@@ -0,0 +1,160 @@
+//! `/notify` command handler — per-event desktop/bell notification settings
+//! and the Slack/Discord sinks from `memini.toml`.
+
+use anyhow::Result;
+
+use crate::constants::NOTIFY_SETTINGS_VAR;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::notifications::{self, NotifyEvent, NotifyMode, NotifySettings};
+use super::super::sinks::{self, SinkEvent};
+
+// ── /notify ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_notify_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            self.show_notify_settings();
+            return;
+        }
+
+        match args[0] {
+            "sinks" => match args.get(1).copied() {
+                None => self.show_notify_sinks(),
+                Some("test") => self.test_notify_sinks(),
+                Some(_) => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /notify sinks [test]".to_string()
+                ),
+            },
+            "test" => {
+                for event in NotifyEvent::ALL {
+                    self.notify(
+                        event,
+                        "Memini test notification".to_string(),
+                        format!("Event type: {}", event.label()),
+                    );
+                }
+                self.log(
+                    LogLevel::Info,
+                    "Sent a test notification for each enabled event.".to_string(),
+                );
+            }
+            target => {
+                let Some(mode) = args.get(1).and_then(|raw| NotifyMode::parse(raw)) else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /notify <waiting|agent|daemon|all> <off|bell|desktop>".to_string()
+                    );
+                    return;
+                };
+                let events: Vec<NotifyEvent> = if target.eq_ignore_ascii_case("all") {
+                    NotifyEvent::ALL.to_vec()
+                } else if let Some(event) = NotifyEvent::parse(target) {
+                    vec![event]
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Unknown /notify event: {target}")
+                    );
+                    return;
+                };
+                for event in events {
+                    self.notify_settings.set_mode(event, mode);
+                }
+                if let Err(err) = self.persist_notify_settings() {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Notification settings not saved to Rice: {err:#}")
+                    );
+                }
+                self.show_notify_settings();
+            }
+        }
+    }
+
+    fn show_notify_settings(&mut self) {
+        self.log(LogLevel::Info, "Notifications:".to_string());
+        for event in NotifyEvent::ALL {
+            let mode = self.notify_settings.mode(event);
+            self.log(
+                LogLevel::Info,
+                format!("  {:<8} {}", event.label(), mode.label()),
+            );
+        }
+    }
+
+    fn show_notify_sinks(&mut self) {
+        let config = sinks::config();
+        if config.sinks.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No sinks. Add [sinks.<name>] with a Slack or Discord webhook url to memini.toml."
+                    .to_string(),
+            );
+            return;
+        }
+        self.log(LogLevel::Info, "Sinks:".to_string());
+        for (name, sink) in &config.sinks {
+            let kind = sink.kind().map_or("unknown", |kind| kind.label());
+            let events: Vec<&str> = sink.events.iter().map(|event| event.label()).collect();
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "  {name:<10} {kind:<8} {:<24} {}",
+                    sink.host(),
+                    events.join(", ")
+                ),
+            );
+        }
+    }
+
+    fn test_notify_sinks(&mut self) {
+        let names: Vec<String> = sinks::config().sinks.keys().cloned().collect();
+        if names.is_empty() {
+            self.show_notify_sinks();
+            return;
+        }
+        self.post_to_sinks(
+            SinkEvent::Daemon,
+            names.clone(),
+            "Memini test notification".to_string(),
+            "This sink is set up.".to_string(),
+        );
+        self.log(
+            LogLevel::Info,
+            format!("Posted a test message to {}.", names.join(", ")),
+        );
+    }
+
+    /// Raise a notification for `event` if the user enabled it.
+    pub(crate) fn notify(&self, event: NotifyEvent, summary: String, body: String) {
+        let mode = self.notify_settings.mode(event);
+        notifications::deliver(self.runtime.handle(), mode, summary, body);
+    }
+
+    fn persist_notify_settings(&mut self) -> Result<()> {
+        let value = serde_json::to_value(&self.notify_settings)?;
+        self.runtime.block_on(
+            self.rice
+                .set_variable(NOTIFY_SETTINGS_VAR, value, "explicit"),
+        )
+    }
+
+    /// Restore notification settings from Rice.
+    pub(crate) fn load_notify_settings_from_rice(&mut self) -> Result<()> {
+        let value = self
+            .runtime
+            .block_on(self.rice.get_variable(NOTIFY_SETTINGS_VAR))?;
+        if let Some(value) = value {
+            self.notify_settings = serde_json::from_value::<NotifySettings>(value)?;
+        }
+        Ok(())
+    }
+}
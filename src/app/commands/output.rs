@@ -0,0 +1,105 @@
+//! `/copy` and `/save-output` command handlers — get agent window output
+//! out of the TUI.
+
+use std::fs;
+use std::path::Path;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /copy, /save-output ──────────────────────────────────────────────
+
+impl App {
+    /// `/copy [id]` — copy the output-mode selection, or a window's output.
+    pub(crate) fn handle_copy_command(&mut self, args: Vec<&str>) {
+        let window_id = match args.first() {
+            Some(raw) => match raw.trim_start_matches('#').parse::<usize>() {
+                Ok(id) => Some(id),
+                Err(_) => {
+                    log_src!(self, LogLevel::Warn, "Usage: /copy [id]".to_string());
+                    return;
+                }
+            },
+            None => self.focused_window,
+        };
+        let Some(window_id) = window_id else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /copy [id] (or open an agent session first)".to_string()
+            );
+            return;
+        };
+        let Some(window) = self.agent_windows.iter().find(|w| w.id == window_id) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Agent #{window_id} not found.")
+            );
+            return;
+        };
+
+        let text = match self
+            .output_view
+            .as_ref()
+            .filter(|view| view.window_id == window_id && view.anchor.is_some())
+        {
+            Some(view) => view.selected_text(&window.output_lines),
+            None => window.output_lines.join("\n"),
+        };
+        self.copy_output(&text);
+    }
+
+    /// `/save-output <id> <path>` — write a window's raw output to a file.
+    pub(crate) fn handle_save_output_command(&mut self, args: Vec<&str>) {
+        let (Some(raw_id), Some(raw_path)) = (args.first(), args.get(1)) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /save-output <id> <path>".to_string()
+            );
+            return;
+        };
+        let Ok(window_id) = raw_id.trim_start_matches('#').parse::<usize>() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /save-output <id> <path>".to_string()
+            );
+            return;
+        };
+        let Some(window) = self.agent_windows.iter().find(|w| w.id == window_id) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Agent #{window_id} not found.")
+            );
+            return;
+        };
+
+        let mut contents = window.output_lines.join("\n");
+        contents.push('\n');
+        let line_count = window.output_lines.len();
+        let path = Path::new(raw_path);
+        let result = path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, contents));
+        match result {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Saved {line_count} line(s) from agent #{window_id} to {}.",
+                    path.display()
+                ),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to write {}: {err}", path.display())
+            ),
+        }
+    }
+}
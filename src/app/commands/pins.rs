@@ -0,0 +1,161 @@
+//! `/pin` and `/pins` command handlers — text and files kept in every
+//! chat turn's context.
+
+use std::fs;
+use std::path::Path;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::pins::{self, Pin};
+
+// ── /pin, /pins ──────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_pin_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /pin <text> | /pin file <path>".to_string()
+            ),
+            // Paths may contain spaces.
+            ["file", path @ ..] if !path.is_empty() => self.pin_file(&path.join(" ")),
+            text => self.add_pin(Pin::Text(text.join(" "))),
+        }
+    }
+
+    pub(crate) fn handle_pins_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_pins(),
+            ["remove" | "unpin" | "rm", "all"] | ["clear"] => self.clear_pins(),
+            ["remove" | "unpin" | "rm", number] => match number.parse::<usize>() {
+                Ok(number) => self.remove_pin(number),
+                Err(_) => log_src!(self, LogLevel::Warn, "Usage: /pins remove <n>".to_string()),
+            },
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /pins [list] | /pins remove <n|all>".to_string()
+            ),
+        }
+    }
+
+    fn pin_file(&mut self, raw: &str) {
+        let path = match fs::canonicalize(Path::new(raw.trim())) {
+            Ok(path) if path.is_file() => path,
+            Ok(path) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("{} is not a file.", path.display())
+                );
+                return;
+            }
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("Cannot pin {raw}: {err}"));
+                return;
+            }
+        };
+        self.add_pin(Pin::File(path));
+    }
+
+    fn add_pin(&mut self, pin: Pin) {
+        let mut store = pins::load_pins();
+        if store.pins.contains(&pin) {
+            self.log(LogLevel::Info, format!("Already pinned: {}", pin.label()));
+            return;
+        }
+        let label = pin.label();
+        store.pins.push(pin);
+        if let Err(err) = pins::persist_pins(&store) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save pins: {err:#}")
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("📌 Pinned #{}: {label}", store.pins.len()),
+        );
+        self.warn_pins_over_budget(&store);
+    }
+
+    fn list_pins(&mut self) {
+        let store = pins::load_pins();
+        if store.pins.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "Nothing pinned. Use /pin <text> or /pin file <path>.".to_string(),
+            );
+            return;
+        }
+        let rendered = store.render();
+        self.log(
+            LogLevel::Info,
+            "Pinned into every chat turn (/pins remove <n> to unpin):".to_string(),
+        );
+        for (index, pin) in store.pins.iter().enumerate() {
+            let note = if rendered.over_budget.contains(&(index + 1)) {
+                "  [over budget]"
+            } else {
+                ""
+            };
+            self.log(
+                LogLevel::Info,
+                format!("  {:>2}  {}{note}", index + 1, pin.label()),
+            );
+        }
+    }
+
+    fn remove_pin(&mut self, number: usize) {
+        let mut store = pins::load_pins();
+        if number == 0 || number > store.pins.len() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No pin #{number}; see /pins.")
+            );
+            return;
+        }
+        let pin = store.pins.remove(number - 1);
+        match pins::persist_pins(&store) {
+            Ok(()) => self.log(LogLevel::Info, format!("Unpinned {}", pin.label())),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save pins: {err:#}")
+            ),
+        }
+    }
+
+    fn clear_pins(&mut self) {
+        let count = pins::load_pins().pins.len();
+        match pins::persist_pins(&pins::PinStore::default()) {
+            Ok(()) => self.log(LogLevel::Info, format!("Removed {count} pin(s).")),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save pins: {err:#}")
+            ),
+        }
+    }
+
+    fn warn_pins_over_budget(&mut self, store: &pins::PinStore) {
+        let over = store.render().over_budget;
+        if over.is_empty() {
+            return;
+        }
+        let numbers: Vec<String> = over.iter().map(|n| format!("#{n}")).collect();
+        log_src!(
+            self,
+            LogLevel::Warn,
+            format!(
+                "Pins over the context budget are left out or cut short: {}",
+                numbers.join(", ")
+            )
+        );
+    }
+}
@@ -0,0 +1,54 @@
+//! `/plugins` command handler — list plugin tools from
+//! `$MEMINI_HOME/tools` (see [`crate::plugins`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::plugins;
+
+// ── /plugins ─────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_plugins_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_plugins(),
+            ["dir"] => self.log(
+                LogLevel::Info,
+                format!("Plugin manifests: {}", plugins::tools_dir().display()),
+            ),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /plugins [list|dir]".to_string()
+            ),
+        }
+    }
+
+    fn list_plugins(&mut self) {
+        let dir = plugins::tools_dir();
+        let set = plugins::load();
+        if set.tools.is_empty() && set.errors.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No plugin tools. Add <name>.json manifests to {}.",
+                    dir.display()
+                ),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Plugin tools in {}:", dir.display()),
+        );
+        for tool in &set.tools {
+            self.log(
+                LogLevel::Info,
+                format!("  {} — {} ({})", tool.name, tool.description, tool.command),
+            );
+        }
+        for error in set.errors {
+            log_src!(self, LogLevel::Warn, format!("Skipped {error}"));
+        }
+    }
+}
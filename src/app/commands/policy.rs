@@ -0,0 +1,148 @@
+//! `/policy` command handler — inspect/reload tool rules and answer
+//! `require-approval` prompts.
+
+use std::fs;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::policy;
+use crate::sandbox;
+
+/// How many audit entries `/policy audit` shows by default.
+const DEFAULT_AUDIT_LINES: usize = 10;
+
+// ── /policy ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_policy_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            self.show_policy();
+            return;
+        }
+
+        match args[0] {
+            "show" | "list" => self.show_policy(),
+            "reload" => match policy::reload() {
+                Ok(loaded) => {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Reloaded tool policy ({} rule(s)).", loaded.rules.len()),
+                    );
+                }
+                Err(err) => {
+                    log_src!(
+                        self,
+                        LogLevel::Error,
+                        format!(
+                            "Policy reload failed, denying every tool call until it loads: {err:#}"
+                        )
+                    );
+                }
+            },
+            "approve" | "deny" => {
+                let approved = args[0] == "approve";
+                let Some(id) = args.get(1).and_then(|raw| raw.parse::<usize>().ok()) else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Usage: /policy {} <approval-id>", args[0])
+                    );
+                    return;
+                };
+                if policy::resolve_approval(id, approved) {
+                    let verb = if approved { "approved" } else { "denied" };
+                    self.log(LogLevel::Info, format!("Tool call #{id} {verb}."));
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("No pending approval #{id} (it may have timed out).")
+                    );
+                }
+            }
+            "audit" => {
+                let limit = args
+                    .get(1)
+                    .and_then(|raw| raw.parse::<usize>().ok())
+                    .unwrap_or(DEFAULT_AUDIT_LINES);
+                self.show_policy_audit(limit);
+            }
+            other => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown /policy command: {other}")
+            ),
+        }
+    }
+
+    fn show_policy(&mut self) {
+        let active = policy::current();
+        let source = active
+            .source
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_else(|| "no memini.toml found".to_string());
+        let default = if active.default_allow {
+            "allow"
+        } else {
+            "deny"
+        };
+        self.log(
+            LogLevel::Info,
+            format!("Tool policy ({source}), default: {default}"),
+        );
+        if let Some(err) = &active.load_error {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("  Does not load, every tool call is denied: {err}")
+            );
+        }
+        match sandbox::load_config() {
+            Ok(config) => self.log(
+                LogLevel::Info,
+                format!("Command sandbox: {}", config.summary()),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Command sandbox: memini.toml does not load, commands are refused: {err:#}"
+                )
+            ),
+        }
+        if active.rules.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "  No rules. Add a [policy] section to memini.toml.".to_string(),
+            );
+            return;
+        }
+        for (idx, rule) in active.rules.iter().enumerate() {
+            self.log(
+                LogLevel::Info,
+                format!("  {}. [{}] {}", idx + 1, rule.effect.label(), rule.source),
+            );
+        }
+    }
+
+    fn show_policy_audit(&mut self, limit: usize) {
+        let Some(path) = policy::audit_log_path() else {
+            return;
+        };
+        let Ok(contents) = fs::read_to_string(&path) else {
+            self.log(LogLevel::Info, "No policy audit entries yet.".to_string());
+            return;
+        };
+        let lines: Vec<&str> = contents.lines().collect();
+        let start = lines.len().saturating_sub(limit);
+        self.log(
+            LogLevel::Info,
+            format!("Policy audit ({}):", path.display()),
+        );
+        for line in &lines[start..] {
+            self.log(LogLevel::Info, format!("  {line}"));
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! `/prompt` command handlers — reusable prompt templates with
+//! `{{variable}}` substitution (see [`super::super::prompt_templates`]).
+
+use serde_json::Value;
+
+use super::super::App;
+use super::super::agents::Agent;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::prompt_templates::{self, PromptTemplate};
+
+// ── /prompt ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_prompt_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() || args[0] == "list" {
+            self.list_prompt_templates();
+            return;
+        }
+
+        match args[0] {
+            "save" => self.save_prompt_template(&args[1..]),
+            "run" => match args.get(1) {
+                Some(name) => self.run_prompt_template(name, &args[2..]),
+                None => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /prompt run <name> key=value ...".to_string()
+                ),
+            },
+            "show" => match args.get(1) {
+                Some(name) => self.show_prompt_template(name),
+                None => self.show_last_prompt(),
+            },
+            "remove" | "rm" => match args.get(1) {
+                Some(name) => match prompt_templates::remove_template(name) {
+                    Ok(Some(path)) => self.log(
+                        LogLevel::Info,
+                        format!("Removed prompt template {}.", path.display()),
+                    ),
+                    Ok(None) => log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("No prompt template named '{name}'.")
+                    ),
+                    Err(err) => log_src!(
+                        self,
+                        LogLevel::Error,
+                        format!("Failed to remove prompt template: {err:#}")
+                    ),
+                },
+                None => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /prompt remove <name>".to_string()
+                ),
+            },
+            other => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown /prompt command: {other}")
+            ),
+        }
+    }
+
+    fn list_prompt_templates(&mut self) {
+        let templates = match prompt_templates::load_templates() {
+            Ok(templates) => templates,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Failed to load prompt templates: {err:#}")
+                );
+                return;
+            }
+        };
+        if templates.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "No prompt templates in {}. Use /prompt save <name> <text>.",
+                    prompt_templates::prompts_dir().display()
+                ),
+            );
+            return;
+        }
+        self.log(LogLevel::Info, "Prompt templates:".to_string());
+        for template in templates {
+            let vars = template
+                .variables()
+                .iter()
+                .map(|name| format!("{name}="))
+                .collect::<Vec<_>>()
+                .join(" ");
+            let description = if template.description.is_empty() {
+                String::new()
+            } else {
+                format!(" — {}", template.description)
+            };
+            self.log(
+                LogLevel::Info,
+                format!("  {} {vars}{description}", template.name),
+            );
+        }
+    }
+
+    /// `/prompt save <name> [--model m] [--persona p] [--force] [text...]`.
+    /// Without text, the last message you sent becomes the template.
+    fn save_prompt_template(&mut self, args: &[&str]) {
+        let Some((name, rest)) = args.split_first() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /prompt save <name> [--model m] [--persona p] [--force] [text]".to_string()
+            );
+            return;
+        };
+
+        let mut model = None;
+        let mut persona = None;
+        let mut force = false;
+        let mut words = Vec::new();
+        let mut iter = rest.iter();
+        while let Some(arg) = iter.next() {
+            match *arg {
+                "--model" => model = iter.next().copied(),
+                "--persona" => persona = iter.next().copied(),
+                "--force" => force = true,
+                word => words.push(word),
+            }
+        }
+
+        let body = if words.is_empty() {
+            match self.last_user_message() {
+                Some(message) => message,
+                None => {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Nothing to save: give the template text or send a message first."
+                            .to_string()
+                    );
+                    return;
+                }
+            }
+        } else {
+            words.join(" ")
+        };
+
+        match prompt_templates::save_template(name, &body, model, persona, force) {
+            Ok(path) => self.log(
+                LogLevel::Info,
+                format!("Saved prompt template to {}.", path.display()),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save prompt template: {err:#}")
+            ),
+        }
+    }
+
+    fn show_prompt_template(&mut self, name: &str) {
+        let Some(template) = self.lookup_prompt_template(name) else {
+            return;
+        };
+        let mut header = vec![format!("Prompt '{}':", template.name)];
+        if let Some(model) = &template.model {
+            header.push(format!("model={model}"));
+        }
+        if let Some(persona) = &template.persona {
+            header.push(format!("persona={persona}"));
+        }
+        self.log(LogLevel::Info, header.join(" "));
+        for line in template.body.lines() {
+            self.log(LogLevel::Info, format!("  {line}"));
+        }
+    }
+
+    /// Everything the last chat turn sent: system messages, memory
+    /// context, skills and thread history, in request order.
+    fn show_last_prompt(&mut self) {
+        let Some(snapshot) = &self.last_prompt else {
+            self.log(
+                LogLevel::Info,
+                "No chat turn yet. Send a message, then /prompt show to inspect what was sent."
+                    .to_string(),
+            );
+            return;
+        };
+        let body = snapshot.markdown();
+        self.log_markdown("Last prompt".to_string(), body);
+    }
+
+    fn run_prompt_template(&mut self, name: &str, assignments: &[&str]) {
+        let Some(template) = self.lookup_prompt_template(name) else {
+            return;
+        };
+        let rendered = match prompt_templates::parse_assignments(assignments)
+            .and_then(|values| template.render(&values))
+        {
+            Ok(rendered) => rendered,
+            Err(err) => {
+                let usage = template
+                    .variables()
+                    .iter()
+                    .map(|name| format!("{name}=…"))
+                    .collect::<Vec<_>>()
+                    .join(" ");
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("{err:#}. Usage: /prompt run {} {usage}", template.name)
+                );
+                return;
+            }
+        };
+
+        if self.chat_busy {
+            self.log(LogLevel::Info, "Still thinking… please wait.".to_string());
+            return;
+        }
+        let agent = self.template_agent(&template);
+        self.log(LogLevel::Info, format!("› {rendered}"));
+        self.chat_busy = true;
+        self.handle_chat_message_as(&rendered, agent, template.model.clone());
+    }
+
+    fn lookup_prompt_template(&mut self, name: &str) -> Option<PromptTemplate> {
+        match prompt_templates::find_template(name) {
+            Ok(Some(template)) => Some(template),
+            Ok(None) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("No prompt template named '{name}'. See /prompt list.")
+                );
+                None
+            }
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Failed to load prompt template: {err:#}")
+                );
+                None
+            }
+        }
+    }
+
+    /// Persona for a template run: a named agent, literal persona text, or
+    /// the active agent.
+    fn template_agent(&self, template: &PromptTemplate) -> Agent {
+        let Some(persona) = &template.persona else {
+            return self.active_agent.clone();
+        };
+        if persona == "memini" {
+            return Agent::default();
+        }
+        if let Some(agent) = self.custom_agents.iter().find(|a| &a.name == persona) {
+            return agent.clone();
+        }
+        Agent {
+            persona: persona.clone(),
+            ..self.active_agent.clone()
+        }
+    }
+
+    fn last_user_message(&self) -> Option<String> {
+        self.conversation_thread.iter().rev().find_map(|entry| {
+            (entry.get("role").and_then(Value::as_str) == Some("user"))
+                .then(|| entry.get("content").and_then(Value::as_str))
+                .flatten()
+                .map(str::to_string)
+        })
+    }
+}
@@ -0,0 +1,137 @@
+//! `/provider` command handler — send model requests to OpenAI or an Azure
+//! OpenAI resource (see [`crate::provider`]).
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use crate::constants::{DEFAULT_AZURE_API_VERSION, DEFAULT_OPENAI_BASE_URL, PROVIDER_VAR};
+use crate::provider::{self, AzureConfig, Provider, parse_deployments};
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+const USAGE: &str = "Usage: /provider [openai [base-url] | azure <endpoint> [--api-version <v>] [--deploy model=deployment]... | reset]";
+
+impl App {
+    pub(crate) fn handle_provider_command(&mut self, args: Vec<&str>) {
+        let provider = match args.as_slice() {
+            [] => {
+                self.show_provider();
+                return;
+            }
+            ["openai"] => Provider::OpenAi {
+                base_url: DEFAULT_OPENAI_BASE_URL.to_string(),
+            },
+            ["openai", base_url] => Provider::OpenAi {
+                base_url: base_url.trim_end_matches('/').to_string(),
+            },
+            ["azure", endpoint, rest @ ..] => match parse_azure(endpoint, rest) {
+                Ok(azure) => Provider::Azure(azure),
+                Err(err) => {
+                    log_src!(self, LogLevel::Warn, format!("{err:#}\n{USAGE}"));
+                    return;
+                }
+            },
+            ["reset"] => {
+                self.reset_provider();
+                return;
+            }
+            _ => {
+                log_src!(self, LogLevel::Warn, USAGE.to_string());
+                return;
+            }
+        };
+        if let Err(err) = self.persist_provider(&provider) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Provider not saved to Rice (this session only): {err:#}")
+            );
+        }
+        provider::set(provider);
+        self.show_provider();
+    }
+
+    fn show_provider(&mut self) {
+        let provider = provider::current();
+        self.log(LogLevel::Info, format!("Provider: {}", provider.label()));
+        if let Provider::Azure(azure) = &provider {
+            if azure.deployments.is_empty() {
+                self.log(
+                    LogLevel::Info,
+                    "  Deployments: each model name is used as its deployment name.".to_string(),
+                );
+            }
+            for (model, deployment) in &azure.deployments {
+                self.log(LogLevel::Info, format!("  {model} → {deployment}"));
+            }
+        }
+    }
+
+    fn persist_provider(&mut self, provider: &Provider) -> Result<()> {
+        let value = serde_json::to_value(provider).context("serialize provider")?;
+        self.runtime
+            .block_on(self.rice.set_variable(PROVIDER_VAR, value, "explicit"))
+    }
+
+    /// Forget the stored choice and go back to the environment's provider.
+    fn reset_provider(&mut self) {
+        if let Err(err) = self
+            .runtime
+            .block_on(self.rice.delete_variable(PROVIDER_VAR))
+        {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Failed to clear stored provider: {err:#}")
+            );
+        }
+        provider::set(Provider::from_env());
+        self.show_provider();
+    }
+
+    /// Restore a `/provider` choice saved in Rice (startup).
+    pub(crate) fn load_provider_from_rice(&mut self) -> Result<()> {
+        let value = self
+            .runtime
+            .block_on(self.rice.get_variable(PROVIDER_VAR))?;
+        let Some(value) = value.filter(Value::is_object) else {
+            return Ok(());
+        };
+        let provider: Provider = serde_json::from_value(value).context("parse stored provider")?;
+        self.log(
+            LogLevel::Info,
+            format!("Loaded provider: {}", provider.label()),
+        );
+        provider::set(provider);
+        Ok(())
+    }
+}
+
+fn parse_azure(endpoint: &str, args: &[&str]) -> Result<AzureConfig> {
+    if !endpoint.starts_with("https://") {
+        bail!("Azure endpoint must be an https:// URL");
+    }
+    let mut api_version = DEFAULT_AZURE_API_VERSION.to_string();
+    let mut pairs = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match *arg {
+            "--api-version" => match iter.next() {
+                Some(version) => api_version = version.to_string(),
+                None => bail!("--api-version needs a value"),
+            },
+            "--deploy" => match iter.next() {
+                Some(pair) => pairs.push(*pair),
+                None => bail!("--deploy needs model=deployment"),
+            },
+            other => bail!("Unexpected argument '{other}'"),
+        }
+    }
+    Ok(AzureConfig {
+        endpoint: endpoint.trim_end_matches('/').to_string(),
+        api_version,
+        deployments: parse_deployments(pairs)?,
+    })
+}
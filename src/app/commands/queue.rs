@@ -0,0 +1,103 @@
+//! `/queue` command handler — chat messages waiting for the current turn
+//! (see [`super::super::chat_queue`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+impl App {
+    pub(crate) fn handle_queue_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_chat_queue(),
+            ["edit", id] => self.recall_queued_message(id),
+            ["edit", id, text @ ..] => self.edit_queued_message(id, &text.join(" ")),
+            ["cancel" | "rm", "all"] | ["clear"] => {
+                let dropped = self.chat_queue.clear();
+                self.log(
+                    LogLevel::Info,
+                    format!("Cancelled {dropped} queued message(s)."),
+                );
+            }
+            ["cancel" | "rm", id] => {
+                let Some(id) = self.queued_id(id) else {
+                    return;
+                };
+                if let Some(message) = self.chat_queue.remove(id) {
+                    self.log(
+                        LogLevel::Info,
+                        format!("Cancelled queued #{id}: {}", message.text),
+                    );
+                }
+            }
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /queue [list] | edit <id> [text] | cancel <id|all>".to_string()
+            ),
+        }
+    }
+
+    fn list_chat_queue(&mut self) {
+        if self.chat_queue.is_empty() {
+            self.log(LogLevel::Info, "No queued messages.".to_string());
+            return;
+        }
+        let lines: Vec<String> = self
+            .chat_queue
+            .iter()
+            .map(|message| format!("  #{} {}", message.id, message.text))
+            .collect();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Queued messages ({}), sent oldest first after the current turn:",
+                lines.len()
+            ),
+        );
+        for line in lines {
+            self.log(LogLevel::Info, line);
+        }
+    }
+
+    /// `/queue edit <id> <text>` — replace the message in place.
+    fn edit_queued_message(&mut self, id: &str, text: &str) {
+        let Some(id) = self.queued_id(id) else {
+            return;
+        };
+        self.chat_queue.edit(id, text);
+        self.log(LogLevel::Info, format!("Updated queued #{id}."));
+    }
+
+    /// `/queue edit <id>` — take the message out of the queue and into the
+    /// input box; Enter queues it again (at the back) or sends it.
+    fn recall_queued_message(&mut self, id: &str) {
+        let Some(id) = self.queued_id(id) else {
+            return;
+        };
+        let Some(message) = self.chat_queue.remove(id) else {
+            return;
+        };
+        self.input = message.text;
+        self.cursor = self.input.len();
+        self.log(
+            LogLevel::Info,
+            format!("Queued #{id} moved to the input box for editing."),
+        );
+    }
+
+    /// Parse `#3` or `3` and check the message is still queued.
+    fn queued_id(&mut self, raw: &str) -> Option<usize> {
+        let id = raw.trim_start_matches('#').parse::<usize>().ok();
+        match id {
+            Some(id) if self.chat_queue.iter().any(|message| message.id == id) => Some(id),
+            _ => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("No queued message {raw}. See /queue.")
+                );
+                None
+            }
+        }
+    }
+}
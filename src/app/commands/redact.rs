@@ -0,0 +1,77 @@
+//! `/redact` command handler — inspect the secret redaction rules
+//! (see [`crate::redact`]).
+
+use std::collections::BTreeMap;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::redact;
+
+// ── /redact ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_redact_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["status"] => self.show_redact_status(),
+            ["test", text @ ..] if !text.is_empty() => self.test_redaction(&text.join(" ")),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /redact [status] | /redact test <text>".to_string()
+            ),
+        }
+    }
+
+    fn show_redact_status(&mut self) {
+        let redactor = redact::redactor();
+        if !redactor.enabled {
+            self.log(
+                LogLevel::Info,
+                "Redaction is off ([redact] enabled = false in memini.toml).".to_string(),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Redaction rules: {}", redactor.kinds().join(", ")),
+        );
+        self.log(
+            LogLevel::Info,
+            format!("{} secret(s) redacted this session.", redact::total()),
+        );
+        for error in &redactor.errors {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Ignored [redact.patterns] {error}")
+            );
+        }
+    }
+
+    /// Show what `text` looks like after redaction, without counting it.
+    fn test_redaction(&mut self, text: &str) {
+        let redaction = redact::redactor().apply(text);
+        if redaction.hits.is_empty() {
+            self.log(LogLevel::Info, "No secrets found.".to_string());
+            return;
+        }
+        let mut kinds: BTreeMap<&str, usize> = BTreeMap::new();
+        for kind in &redaction.hits {
+            *kinds.entry(kind.as_str()).or_default() += 1;
+        }
+        let summary: Vec<String> = kinds
+            .iter()
+            .map(|(kind, count)| format!("{kind} ×{count}"))
+            .collect();
+        self.log(
+            LogLevel::Info,
+            format!(
+                "🔒 {} match(es): {}",
+                redaction.hits.len(),
+                summary.join(", ")
+            ),
+        );
+        self.log(LogLevel::Info, format!("  {}", redaction.text));
+    }
+}
@@ -0,0 +1,97 @@
+//! `/review` command handler — the queue of file writes proposed by
+//! daemons in `writes: propose` mode (see [`super::super::review`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::review::ReviewQueue;
+
+// ── /review ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_review_command(&mut self, args: Vec<&str>) {
+        let id = args
+            .get(1)
+            .and_then(|raw| raw.trim_start_matches('#').parse::<u64>().ok());
+        match (args.first().copied(), id) {
+            (None | Some("list"), _) => self.list_pending_changes(),
+            (Some("diff"), Some(id)) => self.show_pending_diff(id),
+            (Some("apply"), Some(id)) => self.apply_pending_change(id),
+            (Some("discard"), Some(id)) => self.discard_pending_change(id),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /review [list]  |  /review diff|apply|discard <id>".to_string()
+            ),
+        }
+    }
+
+    fn list_pending_changes(&mut self) {
+        let pending = ReviewQueue::open().list();
+        if pending.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No proposed changes. Tasks with `writes: propose` queue their file writes here."
+                    .to_string(),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("{} proposed change(s):", pending.len()),
+        );
+        for change in pending {
+            let (added, removed) = change.file_change().stats();
+            let verb = if change.before.is_none() {
+                "create"
+            } else {
+                "edit"
+            };
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "  #{}  {verb} {} (+{added} -{removed})  from {}  at {}",
+                    change.id, change.path, change.task, change.proposed_at
+                ),
+            );
+        }
+        self.log(
+            LogLevel::Info,
+            "/review diff <id> to inspect, /review apply|discard <id> to decide.".to_string(),
+        );
+    }
+
+    fn show_pending_diff(&mut self, id: u64) {
+        match ReviewQueue::open().get(id) {
+            Ok(change) => {
+                let body = change.file_change().diff_lines().join("\n");
+                self.log_markdown(format!("#{id} from {}", change.task), body);
+            }
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+
+    fn apply_pending_change(&mut self, id: u64) {
+        let root = crate::local_tools::workspace_root();
+        match ReviewQueue::open().apply(id, &root) {
+            Ok(change) => self.log(
+                LogLevel::Info,
+                format!(
+                    "✓ Applied #{id}: wrote {} (from {}).",
+                    change.path, change.task
+                ),
+            ),
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+
+    fn discard_pending_change(&mut self, id: u64) {
+        match ReviewQueue::open().discard(id) {
+            Ok(change) => self.log(
+                LogLevel::Info,
+                format!("Discarded #{id} ({} from {}).", change.path, change.task),
+            ),
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! `/route` command handler — per-task model routing (see [`crate::routing`]).
+
+use anyhow::Result;
+
+use crate::constants::MODEL_ROUTES_VAR;
+use crate::routing::{ModelRoutes, TaskKind};
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /route ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_route_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            self.show_model_routes();
+            return;
+        }
+
+        if args[0] == "reset" {
+            self.openai.routes = match crate::config::read() {
+                Ok(config) => config.routing,
+                Err(err) => {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!("Routing config not loaded: {err:#}")
+                    );
+                    ModelRoutes::default()
+                }
+            };
+            // A null value tells the next launch to read memini.toml again.
+            if let Err(err) = self.runtime.block_on(self.rice.set_variable(
+                MODEL_ROUTES_VAR,
+                serde_json::Value::Null,
+                "explicit",
+            )) {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Model routes not cleared in Rice: {err:#}")
+                );
+            }
+            self.log(
+                LogLevel::Info,
+                "Model routes reset to memini.toml.".to_string(),
+            );
+            self.show_model_routes();
+            return;
+        }
+
+        let (Some(task), Some(model)) = (TaskKind::parse(args[0]), args.get(1)) else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /route <chat|code|agent|daemon|summary|embedding> <model|default> | /route reset"
+                    .to_string()
+            );
+            return;
+        };
+        let model = match *model {
+            "default" | "clear" | "-" => None,
+            name => Some(name.to_string()),
+        };
+        self.openai.routes.set(task, model);
+        if let Err(err) = self.persist_model_routes() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Model routes not saved to Rice: {err:#}")
+            );
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "{} requests now use '{}'.",
+                task.label(),
+                self.openai.model_for(task)
+            ),
+        );
+    }
+
+    fn show_model_routes(&mut self) {
+        self.log(LogLevel::Info, "Model routes:".to_string());
+        for task in TaskKind::ALL {
+            let model = self.openai.model_for(task);
+            let source = if self.openai.routes.get(task).is_some() {
+                "route"
+            } else if task == TaskKind::Embedding {
+                "default"
+            } else {
+                "/model"
+            };
+            self.log(
+                LogLevel::Info,
+                format!("  {:<10} {model}  ({source})", task.label()),
+            );
+        }
+    }
+
+    fn persist_model_routes(&mut self) -> Result<()> {
+        let value = serde_json::to_value(&self.openai.routes)?;
+        self.runtime
+            .block_on(self.rice.set_variable(MODEL_ROUTES_VAR, value, "explicit"))
+    }
+
+    /// Restore routes saved by `/route`; they replace `memini.toml` until
+    /// `/route reset`.
+    pub(crate) fn load_model_routes_from_rice(&mut self) -> Result<()> {
+        let value = self
+            .runtime
+            .block_on(self.rice.get_variable(MODEL_ROUTES_VAR))?;
+        if let Some(value) = value.filter(|value| !value.is_null()) {
+            self.openai.routes = serde_json::from_value::<ModelRoutes>(value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+//! `/run` command handler — replay a `.mm` script of commands and chat
+//! prompts (see [`super::super::scripts`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+
+// ── /run ─────────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_run_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] => match &self.script {
+                Some(run) => {
+                    let message = format!("Running {} ({}).", run.name, run.progress());
+                    self.log(LogLevel::Info, message);
+                }
+                None => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /run <file.mm> | /run stop".to_string()
+                ),
+            },
+            ["stop"] => self.stop_script(),
+            _ => self.start_script(&args.join(" ")),
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! `/secret` command handler — values for recipe `env:` names.
+
+use super::super::App;
+use super::super::agent_recipes;
+use super::super::log_src;
+use super::super::logging::{LogLevel, mask_key};
+use super::super::secrets;
+
+// ── /secret ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_secret_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] | ["list"] => self.list_secrets(),
+            ["set", name, value @ ..] if !value.is_empty() => {
+                self.set_secret(name, &value.join(" "))
+            }
+            ["remove" | "delete", name] => self.remove_secret(name),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /secret [list] | /secret set <NAME> <value> | /secret remove <NAME>"
+                    .to_string()
+            ),
+        }
+    }
+
+    fn list_secrets(&mut self) {
+        let store = secrets::load_secret_store();
+        let recipes = agent_recipes::load_agent_recipes().unwrap_or_default();
+        if store.values.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No secrets stored. Use /secret set <NAME> <value>, then list NAME under a recipe's env:."
+                    .to_string(),
+            );
+        }
+        for (name, value) in &store.values {
+            let users: Vec<&str> = recipes
+                .iter()
+                .filter(|recipe| recipe.env.contains(name))
+                .map(|recipe| recipe.name.as_str())
+                .collect();
+            let used = if users.is_empty() {
+                "unused".to_string()
+            } else {
+                format!("env of {}", users.join(", "))
+            };
+            self.log(
+                LogLevel::Info,
+                format!("  {name} = {} ({used})", mask_key(value)),
+            );
+        }
+        for recipe in &recipes {
+            let (_, missing) = store.resolve(&recipe.env);
+            if !missing.is_empty() {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Recipe {} needs unset: {}", recipe.name, missing.join(", "))
+                );
+            }
+        }
+    }
+
+    fn set_secret(&mut self, name: &str, value: &str) {
+        if let Err(err) = secrets::validate_name(name) {
+            log_src!(self, LogLevel::Warn, format!("{err:#}"));
+            return;
+        }
+        let mut store = secrets::load_secret_store();
+        store.values.insert(name.to_string(), value.to_string());
+        match secrets::persist_secret_store(&store) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Saved secret {name} ({}).", mask_key(value)),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save secret: {err:#}")
+            ),
+        }
+    }
+
+    fn remove_secret(&mut self, name: &str) {
+        let mut store = secrets::load_secret_store();
+        if store.values.remove(name).is_none() {
+            log_src!(self, LogLevel::Warn, format!("No secret named {name}."));
+            return;
+        }
+        match secrets::persist_secret_store(&store) {
+            Ok(()) => self.log(LogLevel::Info, format!("Removed secret {name}.")),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save secret store: {err:#}")
+            ),
+        }
+    }
+}
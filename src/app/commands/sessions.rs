@@ -0,0 +1,251 @@
+//! `/session` command handler — save, list, load, and delete named
+//! conversation sessions.
+
+use chrono::Utc;
+
+use super::super::App;
+use super::super::agents::Agent;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::sessions::{self, SavedSession, SessionIndex};
+
+// ── /session ─────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_session_command(&mut self, args: Vec<&str>) {
+        if args.is_empty() {
+            self.list_sessions();
+            return;
+        }
+
+        match args[0] {
+            "list" | "ls" => self.list_sessions(),
+            "save" => {
+                if let Some(name) = args.get(1) {
+                    self.save_session(name);
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /session save <name>".to_string()
+                    );
+                }
+            }
+            "load" | "resume" => {
+                if let Some(name) = args.get(1) {
+                    self.load_session(name);
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /session load <name>".to_string()
+                    );
+                }
+            }
+            "delete" | "remove" => {
+                if let Some(name) = args.get(1) {
+                    self.delete_session(name);
+                } else {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        "Usage: /session delete <name>".to_string()
+                    );
+                }
+            }
+            other => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unknown /session command: {other}")
+            ),
+        }
+    }
+
+    /// Local sessions merged with whatever Rice holds (newest wins).
+    fn load_session_index(&mut self) -> SessionIndex {
+        let mut index = sessions::load_local_sessions();
+        match self.runtime.block_on(self.rice.load_sessions()) {
+            Ok(Some(value)) => {
+                if let Ok(remote) = serde_json::from_value::<SessionIndex>(value) {
+                    index.merge(remote);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Rice sessions unavailable, using local store: {err:#}")
+                );
+            }
+        }
+        index
+    }
+
+    fn persist_session_index(&mut self, index: &SessionIndex) -> bool {
+        if let Err(err) = sessions::persist_local_sessions(index) {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save sessions: {err:#}")
+            );
+            return false;
+        }
+        let value = serde_json::to_value(index).unwrap_or(serde_json::Value::Null);
+        if let Err(err) = self.runtime.block_on(self.rice.save_sessions(value)) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Sessions saved locally only: {err:#}")
+            );
+        }
+        true
+    }
+
+    fn list_sessions(&mut self) {
+        let index = self.load_session_index();
+        if index.sessions.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No saved sessions. Use /session save <name>.".to_string(),
+            );
+            return;
+        }
+        self.log(LogLevel::Info, "Saved sessions:".to_string());
+        for session in index.sessions.values() {
+            let mcps = if session.mcp_ids.is_empty() {
+                "none".to_string()
+            } else {
+                session.mcp_ids.join(",")
+            };
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "  {} -- {} messages, persona: {}, mcp: {} [{}]",
+                    session.name,
+                    session.thread.len(),
+                    session.agent_name,
+                    mcps,
+                    session.saved_at
+                ),
+            );
+        }
+    }
+
+    fn save_session(&mut self, name: &str) {
+        let turns = self.conversation_thread.len() / 2;
+        if self.store_session(name) {
+            self.log(
+                LogLevel::Info,
+                format!("Session '{name}' saved ({turns} turn(s))."),
+            );
+        }
+    }
+
+    /// Save the current thread, persona and MCP set as session `name`.
+    pub(crate) fn store_session(&mut self, name: &str) -> bool {
+        let mut mcp_ids: Vec<String> = self.mcp_connections.keys().cloned().collect();
+        mcp_ids.sort();
+        let session = SavedSession {
+            name: name.to_string(),
+            saved_at: Utc::now().to_rfc3339(),
+            thread: self.conversation_thread.clone(),
+            agent_name: self.active_agent.name.clone(),
+            mcp_ids,
+            active_mcp: self.active_mcp.as_ref().map(|server| server.id.clone()),
+        };
+        let mut index = self.load_session_index();
+        index.insert(session);
+        self.persist_session_index(&index)
+    }
+
+    fn load_session(&mut self, name: &str) {
+        let index = self.load_session_index();
+        let Some(session) = index.get(name).cloned() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No saved session named '{name}'. Use /session list.")
+            );
+            return;
+        };
+
+        // Persona (without clearing the thread we are about to restore).
+        let agent = if session.agent_name == "memini" {
+            Some(Agent::default())
+        } else {
+            self.custom_agents
+                .iter()
+                .find(|a| a.name == session.agent_name)
+                .cloned()
+        };
+        match agent {
+            Some(agent) => {
+                self.active_agent = agent;
+                let _ = self
+                    .runtime
+                    .block_on(self.rice.save_active_agent_name(&session.agent_name));
+            }
+            None => log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Persona '{}' no longer exists; keeping {}.",
+                    session.agent_name, self.active_agent.name
+                )
+            ),
+        }
+
+        // Thread.
+        self.conversation_thread = session.thread.clone();
+        if let Err(err) = self
+            .runtime
+            .block_on(self.rice.save_thread(&self.conversation_thread))
+        {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Thread not persisted to Rice: {err:#}")
+            );
+        }
+
+        // MCP set: drop extras, connect missing.
+        self.mcp_connections
+            .retain(|id, _| session.mcp_ids.contains(id));
+        for id in &session.mcp_ids {
+            if !self.mcp_connections.contains_key(id) {
+                self.connect_mcp(id);
+            }
+        }
+        if let Some(active) = &session.active_mcp {
+            if self.mcp_connections.contains_key(active) {
+                self.active_mcp = self.mcp_config.find_by_id_or_name(active);
+            }
+        }
+
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Resumed session '{}' ({} turn(s), persona: {}).",
+                session.name,
+                session.thread.len() / 2,
+                self.active_agent.name
+            ),
+        );
+    }
+
+    fn delete_session(&mut self, name: &str) {
+        let mut index = self.load_session_index();
+        if index.remove(name).is_none() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No saved session named '{name}'.")
+            );
+            return;
+        }
+        if self.persist_session_index(&index) {
+            self.log(LogLevel::Info, format!("Session '{name}' deleted."));
+        }
+    }
+}
@@ -1,11 +1,17 @@
-//! `/share` command handlers — shared workspace (team memory) management.
+//! `/share` (alias `/workspace`) command handlers — shared workspace (team
+//! memory) management and the teammate activity feed.
 
-use crate::rice::RiceStatus;
+use crate::rice::{self, RiceStatus};
 
 use super::super::App;
 use super::super::log_src;
 use super::super::logging::LogLevel;
 
+/// Items shown by `/workspace feed` without a count.
+const FEED_DEFAULT_ITEMS: usize = 15;
+/// Longest input / outcome excerpt per feed line.
+const FEED_EXCERPT_CHARS: usize = 80;
+
 impl App {
     pub(crate) fn handle_share_command(&mut self, args: Vec<&str>) {
         if args.is_empty() {
@@ -20,17 +26,24 @@ impl App {
                     log_src!(
                         self,
                         LogLevel::Warn,
-                        "Usage: /share join <workspace-name>".to_string()
+                        "Usage: /workspace join <workspace-name>".to_string()
                     );
                 }
             }
             "leave" => self.leave_shared_workspace(),
             "status" => self.show_share_status(),
+            "feed" => {
+                let limit = args
+                    .get(1)
+                    .and_then(|value| value.parse::<usize>().ok())
+                    .unwrap_or(FEED_DEFAULT_ITEMS);
+                self.show_workspace_feed(limit);
+            }
             other => {
                 log_src!(
                     self,
                     LogLevel::Warn,
-                    format!("Unknown /share command: {other}")
+                    format!("Unknown /workspace command: {other}")
                 );
             }
         }
@@ -47,7 +60,15 @@ impl App {
                 );
                 self.log(
                     LogLevel::Info,
-                    "Use /share leave to return to your private memory.".to_string(),
+                    format!(
+                        "You appear to teammates as '{}' (set MEMINI_USER to change). \
+                        /workspace feed shows their recent activity.",
+                        self.rice.user
+                    ),
+                );
+                self.log(
+                    LogLevel::Info,
+                    "Use /workspace leave to return to your private memory.".to_string(),
                 );
             }
             None => {
@@ -57,7 +78,8 @@ impl App {
                 );
                 self.log(
                     LogLevel::Info,
-                    "Use /share join <name> to join a shared workspace with your team.".to_string(),
+                    "Use /workspace join <name> to join a shared workspace with your team."
+                        .to_string(),
                 );
             }
         }
@@ -154,4 +176,80 @@ impl App {
         }
         self.restart_rice_trigger_listener();
     }
+
+    /// Recent traces committed by other members of the shared workspace.
+    fn show_workspace_feed(&mut self, limit: usize) {
+        let Some(workspace) = self.rice.shared_run_id.clone() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Not in a shared workspace. Use /workspace join <name> first.".to_string()
+            );
+            return;
+        };
+        let limit = limit.clamp(1, 100);
+        // Over-fetch: our own traces are filtered out below.
+        let traces = match self.runtime.block_on(self.rice.reminisce(
+            vec![],
+            (limit * 3) as u64,
+            "recent activity",
+        )) {
+            Ok(traces) => traces,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Could not load workspace feed: {err:#}")
+                );
+                return;
+            }
+        };
+
+        let me = self.rice.user.clone();
+        let entries: Vec<String> = traces
+            .iter()
+            .filter_map(|trace| {
+                let (agent, user) = rice::attribution(&trace.agent_id);
+                let user = user.unwrap_or("unknown");
+                if user == me {
+                    return None;
+                }
+                let agent = agent
+                    .strip_prefix(&format!("{}:", crate::constants::APP_NAME))
+                    .unwrap_or(agent);
+                Some(format!(
+                    "  {user} · {agent}: {} → {}",
+                    excerpt(&trace.input),
+                    excerpt(&trace.outcome)
+                ))
+            })
+            .take(limit)
+            .collect();
+
+        if entries.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!("No teammate activity in '{workspace}' yet."),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Teammate activity in '{workspace}':"),
+        );
+        for entry in entries {
+            self.log(LogLevel::Info, entry);
+        }
+    }
+}
+
+/// First line of `text`, shortened for the feed.
+fn excerpt(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or("");
+    if line.chars().count() <= FEED_EXCERPT_CHARS {
+        return line.to_string();
+    }
+    let mut out: String = line.chars().take(FEED_EXCERPT_CHARS).collect();
+    out.push('…');
+    out
 }
@@ -3,6 +3,7 @@
 use super::super::App;
 use super::super::log_src;
 use super::super::logging::LogLevel;
+use super::super::progress;
 
 impl App {
     pub(crate) fn handle_skills_command(&mut self, args: Vec<&str>) {
@@ -90,7 +91,11 @@ impl App {
     fn import_skill_cmd(&mut self, source: &str) {
         self.log(LogLevel::Info, format!("Importing skill from {source} ..."));
 
-        let result = self.runtime.block_on(crate::skills::import_skill(source));
+        let result = progress::block_on(
+            &self.runtime,
+            format!("Importing skill {source}"),
+            crate::skills::import_skill(source),
+        );
         match result {
             Ok(outcome) => {
                 if let Err(err) = self.reload_imported_skills() {
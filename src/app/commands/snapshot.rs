@@ -0,0 +1,50 @@
+//! `/snapshot` command handler — write the whole app state to a file, or
+//! load one (see [`super::super::snapshot`]).
+
+use std::path::Path;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::snapshot;
+
+// ── /snapshot ────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_snapshot_command(&mut self, args: Vec<&str>) {
+        let path = args.get(1..).unwrap_or_default().join(" ");
+        match args.first().copied() {
+            Some("save") if !path.is_empty() => self.save_snapshot(Path::new(&path)),
+            Some("load") if !path.is_empty() => match snapshot::read(Path::new(&path)) {
+                Ok(snapshot) => self.load_snapshot(snapshot),
+                Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+            },
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /snapshot save <file> | /snapshot load <file>".to_string()
+            ),
+        }
+    }
+
+    fn save_snapshot(&mut self, path: &Path) {
+        let snapshot = self.snapshot();
+        let summary = format!(
+            "{} message(s), {} agent window(s), {} task(s)",
+            snapshot.thread.len(),
+            snapshot.windows.len(),
+            snapshot.daemons.len()
+        );
+        match snapshot::write(path, &snapshot) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Snapshot written to {} ({summary}).", path.display()),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Snapshot not written: {err:#}")
+            ),
+        }
+    }
+}
@@ -0,0 +1,80 @@
+//! `/stats` command handler — request counts and latencies per subsystem
+//! (see [`crate::telemetry`]).
+
+use std::time::Duration;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::telemetry::{self, LatencyStats};
+
+// ── /stats ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_stats_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] => self.show_stats(),
+            ["reset"] => {
+                telemetry::reset();
+                self.log(LogLevel::Info, "Stats cleared.".to_string());
+            }
+            _ => log_src!(self, LogLevel::Warn, "Usage: /stats [reset]".to_string()),
+        }
+    }
+
+    fn show_stats(&mut self) {
+        let stats = telemetry::snapshot();
+        if stats.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No requests recorded yet. Chat, run a task or call a tool, then /stats again."
+                    .to_string(),
+            );
+            return;
+        }
+
+        let mut body = String::from(
+            "| Subsystem / operation | Requests | p50 | p95 |\n| --- | ---: | ---: | ---: |\n",
+        );
+        for subsystem in &stats {
+            body.push_str(&stats_row(
+                &format!("**{}**", subsystem.name),
+                &subsystem.total,
+            ));
+            if subsystem.operations.len() > 1 {
+                for (name, op) in &subsystem.operations {
+                    body.push_str(&stats_row(&format!("  {name}"), op));
+                }
+            }
+        }
+        body.push_str(
+            &match (telemetry::otlp_active(), telemetry::otlp_endpoint()) {
+                (true, Some(endpoint)) => {
+                    format!("\nSpans are exported over OTLP to {endpoint}.\n")
+                }
+                _ => {
+                    "\nOTLP export is off (set OTEL_EXPORTER_OTLP_ENDPOINT with an `otlp` build).\n"
+                        .to_string()
+                }
+            },
+        );
+        self.log_markdown("Stats (this session)".to_string(), body);
+    }
+}
+
+fn stats_row(label: &str, stats: &LatencyStats) -> String {
+    format!(
+        "| {label} | {} | {} | {} |\n",
+        stats.count,
+        format_latency(stats.p50),
+        format_latency(stats.p95)
+    )
+}
+
+fn format_latency(duration: Duration) -> String {
+    if duration < Duration::from_secs(1) {
+        format!("{} ms", duration.as_millis())
+    } else {
+        format!("{:.2} s", duration.as_secs_f64())
+    }
+}
@@ -0,0 +1,179 @@
+//! `/status` command handler — one health report covering MCP, the model
+//! provider, Rice, daemons, agent windows, token usage and the workspace.
+
+use std::time::{Duration, Instant};
+
+use crate::constants::STATUS_PING_TIMEOUT_SECS;
+use crate::i18n::t;
+use crate::local_tools::workspace_root;
+use crate::usage;
+
+use super::super::App;
+use super::super::daemon::AgentWindowStatus;
+use super::super::halt;
+use super::super::log_src;
+use super::super::logging::{LogLevel, mask_key};
+use super::super::window_control;
+
+impl App {
+    pub(crate) fn handle_status_command(&mut self, args: Vec<&str>) {
+        if !args.is_empty() {
+            log_src!(self, LogLevel::Warn, "Usage: /status".to_string());
+            return;
+        }
+        let sections = [
+            ("MCP", self.mcp_status_section()),
+            ("Model", self.model_status_section()),
+            ("Rice", self.rice_status_section()),
+            ("Daemons", self.daemon_status_section()),
+            ("Agent windows", self.window_status_section()),
+            ("Usage today", usage_section()),
+            ("Workspace", format!("`{}`\n", workspace_root().display())),
+        ];
+        let body: String = sections
+            .iter()
+            .map(|(title, section)| format!("**{title}**\n\n{section}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.log_markdown("Status".to_string(), body);
+    }
+
+    /// Each connection with the round trip of a `tools/list` ping.
+    fn mcp_status_section(&self) -> String {
+        if self.mcp_connections.is_empty() {
+            return "No servers connected. `/mcp connect <id>` to add one.\n".to_string();
+        }
+        let timeout = Duration::from_secs(STATUS_PING_TIMEOUT_SECS);
+        let mut rows: Vec<(String, String)> = self.runtime.block_on(async {
+            let mut rows = Vec::new();
+            for (id, connection) in &self.mcp_connections {
+                let started = Instant::now();
+                let health =
+                    match tokio::time::timeout(timeout, connection.client.list_tools()).await {
+                        Ok(Ok(tools)) => format!(
+                            "✓ {} ms · {} tools",
+                            started.elapsed().as_millis(),
+                            tools.len()
+                        ),
+                        Ok(Err(err)) => format!("✗ {}", first_line(&format!("{err:#}"))),
+                        Err(_) => format!("✗ no answer in {STATUS_PING_TIMEOUT_SECS}s"),
+                    };
+                rows.push((id.clone(), health));
+            }
+            rows
+        });
+        rows.sort();
+        let active = self.active_mcp.as_ref().map(|server| server.id.as_str());
+        let mut section = String::from("| Server | Health |\n| --- | --- |\n");
+        for (id, health) in rows {
+            let marker = if Some(id.as_str()) == active {
+                " (active)"
+            } else {
+                ""
+            };
+            section.push_str(&format!("| {id}{marker} | {health} |\n"));
+        }
+        section
+    }
+
+    fn model_status_section(&self) -> String {
+        let mut section = format!(
+            "- model: `{}`{}\n- provider: {}\n- API key: {}\n",
+            self.openai.model,
+            self.openai
+                .reasoning_effort
+                .as_deref()
+                .map(|effort| format!(" (reasoning {effort})"))
+                .unwrap_or_default(),
+            crate::provider::current().label(),
+            match &self.openai_key {
+                Some(key) => mask_key(key),
+                None => "not set — `/key <sk-...>`".to_string(),
+            },
+        );
+        match usage::last_error() {
+            Some(error) => section.push_str(&format!(
+                "- last error: {} `{}` — {}\n",
+                error.at.format("%H:%M:%S"),
+                error.model,
+                first_line(&error.message)
+            )),
+            None => section.push_str("- last error: none\n"),
+        }
+        section
+    }
+
+    fn rice_status_section(&self) -> String {
+        format!(
+            "- {}\n- run id: `{}`\n",
+            self.rice.status_label(),
+            self.rice.active_run_id()
+        )
+    }
+
+    fn daemon_status_section(&self) -> String {
+        if self.daemon_handles.is_empty() {
+            return "None running. `/daemon` lists available tasks.\n".to_string();
+        }
+        let mut section = String::new();
+        if halt::active() {
+            section.push_str("⛔ Halted — `/resume-auto` to continue.\n\n");
+        }
+        for handle in &self.daemon_handles {
+            let left = window_control::format_duration(handle.schedule.remaining());
+            let next = if handle.schedule.is_paused() {
+                format!("paused, {left} left")
+            } else {
+                format!("next run in {left}")
+            };
+            section.push_str(&format!("- {} — {next}\n", handle.def.name));
+        }
+        section
+    }
+
+    fn window_status_section(&self) -> String {
+        if self.agent_windows.is_empty() {
+            return "None.\n".to_string();
+        }
+        let statuses = [
+            (AgentWindowStatus::WaitingForInput, "needs input"),
+            (AgentWindowStatus::Thinking, "working"),
+            (AgentWindowStatus::Done, "done"),
+            (AgentWindowStatus::TimedOut, "timed out"),
+            (AgentWindowStatus::Killed, "killed"),
+        ];
+        let parts: Vec<String> = statuses
+            .iter()
+            .filter_map(|(status, label)| {
+                let count = self
+                    .agent_windows
+                    .iter()
+                    .filter(|window| window.status == *status)
+                    .count();
+                (count > 0).then(|| format!("{count} {}", t(label)))
+            })
+            .collect();
+        format!(
+            "{} total — {}\n",
+            self.agent_windows.len(),
+            parts.join(", ")
+        )
+    }
+}
+
+fn usage_section() -> String {
+    let today = usage::today();
+    format!(
+        "{} request(s) · {} input + {} output tokens (this session)\n",
+        today.requests, today.input_tokens, today.output_tokens
+    )
+}
+
+fn first_line(text: &str) -> String {
+    let line = text.lines().next().unwrap_or_default();
+    if line.chars().count() > 120 {
+        format!("{}…", line.chars().take(120).collect::<String>())
+    } else {
+        line.to_string()
+    }
+}
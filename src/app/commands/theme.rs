@@ -0,0 +1,74 @@
+//! `/theme` command handler — switch between built-in and custom color
+//! themes.
+
+use anyhow::Result;
+
+use crate::constants::THEME_VAR;
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::theme::{self, BUILTIN_THEMES, CUSTOM_THEME};
+
+// ── /theme ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_theme_command(&mut self, args: Vec<&str>) {
+        match args.first().copied() {
+            None | Some("list") => self.show_themes(),
+            Some("reload") => {
+                let name = theme::current().name.clone();
+                self.apply_theme(&name, false);
+            }
+            Some(name) => self.apply_theme(name, true),
+        }
+    }
+
+    fn show_themes(&mut self) {
+        let active = theme::current().name.clone();
+        self.log(LogLevel::Info, format!("Theme: {active}"));
+        for name in BUILTIN_THEMES.iter().copied().chain([CUSTOM_THEME]) {
+            let marker = if name == active { "●" } else { " " };
+            self.log(LogLevel::Info, format!("  {marker} {name}"));
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Custom theme file: {}", theme::theme_file_path().display()),
+        );
+    }
+
+    fn apply_theme(&mut self, name: &str, persist: bool) {
+        let resolved = match theme::resolve(name) {
+            Ok(resolved) => resolved,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err:#}"));
+                return;
+            }
+        };
+        let applied = resolved.name.clone();
+        theme::set(resolved);
+        self.log(LogLevel::Info, format!("Theme set to {applied}."));
+        if persist {
+            let value = serde_json::Value::String(applied);
+            if let Err(err) = self
+                .runtime
+                .block_on(self.rice.set_variable(THEME_VAR, value, "explicit"))
+            {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Theme not saved to Rice: {err:#}")
+                );
+            }
+        }
+    }
+
+    /// Apply the theme saved in Rice (if any).
+    pub(crate) fn load_theme_from_rice(&mut self) -> Result<()> {
+        let value = self.runtime.block_on(self.rice.get_variable(THEME_VAR))?;
+        if let Some(name) = value.as_ref().and_then(|value| value.as_str()) {
+            theme::set(theme::resolve(name)?);
+        }
+        Ok(())
+    }
+}
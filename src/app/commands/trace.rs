@@ -0,0 +1,42 @@
+//! `/trace` command handler — timeline of the last chat turn (see
+//! [`super::super::timeline`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::timeline::Expand;
+
+impl App {
+    pub(crate) fn handle_trace_command(&mut self, args: Vec<&str>) {
+        let expand = match args.as_slice() {
+            [] | ["last"] => Some(Expand::None),
+            ["last", "all"] => Some(Expand::All),
+            ["last", n] => n.parse().ok().filter(|n| *n > 0).map(Expand::Call),
+            _ => None,
+        };
+        let Some(expand) = expand else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /trace last [all|<model call #>]".to_string()
+            );
+            return;
+        };
+        let Some(timeline) = &self.last_timeline else {
+            self.log(
+                LogLevel::Info,
+                "No chat turn yet. Send a message, then /trace last to see where the time went."
+                    .to_string(),
+            );
+            return;
+        };
+        let mut body = format!("```text\n{}\n```", timeline.render(expand).join("\n"));
+        if expand == Expand::None && timeline.calls.iter().any(|call| !call.tools.is_empty()) {
+            body.push_str(
+                "\n\n`/trace last <n>` expands model call n, `/trace last all` every call.",
+            );
+        }
+        let label = format!("Turn timeline ({})", timeline.timestamp);
+        self.log_markdown(label, body);
+    }
+}
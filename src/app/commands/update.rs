@@ -0,0 +1,100 @@
+//! `/update` command handler — install the latest release in place
+//! (see [`crate::update`]).
+
+use tokio::sync::mpsc;
+
+use super::super::App;
+use super::super::daemon::{AgentEvent, ChatLogLevel};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use crate::constants::APP_VERSION;
+use crate::update::{self, Release, UpdateOutcome};
+
+// ── /update ──────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_update_command(&mut self, args: Vec<&str>) {
+        let check_only = match args.as_slice() {
+            [] | ["install"] => false,
+            ["check"] => true,
+            _ => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    "Usage: /update [check|install]".to_string()
+                );
+                return;
+            }
+        };
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            let progress_tx = tx.clone();
+            let progress = move |line: String| {
+                let _ = progress_tx.send(AgentEvent::ChatProgress {
+                    line,
+                    level: ChatLogLevel::Info,
+                });
+            };
+            match update::run(check_only, progress).await {
+                Ok(UpdateOutcome::UpToDate) => {
+                    info(&tx, format!("memini {APP_VERSION} is up to date."))
+                }
+                Ok(UpdateOutcome::Available(release)) => {
+                    info(
+                        &tx,
+                        format!(
+                            "memini {} is available (you have {APP_VERSION}); /update installs it.",
+                            release.version()
+                        ),
+                    );
+                    send_notes(&tx, &release);
+                }
+                Ok(UpdateOutcome::Installed { release, path }) => {
+                    info(
+                        &tx,
+                        format!(
+                            "⬆ Installed memini {} at {} — restart to use it.",
+                            release.version(),
+                            path.display()
+                        ),
+                    );
+                    send_notes(&tx, &release);
+                }
+                Err(err) => {
+                    let _ = tx.send(AgentEvent::ChatProgress {
+                        line: format!("Update failed: {err:#}"),
+                        level: ChatLogLevel::Error,
+                    });
+                }
+            }
+        });
+    }
+}
+
+fn info(tx: &mpsc::UnboundedSender<AgentEvent>, line: String) {
+    let _ = tx.send(AgentEvent::ChatProgress {
+        line,
+        level: ChatLogLevel::Info,
+    });
+}
+
+/// The release notes as Markdown in the activity log.
+fn send_notes(tx: &mpsc::UnboundedSender<AgentEvent>, release: &Release) {
+    let Some(notes) = release
+        .body
+        .as_deref()
+        .map(str::trim)
+        .filter(|notes| !notes.is_empty())
+    else {
+        return;
+    };
+    let link = release
+        .html_url
+        .as_deref()
+        .map(|url| format!("\n\n{url}"))
+        .unwrap_or_default();
+    let _ = tx.send(AgentEvent::ChatMarkdown {
+        label: format!("changelog {}", release.tag_name),
+        body: format!("{notes}{link}"),
+    });
+}
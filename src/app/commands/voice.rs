@@ -0,0 +1,121 @@
+//! `/voice` command handler — dictate into the input composer (see
+//! [`super::super::voice`]).
+
+use super::super::App;
+use super::super::daemon::{AgentEvent, ChatLogLevel};
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::voice;
+
+// ── /voice ───────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_voice_command(&mut self, args: Vec<&str>) {
+        match args.as_slice() {
+            [] if self.voice_recorder.is_some() => self.stop_voice_recording(),
+            [] | ["start"] => self.start_voice_recording(),
+            ["stop"] => self.stop_voice_recording(),
+            ["cancel"] => {
+                if let Some(recorder) = self.voice_recorder.take() {
+                    let _ = recorder.finish();
+                    self.log(LogLevel::Info, "Recording discarded.".to_string());
+                } else {
+                    self.log(LogLevel::Info, "Not recording.".to_string());
+                }
+            }
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /voice [start|stop|cancel]".to_string()
+            ),
+        }
+    }
+
+    fn start_voice_recording(&mut self) {
+        if self.voice_recorder.is_some() {
+            self.log(
+                LogLevel::Info,
+                "Already recording. /voice stop to transcribe.".to_string(),
+            );
+            return;
+        }
+        match voice::start() {
+            Ok(recorder) => {
+                self.voice_recorder = Some(recorder);
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "🎙 Recording… /voice again to transcribe, /voice cancel to discard (max {} min).",
+                        voice::MAX_RECORDING_SECS / 60
+                    ),
+                );
+            }
+            Err(err) => log_src!(self, LogLevel::Error, format!("Cannot record: {err:#}")),
+        }
+    }
+
+    /// Stop recording and transcribe in the background; the transcript
+    /// lands in the input composer via [`AgentEvent::VoiceTranscript`].
+    pub(crate) fn stop_voice_recording(&mut self) {
+        let Some(recorder) = self.voice_recorder.take() else {
+            self.log(
+                LogLevel::Info,
+                "Not recording. Use /voice to start.".to_string(),
+            );
+            return;
+        };
+        let wav = match recorder.finish() {
+            Ok(wav) => wav,
+            Err(err) => {
+                log_src!(self, LogLevel::Error, format!("Recording failed: {err:#}"));
+                return;
+            }
+        };
+        let seconds = voice::wav_duration_secs(&wav);
+        if seconds < 0.3 {
+            self.log(
+                LogLevel::Warn,
+                "Recording was empty; nothing to transcribe.".to_string(),
+            );
+            return;
+        }
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(self, LogLevel::Error, format!("OpenAI key missing: {err}"));
+                return;
+            }
+        };
+
+        self.log(
+            LogLevel::Info,
+            format!("⟳ Transcribing {seconds:.0}s of audio…"),
+        );
+        let openai = self.openai.clone();
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            match openai.transcribe(&key, wav).await {
+                Ok(text) => {
+                    let _ = tx.send(AgentEvent::VoiceTranscript { text });
+                }
+                Err(err) => {
+                    let _ = tx.send(AgentEvent::ChatProgress {
+                        line: format!("Transcription failed: {err:#}"),
+                        level: ChatLogLevel::Error,
+                    });
+                }
+            }
+        });
+    }
+
+    /// Stop recordings that hit the time limit (called every tick).
+    pub(crate) fn poll_voice_recording(&mut self) {
+        if self
+            .voice_recorder
+            .as_ref()
+            .is_some_and(|recorder| recorder.is_finished())
+        {
+            self.stop_voice_recording();
+        }
+    }
+}
@@ -0,0 +1,157 @@
+//! `/worktree` command handler — the isolated workspaces of agent windows
+//! spawned with `/spawn --isolated` (see [`super::super::worktrees`]).
+
+use super::super::App;
+use super::super::log_src;
+use super::super::logging::LogLevel;
+use super::super::worktrees;
+
+// ── /worktree ────────────────────────────────────────────────────────
+
+impl App {
+    pub(crate) fn handle_worktree_command(&mut self, args: Vec<&str>) {
+        let id = args
+            .get(1)
+            .and_then(|raw| raw.trim_start_matches('#').parse::<usize>().ok());
+        match (args.first().copied(), id) {
+            (None | Some("list"), _) => self.list_worktrees(),
+            (Some("diff"), Some(id)) => self.show_worktree_diff(id),
+            (Some("merge"), Some(id)) => self.merge_worktree(id),
+            (Some("discard"), Some(id)) => self.discard_worktree(id),
+            _ => log_src!(
+                self,
+                LogLevel::Warn,
+                "Usage: /worktree [list]  |  /worktree diff|merge|discard <id>".to_string()
+            ),
+        }
+    }
+
+    /// Tell the user what an isolated window changed when its run ends.
+    pub(crate) fn offer_worktree_merge(&mut self, window_id: usize) {
+        match worktrees::changes(window_id) {
+            Ok(changes) if changes.is_empty() => self.log(
+                LogLevel::Info,
+                format!(
+                    "Agent #{window_id} changed nothing in its isolated workspace; /worktree discard {window_id} removes it."
+                ),
+            ),
+            Ok(changes) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Agent #{window_id} changed {} file(s) in its isolated workspace: /worktree diff {window_id} to inspect, /worktree merge|discard {window_id} to decide.",
+                    changes.len()
+                ),
+            ),
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+
+    fn list_worktrees(&mut self) {
+        let isolated = worktrees::list();
+        if isolated.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No isolated workspaces. /spawn --isolated <prompt> gives an agent its own checkout."
+                    .to_string(),
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!("{} isolated workspace(s):", isolated.len()),
+        );
+        for summary in isolated {
+            let changed = match worktrees::changes(summary.window_id) {
+                Ok(changes) => format!("{} changed file(s)", changes.len()),
+                Err(err) => format!("{err:#}"),
+            };
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "  #{}  {} at {}  ({changed})",
+                    summary.window_id,
+                    summary.method,
+                    summary.root.display()
+                ),
+            );
+        }
+    }
+
+    fn show_worktree_diff(&mut self, window_id: usize) {
+        let diff = worktrees::changes(window_id).and_then(|changes| {
+            let lines = worktrees::diff_lines(window_id)?;
+            Ok((changes, lines))
+        });
+        match diff {
+            Ok((changes, _)) if changes.is_empty() => self.log(
+                LogLevel::Info,
+                format!("Agent #{window_id} has no unmerged changes."),
+            ),
+            Ok((changes, lines)) => {
+                let mut body: Vec<String> = changes
+                    .iter()
+                    .map(|change| format!("- `{change}`"))
+                    .collect();
+                body.push(String::new());
+                body.extend(lines);
+                self.log_markdown(
+                    format!("Agent #{window_id} isolated changes"),
+                    body.join("\n"),
+                );
+            }
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+
+    fn merge_worktree(&mut self, window_id: usize) {
+        match worktrees::merge(window_id) {
+            Ok(merged) if merged.applied.is_empty() && merged.conflicts.is_empty() => self.log(
+                LogLevel::Info,
+                format!("Agent #{window_id} has no unmerged changes."),
+            ),
+            Ok(merged) => {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "✓ Merged {} file(s) from Agent #{window_id} into the workspace.",
+                        merged.applied.len()
+                    ),
+                );
+                for change in &merged.conflicts {
+                    log_src!(
+                        self,
+                        LogLevel::Warn,
+                        format!(
+                            "Kept the workspace's {}: it changed there too (/worktree diff {window_id}).",
+                            change.path
+                        )
+                    );
+                }
+            }
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+
+    fn discard_worktree(&mut self, window_id: usize) {
+        let running = self
+            .window_active_runs
+            .get(&window_id)
+            .copied()
+            .unwrap_or(0);
+        if running > 0 {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Agent #{window_id} is still running; /kill {window_id} first.")
+            );
+            return;
+        }
+        match worktrees::discard(window_id) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Removed Agent #{window_id}'s isolated workspace."),
+            ),
+            Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+        }
+    }
+}
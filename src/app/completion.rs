@@ -0,0 +1,492 @@
+//! Tab completion for the input composer — slash commands, subcommands,
+//! and live values (MCP server ids, cached tool names, personas,
+//! background tasks, agent window ids).
+//!
+//! `Tab` on a `/` line completes the word under the cursor.  A single
+//! match is inserted directly; several matches extend the input to their
+//! common prefix and open a suggestion popup above the input box, which
+//! further `Tab`/`Shift+Tab` presses cycle through.  `Enter` accepts the
+//! highlighted candidate and `Esc` closes the popup.
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::App;
+use super::agent_recipes;
+use super::daemon;
+use super::prompt_templates;
+use super::theme::{BUILTIN_THEMES, CUSTOM_THEME};
+
+/// Slash commands and their fixed subcommands / first arguments.
+const COMMANDS: &[(&str, &[&str])] = &[
+    ("/help", &[]),
+    ("/quit", &[]),
+    ("/clear", &[]),
+    ("/panel", &[]),
+    (
+        "/mcp",
+        &[
+            "connect",
+            "disconnect",
+            "status",
+            "tools",
+            "call",
+            "ask",
+            "auth",
+            "auth-code",
+            "token",
+            "token-clear",
+            "add",
+            "remove",
+            "import",
+            "reload",
+            "roots",
+        ],
+    ),
+    ("/openai", &["set", "clear", "import-env"]),
+    ("/model", &["list", "set", "thinking", "help"]),
+    (
+        "/route",
+        &[
+            "chat",
+            "code",
+            "agent",
+            "daemon",
+            "summary",
+            "embedding",
+            "reset",
+        ],
+    ),
+    ("/key", &[]),
+    ("/provider", &["openai", "azure", "reset"]),
+    ("/rice", &["setup"]),
+    (
+        "/agent",
+        &[
+            "use", "create", "delete", "info", "restrict", "export", "import",
+        ],
+    ),
+    ("/thread", &["clear"]),
+    ("/incognito", &["on", "off", "status"]),
+    ("/attach", &["list", "clear"]),
+    ("/voice", &["start", "stop", "cancel"]),
+    ("/dry", &[]),
+    ("/context", &["files"]),
+    ("/digest", &["today", "week"]),
+    ("/queue", &["list", "edit", "cancel"]),
+    ("/stats", &["reset"]),
+    ("/status", &[]),
+    ("/trace", &["last"]),
+    ("/debug", &["http"]),
+    ("/update", &["check", "install"]),
+    ("/run", &["stop"]),
+    ("/session", &["list", "save", "load", "delete"]),
+    ("/snapshot", &["save", "load"]),
+    ("/branch", &[]),
+    ("/rewind", &[]),
+    ("/pin", &["file"]),
+    ("/pins", &["list", "remove"]),
+    ("/index", &["status", "rebuild"]),
+    ("/json", &["list"]),
+    ("/plugins", &["list", "dir"]),
+    (
+        "/memory",
+        &["search", "list", "export", "import", "compact", "facts"],
+    ),
+    (
+        "/daemon",
+        &[
+            "list",
+            "dir",
+            "reload",
+            "templates",
+            "scaffold",
+            "run",
+            "start",
+            "stop",
+            "pause",
+            "resume",
+            "create",
+            "generate",
+            "lint",
+            "remove",
+            "results",
+            "queue",
+        ],
+    ),
+    (
+        "/auto",
+        &[
+            "list",
+            "dir",
+            "reload",
+            "templates",
+            "scaffold",
+            "run",
+            "start",
+            "stop",
+            "pause",
+            "resume",
+            "create",
+            "generate",
+            "lint",
+            "remove",
+            "results",
+            "queue",
+        ],
+    ),
+    ("/spawn", &["list", "resume", "--timeout", "--isolated"]),
+    ("/agents", &["status", "id", "name"]),
+    ("/split", &["off"]),
+    ("/kill", &[]),
+    ("/cancel", &[]),
+    ("/halt", &["status"]),
+    ("/resume-auto", &[]),
+    ("/reply", &["list", "next"]),
+    ("/copy", &[]),
+    ("/save-output", &[]),
+    ("/share", &["join", "leave", "status", "feed"]),
+    ("/workspace", &["join", "leave", "status", "feed"]),
+    ("/skills", &["list", "reload", "import"]),
+    ("/hooks", &["list", "new", "revoke"]),
+    ("/artifacts", &["list", "open"]),
+    ("/review", &["list", "diff", "apply", "discard"]),
+    ("/worktree", &["list", "diff", "merge", "discard"]),
+    ("/mission", &["list", "run", "status", "stop", "dir"]),
+    ("/secret", &["list", "set", "remove"]),
+    ("/redact", &["status", "test"]),
+    (
+        "/notify",
+        &["waiting", "agent", "daemon", "all", "test", "sinks"],
+    ),
+    ("/policy", &["show", "reload", "approve", "deny", "audit"]),
+    ("/theme", &["list", "reload"]),
+    ("/lang", &["list"]),
+    ("/prompt", &["list", "save", "run", "show", "remove"]),
+];
+
+/// Live values completion can draw from, gathered from [`App`] state.
+#[derive(Debug, Default)]
+pub(crate) struct Sources {
+    pub servers: Vec<String>,
+    /// Namespaced `server__tool` names from the connection tool caches.
+    pub tools: Vec<String>,
+    pub agents: Vec<String>,
+    pub tasks: Vec<String>,
+    pub templates: Vec<String>,
+    /// `/prompt` template names.
+    pub prompts: Vec<String>,
+    pub windows: Vec<String>,
+}
+
+/// Open completion popup.
+#[derive(Clone, Debug)]
+pub(crate) struct Completion {
+    /// Byte offset in the input where the completed word starts.
+    pub start: usize,
+    pub candidates: Vec<String>,
+    /// Candidate currently inserted into the input, if cycling started.
+    pub selected: Option<usize>,
+}
+
+/// Candidates for the word ending at `cursor`, plus where that word starts.
+pub(crate) fn candidates(input: &str, cursor: usize, sources: &Sources) -> (usize, Vec<String>) {
+    let before = &input[..cursor.min(input.len())];
+    if !before.starts_with('/') || before.contains('\n') {
+        return (cursor, Vec::new());
+    }
+    let start = before
+        .rfind(char::is_whitespace)
+        .map(|idx| idx + 1)
+        .unwrap_or(0);
+    let word = &before[start..];
+    let prior: Vec<&str> = before[..start].split_whitespace().collect();
+
+    let pool: Vec<String> = match prior.split_first() {
+        None => COMMANDS.iter().map(|(cmd, _)| cmd.to_string()).collect(),
+        Some((cmd, args)) => argument_pool(canonical(cmd), args, sources),
+    };
+
+    let mut matches: Vec<String> = pool
+        .into_iter()
+        .filter(|candidate| candidate.starts_with(word))
+        .collect();
+    matches.sort();
+    matches.dedup();
+    (start, matches)
+}
+
+/// Map command aliases onto the names in [`COMMANDS`].
+fn canonical(cmd: &str) -> &str {
+    match cmd {
+        "/exit" => "/quit",
+        "/sessions" => "/session",
+        "/mem" => "/memory",
+        "/d" => "/daemon",
+        "/r" => "/reply",
+        other => other,
+    }
+}
+
+fn argument_pool(cmd: &str, args: &[&str], sources: &Sources) -> Vec<String> {
+    match (cmd, args) {
+        (_, []) => {
+            let mut pool = COMMANDS
+                .iter()
+                .find(|(name, _)| *name == cmd)
+                .map(|(_, subs)| owned(subs))
+                .unwrap_or_default();
+            match cmd {
+                "/split" | "/copy" | "/save-output" | "/reply" | "/kill" | "/cancel" => {
+                    pool.extend(sources.windows.iter().cloned())
+                }
+                "/theme" => {
+                    pool.extend(owned(BUILTIN_THEMES));
+                    pool.push(CUSTOM_THEME.to_string());
+                }
+                _ => {}
+            }
+            pool
+        }
+        ("/mcp", [sub]) => match *sub {
+            "connect" | "use" | "disconnect" | "auth" | "auth-code" | "token" | "token-clear"
+            | "remove" => sources.servers.clone(),
+            "tools" => {
+                let mut pool = sources.servers.clone();
+                pool.push("all".to_string());
+                pool
+            }
+            "call" => sources.tools.clone(),
+            "import" => owned(&["claude", "cursor"]),
+            "roots" => owned(&["list", "add", "remove"]),
+            _ => Vec::new(),
+        },
+        ("/agent", [sub]) => match *sub {
+            "use" | "switch" | "delete" | "remove" | "restrict" | "export" => {
+                sources.agents.clone()
+            }
+            _ => Vec::new(),
+        },
+        ("/daemon" | "/auto", ["generate", "set"]) => owned(super::recipe_drafts::FIELDS),
+        ("/daemon" | "/auto", ["generate", "save"]) => owned(&["--start"]),
+        ("/memory", ["facts"]) => owned(&["list", "add", "edit", "remove", "clear", "distill"]),
+        ("/daemon" | "/auto", [sub]) => match *sub {
+            "run" | "start" | "stop" | "pause" | "resume" | "remove" | "results" | "lint" => {
+                sources.tasks.clone()
+            }
+            "scaffold" => sources.templates.clone(),
+            "generate" => owned(&["show", "set", "save", "discard"]),
+            _ => Vec::new(),
+        },
+        ("/artifacts", [sub]) => match *sub {
+            "list" | "open" => sources.tasks.clone(),
+            _ => Vec::new(),
+        },
+        ("/artifacts", ["open", _]) => owned(&["latest"]),
+        ("/hooks", [sub]) => match *sub {
+            "new" | "generate" | "revoke" | "remove" => sources.tasks.clone(),
+            _ => Vec::new(),
+        },
+        ("/prompt", [sub]) => match *sub {
+            "run" | "show" | "remove" | "rm" => sources.prompts.clone(),
+            _ => Vec::new(),
+        },
+        ("/notify", ["sinks"]) => owned(&["test"]),
+        ("/debug", ["http"]) => owned(&["on", "off", "show", "clear", "dump"]),
+        ("/notify", [event]) if *event != "test" => owned(&["off", "bell", "desktop"]),
+        ("/model", ["thinking"]) => owned(&["on", "off", "low", "medium", "high"]),
+        _ => Vec::new(),
+    }
+}
+
+fn owned(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+/// Longest prefix shared by every candidate.
+fn common_prefix(candidates: &[String]) -> &str {
+    let Some(first) = candidates.first() else {
+        return "";
+    };
+    let mut len = first.len();
+    for candidate in &candidates[1..] {
+        len = first
+            .bytes()
+            .zip(candidate.bytes())
+            .take(len)
+            .take_while(|(a, b)| a == b)
+            .count();
+    }
+    &first[..len]
+}
+
+// ── App integration ──────────────────────────────────────────────────
+
+impl App {
+    /// Gather completion values from the current app state.
+    fn completion_sources(&self) -> Sources {
+        let mut tools: Vec<String> = self
+            .mcp_connections
+            .iter()
+            .flat_map(|(id, connection)| {
+                connection
+                    .tool_cache
+                    .iter()
+                    .map(move |tool| crate::mcp::namespaced_tool_name(id, &tool.name))
+            })
+            .collect();
+        tools.sort();
+
+        let mut agents = vec!["memini".to_string()];
+        agents.extend(self.custom_agents.iter().map(|agent| agent.name.clone()));
+
+        let mut tasks: Vec<String> = daemon::builtin_tasks()
+            .into_iter()
+            .map(|task| task.name)
+            .collect();
+        tasks.extend(self.daemon_handles.iter().map(|h| h.def.name.clone()));
+        if let Ok(recipes) = agent_recipes::load_agent_recipes() {
+            tasks.extend(recipes.into_iter().map(|recipe| recipe.name));
+        }
+
+        Sources {
+            servers: self
+                .mcp_config
+                .servers
+                .iter()
+                .map(|s| s.id.clone())
+                .collect(),
+            tools,
+            agents,
+            tasks,
+            templates: agent_recipes::recipe_templates()
+                .iter()
+                .map(|template| template.id.to_string())
+                .collect(),
+            prompts: prompt_templates::load_templates()
+                .map(|templates| templates.into_iter().map(|t| t.name).collect())
+                .unwrap_or_default(),
+            windows: self
+                .agent_windows
+                .iter()
+                .map(|w| w.id.to_string())
+                .collect(),
+        }
+    }
+
+    /// Handle `Tab` (`forward`) / `Shift+Tab` on a slash-command line.
+    pub(crate) fn complete_input(&mut self, forward: bool) {
+        if let Some(completion) = self.completion.as_mut() {
+            let count = completion.candidates.len();
+            let next = match (completion.selected, forward) {
+                (None, true) => 0,
+                (None, false) => count - 1,
+                (Some(idx), true) => (idx + 1) % count,
+                (Some(idx), false) => (idx + count - 1) % count,
+            };
+            completion.selected = Some(next);
+            let start = completion.start;
+            let candidate = completion.candidates[next].clone();
+            self.replace_completion_word(start, &candidate);
+            return;
+        }
+
+        let sources = self.completion_sources();
+        let (start, candidates) = candidates(&self.input, self.cursor, &sources);
+        match candidates.len() {
+            0 => {}
+            1 => {
+                let completed = format!("{} ", candidates[0]);
+                self.replace_completion_word(start, &completed);
+            }
+            _ => {
+                let prefix = common_prefix(&candidates).to_string();
+                if prefix.len() > self.cursor - start {
+                    self.replace_completion_word(start, &prefix);
+                }
+                self.completion = Some(Completion {
+                    start,
+                    candidates,
+                    selected: None,
+                });
+            }
+        }
+    }
+
+    /// Keys other than `Tab` close the popup.  `Esc` only closes it, and
+    /// `Enter` accepts the highlighted candidate instead of submitting.
+    /// Returns `true` when the key was consumed.
+    pub(crate) fn handle_completion_key(&mut self, key: &KeyEvent) -> bool {
+        let Some(completion) = self.completion.as_ref() else {
+            return false;
+        };
+        match key.code {
+            KeyCode::Tab | KeyCode::BackTab => false,
+            KeyCode::Esc => {
+                self.completion = None;
+                true
+            }
+            KeyCode::Enter if key.modifiers.is_empty() && completion.selected.is_some() => {
+                self.completion = None;
+                self.insert_char(' ');
+                true
+            }
+            _ => {
+                self.completion = None;
+                false
+            }
+        }
+    }
+
+    /// Replace `input[start..cursor]` with `text` and move the cursor after it.
+    fn replace_completion_word(&mut self, start: usize, text: &str) {
+        let end = self.cursor.min(self.input.len());
+        self.input.replace_range(start..end, text);
+        self.cursor = start + text.len();
+        self.history_index = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources() -> Sources {
+        Sources {
+            servers: vec!["github".into(), "linear".into()],
+            tools: vec!["github__create_issue".into(), "github__search".into()],
+            tasks: vec!["digest".into()],
+            windows: vec!["3".into()],
+            ..Sources::default()
+        }
+    }
+
+    #[test]
+    fn completes_commands_and_subcommands() {
+        let (start, matches) = candidates("/sp", 3, &sources());
+        assert_eq!(start, 0);
+        assert_eq!(matches, vec!["/spawn", "/split"]);
+
+        let (start, matches) = candidates("/mcp dis", 8, &sources());
+        assert_eq!(start, 5);
+        assert_eq!(matches, vec!["disconnect"]);
+
+        let (_, matches) = candidates("/split ", 7, &sources());
+        assert_eq!(matches, vec!["3", "off"]);
+    }
+
+    #[test]
+    fn completes_live_values() {
+        let (_, matches) = candidates("/mcp connect li", 15, &sources());
+        assert_eq!(matches, vec!["linear"]);
+
+        let (start, matches) = candidates("/mcp call github__", 18, &sources());
+        assert_eq!(start, 10);
+        assert_eq!(common_prefix(&matches), "github__");
+        assert_eq!(matches.len(), 2);
+
+        let (_, matches) = candidates("/d run d", 8, &sources());
+        assert_eq!(matches, vec!["digest"]);
+
+        assert!(candidates("hello /mcp", 10, &sources()).1.is_empty());
+    }
+}
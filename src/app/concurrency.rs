@@ -0,0 +1,213 @@
+//! Global limit on how much agent work runs at once.
+//!
+//! Daemon runs, spawned agent windows and chat tool loops all take a slot
+//! from one shared semaphore before calling the model, so a burst of
+//! recipes waking together queues instead of saturating the API and the
+//! workspace.  The limit comes from `memini.toml`:
+//!
+//! ```toml
+//! max_concurrent_agents = 4
+//! ```
+//!
+//! Running and waiting work is tracked for `/daemon queue`.
+
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Instant;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// What kind of work holds (or waits for) a slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkKind {
+    Daemon,
+    Agent,
+    Chat,
+}
+
+impl WorkKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            WorkKind::Daemon => "daemon",
+            WorkKind::Agent => "agent",
+            WorkKind::Chat => "chat",
+        }
+    }
+}
+
+/// One entry in the `/daemon queue` view.
+#[derive(Clone, Debug)]
+pub struct WorkEntry {
+    pub kind: WorkKind,
+    pub label: String,
+    pub queued_at: Instant,
+    /// When the work got its slot; `None` while it is still waiting.
+    pub started_at: Option<Instant>,
+}
+
+struct Limiter {
+    semaphore: Arc<Semaphore>,
+    limit: usize,
+    entries: Mutex<BTreeMap<u64, WorkEntry>>,
+    next_id: AtomicU64,
+}
+
+/// A held slot; dropping it frees the slot for the next queued work.
+pub struct Slot {
+    id: u64,
+    _permit: OwnedSemaphorePermit,
+}
+
+impl Drop for Slot {
+    fn drop(&mut self) {
+        if let Ok(mut entries) = limiter().entries.lock() {
+            entries.remove(&self.id);
+        }
+    }
+}
+
+/// Removes a waiting entry if the acquiring future is dropped (e.g. the
+/// daemon was stopped while queued).
+struct Waiting(u64);
+
+impl Drop for Waiting {
+    fn drop(&mut self) {
+        if let Ok(mut entries) = limiter().entries.lock() {
+            if entries
+                .get(&self.0)
+                .is_some_and(|entry| entry.started_at.is_none())
+            {
+                entries.remove(&self.0);
+            }
+        }
+    }
+}
+
+fn limiter() -> &'static Limiter {
+    static LIMITER: OnceLock<Limiter> = OnceLock::new();
+    LIMITER.get_or_init(|| {
+        let limit = crate::config::get().max_concurrent_agents();
+        Limiter {
+            semaphore: Arc::new(Semaphore::new(limit)),
+            limit,
+            entries: Mutex::new(BTreeMap::new()),
+            next_id: AtomicU64::new(1),
+        }
+    })
+}
+
+/// The configured slot count (read once per process).
+pub fn limit() -> usize {
+    limiter().limit
+}
+
+/// Wait for a free slot.  `on_queued` runs once, with the number of slots
+/// in use, when the work has to wait.
+pub async fn acquire(kind: WorkKind, label: &str, on_queued: impl FnOnce(usize)) -> Slot {
+    let limiter = limiter();
+    let id = limiter.next_id.fetch_add(1, Ordering::Relaxed);
+    if let Ok(mut entries) = limiter.entries.lock() {
+        entries.insert(
+            id,
+            WorkEntry {
+                kind,
+                label: label.to_string(),
+                queued_at: Instant::now(),
+                started_at: None,
+            },
+        );
+    }
+    let _waiting = Waiting(id);
+
+    let permit = match limiter.semaphore.clone().try_acquire_owned() {
+        Ok(permit) => permit,
+        Err(_) => {
+            on_queued(limiter.limit - limiter.semaphore.available_permits());
+            limiter
+                .semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .expect("concurrency semaphore is never closed")
+        }
+    };
+
+    if let Ok(mut entries) = limiter.entries.lock() {
+        if let Some(entry) = entries.get_mut(&id) {
+            entry.started_at = Some(Instant::now());
+        }
+    }
+    Slot {
+        id,
+        _permit: permit,
+    }
+}
+
+/// Running work first (oldest first), then waiting work in queue order.
+pub fn snapshot() -> Vec<WorkEntry> {
+    let mut entries: Vec<WorkEntry> = limiter()
+        .entries
+        .lock()
+        .map(|entries| entries.values().cloned().collect())
+        .unwrap_or_default();
+    entries.sort_by_key(|entry| {
+        (
+            entry.started_at.is_none(),
+            entry.started_at,
+            entry.queued_at,
+        )
+    });
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+    use crate::constants::DEFAULT_MAX_CONCURRENT_AGENTS;
+
+    #[test]
+    fn reads_limit_from_config() {
+        let limit = |raw: &str| MeminiToml::parse(raw).unwrap().max_concurrent_agents();
+        assert_eq!(
+            limit("max_concurrent_agents = 2\n\n[routing]\nchat = \"gpt-5\"\n"),
+            2
+        );
+        assert_eq!(limit("max_concurrent_agents = 0"), 1);
+        assert_eq!(
+            limit("[policy]\nrules = []\n"),
+            DEFAULT_MAX_CONCURRENT_AGENTS
+        );
+    }
+
+    #[test]
+    fn queues_work_beyond_the_limit() {
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            let mut held = Vec::new();
+            for n in 0..limit() {
+                held.push(acquire(WorkKind::Daemon, &format!("test-{n}"), |_| {}).await);
+            }
+            let queued_with = std::cell::Cell::new(None);
+            let waiter = acquire(WorkKind::Agent, "test-waiter", |busy| {
+                queued_with.set(Some(busy))
+            });
+            tokio::pin!(waiter);
+            assert!(
+                tokio::time::timeout(std::time::Duration::from_millis(20), &mut waiter)
+                    .await
+                    .is_err()
+            );
+            assert!(
+                snapshot()
+                    .iter()
+                    .any(|entry| entry.label == "test-waiter" && entry.started_at.is_none())
+            );
+            held.pop();
+            let slot = waiter.await;
+            assert_eq!(queued_with.get(), Some(limit()));
+            drop(slot);
+        });
+    }
+}
@@ -0,0 +1,184 @@
+//! Project context files loaded from the workspace root.
+//!
+//! `MEMINI.md`, `AGENTS.md` and `CLAUDE.md` hold a project's conventions,
+//! build commands and do-nots.  The ones present in the workspace root are
+//! read on every chat turn and agent-window run, in that order, and sent as
+//! one system message capped at `max_tokens` (≈ chars / 4); files past the
+//! cap are cut or left out and `/context files` says which.  Set in
+//! `memini.toml`:
+//!
+//! ```toml
+//! [context_files]
+//! enabled = true      # false: never load them
+//! max_tokens = 4000
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::constants::CONTEXT_FILES_TOKENS;
+
+use super::pins::estimate_tokens;
+
+/// Files looked for in the workspace root, in load order.
+pub const CONTEXT_FILE_NAMES: &[&str] = &["MEMINI.md", "AGENTS.md", "CLAUDE.md"];
+
+/// The `[context_files]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ContextFilesConfig {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: usize,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+fn default_max_tokens() -> usize {
+    CONTEXT_FILES_TOKENS
+}
+
+impl Default for ContextFilesConfig {
+    fn default() -> Self {
+        ContextFilesConfig {
+            enabled: true,
+            max_tokens: CONTEXT_FILES_TOKENS,
+        }
+    }
+}
+
+/// The `[context_files]` section of `memini.toml`.
+pub(crate) fn config() -> &'static ContextFilesConfig {
+    &crate::config::get().context_files
+}
+
+/// How much of one context file made it into the prompt.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Inclusion {
+    Full,
+    /// Cut to fit the budget.
+    Truncated,
+    /// No budget left.
+    Skipped,
+}
+
+#[derive(Clone, Debug)]
+pub struct LoadedFile {
+    pub path: PathBuf,
+    /// Estimated tokens of the whole file.
+    pub tokens: usize,
+    pub inclusion: Inclusion,
+}
+
+/// The context files of one workspace, rendered for a turn.
+#[derive(Debug, Default)]
+pub struct ContextFiles {
+    /// System message content; empty when no file was found.
+    pub content: String,
+    pub files: Vec<LoadedFile>,
+}
+
+/// Read the context files in `root` within `budget` tokens.
+fn load_from(root: &Path, budget: usize) -> ContextFiles {
+    let mut loaded = ContextFiles::default();
+    let mut parts = Vec::new();
+    let mut used = 0usize;
+    for name in CONTEXT_FILE_NAMES {
+        let path = root.join(name);
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let text = text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let tokens = estimate_tokens(text);
+        let remaining_chars = budget.saturating_sub(used) * 4;
+        let inclusion = if used + tokens <= budget {
+            used += tokens;
+            parts.push(format!("# {name}\n\n{text}"));
+            Inclusion::Full
+        } else if remaining_chars > 0 {
+            let head: String = text.chars().take(remaining_chars).collect();
+            parts.push(format!("# {name}\n\n{head}\n… (truncated)"));
+            used = budget;
+            Inclusion::Truncated
+        } else {
+            Inclusion::Skipped
+        };
+        loaded.files.push(LoadedFile {
+            path,
+            tokens,
+            inclusion,
+        });
+    }
+    if !parts.is_empty() {
+        loaded.content = format!(
+            "Project context files from the workspace root. Follow their conventions and instructions for work in this project:\n\n{}",
+            parts.join("\n\n---\n\n")
+        );
+    }
+    loaded
+}
+
+/// The current workspace's context files, or nothing when turned off.
+pub fn load() -> ContextFiles {
+    let config = config();
+    if !config.enabled {
+        return ContextFiles::default();
+    }
+    load_from(&crate::local_tools::workspace_root(), config.max_tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn loads_in_order_and_reports_what_the_budget_cut() {
+        let root = std::env::temp_dir().join(format!("memini-context-{}", std::process::id()));
+        fs::create_dir_all(&root).expect("temp dir");
+        fs::write(root.join("AGENTS.md"), "Run cargo test before committing.").expect("write");
+        fs::write(root.join("MEMINI.md"), "Use anyhow for errors.").expect("write");
+        fs::write(root.join("CLAUDE.md"), "x".repeat(400)).expect("write");
+
+        let loaded = load_from(&root, 30);
+        let names: Vec<(String, Inclusion)> = loaded
+            .files
+            .iter()
+            .map(|file| {
+                (
+                    file.path
+                        .file_name()
+                        .expect("name")
+                        .to_string_lossy()
+                        .to_string(),
+                    file.inclusion.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("MEMINI.md".to_string(), Inclusion::Full),
+                ("AGENTS.md".to_string(), Inclusion::Full),
+                ("CLAUDE.md".to_string(), Inclusion::Truncated),
+            ]
+        );
+        assert!(loaded.content.find("# MEMINI.md") < loaded.content.find("# AGENTS.md"));
+        assert!(loaded.content.ends_with("… (truncated)"));
+
+        let _ = fs::remove_dir_all(&root);
+        assert!(
+            !MeminiToml::parse("[context_files]\nenabled = false\n")
+                .expect("parse")
+                .context_files
+                .enabled
+        );
+    }
+}
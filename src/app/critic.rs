@@ -0,0 +1,141 @@
+//! Result verification — a critic model reviews an agent window's final
+//! output against its prompt before the window is marked done.
+//!
+//! The critic looks for claims the output does not back up and steps the
+//! prompt asked for that were not finished.  Its notes are shown in the
+//! window, logged when it flags something, and stored with the run's
+//! memory trace (`/memory list` shows `critic: ok` or `critic: flagged`).
+//! Off by default; set in `memini.toml`:
+//!
+//! ```toml
+//! [critic]
+//! enabled = true
+//! model = "gpt-5"   # default: the model that ran the agent
+//! ```
+//!
+//! A flagged result still finishes; the critique is advice for whoever
+//! reads it, not a retry.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::openai::{self, OpenAiClient};
+use crate::provenance::Critique;
+
+/// Longest output sent to the critic.
+const MAX_REVIEWED_CHARS: usize = 12_000;
+
+/// Line the critic ends its review with.
+const VERDICT_PREFIX: &str = "VERDICT:";
+
+/// The `[critic]` section of `memini.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub(crate) struct CriticConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub model: Option<String>,
+}
+
+impl CriticConfig {
+    /// The critic's model: the configured one, else `agent_model`.
+    pub fn model_or(&self, agent_model: &str) -> String {
+        self.model
+            .as_deref()
+            .map(str::trim)
+            .filter(|model| !model.is_empty())
+            .unwrap_or(agent_model)
+            .to_string()
+    }
+}
+
+/// The `[critic]` section of `memini.toml`.
+pub(crate) fn config() -> &'static CriticConfig {
+    &crate::config::get().critic
+}
+
+/// Ask `model` to review `output` against the `prompt` that produced it.
+pub(crate) async fn review(
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    prompt: &str,
+    output: &str,
+) -> Result<Critique> {
+    let clipped: String = output.chars().take(MAX_REVIEWED_CHARS).collect();
+    let request = format!(
+        "You review an AI agent's final answer before it is handed to the user.\n\n\
+         The agent was asked:\n{prompt}\n\n\
+         Its final answer:\n{clipped}\n\n\
+         List, briefly, every claim the answer states as fact without support \
+         from the work it describes, and every step the request asked for that \
+         the answer did not finish. Say nothing else. If there is nothing to \
+         flag, say so in one sentence. End with one line: \
+         `{VERDICT_PREFIX} OK` or `{VERDICT_PREFIX} ISSUES`."
+    );
+    let input = [json!({"role": "user", "content": request})];
+    let response = openai
+        .response(key, model, &input, None)
+        .await
+        .context("critic request failed")?;
+    let text = openai::extract_output_text(&openai::extract_output_items(&response));
+    Ok(parse_review(
+        &openai::answering_model(&response, model),
+        &text,
+    ))
+}
+
+/// Split the critic's answer into notes and its verdict line.  A review
+/// without a verdict counts as flagged, so it is not mistaken for a pass.
+fn parse_review(model: &str, text: &str) -> Critique {
+    let mut passed = false;
+    let mut notes = Vec::new();
+    for line in text.lines() {
+        let bare = line.trim().trim_matches('`').trim();
+        match bare.strip_prefix(VERDICT_PREFIX) {
+            Some(verdict) => passed = verdict.trim().eq_ignore_ascii_case("ok"),
+            None => notes.push(line),
+        }
+    }
+    Critique {
+        model: model.to_string(),
+        passed,
+        notes: notes.join("\n").trim().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn reads_the_critic_section_and_verdicts() {
+        let config = MeminiToml::parse("[critic]\nenabled = true\nmodel = \"gpt-5\"\n")
+            .unwrap()
+            .critic;
+        assert!(config.enabled);
+        assert_eq!(config.model_or("gpt-5-mini"), "gpt-5");
+        let config = MeminiToml::parse("[routing]\nagent = \"gpt-5-mini\"\n")
+            .unwrap()
+            .critic;
+        assert!(!config.enabled);
+        assert_eq!(config.model_or("gpt-5-mini"), "gpt-5-mini");
+
+        let review = parse_review("gpt-5", "Nothing to flag.\n`VERDICT: OK`");
+        assert!(review.passed);
+        assert_eq!(review.notes, "Nothing to flag.");
+
+        let review = parse_review(
+            "gpt-5",
+            "- Claims the tests pass; no test run is shown.\nVERDICT: ISSUES\n",
+        );
+        assert!(!review.passed);
+        assert_eq!(
+            review.notes,
+            "- Claims the tests pass; no test run is shown."
+        );
+        assert!(!parse_review("gpt-5", "Looks fine.").passed);
+    }
+}
@@ -9,19 +9,53 @@
 //! output line-by-line so the user can watch the reasoning unfold.
 
 use std::collections::HashSet;
-use std::sync::Arc;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::time::Duration;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use chrono::Local;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
 use tokio::sync::{Notify, mpsc};
+use tracing::Instrument;
 
+use crate::backend::McpBackend;
+use crate::code_index::{self, SEMANTIC_SEARCH_TOOL};
+use crate::constants::COMMAND_STREAM_LINES;
+use crate::diff::FileChange;
+use crate::facts;
 use crate::mcp;
 use crate::mcp::config::McpServer;
 use crate::openai::{self, OpenAiClient};
+use crate::provenance::{Critique, Provenance};
 use crate::rice::{self, RiceStore};
+use crate::routing::TaskKind;
+use crate::util::env_first;
+
+use super::agents::ToolRestrictions;
+use super::artifacts::{self, ArtifactRun, SAVE_ARTIFACT_TOOL};
+use super::attachments::{self, ImageAttachment};
+use super::checkpoints::{self, RunGates};
+use super::concurrency::{self, WorkKind};
+use super::context_files;
+use super::critic;
+use super::digest;
+use super::halt;
+use super::memory_scope;
+use super::policy;
+use super::processes;
+use super::progress;
+use super::prompt_inspect::PromptSnapshot;
+use super::review;
+use super::shell_sessions;
+use super::shutdown;
+use super::sub_agents::SubAgentResults;
+use super::timeline::TurnTimeline;
+use super::tool_failures::{self, FailedCalls};
+use super::tool_loops::LoopGuard;
+use super::tool_results;
+use super::window_control;
+use super::worktrees;
 
 // ── Public types ─────────────────────────────────────────────────────
 
@@ -40,18 +74,77 @@ pub enum AgentEvent {
     },
     /// Agent needs user input to continue.
     NeedsInput { window_id: usize, question: String },
+    /// The `[critic]` model's review of the window's final output, sent
+    /// just before `Finished`.
+    Critique {
+        window_id: usize,
+        critique: Critique,
+    },
+    /// A reasoning summary from one of the window's model calls.
+    Reasoning { window_id: usize, summary: String },
+    /// An output line of the window's running `workspace_run_command`;
+    /// `line` is `None` once the stream cap is reached.
+    CommandOutput {
+        window_id: usize,
+        command: String,
+        line: Option<String>,
+        stdout_lines: usize,
+        stderr_lines: usize,
+    },
+    /// The window's running command ended.
+    CommandEnded { window_id: usize },
+    /// A window run's prompt and answer, appended to the window's thread.
+    WindowThread {
+        window_id: usize,
+        entries: Vec<Value>,
+    },
     /// Legacy: a simple result from a periodic daemon task.
     DaemonResult {
         task_name: String,
         message: String,
         timestamp: String,
     },
+    /// A daemon run reached one of its recipe's `checkpoints:` and waits
+    /// for `/reply`.
+    CheckpointReached {
+        checkpoint_id: usize,
+        task_name: String,
+        checkpoint: String,
+        /// Markdown list of the calls it is about to make.
+        actions: String,
+    },
+    /// A task in propose mode queued a file write for `/review`.
+    ChangeProposed {
+        task_name: String,
+        id: u64,
+        path: String,
+        added: usize,
+        removed: usize,
+    },
 
     // ── Main-chat events (non-blocking chat flow) ────────────────
     /// A progress/status line for the main chat (shows in activity log).
     ChatProgress { line: String, level: ChatLogLevel },
     /// Markdown output from the main chat LLM.
     ChatMarkdown { label: String, body: String },
+    /// The exact input of the main chat's first model call (`/prompt show`).
+    ChatPromptSent { snapshot: PromptSnapshot },
+    /// Model and tool call timings of the last chat turn (`/trace last`).
+    ChatTimeline { timeline: TurnTimeline },
+    /// An agent window stopped early because the app is shutting down.
+    Interrupted { window_id: usize },
+    /// An agent window run hit its maximum runtime and was cancelled.
+    TimedOut { window_id: usize, timeout: Duration },
+    /// `/kill` cancelled an agent window run.
+    Killed { window_id: usize },
+    /// `/voice` transcription finished — goes into the input composer.
+    VoiceTranscript { text: String },
+    /// Files under `$MEMINI_HOME/agents` or `$MEMINI_HOME/skills` changed.
+    HomeFilesChanged { recipes: bool, skills: bool },
+    /// `/daemon generate` got a recipe draft back from the model.
+    RecipeDrafted {
+        result: Result<super::agent_recipes::NewAgentRecipe, String>,
+    },
     /// The main chat turn finished — update thread + commit to Rice.
     #[allow(dead_code)]
     ChatFinished {
@@ -69,6 +162,7 @@ pub enum AgentEvent {
         coordination_key: String,
         persona: String,
         skill_context: String,
+        restrictions: ToolRestrictions,
     },
     /// Rice pub-sub event observed for the active run/workspace.
     RiceStateEvent {
@@ -77,6 +171,41 @@ pub enum AgentEvent {
         agent_id: String,
         payload: String,
     },
+    /// An authenticated POST hit the local webhook listener for a task.
+    WebhookFired { task_name: String, body: String },
+    /// An authenticated call to the local REST API; answered on `reply`.
+    ApiRequest {
+        request: super::rest_api::ApiRequest,
+        reply: std::sync::mpsc::Sender<super::rest_api::ApiReply>,
+    },
+    /// Files changed in the workspace (paths relative to the workspace root).
+    FilesChanged { paths: Vec<String> },
+    /// A tool call hit a `require-approval` policy rule and is waiting.
+    ApprovalRequested {
+        approval_id: usize,
+        tool: String,
+        context: String,
+        arguments: String,
+        rule: String,
+        /// Fenced diff when the call is a file write; empty otherwise.
+        diff: Vec<String>,
+    },
+    /// A background device-code OAuth flow for an MCP server finished.
+    McpDeviceAuthFinished {
+        server_id: String,
+        result: Result<mcp::oauth::OAuthToken, String>,
+    },
+    /// An MCP server asked for a model completion; answered on `reply`.
+    SamplingRequested {
+        request: mcp::sampling::SamplingRequest,
+        reply: mpsc::UnboundedSender<anyhow::Result<mcp::sampling::SamplingReply>>,
+    },
+    /// A tool call was blocked by the tool policy.
+    PolicyDenied {
+        tool: String,
+        context: String,
+        reason: String,
+    },
 }
 
 /// Log level for ChatProgress events.
@@ -102,9 +231,12 @@ pub struct AgentWindow {
     pub output_lines: Vec<String>,
     /// If the agent asked for input, what it asked.
     pub pending_question: Option<String>,
-    /// Scroll offset within this window (for long output).
-    #[allow(dead_code)]
+    /// Rendered lines between the bottom of the view and the bottom of
+    /// the output; 0 follows new output.
     pub scroll: u16,
+    /// While scrolled up, how many output lines the view is pinned to;
+    /// lines past it wait below instead of moving the view.
+    pub scroll_lock: Option<usize>,
     /// Persona used by this window for continuation runs.
     pub persona: String,
     /// Skill context resolved for this window.
@@ -113,10 +245,111 @@ pub struct AgentWindow {
     pub mcp_snapshots: Vec<McpServerSnapshot>,
     /// Optional coordination key used by orchestrated windows.
     pub coordination_key: String,
+    /// Tool allowlists inherited from the spawning persona.
+    pub restrictions: ToolRestrictions,
+    /// User/assistant turns so far; sent with every run of this window.
+    pub thread: Vec<Value>,
+    /// Reasoning summaries of this window's model calls; shown dimmed and
+    /// never committed to memory.
+    pub reasoning: Vec<String>,
+    /// Whether the session view expands [`Self::reasoning`].
+    pub show_reasoning: bool,
+    /// The `workspace_run_command` this window is waiting on, if any.
+    pub running_command: Option<RunningCommand>,
+    /// The critic's review of the last finished run (`[critic]`).
+    pub critique: Option<Critique>,
+}
+
+/// A command an agent window is running, with live line counts.
+#[derive(Clone, Debug, Default)]
+pub struct RunningCommand {
+    pub command: String,
+    pub stdout_lines: usize,
+    pub stderr_lines: usize,
+}
+
+impl AgentWindow {
+    /// A window with no output, thread, persona or tool restrictions yet.
+    pub fn new(id: usize, label: String, prompt: String, status: AgentWindowStatus) -> Self {
+        AgentWindow {
+            id,
+            label,
+            prompt,
+            status,
+            output_lines: Vec::new(),
+            pending_question: None,
+            scroll: 0,
+            scroll_lock: None,
+            persona: String::new(),
+            skill_context: String::new(),
+            mcp_snapshots: Vec::new(),
+            coordination_key: String::new(),
+            restrictions: ToolRestrictions::default(),
+            thread: Vec::new(),
+            reasoning: Vec::new(),
+            show_reasoning: false,
+            running_command: None,
+            critique: None,
+        }
+    }
+
+    /// Scroll `rows` up from the bottom, pinning the view to the current
+    /// output.
+    pub fn scroll_up(&mut self, rows: u16) {
+        self.scroll_lock.get_or_insert(self.output_lines.len());
+        let max = self.output_lines.len().min(u16::MAX as usize) as u16;
+        self.scroll = self.scroll.saturating_add(rows).min(max);
+    }
+
+    /// Scroll `rows` down; reaching the bottom follows new output again.
+    pub fn scroll_down(&mut self, rows: u16) {
+        self.scroll = self.scroll.saturating_sub(rows);
+        if self.scroll == 0 {
+            self.scroll_to_bottom();
+        }
+    }
+
+    pub fn scroll_to_bottom(&mut self) {
+        self.scroll = 0;
+        self.scroll_lock = None;
+    }
+
+    /// The output the view shows: everything, or what was there when the
+    /// view was scrolled up.
+    pub fn visible_output(&self) -> &[String] {
+        let end = self.scroll_lock.map_or(self.output_lines.len(), |lines| {
+            lines.min(self.output_lines.len())
+        });
+        &self.output_lines[..end]
+    }
+
+    /// Output lines that arrived below a scrolled-up view.
+    pub fn unread_lines(&self) -> usize {
+        self.output_lines.len() - self.visible_output().len()
+    }
+
+    /// Append a run's turns, dropping the oldest exchanges past
+    /// `MAX_THREAD_MESSAGES` but always keeping the original task.
+    pub fn append_thread(&mut self, entries: Vec<Value>) {
+        append_bounded_thread(
+            &mut self.thread,
+            entries,
+            crate::constants::MAX_THREAD_MESSAGES,
+        );
+    }
+}
+
+fn append_bounded_thread(thread: &mut Vec<Value>, entries: Vec<Value>, max: usize) {
+    thread.extend(entries);
+    // The first exchange is the task itself; trim the turns after it.
+    while thread.len() > max && thread.len() > 4 {
+        thread.drain(2..4);
+    }
 }
 
 /// Status of an agent window.
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum AgentWindowStatus {
     /// Agent is working (LLM call in flight).
     Thinking,
@@ -124,10 +357,35 @@ pub enum AgentWindowStatus {
     Done,
     /// Agent needs user input.
     WaitingForInput,
+    /// Cancelled after running longer than its timeout.
+    TimedOut,
+    /// Cancelled with `/kill`.
+    Killed,
+}
+
+impl AgentWindowStatus {
+    /// Ordering for status-sorted lists: needs-input, thinking, done,
+    /// then cancelled.
+    pub fn sort_rank(&self) -> u8 {
+        match self {
+            AgentWindowStatus::WaitingForInput => 0,
+            AgentWindowStatus::Thinking => 1,
+            AgentWindowStatus::Done => 2,
+            AgentWindowStatus::TimedOut | AgentWindowStatus::Killed => 3,
+        }
+    }
+
+    /// Still working or waiting: worth saving for `/spawn resume`.
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self,
+            AgentWindowStatus::Thinking | AgentWindowStatus::WaitingForInput
+        )
+    }
 }
 
 /// Persisted definition of a daemon task (stored in Rice).
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
 pub struct DaemonTaskDef {
     pub name: String,
     pub persona: String,
@@ -138,10 +396,98 @@ pub struct DaemonTaskDef {
     #[serde(default)]
     pub trigger_variables: Vec<String>,
     #[serde(default)]
+    pub trigger_paths: Vec<String>,
+    #[serde(default)]
     pub tools: Vec<String>,
+    /// Upstream tasks whose output this task waits for (pipelines).
+    #[serde(default)]
+    pub depends_on: Vec<String>,
+    /// Downstream tasks that receive this task's output when it finishes.
+    #[serde(default)]
+    pub pipe_output_to: Vec<String>,
+    /// Model override from the recipe's `model:` front matter; otherwise
+    /// the `daemon` route applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Reasoning effort from the recipe's `thinking:` (`off`, `low`,
+    /// `medium`, `high`); otherwise the global setting applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thinking: Option<String>,
+    /// Schema name from the recipe's `schema:`; the final answer is
+    /// re-requested as JSON matching it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub schema: Option<String>,
+    /// Rice variable set to the parsed JSON of a `schema:` run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_var: Option<String>,
+    /// Secret names from the recipe's `env:`, exported to its
+    /// `workspace_run_command` processes.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+    /// The recipe's `writes: propose`: `workspace_write_file` calls are
+    /// queued for `/review` instead of writing.
+    #[serde(default)]
+    pub propose_writes: bool,
+    /// Sink names from the recipe's `notify:`; its completions go to these
+    /// instead of the sinks that take `daemon` events.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notify: Vec<String>,
+    /// `email:<address>` targets from the recipe's `deliver:`; each run's
+    /// output is mailed to them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deliver: Vec<String>,
+    /// Tool-call rounds per run from the recipe's `max_tool_loops:`;
+    /// otherwise `[tool_loops] max_rounds` applies.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_tool_loops: Option<usize>,
+    /// The recipe's `checkpoints:`; runs wait for `/reply` before the
+    /// tool calls they cover.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub checkpoints: Vec<String>,
     pub paused: bool,
 }
 
+impl DaemonTaskDef {
+    /// Model for this task's runs: the recipe's `model:`, else the `daemon`
+    /// route.
+    pub fn effective_model(&self, openai: &OpenAiClient) -> String {
+        self.model
+            .clone()
+            .unwrap_or_else(|| openai.model_for(TaskKind::Daemon))
+    }
+
+    /// Reasoning effort for this task's runs: the recipe's `thinking:`,
+    /// else the global setting.
+    pub fn effective_reasoning(&self, openai: &OpenAiClient) -> Option<String> {
+        match self
+            .thinking
+            .as_deref()
+            .and_then(openai::parse_reasoning_setting)
+        {
+            Some(effort) => effort,
+            None => openai.reasoning_effort.clone(),
+        }
+    }
+
+    /// `model` or `model, thinking <effort>` for `/daemon list`.
+    pub fn model_label(&self, openai: &OpenAiClient) -> String {
+        let model = self.effective_model(openai);
+        match self.effective_reasoning(openai) {
+            Some(effort) => format!("{model}, thinking {effort}"),
+            None => model,
+        }
+    }
+
+    /// Whether this task's file writes go to the review queue: the
+    /// recipe's `writes: propose`, or `MEMINI_DAEMON_WRITES=propose` for
+    /// every task.  Such a task may not move files or run commands.
+    pub fn proposes_writes(&self) -> bool {
+        self.propose_writes
+            || env_first(&["MEMINI_DAEMON_WRITES"])
+                .is_some_and(|mode| mode.trim().eq_ignore_ascii_case("propose"))
+    }
+}
+
 /// Runtime handle for a running daemon task.
 pub struct DaemonHandle {
     pub def: DaemonTaskDef,
@@ -149,6 +495,91 @@ pub struct DaemonHandle {
     pub wake: Arc<Notify>,
     /// Abort handle for the spawned tokio task.
     pub abort: tokio::task::AbortHandle,
+    /// Next-run countdown, frozen by `/daemon pause`.
+    pub schedule: Arc<Schedule>,
+}
+
+/// A daemon loop's next-run time, shared with `/daemon pause` and
+/// `/daemon resume`.  Pausing freezes the time left; resuming restarts the
+/// countdown from there, so the task keeps its place in the schedule.
+#[derive(Debug)]
+pub struct Schedule {
+    state: Mutex<ScheduleState>,
+    /// Notified on pause/resume so a sleeping loop re-reads the deadline.
+    changed: Notify,
+}
+
+#[derive(Debug)]
+struct ScheduleState {
+    next_run: Instant,
+    /// Time left when paused; `None` while running.
+    held: Option<Duration>,
+}
+
+impl Schedule {
+    fn new(interval: Duration, paused: bool) -> Self {
+        Schedule {
+            state: Mutex::new(ScheduleState {
+                next_run: Instant::now() + interval,
+                held: paused.then_some(interval),
+            }),
+            changed: Notify::new(),
+        }
+    }
+
+    fn with_state<T>(&self, f: impl FnOnce(&mut ScheduleState) -> T) -> T {
+        let mut state = self.state.lock().unwrap_or_else(|err| err.into_inner());
+        f(&mut state)
+    }
+
+    /// When the loop should run next, or `None` while paused.
+    fn deadline(&self) -> Option<Instant> {
+        self.with_state(|state| state.held.is_none().then_some(state.next_run))
+    }
+
+    fn reschedule(&self, interval: Duration) {
+        self.with_state(|state| state.next_run = Instant::now() + interval);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.with_state(|state| state.held.is_some())
+    }
+
+    /// Time until the next run (frozen while paused).
+    pub fn remaining(&self) -> Duration {
+        self.with_state(|state| {
+            state
+                .held
+                .unwrap_or_else(|| state.next_run.saturating_duration_since(Instant::now()))
+        })
+    }
+
+    /// Freeze the countdown.  Returns the time left, or `None` if the task
+    /// was already paused.
+    pub fn pause(&self) -> Option<Duration> {
+        let held = self.with_state(|state| {
+            if state.held.is_some() {
+                return None;
+            }
+            let left = state.next_run.saturating_duration_since(Instant::now());
+            state.held = Some(left);
+            Some(left)
+        })?;
+        self.changed.notify_one();
+        Some(held)
+    }
+
+    /// Restart the countdown from where [`Schedule::pause`] left it.
+    /// Returns the time left, or `None` if the task was not paused.
+    pub fn resume(&self) -> Option<Duration> {
+        let left = self.with_state(|state| {
+            let left = state.held.take()?;
+            state.next_run = Instant::now() + left;
+            Some(left)
+        })?;
+        self.changed.notify_one();
+        Some(left)
+    }
 }
 
 // ── Built-in task definitions ────────────────────────────────────────
@@ -161,20 +592,27 @@ pub fn builtin_tasks() -> Vec<DaemonTaskDef> {
             persona: crate::prompts::daemon_briefing_persona(),
             prompt: crate::prompts::daemon_briefing_prompt(),
             interval_secs: 3600, // every hour
-            trigger_events: Vec::new(),
-            trigger_variables: Vec::new(),
             tools: vec!["local".to_string()],
             paused: true, // off by default, user enables
+            ..Default::default()
         },
         DaemonTaskDef {
-            name: "digest".to_string(),
+            name: digest::DIGEST_TASK.to_string(),
             persona: crate::prompts::daemon_digest_persona(),
             prompt: crate::prompts::daemon_digest_prompt(),
-            interval_secs: 7200, // every 2 hours
-            trigger_events: Vec::new(),
-            trigger_variables: Vec::new(),
-            tools: vec!["local".to_string()],
+            interval_secs: 86400, // once a day
+            tools: vec!["none".to_string()],
+            paused: true,
+            ..Default::default()
+        },
+        DaemonTaskDef {
+            name: crate::facts::FACTS_TASK.to_string(),
+            persona: crate::prompts::daemon_facts_persona(),
+            prompt: crate::prompts::daemon_facts_prompt(),
+            interval_secs: 21600, // every 6 hours
+            tools: vec!["none".to_string()],
             paused: true,
+            ..Default::default()
         },
     ]
 }
@@ -236,6 +674,9 @@ pub fn spawn_task(
     let wake = Arc::new(Notify::new());
     let wake_clone = wake.clone();
     let def_clone = def.clone();
+    let interval = Duration::from_secs(def.interval_secs);
+    let schedule = Arc::new(Schedule::new(interval, def.paused));
+    let schedule_clone = schedule.clone();
 
     let handle = rt.spawn(async move {
         // Wait for our own Rice connection.
@@ -244,16 +685,32 @@ pub fn spawn_task(
             Err(_) => return,
         };
 
-        let interval = Duration::from_secs(def_clone.interval_secs);
-
         loop {
-            // Sleep or wait for manual wake-up.
+            // Sleep until the next run or a manual wake-up; a pause or
+            // resume re-reads the deadline.
+            let deadline = schedule_clone.deadline();
+            let sleep = async move {
+                match deadline {
+                    Some(at) => tokio::time::sleep_until(tokio::time::Instant::from_std(at)).await,
+                    None => std::future::pending().await,
+                }
+            };
             tokio::select! {
-                _ = tokio::time::sleep(interval) => {}
+                _ = sleep => {}
                 _ = wake_clone.notified() => {}
+                _ = schedule_clone.changed.notified() => continue,
+                _ = shutdown::wait() => break,
             }
 
-            if def_clone.paused {
+            if schedule_clone.is_paused() {
+                continue;
+            }
+            schedule_clone.reschedule(interval);
+            if shutdown::requested() {
+                break;
+            }
+            // Halted: keep the schedule, skip the run.
+            if halt::active() {
                 continue;
             }
 
@@ -266,18 +723,22 @@ pub fn spawn_task(
                 continue;
             };
 
-            let output_text = run_daemon_task_once(&def_clone, &openai, key, &mut rice).await;
-
-            // Commit to Rice memory.
-            let _ = rice
-                .commit_trace(
-                    &def_clone.prompt,
-                    &output_text,
-                    &format!("daemon:{}", def_clone.name),
-                    vec![],
-                    &format!("memini:{}", def_clone.name),
-                )
-                .await;
+            let _running = shutdown::track();
+            let run = async {
+                let artifacts = ArtifactRun::start(&def_clone.name);
+                let output =
+                    run_daemon_task_once(&def_clone, &openai, key, &mut rice, &tx, &artifacts)
+                        .await;
+                artifacts.save_output(&output.text);
+
+                // Commit to Rice memory.
+                record_daemon_run(&def_clone, &output, &mut rice, &tx).await;
+                output.text
+            };
+            let output_text = tokio::select! {
+                output_text = run => output_text,
+                _ = halt::wait() => HALTED_RUN_MESSAGE.to_string(),
+            };
 
             let _ = tx.send(AgentEvent::DaemonResult {
                 task_name: def_clone.name.clone(),
@@ -291,9 +752,13 @@ pub fn spawn_task(
         def,
         wake,
         abort: handle.abort_handle(),
+        schedule,
     }
 }
 
+/// Result reported for a daemon run cancelled by `/halt`.
+const HALTED_RUN_MESSAGE: &str = "Run cancelled by /halt.";
+
 /// Spawn an immediate one-shot run of a daemon task (doesn't loop).
 pub fn spawn_oneshot(
     def: DaemonTaskDef,
@@ -306,6 +771,7 @@ pub fn spawn_oneshot(
     let def_clone = def.clone();
 
     rt.spawn(async move {
+        let _running = shutdown::track();
         let mut rice = match rice_future.await {
             Ok(r) => r,
             Err(_) => return,
@@ -320,17 +786,19 @@ pub fn spawn_oneshot(
             return;
         };
 
-        let output_text = run_daemon_task_once(&def_clone, &openai, key, &mut rice).await;
+        let run = async {
+            let artifacts = ArtifactRun::start(&def_clone.name);
+            let output =
+                run_daemon_task_once(&def_clone, &openai, key, &mut rice, &tx, &artifacts).await;
+            artifacts.save_output(&output.text);
 
-        let _ = rice
-            .commit_trace(
-                &def_clone.prompt,
-                &output_text,
-                &format!("daemon:{}", def_clone.name),
-                vec![],
-                &format!("memini:{}", def_clone.name),
-            )
-            .await;
+            record_daemon_run(&def_clone, &output, &mut rice, &tx).await;
+            output.text
+        };
+        let output_text = tokio::select! {
+            output_text = run => output_text,
+            _ = halt::wait() => HALTED_RUN_MESSAGE.to_string(),
+        };
 
         let _ = tx.send(AgentEvent::DaemonResult {
             task_name: def_clone.name.clone(),
@@ -340,6 +808,58 @@ pub fn spawn_oneshot(
     });
 }
 
+/// Commit a daemon run to episodic memory; runs of the facts task also
+/// replace the distilled semantic facts.
+pub(crate) async fn record_daemon_run(
+    def: &DaemonTaskDef,
+    output: &DaemonOutput,
+    rice: &mut RiceStore,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) {
+    if def.name == digest::DIGEST_TASK && !digest::config().remember {
+        return;
+    }
+    let _ = rice
+        .commit_model_trace(
+            &def.prompt,
+            &output.text,
+            &format!("daemon:{}", def.name),
+            &format!("memini:{}", def.name),
+            &output.model,
+            &output.provenance,
+        )
+        .await;
+
+    if !def.name.eq_ignore_ascii_case(facts::FACTS_TASK) {
+        return;
+    }
+    let Some(lines) = facts::parse_distilled(&output.text) else {
+        let _ = tx.send(AgentEvent::ChatProgress {
+            line: "Fact distillation returned no fact list; facts unchanged.".to_string(),
+            level: ChatLogLevel::Warn,
+        });
+        return;
+    };
+    let existing = rice.load_facts().await.unwrap_or_default();
+    let updated = facts::apply_distilled(&existing, lines);
+    let (line, level) = match rice.save_facts(&updated).await {
+        Ok(()) => (
+            format!("Semantic memory now holds {} fact(s).", updated.len()),
+            ChatLogLevel::Info,
+        ),
+        Err(err) => (
+            format!("Semantic facts not saved: {err:#}"),
+            ChatLogLevel::Warn,
+        ),
+    };
+    let _ = tx.send(AgentEvent::ChatProgress { line, level });
+}
+
+/// System prompt block with the semantic facts (empty when there are none).
+async fn semantic_context(rice: &mut RiceStore) -> String {
+    facts::format_facts(&rice.load_facts().await.unwrap_or_default())
+}
+
 fn normalize_tool_selector(raw: &str) -> String {
     raw.trim().to_ascii_lowercase()
 }
@@ -425,7 +945,7 @@ fn is_rice_memory_state_tool(name: &str) -> bool {
 }
 
 fn is_workspace_or_delegation_tool(name: &str) -> bool {
-    if name == "spawn_agent" || name == "collect_results" {
+    if name == "spawn_agent" || name == "collect_results" || name == "wait_for_agent" {
         return true;
     }
     if name.starts_with("workspace_") {
@@ -442,12 +962,42 @@ fn rice_first_tool_error() -> String {
         .to_string()
 }
 
-async fn run_daemon_task_once(
+/// Final text of a daemon run, the model that produced it and how.
+pub(crate) struct DaemonOutput {
+    pub text: String,
+    /// A `[fallback]` model when the requested one failed.
+    pub model: String,
+    pub provenance: Provenance,
+}
+
+#[tracing::instrument(name = "daemon.run", skip_all, fields(task = %def.name))]
+pub(crate) async fn run_daemon_task_once(
     def: &DaemonTaskDef,
     openai: &OpenAiClient,
     key: &str,
     rice: &mut RiceStore,
-) -> String {
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    artifacts: &ArtifactRun,
+) -> DaemonOutput {
+    let mut provenance = Provenance::start();
+    let tuned = openai.with_reasoning(def.effective_reasoning(openai));
+    let openai = &tuned;
+    let mut slot = Some(daemon_slot(def, tx).await);
+    if def.name == digest::DIGEST_TASK {
+        return digest::run_daemon(openai, key, &def.effective_model(openai), rice).await;
+    }
+    let policy_context = format!("daemon:{}", def.name);
+    let (task_env, missing_env) = super::secrets::load_secret_store().resolve(&def.env);
+    if !missing_env.is_empty() {
+        let _ = tx.send(AgentEvent::ChatProgress {
+            line: format!(
+                "Daemon {}: no secret for {} (set with /secret set).",
+                def.name,
+                missing_env.join(", ")
+            ),
+            level: ChatLogLevel::Warn,
+        });
+    }
     let memory_or_state_query = message_requests_memory_or_state(&def.prompt);
     let mut rice_first_satisfied = !memory_or_state_query;
 
@@ -458,11 +1008,16 @@ async fn run_daemon_task_once(
 
     let memory_ctx = crate::rice::format_memories(&memories);
     let now = Local::now().format("%A, %B %e, %Y at %H:%M");
-    let all_tools = with_rice_priority_tools(selected_local_tools(&def.tools));
+    let mut all_tools = with_rice_priority_tools(selected_local_tools(&def.tools));
+    all_tools.push(artifacts::tool_def());
 
     let system_prompt =
         crate::prompts::worker_system_prompt(&def.persona, &now.to_string(), !all_tools.is_empty());
     let mut input = vec![json!({"role": "system", "content": system_prompt})];
+    let facts_ctx = semantic_context(rice).await;
+    if !facts_ctx.is_empty() {
+        input.push(json!({"role": "system", "content": facts_ctx}));
+    }
     if memory_or_state_query {
         input.push(json!({
             "role": "system",
@@ -480,10 +1035,19 @@ async fn run_daemon_task_once(
         Some(&all_tools)
     };
 
-    let mut response = match openai.response(key, &input, tools_opt).await {
+    let model = def.effective_model(openai);
+    let mut response = match openai.response(key, &model, &input, tools_opt).await {
         Ok(value) => value,
-        Err(err) => return format!("Error: {err:#}"),
+        Err(err) => {
+            return DaemonOutput {
+                text: format!("Error: {err:#}"),
+                model,
+                provenance,
+            };
+        }
     };
+    report_fallback(tx, None, &response);
+    provenance.record_response(&response);
 
     let mut output_items = openai::extract_output_items(&response);
     if !output_items.is_empty() {
@@ -491,16 +1055,43 @@ async fn run_daemon_task_once(
     }
     let mut output_text = openai::extract_output_text(&output_items);
     let mut tool_calls = openai::extract_tool_calls(&output_items);
-    let mut tool_loops = 0usize;
+    let mut loop_guard = LoopGuard::new(def.max_tool_loops);
+    let mut failed_calls = FailedCalls::default();
+    let mut gates = RunGates::new(&def.checkpoints);
 
     while !tool_calls.is_empty() {
-        if openai::tool_loop_limit_reached(tool_loops) {
+        if shutdown::requested() || !loop_guard.next_round() {
             break;
         }
-        tool_loops += 1;
+
+        let mut checkpoint_replies = Vec::new();
+        while let Some(index) = gates.reached(&tool_calls) {
+            let label = gates.label(index).to_string();
+            let actions = checkpoints::intended_actions(&output_text, &tool_calls);
+            // Waiting on a person can take hours; free the slot meanwhile.
+            drop(slot.take());
+            let reply = checkpoints::wait(&def.name, &label, actions, tx).await;
+            slot.replace(daemon_slot(def, tx).await);
+            match reply {
+                Some(reply) if !checkpoints::is_stop(&reply) => {
+                    gates.pass(index);
+                    checkpoint_replies.push(format!(
+                        "The user reviewed checkpoint '{label}' and replied: {reply}"
+                    ));
+                }
+                _ => gates.stop(index),
+            }
+        }
 
         for call in &tool_calls {
-            let tool_output = if memory_or_state_query
+            provenance.record_tool(&call.name);
+            let tool_output = if let Some(repeated) = loop_guard.check(call) {
+                repeated
+            } else if let Some(refused) = gates.refused(call) {
+                refused
+            } else if let Some(blocked) = policy::enforce(call, &policy_context, tx).await {
+                blocked
+            } else if memory_or_state_query
                 && !rice_first_satisfied
                 && is_workspace_or_delegation_tool(&call.name)
             {
@@ -511,7 +1102,37 @@ async fn run_daemon_task_once(
             } else if call.name == "rice_state_get" {
                 rice_first_satisfied = true;
                 handle_rice_state_get_bg(call, rice).await
-            } else if let Some(output) = crate::local_tools::handle_tool_call(call).await {
+            } else if call.name == SAVE_ARTIFACT_TOOL {
+                artifacts::handle_tool_call(call, artifacts)
+            } else if call.name == SEMANTIC_SEARCH_TOOL {
+                code_index::handle_tool_call(call, openai, key).await
+            } else if (crate::local_tools::REORGANIZE_TOOLS.contains(&call.name.as_str())
+                || crate::local_tools::COMMAND_TOOLS.contains(&call.name.as_str()))
+                && def.proposes_writes()
+            {
+                json!({
+                    "error": format!(
+                        "{} is not available with writes: propose; only workspace_write_file changes can be queued for review",
+                        call.name
+                    )
+                })
+                .to_string()
+            } else if call.name == "workspace_write_file" && def.proposes_writes() {
+                let (output, pending) = review::handle_tool_call(&def.name, call);
+                if let Some(pending) = pending {
+                    let (added, removed) = pending.file_change().stats();
+                    let _ = tx.send(AgentEvent::ChangeProposed {
+                        task_name: def.name.clone(),
+                        id: pending.id,
+                        path: pending.path,
+                        added,
+                        removed,
+                    });
+                }
+                output
+            } else if let Some(output) =
+                crate::local_tools::handle_tool_call_with_env(call, &task_env).await
+            {
                 output
             } else {
                 format!(
@@ -520,17 +1141,31 @@ async fn run_daemon_task_once(
                 )
             };
 
+            failed_calls.record(&call.name, &tool_output);
+            let tool_output = tool_results::fit(openai, key, call, tool_output, artifacts).await;
+
             input.push(json!({
                 "type": "function_call_output",
                 "call_id": call.call_id,
                 "output": tool_output
             }));
         }
+        for reply in checkpoint_replies {
+            input.push(json!({"role": "user", "content": reply}));
+        }
 
-        response = match openai.response(key, &input, tools_opt).await {
+        response = match openai.response(key, &model, &input, tools_opt).await {
             Ok(value) => value,
-            Err(err) => return format!("Error: {err:#}"),
+            Err(err) => {
+                return DaemonOutput {
+                    text: format!("Error: {err:#}"),
+                    model,
+                    provenance,
+                };
+            }
         };
+        report_fallback(tx, None, &response);
+        provenance.record_response(&response);
         output_items = openai::extract_output_items(&response);
         if !output_items.is_empty() {
             input.extend(output_items.clone());
@@ -539,31 +1174,211 @@ async fn run_daemon_task_once(
         tool_calls = openai::extract_tool_calls(&output_items);
     }
 
-    if output_text.trim().is_empty() {
+    if let Some(line) = failed_calls.summary() {
+        let _ = tx.send(AgentEvent::ChatProgress {
+            line: format!("Daemon {}: {line}", def.name),
+            level: ChatLogLevel::Warn,
+        });
+    }
+
+    let mut text = if output_text.trim().is_empty() {
         "(no output)".to_string()
     } else {
         output_text
+    };
+    if let Some(schema) = &def.schema {
+        match structured_output(def, schema, openai, key, &model, input, rice, artifacts).await {
+            Ok(json) => text = json,
+            Err(err) => {
+                let _ = tx.send(AgentEvent::ChatProgress {
+                    line: format!("Daemon {}: schema {schema}: {err:#}", def.name),
+                    level: ChatLogLevel::Warn,
+                });
+            }
+        }
+    }
+    DaemonOutput {
+        text,
+        model: openai::answering_model(&response, &model),
+        provenance,
+    }
+}
+
+/// Take a concurrency slot for a daemon run, noting in the activity log
+/// when it has to wait.
+async fn daemon_slot(
+    def: &DaemonTaskDef,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> concurrency::Slot {
+    concurrency::acquire(WorkKind::Daemon, &def.name, |busy| {
+        let _ = tx.send(AgentEvent::ChatProgress {
+            line: format!(
+                "⏳ Daemon {} queued — {busy}/{} agent slots busy.",
+                def.name,
+                concurrency::limit()
+            ),
+            level: ChatLogLevel::Info,
+        });
+    })
+    .await
+}
+
+/// Re-ask for a `schema:` recipe's final answer as validated JSON, save it
+/// as `output.json` and in the recipe's `output_var`.  Returns the pretty
+/// JSON, which becomes the run's output.
+#[allow(clippy::too_many_arguments)]
+async fn structured_output(
+    def: &DaemonTaskDef,
+    schema: &str,
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    mut input: Vec<Value>,
+    rice: &mut RiceStore,
+    artifacts: &ArtifactRun,
+) -> anyhow::Result<String> {
+    let schema = super::schemas::find_schema(schema)?;
+    input.push(json!({
+        "role": "user",
+        "content": format!(
+            "Give your final answer as JSON matching the `{}` schema.",
+            schema.name
+        ),
+    }));
+    let value = super::schemas::generate(openai, key, model, input, &schema).await?;
+    let pretty = serde_json::to_string_pretty(&value)?;
+    artifacts.save("output.json", &pretty)?;
+    if let Some(var) = &def.output_var {
+        rice.set_variable(var, value, &format!("daemon:{}", def.name))
+            .await?;
+    }
+    Ok(pretty)
+}
+
+/// Tell the user when a `[fallback]` model answered instead of the one
+/// requested: in the agent window, else in the chat log.
+fn report_fallback(
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    window_id: Option<usize>,
+    response: &Value,
+) {
+    let Some(line) = openai::fallback_note(response) else {
+        return;
+    };
+    let _ = match window_id {
+        Some(window_id) => tx.send(AgentEvent::Progress { window_id, line }),
+        None => tx.send(AgentEvent::ChatProgress {
+            line,
+            level: ChatLogLevel::Warn,
+        }),
+    };
+}
+
+/// Run a `workspace_run_command` call for window `window_id`, streaming
+/// its first [`COMMAND_STREAM_LINES`] output lines into the window and
+/// live line counts after that.  `/cancel` stops the command.
+async fn run_command_in_window(
+    window_id: usize,
+    call: &ToolCall,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> String {
+    let command = call
+        .arguments
+        .get("command")
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string();
+    let on_line = |line: &str, is_stderr: bool, stdout_lines: usize, stderr_lines: usize| {
+        let shown = stdout_lines + stderr_lines <= COMMAND_STREAM_LINES;
+        let line = shown.then(|| {
+            let marker = if is_stderr { "│!" } else { "│" };
+            format!("{marker} {line}")
+        });
+        let _ = tx.send(AgentEvent::CommandOutput {
+            window_id,
+            command: command.clone(),
+            line,
+            stdout_lines,
+            stderr_lines,
+        });
+    };
+    let stream = crate::local_tools::CommandStream {
+        on_line: &on_line,
+        cancel: window_control::command_cancel(window_id),
+    };
+    let output = crate::local_tools::run_command_streaming(call, &stream).await;
+    let _ = tx.send(AgentEvent::CommandEnded { window_id });
+    output
+}
+
+/// Send the reasoning summaries among `output_items` to the window.
+fn report_reasoning(
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    window_id: usize,
+    output_items: &[Value],
+) {
+    let summary = openai::extract_reasoning_summary(output_items);
+    if !summary.is_empty() {
+        let _ = tx.send(AgentEvent::Reasoning { window_id, summary });
     }
 }
 
 // ── Spawn an agent window (streaming, interactive) ───────────────────
 
+/// Take a concurrency slot for a round of the main chat's tool loop.
+async fn chat_slot(tx: &mpsc::UnboundedSender<AgentEvent>) -> concurrency::Slot {
+    concurrency::acquire(WorkKind::Chat, "chat tool loop", |busy| {
+        let _ = tx.send(AgentEvent::ChatProgress {
+            line: format!(
+                "⏳ Queued — {busy}/{} agent slots busy (max_concurrent_agents).",
+                concurrency::limit()
+            ),
+            level: ChatLogLevel::Info,
+        });
+    })
+    .await
+}
+
+/// Take a concurrency slot for an agent window, noting in the window when
+/// it has to wait.
+async fn window_slot(
+    window_id: usize,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> concurrency::Slot {
+    concurrency::acquire(WorkKind::Agent, &format!("agent #{window_id}"), |busy| {
+        let _ = tx.send(AgentEvent::Progress {
+            window_id,
+            line: format!(
+                "Queued — {busy}/{} agent slots busy (max_concurrent_agents).",
+                concurrency::limit()
+            ),
+        });
+    })
+    .await
+}
+
 /// Spawn a one-shot agent that streams progress into an [`AgentWindow`].
 ///
-/// Sends `Started`, then `Progress` lines as it works, then `Finished`.
-/// The window_id must already be allocated by the caller.
+/// Sends `Started`, then `Progress` lines as it works, then `WindowThread`
+/// and `Finished`.  `thread` holds the window's earlier turns, so a reply
+/// continues the same conversation.  The window_id must already be
+/// allocated by the caller.
 pub fn spawn_agent_window(
     window_id: usize,
     persona: String,
     prompt: String,
+    thread: Vec<Value>,
     skill_context: String,
+    restrictions: ToolRestrictions,
     tx: mpsc::UnboundedSender<AgentEvent>,
     openai: OpenAiClient,
     openai_key: Option<String>,
     rice_future: tokio::task::JoinHandle<RiceStore>,
     rt: tokio::runtime::Handle,
 ) {
-    rt.spawn(async move {
+    let supervisor_tx = tx.clone();
+    let task = async move {
+        let _running = shutdown::track();
         let _ = tx.send(AgentEvent::Started { window_id });
 
         let mut rice = match rice_future.await {
@@ -581,6 +1396,8 @@ pub fn spawn_agent_window(
                 return;
             }
         };
+        let memory = memory_scope::config();
+        memory_scope::enter(&mut rice, window_id, memory);
 
         let Some(key) = &openai_key else {
             let _ = tx.send(AgentEvent::Progress {
@@ -595,6 +1412,9 @@ pub fn spawn_agent_window(
             return;
         };
 
+        let _slot = window_slot(window_id, &tx).await;
+        let artifacts = ArtifactRun::start(&format!("agent-{window_id}"));
+
         // -- Step 1: Recall memories
         let _ = tx.send(AgentEvent::Progress {
             window_id,
@@ -624,22 +1444,35 @@ pub fn spawn_agent_window(
             line: "Thinking...".to_string(),
         });
 
-        let all_tools = with_rice_priority_tools(crate::local_tools::tool_defs());
+        let mut worker_tools = crate::local_tools::tool_defs();
+        worker_tools.extend(shell_sessions::tool_defs());
+        worker_tools.extend(processes::tool_defs());
+        worker_tools.push(artifacts::tool_def());
+        let all_tools = with_rice_priority_tools(restrictions.retain_tools(worker_tools));
         let system_prompt =
             crate::prompts::worker_system_prompt(&persona, &now.to_string(), !all_tools.is_empty());
         let mut input = vec![json!({"role": "system", "content": system_prompt})];
+        let facts_ctx = semantic_context(&mut rice).await;
+        if !facts_ctx.is_empty() {
+            input.push(json!({"role": "system", "content": facts_ctx}));
+        }
         if memory_or_state_query {
             input.push(json!({
                 "role": "system",
                 "content": "For memory/state work, use rice_memories and/or rice_state_get first. Use workspace tools only if Rice output is insufficient and file context is explicitly needed."
             }));
         }
+        let project_ctx = context_files::load().content;
+        if !project_ctx.is_empty() {
+            input.push(json!({"role": "system", "content": project_ctx}));
+        }
         if !skill_context.trim().is_empty() {
             input.push(json!({"role": "system", "content": skill_context.clone()}));
         }
         if !memory_ctx.is_empty() {
             input.push(json!({"role": "system", "content": memory_ctx}));
         }
+        input.extend(thread);
         input.push(json!({"role": "user", "content": prompt.clone()}));
 
         let tools_opt: Option<&[Value]> = if all_tools.is_empty() {
@@ -648,7 +1481,9 @@ pub fn spawn_agent_window(
             Some(&all_tools)
         };
 
-        let mut response = match openai.response(key, &input, tools_opt).await {
+        let mut provenance = Provenance::start();
+        let model = openai.model_for(TaskKind::Agent);
+        let mut response = match openai.response(key, &model, &input, tools_opt).await {
             Ok(r) => r,
             Err(err) => {
                 let msg = format!("Error: {err:#}");
@@ -664,32 +1499,57 @@ pub fn spawn_agent_window(
                 return;
             }
         };
+        report_fallback(&tx, Some(window_id), &response);
+        provenance.record_response(&response);
 
         let mut output_items = openai::extract_output_items(&response);
+        report_reasoning(&tx, window_id, &output_items);
         if !output_items.is_empty() {
             input.extend(output_items.clone());
         }
         let mut output_text = openai::extract_output_text(&output_items);
         let mut tool_calls = openai::extract_tool_calls(&output_items);
-        let mut tool_loops = 0usize;
+        let mut loop_guard = LoopGuard::new(None);
+        let mut failed_calls = FailedCalls::default();
+        let mut interrupted = false;
 
         while !tool_calls.is_empty() {
-            if openai::tool_loop_limit_reached(tool_loops) {
+            if !loop_guard.next_round() {
+                let _ = tx.send(AgentEvent::Progress {
+                    window_id,
+                    line: format!(
+                        "Tool loop limit reached ({} rounds).",
+                        loop_guard.max_rounds()
+                    ),
+                });
+                break;
+            }
+            if shutdown::requested() {
                 let _ = tx.send(AgentEvent::Progress {
                     window_id,
-                    line: "Tool loop limit reached.".to_string(),
+                    line: "Stopping for shutdown; progress is saved for /spawn resume.".to_string(),
                 });
+                interrupted = true;
                 break;
             }
-            tool_loops += 1;
 
             for call in &tool_calls {
+                provenance.record_tool(&call.name);
                 let _ = tx.send(AgentEvent::Progress {
                     window_id,
                     line: format!("Calling tool: {}", call.name),
                 });
 
-                let tool_output = if memory_or_state_query
+                let change = crate::local_tools::preview_write(call);
+                let tool_output = if let Some(repeated) = loop_guard.check(call) {
+                    repeated
+                } else if let Some(blocked) =
+                    policy::enforce(call, &format!("agent #{window_id}"), &tx).await
+                {
+                    blocked
+                } else if !restrictions.allows_tool(&call.name) {
+                    ToolRestrictions::denied_output(&call.name)
+                } else if memory_or_state_query
                     && !rice_first_satisfied
                     && is_workspace_or_delegation_tool(&call.name)
                 {
@@ -700,6 +1560,18 @@ pub fn spawn_agent_window(
                 } else if call.name == "rice_state_get" {
                     rice_first_satisfied = true;
                     handle_rice_state_get_bg(call, &mut rice).await
+                } else if call.name == SAVE_ARTIFACT_TOOL {
+                    artifacts::handle_tool_call(call, &artifacts)
+                } else if call.name == SEMANTIC_SEARCH_TOOL {
+                    code_index::handle_tool_call(call, &openai, key).await
+                } else if call.name == "workspace_run_command" {
+                    run_command_in_window(window_id, call, &tx).await
+                } else if let Some(output) =
+                    shell_sessions::handle_tool_call(window_id, call, &tx).await
+                {
+                    output
+                } else if let Some(output) = processes::handle_tool_call(window_id, call).await {
+                    output
                 } else if let Some(output) = crate::local_tools::handle_tool_call(call).await {
                     output
                 } else {
@@ -710,6 +1582,11 @@ pub fn spawn_agent_window(
                     window_id,
                     line: format!("Tool {} returned.", call.name),
                 });
+                report_file_change(&tx, window_id, change, &tool_output);
+
+                failed_calls.record(&call.name, &tool_output);
+                let tool_output =
+                    tool_results::fit(&openai, key, call, tool_output, &artifacts).await;
 
                 input.push(json!({
                     "type": "function_call_output",
@@ -718,7 +1595,7 @@ pub fn spawn_agent_window(
                 }));
             }
 
-            response = match openai.response(key, &input, tools_opt).await {
+            response = match openai.response(key, &model, &input, tools_opt).await {
                 Ok(r) => r,
                 Err(err) => {
                     let _ = tx.send(AgentEvent::Progress {
@@ -728,7 +1605,10 @@ pub fn spawn_agent_window(
                     break;
                 }
             };
+            report_fallback(&tx, Some(window_id), &response);
+            provenance.record_response(&response);
             output_items = openai::extract_output_items(&response);
+            report_reasoning(&tx, window_id, &output_items);
             if !output_items.is_empty() {
                 input.extend(output_items.clone());
             }
@@ -736,6 +1616,10 @@ pub fn spawn_agent_window(
             tool_calls = openai::extract_tool_calls(&output_items);
         }
 
+        if let Some(line) = failed_calls.summary() {
+            let _ = tx.send(AgentEvent::Progress { window_id, line });
+        }
+
         // -- Step 3: Stream output line by line
         for line in output_text.lines() {
             let _ = tx.send(AgentEvent::Progress {
@@ -743,7 +1627,23 @@ pub fn spawn_agent_window(
                 line: line.to_string(),
             });
             // Small delay between lines for visual streaming effect.
-            tokio::time::sleep(Duration::from_millis(30)).await;
+            if !interrupted {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
+        }
+
+        if !interrupted && !output_text.contains("[NEEDS_INPUT]") {
+            critique_output(
+                window_id,
+                &tx,
+                &openai,
+                key,
+                &model,
+                &prompt,
+                &output_text,
+                &mut provenance,
+            )
+            .await;
         }
 
         // -- Step 4: Commit to Rice memory
@@ -753,17 +1653,29 @@ pub fn spawn_agent_window(
         });
 
         let _ = rice
-            .commit_trace(
+            .commit_model_trace(
                 &prompt,
                 &output_text,
                 &format!("agent-window:{window_id}"),
-                vec![],
                 &format!("memini:agent-{window_id}"),
+                &openai::answering_model(&response, &model),
+                &provenance,
             )
             .await;
 
         // -- Step 5: Check if agent needs user input
-        if output_text.contains("[NEEDS_INPUT]") {
+        if !interrupted {
+            let _ = tx.send(AgentEvent::WindowThread {
+                window_id,
+                entries: vec![
+                    json!({"role": "user", "content": prompt.clone()}),
+                    json!({"role": "assistant", "content": output_text.clone()}),
+                ],
+            });
+        }
+        if interrupted {
+            let _ = tx.send(AgentEvent::Interrupted { window_id });
+        } else if output_text.contains("[NEEDS_INPUT]") {
             let question = output_text
                 .split("[NEEDS_INPUT]")
                 .nth(1)
@@ -775,13 +1687,18 @@ pub fn spawn_agent_window(
                 question,
             });
         } else {
+            memory_scope::promote(&mut rice, window_id, &prompt, &output_text, memory).await;
+            artifacts.save_output(&output_text);
             let _ = tx.send(AgentEvent::Finished {
                 window_id,
                 message: output_text,
                 timestamp: Local::now().format("%H:%M:%S").to_string(),
             });
         }
-    });
+    };
+    let task =
+        window_control::supervise(window_id, supervisor_tx, worktrees::scope(window_id, task));
+    rt.spawn(task.instrument(tracing::info_span!("agent.run", window_id)));
 }
 
 // ── MCP server info for agent spawning ───────────────────────────────
@@ -792,6 +1709,8 @@ pub fn spawn_agent_window(
 pub struct McpServerSnapshot {
     pub server: McpServer,
     pub bearer: Option<String>,
+    /// How the task connects (the app's [`crate::backend::McpBackend`]).
+    pub backend: Arc<dyn McpBackend>,
     /// Pre-serialised OpenAI tool definitions for this server.
     pub openai_tools: Vec<Value>,
 }
@@ -807,7 +1726,9 @@ pub fn spawn_agent_window_with_mcp(
     coordination_key: String,
     persona: String,
     prompt: String,
+    thread: Vec<Value>,
     skill_context: String,
+    restrictions: ToolRestrictions,
     mcp_snapshots: Vec<McpServerSnapshot>,
     tx: mpsc::UnboundedSender<AgentEvent>,
     openai: OpenAiClient,
@@ -815,7 +1736,9 @@ pub fn spawn_agent_window_with_mcp(
     rice_future: tokio::task::JoinHandle<RiceStore>,
     rt: tokio::runtime::Handle,
 ) {
-    rt.spawn(async move {
+    let supervisor_tx = tx.clone();
+    let task = async move {
+        let _running = shutdown::track();
         let _ = tx.send(AgentEvent::Started { window_id });
 
         let mut rice = match rice_future.await {
@@ -833,6 +1756,8 @@ pub fn spawn_agent_window_with_mcp(
                 return;
             }
         };
+        let memory = memory_scope::config();
+        memory_scope::enter(&mut rice, window_id, memory);
 
         let Some(key) = &openai_key else {
             let _ = tx.send(AgentEvent::Progress {
@@ -847,6 +1772,9 @@ pub fn spawn_agent_window_with_mcp(
             return;
         };
 
+        let _slot = window_slot(window_id, &tx).await;
+        let artifacts = ArtifactRun::start(&format!("agent-{window_id}"));
+
         // -- Step 1: Connect to MCP servers
         let mut connections: Vec<mcp::McpConnection> = Vec::new();
         let mut all_tools: Vec<Value> = Vec::new();
@@ -857,7 +1785,11 @@ pub fn spawn_agent_window_with_mcp(
                 line: format!("Connecting to MCP: {}...", snap.server.display_name()),
             });
 
-            match mcp::connect_http(&snap.server, snap.bearer.clone()).await {
+            match snap
+                .backend
+                .connect(&snap.server, snap.bearer.clone())
+                .await
+            {
                 Ok(mut conn) => {
                     // Refresh tools from the live connection.
                     match mcp::refresh_tools(&mut conn).await {
@@ -905,7 +1837,10 @@ pub fn spawn_agent_window_with_mcp(
             }
         }
         all_tools.extend(crate::local_tools::tool_defs());
-        all_tools = with_rice_priority_tools(all_tools);
+        all_tools.extend(shell_sessions::tool_defs());
+        all_tools.extend(processes::tool_defs());
+        all_tools.push(artifacts::tool_def());
+        all_tools = with_rice_priority_tools(restrictions.retain_tools(all_tools));
 
         // -- Step 2: Recall memories
         let _ = tx.send(AgentEvent::Progress {
@@ -939,18 +1874,27 @@ pub fn spawn_agent_window_with_mcp(
         let system_prompt =
             crate::prompts::worker_system_prompt(&persona, &now.to_string(), !all_tools.is_empty());
         let mut input = vec![json!({"role": "system", "content": system_prompt})];
+        let facts_ctx = semantic_context(&mut rice).await;
+        if !facts_ctx.is_empty() {
+            input.push(json!({"role": "system", "content": facts_ctx}));
+        }
         if memory_or_state_query {
             input.push(json!({
                 "role": "system",
                 "content": "For memory/state work, use rice_memories and/or rice_state_get first. Use workspace tools only if Rice output is insufficient and file context is explicitly needed."
             }));
         }
+        let project_ctx = context_files::load().content;
+        if !project_ctx.is_empty() {
+            input.push(json!({"role": "system", "content": project_ctx}));
+        }
         if !skill_context.trim().is_empty() {
             input.push(json!({"role": "system", "content": skill_context.clone()}));
         }
         if !memory_ctx.is_empty() {
             input.push(json!({"role": "system", "content": memory_ctx}));
         }
+        input.extend(thread);
         input.push(json!({"role": "user", "content": prompt.clone()}));
 
         let tools_opt: Option<&[Value]> = if all_tools.is_empty() {
@@ -959,7 +1903,9 @@ pub fn spawn_agent_window_with_mcp(
             Some(&all_tools)
         };
 
-        let mut response = match openai.response(key, &input, tools_opt).await {
+        let mut provenance = Provenance::start();
+        let model = openai.model_for(TaskKind::Agent);
+        let mut response = match openai.response(key, &model, &input, tools_opt).await {
             Ok(r) => r,
             Err(err) => {
                 let msg = format!("Error: {err:#}");
@@ -975,32 +1921,57 @@ pub fn spawn_agent_window_with_mcp(
                 return;
             }
         };
+        report_fallback(&tx, Some(window_id), &response);
+        provenance.record_response(&response);
 
         let mut output_items = openai::extract_output_items(&response);
+        report_reasoning(&tx, window_id, &output_items);
         if !output_items.is_empty() {
             input.extend(output_items.clone());
         }
         let mut output_text = openai::extract_output_text(&output_items);
         let mut tool_calls = openai::extract_tool_calls(&output_items);
-        let mut tool_loops = 0usize;
+        let mut loop_guard = LoopGuard::new(None);
+        let mut failed_calls = FailedCalls::default();
+        let mut interrupted = false;
 
         while !tool_calls.is_empty() {
-            if openai::tool_loop_limit_reached(tool_loops) {
+            if !loop_guard.next_round() {
+                let _ = tx.send(AgentEvent::Progress {
+                    window_id,
+                    line: format!(
+                        "Tool loop limit reached ({} rounds).",
+                        loop_guard.max_rounds()
+                    ),
+                });
+                break;
+            }
+            if shutdown::requested() {
                 let _ = tx.send(AgentEvent::Progress {
                     window_id,
-                    line: "Tool loop limit reached.".to_string(),
+                    line: "Stopping for shutdown; progress is saved for /spawn resume.".to_string(),
                 });
+                interrupted = true;
                 break;
             }
-            tool_loops += 1;
 
             for call in &tool_calls {
+                provenance.record_tool(&call.name);
                 let _ = tx.send(AgentEvent::Progress {
                     window_id,
                     line: format!("Calling tool: {}", call.name),
                 });
 
-                let tool_output = if memory_or_state_query
+                let change = crate::local_tools::preview_write(call);
+                let tool_output = if let Some(repeated) = loop_guard.check(call) {
+                    repeated
+                } else if let Some(blocked) =
+                    policy::enforce(call, &format!("agent #{window_id}"), &tx).await
+                {
+                    blocked
+                } else if !restrictions.allows_tool(&call.name) {
+                    ToolRestrictions::denied_output(&call.name)
+                } else if memory_or_state_query
                     && !rice_first_satisfied
                     && is_workspace_or_delegation_tool(&call.name)
                 {
@@ -1011,17 +1982,31 @@ pub fn spawn_agent_window_with_mcp(
                 } else if call.name == "rice_state_get" {
                     rice_first_satisfied = true;
                     handle_rice_state_get_bg(call, &mut rice).await
+                } else if call.name == SAVE_ARTIFACT_TOOL {
+                    artifacts::handle_tool_call(call, &artifacts)
+                } else if call.name == SEMANTIC_SEARCH_TOOL {
+                    code_index::handle_tool_call(call, &openai, key).await
+                } else if call.name == "workspace_run_command" {
+                    run_command_in_window(window_id, call, &tx).await
+                } else if let Some(output) =
+                    shell_sessions::handle_tool_call(window_id, call, &tx).await
+                {
+                    output
+                } else if let Some(output) = processes::handle_tool_call(window_id, call).await {
+                    output
                 } else if let Some(output) = crate::local_tools::handle_tool_call(call).await {
                     output
                 } else if let Some((server_id, tool_name)) =
                     mcp::split_namespaced_tool_name(&call.name)
                 {
                     if let Some(conn) = connections.iter().find(|c| c.server.id == server_id) {
-                        match mcp::call_tool(conn, tool_name, call.arguments.clone()).await {
-                            Ok(value) => {
-                                serde_json::to_string(&value).unwrap_or_else(|_| "{}".to_string())
-                            }
-                            Err(err) => format!(r#"{{"error":"{err}"}}"#),
+                        match tool_failures::call_mcp_tool(conn, tool_name, call.arguments.clone())
+                            .await
+                        {
+                            Ok(value) => mcp::content::render_result(&value, |name, bytes| {
+                                artifacts.save_bytes(&format!("mcp/{}-{name}", call.call_id), bytes)
+                            }),
+                            Err(envelope) => envelope,
                         }
                     } else {
                         format!(r#"{{"error":"No MCP connection for server '{server_id}'"}}"#)
@@ -1034,6 +2019,11 @@ pub fn spawn_agent_window_with_mcp(
                     window_id,
                     line: format!("Tool {} returned.", call.name),
                 });
+                report_file_change(&tx, window_id, change, &tool_output);
+
+                failed_calls.record(&call.name, &tool_output);
+                let tool_output =
+                    tool_results::fit(&openai, key, call, tool_output, &artifacts).await;
 
                 input.push(json!({
                     "type": "function_call_output",
@@ -1042,7 +2032,7 @@ pub fn spawn_agent_window_with_mcp(
                 }));
             }
 
-            response = match openai.response(key, &input, tools_opt).await {
+            response = match openai.response(key, &model, &input, tools_opt).await {
                 Ok(r) => r,
                 Err(err) => {
                     let _ = tx.send(AgentEvent::Progress {
@@ -1052,7 +2042,10 @@ pub fn spawn_agent_window_with_mcp(
                     break;
                 }
             };
+            report_fallback(&tx, Some(window_id), &response);
+            provenance.record_response(&response);
             output_items = openai::extract_output_items(&response);
+            report_reasoning(&tx, window_id, &output_items);
             if !output_items.is_empty() {
                 input.extend(output_items.clone());
             }
@@ -1060,15 +2053,37 @@ pub fn spawn_agent_window_with_mcp(
             tool_calls = openai::extract_tool_calls(&output_items);
         }
 
+        if let Some(line) = failed_calls.summary() {
+            let _ = tx.send(AgentEvent::Progress { window_id, line });
+        }
+
         // -- Step 4: Stream output
         for line in output_text.lines() {
             let _ = tx.send(AgentEvent::Progress {
                 window_id,
                 line: line.to_string(),
             });
-            tokio::time::sleep(Duration::from_millis(30)).await;
+            if !interrupted {
+                tokio::time::sleep(Duration::from_millis(30)).await;
+            }
         }
 
+        let critique = if !interrupted && !output_text.contains("[NEEDS_INPUT]") {
+            critique_output(
+                window_id,
+                &tx,
+                &openai,
+                key,
+                &model,
+                &prompt,
+                &output_text,
+                &mut provenance,
+            )
+            .await
+        } else {
+            None
+        };
+
         // -- Step 5: Save to Rice — both as memory and as a coordination variable
         let _ = tx.send(AgentEvent::Progress {
             window_id,
@@ -1076,12 +2091,13 @@ pub fn spawn_agent_window_with_mcp(
         });
 
         let _ = rice
-            .commit_trace(
+            .commit_model_trace(
                 &prompt,
                 &output_text,
                 &format!("agent-window:{window_id}"),
-                vec![],
                 &format!("memini:agent-{window_id}"),
+                &openai::answering_model(&response, &model),
+                &provenance,
             )
             .await;
 
@@ -1090,8 +2106,12 @@ pub fn spawn_agent_window_with_mcp(
             let coord_var = format!("agent_result:{coordination_key}:{window_id}");
             let result_value = json!({
                 "window_id": window_id,
-                "status": "done",
+                "status": if interrupted { "interrupted" } else { "done" },
                 "output": output_text,
+                "critique": critique.map(|critique| json!({
+                    "passed": critique.passed,
+                    "notes": critique.notes,
+                })),
                 "timestamp": Local::now().format("%H:%M:%S").to_string(),
             });
             let _ = rice
@@ -1100,7 +2120,18 @@ pub fn spawn_agent_window_with_mcp(
         }
 
         // -- Step 6: Check if agent needs user input
-        if output_text.contains("[NEEDS_INPUT]") {
+        if !interrupted {
+            let _ = tx.send(AgentEvent::WindowThread {
+                window_id,
+                entries: vec![
+                    json!({"role": "user", "content": prompt.clone()}),
+                    json!({"role": "assistant", "content": output_text.clone()}),
+                ],
+            });
+        }
+        if interrupted {
+            let _ = tx.send(AgentEvent::Interrupted { window_id });
+        } else if output_text.contains("[NEEDS_INPUT]") {
             let question = output_text
                 .split("[NEEDS_INPUT]")
                 .nth(1)
@@ -1112,13 +2143,80 @@ pub fn spawn_agent_window_with_mcp(
                 question,
             });
         } else {
+            memory_scope::promote(&mut rice, window_id, &prompt, &output_text, memory).await;
+            artifacts.save_output(&output_text);
             let _ = tx.send(AgentEvent::Finished {
                 window_id,
                 message: output_text,
                 timestamp: Local::now().format("%H:%M:%S").to_string(),
             });
         }
+    };
+    let task =
+        window_control::supervise(window_id, supervisor_tx, worktrees::scope(window_id, task));
+    rt.spawn(task.instrument(tracing::info_span!("agent.run", window_id)));
+}
+
+/// Have the `[critic]` model review a window's final output, when it is
+/// enabled: the review goes to the window and into `provenance`, so it is
+/// stored with the run's trace.
+#[allow(clippy::too_many_arguments)]
+async fn critique_output(
+    window_id: usize,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    openai: &OpenAiClient,
+    key: &str,
+    agent_model: &str,
+    prompt: &str,
+    output: &str,
+    provenance: &mut Provenance,
+) -> Option<Critique> {
+    let config = critic::config();
+    if !config.enabled || output.trim().is_empty() {
+        return None;
+    }
+    let model = config.model_or(agent_model);
+    let _ = tx.send(AgentEvent::Progress {
+        window_id,
+        line: format!("Reviewing the result with {model}..."),
     });
+    match critic::review(openai, key, &model, prompt, output).await {
+        Ok(critique) => {
+            provenance.record_critique(critique.clone());
+            let _ = tx.send(AgentEvent::Critique {
+                window_id,
+                critique: critique.clone(),
+            });
+            Some(critique)
+        }
+        Err(err) => {
+            let _ = tx.send(AgentEvent::Progress {
+                window_id,
+                line: format!("Critic review skipped: {err:#}"),
+            });
+            None
+        }
+    }
+}
+
+/// Show the diff of a successful `workspace_write_file` in the window.
+fn report_file_change(
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    window_id: usize,
+    change: Option<FileChange>,
+    tool_output: &str,
+) {
+    let Some(change) = change else {
+        return;
+    };
+    let written = serde_json::from_str::<Value>(tool_output)
+        .is_ok_and(|value| value.get("status").and_then(Value::as_str) == Some("ok"));
+    if !written {
+        return;
+    }
+    for line in change.diff_lines() {
+        let _ = tx.send(AgentEvent::Progress { window_id, line });
+    }
 }
 
 // ── Async main-chat task ─────────────────────────────────────────────
@@ -1129,18 +2227,32 @@ pub struct ChatTaskParams {
     pub message: String,
     pub persona: String,
     pub agent_name: String,
+    /// Model for this turn, bypassing routing (prompt templates).
+    pub model: Option<String>,
     pub skill_context: String,
+    /// `/pin` items rendered as one system message; empty when none.
+    pub pinned_context: String,
+    /// Workspace context files (`AGENTS.md`, …); empty when none.
+    pub project_context: String,
     pub memory_limit: u64,
     pub conversation_thread: Vec<Value>,
     pub mcp_snapshots: Vec<McpServerSnapshot>,
     pub builtin_tools: Vec<Value>,
     pub next_window_id: Arc<AtomicUsize>,
+    pub sub_agents: SubAgentResults,
+    /// The persona's tool / MCP server allowlists.
+    pub restrictions: ToolRestrictions,
+    /// Images queued with `/attach`, sent alongside `message`.
+    pub images: Vec<ImageAttachment>,
+    /// Assemble the request and report it without calling the model.
+    pub dry_run: bool,
 }
 
 /// Spawn the main chat turn on a background tokio task.
 ///
 /// Sends real-time `ChatProgress` / `ChatMarkdown` / `ChatFinished` events
 /// through `tx` so the TUI keeps rendering and the user sees progress live.
+/// A `/halt` during the turn cancels it and finishes with no reply.
 pub fn spawn_chat_task(
     params: ChatTaskParams,
     tx: mpsc::UnboundedSender<AgentEvent>,
@@ -1148,19 +2260,35 @@ pub fn spawn_chat_task(
     rice_future: tokio::task::JoinHandle<RiceStore>,
     rt: tokio::runtime::Handle,
 ) {
-    rt.spawn(async move {
+    let span = tracing::info_span!("chat.turn", agent = %params.agent_name);
+    let halted_tx = tx.clone();
+    let halted_message = crate::redact::redactor().apply(&params.message).text;
+    let halted_agent = params.agent_name.clone();
+    let task = async move {
+        let _running = shutdown::track();
         let ChatTaskParams {
             key,
             message,
             persona,
             agent_name,
+            model,
             skill_context,
+            pinned_context,
+            project_context,
             memory_limit,
             conversation_thread,
             mcp_snapshots,
             builtin_tools,
             next_window_id,
+            sub_agents,
+            restrictions,
+            images,
+            dry_run,
         } = params;
+        // Secrets are masked before the message reaches memory, the thread
+        // or the model; tool results and traces are redacted on the way.
+        let redacted_before = crate::redact::total();
+        let message = crate::redact::redact(&message);
 
         let mut rice = match rice_future.await {
             Ok(r) => r,
@@ -1180,7 +2308,9 @@ pub fn spawn_chat_task(
         };
 
         // ── Step 1: Focus Rice ───────────────────────────────────────
-        let _ = rice.focus(&message).await;
+        if !dry_run {
+            let _ = rice.focus(&message).await;
+        }
 
         // ── Step 2: Recall memories ──────────────────────────────────
         let _ = tx.send(AgentEvent::ChatProgress {
@@ -1188,8 +2318,12 @@ pub fn spawn_chat_task(
             level: ChatLogLevel::Info,
         });
 
+        // Episodic memories are only injected when they relate to the
+        // message; semantic facts go into every prompt below.
+        let memory_or_state_query = message_requests_memory_or_state(&message);
         let memories = match rice.reminisce(vec![], memory_limit, &message).await {
-            Ok(traces) => traces,
+            Ok(traces) if memory_or_state_query => traces,
+            Ok(traces) => rice::relevant_memories(traces, &message),
             Err(err) => {
                 let _ = tx.send(AgentEvent::ChatProgress {
                     line: format!("Rice recall failed: {err:#}"),
@@ -1206,19 +2340,29 @@ pub fn spawn_chat_task(
             });
         }
 
-        let memory_or_state_query = message_requests_memory_or_state(&message);
-
         // ── Step 3: Connect to MCP servers ───────────────────────────
         let mut connections: Vec<mcp::McpConnection> = Vec::new();
         let mut all_tools: Vec<Value> = Vec::new();
 
-        for snap in &mcp_snapshots {
+        // A dry run reports the cached schemas rather than connecting.
+        if dry_run {
+            all_tools.extend(
+                mcp_snapshots
+                    .iter()
+                    .flat_map(|snap| snap.openai_tools.clone()),
+            );
+        }
+        for snap in mcp_snapshots.iter().filter(|_| !dry_run) {
             let _ = tx.send(AgentEvent::ChatProgress {
                 line: format!("⟳ Connecting to MCP: {}…", snap.server.display_name()),
                 level: ChatLogLevel::Info,
             });
 
-            match mcp::connect_http(&snap.server, snap.bearer.clone()).await {
+            match snap
+                .backend
+                .connect(&snap.server, snap.bearer.clone())
+                .await
+            {
                 Ok(mut conn) => {
                     match mcp::refresh_tools(&mut conn).await {
                         Ok(tools) => {
@@ -1262,34 +2406,77 @@ pub fn spawn_chat_task(
             }
         }
 
-        // Add built-in tools.
+        // Add built-in tools, then drop anything the persona may not use.
         all_tools.extend(builtin_tools);
+        let all_tools = restrictions.retain_tools(all_tools);
 
         // ── Step 4: Build LLM input ──────────────────────────────────
         let memory_context = rice::format_memories(&memories);
-        let sys = rice::system_prompt(&persona, !mcp_snapshots.is_empty());
+        let facts_context = semantic_context(&mut rice).await;
+        let sys = rice::system_prompt(&agent_name, &persona, !mcp_snapshots.is_empty());
         let mut input: Vec<Value> = Vec::new();
-        input.push(json!({"role": "system", "content": sys}));
-        input.push(json!({
+        let mut snapshot = PromptSnapshot::new(&agent_name);
+        snapshot.push(
+            &mut input,
+            "system prompt",
+            json!({"role": "system", "content": sys}),
+        );
+        if !facts_context.is_empty() {
+            snapshot.push(
+                &mut input,
+                "semantic facts",
+                json!({"role": "system", "content": facts_context}),
+            );
+        }
+        if !project_context.is_empty() {
+            snapshot.push(
+                &mut input,
+                "context files",
+                json!({"role": "system", "content": project_context}),
+            );
+        }
+        if !pinned_context.is_empty() {
+            snapshot.push(
+                &mut input,
+                "pinned",
+                json!({"role": "system", "content": pinned_context}),
+            );
+        }
+        snapshot.push(&mut input, "spawn rule", json!({
             "role": "system",
             "content": "Only claim that a sub-agent/worker was started if you actually called spawn_agent in this turn and received a success result."
         }));
         if memory_or_state_query {
-            input.push(json!({
+            snapshot.push(&mut input, "rice-first rule", json!({
                 "role": "system",
                 "content": "The user is asking about Rice memory/state. Use `rice_memories` and/or `rice_state_get` first. Only use workspace or delegation tools after Rice output is insufficient."
             }));
         }
         if !skill_context.trim().is_empty() {
-            input.push(json!({"role": "system", "content": skill_context.clone()}));
+            snapshot.push(
+                &mut input,
+                "skills",
+                json!({"role": "system", "content": skill_context.clone()}),
+            );
         }
         if !memory_context.is_empty() {
-            input.push(json!({"role": "system", "content": memory_context}));
+            snapshot.push(
+                &mut input,
+                "memory context",
+                json!({"role": "system", "content": memory_context}),
+            );
         }
         for msg in &conversation_thread {
-            input.push(msg.clone());
+            snapshot.push(&mut input, "thread history", msg.clone());
         }
-        input.push(json!({"role": "user", "content": message}));
+        snapshot.push(
+            &mut input,
+            "user message",
+            json!({
+                "role": "user",
+                "content": attachments::user_content(&message, &images),
+            }),
+        );
 
         let tools_opt: Option<&[Value]> = if all_tools.is_empty() {
             None
@@ -1297,15 +2484,43 @@ pub fn spawn_chat_task(
             Some(&all_tools)
         };
 
+        let mut provenance = Provenance::start();
+        let model = model.unwrap_or_else(|| openai.model_for(TaskKind::for_message(&message)));
+        snapshot.model = model.clone();
+        snapshot.set_tools(&all_tools);
+        if dry_run {
+            let _ = tx.send(AgentEvent::ChatMarkdown {
+                label: "Dry run — not sent".to_string(),
+                body: snapshot.markdown(),
+            });
+            let _ = tx.send(AgentEvent::ChatFinished {
+                user_message: message,
+                output_text: String::new(),
+                agent_name,
+                thread_entries: Vec::new(),
+            });
+            return;
+        }
+
         // ── Step 5: Initial LLM call ─────────────────────────────────
         let _ = tx.send(AgentEvent::ChatProgress {
             line: "⟳ Thinking…".to_string(),
             level: ChatLogLevel::Info,
         });
 
-        let mut response = match openai.response(&key, &input, tools_opt).await {
+        let _ = tx.send(AgentEvent::ChatPromptSent { snapshot });
+        let mut timeline = TurnTimeline::start(&message);
+        let call_started = Instant::now();
+        let waiting = progress::start(format!("Waiting for {model}"));
+        let response = openai.response(&key, &model, &input, tools_opt).await;
+        drop(waiting);
+        let mut response = match response {
             Ok(r) => r,
             Err(err) => {
+                timeline.model_failed(call_started, &model, &err);
+                let _ = tx.send(AgentEvent::ChatTimeline {
+                    timeline: timeline.finish(),
+                });
                 let _ = tx.send(AgentEvent::ChatProgress {
                     line: format!("OpenAI request failed: {err:#}"),
                     level: ChatLogLevel::Error,
@@ -1319,6 +2534,9 @@ pub fn spawn_chat_task(
                 return;
             }
         };
+        report_fallback(&tx, None, &response);
+        provenance.record_response(&response);
+        timeline.model_call(call_started, &model, &response);
 
         let mut output_items = openai::extract_output_items(&response);
         if !output_items.is_empty() {
@@ -1326,28 +2544,45 @@ pub fn spawn_chat_task(
         }
         let mut output_text = openai::extract_output_text(&output_items);
         let mut tool_calls = openai::extract_tool_calls(&output_items);
-        let mut tool_loops = 0usize;
+        let mut loop_guard = LoopGuard::new(None);
+        let mut failed_calls = FailedCalls::default();
         let mut rice_first_satisfied = !memory_or_state_query;
         let mut spawned_windows: Vec<(usize, String)> = Vec::new();
 
+        let artifacts = ArtifactRun::start("chat");
         // ── Step 6: Tool-call loop ───────────────────────────────────
         while !tool_calls.is_empty() {
-            if openai::tool_loop_limit_reached(tool_loops) {
+            if !loop_guard.next_round() {
                 let _ = tx.send(AgentEvent::ChatProgress {
-                    line: "Tool loop limit reached.".to_string(),
+                    line: format!(
+                        "Tool loop limit reached ({} rounds).",
+                        loop_guard.max_rounds()
+                    ),
                     level: ChatLogLevel::Warn,
                 });
                 break;
             }
-            tool_loops += 1;
+            if shutdown::requested() {
+                break;
+            }
+            let mut slot = Some(chat_slot(&tx).await);
 
             for call in &tool_calls {
+                provenance.record_tool(&call.name);
                 let _ = tx.send(AgentEvent::ChatProgress {
                     line: format!("⚙ Calling tool: {}", call.name),
                     level: ChatLogLevel::Info,
                 });
-
-                let tool_output = if memory_or_state_query
+                let tool_started = Instant::now();
+                let running = progress::start(format!("Running {}", call.name));
+
+                let tool_output = if let Some(repeated) = loop_guard.check(call) {
+                    repeated
+                } else if let Some(blocked) = policy::enforce(call, "chat", &tx).await {
+                    blocked
+                } else if !restrictions.allows_tool(&call.name) {
+                    ToolRestrictions::denied_output(&call.name)
+                } else if memory_or_state_query
                     && !rice_first_satisfied
                     && is_workspace_or_delegation_tool(&call.name)
                 {
@@ -1360,6 +2595,7 @@ pub fn spawn_chat_task(
                         &tx,
                         &persona,
                         &skill_context,
+                        &restrictions,
                     );
                     if let Some((window_id, label)) = spawn_result.spawned_window {
                         spawned_windows.push((window_id, label));
@@ -1367,6 +2603,11 @@ pub fn spawn_chat_task(
                     spawn_result.tool_output
                 } else if call.name == "collect_results" {
                     handle_collect_results_bg(call, &mut rice).await
+                } else if call.name == "wait_for_agent" {
+                    // Free the slot so the awaited agent is not queued
+                    // behind this turn.
+                    slot = None;
+                    handle_wait_for_agent_bg(call, &sub_agents, &tx).await
                 } else if call.name == "rice_memories" {
                     handle_rice_memories_bg(call, &mut rice, memory_limit).await
                 } else if call.name == "rice_state_get" {
@@ -1377,16 +2618,26 @@ pub fn spawn_chat_task(
                         mcp::split_namespaced_tool_name(&call.name)
                     {
                         if let Some(conn) = connections.iter().find(|c| c.server.id == server_id) {
-                            match mcp::call_tool(conn, tool_name, call.arguments.clone()).await {
+                            match tool_failures::call_mcp_tool(
+                                conn,
+                                tool_name,
+                                call.arguments.clone(),
+                            )
+                            .await
+                            {
                                 Ok(value) => {
                                     let _ = tx.send(AgentEvent::ChatProgress {
                                         line: format!("✓ Tool {} returned.", call.name),
                                         level: ChatLogLevel::Info,
                                     });
-                                    serde_json::to_string(&value)
-                                        .unwrap_or_else(|_| "{}".to_string())
+                                    mcp::content::render_result(&value, |name, bytes| {
+                                        artifacts.save_bytes(
+                                            &format!("mcp/{}-{name}", call.call_id),
+                                            bytes,
+                                        )
+                                    })
                                 }
-                                Err(err) => format!(r#"{{"error":"{err}"}}"#),
+                                Err(envelope) => envelope,
                             }
                         } else {
                             format!(r#"{{"error":"No MCP connection for server '{server_id}'"}}"#)
@@ -1395,10 +2646,17 @@ pub fn spawn_chat_task(
                         format!(r#"{{"error":"Unknown tool '{}'"}}"#, call.name)
                     }
                 };
+                drop(running);
                 if is_rice_memory_state_tool(&call.name) {
                     rice_first_satisfied = true;
                 }
 
+                failed_calls.record(&call.name, &tool_output);
+                let raw_output = tool_output.clone();
+                let tool_output =
+                    tool_results::fit(&openai, &key, call, tool_output, &artifacts).await;
+                timeline.tool_call(call, tool_started, &raw_output, &tool_output);
+
                 input.push(json!({
                     "type": "function_call_output",
                     "call_id": call.call_id,
@@ -1406,14 +2664,23 @@ pub fn spawn_chat_task(
                 }));
             }
 
+            let _slot = match slot {
+                Some(slot) => slot,
+                None => chat_slot(&tx).await,
+            };
             let _ = tx.send(AgentEvent::ChatProgress {
                 line: "⟳ Thinking…".to_string(),
                 level: ChatLogLevel::Info,
             });
 
-            response = match openai.response(&key, &input, tools_opt).await {
+            let call_started = Instant::now();
+            let waiting = progress::start(format!("Waiting for {model}"));
+            let next = openai.response(&key, &model, &input, tools_opt).await;
+            drop(waiting);
+            response = match next {
                 Ok(r) => r,
                 Err(err) => {
+                    timeline.model_failed(call_started, &model, &err);
                     let _ = tx.send(AgentEvent::ChatProgress {
                         line: format!("OpenAI request failed: {err:#}"),
                         level: ChatLogLevel::Error,
@@ -1421,6 +2688,9 @@ pub fn spawn_chat_task(
                     break;
                 }
             };
+            report_fallback(&tx, None, &response);
+            provenance.record_response(&response);
+            timeline.model_call(call_started, &model, &response);
             output_items = openai::extract_output_items(&response);
             if !output_items.is_empty() {
                 input.extend(output_items.clone());
@@ -1429,6 +2699,13 @@ pub fn spawn_chat_task(
             tool_calls = openai::extract_tool_calls(&output_items);
         }
 
+        if let Some(line) = failed_calls.summary() {
+            let _ = tx.send(AgentEvent::ChatProgress {
+                line,
+                level: ChatLogLevel::Warn,
+            });
+        }
+
         if output_claims_agent_spawn(&output_text) && spawned_windows.is_empty() {
             let _ = tx.send(AgentEvent::ChatProgress {
                 line: "⚠ Assistant mentioned spawning a sub-agent, but no spawn_agent tool call occurred."
@@ -1441,6 +2718,9 @@ pub fn spawn_chat_task(
         }
 
         // ── Step 7: Send result ──────────────────────────────────────
+        let _ = tx.send(AgentEvent::ChatTimeline {
+            timeline: timeline.finish(),
+        });
         if output_text.is_empty() {
             let _ = tx.send(AgentEvent::ChatProgress {
                 line: "No response received.".to_string(),
@@ -1455,23 +2735,60 @@ pub fn spawn_chat_task(
 
         // ── Step 8: Commit to Rice ───────────────────────────────────
         let mut thread_entries = Vec::new();
-        thread_entries.push(json!({"role": "user", "content": message}));
+        // The thread keeps chips, not image data, so later turns stay small.
+        let thread_message = std::iter::once(message.clone())
+            .chain(images.iter().map(ImageAttachment::chip))
+            .collect::<Vec<_>>()
+            .join(" ");
+        thread_entries.push(json!({"role": "user", "content": thread_message}));
         if !output_text.is_empty() {
             thread_entries.push(json!({"role": "assistant", "content": output_text.clone()}));
         }
 
         let aid = rice::agent_id_for(&agent_name);
         let _ = rice
-            .commit_trace(&message, &output_text, "chat", vec![], &aid)
+            .commit_model_trace(
+                &message,
+                &output_text,
+                "chat",
+                &aid,
+                &openai::answering_model(&response, &model),
+                &provenance,
+            )
             .await;
 
+        let redacted = crate::redact::total().saturating_sub(redacted_before);
+        if redacted > 0 {
+            let _ = tx.send(AgentEvent::ChatProgress {
+                line: format!("🔒 Redacted {redacted} secret(s) this turn."),
+                level: ChatLogLevel::Info,
+            });
+        }
         let _ = tx.send(AgentEvent::ChatFinished {
             user_message: message,
             output_text,
             agent_name,
             thread_entries,
         });
-    });
+    };
+    let task = async move {
+        tokio::select! {
+            _ = task => {}
+            _ = halt::engaged() => {
+                let _ = halted_tx.send(AgentEvent::ChatProgress {
+                    line: "⏹ Turn cancelled by /halt.".to_string(),
+                    level: ChatLogLevel::Warn,
+                });
+                let _ = halted_tx.send(AgentEvent::ChatFinished {
+                    user_message: halted_message,
+                    output_text: String::new(),
+                    agent_name: halted_agent,
+                    thread_entries: Vec::new(),
+                });
+            }
+        }
+    };
+    rt.spawn(task.instrument(span));
 }
 
 fn message_requests_memory_or_state(message: &str) -> bool {
@@ -1535,6 +2852,7 @@ fn handle_spawn_agent_bg(
     tx: &mpsc::UnboundedSender<AgentEvent>,
     persona: &str,
     skill_context: &str,
+    restrictions: &ToolRestrictions,
 ) -> SpawnAgentResult {
     let label = call
         .arguments
@@ -1592,6 +2910,7 @@ fn handle_spawn_agent_bg(
             coordination_key: coordination_key.clone(),
             persona: persona.to_string(),
             skill_context: skill_context.to_string(),
+            restrictions: restrictions.clone(),
         })
         .is_err()
     {
@@ -1771,13 +3090,88 @@ async fn handle_collect_results_bg(call: &openai::ToolCall, rice: &mut RiceStore
         .unwrap_or_else(|_| r#"{"error":"serialize failed"}"#.to_string())
 }
 
+/// Default and maximum wait for `wait_for_agent`, in seconds.
+const WAIT_FOR_AGENT_DEFAULT_SECS: u64 = 120;
+const WAIT_FOR_AGENT_MAX_SECS: u64 = 600;
+
+/// Handle `wait_for_agent`: block until a spawned window finishes.
+async fn handle_wait_for_agent_bg(
+    call: &openai::ToolCall,
+    sub_agents: &SubAgentResults,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> String {
+    let Some(window_id) = call.arguments.get("window_id").and_then(|v| v.as_u64()) else {
+        return r#"{"error":"window_id is required"}"#.to_string();
+    };
+    let timeout_secs = call
+        .arguments
+        .get("timeout_secs")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(WAIT_FOR_AGENT_DEFAULT_SECS)
+        .min(WAIT_FOR_AGENT_MAX_SECS);
+
+    let _ = tx.send(AgentEvent::ChatProgress {
+        line: format!("⏳ Waiting for agent #{window_id} (up to {timeout_secs}s)…"),
+        level: ChatLogLevel::Info,
+    });
+    let result = sub_agents
+        .wait(window_id as usize, Duration::from_secs(timeout_secs))
+        .await;
+    serde_json::to_string(&result).unwrap_or_else(|_| r#"{"error":"serialize failed"}"#.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{
-        DaemonTaskDef, is_rice_memory_state_tool, is_workspace_or_delegation_tool,
+        AgentWindow, AgentWindowStatus, DaemonTaskDef, Schedule, append_bounded_thread,
+        is_rice_memory_state_tool, is_workspace_or_delegation_tool,
         message_requests_memory_or_state, output_claims_agent_spawn, trigger_matches,
     };
 
+    #[test]
+    fn scrolled_up_windows_hold_new_output_below() {
+        let mut window = AgentWindow {
+            output_lines: vec!["one".to_string(), "two".to_string()],
+            ..AgentWindow::new(
+                1,
+                "reader".to_string(),
+                String::new(),
+                AgentWindowStatus::Thinking,
+            )
+        };
+        window.output_lines.push("three".to_string());
+        assert_eq!(window.visible_output().len(), 3);
+
+        window.scroll_up(1);
+        window.output_lines.push("four".to_string());
+        assert_eq!(window.visible_output(), ["one", "two", "three"]);
+        assert_eq!(window.unread_lines(), 1);
+
+        window.scroll_down(5);
+        assert_eq!(window.scroll, 0);
+        assert_eq!(window.unread_lines(), 0);
+        assert_eq!(window.visible_output().len(), 4);
+    }
+
+    #[test]
+    fn window_thread_keeps_the_task_when_trimmed() {
+        let turn = |n: usize| {
+            vec![
+                serde_json::json!({"role": "user", "content": format!("q{n}")}),
+                serde_json::json!({"role": "assistant", "content": format!("a{n}")}),
+            ]
+        };
+        let mut thread = Vec::new();
+        for n in 0..5 {
+            append_bounded_thread(&mut thread, turn(n), 6);
+        }
+        let contents: Vec<&str> = thread
+            .iter()
+            .filter_map(|entry| entry["content"].as_str())
+            .collect();
+        assert_eq!(contents, ["q0", "a0", "q3", "a3", "q4", "a4"]);
+    }
+
     #[test]
     fn detects_memory_queries() {
         assert!(message_requests_memory_or_state(
@@ -1833,8 +3227,7 @@ mod tests {
             interval_secs: 60,
             trigger_events: vec!["VariableUpdate".to_string()],
             trigger_variables: vec!["deploy.request".to_string(), "ci.*".to_string()],
-            tools: vec![],
-            paused: false,
+            ..Default::default()
         };
         assert!(trigger_matches(
             &def,
@@ -1863,4 +3256,21 @@ mod tests {
         ));
         assert!(!output_claims_agent_spawn("I did not spawn any worker."));
     }
+
+    #[test]
+    fn pausing_freezes_the_time_left() {
+        use std::time::Duration;
+
+        let schedule = Schedule::new(Duration::from_secs(600), false);
+        let held = schedule.pause().expect("was running");
+        assert!(held > Duration::from_secs(590));
+        assert!(schedule.is_paused());
+        assert!(schedule.deadline().is_none());
+        assert!(schedule.pause().is_none());
+        assert_eq!(schedule.remaining(), held);
+
+        assert_eq!(schedule.resume(), Some(held));
+        assert!(schedule.deadline().is_some());
+        assert!(schedule.resume().is_none());
+    }
 }
@@ -0,0 +1,375 @@
+//! Daily and weekly digests of what happened, built from memory.
+//!
+//! A digest gathers the traces committed in its period (chat turns, daemon
+//! runs, agent windows, MCP commits) and the tools and tokens recorded in
+//! their metadata, asks the model for a short Markdown summary, and appends
+//! the activity counts.  The built-in `digest` daemon writes today's digest
+//! once a day; `/digest today|week` writes one on demand.  Both save it as
+//! an artifact under `$MEMINI_HOME/artifacts/digest/` and, unless turned off
+//! in `memini.toml`, commit it back as one summary trace:
+//!
+//! ```toml
+//! [digest]
+//! remember = true  # false: digests are only saved as artifacts
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::{Result, bail};
+use chrono::{DateTime, Duration, FixedOffset, Local, TimeZone};
+use rice::rice_state::proto::Trace;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::constants::{DIGEST_ENTRY_CHARS, MAX_DIGEST_TRACES};
+use crate::importance::TraceMeta;
+use crate::openai::{self, OpenAiClient};
+use crate::provenance::{Provenance, TokenUsage};
+use crate::rice::RiceStore;
+
+use super::artifacts::ArtifactRun;
+use super::daemon::DaemonOutput;
+
+/// Name of the built-in daemon task that writes the daily digest.
+pub const DIGEST_TASK: &str = "digest";
+
+/// The `[digest]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct DigestConfig {
+    #[serde(default = "enabled")]
+    pub remember: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for DigestConfig {
+    fn default() -> Self {
+        DigestConfig { remember: true }
+    }
+}
+
+/// The `[digest]` section of `memini.toml`.
+pub(crate) fn config() -> &'static DigestConfig {
+    &crate::config::get().digest
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Period {
+    Today,
+    Week,
+}
+
+impl Period {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "" | "today" | "day" | "daily" => Some(Period::Today),
+            "week" | "weekly" => Some(Period::Week),
+            _ => None,
+        }
+    }
+
+    /// Local midnight at the start of the period (the last seven days for
+    /// a week, today included).
+    fn start(self, now: DateTime<Local>) -> DateTime<FixedOffset> {
+        let days = match self {
+            Period::Today => 0,
+            Period::Week => 6,
+        };
+        let midnight = (now.date_naive() - Duration::days(days))
+            .and_hms_opt(0, 0, 0)
+            .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+            .unwrap_or(now);
+        midnight.fixed_offset()
+    }
+
+    fn title(self, now: DateTime<Local>) -> String {
+        match self {
+            Period::Today => format!("Digest — {}", now.format("%A, %B %e, %Y")),
+            Period::Week => format!(
+                "Weekly digest — {} to {}",
+                (now - Duration::days(6)).format("%b %e"),
+                now.format("%b %e, %Y")
+            ),
+        }
+    }
+
+    fn artifact_name(self) -> &'static str {
+        match self {
+            Period::Today => "digest-today.md",
+            Period::Week => "digest-week.md",
+        }
+    }
+}
+
+/// One trace inside the period.
+struct Entry {
+    at: DateTime<FixedOffset>,
+    source: String,
+    input: String,
+    outcome: String,
+}
+
+/// What the period's traces add up to.
+#[derive(Default)]
+struct Activity {
+    entries: Vec<Entry>,
+    /// Traces per source kind (`chat`, `daemon`, `agent window`, …).
+    sources: BTreeMap<String, usize>,
+    /// Calls per tool, counted once per trace that used it.
+    tools: BTreeMap<String, usize>,
+    tokens: TokenUsage,
+}
+
+/// `daemon:repo-watch` → `daemon`, `agent-window:3` → `agent window`.
+fn source_kind(action: &str) -> &str {
+    match action.split_once(':') {
+        Some(("agent-window", _)) => "agent window",
+        Some((kind, _)) => kind,
+        None if action.is_empty() => "other",
+        None => action,
+    }
+}
+
+fn gather(traces: &[Trace], since: DateTime<FixedOffset>) -> Activity {
+    let mut activity = Activity::default();
+    for trace in traces {
+        let Some(meta) = TraceMeta::parse(&trace.reasoning) else {
+            continue;
+        };
+        let Some(at) = meta.committed_at().filter(|at| *at >= since) else {
+            continue;
+        };
+        // Earlier digests would otherwise be summarized again.
+        if trace.action == format!("daemon:{DIGEST_TASK}") || trace.action == "digest" {
+            continue;
+        }
+        *activity
+            .sources
+            .entry(source_kind(&trace.action).to_string())
+            .or_default() += 1;
+        for tool in &meta.tools {
+            *activity.tools.entry(tool.clone()).or_default() += 1;
+        }
+        if let Some(tokens) = meta.tokens {
+            activity.tokens.input += tokens.input;
+            activity.tokens.output += tokens.output;
+        }
+        activity.entries.push(Entry {
+            at,
+            source: trace.action.clone(),
+            input: clip(&trace.input),
+            outcome: clip(&trace.outcome),
+        });
+    }
+    activity.entries.sort_by_key(|entry| entry.at);
+    activity
+}
+
+fn clip(text: &str) -> String {
+    let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if flat.chars().count() <= DIGEST_ENTRY_CHARS {
+        return flat;
+    }
+    let cut: String = flat.chars().take(DIGEST_ENTRY_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+impl Activity {
+    /// The chronological log the model summarizes.
+    fn log(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| {
+                format!(
+                    "[{} {}] {}\n→ {}",
+                    entry.at.format("%a %H:%M"),
+                    entry.source,
+                    entry.input,
+                    entry.outcome
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+
+    /// Counts appended under `## Activity`.
+    fn stats(&self) -> String {
+        let sources = self
+            .sources
+            .iter()
+            .map(|(kind, count)| format!("{count} {kind}"))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut lines = vec![format!("- {} trace(s): {sources}", self.entries.len())];
+        if !self.tools.is_empty() {
+            let mut tools: Vec<(&String, &usize)> = self.tools.iter().collect();
+            tools.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+            let top = tools
+                .iter()
+                .take(8)
+                .map(|(tool, count)| format!("`{tool}` ×{count}"))
+                .collect::<Vec<_>>()
+                .join(", ");
+            lines.push(format!("- tools: {top}"));
+        }
+        if self.tokens != TokenUsage::default() {
+            lines.push(format!(
+                "- tokens: {} input + {} output",
+                self.tokens.input, self.tokens.output
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// A finished digest.
+pub struct Digest {
+    pub title: String,
+    pub markdown: String,
+    /// Model that wrote the summary; `None` when there was nothing to
+    /// summarize.
+    pub model: Option<String>,
+}
+
+/// Build the digest for `period` from the traces in `rice`.
+pub(crate) async fn generate(
+    period: Period,
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    rice: &mut RiceStore,
+) -> Result<Digest> {
+    let now = Local::now();
+    let title = period.title(now);
+    let traces = rice.reminisce(vec![], MAX_DIGEST_TRACES, "").await?;
+    let activity = gather(&traces, period.start(now));
+    if activity.entries.is_empty() {
+        return Ok(Digest {
+            markdown: format!("# {title}\n\nNo activity recorded in this period.\n"),
+            title,
+            model: None,
+        });
+    }
+
+    let input = vec![
+        json!({"role": "system", "content": crate::prompts::daemon_digest_persona()}),
+        json!({
+            "role": "user",
+            "content": format!(
+                "{}\n\nActivity log ({}):\n\n{}",
+                crate::prompts::daemon_digest_prompt(),
+                title,
+                activity.log()
+            ),
+        }),
+    ];
+    let response = openai.response(key, model, &input, None).await?;
+    let summary = openai::extract_output_text(&openai::extract_output_items(&response));
+    if summary.trim().is_empty() {
+        bail!("the model returned an empty digest");
+    }
+    Ok(Digest {
+        markdown: format!(
+            "# {title}\n\n{}\n\n## Activity\n\n{}\n",
+            summary.trim(),
+            activity.stats()
+        ),
+        title,
+        model: Some(openai::answering_model(&response, model)),
+    })
+}
+
+/// Save `digest` as an artifact and, with `[digest] remember`, commit it
+/// as a summary trace.  Returns where it was saved.
+pub(crate) async fn publish(
+    digest: &Digest,
+    period: Period,
+    rice: &mut RiceStore,
+) -> Result<std::path::PathBuf> {
+    let path = ArtifactRun::start(DIGEST_TASK).save(period.artifact_name(), &digest.markdown)?;
+    if config().remember && digest.model.is_some() {
+        rice.commit_trace(
+            &digest.title,
+            &digest.markdown,
+            "digest",
+            Vec::new(),
+            "memini:digest",
+        )
+        .await?;
+    }
+    Ok(path)
+}
+
+/// A run of the built-in `digest` daemon: today's digest as its output.
+/// The daemon loop saves it as `output.md` and commits it (see
+/// [`super::daemon::record_daemon_run`]).
+pub(crate) async fn run_daemon(
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    rice: &mut RiceStore,
+) -> DaemonOutput {
+    let provenance = Provenance::start();
+    match generate(Period::Today, openai, key, model, rice).await {
+        Ok(digest) => DaemonOutput {
+            text: digest.markdown,
+            model: digest.model.unwrap_or_else(|| model.to_string()),
+            provenance,
+        },
+        Err(err) => DaemonOutput {
+            text: format!("Error: {err:#}"),
+            model: model.to_string(),
+            provenance,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(action: &str, input: &str, meta: TraceMeta) -> Trace {
+        Trace {
+            input: input.to_string(),
+            reasoning: meta.encode(),
+            action: action.to_string(),
+            outcome: "done".to_string(),
+            ..Trace::default()
+        }
+    }
+
+    #[test]
+    fn gathers_traces_in_the_period_with_tools_and_tokens() {
+        let now = Local::now();
+        let at = |hours_ago: i64| TraceMeta {
+            importance: 0.5,
+            committed_at: (now - Duration::hours(hours_ago)).to_rfc3339(),
+            ..TraceMeta::default()
+        };
+        let mut daemon_meta = at(0);
+        daemon_meta.tools = vec!["workspace_run_command".to_string()];
+        daemon_meta.tokens = Some(TokenUsage {
+            input: 900,
+            output: 100,
+        });
+        let traces = vec![
+            trace("chat", "Plan the release", at(0)),
+            trace("daemon:repo-watch", "Check CI", daemon_meta),
+            trace("daemon:digest", "Earlier digest", at(0)),
+            trace("agent-window:3", "Research crates", at(24 * 10)),
+        ];
+
+        let today = gather(&traces, Period::Today.start(now));
+        assert_eq!(today.entries.len(), 2);
+        assert_eq!(today.sources.get("daemon"), Some(&1));
+        assert_eq!(today.tools.get("workspace_run_command"), Some(&1));
+        let stats = today.stats();
+        assert!(stats.contains("2 trace(s): 1 chat, 1 daemon"));
+        assert!(stats.contains("900 input + 100 output"));
+
+        assert_eq!(Period::parse("weekly"), Some(Period::Week));
+        assert_eq!(Period::parse("month"), None);
+    }
+}
@@ -0,0 +1,122 @@
+//! Workspace file watcher backing recipe `trigger_paths`.
+//!
+//! A single recursive [`notify`] watcher observes the workspace root and
+//! forwards changed paths (relative to the root) to the TUI as
+//! [`AgentEvent::FilesChanged`].  Debouncing and glob matching happen on the
+//! scheduler side so that a burst of saves turns into one daemon run.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSetBuilder};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+
+use super::daemon::AgentEvent;
+
+/// Directory names whose contents never trigger recipes.
+const IGNORED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+
+/// A live recursive watcher on the workspace root.
+pub struct WorkspaceWatcher {
+    pub root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+/// Start watching `root` recursively, sending batches of changed paths.
+pub fn spawn_workspace_watcher(
+    root: PathBuf,
+    tx: mpsc::UnboundedSender<AgentEvent>,
+) -> Result<WorkspaceWatcher> {
+    let event_root = root.clone();
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+            return;
+        }
+        let paths: Vec<String> = event
+            .paths
+            .iter()
+            .filter_map(|path| relative_path(&event_root, path))
+            .collect();
+        if !paths.is_empty() {
+            let _ = tx.send(AgentEvent::FilesChanged { paths });
+        }
+    })
+    .context("create file watcher")?;
+    watcher
+        .watch(&root, RecursiveMode::Recursive)
+        .with_context(|| format!("watch {}", root.display()))?;
+    Ok(WorkspaceWatcher {
+        root,
+        _watcher: watcher,
+    })
+}
+
+/// Return the `/`-separated path of `path` under `root`, skipping ignored dirs.
+fn relative_path(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let parts: Vec<String> = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy().to_string())
+        .collect();
+    if parts.is_empty()
+        || parts
+            .iter()
+            .any(|part| IGNORED_DIRS.contains(&part.as_str()))
+    {
+        return None;
+    }
+    Some(parts.join("/"))
+}
+
+/// Return the subset of `paths` matching any of the glob `patterns`.
+pub fn matching_paths(patterns: &[String], paths: &[String]) -> Vec<String> {
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        if let Ok(glob) = Glob::new(pattern.trim()) {
+            builder.add(glob);
+        }
+    }
+    let Ok(set) = builder.build() else {
+        return Vec::new();
+    };
+    paths
+        .iter()
+        .filter(|path| set.is_match(path.as_str()))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_paths_applies_globs() {
+        let patterns = vec!["src/**/*.rs".to_string(), "Cargo.toml".to_string()];
+        let paths = vec![
+            "src/app/mod.rs".to_string(),
+            "src/main.rs".to_string(),
+            "docs/rice.md".to_string(),
+            "Cargo.toml".to_string(),
+        ];
+        assert_eq!(
+            matching_paths(&patterns, &paths),
+            vec!["src/app/mod.rs", "src/main.rs", "Cargo.toml"]
+        );
+    }
+
+    #[test]
+    fn relative_path_skips_ignored_dirs() {
+        let root = Path::new("/ws");
+        assert_eq!(
+            relative_path(root, Path::new("/ws/src/lib.rs")).as_deref(),
+            Some("src/lib.rs")
+        );
+        assert_eq!(relative_path(root, Path::new("/ws/target/debug/x")), None);
+        assert_eq!(relative_path(root, Path::new("/elsewhere/a.rs")), None);
+    }
+}
@@ -0,0 +1,110 @@
+//! Global kill switch for autonomous work (`/halt`, Ctrl+X).
+//!
+//! Halting raises a process-wide flag and wakes every waiter.  Agent
+//! window runs and daemon runs race their work against [`wait`]; the chat
+//! tool loop races against [`engaged`].  Losers are dropped at their next
+//! await point.  While the flag is up, daemon loops keep their schedule
+//! but skip each run and new agent windows are cancelled as they start,
+//! until `/resume-auto` calls [`release`].  Chat typed by the user still
+//! runs.
+
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tokio::sync::Notify;
+
+#[derive(Default)]
+struct Switch {
+    halted: AtomicBool,
+    notify: Notify,
+}
+
+impl Switch {
+    fn engage(&self) -> bool {
+        let was_halted = self.halted.swap(true, Ordering::SeqCst);
+        self.notify.notify_waiters();
+        !was_halted
+    }
+
+    fn release(&self) -> bool {
+        self.halted.swap(false, Ordering::SeqCst)
+    }
+
+    fn active(&self) -> bool {
+        self.halted.load(Ordering::SeqCst)
+    }
+
+    async fn wait(&self) {
+        let notified = self.notify.notified();
+        if self.active() {
+            return;
+        }
+        notified.await;
+    }
+}
+
+fn switch() -> &'static Switch {
+    static SWITCH: OnceLock<Switch> = OnceLock::new();
+    SWITCH.get_or_init(Switch::default)
+}
+
+/// Cancel all autonomous work and block new work.  Returns `false` if
+/// already halted.
+pub fn engage() -> bool {
+    switch().engage()
+}
+
+/// Allow autonomous work again.  Returns `false` if not halted.
+pub fn release() -> bool {
+    switch().release()
+}
+
+/// Whether autonomous work is halted.
+pub fn active() -> bool {
+    switch().active()
+}
+
+/// Resolves immediately while halted, else at the next [`engage`].
+pub async fn wait() {
+    switch().wait().await
+}
+
+/// Resolves at the next [`engage`] only — for work the user started by
+/// hand, which may run while halted but stops on a fresh `/halt`.
+pub async fn engaged() {
+    switch().notify.notified().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn halting_wakes_waiters_until_released() {
+        let switch = Arc::new(Switch::default());
+        let waiter = tokio::spawn({
+            let switch = switch.clone();
+            async move { switch.wait().await }
+        });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(switch.engage());
+        assert!(!switch.engage());
+        tokio::time::timeout(Duration::from_secs(1), waiter)
+            .await
+            .expect("waiter woke")
+            .expect("waiter task");
+        // Already halted: resolves at once.
+        tokio::time::timeout(Duration::from_millis(50), switch.wait())
+            .await
+            .expect("immediate while halted");
+        assert!(switch.release());
+        assert!(!switch.active());
+        assert!(
+            tokio::time::timeout(Duration::from_millis(20), switch.wait())
+                .await
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Test harness: an [`App`] on the mock backends, driven through the same
+//! input path as the TUI.
+//!
+//! ```ignore
+//! let mut harness = Harness::new();
+//! harness.mocks.llm.reply_text("Hi!");
+//! harness.submit("hello");
+//! assert!(harness.logged("Hi!"));
+//! ```
+
+use std::time::{Duration, Instant};
+
+use crate::backend::mock::Mocks;
+use crate::mcp::config::McpServer;
+
+use super::App;
+use super::logging::LogContent;
+
+/// How long [`Harness::settle`] waits for a chat turn.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub(crate) struct Harness {
+    pub app: App,
+    pub mocks: Mocks,
+}
+
+impl Harness {
+    /// A fresh app with an OpenAI key set and nothing restored.
+    pub fn new() -> Self {
+        let mocks = Mocks::new();
+        let mut app = App::with_backends(mocks.backends()).expect("build app");
+        app.openai_key = Some("sk-test".to_string());
+        Harness { app, mocks }
+    }
+
+    /// Add an MCP server to the config (the mock backend accepts any).
+    pub fn add_mcp_server(&mut self, id: &str) {
+        let server: McpServer =
+            serde_json::from_value(serde_json::json!({"id": id, "url": "http://mcp.invalid"}))
+                .expect("server entry");
+        self.app.mcp_config.servers.push(server);
+    }
+
+    /// Type `line` and press Enter, then wait for any chat turn to finish.
+    pub fn submit(&mut self, line: &str) {
+        self.app.input = line.to_string();
+        self.app.submit_input().expect("submit input");
+        self.settle();
+    }
+
+    /// Route background events until the chat is idle.
+    pub fn settle(&mut self) {
+        let deadline = Instant::now() + SETTLE_TIMEOUT;
+        loop {
+            self.app.drain_daemon_events();
+            if !self.app.chat_busy {
+                return;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "chat turn did not finish; log:\n{}",
+                self.logs().join("\n")
+            );
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+
+    /// Activity-log text, oldest first.
+    pub fn logs(&self) -> Vec<String> {
+        self.app
+            .logs
+            .iter()
+            .map(|line| match &line.content {
+                LogContent::Plain(text) => text.clone(),
+                LogContent::Markdown { label, body } => format!("{label}: {body}"),
+            })
+            .collect()
+    }
+
+    pub fn logged(&self, needle: &str) -> bool {
+        self.logs().iter().any(|line| line.contains(needle))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use rmcp::model::Tool as McpTool;
+    use serde_json::{Value, json};
+
+    use super::*;
+    use crate::constants::CONVERSATION_THREAD_VAR;
+
+    #[test]
+    fn chat_turn_round_trips_through_the_backends() {
+        let mut harness = Harness::new();
+        harness.mocks.llm.reply_text("Noted — Friday it is.");
+
+        harness.submit("The deploy is on Friday.");
+
+        assert!(harness.logged("Noted — Friday it is."));
+        let sent = harness.mocks.llm.requests("responses");
+        assert_eq!(sent.len(), 1);
+        assert!(
+            sent[0]["input"]
+                .to_string()
+                .contains("The deploy is on Friday.")
+        );
+        assert!(
+            harness
+                .mocks
+                .memory
+                .traces()
+                .iter()
+                .any(|trace| trace.input == "The deploy is on Friday.")
+        );
+        let thread = harness.mocks.memory.variable(CONVERSATION_THREAD_VAR);
+        assert!(matches!(thread, Some(Value::Array(entries)) if !entries.is_empty()));
+    }
+
+    #[test]
+    fn mcp_commands_go_through_the_mcp_backend() {
+        let mut harness = Harness::new();
+        harness.add_mcp_server("mockmcp");
+
+        harness.submit("/mcp connect mockmcp");
+        assert!(harness.logged("Connected to mockmcp."));
+        assert!(harness.logged("- mockmcp__echo"));
+
+        harness.submit(r#"/mcp call mockmcp__echo {"text": "hi"}"#);
+        assert_eq!(
+            harness.mocks.mcp.calls(),
+            vec![(
+                "mockmcp".to_string(),
+                "echo".to_string(),
+                json!({"text": "hi"})
+            )]
+        );
+        assert!(harness.logged("Tool mockmcp__echo result:"));
+    }
+
+    #[test]
+    fn tool_list_changes_refresh_the_cache() {
+        let mut harness = Harness::new();
+        harness.add_mcp_server("mockmcp");
+        harness.submit("/mcp connect mockmcp");
+        let tool = McpTool::new("search", "Search.", Arc::new(serde_json::Map::new()));
+        crate::mcp::tool_changes::stash("mockmcp", vec![tool]);
+
+        harness.app.tick();
+
+        assert!(harness.logged("mockmcp tools updated: added search; removed echo"));
+        let cache = &harness.app.mcp_connections["mockmcp"].tool_cache;
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn chat_tool_calls_reach_mcp_servers() {
+        let mut harness = Harness::new();
+        harness.add_mcp_server("mockmcp");
+        harness.submit("/mcp connect mockmcp");
+        harness
+            .mocks
+            .llm
+            .reply_tool_call("mockmcp__echo", json!({"text": "ping"}));
+        harness.mocks.llm.reply_text("The tool answered.");
+
+        harness.submit("Ping the echo tool.");
+
+        assert_eq!(
+            harness.mocks.mcp.calls(),
+            vec![(
+                "mockmcp".to_string(),
+                "echo".to_string(),
+                json!({"text": "ping"})
+            )]
+        );
+        assert!(harness.logged("The tool answered."));
+        let sent = harness.mocks.llm.requests("responses");
+        assert_eq!(sent.len(), 2);
+        assert!(
+            sent[1]["input"]
+                .to_string()
+                .contains("function_call_output")
+        );
+    }
+}
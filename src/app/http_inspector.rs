@@ -0,0 +1,140 @@
+//! Popup viewer for `/debug http show` — the captured OpenAI and MCP
+//! exchanges (see [`crate::capture`]).
+//!
+//! Keys: `j`/`k` pick an exchange, `PgDn`/`PgUp` (or `J`/`K`) scroll its
+//! request and response, `r` reloads the capture, `d` dumps it to a file,
+//! `Esc` closes.
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent};
+
+use crate::capture::{self, Exchange};
+
+use super::App;
+use super::log_src;
+use super::logging::LogLevel;
+
+/// Exchanges shown by the popup and the position in them.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HttpInspector {
+    /// Newest first.
+    pub entries: Vec<Exchange>,
+    pub selected: usize,
+    /// Lines scrolled in the detail pane.
+    pub scroll: u16,
+}
+
+impl HttpInspector {
+    pub fn load() -> Self {
+        let mut entries = capture::snapshot();
+        entries.reverse();
+        HttpInspector {
+            entries,
+            ..HttpInspector::default()
+        }
+    }
+
+    pub fn current(&self) -> Option<&Exchange> {
+        self.entries.get(self.selected)
+    }
+
+    fn select(&mut self, idx: usize) {
+        self.selected = idx.min(self.entries.len().saturating_sub(1));
+        self.scroll = 0;
+    }
+}
+
+/// Request and response of `exchange` as pretty-printed JSON lines.
+pub(crate) fn detail_lines(exchange: &Exchange) -> Vec<String> {
+    let pretty = |value: &serde_json::Value| {
+        serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+    };
+    let mut lines = vec![
+        format!(
+            "{} {} · {} · {} ms",
+            exchange.channel.label(),
+            exchange.target,
+            exchange.status,
+            exchange.elapsed_ms
+        ),
+        exchange.at.clone(),
+        String::new(),
+        "── request ──".to_string(),
+    ];
+    lines.extend(pretty(&exchange.request).lines().map(str::to_string));
+    lines.push(String::new());
+    lines.push("── response ──".to_string());
+    lines.extend(pretty(&exchange.response).lines().map(str::to_string));
+    lines
+}
+
+/// `$MEMINI_HOME/debug/http-<timestamp>.json`.
+pub(crate) fn default_dump_path() -> PathBuf {
+    crate::util::memini_home().join("debug").join(format!(
+        "http-{}.json",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+impl App {
+    /// Write the capture to `path` (or the default dump path) and log where.
+    pub(crate) fn dump_http_capture(&mut self, path: Option<PathBuf>) {
+        let path = path.unwrap_or_else(default_dump_path);
+        match capture::dump(&path) {
+            Ok(count) => self.log(
+                LogLevel::Info,
+                format!("Wrote {count} exchange(s) to {}.", path.display()),
+            ),
+            Err(err) => log_src!(self, LogLevel::Error, format!("Dump failed: {err:#}")),
+        }
+    }
+
+    /// Key handling while the inspector popup is open.
+    pub(crate) fn handle_http_inspector_key(&mut self, key: KeyEvent) {
+        let Some(mut view) = self.http_inspector.take() else {
+            return;
+        };
+        let last = view.entries.len().saturating_sub(1);
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => return,
+            KeyCode::Char('j') | KeyCode::Down => view.select(view.selected + 1),
+            KeyCode::Char('k') | KeyCode::Up => view.select(view.selected.saturating_sub(1)),
+            KeyCode::Char('g') | KeyCode::Home => view.select(0),
+            KeyCode::Char('G') | KeyCode::End => view.select(last),
+            KeyCode::Char('J') | KeyCode::PageDown => view.scroll = view.scroll.saturating_add(10),
+            KeyCode::Char('K') | KeyCode::PageUp => view.scroll = view.scroll.saturating_sub(10),
+            KeyCode::Char('r') => view = HttpInspector::load(),
+            KeyCode::Char('d') => self.dump_http_capture(None),
+            _ => {}
+        }
+        self.http_inspector = Some(view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::capture::Channel;
+    use serde_json::json;
+
+    #[test]
+    fn detail_shows_status_request_and_response() {
+        let exchange = Exchange {
+            at: "2026-01-01T10:00:00.000+00:00".to_string(),
+            channel: Channel::Mcp,
+            target: "github/search".to_string(),
+            status: "error".to_string(),
+            elapsed_ms: 42,
+            request: json!({"name": "search"}),
+            response: json!({"error": "invalid schema"}),
+        };
+        let lines = detail_lines(&exchange);
+        assert_eq!(lines[0], "mcp github/search · error · 42 ms");
+        let request = lines.iter().position(|l| l == "── request ──");
+        let response = lines.iter().position(|l| l == "── response ──");
+        assert!(request < response);
+        assert!(lines.iter().any(|l| l.contains("invalid schema")));
+        assert!(!exchange.succeeded());
+    }
+}
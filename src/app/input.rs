@@ -1,48 +1,74 @@
-//! Text-input editing helpers (cursor movement, insertion, deletion).
+//! Text-input editing helpers (cursor movement, insertion, deletion) and
+//! bracketed-paste handling.
+//!
+//! `cursor` is a byte offset into `input` and always sits on a char
+//! boundary, so any Unicode text can be typed or pasted.
 
 use super::App;
+use super::attachments;
+use super::log_src;
+use super::logging::LogLevel;
 
 impl App {
-    /// Insert an ASCII character at the current cursor position.
+    /// Insert a character at the current cursor position.
     pub(crate) fn insert_char(&mut self, ch: char) {
-        if !ch.is_ascii() {
+        if ch.is_control() && ch != '\n' && ch != '\t' {
             return;
         }
-        self.input.insert(self.cursor, ch);
-        self.cursor = (self.cursor + 1).min(self.input.len());
+        let at = self.cursor.min(self.input.len());
+        self.input.insert(at, ch);
+        self.cursor = at + ch.len_utf8();
+    }
+
+    /// Insert a block of text (a paste) at the cursor as-is, newlines
+    /// included — nothing is submitted until the user presses Enter.
+    pub(crate) fn insert_str(&mut self, text: &str) {
+        let text = normalize_paste(text);
+        let at = self.cursor.min(self.input.len());
+        self.input.insert_str(at, &text);
+        self.cursor = at + text.len();
     }
 
     /// Delete the character before the cursor.
     pub(crate) fn backspace(&mut self) {
-        if self.cursor == 0 {
-            return;
+        if let Some(prev) = self.prev_boundary() {
+            self.input.remove(prev);
+            self.cursor = prev;
         }
-        self.cursor -= 1;
-        self.input.remove(self.cursor);
     }
 
     /// Delete the character at the cursor.
     pub(crate) fn delete(&mut self) {
-        if self.cursor >= self.input.len() {
-            return;
+        if self.cursor < self.input.len() {
+            self.input.remove(self.cursor);
         }
-        self.input.remove(self.cursor);
     }
 
-    /// Move the cursor one position to the left.
+    /// Move the cursor one character to the left.
     pub(crate) fn move_cursor_left(&mut self) {
-        if self.cursor > 0 {
-            self.cursor -= 1;
+        if let Some(prev) = self.prev_boundary() {
+            self.cursor = prev;
         }
     }
 
-    /// Move the cursor one position to the right.
+    /// Move the cursor one character to the right.
     pub(crate) fn move_cursor_right(&mut self) {
-        if self.cursor < self.input.len() {
-            self.cursor += 1;
+        if let Some(ch) = self.input[self.cursor.min(self.input.len())..]
+            .chars()
+            .next()
+        {
+            self.cursor += ch.len_utf8();
         }
     }
 
+    fn prev_boundary(&self) -> Option<usize> {
+        let at = self.cursor.min(self.input.len());
+        self.input[..at]
+            .char_indices()
+            .next_back()
+            .map(|(index, _)| index)
+    }
+
     /// Move the cursor to the beginning of the input.
     pub(crate) fn move_cursor_home(&mut self) {
         self.cursor = 0;
@@ -95,3 +121,68 @@ impl App {
         }
     }
 }
+
+// ── Paste ────────────────────────────────────────────────────────────
+
+impl App {
+    /// Bracketed paste: the whole paste lands in the input as one block.
+    /// A pasted image path (e.g. a file dragged onto the terminal) is
+    /// attached instead, and an empty paste checks the clipboard for image
+    /// data, which terminals cannot paste as text.
+    pub(crate) fn handle_paste(&mut self, text: String) {
+        if text.trim().is_empty() {
+            self.paste_clipboard_image(false);
+            return;
+        }
+        if let Some(path) = attachments::pasted_image_path(&text) {
+            self.attach_image(&path.to_string_lossy());
+            return;
+        }
+        self.scroll_offset = 0;
+        self.history_index = None;
+        self.insert_str(&text);
+    }
+
+    /// Ctrl+V: attach a clipboard image, or paste clipboard text for
+    /// terminals that pass the key through instead of pasting.
+    pub(crate) fn paste_clipboard_image(&mut self, text_fallback: bool) {
+        match attachments::clipboard_image() {
+            Ok(Some(image)) => self.queue_image(image),
+            Ok(None) if text_fallback => {
+                if let Ok(text) = arboard::Clipboard::new().and_then(|mut c| c.get_text()) {
+                    self.scroll_offset = 0;
+                    self.history_index = None;
+                    self.insert_str(&text);
+                }
+            }
+            Ok(None) => {}
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Clipboard image not pasted: {err:#}")
+            ),
+        }
+    }
+}
+
+/// Unify line endings and drop control characters other than newline/tab.
+fn normalize_paste(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .chars()
+        .filter(|ch| !ch.is_control() || *ch == '\n' || *ch == '\t')
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_paste;
+
+    #[test]
+    fn normalizes_pasted_line_endings() {
+        assert_eq!(
+            normalize_paste("fn main() {\r\n    println!(\"héllo ✓\");\r}\u{1b}"),
+            "fn main() {\n    println!(\"héllo ✓\");\n}"
+        );
+    }
+}
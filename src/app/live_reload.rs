@@ -0,0 +1,265 @@
+//! Live reload of recipes and skills.
+//!
+//! A [`notify`] watcher on `$MEMINI_HOME/agents` and `$MEMINI_HOME/skills`
+//! reports edits as [`AgentEvent::HomeFilesChanged`].  After
+//! [`LIVE_RELOAD_DEBOUNCE_MS`] of quiet, running recipe daemons whose
+//! definition changed are restarted with the new one, daemons whose recipe
+//! file is gone are stopped, and skills are re-indexed.  A recipe that no
+//! longer parses leaves its daemon running as before.  Set in
+//! `memini.toml`:
+//!
+//! ```toml
+//! [live_reload]
+//! enabled = true  # false: edits apply on /daemon reload and /skills reload
+//! ```
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Deserialize;
+use tokio::sync::mpsc;
+
+use crate::constants::LIVE_RELOAD_DEBOUNCE_MS;
+
+use super::App;
+use super::agent_recipes;
+use super::daemon::{self, AgentEvent};
+use super::log_src;
+use super::logging::LogLevel;
+
+/// The `[live_reload]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct LiveReload {
+    #[serde(default = "enabled")]
+    pub enabled: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for LiveReload {
+    fn default() -> Self {
+        LiveReload { enabled: true }
+    }
+}
+
+/// The `[live_reload]` section of `memini.toml`.
+pub(crate) fn config() -> &'static LiveReload {
+    &crate::config::get().live_reload
+}
+
+/// A live watcher on the recipe and skill directories.
+pub struct HomeWatcher {
+    _watcher: RecommendedWatcher,
+}
+
+/// Changes seen since the last reload, waiting out the debounce.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PendingReload {
+    pub last_change: Instant,
+    pub recipes: bool,
+    pub skills: bool,
+}
+
+/// Which of the two directories `paths` touch.  Only `.md` files count
+/// for recipes, since editors write swap and backup files next to them.
+fn classify(paths: &[PathBuf], agents: &Path, skills: &Path) -> (bool, bool) {
+    let recipes = paths.iter().any(|path| {
+        path.parent() == Some(agents)
+            && path
+                .extension()
+                .and_then(|ext| ext.to_str())
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("md"))
+    });
+    let skills = paths.iter().any(|path| path.starts_with(skills));
+    (recipes, skills)
+}
+
+fn spawn_home_watcher(tx: mpsc::UnboundedSender<AgentEvent>) -> Result<HomeWatcher> {
+    let agents = agent_recipes::ensure_agents_dir()?;
+    let skills = crate::skills::memini_skills_root();
+    fs::create_dir_all(&skills).with_context(|| format!("Create {}", skills.display()))?;
+
+    let (event_agents, event_skills) = (agents.clone(), skills.clone());
+    let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        let Ok(event) = res else {
+            return;
+        };
+        if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove()) {
+            return;
+        }
+        let (recipes, skills) = classify(&event.paths, &event_agents, &event_skills);
+        if recipes || skills {
+            let _ = tx.send(AgentEvent::HomeFilesChanged { recipes, skills });
+        }
+    })
+    .context("create file watcher")?;
+    watcher
+        .watch(&agents, RecursiveMode::NonRecursive)
+        .with_context(|| format!("watch {}", agents.display()))?;
+    watcher
+        .watch(&skills, RecursiveMode::Recursive)
+        .with_context(|| format!("watch {}", skills.display()))?;
+    Ok(HomeWatcher { _watcher: watcher })
+}
+
+impl App {
+    /// Start watching recipes and skills unless `[live_reload]` is off.
+    pub(crate) fn start_live_reload(&mut self) {
+        if self.home_watcher.is_some() || !config().enabled {
+            return;
+        }
+        match spawn_home_watcher(self.daemon_tx.clone()) {
+            Ok(watcher) => self.home_watcher = Some(watcher),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Live reload of recipes and skills disabled: {err:#}")
+            ),
+        }
+    }
+
+    pub(crate) fn queue_live_reload(&mut self, recipes: bool, skills: bool) {
+        let pending = self.pending_reload.get_or_insert(PendingReload {
+            last_change: Instant::now(),
+            recipes: false,
+            skills: false,
+        });
+        pending.last_change = Instant::now();
+        pending.recipes |= recipes;
+        pending.skills |= skills;
+    }
+
+    /// Apply queued changes once the directories have been quiet for
+    /// [`LIVE_RELOAD_DEBOUNCE_MS`].
+    pub(crate) fn flush_live_reload(&mut self) {
+        let Some(pending) = self.pending_reload else {
+            return;
+        };
+        if pending.last_change.elapsed() < Duration::from_millis(LIVE_RELOAD_DEBOUNCE_MS) {
+            return;
+        }
+        self.pending_reload = None;
+        if pending.recipes {
+            self.reload_running_recipes();
+        }
+        if pending.skills {
+            match self.reload_imported_skills() {
+                Ok(()) => self.log(
+                    LogLevel::Info,
+                    format!(
+                        "♻ Skills re-indexed ({} loaded).",
+                        self.imported_skills.len()
+                    ),
+                ),
+                Err(err) => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Skill reload failed: {err:#}")
+                ),
+            }
+        }
+    }
+
+    /// Restart running recipe daemons whose file changed; stop those whose
+    /// file was removed.
+    fn reload_running_recipes(&mut self) {
+        let recipes = match agent_recipes::load_agent_recipes() {
+            Ok(recipes) => recipes,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Recipe change not applied: {err:#} (/daemon lint for details)")
+                );
+                return;
+            }
+        };
+        let builtins = daemon::builtin_tasks();
+        let recipe_backed: Vec<String> = self
+            .daemon_handles
+            .iter()
+            .map(|handle| handle.def.name.clone())
+            .filter(|name| {
+                !builtins
+                    .iter()
+                    .any(|task| task.name.eq_ignore_ascii_case(name))
+            })
+            .collect();
+
+        for name in recipe_backed {
+            let Some(pos) = self
+                .daemon_handles
+                .iter()
+                .position(|handle| handle.def.name == name)
+            else {
+                continue;
+            };
+            let recipe = recipes
+                .iter()
+                .find(|recipe| recipe.name.eq_ignore_ascii_case(&name));
+            let Some(recipe) = recipe else {
+                let handle = self.daemon_handles.remove(pos);
+                handle.abort.abort();
+                self.log(
+                    LogLevel::Info,
+                    format!("♻ Recipe '{name}' removed — task stopped."),
+                );
+                continue;
+            };
+            let running = &self.daemon_handles[pos];
+            let mut def = Self::daemon_def_from_recipe(recipe, running.def.paused);
+            if def == running.def {
+                continue;
+            }
+            def.paused = running.schedule.is_paused();
+            let handle = self.daemon_handles.remove(pos);
+            handle.abort.abort();
+            self.log(
+                LogLevel::Info,
+                format!("♻ Recipe '{name}' changed — restarting with the new definition."),
+            );
+            self.spawn_daemon_task(def);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn only_recipe_markdown_and_skill_files_count() {
+        let agents = Path::new("/home/me/Memini/agents");
+        let skills = Path::new("/home/me/Memini/skills");
+        let paths = |list: &[&str]| list.iter().map(PathBuf::from).collect::<Vec<_>>();
+
+        assert_eq!(
+            classify(&paths(&["/home/me/Memini/agents/ci.md"]), agents, skills),
+            (true, false)
+        );
+        assert_eq!(
+            classify(
+                &paths(&[
+                    "/home/me/Memini/agents/.ci.md.swp",
+                    "/home/me/Memini/skills/pdf/SKILL.md"
+                ]),
+                agents,
+                skills
+            ),
+            (false, true)
+        );
+        assert!(
+            !MeminiToml::parse("[live_reload]\nenabled = false\n")
+                .expect("parse")
+                .live_reload
+                .enabled
+        );
+        assert!(MeminiToml::parse("").expect("parse").live_reload.enabled);
+    }
+}
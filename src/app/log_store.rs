@@ -0,0 +1,200 @@
+//! Ring-buffer store for the activity log with lazy, slice-only rendering.
+//!
+//! Entries live in a bounded `VecDeque`; the oldest are dropped once the
+//! capacity is reached.  Drawing walks backwards from the newest entry and
+//! only renders as many entries as the viewport (plus scroll offset)
+//! needs.  Each entry caches its wrapped height per panel width so deep
+//! scrolling does not re-wrap the whole history every frame.
+
+use std::collections::VecDeque;
+
+use ratatui::text::{Line, Text};
+use ratatui::widgets::{Paragraph, Wrap};
+
+use super::logging::LogLine;
+
+/// Lines to draw plus the paragraph scroll needed to show them.
+pub(crate) struct LogSlice<'a> {
+    pub lines: Vec<Line<'a>>,
+    /// Rows to skip at the top of `lines`.
+    pub top_row: u16,
+    /// Scroll offset after clamping to the available history.
+    pub scroll_offset: usize,
+}
+
+/// Bounded activity-log history.
+#[derive(Debug)]
+pub(crate) struct LogStore {
+    entries: VecDeque<LogLine>,
+    capacity: usize,
+    /// Entries ever pushed, including dropped and cleared ones.
+    pushed: usize,
+}
+
+impl LogStore {
+    pub fn new(capacity: usize) -> Self {
+        LogStore {
+            entries: VecDeque::new(),
+            capacity: capacity.max(1),
+            pushed: 0,
+        }
+    }
+
+    pub fn push(&mut self, line: LogLine) {
+        if self.entries.len() >= self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(line);
+        self.pushed += 1;
+    }
+
+    /// Count of entries ever pushed; compare two readings with
+    /// [`Self::rows_after`].
+    pub fn pushed(&self) -> usize {
+        self.pushed
+    }
+
+    /// Wrapped rows of the entries pushed since [`Self::pushed`] returned
+    /// `seen`.
+    pub fn rows_after(&self, seen: usize, width: u16) -> usize {
+        self.since(seen)
+            .map(|entry| entry_height(entry, width))
+            .sum()
+    }
+
+    /// Entries pushed since [`Self::pushed`] returned `seen` and still
+    /// held, oldest first.
+    pub fn since(&self, seen: usize) -> impl Iterator<Item = &LogLine> {
+        let new = self.pushed.saturating_sub(seen).min(self.entries.len());
+        self.entries.iter().skip(self.entries.len() - new)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    #[cfg(test)]
+    pub fn iter(&self) -> impl Iterator<Item = &LogLine> {
+        self.entries.iter()
+    }
+
+    /// Render only the entries intersecting a `height`-row viewport that
+    /// sits `scroll_offset` rows above the bottom of the log.
+    pub fn visible_slice(&self, width: u16, height: usize, scroll_offset: usize) -> LogSlice<'_> {
+        // Clamp the offset when it reaches past the oldest entry.
+        let wanted = scroll_offset.saturating_add(height);
+        let mut total = 0usize;
+        let mut exhausted = true;
+        for entry in self.entries.iter().rev() {
+            if total >= wanted {
+                exhausted = false;
+                break;
+            }
+            total += entry_height(entry, width);
+        }
+        let scroll_offset = if exhausted {
+            scroll_offset.min(total.saturating_sub(height))
+        } else {
+            scroll_offset
+        };
+
+        // Skip entries entirely below the viewport, stop once it is full.
+        let viewport_top = scroll_offset.saturating_add(height);
+        let mut visible: Vec<&LogLine> = Vec::new();
+        let mut below = 0usize;
+        let mut bottom = 0usize;
+        for entry in self.entries.iter().rev() {
+            if bottom >= viewport_top {
+                break;
+            }
+            bottom += entry_height(entry, width);
+            if bottom <= scroll_offset {
+                below = bottom;
+                continue;
+            }
+            visible.push(entry);
+        }
+
+        let visible_rows = bottom - below;
+        let top_row = visible_rows.saturating_sub((scroll_offset - below) + height);
+        let lines = visible
+            .into_iter()
+            .rev()
+            .flat_map(|entry| entry.render())
+            .collect();
+        LogSlice {
+            lines,
+            top_row: top_row.min(u16::MAX as usize) as u16,
+            scroll_offset,
+        }
+    }
+}
+
+/// Wrapped height of one entry, cached per width.
+fn entry_height(entry: &LogLine, width: u16) -> usize {
+    if let Some((cached_width, height)) = entry.height_cache.get() {
+        if cached_width == width {
+            return height;
+        }
+    }
+    let height = Paragraph::new(Text::from(entry.render()))
+        .wrap(Wrap { trim: false })
+        .line_count(width);
+    entry.height_cache.set(Some((width, height)));
+    height
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::logging::{LogContent, LogLevel};
+
+    fn entry(msg: &str) -> LogLine {
+        LogLine::new(LogLevel::Info, LogContent::Plain(msg.to_string()))
+    }
+
+    #[test]
+    fn drops_oldest_entries_past_capacity() {
+        let mut store = LogStore::new(3);
+        for idx in 0..5 {
+            store.push(entry(&format!("line {idx}")));
+        }
+        let slice = store.visible_slice(80, 10, 0);
+        assert_eq!(slice.lines.len(), 3);
+    }
+
+    #[test]
+    fn renders_only_the_visible_tail() {
+        let mut store = LogStore::new(100);
+        for idx in 0..50 {
+            store.push(entry(&format!("line {idx}")));
+        }
+        let slice = store.visible_slice(80, 5, 0);
+        assert_eq!(slice.lines.len(), 5);
+        assert_eq!(slice.top_row, 0);
+
+        let slice = store.visible_slice(80, 5, 10);
+        assert_eq!(slice.lines.len(), 5);
+        assert_eq!(slice.lines[4].spans[2].content, "line 39");
+
+        // Scrolling past the top clamps to the first entry.
+        let slice = store.visible_slice(80, 5, usize::MAX);
+        assert_eq!(slice.scroll_offset, 45);
+        assert_eq!(slice.lines.len(), 5);
+        assert_eq!(slice.lines[0].spans[2].content, "line 0");
+    }
+
+    #[test]
+    fn counts_rows_pushed_since_a_reading() {
+        let mut store = LogStore::new(3);
+        store.push(entry("old"));
+        let seen = store.pushed();
+        for idx in 0..4 {
+            store.push(entry(&format!("new {idx}")));
+        }
+        assert_eq!(store.pushed(), 5);
+        // Only the entries still held are counted.
+        assert_eq!(store.rows_after(seen, 80), 3);
+        assert_eq!(store.rows_after(store.pushed(), 80), 0);
+    }
+}
@@ -3,11 +3,19 @@
 //! Log entries come in two flavours:
 //!
 //! - **Plain** – timestamped single-line messages (system info, warnings, etc.)
-//! - **Markdown** – multi-line rich content from LLM responses, rendered with
-//!   `tui-markdown` for proper headings, bold, code blocks, lists, etc.
+//! - **Markdown** – multi-line rich content from LLM responses, rendered by
+//!   [`super::markdown`] (headings, lists, highlighted code blocks, etc.)
 
+use std::cell::Cell;
+
+use chrono::Local;
 use ratatui::style::{Color, Modifier, Style};
-use ratatui::text::{Line, Span, Text};
+use ratatui::text::{Line, Span};
+
+use crate::i18n::t;
+
+use super::markdown;
+use super::theme;
 
 // ── Log severity ─────────────────────────────────────────────────────
 
@@ -23,18 +31,19 @@ impl LogLevel {
     /// Short uppercase label for display.
     pub fn label(&self) -> &'static str {
         match self {
-            LogLevel::Info => "INFO",
-            LogLevel::Warn => "WARN",
-            LogLevel::Error => "ERROR",
+            LogLevel::Info => t("INFO"),
+            LogLevel::Warn => t("WARN"),
+            LogLevel::Error => t("ERROR"),
         }
     }
 
     /// Colour associated with this severity.
     pub fn color(&self) -> Color {
+        let theme = theme::current();
         match self {
-            LogLevel::Info => Color::Cyan,
-            LogLevel::Warn => Color::Yellow,
-            LogLevel::Error => Color::Red,
+            LogLevel::Info => theme.info,
+            LogLevel::Warn => theme.warning,
+            LogLevel::Error => theme.error,
         }
     }
 }
@@ -61,23 +70,36 @@ pub struct LogLine {
     pub timestamp: String,
     pub level: LogLevel,
     pub content: LogContent,
+    /// Wrapped height for the last panel width it was measured at.
+    pub height_cache: Cell<Option<(u16, usize)>>,
 }
 
 impl LogLine {
+    /// Create an entry stamped with the current local time.
+    pub fn new(level: LogLevel, content: LogContent) -> Self {
+        LogLine {
+            timestamp: Local::now().format("%H:%M:%S").to_string(),
+            level,
+            content,
+            height_cache: Cell::new(None),
+        }
+    }
+
     /// Render this entry as one or more styled ratatui [`Line`]s.
     ///
     /// Plain entries produce a single line:
     ///   `[HH:MM:SS] INFO  message text`
     ///
-    /// Markdown entries produce a coloured header line followed by
-    /// tui-markdown–rendered lines for the body.
+    /// Markdown entries produce a coloured header line followed by the
+    /// rendered markdown body.
     pub fn render(&self) -> Vec<Line<'_>> {
+        let theme = theme::current();
         match &self.content {
             LogContent::Plain(msg) => {
                 vec![Line::from(vec![
                     Span::styled(
                         format!("[{}] ", self.timestamp),
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.dim),
                     ),
                     Span::styled(
                         format!("{:<5} ", self.level.label()),
@@ -95,21 +117,17 @@ impl LogLine {
                 lines.push(Line::from(vec![
                     Span::styled(
                         format!("[{}] ", self.timestamp),
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.dim),
                     ),
                     Span::styled(
                         label.clone(),
                         Style::default()
-                            .fg(Color::Magenta)
+                            .fg(theme.label)
                             .add_modifier(Modifier::BOLD),
                     ),
                 ]));
 
-                // Render markdown body through tui-markdown.
-                let text: Text<'_> = tui_markdown::from_str(body);
-                for line in text.lines {
-                    lines.push(line.to_owned());
-                }
+                lines.extend(markdown::render(body));
 
                 // Blank line after the response for readability.
                 lines.push(Line::raw(""));
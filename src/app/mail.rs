@@ -0,0 +1,227 @@
+//! Email delivery of daemon results over SMTP.
+//!
+//! A recipe with `deliver: email:ops@example.com` mails each run's output
+//! to that address, next to the usual chat log and artifact.  The server
+//! comes from `memini.toml` and the password from the secret store
+//! (`/secret set SMTP_PASSWORD <value>`):
+//!
+//! ```toml
+//! [smtp]
+//! host = "smtp.example.com"
+//! port = 587                 # default: 587, or 465 with tls = "tls"
+//! tls = "starttls"           # "tls", "starttls" or "none"
+//! username = "reports@example.com"
+//! from = "memini <reports@example.com>"
+//! password_secret = "SMTP_PASSWORD"  # the default
+//! ```
+
+use anyhow::{Context, Result, bail};
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, Message};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Tokio1Executor};
+use serde::Deserialize;
+
+use super::App;
+use super::daemon::{AgentEvent, ChatLogLevel};
+use super::log_src;
+use super::logging::LogLevel;
+use super::secrets;
+
+/// Prefix of an email target in a recipe's `deliver:`.
+const EMAIL_PREFIX: &str = "email:";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum SmtpTls {
+    /// Implicit TLS, usually port 465.
+    Tls,
+    /// Plain connection upgraded with STARTTLS, usually port 587.
+    #[default]
+    Starttls,
+    /// No encryption; only for local relays.
+    #[serde(rename = "none")]
+    Plain,
+}
+
+/// The `[smtp]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct SmtpConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub tls: SmtpTls,
+    #[serde(default)]
+    pub username: Option<String>,
+    pub from: String,
+    #[serde(default = "default_password_secret")]
+    pub password_secret: String,
+}
+
+fn default_password_secret() -> String {
+    "SMTP_PASSWORD".to_string()
+}
+
+impl SmtpConfig {
+    pub fn port(&self) -> u16 {
+        self.port.unwrap_or(match self.tls {
+            SmtpTls::Tls => 465,
+            SmtpTls::Starttls => 587,
+            SmtpTls::Plain => 25,
+        })
+    }
+}
+
+/// The `[smtp]` section of `memini.toml`; `None` without one.
+pub(crate) fn config() -> Option<&'static SmtpConfig> {
+    crate::config::get().smtp.as_ref()
+}
+
+/// Check one `deliver:` target: `email:<address>`.
+pub(crate) fn validate_target(target: &str) -> Result<()> {
+    let Some(address) = target.strip_prefix(EMAIL_PREFIX) else {
+        bail!("expected email:<address>, got '{target}'");
+    };
+    address
+        .trim()
+        .parse::<Mailbox>()
+        .with_context(|| format!("'{}' is not an email address", address.trim()))?;
+    Ok(())
+}
+
+/// Addresses of the `email:` targets in `deliver`.
+pub(crate) fn email_recipients(deliver: &[String]) -> Vec<String> {
+    deliver
+        .iter()
+        .filter_map(|target| target.strip_prefix(EMAIL_PREFIX))
+        .map(|address| address.trim().to_string())
+        .filter(|address| !address.is_empty())
+        .collect()
+}
+
+fn build_message(config: &SmtpConfig, to: &[String], subject: &str, body: &str) -> Result<Message> {
+    let from: Mailbox = config
+        .from
+        .parse()
+        .with_context(|| format!("smtp.from: '{}' is not an email address", config.from))?;
+    let mut builder = Message::builder().from(from).subject(subject);
+    for address in to {
+        let mailbox: Mailbox = address
+            .parse()
+            .with_context(|| format!("'{address}' is not an email address"))?;
+        builder = builder.to(mailbox);
+    }
+    builder
+        .header(ContentType::TEXT_PLAIN)
+        .body(body.to_string())
+        .context("build email")
+}
+
+/// Send `body` to `to` through the configured server.
+pub(crate) async fn send(
+    config: &SmtpConfig,
+    password: Option<String>,
+    to: &[String],
+    subject: &str,
+    body: &str,
+) -> Result<()> {
+    let message = build_message(config, to, subject, body)?;
+    let builder = match config.tls {
+        SmtpTls::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+        SmtpTls::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?,
+        SmtpTls::Plain => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host),
+    };
+    let mut builder = builder.port(config.port());
+    if let Some(username) = &config.username {
+        let Some(password) = password else {
+            bail!(
+                "no password: /secret set {} <password>",
+                config.password_secret
+            );
+        };
+        builder = builder.credentials(Credentials::new(username.clone(), password));
+    }
+    builder
+        .build()
+        .send(message)
+        .await
+        .with_context(|| format!("send via {}:{}", config.host, config.port()))?;
+    Ok(())
+}
+
+impl App {
+    /// Mail a daemon result to the `email:` targets of its `deliver:`.
+    pub(crate) fn deliver_by_email(&mut self, task_name: &str, deliver: &[String], body: String) {
+        let recipients = email_recipients(deliver);
+        if recipients.is_empty() {
+            return;
+        }
+        let Some(config) = config() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("'{task_name}' has deliver: email but memini.toml has no [smtp] section.")
+            );
+            return;
+        };
+        let password = secrets::load_secret_store()
+            .values
+            .get(&config.password_secret)
+            .cloned();
+        let subject = format!(
+            "[memini] {task_name} — {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M")
+        );
+        let task_name = task_name.to_string();
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            let (line, level) = match send(config, password, &recipients, &subject, &body).await {
+                Ok(()) => (
+                    format!("📧 Emailed {task_name} to {}.", recipients.join(", ")),
+                    ChatLogLevel::Info,
+                ),
+                Err(err) => (
+                    format!("Email of {task_name} failed: {err:#}"),
+                    ChatLogLevel::Warn,
+                ),
+            };
+            let _ = tx.send(AgentEvent::ChatProgress { line, level });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn deliver_targets_and_smtp_settings_parse() {
+        assert!(validate_target("email:ops@example.com").is_ok());
+        assert!(validate_target("email:not-an-address").is_err());
+        assert!(validate_target("slack:team").is_err());
+        assert_eq!(
+            email_recipients(&[
+                "email:ops@example.com".to_string(),
+                "email: me@example.com".to_string(),
+            ]),
+            vec!["ops@example.com", "me@example.com"]
+        );
+
+        let config = MeminiToml::parse(
+            "[smtp]\nhost = \"smtp.example.com\"\ntls = \"tls\"\nfrom = \"memini <r@example.com>\"\n",
+        )
+        .expect("parse")
+        .smtp
+        .expect("smtp section");
+        assert_eq!(config.port(), 465);
+        assert_eq!(config.password_secret, "SMTP_PASSWORD");
+        assert!(MeminiToml::parse("").expect("parse").smtp.is_none());
+
+        let message = build_message(&config, &["ops@example.com".to_string()], "report", "ok")
+            .expect("message");
+        let raw = String::from_utf8(message.formatted()).expect("utf8");
+        assert!(raw.contains("To: ops@example.com"));
+    }
+}
@@ -0,0 +1,412 @@
+//! Markdown → styled ratatui lines.
+//!
+//! Parses with `pulldown-cmark` and renders headings, emphasis, lists,
+//! block quotes, links, rules, and tables.  Fenced code blocks are
+//! syntax-highlighted with `syntect`.  Colors come from the active
+//! [`theme`](super::theme).  Rendered bodies are cached (per theme) because
+//! the activity log and agent sessions are redrawn every frame.
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, OnceLock};
+
+use pulldown_cmark::{CodeBlockKind, Event, HeadingLevel, Options, Parser, Tag, TagEnd};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use syntect::easy::HighlightLines;
+use syntect::highlighting::ThemeSet;
+use syntect::parsing::SyntaxSet;
+use syntect::util::LinesWithEndings;
+
+use super::theme::{self, Theme};
+
+/// Rendered bodies kept before the cache is flushed.
+const MAX_CACHED_BODIES: usize = 512;
+/// syntect theme used when the palette names one that does not exist.
+const FALLBACK_CODE_THEME: &str = "base16-ocean.dark";
+
+thread_local! {
+    static CACHE: RefCell<HashMap<u64, Vec<Line<'static>>>> = RefCell::new(HashMap::new());
+}
+
+/// Render a markdown body into styled lines (cached by content).
+pub fn render(body: &str) -> Vec<Line<'static>> {
+    let mut hasher = DefaultHasher::new();
+    theme::generation().hash(&mut hasher);
+    body.hash(&mut hasher);
+    let key = hasher.finish();
+
+    if let Some(lines) = CACHE.with(|cache| cache.borrow().get(&key).cloned()) {
+        return lines;
+    }
+    let lines = Renderer::new().run(body);
+    CACHE.with(|cache| {
+        let mut cache = cache.borrow_mut();
+        if cache.len() >= MAX_CACHED_BODIES {
+            cache.clear();
+        }
+        cache.insert(key, lines.clone());
+    });
+    lines
+}
+
+fn highlighting() -> &'static (SyntaxSet, ThemeSet) {
+    static ASSETS: OnceLock<(SyntaxSet, ThemeSet)> = OnceLock::new();
+    ASSETS.get_or_init(|| {
+        (
+            SyntaxSet::load_defaults_newlines(),
+            ThemeSet::load_defaults(),
+        )
+    })
+}
+
+/// Highlight a fenced code block; unknown languages fall back to plain text.
+fn highlight_code(lang: &str, code: &str, palette: &Theme) -> Vec<Vec<Span<'static>>> {
+    let (syntaxes, themes) = highlighting();
+    let Some(code_theme) = themes
+        .themes
+        .get(&palette.code_theme)
+        .or_else(|| themes.themes.get(FALLBACK_CODE_THEME))
+    else {
+        return LinesWithEndings::from(code)
+            .map(|line| {
+                vec![Span::styled(
+                    line.trim_end_matches(['\n', '\r']).to_string(),
+                    Style::default().fg(palette.code_fg),
+                )]
+            })
+            .collect();
+    };
+    let syntax = syntaxes
+        .find_syntax_by_token(lang)
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, code_theme);
+
+    LinesWithEndings::from(code)
+        .map(|line| match highlighter.highlight_line(line, syntaxes) {
+            Ok(ranges) => ranges
+                .into_iter()
+                .map(|(style, text)| {
+                    let fg = style.foreground;
+                    Span::styled(
+                        text.trim_end_matches(['\n', '\r']).to_string(),
+                        Style::default().fg(theme::fit(Color::Rgb(fg.r, fg.g, fg.b))),
+                    )
+                })
+                .collect(),
+            Err(_) => vec![Span::styled(
+                line.trim_end_matches(['\n', '\r']).to_string(),
+                Style::default().fg(palette.subtle),
+            )],
+        })
+        .collect()
+}
+
+fn heading_style(level: HeadingLevel, palette: &Theme) -> Style {
+    let color = match level {
+        HeadingLevel::H1 => palette.success,
+        HeadingLevel::H2 => palette.primary,
+        _ => palette.brand,
+    };
+    Style::default().fg(color).add_modifier(Modifier::BOLD)
+}
+
+#[derive(Default)]
+struct Renderer {
+    theme: Arc<Theme>,
+    lines: Vec<Line<'static>>,
+    current: Vec<Span<'static>>,
+    /// Inline style stack (emphasis, strong, links, headings…).
+    styles: Vec<Style>,
+    /// One entry per open list: next ordinal for ordered lists.
+    lists: Vec<Option<u64>>,
+    /// Marker waiting to be emitted at the start of the next line.
+    item_marker: Option<String>,
+    quote_depth: usize,
+    /// Language + accumulated source of the open fenced block.
+    code_block: Option<(String, String)>,
+    link_dest: Option<String>,
+    table_row: Vec<String>,
+    table_cell: Option<String>,
+}
+
+impl Renderer {
+    fn new() -> Self {
+        Renderer {
+            theme: theme::current(),
+            ..Renderer::default()
+        }
+    }
+
+    fn run(mut self, body: &str) -> Vec<Line<'static>> {
+        let options =
+            Options::ENABLE_STRIKETHROUGH | Options::ENABLE_TABLES | Options::ENABLE_TASKLISTS;
+        for event in Parser::new_ext(body, options) {
+            self.event(event);
+        }
+        self.flush_line();
+        while self.lines.last().is_some_and(|line| line.spans.is_empty()) {
+            self.lines.pop();
+        }
+        self.lines
+    }
+
+    fn style(&self) -> Style {
+        self.styles.last().copied().unwrap_or_default()
+    }
+
+    fn push_style(&mut self, patch: Style) {
+        let next = self.style().patch(patch);
+        self.styles.push(next);
+    }
+
+    /// Quote bars and list indentation for the start of a line.
+    fn prefix(&mut self) -> Vec<Span<'static>> {
+        let mut spans = Vec::new();
+        if self.quote_depth > 0 {
+            spans.push(Span::styled(
+                "│ ".repeat(self.quote_depth),
+                Style::default().fg(self.theme.dim),
+            ));
+        }
+        if !self.lists.is_empty() {
+            let indent = "  ".repeat(self.lists.len() - 1);
+            match self.item_marker.take() {
+                Some(marker) => spans.push(Span::styled(
+                    format!("{indent}{marker}"),
+                    Style::default().fg(self.theme.primary),
+                )),
+                None => spans.push(Span::raw(format!("{indent}  "))),
+            }
+        }
+        spans
+    }
+
+    fn flush_line(&mut self) {
+        if self.current.is_empty() && self.item_marker.is_none() {
+            return;
+        }
+        let mut spans = self.prefix();
+        spans.append(&mut self.current);
+        self.lines.push(Line::from(spans));
+    }
+
+    fn blank_line(&mut self) {
+        self.flush_line();
+        if self.lists.is_empty() && self.lines.last().is_some_and(|l| !l.spans.is_empty()) {
+            self.lines.push(Line::raw(""));
+        }
+    }
+
+    fn text(&mut self, text: &str) {
+        if let Some(cell) = self.table_cell.as_mut() {
+            cell.push_str(text);
+            return;
+        }
+        if let Some((_, code)) = self.code_block.as_mut() {
+            code.push_str(text);
+            return;
+        }
+        let style = self.style();
+        self.current.push(Span::styled(text.to_string(), style));
+    }
+
+    fn event(&mut self, event: Event<'_>) {
+        match event {
+            Event::Start(tag) => self.start(tag),
+            Event::End(tag) => self.end(tag),
+            Event::Text(text) => self.text(&text),
+            Event::Code(code) => {
+                if let Some(cell) = self.table_cell.as_mut() {
+                    cell.push_str(&code);
+                } else {
+                    self.current.push(Span::styled(
+                        code.to_string(),
+                        Style::default()
+                            .fg(self.theme.code_fg)
+                            .bg(self.theme.code_bg),
+                    ));
+                }
+            }
+            Event::SoftBreak => self.text(" "),
+            Event::HardBreak => self.flush_line(),
+            Event::Rule => {
+                self.flush_line();
+                self.lines.push(Line::from(Span::styled(
+                    "─".repeat(40),
+                    Style::default().fg(self.theme.dim),
+                )));
+            }
+            Event::TaskListMarker(done) => {
+                self.current
+                    .push(Span::raw(if done { "[x] " } else { "[ ] " }));
+            }
+            Event::Html(html) | Event::InlineHtml(html) => self.text(&html),
+            _ => {}
+        }
+    }
+
+    fn start(&mut self, tag: Tag<'_>) {
+        match tag {
+            Tag::Heading { level, .. } => {
+                self.flush_line();
+                let style = heading_style(level, &self.theme);
+                self.push_style(style);
+                let hashes = "#".repeat(level as usize);
+                self.current.push(Span::styled(format!("{hashes} "), style));
+            }
+            Tag::BlockQuote(_) => {
+                self.flush_line();
+                self.quote_depth += 1;
+                self.push_style(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            Tag::CodeBlock(kind) => {
+                self.flush_line();
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => {
+                        lang.split_whitespace().next().unwrap_or("").to_string()
+                    }
+                    CodeBlockKind::Indented => String::new(),
+                };
+                self.code_block = Some((lang, String::new()));
+            }
+            Tag::List(start) => {
+                self.flush_line();
+                self.lists.push(start);
+            }
+            Tag::Item => {
+                self.flush_line();
+                let marker = match self.lists.last_mut() {
+                    Some(Some(next)) => {
+                        let marker = format!("{next}. ");
+                        *next += 1;
+                        marker
+                    }
+                    _ => "• ".to_string(),
+                };
+                self.item_marker = Some(marker);
+            }
+            Tag::Emphasis => self.push_style(Style::default().add_modifier(Modifier::ITALIC)),
+            Tag::Strong => self.push_style(Style::default().add_modifier(Modifier::BOLD)),
+            Tag::Strikethrough => {
+                self.push_style(Style::default().add_modifier(Modifier::CROSSED_OUT))
+            }
+            Tag::Link { dest_url, .. } => {
+                self.link_dest = Some(dest_url.to_string());
+                self.push_style(
+                    Style::default()
+                        .fg(self.theme.link)
+                        .add_modifier(Modifier::UNDERLINED),
+                );
+            }
+            Tag::Table(_) => self.flush_line(),
+            Tag::TableRow | Tag::TableHead => self.table_row.clear(),
+            Tag::TableCell => self.table_cell = Some(String::new()),
+            _ => {}
+        }
+    }
+
+    fn end(&mut self, tag: TagEnd) {
+        match tag {
+            TagEnd::Paragraph => self.blank_line(),
+            TagEnd::Heading(_) => {
+                self.styles.pop();
+                self.blank_line();
+            }
+            TagEnd::BlockQuote(_) => {
+                self.flush_line();
+                self.styles.pop();
+                self.quote_depth = self.quote_depth.saturating_sub(1);
+                self.blank_line();
+            }
+            TagEnd::CodeBlock => {
+                if let Some((lang, code)) = self.code_block.take() {
+                    for spans in highlight_code(&lang, &code, &self.theme) {
+                        let mut line = self.prefix();
+                        line.push(Span::styled("▏ ", Style::default().fg(self.theme.dim)));
+                        line.extend(spans);
+                        self.lines.push(Line::from(line));
+                    }
+                }
+                self.blank_line();
+            }
+            TagEnd::List(_) => {
+                self.flush_line();
+                self.lists.pop();
+                self.blank_line();
+            }
+            TagEnd::Item => self.flush_line(),
+            TagEnd::Emphasis | TagEnd::Strong | TagEnd::Strikethrough => {
+                self.styles.pop();
+            }
+            TagEnd::Link => {
+                self.styles.pop();
+                if let Some(dest) = self.link_dest.take() {
+                    self.current.push(Span::styled(
+                        format!(" ({dest})"),
+                        Style::default().fg(self.theme.dim),
+                    ));
+                }
+            }
+            TagEnd::TableCell => {
+                if let Some(cell) = self.table_cell.take() {
+                    self.table_row.push(cell.trim().to_string());
+                }
+            }
+            TagEnd::TableHead | TagEnd::TableRow => {
+                let head = matches!(tag, TagEnd::TableHead);
+                let style = if head {
+                    Style::default().add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default()
+                };
+                let row = std::mem::take(&mut self.table_row).join(" │ ");
+                let width = row.chars().count();
+                self.lines.push(Line::from(Span::styled(row, style)));
+                if head {
+                    self.lines.push(Line::from(Span::styled(
+                        "─".repeat(width),
+                        Style::default().fg(self.theme.dim),
+                    )));
+                }
+            }
+            TagEnd::Table => self.blank_line(),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line<'_>]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|line| line.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn renders_headings_and_lists() {
+        let lines = Renderer::new().run("# Title\n\n- one\n- two\n\n1. first\n2. second\n");
+        assert_eq!(
+            plain(&lines),
+            vec!["# Title", "", "• one", "• two", "", "1. first", "2. second"]
+        );
+    }
+
+    #[test]
+    fn highlights_fenced_code() {
+        let lines = Renderer::new().run("```rust\nfn main() {}\n```\n");
+        assert_eq!(plain(&lines), vec!["▏ fn main() {}"]);
+        assert!(lines[0].spans.len() > 2, "expected highlighted spans");
+    }
+
+    #[test]
+    fn renders_links_with_destination() {
+        let lines = Renderer::new().run("see [docs](https://example.com)");
+        assert_eq!(plain(&lines), vec!["see docs (https://example.com)"]);
+    }
+}
@@ -0,0 +1,689 @@
+//! `memini serve-mcp` — expose memini itself as an MCP server so other
+//! agent frontends (Claude Desktop, other CLIs) can search and write the
+//! shared Rice memory, run memini agents and daemon tasks, and use the
+//! workspace tools.
+//!
+//! Speaks JSON-RPC 2.0 with the MCP `initialize`, `ping`, `tools/list` and
+//! `tools/call` methods, over newline-delimited stdio (default) or plain
+//! HTTP POST (`--http [addr]`, JSON responses, no SSE streams).  The HTTP
+//! endpoint requires `MEMINI_MCP_SERVER_TOKEN` as a bearer token, binds to
+//! loopback addresses only unless `--allow-remote` is given, rejects
+//! requests from browser pages on other origins, and refuses bodies over
+//! `MAX_MCP_BODY_BYTES`.
+//!
+//! Agents run headless to completion and return their output; every tool
+//! call still goes through the tool policy, with `require-approval` rules
+//! treated as denials since nobody is there to approve them.  Logs go to
+//! stderr so stdout stays clean for the protocol.
+
+use std::io::{BufRead, Read, Write};
+use std::net::ToSocketAddrs;
+use std::sync::Arc;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::Local;
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Response, Server};
+use tokio::sync::{Mutex, mpsc};
+
+use crate::constants::{
+    APP_NAME, APP_VERSION, MAX_MCP_BODY_BYTES, MODEL_ROUTES_VAR, OPENAI_KEY_VAR, OPENAI_MODEL_VAR,
+};
+use crate::openai::{OpenAiClient, ToolCall};
+use crate::rice::RiceStore;
+use crate::util::constant_time_eq;
+
+use super::App;
+use super::agent_recipes;
+use super::artifacts::ArtifactRun;
+use super::daemon::{self, AgentEvent, DaemonTaskDef};
+use super::policy;
+
+/// Default `--http` listen address.
+const DEFAULT_HTTP_ADDR: &str = "127.0.0.1:8765";
+/// Flag that allows `--http` to bind a non-loopback address.
+const ALLOW_REMOTE_FLAG: &str = "--allow-remote";
+/// Bearer token the HTTP transport requires.
+const SERVER_TOKEN_ENV: &str = "MEMINI_MCP_SERVER_TOKEN";
+/// Policy context recorded for calls made through the server.
+const POLICY_CONTEXT: &str = "mcp-server";
+const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Tools `run_tool` answers itself; every other listed tool goes to
+/// [`crate::local_tools::handle_tool_call`].
+const SERVER_TOOLS: &[&str] = &[
+    "memory_search",
+    "memory_get_variable",
+    "memory_commit",
+    "spawn_agent",
+    "daemon_list",
+    "daemon_run",
+    crate::code_index::SEMANTIC_SEARCH_TOOL,
+];
+
+/// Shared state behind every request.  Cheap to clone.
+#[derive(Clone)]
+struct McpServerState {
+    rice: Arc<Mutex<RiceStore>>,
+    openai: OpenAiClient,
+    openai_key: Option<String>,
+    events: mpsc::UnboundedSender<AgentEvent>,
+}
+
+/// Entry point for `memini serve-mcp [--http [addr]]`.
+pub(crate) fn serve(args: &[String]) -> Result<()> {
+    let http_addr = match args.iter().position(|arg| arg == "--http") {
+        Some(index) => Some(
+            args.get(index + 1)
+                .filter(|value| !value.starts_with("--"))
+                .cloned()
+                .unwrap_or_else(|| DEFAULT_HTTP_ADDR.to_string()),
+        ),
+        None => None,
+    };
+    if let Some(unknown) = args
+        .iter()
+        .find(|arg| arg.starts_with("--") && *arg != "--http" && *arg != ALLOW_REMOTE_FLAG)
+    {
+        bail!("Unknown serve-mcp option {unknown} (expected --http [addr] [{ALLOW_REMOTE_FLAG}])");
+    }
+    let allow_remote = args.iter().any(|arg| arg == ALLOW_REMOTE_FLAG);
+
+    let runtime = tokio::runtime::Runtime::new().context("start tokio runtime")?;
+    let telemetry = {
+        let _guard = runtime.enter();
+        crate::telemetry::init()
+    };
+    if let Err(err) = telemetry {
+        eprintln!("{APP_NAME}: OTLP export disabled: {err:#}");
+    }
+    if let Some(err) = crate::config::load_error() {
+        eprintln!("{APP_NAME}: memini.toml not loaded, using defaults: {err}");
+    }
+    if let Err(err) = policy::reload() {
+        eprintln!("{APP_NAME}: policy does not load, denying every tool call: {err:#}");
+    }
+    let state = runtime.block_on(McpServerState::connect());
+
+    match http_addr {
+        Some(addr) => serve_http(&runtime, state, &addr, allow_remote),
+        None => serve_stdio(&runtime, state),
+    }
+}
+
+impl McpServerState {
+    async fn connect() -> Self {
+        let mut rice = RiceStore::connect().await;
+        let mut openai = OpenAiClient::new();
+        if let Ok(Some(Value::String(model))) = rice.get_variable(OPENAI_MODEL_VAR).await {
+            openai.model = model;
+        }
+        if let Ok(Some(routes)) = rice.get_variable(MODEL_ROUTES_VAR).await
+            && !routes.is_null()
+        {
+            if let Ok(routes) = serde_json::from_value(routes) {
+                openai.routes = routes;
+            }
+        }
+        let openai_key = match std::env::var("OPENAI_API_KEY") {
+            Ok(key) if !key.trim().is_empty() => Some(key),
+            _ => match rice.get_variable(OPENAI_KEY_VAR).await {
+                Ok(Some(Value::String(key))) => Some(key),
+                _ => None,
+            },
+        };
+        eprintln!(
+            "{APP_NAME}: MCP server ready (rice: {}, model: {})",
+            rice.status_label(),
+            openai.model
+        );
+
+        let (events, mut receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(event) = receiver.recv().await {
+                match event {
+                    AgentEvent::ApprovalRequested {
+                        approval_id, tool, ..
+                    } => {
+                        eprintln!("{APP_NAME}: {tool} needs approval; denied in serve-mcp mode");
+                        policy::resolve_approval(approval_id, false);
+                    }
+                    AgentEvent::PolicyDenied { tool, reason, .. } => {
+                        eprintln!("{APP_NAME}: policy blocked {tool}: {reason}");
+                    }
+                    _ => {}
+                }
+            }
+        });
+
+        McpServerState {
+            rice: Arc::new(Mutex::new(rice)),
+            openai,
+            openai_key,
+            events,
+        }
+    }
+
+    /// Handle one JSON-RPC message; `None` for notifications.
+    async fn handle_message(&self, message: Value) -> Option<Value> {
+        let id = message.get("id").cloned()?;
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let result = match method {
+            "initialize" => Ok(json!({
+                "protocolVersion": params
+                    .get("protocolVersion")
+                    .and_then(Value::as_str)
+                    .unwrap_or(PROTOCOL_VERSION),
+                "capabilities": { "tools": { "listChanged": false } },
+                "serverInfo": { "name": APP_NAME, "version": APP_VERSION },
+            })),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tool_list() })),
+            "tools/call" => Ok(self.call_tool(&params).await),
+            other => Err((-32601, format!("Method not found: {other}"))),
+        };
+
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        })
+    }
+
+    /// Run a `tools/call` and wrap the output as MCP text content.
+    async fn call_tool(&self, params: &Value) -> Value {
+        let name = params.get("name").and_then(Value::as_str).unwrap_or("");
+        let arguments = params
+            .get("arguments")
+            .cloned()
+            .unwrap_or_else(|| json!({}));
+        let (text, is_error) = match self.run_tool(name, &arguments).await {
+            Ok(text) => (text, false),
+            Err(err) => (format!("{err:#}"), true),
+        };
+        json!({
+            "content": [{ "type": "text", "text": text }],
+            "isError": is_error,
+        })
+    }
+
+    async fn run_tool(&self, name: &str, arguments: &Value) -> Result<String> {
+        let call = ToolCall {
+            name: name.to_string(),
+            arguments: arguments.clone(),
+            call_id: format!("mcp-{}", Local::now().timestamp_millis()),
+        };
+        if let Some(blocked) = policy::enforce(&call, POLICY_CONTEXT, &self.events).await {
+            bail!("{blocked}");
+        }
+
+        match name {
+            "memory_search" => {
+                let query = str_arg(arguments, "query").unwrap_or("recent activity");
+                let limit = arguments
+                    .get("limit")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(6)
+                    .clamp(1, 50);
+                let traces = self
+                    .rice
+                    .lock()
+                    .await
+                    .reminisce(vec![], limit, query)
+                    .await?;
+                let memories: Vec<Value> = traces
+                    .iter()
+                    .map(|trace| {
+                        json!({
+                            "input": trace.input,
+                            "action": trace.action,
+                            "outcome": trace.outcome,
+                            "agent_id": trace.agent_id,
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::to_string_pretty(&memories)?)
+            }
+            "memory_get_variable" => {
+                let key = str_arg(arguments, "key").ok_or_else(|| anyhow!("key is required"))?;
+                let value = self.rice.lock().await.get_variable(key).await?;
+                Ok(serde_json::to_string_pretty(&value.unwrap_or(Value::Null))?)
+            }
+            "memory_commit" => {
+                let input =
+                    str_arg(arguments, "input").ok_or_else(|| anyhow!("input is required"))?;
+                let outcome =
+                    str_arg(arguments, "outcome").ok_or_else(|| anyhow!("outcome is required"))?;
+                let agent = str_arg(arguments, "agent").unwrap_or("external");
+                self.rice
+                    .lock()
+                    .await
+                    .commit_trace(
+                        input,
+                        outcome,
+                        "mcp:memory_commit",
+                        vec![],
+                        &crate::rice::agent_id_for(agent),
+                    )
+                    .await?;
+                Ok("Committed to memory.".to_string())
+            }
+            "spawn_agent" => {
+                let prompt =
+                    str_arg(arguments, "prompt").ok_or_else(|| anyhow!("prompt is required"))?;
+                let label = str_arg(arguments, "label").unwrap_or("mcp-agent");
+                let def = DaemonTaskDef {
+                    name: label.to_string(),
+                    persona: str_arg(arguments, "persona")
+                        .map(str::to_string)
+                        .unwrap_or_else(crate::prompts::default_memini_persona),
+                    prompt: prompt.to_string(),
+                    tools: string_list(arguments, "tools"),
+                    ..Default::default()
+                };
+                self.run_agent(&def).await
+            }
+            "daemon_list" => {
+                let tasks: Vec<Value> = known_tasks()
+                    .iter()
+                    .map(|def| {
+                        json!({
+                            "name": def.name,
+                            "interval_secs": def.interval_secs,
+                            "prompt": def.prompt,
+                            "depends_on": def.depends_on,
+                            "pipe_output_to": def.pipe_output_to,
+                        })
+                    })
+                    .collect();
+                Ok(serde_json::to_string_pretty(&tasks)?)
+            }
+            "daemon_run" => {
+                let task = str_arg(arguments, "name").ok_or_else(|| anyhow!("name is required"))?;
+                let def = known_tasks()
+                    .into_iter()
+                    .find(|def| def.name.eq_ignore_ascii_case(task))
+                    .ok_or_else(|| anyhow!("Unknown daemon task '{task}'"))?;
+                self.run_agent(&def).await
+            }
+            crate::code_index::SEMANTIC_SEARCH_TOOL => {
+                let key = self
+                    .openai_key
+                    .as_deref()
+                    .ok_or_else(|| anyhow!("OpenAI key not configured (set OPENAI_API_KEY)"))?;
+                Ok(crate::code_index::handle_tool_call(&call, &self.openai, key).await)
+            }
+            // Workspace, sqlite, http and plugin tools.
+            _ => crate::local_tools::handle_tool_call(&call)
+                .await
+                .ok_or_else(|| anyhow!("Unknown tool '{name}'")),
+        }
+    }
+
+    /// Run a task definition once, headless, and record it in memory.
+    async fn run_agent(&self, def: &DaemonTaskDef) -> Result<String> {
+        let key = self
+            .openai_key
+            .as_deref()
+            .ok_or_else(|| anyhow!("OpenAI key not configured (set OPENAI_API_KEY)"))?;
+        // A dedicated Rice connection keeps long runs from holding the
+        // shared one.
+        let mut rice = RiceStore::connect().await;
+        let artifacts = ArtifactRun::start(&def.name);
+        let daemon::DaemonOutput {
+            text: output,
+            model,
+            provenance,
+        } = daemon::run_daemon_task_once(
+            def,
+            &self.openai,
+            key,
+            &mut rice,
+            &self.events,
+            &artifacts,
+        )
+        .await;
+        artifacts.save_output(&output);
+        let _ = rice
+            .commit_model_trace(
+                &def.prompt,
+                &output,
+                &format!("mcp:{}", def.name),
+                &format!("{APP_NAME}:{}", def.name),
+                &model,
+                &provenance,
+            )
+            .await;
+        if output.starts_with("Error:") {
+            bail!("{output}");
+        }
+        Ok(output)
+    }
+}
+
+/// Built-in tasks plus recipes from the agents directory.
+fn known_tasks() -> Vec<DaemonTaskDef> {
+    let mut defs = daemon::builtin_tasks();
+    for recipe in agent_recipes::load_agent_recipes().unwrap_or_default() {
+        if !defs
+            .iter()
+            .any(|d| d.name.eq_ignore_ascii_case(&recipe.name))
+        {
+            defs.push(App::daemon_def_from_recipe(&recipe, false));
+        }
+    }
+    defs
+}
+
+fn str_arg<'a>(arguments: &'a Value, key: &str) -> Option<&'a str> {
+    arguments
+        .get(key)
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+fn string_list(arguments: &Value, key: &str) -> Vec<String> {
+    arguments
+        .get(key)
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(Value::as_str)
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// MCP tool descriptors: memini's own tools plus the workspace tools.
+fn tool_list() -> Vec<Value> {
+    let mut tools = vec![
+        json!({
+            "name": "memory_search",
+            "description": "Search memini's shared Rice memory for relevant traces.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "What to look for." },
+                    "limit": { "type": "integer", "description": "Max results (default 6, max 50)." }
+                },
+                "required": ["query"]
+            }
+        }),
+        json!({
+            "name": "memory_get_variable",
+            "description": "Read a Rice state variable by key.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "key": { "type": "string" } },
+                "required": ["key"]
+            }
+        }),
+        json!({
+            "name": "memory_commit",
+            "description": "Record a fact or outcome in memini's shared memory.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "input": { "type": "string", "description": "What was asked or observed." },
+                    "outcome": { "type": "string", "description": "What was concluded or done." },
+                    "agent": { "type": "string", "description": "Name to attribute the trace to." }
+                },
+                "required": ["input", "outcome"]
+            }
+        }),
+        json!({
+            "name": "spawn_agent",
+            "description": "Run a memini agent on a prompt until it finishes and return its output. Agents can use Rice memory and the workspace tools.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "prompt": { "type": "string" },
+                    "label": { "type": "string", "description": "Short name recorded with the run." },
+                    "persona": { "type": "string", "description": "Optional persona / system instructions." },
+                    "tools": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Workspace tool selectors (default all, or [\"none\"])."
+                    }
+                },
+                "required": ["prompt"]
+            }
+        }),
+        json!({
+            "name": "daemon_list",
+            "description": "List built-in and recipe daemon tasks.",
+            "inputSchema": { "type": "object", "properties": {} }
+        }),
+        json!({
+            "name": "daemon_run",
+            "description": "Run a daemon task once and return its output.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "name": { "type": "string" } },
+                "required": ["name"]
+            }
+        }),
+    ];
+    tools.extend(crate::local_tools::tool_defs().into_iter().map(|def| {
+        json!({
+            "name": def["name"],
+            "description": def["description"],
+            "inputSchema": def["parameters"],
+        })
+    }));
+    tools
+}
+
+// ── Transports ───────────────────────────────────────────────────────
+
+/// Newline-delimited JSON-RPC on stdin/stdout.  Requests are handled
+/// concurrently; responses are written as they complete, and in-flight
+/// requests are finished before exiting at end of input.
+fn serve_stdio(runtime: &tokio::runtime::Runtime, state: McpServerState) -> Result<()> {
+    let stdin = std::io::stdin();
+    let mut in_flight = Vec::new();
+    for line in stdin.lock().lines() {
+        let line = line.context("read stdin")?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        in_flight.retain(|handle: &tokio::task::JoinHandle<()>| !handle.is_finished());
+        let state = state.clone();
+        in_flight.push(runtime.spawn(async move {
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => state.handle_message(message).await,
+                Err(err) => Some(parse_error(&err)),
+            };
+            if let Some(response) = response {
+                let mut stdout = std::io::stdout().lock();
+                let _ = writeln!(stdout, "{response}");
+                let _ = stdout.flush();
+            }
+        }));
+    }
+    runtime.block_on(async {
+        for handle in in_flight {
+            let _ = handle.await;
+        }
+    });
+    Ok(())
+}
+
+/// JSON-RPC over HTTP POST on `addr`.
+fn serve_http(
+    runtime: &tokio::runtime::Runtime,
+    state: McpServerState,
+    addr: &str,
+    allow_remote: bool,
+) -> Result<()> {
+    let token = std::env::var(SERVER_TOKEN_ENV)
+        .ok()
+        .map(|token| token.trim().to_string())
+        .filter(|token| !token.is_empty())
+        .ok_or_else(|| anyhow!("set {SERVER_TOKEN_ENV} to serve MCP over HTTP"))?;
+    let resolved: Vec<_> = addr
+        .to_socket_addrs()
+        .with_context(|| format!("resolve {addr}"))?
+        .collect();
+    if !allow_remote && resolved.iter().any(|addr| !addr.ip().is_loopback()) {
+        bail!("{addr} is not a loopback address; pass {ALLOW_REMOTE_FLAG} to serve on it");
+    }
+    let server = Server::http(addr).map_err(|err| anyhow!("bind MCP server on {addr}: {err}"))?;
+    eprintln!("{APP_NAME}: serving MCP on http://{addr}/mcp");
+
+    for mut request in server.incoming_requests() {
+        let body = match accept_request(&mut request, &token) {
+            Ok(body) => body,
+            Err((status, message)) => {
+                let _ = request.respond(Response::from_string(message).with_status_code(status));
+                continue;
+            }
+        };
+
+        let state = state.clone();
+        runtime.spawn(async move {
+            let response = match serde_json::from_str::<Value>(&body) {
+                Ok(message) => state.handle_message(message).await,
+                Err(err) => Some(parse_error(&err)),
+            };
+            let reply = match response {
+                Some(response) => Response::from_string(response.to_string())
+                    .with_header(json_header())
+                    .with_status_code(200),
+                None => Response::from_string("").with_status_code(202),
+            };
+            let _ = request.respond(reply);
+        });
+    }
+    Ok(())
+}
+
+/// Check method, origin, token and size, and read the body.
+fn accept_request(
+    request: &mut tiny_http::Request,
+    token: &str,
+) -> std::result::Result<String, (u16, &'static str)> {
+    if *request.method() != Method::Post {
+        return Err((405, "method not allowed"));
+    }
+    if header_value(request, "Origin").is_some_and(|origin| !is_local_origin(&origin)) {
+        return Err((403, "origin not allowed"));
+    }
+    let expected = format!("Bearer {token}");
+    let authorized = header_value(request, "Authorization")
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()));
+    if !authorized {
+        return Err((401, "unauthorized"));
+    }
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_MCP_BODY_BYTES)
+    {
+        return Err((413, "request body too large"));
+    }
+    let mut raw = Vec::new();
+    if request
+        .as_reader()
+        .take(MAX_MCP_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut raw)
+        .is_err()
+    {
+        return Err((400, "bad request"));
+    }
+    if raw.len() > MAX_MCP_BODY_BYTES {
+        return Err((413, "request body too large"));
+    }
+    String::from_utf8(raw).map_err(|_| (400, "body is not UTF-8"))
+}
+
+fn header_value(request: &tiny_http::Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str().to_string())
+}
+
+/// Whether a browser `Origin` is a page served from this machine.
+fn is_local_origin(origin: &str) -> bool {
+    let Ok(url) = url::Url::parse(origin) else {
+        return false;
+    };
+    match url.host() {
+        Some(url::Host::Domain(domain)) => domain.eq_ignore_ascii_case("localhost"),
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    }
+}
+
+fn json_header() -> Header {
+    Header::from_bytes("Content-Type", "application/json").expect("static header")
+}
+
+fn parse_error(err: &serde_json::Error) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": Value::Null,
+        "error": { "code": -32700, "message": format!("Parse error: {err}") },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lists_memini_and_workspace_tools() {
+        let tools = tool_list();
+        let names: Vec<&str> = tools
+            .iter()
+            .filter_map(|tool| tool["name"].as_str())
+            .collect();
+        for expected in [
+            "memory_search",
+            "spawn_agent",
+            "daemon_run",
+            "workspace_read_file",
+        ] {
+            assert!(names.contains(&expected), "missing {expected}");
+        }
+        assert!(tools.iter().all(|tool| tool["inputSchema"].is_object()));
+    }
+
+    #[tokio::test]
+    async fn every_listed_tool_dispatches() {
+        for tool in tool_list() {
+            let name = tool["name"].as_str().expect("tool name");
+            if SERVER_TOOLS.contains(&name) {
+                continue;
+            }
+            let call = ToolCall {
+                name: name.to_string(),
+                arguments: json!({}),
+                call_id: "test".to_string(),
+            };
+            assert!(
+                crate::local_tools::handle_tool_call(&call).await.is_some(),
+                "{name} is listed but not handled"
+            );
+        }
+    }
+
+    #[test]
+    fn only_local_origins_are_accepted() {
+        assert!(is_local_origin("http://localhost:3000"));
+        assert!(is_local_origin("http://127.0.0.1:8765"));
+        assert!(is_local_origin("http://[::1]"));
+        assert!(!is_local_origin("https://evil.example"));
+        assert!(!is_local_origin("null"));
+    }
+}
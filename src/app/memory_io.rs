@@ -0,0 +1,317 @@
+//! `/memory export` and `/memory import` — JSONL dumps of the active run's
+//! Rice traces and state variables for backup, inspection, and moving
+//! memory between Rice instances.
+//!
+//! Each line is one [`MemoryRecord`], tagged by `kind`: a `header` naming
+//! the source run, then `trace` and `variable` records.  Import replays
+//! traces with `commit_trace` (re-embedding those exported without a
+//! vector when an OpenAI key is available) and restores variables with
+//! `set_variable`, all into the currently active run.
+//!
+//! Rice has no "list variables" call, so exports cover memini's known
+//! state keys.  Secrets (the OpenAI key, MCP tokens) are left out unless
+//! `--include-secrets` is given.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::{
+    ACTIVE_AGENT_VAR, ACTIVE_MCP_VAR, CONVERSATION_THREAD_VAR, CUSTOM_AGENTS_VAR,
+    MAX_MEMORY_EXPORT_TRACES, MODEL_ROUTES_VAR, NOTIFY_SETTINGS_VAR, OPENAI_KEY_VAR,
+    OPENAI_MODEL_VAR, OPENAI_REASONING_EFFORT_VAR, PRUNED_TRACES_VAR, SESSIONS_VAR, THEME_VAR,
+};
+
+use super::App;
+use super::log_src;
+use super::logging::LogLevel;
+
+/// Format version written in the export header.
+const EXPORT_VERSION: u32 = 1;
+
+/// State variables included in every export.
+const STATE_VARS: &[&str] = &[
+    CONVERSATION_THREAD_VAR,
+    ACTIVE_AGENT_VAR,
+    CUSTOM_AGENTS_VAR,
+    SESSIONS_VAR,
+    OPENAI_MODEL_VAR,
+    OPENAI_REASONING_EFFORT_VAR,
+    MODEL_ROUTES_VAR,
+    ACTIVE_MCP_VAR,
+    NOTIFY_SETTINGS_VAR,
+    THEME_VAR,
+    PRUNED_TRACES_VAR,
+    SEMANTIC_FACTS_VAR,
+];
+
+/// One line of an export file.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum MemoryRecord {
+    Header {
+        version: u32,
+        run_id: String,
+        exported_at: String,
+    },
+    Trace {
+        input: String,
+        #[serde(default)]
+        action: String,
+        #[serde(default)]
+        outcome: String,
+        #[serde(default)]
+        agent_id: String,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        embedding: Vec<f32>,
+    },
+    Variable {
+        name: String,
+        value: Value,
+    },
+}
+
+/// Serialise records as JSON Lines.
+pub(crate) fn to_jsonl(records: &[MemoryRecord]) -> Result<String> {
+    let mut out = String::new();
+    for record in records {
+        out.push_str(&serde_json::to_string(record).context("serialise memory record")?);
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Parse JSON Lines, reporting the failing line number.
+pub(crate) fn parse_jsonl(contents: &str) -> Result<Vec<MemoryRecord>> {
+    let mut records = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record = serde_json::from_str(line)
+            .with_context(|| format!("line {}: not a memory record", index + 1))?;
+        if let MemoryRecord::Header { version, .. } = &record {
+            if *version > EXPORT_VERSION {
+                bail!(
+                    "line {}: export version {version} is newer than this memini",
+                    index + 1
+                );
+            }
+        }
+        records.push(record);
+    }
+    Ok(records)
+}
+
+// ── App integration ──────────────────────────────────────────────────
+
+impl App {
+    /// Write the active run's traces and state variables to `path`.
+    pub(crate) fn export_memory(&mut self, path: &str, include_secrets: bool) {
+        let run_id = self.rice.active_run_id();
+        let traces =
+            match self
+                .runtime
+                .block_on(self.rice.reminisce(vec![], MAX_MEMORY_EXPORT_TRACES, ""))
+            {
+                Ok(traces) => traces,
+                Err(err) => {
+                    log_src!(
+                        self,
+                        LogLevel::Error,
+                        format!("Memory export failed: {err:#}")
+                    );
+                    return;
+                }
+            };
+
+        let mut records = vec![MemoryRecord::Header {
+            version: EXPORT_VERSION,
+            run_id,
+            exported_at: Local::now().to_rfc3339(),
+        }];
+        records.extend(traces.into_iter().map(|trace| MemoryRecord::Trace {
+            input: trace.input,
+            action: trace.action,
+            outcome: trace.outcome,
+            agent_id: trace.agent_id,
+            embedding: trace.embedding,
+        }));
+
+        let mut names: Vec<String> = STATE_VARS.iter().map(|name| name.to_string()).collect();
+        if include_secrets {
+            names.push(OPENAI_KEY_VAR.to_string());
+            names.extend(
+                self.mcp_config
+                    .servers
+                    .iter()
+                    .map(|server| format!("mcp_token_{}", server.id)),
+            );
+        }
+        let mut variable_count = 0;
+        for name in names {
+            if let Ok(Some(value)) = self.runtime.block_on(self.rice.get_variable(&name)) {
+                records.push(MemoryRecord::Variable { name, value });
+                variable_count += 1;
+            }
+        }
+        let trace_count = records.len() - 1 - variable_count;
+
+        let written = to_jsonl(&records).and_then(|contents| {
+            fs::write(Path::new(path), contents).with_context(|| format!("write {path}"))
+        });
+        match written {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Exported {trace_count} trace(s) and {variable_count} variable(s) to {path}."
+                ),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Memory export failed: {err:#}")
+            ),
+        }
+    }
+
+    /// Replay an export file into the active run.
+    pub(crate) fn import_memory(&mut self, path: &str) {
+        if crate::rice::incognito() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "Memory import is off while incognito; run /incognito off first.".to_string()
+            );
+            return;
+        }
+        let records = match fs::read_to_string(path)
+            .with_context(|| format!("read {path}"))
+            .and_then(|contents| parse_jsonl(&contents))
+        {
+            Ok(records) => records,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Error,
+                    format!("Memory import failed: {err:#}")
+                );
+                return;
+            }
+        };
+
+        let openai_key = self.openai_key.clone();
+        let (mut traces, mut variables, mut embedded, mut failed) = (0, 0, 0, 0);
+        for record in records {
+            let result = match record {
+                MemoryRecord::Header { run_id, .. } => {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "Importing memory from run '{run_id}' into '{}'…",
+                            self.rice.active_run_id()
+                        ),
+                    );
+                    continue;
+                }
+                MemoryRecord::Trace {
+                    input,
+                    action,
+                    outcome,
+                    agent_id,
+                    mut embedding,
+                } => {
+                    if embedding.is_empty() {
+                        if let Some(key) = &openai_key {
+                            let text = format!("{input}\n{outcome}");
+                            if let Ok(vector) =
+                                self.runtime.block_on(self.openai.embedding(key, &text))
+                            {
+                                embedding = vector;
+                                embedded += 1;
+                            }
+                        }
+                    }
+                    traces += 1;
+                    self.runtime.block_on(
+                        self.rice
+                            .commit_trace(&input, &outcome, &action, embedding, &agent_id),
+                    )
+                }
+                MemoryRecord::Variable { name, value } => {
+                    variables += 1;
+                    self.runtime
+                        .block_on(self.rice.set_variable(&name, value, "import"))
+                }
+            };
+            if let Err(err) = result {
+                failed += 1;
+                if failed <= 3 {
+                    log_src!(self, LogLevel::Warn, format!("Import error: {err:#}"));
+                }
+            }
+        }
+
+        self.log(
+            LogLevel::Info,
+            format!(
+                "Imported {traces} trace(s) ({embedded} re-embedded) and {variables} variable(s) \
+                from {path}{}.",
+                if failed > 0 {
+                    format!("; {failed} failed")
+                } else {
+                    String::new()
+                }
+            ),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn round_trips_jsonl() {
+        let records = vec![
+            MemoryRecord::Header {
+                version: EXPORT_VERSION,
+                run_id: "memini".to_string(),
+                exported_at: "2026-01-01T00:00:00Z".to_string(),
+            },
+            MemoryRecord::Trace {
+                input: "what is rice?".to_string(),
+                action: "chat".to_string(),
+                outcome: "a memory store".to_string(),
+                agent_id: "memini:memini".to_string(),
+                embedding: Vec::new(),
+            },
+            MemoryRecord::Variable {
+                name: "memini_theme".to_string(),
+                value: json!("dark"),
+            },
+        ];
+        let text = to_jsonl(&records).unwrap();
+        assert_eq!(text.lines().count(), 3);
+        assert!(!text.contains("embedding"));
+        assert_eq!(parse_jsonl(&text).unwrap(), records);
+    }
+
+    #[test]
+    fn rejects_bad_lines_and_newer_versions() {
+        let err =
+            parse_jsonl("{\"kind\":\"variable\",\"name\":\"x\",\"value\":1}\nnope\n").unwrap_err();
+        assert!(format!("{err:#}").contains("line 2"));
+        assert!(
+            parse_jsonl(
+                "{\"kind\":\"header\",\"version\":99,\"run_id\":\"r\",\"exported_at\":\"t\"}"
+            )
+            .is_err()
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! Memory scopes for spawned agent windows.
+//!
+//! Each agent window commits its traces under its own run id,
+//! `<parent>.agent-<id>`, so sub-task noise stays out of the parent's
+//! recall.  A child still recalls the parent's memory, and its final answer
+//! can be promoted upward as one short summary trace.  Set in
+//! `memini.toml`:
+//!
+//! ```toml
+//! [window_memory]
+//! isolate = true          # false: windows share the parent's run id
+//! inherit = true          # children also recall the parent's traces
+//! promote_summary = true  # commit a summary of the final answer upward
+//! ```
+//!
+//! State variables are not scoped, so `agent_result:*` coordination and
+//! `rice_state_*` tools keep working across windows.
+
+use serde::Deserialize;
+
+use crate::constants::PROMOTED_SUMMARY_CHARS;
+use crate::rice::RiceStore;
+
+/// The `[window_memory]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct WindowMemory {
+    #[serde(default = "enabled")]
+    pub isolate: bool,
+    #[serde(default = "enabled")]
+    pub inherit: bool,
+    #[serde(default = "enabled")]
+    pub promote_summary: bool,
+}
+
+fn enabled() -> bool {
+    true
+}
+
+impl Default for WindowMemory {
+    fn default() -> Self {
+        WindowMemory {
+            isolate: true,
+            inherit: true,
+            promote_summary: true,
+        }
+    }
+}
+
+/// The `[window_memory]` section of `memini.toml`.
+pub(crate) fn config() -> &'static WindowMemory {
+    &crate::config::get().window_memory
+}
+
+/// Move a window's Rice connection into its own scope.
+pub(crate) fn enter(rice: &mut RiceStore, window_id: usize, config: &WindowMemory) {
+    if config.isolate {
+        rice.enter_scope(&format!("agent-{window_id}"), config.inherit);
+    }
+}
+
+/// Commit a short summary of a scoped window's final answer to the parent.
+pub(crate) async fn promote(
+    rice: &mut RiceStore,
+    window_id: usize,
+    prompt: &str,
+    output: &str,
+    config: &WindowMemory,
+) {
+    if rice.scope().is_none() || !config.promote_summary || output.trim().is_empty() {
+        return;
+    }
+    let _ = rice
+        .promote_trace(
+            prompt,
+            &summarize(output),
+            &format!("agent-summary:{window_id}"),
+            &format!("memini:agent-{window_id}"),
+        )
+        .await;
+}
+
+/// The first paragraph of `output`, cut at [`PROMOTED_SUMMARY_CHARS`].
+fn summarize(output: &str) -> String {
+    let paragraph = output.trim().split("\n\n").next().unwrap_or_default();
+    if paragraph.chars().count() <= PROMOTED_SUMMARY_CHARS {
+        return paragraph.to_string();
+    }
+    let cut: String = paragraph.chars().take(PROMOTED_SUMMARY_CHARS).collect();
+    format!("{}…", cut.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::mock::MockMemory;
+    use crate::config::MeminiToml;
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn children_commit_into_their_own_scope_and_promote_a_summary() {
+        let memory = Arc::new(MockMemory::default());
+        let mut parent = RiceStore::open(Some(memory.clone())).await;
+        parent
+            .commit_trace(
+                "deploy target",
+                "We deploy to fly.io.",
+                "chat",
+                Vec::new(),
+                "memini:chat",
+            )
+            .await
+            .unwrap();
+
+        let config = MeminiToml::parse("[window_memory]\ninherit = true\n")
+            .unwrap()
+            .window_memory;
+        let mut child = RiceStore::open(Some(memory.clone())).await;
+        enter(&mut child, 3, &config);
+        child
+            .commit_trace(
+                "step 1",
+                "Read the Dockerfile.",
+                "tool",
+                Vec::new(),
+                "memini:agent-3",
+            )
+            .await
+            .unwrap();
+        // The child recalls its own trace and the parent's.
+        assert_eq!(child.reminisce(vec![], 10, "").await.unwrap().len(), 2);
+        promote(
+            &mut child,
+            3,
+            "Check the deploy",
+            "Deploys fine.\n\nDetails…",
+            &config,
+        )
+        .await;
+
+        let traces = memory.traces();
+        let parent_run = parent.active_run_id();
+        assert_eq!(traces[1].run_id, format!("{parent_run}.agent-3"));
+        assert_eq!(traces[2].run_id, parent_run);
+        assert_eq!(traces[2].outcome, "Deploys fine.");
+        // The parent sees the promoted summary but not the sub-task step.
+        let recalled = parent.reminisce(vec![], 10, "").await.unwrap();
+        assert_eq!(recalled.len(), 2);
+        assert!(recalled.iter().all(|trace| trace.input != "step 1"));
+    }
+}
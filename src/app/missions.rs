@@ -0,0 +1,837 @@
+//! Missions — several agent windows working toward one goal, described in
+//! `$MEMINI_HOME/missions/<name>.yaml`:
+//!
+//! ```yaml
+//! goal: Draft the v2 release notes
+//! success:
+//!   - Every merged PR since v1.9 is mentioned
+//!   - The notes fit on one page
+//! agents:
+//!   - id: collector
+//!     persona: researcher
+//!     tools: [workspace_read_file, workspace_list_files]
+//!     prompt: List the PRs merged since v1.9 from CHANGELOG.md.
+//!   - id: writer
+//!     depends_on: [collector]
+//!     isolated: true
+//!     timeout: 20m
+//!     prompt: |
+//!       Write release notes from the collector's list.
+//!       Group them by feature.
+//! ```
+//!
+//! `/mission run <name>` opens a window for every agent whose
+//! `depends_on` are done, with the goal, the success criteria and the
+//! upstream results in its prompt.  `persona` is a saved `/agent` name or
+//! literal persona text; `tools` replaces the persona's tool allowlist.
+//! An `isolated` agent's changes are merged into the workspace when it
+//! finishes, before the agents after it start.  A downstream agent that is
+//! isolated too gets a fresh tree, which in a git repository holds only
+//! tracked files: files the upstream agent added are missing there until
+//! they are committed, though its result is still in the prompt.
+//! When every agent is done, a check window judges the results against
+//! `success` (skipped when there are none).  An agent that times out or is
+//! killed fails the mission and skips whatever depends on it.  The
+//! dashboard's agent pane shows the latest mission's progress.
+//!
+//! The files are parsed with `serde_yaml`.  `tools`, `success` and
+//! `depends_on` also take one comma-separated string.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+
+use super::App;
+use super::agent_recipes::{parse_csv, sanitize_name};
+use super::agents::Agent;
+use super::log_src;
+use super::logging::LogLevel;
+use super::window_control;
+use super::worktrees;
+use crate::util::memini_home;
+
+/// Longest upstream result passed into a downstream agent's prompt.
+const MAX_UPSTREAM_CHARS: usize = 8_000;
+
+/// Line the check window ends its verdict with.
+const VERDICT_PREFIX: &str = "MISSION:";
+
+#[derive(Clone, Debug)]
+pub struct Mission {
+    pub name: String,
+    pub goal: String,
+    pub success: Vec<String>,
+    pub agents: Vec<MissionAgent>,
+    pub path: PathBuf,
+}
+
+#[derive(Clone, Debug)]
+pub struct MissionAgent {
+    pub id: String,
+    pub prompt: String,
+    /// A saved `/agent` name or literal persona text; `None` uses the
+    /// active agent.
+    pub persona: Option<String>,
+    /// Tool allowlist replacing the persona's own.
+    pub tools: Vec<String>,
+    pub depends_on: Vec<String>,
+    /// Work in a `/spawn --isolated` workspace.
+    pub isolated: bool,
+    pub timeout: Option<Duration>,
+}
+
+pub fn missions_dir() -> PathBuf {
+    memini_home().join("missions")
+}
+
+/// Mission files in the missions dir, sorted by name.  Files that do not
+/// parse are returned as errors so `/mission list` can point at them.
+pub fn load_missions() -> Vec<Result<Mission>> {
+    let Ok(entries) = fs::read_dir(missions_dir()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            matches!(
+                path.extension().and_then(|ext| ext.to_str()),
+                Some("yaml" | "yml")
+            )
+        })
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| load_mission(path)).collect()
+}
+
+pub fn find_mission(name: &str) -> Result<Mission> {
+    let name = sanitize_name(name)?;
+    let dir = missions_dir();
+    let path = ["yaml", "yml"]
+        .iter()
+        .map(|ext| dir.join(format!("{name}.{ext}")))
+        .find(|path| path.is_file())
+        .ok_or_else(|| anyhow!("No mission '{name}' in {}", dir.display()))?;
+    load_mission(&path)
+}
+
+fn load_mission(path: &Path) -> Result<Mission> {
+    let raw = fs::read_to_string(path).with_context(|| format!("Read {}", path.display()))?;
+    parse_mission(path, &raw).with_context(|| format!("{}", path.display()))
+}
+
+/// A mission file as written.
+#[derive(Debug, Deserialize)]
+struct MissionFile {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    goal: Option<String>,
+    #[serde(default)]
+    success: Option<Items>,
+    #[serde(default)]
+    agents: Vec<AgentFile>,
+}
+
+/// One entry of `agents:` as written.
+#[derive(Debug, Deserialize)]
+struct AgentFile {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default, alias = "instructions")]
+    prompt: Option<String>,
+    #[serde(default)]
+    persona: Option<String>,
+    #[serde(default)]
+    tools: Option<Items>,
+    #[serde(default, alias = "after")]
+    depends_on: Option<Items>,
+    #[serde(default)]
+    isolated: bool,
+    #[serde(default)]
+    timeout: Option<Timeout>,
+}
+
+/// A list, or one comma-separated value.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Items {
+    List(Vec<String>),
+    Text(String),
+}
+
+impl Items {
+    fn into_vec(self) -> Vec<String> {
+        match self {
+            Items::List(items) => items,
+            Items::Text(text) => parse_csv(&text),
+        }
+    }
+}
+
+/// `timeout: 90` (seconds) or `timeout: 20m`.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Timeout {
+    Seconds(u64),
+    Text(String),
+}
+
+pub(crate) fn parse_mission(path: &Path, raw: &str) -> Result<Mission> {
+    let file: MissionFile = serde_yaml::from_str(raw)?;
+    let name = match file.name {
+        Some(name) => sanitize_name(&name)?,
+        None => path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .unwrap_or("mission")
+            .to_string(),
+    };
+    let Some(goal) = file.goal else {
+        bail!("missing `goal:`");
+    };
+    let success = file.success.map(Items::into_vec).unwrap_or_default();
+    if file.agents.is_empty() {
+        bail!("`agents:` must list at least one agent");
+    }
+
+    let mut agents: Vec<MissionAgent> = Vec::new();
+    for (index, item) in file.agents.into_iter().enumerate() {
+        let id = match item.id {
+            Some(id) => sanitize_name(&id).with_context(|| format!("agents[{index}].id"))?,
+            None => format!("agent-{}", index + 1),
+        };
+        if agents.iter().any(|agent| agent.id == id) {
+            bail!("agents: two agents are called '{id}'");
+        }
+        let prompt = item.prompt.unwrap_or_default().trim_end().to_string();
+        if prompt.trim().is_empty() {
+            bail!("{id}: missing `prompt:`");
+        }
+        let persona = item.persona.filter(|text| !text.trim().is_empty());
+        let tools = item.tools.map(Items::into_vec).unwrap_or_default();
+        let depends_on = item
+            .depends_on
+            .map(Items::into_vec)
+            .unwrap_or_default()
+            .iter()
+            .map(|upstream| sanitize_name(upstream))
+            .collect::<Result<Vec<_>>>()
+            .with_context(|| format!("{id}: depends_on"))?;
+        let timeout = match item.timeout {
+            Some(Timeout::Seconds(secs)) => Some(Duration::from_secs(secs)),
+            Some(Timeout::Text(text)) => Some(
+                window_control::parse_duration(&text).with_context(|| format!("{id}: timeout"))?,
+            ),
+            None => None,
+        };
+        agents.push(MissionAgent {
+            id,
+            prompt,
+            persona,
+            tools,
+            depends_on,
+            isolated: item.isolated,
+            timeout,
+        });
+    }
+
+    for agent in &agents {
+        for upstream in &agent.depends_on {
+            if !agents.iter().any(|other| &other.id == upstream) {
+                bail!("{}: depends_on names unknown agent '{upstream}'", agent.id);
+            }
+        }
+    }
+    // Every agent must become ready eventually, or the graph has a cycle.
+    let mut done: Vec<&str> = Vec::new();
+    while done.len() < agents.len() {
+        let before = done.len();
+        for agent in &agents {
+            if !done.contains(&agent.id.as_str())
+                && agent
+                    .depends_on
+                    .iter()
+                    .all(|upstream| done.contains(&upstream.as_str()))
+            {
+                done.push(&agent.id);
+            }
+        }
+        if done.len() == before {
+            bail!("depends_on forms a cycle");
+        }
+    }
+
+    Ok(Mission {
+        name,
+        goal,
+        success,
+        agents,
+        path: path.to_path_buf(),
+    })
+}
+
+// ── Runs ─────────────────────────────────────────────────────────────
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AgentState {
+    Waiting,
+    Running(usize),
+    Done(usize),
+    Failed(String),
+    /// Not started because something it depends on failed.
+    Skipped,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum MissionStatus {
+    Running,
+    /// The check window is judging the results.
+    Checking(usize),
+    Succeeded,
+    Failed(String),
+}
+
+impl MissionStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(self, MissionStatus::Succeeded | MissionStatus::Failed(_))
+    }
+}
+
+/// One `/mission run`.
+pub struct MissionRun {
+    pub mission: Mission,
+    /// Per agent, in file order.
+    pub states: Vec<AgentState>,
+    outputs: HashMap<String, String>,
+    pub status: MissionStatus,
+    pub started: Instant,
+}
+
+impl MissionRun {
+    pub fn new(mission: Mission) -> Self {
+        let states = vec![AgentState::Waiting; mission.agents.len()];
+        MissionRun {
+            mission,
+            states,
+            outputs: HashMap::new(),
+            status: MissionStatus::Running,
+            started: Instant::now(),
+        }
+    }
+
+    /// Waiting agents whose dependencies are all done.
+    pub fn ready(&self) -> Vec<usize> {
+        (0..self.mission.agents.len())
+            .filter(|&index| self.states[index] == AgentState::Waiting)
+            .filter(|&index| {
+                self.mission.agents[index]
+                    .depends_on
+                    .iter()
+                    .all(|upstream| {
+                        self.index_of(upstream)
+                            .is_some_and(|up| matches!(self.states[up], AgentState::Done(_)))
+                    })
+            })
+            .collect()
+    }
+
+    fn index_of(&self, id: &str) -> Option<usize> {
+        self.mission.agents.iter().position(|agent| agent.id == id)
+    }
+
+    /// The agent running in `window_id`.
+    pub fn agent_in(&self, window_id: usize) -> Option<usize> {
+        self.states
+            .iter()
+            .position(|state| *state == AgentState::Running(window_id))
+    }
+
+    pub fn owns_window(&self, window_id: usize) -> bool {
+        self.agent_in(window_id).is_some() || self.status == MissionStatus::Checking(window_id)
+    }
+
+    /// Prompt for agent `index`: its own prompt framed by the mission goal,
+    /// with the results of what it depends on.
+    pub fn prompt_for(&self, index: usize) -> String {
+        let agent = &self.mission.agents[index];
+        let mut prompt = format!(
+            "You are agent '{}' of the mission '{}'.\nMission goal: {}\n",
+            agent.id, self.mission.name, self.mission.goal
+        );
+        if !self.mission.success.is_empty() {
+            prompt.push_str("The mission succeeds when:\n");
+            for criterion in &self.mission.success {
+                prompt.push_str(&format!("- {criterion}\n"));
+            }
+        }
+        prompt.push_str(&format!("\nYour part:\n{}\n", agent.prompt.trim()));
+        for upstream in &agent.depends_on {
+            let output = self.outputs.get(upstream).map(String::as_str).unwrap_or("");
+            let clipped: String = output.chars().take(MAX_UPSTREAM_CHARS).collect();
+            prompt.push_str(&format!(
+                "\n--- Result of agent '{upstream}' ---\n{clipped}\n"
+            ));
+        }
+        prompt
+    }
+
+    /// Prompt for the window that judges the results against `success`.
+    pub fn check_prompt(&self) -> String {
+        let mut prompt = format!(
+            "Check whether the mission '{}' met its success criteria.\nMission goal: {}\n\nSuccess criteria:\n",
+            self.mission.name, self.mission.goal
+        );
+        for (index, criterion) in self.mission.success.iter().enumerate() {
+            prompt.push_str(&format!("{}. {criterion}\n", index + 1));
+        }
+        for agent in &self.mission.agents {
+            let output = self
+                .outputs
+                .get(&agent.id)
+                .map(String::as_str)
+                .unwrap_or("");
+            let clipped: String = output.chars().take(MAX_UPSTREAM_CHARS).collect();
+            prompt.push_str(&format!(
+                "\n--- Result of agent '{}' ---\n{clipped}\n",
+                agent.id
+            ));
+        }
+        prompt.push_str(&format!(
+            "\nFor each criterion, say whether the results meet it and why. \
+             End with one line: `{VERDICT_PREFIX} PASS` or `{VERDICT_PREFIX} FAIL`."
+        ));
+        prompt
+    }
+
+    /// Record agent `index`'s result.
+    pub fn finish(&mut self, index: usize, output: &str) {
+        if let AgentState::Running(window_id) = self.states[index] {
+            self.states[index] = AgentState::Done(window_id);
+        }
+        let id = self.mission.agents[index].id.clone();
+        self.outputs.insert(id, output.to_string());
+    }
+
+    /// Mark agent `index` failed and skip everything downstream of it.
+    pub fn fail(&mut self, index: usize, reason: &str) {
+        self.states[index] = AgentState::Failed(reason.to_string());
+        let mut failed = vec![self.mission.agents[index].id.clone()];
+        while let Some(id) = failed.pop() {
+            for (other, agent) in self.mission.agents.iter().enumerate() {
+                if self.states[other] == AgentState::Waiting && agent.depends_on.contains(&id) {
+                    self.states[other] = AgentState::Skipped;
+                    failed.push(agent.id.clone());
+                }
+            }
+        }
+    }
+
+    pub fn running(&self) -> usize {
+        self.states
+            .iter()
+            .filter(|state| matches!(state, AgentState::Running(_)))
+            .count()
+    }
+
+    pub fn done(&self) -> usize {
+        self.states
+            .iter()
+            .filter(|state| matches!(state, AgentState::Done(_)))
+            .count()
+    }
+
+    /// `release-notes · 2/3 agents done · running`
+    pub fn summary(&self) -> String {
+        let status = match &self.status {
+            MissionStatus::Running => "running".to_string(),
+            MissionStatus::Checking(_) => "checking success criteria".to_string(),
+            MissionStatus::Succeeded => "succeeded".to_string(),
+            MissionStatus::Failed(reason) => format!("failed: {reason}"),
+        };
+        format!(
+            "{} · {}/{} agents done · {status}",
+            self.mission.name,
+            self.done(),
+            self.mission.agents.len()
+        )
+    }
+}
+
+/// `PASS` / `FAIL` from the check window's last verdict line; `None` when
+/// it gave none.
+fn verdict(output: &str) -> Option<bool> {
+    output.lines().rev().find_map(|line| {
+        let line = line.trim().trim_matches('`').trim();
+        let rest = line
+            .strip_prefix(VERDICT_PREFIX)?
+            .trim()
+            .to_ascii_uppercase();
+        Some(rest.starts_with("PASS"))
+    })
+}
+
+impl App {
+    /// `/mission run <name>`.
+    pub(crate) fn run_mission(&mut self, name: &str) {
+        if self.refuse_while_halted() {
+            return;
+        }
+        let mission = match find_mission(name) {
+            Ok(mission) => mission,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err:#}"));
+                return;
+            }
+        };
+        if self
+            .missions
+            .iter()
+            .any(|run| run.mission.name == mission.name && !run.status.is_finished())
+        {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Mission '{}' is already running; /mission stop {} first.",
+                    mission.name, mission.name
+                )
+            );
+            return;
+        }
+        self.log(
+            LogLevel::Info,
+            format!(
+                "◎ Mission '{}': {} ({} agent(s)).",
+                mission.name,
+                mission.goal,
+                mission.agents.len()
+            ),
+        );
+        self.missions.push(MissionRun::new(mission));
+        self.advance_mission(self.missions.len() - 1);
+    }
+
+    /// `/mission stop <name>`: kill the mission's running windows.
+    pub(crate) fn stop_mission(&mut self, name: &str) {
+        let Some(index) = self
+            .missions
+            .iter()
+            .rposition(|run| run.mission.name == name && !run.status.is_finished())
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("No running mission '{name}'. See /mission status.")
+            );
+            return;
+        };
+        let run = &mut self.missions[index];
+        if let MissionStatus::Checking(window_id) = run.status {
+            window_control::kill(window_id);
+        }
+        run.status = MissionStatus::Failed("stopped".to_string());
+        for state in run.states.iter_mut() {
+            match state {
+                AgentState::Running(window_id) => window_control::kill(*window_id),
+                AgentState::Waiting => *state = AgentState::Skipped,
+                _ => {}
+            }
+        }
+        self.log(LogLevel::Info, format!("Stopping mission '{name}'…"));
+    }
+
+    /// A mission window finished its run with `output`.
+    pub(crate) fn mission_window_finished(&mut self, window_id: usize, output: &str) {
+        let Some(index) = self
+            .missions
+            .iter()
+            .position(|run| run.owns_window(window_id))
+        else {
+            return;
+        };
+        let run = &mut self.missions[index];
+        if run.status == MissionStatus::Checking(window_id) {
+            run.status = match verdict(output) {
+                Some(true) => MissionStatus::Succeeded,
+                Some(false) => MissionStatus::Failed("success criteria not met".to_string()),
+                None => MissionStatus::Failed("the check gave no verdict".to_string()),
+            };
+            let summary = run.summary();
+            self.log(LogLevel::Info, format!("◎ Mission {summary}."));
+            return;
+        }
+        if let Some(agent) = run.agent_in(window_id) {
+            let merged = if run.mission.agents[agent].isolated {
+                self.merge_mission_agent(window_id)
+            } else {
+                Ok(())
+            };
+            if let Err(err) = merged {
+                let reason = format!("could not merge its workspace: {err:#}");
+                let run = &mut self.missions[index];
+                let id = run.mission.agents[agent].id.clone();
+                run.fail(agent, &reason);
+                run.status = MissionStatus::Failed(format!("{id} {reason}"));
+                let summary = run.summary();
+                log_src!(self, LogLevel::Warn, format!("◎ Mission {summary}."));
+                return;
+            }
+            self.missions[index].finish(agent, output);
+        }
+        self.advance_mission(index);
+    }
+
+    /// Merge an isolated mission agent's changes into the workspace, so
+    /// the agents after it work on its output.
+    fn merge_mission_agent(&mut self, window_id: usize) -> Result<()> {
+        let merged = worktrees::merge(window_id)?;
+        if !merged.applied.is_empty() {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "◎ Merged {} file(s) from Agent #{window_id} into the workspace.",
+                    merged.applied.len()
+                ),
+            );
+        }
+        for change in &merged.conflicts {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Kept the workspace's {}: it changed there too (/worktree diff {window_id}).",
+                    change.path
+                )
+            );
+        }
+        Ok(())
+    }
+
+    /// A mission window timed out or was killed.
+    pub(crate) fn mission_window_stopped(&mut self, window_id: usize, reason: &str) {
+        let Some(index) = self
+            .missions
+            .iter()
+            .position(|run| run.owns_window(window_id))
+        else {
+            return;
+        };
+        let run = &mut self.missions[index];
+        if run.status == MissionStatus::Checking(window_id) {
+            run.status = MissionStatus::Failed(format!("the check {reason}"));
+        } else if let Some(agent) = run.agent_in(window_id) {
+            let id = run.mission.agents[agent].id.clone();
+            run.fail(agent, reason);
+            if !run.status.is_finished() {
+                run.status = MissionStatus::Failed(format!("{id} {reason}"));
+            }
+        }
+        let summary = run.summary();
+        if run.running() == 0 {
+            self.log(LogLevel::Warn, format!("◎ Mission {summary}."));
+        }
+    }
+
+    /// Open windows for every ready agent; when all are done, check the
+    /// success criteria.
+    fn advance_mission(&mut self, index: usize) {
+        if self.missions[index].status != MissionStatus::Running {
+            return;
+        }
+        for agent in self.missions[index].ready() {
+            if self.missions[index].status != MissionStatus::Running {
+                return;
+            }
+            self.start_mission_agent(index, agent);
+        }
+        let run = &self.missions[index];
+        if run.status != MissionStatus::Running || run.done() < run.mission.agents.len() {
+            return;
+        }
+        if run.mission.success.is_empty() {
+            self.missions[index].status = MissionStatus::Succeeded;
+            let summary = self.missions[index].summary();
+            self.log(LogLevel::Info, format!("◎ Mission {summary}."));
+            return;
+        }
+        let prompt = self.missions[index].check_prompt();
+        let window_id = self.take_window_id();
+        let label = format!("{}/check", self.missions[index].mission.name);
+        let agent = self.active_agent.clone();
+        self.missions[index].status = MissionStatus::Checking(window_id);
+        self.open_prompt_window(window_id, label, &prompt, None, &agent);
+    }
+
+    fn start_mission_agent(&mut self, index: usize, agent_index: usize) {
+        let spec = self.missions[index].mission.agents[agent_index].clone();
+        let mission_name = self.missions[index].mission.name.clone();
+        let window_id = self.take_window_id();
+        if spec.isolated {
+            if let Err(err) = worktrees::create(window_id) {
+                let reason = format!("could not isolate its workspace: {err:#}");
+                let run = &mut self.missions[index];
+                run.fail(agent_index, &reason);
+                run.status = MissionStatus::Failed(format!("{} {reason}", spec.id));
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Mission '{mission_name}': {} {reason}", spec.id)
+                );
+                return;
+            }
+        }
+        let mut agent = match &spec.persona {
+            None => self.active_agent.clone(),
+            Some(persona) if persona == "memini" => Agent::default(),
+            Some(persona) => match self.custom_agents.iter().find(|a| &a.name == persona) {
+                Some(saved) => saved.clone(),
+                None => Agent {
+                    persona: persona.clone(),
+                    ..self.active_agent.clone()
+                },
+            },
+        };
+        if !spec.tools.is_empty() {
+            agent.tools = spec.tools.clone();
+        }
+        let prompt = self.missions[index].prompt_for(agent_index);
+        self.missions[index].states[agent_index] = AgentState::Running(window_id);
+        self.open_prompt_window(
+            window_id,
+            format!("{mission_name}/{}", spec.id),
+            &prompt,
+            spec.timeout,
+            &agent,
+        );
+        self.log(
+            LogLevel::Info,
+            format!(
+                "◎ Mission '{mission_name}': started {} as Agent #{window_id}.",
+                spec.id
+            ),
+        );
+    }
+
+    fn take_window_id(&self) -> usize {
+        self.next_window_id
+            .fetch_add(1, std::sync::atomic::Ordering::SeqCst)
+    }
+
+    /// The mission shown on the dashboard: the newest running one, else
+    /// the newest.
+    pub(crate) fn dashboard_mission(&self) -> Option<&MissionRun> {
+        self.missions
+            .iter()
+            .rev()
+            .find(|run| !run.status.is_finished())
+            .or_else(|| self.missions.last())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RELEASE: &str = r#"
+# Release notes in two steps.
+goal: Draft the v2 release notes
+success:
+  - Every merged PR since v1.9 is mentioned
+  - "Fits on one page: no more"
+agents:
+  - id: collector
+    persona: researcher
+    tools: [workspace_read_file, workspace_list_files]
+    prompt: List the PRs merged since v1.9.
+  - id: writer
+    depends_on: [collector]
+    isolated: true
+    timeout: 20m
+    prompt: |
+      Write release notes from the list.
+
+      Group them by feature.
+"#;
+
+    #[test]
+    fn parses_agents_dependencies_and_blocks() {
+        let mission = parse_mission(Path::new("release.yaml"), RELEASE).expect("parse");
+        assert_eq!(mission.name, "release");
+        assert_eq!(mission.goal, "Draft the v2 release notes");
+        assert_eq!(mission.success[1], "Fits on one page: no more");
+        let collector = &mission.agents[0];
+        assert_eq!(collector.persona.as_deref(), Some("researcher"));
+        assert_eq!(
+            collector.tools,
+            ["workspace_read_file", "workspace_list_files"]
+        );
+        let writer = &mission.agents[1];
+        assert_eq!(writer.depends_on, ["collector"]);
+        assert!(writer.isolated);
+        assert_eq!(writer.timeout, Some(Duration::from_secs(1200)));
+        assert_eq!(
+            writer.prompt,
+            "Write release notes from the list.\n\nGroup them by feature."
+        );
+    }
+
+    #[test]
+    fn accepts_comma_lists_folded_blocks_and_plain_seconds() {
+        let raw = "goal: g\nsuccess: fast, small\nagents:\n  - id: a\n    tools: workspace_read_file, workspace_list_files\n    timeout: 90\n    prompt: >\n      One\n      line.\n";
+        let mission = parse_mission(Path::new("m.yaml"), raw).expect("parse");
+        assert_eq!(mission.success, ["fast", "small"]);
+        let agent = &mission.agents[0];
+        assert_eq!(agent.tools, ["workspace_read_file", "workspace_list_files"]);
+        assert_eq!(agent.timeout, Some(Duration::from_secs(90)));
+        assert_eq!(agent.prompt, "One line.");
+        assert!(!agent.isolated);
+
+        let bad = "goal: g\nagents:\n  - id: a\n    isolated: maybe\n    prompt: p\n";
+        assert!(parse_mission(Path::new("m.yaml"), bad).is_err());
+    }
+
+    #[test]
+    fn rejects_unknown_dependencies_and_cycles() {
+        let unknown = "goal: g\nagents:\n  - id: a\n    depends_on: [b]\n    prompt: p\n";
+        assert!(
+            parse_mission(Path::new("m.yaml"), unknown)
+                .unwrap_err()
+                .to_string()
+                .contains("unknown agent 'b'")
+        );
+        let cycle = "goal: g\nagents:\n  - id: a\n    after: b\n    prompt: p\n  - id: b\n    after: a\n    prompt: p\n";
+        assert!(
+            parse_mission(Path::new("m.yaml"), cycle)
+                .unwrap_err()
+                .to_string()
+                .contains("cycle")
+        );
+    }
+
+    #[test]
+    fn runs_wire_outputs_and_skip_after_failures() {
+        let mission = parse_mission(Path::new("release.yaml"), RELEASE).expect("parse");
+        let mut run = MissionRun::new(mission);
+        assert_eq!(run.ready(), [0]);
+        run.states[0] = AgentState::Running(7);
+        assert_eq!(run.agent_in(7), Some(0));
+        run.finish(0, "PR #12, PR #15");
+        assert_eq!(run.ready(), [1]);
+        assert!(run.prompt_for(1).contains("Result of agent 'collector'"));
+        assert!(run.prompt_for(1).contains("PR #12, PR #15"));
+
+        let mut failing = MissionRun::new(run.mission.clone());
+        failing.fail(0, "timed out");
+        assert_eq!(failing.states[1], AgentState::Skipped);
+        assert!(failing.ready().is_empty());
+
+        assert_eq!(verdict("1. yes\n2. yes\n`MISSION: PASS`"), Some(true));
+        assert_eq!(verdict("MISSION: FAIL — too long"), Some(false));
+        assert_eq!(verdict("looks fine"), None);
+    }
+}
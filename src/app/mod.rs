@@ -6,22 +6,135 @@
 //!
 //! | Module       | Responsibility                            |
 //! |--------------|-------------------------------------------|
+//! | `artifacts`  | Saved run outputs + `save_artifact` tool  |
+//! | `attachments` | `/attach` images for the next chat turn  |
 //! | `chat`       | AI chat flow & tool loops                 |
+//! | `chat_queue` | Messages typed while a chat turn runs     |
+//! | `checkpoints` | Recipe `checkpoints:` that wait for `/reply` |
 //! | `commands`   | Slash-command dispatch & handlers          |
+//! | `completion` | Tab completion + suggestion popup         |
+//! | `concurrency` | `max_concurrent_agents` slots + `/daemon queue` |
+//! | `context_files` | Workspace `MEMINI.md` / `AGENTS.md` / `CLAUDE.md` |
+//! | `critic`     | `[critic]` review of agent results before done |
+//! | `digest`     | Daily/weekly summaries of activity from memory |
+//! | `file_watch` | Workspace watcher for recipe `trigger_paths` |
+//! | `halt`       | `/halt` kill switch for autonomous work   |
+//! | `harness`    | Test driver on the mock backends          |
+//! | `http_inspector` | `/debug http show` popup of captured payloads |
 //! | `input`      | Text-input editing (cursor, insert, etc.) |
+//! | `live_reload` | Reload edited recipes and skills on the fly |
+//! | `log_store`  | Ring-buffer log history, visible-slice render |
 //! | `logging`    | `LogLevel`, `LogLine`, `mask_key`         |
+//! | `mail`       | SMTP delivery for recipe `deliver: email:` |
+//! | `markdown`   | Markdown → styled lines (syntect code)    |
+//! | `mcp_server` | `memini serve-mcp` — memini as an MCP server |
+//! | `memory_io`  | `/memory export` / `import` JSONL dumps   |
+//! | `memory_scope` | Per-window Rice scopes for spawned agents |
+//! | `missions`   | `missions/*.yaml` multi-agent runs (`/mission`) |
+//! | `notifications` | Desktop/bell alerts for background events |
+//! | `output_view` | Agent output search, selection & clipboard |
+//! | `pins`       | `/pin` text and files for every chat turn |
+//! | `pipeline`   | `depends_on` / `pipe_output_to` task DAGs |
+//! | `policy`     | Tool permission rules from `memini.toml`  |
+//! | `processes`  | `process_*` background processes per agent window |
+//! | `progress`   | Spinners for long operations (status bar + ticker) |
+//! | `prompt_inspect` | Last chat turn's request for `/prompt show` |
+//! | `prompt_templates` | `/prompt` templates with `{{variables}}` |
+//! | `recipe_drafts` | `/daemon generate` recipes drafted by the model |
+//! | `recipe_lint` | `/daemon lint` checks with file:line errors |
+//! | `recording`  | JSONL session recordings for `memini replay` |
+//! | `replay`     | `memini replay` viewer with play/pause/seek |
+//! | `rest_api`   | Local HTTP API for editors and scripts    |
+//! | `review`     | `/review` queue of daemon-proposed writes |
+//! | `sampling`   | MCP `sampling/createMessage` via the chat model |
+//! | `schemas`    | `/json` schemas + validated structured output |
+//! | `scripts`    | `/run` `.mm` scripts: parse + step runner |
+//! | `secrets`    | `/secret` store for recipe `env:` names   |
+//! | `sessions`   | Named conversation session snapshots      |
+//! | `shell_sessions` | Persistent `shell_*` sessions per agent window |
+//! | `snapshot`   | Whole-app snapshots for tests and bug reports |
+//! | `shutdown`   | Graceful quit + `/spawn resume` of unfinished agents |
+//! | `sinks`      | Slack/Discord webhooks for results and waits |
 //! | `store`      | Local on-disk MCP credential cache        |
+//! | `sub_agents` | Sub-agent results for `wait_for_agent`    |
+//! | `theme`      | Built-in and `theme.toml` color themes    |
+//! | `timeline`   | Model/tool timings of a turn for `/trace` |
+//! | `tool_failures` | Error envelopes, MCP retry, failed-call summary |
+//! | `tool_form`  | Schema-driven prompts for `/mcp call`     |
+//! | `tool_loops` | Round limit + repeated-call detection in tool loops |
+//! | `tool_results` | Size limits + summaries for big tool output |
 //! | `ui`         | TUI rendering & status-bar helpers        |
+//! | `voice`      | Microphone capture for `/voice` dictation |
+//! | `webhooks`   | Local HTTP listener that wakes daemons    |
+//! | `window_control` | Agent window timeouts and `/kill`     |
+//! | `worktrees`  | Isolated workspaces for `/spawn --isolated` |
 
 mod agent_recipes;
 mod agents;
+mod artifacts;
+mod attachments;
 mod chat;
+mod chat_queue;
+mod checkpoints;
 mod commands;
+mod completion;
+mod concurrency;
+pub(crate) mod context_files;
+pub(crate) mod critic;
 mod daemon;
+pub(crate) mod digest;
+mod file_watch;
+mod halt;
+#[cfg(test)]
+mod harness;
+mod http_inspector;
 mod input;
+pub(crate) mod live_reload;
+mod log_store;
 mod logging;
+pub(crate) mod mail;
+mod markdown;
+pub(crate) mod mcp_server;
+mod memory_io;
+pub(crate) mod memory_scope;
+mod missions;
+mod notifications;
+mod output_view;
+mod pins;
+mod pipeline;
+pub(crate) mod policy;
+pub(crate) mod processes;
+pub(crate) mod progress;
+mod prompt_inspect;
+mod prompt_templates;
+mod recipe_drafts;
+mod recipe_lint;
+pub(crate) mod recording;
+pub(crate) mod replay;
+mod rest_api;
+mod review;
+mod sampling;
+mod schemas;
+mod scripts;
+mod secrets;
+mod sessions;
+pub(crate) mod shell_sessions;
+mod shutdown;
+pub(crate) mod sinks;
+pub(crate) mod snapshot;
 mod store;
+mod sub_agents;
+mod theme;
+mod timeline;
+mod tool_failures;
+mod tool_form;
+pub(crate) mod tool_loops;
+pub(crate) mod tool_results;
 mod ui;
+mod voice;
+mod webhooks;
+mod window_control;
+mod worktrees;
 
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
@@ -29,12 +142,15 @@ use std::sync::atomic::AtomicUsize;
 use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
-use chrono::Local;
 use crossterm::event::{Event, KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 
-use crate::constants::{DEFAULT_MEMORY_LIMIT, MAX_DAEMON_RESULTS, MAX_LOGS};
+use crate::backend::Backends;
+use crate::constants::{
+    COMMAND_STREAM_LINES, DEFAULT_MEMORY_LIMIT, FILE_TRIGGER_DEBOUNCE_MS, MAX_DAEMON_RESULTS,
+    MAX_LOGS,
+};
 use crate::mcp::McpConnection;
 use crate::mcp::config::{McpConfig, McpServer, McpSource};
 use crate::mcp::oauth::PendingOAuth;
@@ -43,16 +159,21 @@ use crate::rice::{RiceStatus, RiceStore};
 use crate::util::env_first;
 
 use self::agents::Agent;
-use self::daemon::{AgentEvent, AgentWindow, AgentWindowStatus, ChatLogLevel, DaemonHandle};
+use self::daemon::{
+    AgentEvent, AgentWindow, AgentWindowStatus, ChatLogLevel, DaemonHandle, RunningCommand,
+};
 use self::logging::{LogContent, LogLevel, LogLine};
+use self::notifications::{NotifyEvent, NotifySettings};
+use self::sinks::SinkEvent;
 use self::store::{LocalMcpStore, load_local_mcp_store};
+use self::sub_agents::{SubAgentResults, SubAgentState};
 
 // ── View modes ───────────────────────────────────────────────────────
 
 /// Which top-level screen the TUI is showing.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub(crate) enum ViewMode {
-    /// Home dashboard — status bar + 3×3 agent grid.
+    /// Home dashboard — status bar, activity log, and paged agent grid.
     Dashboard,
     /// Full-screen session for a single agent window (by id).
     AgentSession(usize),
@@ -147,9 +268,11 @@ const TRIGGER_RUN_COOLDOWN_SECS: u64 = 5;
 /// them hidden from the rest of the crate.
 pub struct App {
     pub(crate) runtime: Runtime,
+    /// Model, MCP and memory backends (swapped for mocks in tests).
+    pub(crate) backends: Backends,
     pub(crate) input: String,
     pub(crate) cursor: usize,
-    pub(crate) logs: Vec<LogLine>,
+    pub(crate) logs: log_store::LogStore,
     pub(crate) mcp_config: McpConfig,
     pub(crate) mcp_source: McpSource,
     pub(crate) active_mcp: Option<McpServer>,
@@ -165,9 +288,16 @@ pub struct App {
     pub(crate) openai: OpenAiClient,
     pub(crate) memory_limit: u64,
     pub(crate) pending_oauth: Option<(String, PendingOAuth)>,
-    pub(crate) scroll_offset: u16,
+    pub(crate) scroll_offset: usize,
+    /// `logs.pushed()` at the last draw, for the log's scroll lock.
+    pub(crate) log_seen: usize,
+    /// Entries that arrived below the scrolled-up activity log.
+    pub(crate) log_unread: usize,
+    /// This session's recording, when [`recording::enabled`].
+    pub(crate) recorder: Option<recording::Recorder>,
     pub(crate) should_quit: bool,
     pub(crate) show_side_panel: bool,
+    pub(crate) notify_settings: NotifySettings,
     // Input history (up/down arrow cycling)
     pub(crate) input_history: Vec<String>,
     pub(crate) history_index: Option<usize>,
@@ -176,10 +306,32 @@ pub struct App {
     pub(crate) daemon_tx: mpsc::UnboundedSender<AgentEvent>,
     pub(crate) daemon_rx: mpsc::UnboundedReceiver<AgentEvent>,
     pub(crate) daemon_handles: Vec<DaemonHandle>,
+    // Outcomes of windows spawned by the chat's `spawn_agent` tool
+    pub(crate) sub_agents: SubAgentResults,
+    // `/mission run` runs of this session, oldest first
+    pub(crate) missions: Vec<missions::MissionRun>,
+    // Windows opened by daemon `checkpoints:`, mapped to the checkpoint
+    // their run waits on
+    pub(crate) checkpoint_windows: HashMap<usize, usize>,
+    // Upstream outputs collected for pipeline tasks not yet ready to run
+    pub(crate) pipeline_inbox: pipeline::PipelineInbox,
     pub(crate) daemon_results: Vec<(String, String, String)>, // (task_name, message, timestamp)
     pub(crate) rice_trigger_listener: Option<tokio::task::JoinHandle<()>>,
     pub(crate) trigger_last_run: HashMap<String, Instant>,
     pub(crate) window_active_runs: HashMap<usize, usize>,
+    // Workspace file watcher + debounced `trigger_paths` runs (task -> changes)
+    pub(crate) file_watcher: Option<file_watch::WorkspaceWatcher>,
+    pub(crate) pending_file_triggers: HashMap<String, (Instant, Vec<String>)>,
+    // Watcher on the recipe and skill directories (`[live_reload]`)
+    pub(crate) home_watcher: Option<live_reload::HomeWatcher>,
+    // Recipe/skill edits waiting out the live-reload debounce
+    pub(crate) pending_reload: Option<live_reload::PendingReload>,
+    // Local webhook listener (`MEMINI_WEBHOOK_PORT`)
+    pub(crate) webhook_port: Option<u16>,
+    // REST API (`MEMINI_API_PORT`): `POST /chat` calls waiting for the turn
+    pub(crate) api_chat_waiters: Vec<std::sync::mpsc::Sender<rest_api::ApiReply>>,
+    // Last finished chat turn as (message, reply), for `GET /chat`
+    pub(crate) last_chat_reply: Option<(String, String)>,
     // Agent windows (live interactive agents in side panel)
     pub(crate) agent_windows: Vec<AgentWindow>,
     // FIFO queue of window ids waiting for user input.
@@ -189,12 +341,43 @@ pub struct App {
     // Dashboard grid navigation
     pub(crate) view_mode: ViewMode,
     pub(crate) grid_selected: usize, // selected live-agent row index on dashboard
+    pub(crate) grid_page_size: usize, // cards per grid page (set while drawing)
+    // Split mode: agent window streamed beside the activity log
+    pub(crate) split_window: Option<usize>,
+    // Output mode (search/select/copy) inside an agent session
+    pub(crate) output_view: Option<output_view::OutputView>,
+    // `/debug http show` popup over either view
+    pub(crate) http_inspector: Option<http_inspector::HttpInspector>,
+    // Tab-completion popup above the input box
+    pub(crate) completion: Option<completion::Completion>,
+    // Plain mode (`--plain` / `MEMINI_UI=basic`): 16 colors, agent list
+    pub(crate) plain_ui: bool,
     // Chat-in-progress flag (prevents double-sends and shows thinking UI)
     pub(crate) chat_busy: bool,
+    // Messages typed while a turn was in flight (`/queue`)
+    pub(crate) chat_queue: chat_queue::ChatQueue,
+    // Warnings and errors logged so far (`/run` step outcomes)
+    pub(crate) warnings_logged: u64,
+    // Script started by `/run`
+    pub(crate) script: Option<scripts::ScriptRun>,
+    // Images queued by `/attach` for the next chat turn
+    pub(crate) pending_images: Vec<attachments::ImageAttachment>,
+    // What the last chat turn sent to the model (`/prompt show`)
+    pub(crate) last_prompt: Option<prompt_inspect::PromptSnapshot>,
+    // Recipe from `/daemon generate` waiting to be saved
+    pub(crate) recipe_draft: Option<agent_recipes::NewAgentRecipe>,
+    // Model and tool timings of the last chat turn (`/trace last`)
+    pub(crate) last_timeline: Option<timeline::TurnTimeline>,
+    // Set once quitting starts; background work gets until then to stop
+    pub(crate) shutdown_deadline: Option<std::time::Instant>,
+    // Microphone recording started by `/voice`
+    pub(crate) voice_recorder: Option<voice::Recorder>,
     // Tick counter for animations (incremented every frame)
     pub(crate) tick_count: u64,
     // Interactive Rice setup wizard state
     pub(crate) rice_setup_step: Option<RiceSetupStep>,
+    // `/mcp call` argument form awaiting answers
+    pub(crate) tool_form: Option<tool_form::ToolForm>,
     pub(crate) rice_setup_state_url: String,
     pub(crate) rice_setup_state_token: String,
     pub(crate) rice_setup_storage_url: String,
@@ -206,10 +389,35 @@ pub struct App {
 impl App {
     /// Create and initialise a new application instance.
     pub fn new() -> Result<Self> {
+        let mut app = Self::with_backends(Backends::live())?;
+        if recording::enabled() {
+            app.start_recording();
+        }
+        app.bootstrap();
+        Ok(app)
+    }
+
+    /// Switch to plain mode: 16-color styles (the `basic` theme unless
+    /// another one was chosen) and a list instead of the agent grid.
+    pub fn use_plain_ui(&mut self) {
+        self.plain_ui = true;
+        if theme::current().name == "dark" {
+            theme::set(theme::Theme::basic());
+        }
+        theme::set_basic_colors(true);
+    }
+
+    /// An app on `backends`, before [`App::bootstrap`] restores anything
+    /// from Rice or starts background work.
+    fn with_backends(backends: Backends) -> Result<Self> {
         let runtime = Runtime::new().context("create tokio runtime")?;
+        let telemetry = {
+            let _guard = runtime.enter();
+            crate::telemetry::init()
+        };
         let (mcp_config, mcp_source) = McpConfig::load()?;
         let local_mcp_store = load_local_mcp_store();
-        let rice = runtime.block_on(RiceStore::connect());
+        let rice = runtime.block_on(RiceStore::open(backends.memory.clone()));
         let memory_limit = env_first(&["MEMINI_MEMORY_LIMIT"])
             .and_then(|v| v.parse::<u64>().ok())
             .unwrap_or(DEFAULT_MEMORY_LIMIT);
@@ -218,9 +426,10 @@ impl App {
 
         let mut app = App {
             runtime,
+            backends,
             input: String::new(),
             cursor: 0,
-            logs: Vec::new(),
+            logs: log_store::LogStore::new(MAX_LOGS),
             mcp_config,
             mcp_source,
             active_mcp: None,
@@ -233,31 +442,62 @@ impl App {
             conversation_thread: Vec::new(),
             openai_key_hint: None,
             openai_key: None,
-            openai: OpenAiClient::new(),
+            openai: OpenAiClient::with_backend(backends.llm.clone()),
             memory_limit,
             pending_oauth: None,
             scroll_offset: 0,
+            log_seen: 0,
+            log_unread: 0,
+            recorder: None,
             should_quit: false,
             show_side_panel: false,
+            notify_settings: NotifySettings::default(),
             input_history: Vec::new(),
             history_index: None,
             history_stash: String::new(),
             daemon_tx,
             daemon_rx,
             daemon_handles: Vec::new(),
+            sub_agents: SubAgentResults::default(),
+            missions: Vec::new(),
+            checkpoint_windows: HashMap::new(),
+            pipeline_inbox: HashMap::new(),
             daemon_results: Vec::new(),
             rice_trigger_listener: None,
             trigger_last_run: HashMap::new(),
             window_active_runs: HashMap::new(),
+            file_watcher: None,
+            pending_file_triggers: HashMap::new(),
+            home_watcher: None,
+            pending_reload: None,
+            webhook_port: None,
+            api_chat_waiters: Vec::new(),
+            last_chat_reply: None,
             agent_windows: Vec::new(),
             pending_input_queue: VecDeque::new(),
             next_window_id: Arc::new(AtomicUsize::new(1)),
             focused_window: None,
             view_mode: ViewMode::Dashboard,
             grid_selected: 0,
+            grid_page_size: 1,
+            split_window: None,
+            output_view: None,
+            http_inspector: None,
+            completion: None,
+            plain_ui: false,
             chat_busy: false,
+            chat_queue: chat_queue::ChatQueue::default(),
+            warnings_logged: 0,
+            script: None,
+            pending_images: Vec::new(),
+            last_prompt: None,
+            recipe_draft: None,
+            last_timeline: None,
+            shutdown_deadline: None,
+            voice_recorder: None,
             tick_count: 0,
             rice_setup_step: None,
+            tool_form: None,
             rice_setup_state_url: String::new(),
             rice_setup_state_token: String::new(),
             rice_setup_storage_url: String::new(),
@@ -284,12 +524,19 @@ impl App {
             "Type /help for commands, /rice setup to configure Rice.".to_string(),
         );
 
-        app.bootstrap();
+        if let Err(err) = telemetry {
+            log_src!(
+                app,
+                LogLevel::Warn,
+                format!("OTLP export disabled: {err:#}")
+            );
+        }
         Ok(app)
     }
 
     /// Load persisted state from Rice on startup.
     fn bootstrap(&mut self) {
+        sampling::install_hook(self.daemon_tx.clone());
         if let Err(err) = self.load_openai_from_rice() {
             log_src!(
                 self,
@@ -304,6 +551,20 @@ impl App {
                 format!("OpenAI model settings load skipped: {err}")
             );
         }
+        if let Err(err) = self.load_provider_from_rice() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Provider settings load skipped: {err:#}")
+            );
+        }
+        if let Err(err) = self.load_model_routes_from_rice() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Model routes load skipped: {err}")
+            );
+        }
         if let Err(err) = self.load_active_mcp_from_rice() {
             log_src!(
                 self,
@@ -312,9 +573,56 @@ impl App {
             );
         }
 
+        if let Some(err) = crate::config::load_error() {
+            log_src!(
+                self,
+                LogLevel::Error,
+                format!("memini.toml not loaded, using defaults: {err}")
+            );
+        }
+        match policy::reload() {
+            Ok(loaded) if !loaded.rules.is_empty() => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Loaded {} tool policy rule(s).", loaded.rules.len()),
+                );
+            }
+            Ok(_) => {}
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Tool policy does not load, denying every tool call: {err:#}")
+                );
+            }
+        }
+        if let Err(err) = self.load_notify_settings_from_rice() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Notification settings load skipped: {err}")
+            );
+        }
+        if let Err(err) = self.load_theme_from_rice() {
+            log_src!(self, LogLevel::Warn, format!("Theme load skipped: {err:#}"));
+        }
+        if let Some(lang) = env_first(&["MEMINI_LANG"]) {
+            match crate::i18n::load(&lang) {
+                Ok(catalog) => crate::i18n::set(catalog),
+                Err(err) => log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("MEMINI_LANG ignored: {err:#}")
+                ),
+            }
+        }
+
         // Auto-connect MCP servers we already have tokens for.
         self.autoconnect_saved_mcps();
 
+        // Keep the semantic workspace index fresh in the background.
+        self.start_code_indexer();
+
         // Restore custom agents.
         match self.runtime.block_on(self.rice.load_custom_agents()) {
             Ok(Some(value)) => {
@@ -401,7 +709,19 @@ impl App {
 
         // Auto-start recipe-based background agents marked `auto_start: true`.
         self.autostart_daemon_recipes();
+        self.ensure_file_watcher();
+        self.start_live_reload();
+        for problem in crate::http::config_problems() {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("{problem}; using default HTTP settings")
+            );
+        }
         self.restart_rice_trigger_listener();
+        self.start_webhook_listener();
+        self.start_rest_api();
+        self.announce_saved_windows();
     }
 
     /// Whether the user has requested to quit.
@@ -409,6 +729,12 @@ impl App {
         self.should_quit
     }
 
+    /// Open a Rice handle for a background task.
+    pub(crate) fn spawn_rice_handle(&self) -> tokio::task::JoinHandle<RiceStore> {
+        self.runtime
+            .spawn(RiceStore::open(self.backends.memory.clone()))
+    }
+
     fn is_window_waiting(&self, window_id: usize) -> bool {
         self.agent_windows
             .iter()
@@ -477,6 +803,49 @@ impl App {
         remaining
     }
 
+    /// A window run was cancelled (timeout, `/kill` or `/halt`); once no other run
+    /// of the window is in flight it takes `status`.
+    pub(crate) fn stop_window_run(
+        &mut self,
+        window_id: usize,
+        status: AgentWindowStatus,
+        reason: String,
+    ) {
+        let remaining = self.mark_window_run_finished(window_id);
+        let mut label = format!("Agent #{window_id}");
+        if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+            win.output_lines.push(format!("-- {reason} --"));
+            win.running_command = None;
+            if remaining == 0 {
+                win.status = status;
+                win.pending_question = None;
+            }
+            label = win.label.clone();
+        }
+        if remaining > 0 {
+            return;
+        }
+        self.dequeue_waiting_window(window_id);
+        self.checkpoint_window_stopped(window_id);
+        self.mission_window_stopped(window_id, &reason);
+        let tracked = self
+            .sub_agents
+            .record(window_id, SubAgentState::Stopped, &reason);
+        if tracked && !self.chat_busy {
+            self.inject_sub_agent_summaries(true);
+        }
+        self.notify(
+            NotifyEvent::AgentDone,
+            format!("{label} stopped"),
+            reason.clone(),
+        );
+        log_src!(
+            self,
+            LogLevel::Warn,
+            format!("{label} (#{window_id}) {reason}.")
+        );
+    }
+
     fn cycle_live_agent_selection(&mut self, forward: bool) {
         if self.agent_windows.is_empty() {
             return;
@@ -487,6 +856,26 @@ impl App {
         } else {
             (self.grid_selected + len - 1) % len
         };
+        // In split mode the right pane follows the selection.
+        if self.split_window.is_some() {
+            self.split_window = self.agent_windows.get(self.grid_selected).map(|w| w.id);
+        }
+    }
+
+    /// Move the grid selection to the first card of the next/previous page.
+    fn page_live_agents(&mut self, forward: bool) {
+        if self.agent_windows.is_empty() {
+            return;
+        }
+        let page_size = self.grid_page_size.max(1);
+        let page = self.grid_selected / page_size;
+        let pages = self.agent_windows.len().div_ceil(page_size);
+        let target = if forward {
+            (page + 1) % pages
+        } else {
+            (page + pages - 1) % pages
+        };
+        self.grid_selected = target * page_size;
     }
 
     fn parse_inline_agent_reply(line: &str) -> Option<(usize, String)> {
@@ -525,12 +914,20 @@ impl App {
     pub fn tick(&mut self) {
         self.tick_count = self.tick_count.wrapping_add(1);
         self.drain_daemon_events();
+        self.apply_mcp_tool_changes();
+        self.dispatch_queued_chat();
+        self.flush_file_triggers();
+        self.flush_live_reload();
+        self.poll_voice_recording();
+        self.advance_script();
+        self.record_session();
     }
 
     /// Route a terminal event to the appropriate handler.
     pub fn handle_event(&mut self, event: Event) -> Result<()> {
         match event {
             Event::Key(key) => self.handle_key(key)?,
+            Event::Paste(text) => self.handle_paste(text),
             Event::Mouse(mouse) => self.handle_mouse(mouse),
             _ => {}
         }
@@ -539,6 +936,11 @@ impl App {
 
     /// Dispatch a key press to input editing, commands, or control actions.
     fn handle_key(&mut self, key: KeyEvent) -> Result<()> {
+        let quit = key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL);
+        if self.http_inspector.is_some() && !quit {
+            self.handle_http_inspector_key(key);
+            return Ok(());
+        }
         match key {
             KeyEvent {
                 code: KeyCode::Char('c'),
@@ -552,6 +954,19 @@ impl App {
                 ..
             } => self.logs.clear(),
 
+            KeyEvent {
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.paste_clipboard_image(true),
+
+            // Ctrl+X: panic button, same as /halt.
+            KeyEvent {
+                code: KeyCode::Char('x'),
+                modifiers: KeyModifiers::CONTROL,
+                ..
+            } => self.halt_autonomy(),
+
             // Ctrl+1 through Ctrl+9: jump straight into an agent session.
             KeyEvent {
                 code: KeyCode::Char(ch @ '1'..='9'),
@@ -596,12 +1011,22 @@ impl App {
             self.grid_selected = self.agent_windows.len() - 1;
         }
 
+        if self.handle_completion_key(&key) {
+            return Ok(());
+        }
         if Self::should_insert_newline(&key) {
             self.scroll_offset = 0;
             self.history_index = None;
             self.insert_char('\n');
             return Ok(());
         }
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.split_window = match self.split_window {
+                Some(_) => None,
+                None => self.agent_windows.get(self.grid_selected).map(|w| w.id),
+            };
+            return Ok(());
+        }
 
         match key.code {
             // Enter with empty input and a selected agent → open that session.
@@ -624,6 +1049,8 @@ impl App {
                 if self.rice_setup_step.is_some() {
                     self.rice_setup_step = None;
                     self.log(LogLevel::Info, "Rice setup cancelled.".to_string());
+                } else if let Some(form) = self.tool_form.take() {
+                    self.log(LogLevel::Info, format!("Call to {} cancelled.", form.tool));
                 } else if !self.input.is_empty() {
                     self.input.clear();
                     self.cursor = 0;
@@ -643,12 +1070,25 @@ impl App {
             KeyCode::Delete => self.delete(),
             KeyCode::Left => self.move_cursor_left(),
             KeyCode::Right => self.move_cursor_right(),
+            // Home/End jump through log history when not editing input.
+            KeyCode::Home if self.input.is_empty() => self.scroll_offset = usize::MAX,
+            KeyCode::End if self.input.is_empty() || self.scroll_offset > 0 => {
+                self.scroll_offset = 0
+            }
             KeyCode::Home => self.move_cursor_home(),
             KeyCode::End => self.move_cursor_end(),
             KeyCode::Up => self.history_prev(),
             KeyCode::Down => self.history_next(),
+            KeyCode::PageUp if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.page_live_agents(false)
+            }
+            KeyCode::PageDown if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.page_live_agents(true)
+            }
             KeyCode::PageUp => self.scroll_up(10),
             KeyCode::PageDown => self.scroll_down(10),
+            KeyCode::Tab if self.input.starts_with('/') => self.complete_input(true),
+            KeyCode::BackTab if self.input.starts_with('/') => self.complete_input(false),
             KeyCode::Tab => {
                 // Tab on dashboard cycles live-agent selection forward.
                 self.cycle_live_agent_selection(true);
@@ -661,6 +1101,37 @@ impl App {
 
     /// Key handling while in an agent session (full-screen agent view).
     fn handle_session_key(&mut self, key: KeyEvent, window_id: usize) -> Result<()> {
+        if self
+            .output_view
+            .as_ref()
+            .is_some_and(|view| view.window_id == window_id)
+        {
+            self.handle_output_view_key(key);
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('o') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.enter_output_view(window_id);
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('t') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            self.toggle_reasoning(window_id);
+            return Ok(());
+        }
+        if key.code == KeyCode::Char('s') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            // Back to the dashboard with this agent streaming beside the log.
+            self.view_mode = ViewMode::Dashboard;
+            self.focused_window = None;
+            self.output_view = None;
+            self.split_window = Some(window_id);
+            if let Some(idx) = self.agent_windows.iter().position(|w| w.id == window_id) {
+                self.grid_selected = idx;
+            }
+            return Ok(());
+        }
+        if self.handle_completion_key(&key) {
+            return Ok(());
+        }
+
         if Self::should_insert_newline(&key) {
             self.scroll_offset = 0;
             self.history_index = None;
@@ -678,6 +1149,7 @@ impl App {
                 } else {
                     self.view_mode = ViewMode::Dashboard;
                     self.focused_window = None;
+                    self.output_view = None;
                     // Keep grid_selected pointing at this agent.
                     if let Some(idx) = self.agent_windows.iter().position(|w| w.id == window_id) {
                         self.grid_selected = idx;
@@ -703,11 +1175,16 @@ impl App {
             KeyCode::Left => self.move_cursor_left(),
             KeyCode::Right => self.move_cursor_right(),
             KeyCode::Home => self.move_cursor_home(),
+            KeyCode::End if self.input.is_empty() || self.window_scrolled(window_id) => {
+                self.with_window(window_id, AgentWindow::scroll_to_bottom)
+            }
             KeyCode::End => self.move_cursor_end(),
             KeyCode::Up => self.history_prev(),
             KeyCode::Down => self.history_next(),
-            KeyCode::PageUp => self.scroll_up(10),
-            KeyCode::PageDown => self.scroll_down(10),
+            KeyCode::PageUp => self.with_window(window_id, |w| w.scroll_up(10)),
+            KeyCode::PageDown => self.with_window(window_id, |w| w.scroll_down(10)),
+            KeyCode::Tab => self.complete_input(true),
+            KeyCode::BackTab => self.complete_input(false),
             _ => {}
         }
         Ok(())
@@ -725,6 +1202,10 @@ impl App {
             self.handle_rice_setup_input(&trimmed_line, step);
             return Ok(());
         }
+        if self.tool_form.is_some() {
+            self.handle_tool_form_input(&trimmed_line);
+            return Ok(());
+        }
 
         if trimmed_line.is_empty() {
             return Ok(());
@@ -735,7 +1216,7 @@ impl App {
             self.input_history.push(line.clone());
         }
 
-        // If an agent window is focused and waiting for input, reply to it.
+        // If an agent window is focused and waiting or done, continue it.
         if let Some(focused_id) = self.focused_window {
             if self.reply_to_agent_window(focused_id, &line) {
                 return Ok(());
@@ -747,7 +1228,9 @@ impl App {
             if !self.reply_to_agent_window(window_id, &reply) {
                 self.log(
                     LogLevel::Warn,
-                    format!("Agent #{window_id} is not waiting for input. Use /reply list."),
+                    format!(
+                        "Agent #{window_id} is still working or was cancelled. Use /reply list."
+                    ),
                 );
             }
             return Ok(());
@@ -763,9 +1246,10 @@ impl App {
                 return Ok(());
             }
 
-            // Prevent double-sends while the LLM is working.
-            if self.chat_busy {
-                self.log(LogLevel::Info, "Still thinking… please wait.".to_string());
+            // Hold messages typed while the LLM is working, and keep
+            // their order behind any already queued.
+            if self.chat_busy || !self.chat_queue.is_empty() {
+                self.queue_chat_message(&line);
                 return Ok(());
             }
 
@@ -786,20 +1270,40 @@ impl App {
 
 impl App {
     /// Scroll the activity log up by `n` lines.
-    pub(crate) fn scroll_up(&mut self, n: u16) {
+    pub(crate) fn scroll_up(&mut self, n: usize) {
         self.scroll_offset = self.scroll_offset.saturating_add(n);
     }
 
     /// Scroll the activity log down by `n` lines (towards the latest).
-    pub(crate) fn scroll_down(&mut self, n: u16) {
+    pub(crate) fn scroll_down(&mut self, n: usize) {
         self.scroll_offset = self.scroll_offset.saturating_sub(n);
     }
 
-    /// Handle mouse events (scroll wheel / trackpad).
+    /// Run `f` on window `window_id`, if it still exists.
+    fn with_window(&mut self, window_id: usize, f: impl FnOnce(&mut AgentWindow)) {
+        if let Some(window) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+            f(window);
+        }
+    }
+
+    fn window_scrolled(&self, window_id: usize) -> bool {
+        self.agent_windows
+            .iter()
+            .any(|w| w.id == window_id && w.scroll > 0)
+    }
+
+    /// Handle mouse events (scroll wheel / trackpad): the agent output in
+    /// a session, the activity log elsewhere.
     fn handle_mouse(&mut self, mouse: MouseEvent) {
-        match mouse.kind {
-            MouseEventKind::ScrollUp => self.scroll_up(3),
-            MouseEventKind::ScrollDown => self.scroll_down(3),
+        let session = match self.view_mode {
+            ViewMode::AgentSession(window_id) => Some(window_id),
+            _ => None,
+        };
+        match (mouse.kind, session) {
+            (MouseEventKind::ScrollUp, Some(id)) => self.with_window(id, |w| w.scroll_up(3)),
+            (MouseEventKind::ScrollDown, Some(id)) => self.with_window(id, |w| w.scroll_down(3)),
+            (MouseEventKind::ScrollUp, None) => self.scroll_up(3),
+            (MouseEventKind::ScrollDown, None) => self.scroll_down(3),
             _ => {}
         }
     }
@@ -832,30 +1336,19 @@ pub(crate) use log_src;
 impl App {
     /// Append a plain-text message to the activity log.
     pub(crate) fn log(&mut self, level: LogLevel, message: String) {
-        let timestamp = Local::now().format("%H:%M:%S").to_string();
-        self.logs.push(LogLine {
-            timestamp,
-            level,
-            content: LogContent::Plain(message),
-        });
-        if self.logs.len() > MAX_LOGS {
-            let overflow = self.logs.len() - MAX_LOGS;
-            self.logs.drain(0..overflow);
+        if matches!(level, LogLevel::Warn | LogLevel::Error) {
+            self.warnings_logged += 1;
         }
+        self.logs
+            .push(LogLine::new(level, LogContent::Plain(message)));
     }
 
     /// Append markdown content (LLM output) to the activity log.
     pub(crate) fn log_markdown(&mut self, label: String, body: String) {
-        let timestamp = Local::now().format("%H:%M:%S").to_string();
-        self.logs.push(LogLine {
-            timestamp,
-            level: LogLevel::Info,
-            content: LogContent::Markdown { label, body },
-        });
-        if self.logs.len() > MAX_LOGS {
-            let overflow = self.logs.len() - MAX_LOGS;
-            self.logs.drain(0..overflow);
-        }
+        self.logs.push(LogLine::new(
+            LogLevel::Info,
+            LogContent::Markdown { label, body },
+        ));
     }
 
     /// Append a message with a source location suffix (debug-logs builds only).
@@ -872,6 +1365,36 @@ impl App {
 // ── Daemon (background agents) ───────────────────────────────────────
 
 impl App {
+    /// Trim the conversation thread and persist it to Rice (best-effort).
+    fn save_conversation_thread(&mut self) {
+        let max = crate::constants::MAX_THREAD_MESSAGES;
+        while self.conversation_thread.len() > max {
+            self.conversation_thread.drain(0..2);
+        }
+        let _ = self
+            .runtime
+            .block_on(self.rice.save_thread(&self.conversation_thread));
+    }
+
+    /// Append summaries of uncollected sub-agent results to the thread so
+    /// the next chat turn sees them.
+    fn inject_sub_agent_summaries(&mut self, save: bool) {
+        let summaries = self.sub_agents.take_summaries();
+        if summaries.is_empty() {
+            return;
+        }
+        for (label, entry) in summaries {
+            self.log(
+                LogLevel::Info,
+                format!("↩ Added {label}'s result to the conversation."),
+            );
+            self.conversation_thread.push(entry);
+        }
+        if save {
+            self.save_conversation_thread();
+        }
+    }
+
     /// Drain pending background agent events and route them.
     pub(crate) fn drain_daemon_events(&mut self) {
         while let Ok(event) = self.daemon_rx.try_recv() {
@@ -882,6 +1405,7 @@ impl App {
                         if win.status != AgentWindowStatus::WaitingForInput {
                             win.status = AgentWindowStatus::Thinking;
                         }
+                        win.critique = None;
                         if in_flight > 1 {
                             win.output_lines.push(format!(
                                 "-- parallel run started ({in_flight} in flight) --"
@@ -896,6 +1420,71 @@ impl App {
                         win.output_lines.push(line);
                     }
                 }
+                AgentEvent::Reasoning { window_id, summary } => {
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        win.reasoning.extend(summary.lines().map(str::to_string));
+                        win.reasoning.push(String::new());
+                    }
+                }
+                AgentEvent::Critique {
+                    window_id,
+                    critique,
+                } => {
+                    let mut label = format!("Agent #{window_id}");
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        label = win.label.clone();
+                        let verdict = if critique.passed { "ok" } else { "flagged" };
+                        win.output_lines
+                            .push(format!("-- critic ({}): {verdict} --", critique.model));
+                        win.output_lines
+                            .extend(critique.notes.lines().map(str::to_string));
+                        win.critique = Some(critique.clone());
+                    }
+                    if !critique.passed {
+                        self.log_markdown(
+                            format!("{label} · critic ({})", critique.model),
+                            critique.notes,
+                        );
+                    }
+                }
+                AgentEvent::CommandOutput {
+                    window_id,
+                    command,
+                    line,
+                    stdout_lines,
+                    stderr_lines,
+                } => {
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        if win.running_command.is_none() {
+                            win.output_lines.push(format!("$ {command}"));
+                        }
+                        win.running_command = Some(RunningCommand {
+                            command,
+                            stdout_lines,
+                            stderr_lines,
+                        });
+                        if let Some(line) = line {
+                            win.output_lines.push(line);
+                        }
+                    }
+                }
+                AgentEvent::CommandEnded { window_id } => {
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        if let Some(running) = win.running_command.take() {
+                            let total = running.stdout_lines + running.stderr_lines;
+                            if total > COMMAND_STREAM_LINES {
+                                win.output_lines.push(format!(
+                                    "│ … {} more lines",
+                                    total - COMMAND_STREAM_LINES
+                                ));
+                            }
+                            win.output_lines.push(format!(
+                                "-- command ended: {} stdout, {} stderr lines --",
+                                running.stdout_lines, running.stderr_lines
+                            ));
+                        }
+                    }
+                }
                 AgentEvent::Finished {
                     window_id,
                     message,
@@ -927,7 +1516,36 @@ impl App {
                         .find(|w| w.id == window_id)
                         .map(|w| w.label.clone())
                         .unwrap_or_else(|| format!("agent-{window_id}"));
-                    self.log_markdown(label, message);
+                    if remaining == 0 {
+                        self.notify(
+                            NotifyEvent::AgentDone,
+                            format!("{label} finished"),
+                            message.chars().take(200).collect(),
+                        );
+                    }
+                    let tracked = remaining == 0
+                        && self
+                            .sub_agents
+                            .record(window_id, SubAgentState::Done, &message);
+                    self.log_markdown(label, message.clone());
+                    // Mission agents are merged by the mission itself.
+                    if remaining == 0
+                        && worktrees::is_isolated(window_id)
+                        && !self.missions.iter().any(|run| run.owns_window(window_id))
+                    {
+                        self.offer_worktree_merge(window_id);
+                    }
+                    if remaining == 0 {
+                        self.mission_window_finished(window_id, &message);
+                    }
+                    if tracked && !self.chat_busy {
+                        self.inject_sub_agent_summaries(true);
+                    }
+                }
+                AgentEvent::WindowThread { window_id, entries } => {
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        win.append_thread(entries);
+                    }
                 }
                 AgentEvent::NeedsInput {
                     window_id,
@@ -943,6 +1561,23 @@ impl App {
                             .push(format!(">> Waiting for your input: {question}"));
                     }
                     self.enqueue_waiting_window(window_id);
+                    let tracked =
+                        self.sub_agents
+                            .record(window_id, SubAgentState::NeedsInput, &question);
+                    if tracked && !self.chat_busy {
+                        self.inject_sub_agent_summaries(true);
+                    }
+                    self.notify(
+                        NotifyEvent::Waiting,
+                        format!("{label} (#{window_id}) needs input"),
+                        question.clone(),
+                    );
+                    self.post_to_sinks(
+                        SinkEvent::Waiting,
+                        Vec::new(),
+                        format!("{label} (#{window_id}) needs input"),
+                        question.clone(),
+                    );
                     self.log(
                         LogLevel::Info,
                         format!("◈ {label} (#{window_id}) needs input: {question}"),
@@ -969,12 +1604,58 @@ impl App {
                     timestamp,
                 } => {
                     let label = format!("{task_name} (background)");
+                    self.notify(
+                        NotifyEvent::Daemon,
+                        format!("{task_name} finished"),
+                        message.chars().take(200).collect(),
+                    );
+                    let (notify, deliver) = self
+                        .daemon_handles
+                        .iter()
+                        .find(|handle| handle.def.name == task_name)
+                        .map(|handle| (handle.def.notify.clone(), handle.def.deliver.clone()))
+                        .unwrap_or_default();
+                    let (event, notify) = if task_name == digest::DIGEST_TASK {
+                        (SinkEvent::Digest, Vec::new())
+                    } else {
+                        (SinkEvent::Daemon, notify)
+                    };
+                    self.post_to_sinks(
+                        event,
+                        notify,
+                        format!("{task_name} finished"),
+                        message.clone(),
+                    );
+                    self.deliver_by_email(&task_name, &deliver, message.clone());
                     self.log_markdown(label, message.clone());
+                    self.advance_pipelines(&task_name, &message);
                     self.daemon_results.push((task_name, message, timestamp));
                     if self.daemon_results.len() > MAX_DAEMON_RESULTS {
                         self.daemon_results.remove(0);
                     }
                 }
+                AgentEvent::CheckpointReached {
+                    checkpoint_id,
+                    task_name,
+                    checkpoint,
+                    actions,
+                } => {
+                    self.open_checkpoint_window(checkpoint_id, task_name, checkpoint, actions);
+                }
+                AgentEvent::ChangeProposed {
+                    task_name,
+                    id,
+                    path,
+                    added,
+                    removed,
+                } => {
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "📝 {task_name} proposed a change to {path} (+{added} -{removed}) — /review diff {id}, /review apply {id}"
+                        ),
+                    );
+                }
 
                 // ── Main-chat async events ───────────────────────────
                 AgentEvent::ChatProgress { line, level } => {
@@ -988,9 +1669,50 @@ impl App {
                 AgentEvent::ChatMarkdown { label, body } => {
                     self.log_markdown(label, body);
                 }
+                AgentEvent::Interrupted { window_id } => {
+                    let _ = self.mark_window_run_finished(window_id);
+                    if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+                        win.output_lines
+                            .push("-- interrupted by shutdown --".to_string());
+                    }
+                }
+                AgentEvent::TimedOut { window_id, timeout } => {
+                    let after = window_control::format_duration(timeout);
+                    self.stop_window_run(
+                        window_id,
+                        AgentWindowStatus::TimedOut,
+                        format!("timed out after {after}"),
+                    );
+                }
+                AgentEvent::Killed { window_id } => {
+                    self.stop_window_run(
+                        window_id,
+                        AgentWindowStatus::Killed,
+                        "killed".to_string(),
+                    );
+                }
+                AgentEvent::ChatTimeline { timeline } => {
+                    self.last_timeline = Some(timeline);
+                }
+                AgentEvent::ChatPromptSent { snapshot } => {
+                    self.last_prompt = Some(snapshot);
+                }
+                AgentEvent::VoiceTranscript { text } => {
+                    let text = text.trim();
+                    if text.is_empty() {
+                        self.log(LogLevel::Warn, "No speech recognised.".to_string());
+                    } else {
+                        self.insert_str(text);
+                        self.log(
+                            LogLevel::Info,
+                            "Transcript ready — review it, then press Enter to send.".to_string(),
+                        );
+                    }
+                }
+                AgentEvent::RecipeDrafted { result } => self.receive_recipe_draft(result),
                 AgentEvent::ChatFinished {
-                    user_message: _,
-                    output_text: _,
+                    user_message,
+                    output_text,
                     agent_name: _,
                     thread_entries,
                 } => {
@@ -998,16 +1720,11 @@ impl App {
                     for entry in thread_entries {
                         self.conversation_thread.push(entry);
                     }
-                    // Trim thread if over limit.
-                    let max = crate::constants::MAX_THREAD_MESSAGES;
-                    while self.conversation_thread.len() > max {
-                        self.conversation_thread.drain(0..2);
-                    }
-                    // Persist thread to Rice (best-effort).
-                    let _ = self
-                        .runtime
-                        .block_on(self.rice.save_thread(&self.conversation_thread));
                     self.chat_busy = false;
+                    self.finish_api_chat(user_message, output_text);
+                    // Sub-agents that finished mid-turn without being awaited.
+                    self.inject_sub_agent_summaries(false);
+                    self.save_conversation_thread();
                 }
                 AgentEvent::ChatSpawnAgent {
                     window_id,
@@ -1017,30 +1734,32 @@ impl App {
                     coordination_key,
                     persona,
                     skill_context,
+                    restrictions,
                 } => {
                     // Create the agent window on the main thread.
                     let window = AgentWindow {
-                        id: window_id,
-                        label: label.clone(),
-                        prompt: prompt.clone(),
-                        status: AgentWindowStatus::Thinking,
-                        output_lines: Vec::new(),
-                        pending_question: None,
-                        scroll: 0,
                         persona: persona.clone(),
                         skill_context: skill_context.clone(),
                         mcp_snapshots: mcp_snapshots.clone(),
                         coordination_key: coordination_key.clone(),
+                        restrictions: restrictions.clone(),
+                        ..AgentWindow::new(
+                            window_id,
+                            label.clone(),
+                            prompt.clone(),
+                            AgentWindowStatus::Thinking,
+                        )
                     };
                     self.agent_windows.push(window);
                     let idx = self.agent_windows.len().saturating_sub(1);
                     self.grid_selected = idx;
+                    self.sub_agents.track(window_id, &label);
 
                     // Spawn the sub-agent background task.
                     let tx = self.daemon_tx.clone();
                     let openai = self.openai.clone();
                     let key = self.openai_key.clone();
-                    let rice_handle = self.runtime.spawn(crate::rice::RiceStore::connect());
+                    let rice_handle = self.spawn_rice_handle();
                     let has_mcp = !mcp_snapshots.is_empty();
 
                     if has_mcp {
@@ -1049,7 +1768,9 @@ impl App {
                             coordination_key,
                             persona,
                             prompt,
+                            Vec::new(),
                             skill_context,
+                            restrictions,
                             mcp_snapshots,
                             tx,
                             openai,
@@ -1062,7 +1783,9 @@ impl App {
                             window_id,
                             persona,
                             prompt,
+                            Vec::new(),
                             skill_context,
+                            restrictions,
                             tx,
                             openai,
                             key,
@@ -1079,6 +1802,85 @@ impl App {
                 } => {
                     self.handle_rice_trigger_event(run_id, event_type, agent_id, payload);
                 }
+                AgentEvent::WebhookFired { task_name, body } => {
+                    self.handle_webhook_event(task_name, body);
+                }
+                AgentEvent::ApiRequest { request, reply } => {
+                    self.handle_api_request(request, reply);
+                }
+                AgentEvent::FilesChanged { paths } => {
+                    self.queue_file_triggers(paths);
+                }
+                AgentEvent::HomeFilesChanged { recipes, skills } => {
+                    self.queue_live_reload(recipes, skills);
+                }
+                AgentEvent::ApprovalRequested {
+                    approval_id,
+                    tool,
+                    context,
+                    arguments,
+                    rule,
+                    diff,
+                } => {
+                    self.log(
+                        LogLevel::Warn,
+                        format!(
+                            "⚠ Approval #{approval_id}: {context} wants to call {tool} ({rule})"
+                        ),
+                    );
+                    if diff.is_empty() {
+                        let preview: String = arguments.chars().take(300).collect();
+                        self.log(LogLevel::Info, format!("   args: {preview}"));
+                    } else {
+                        for line in &diff {
+                            self.log(LogLevel::Info, format!("   {line}"));
+                        }
+                    }
+                    let hint =
+                        format!("   /policy approve {approval_id}  or  /policy deny {approval_id}");
+                    // Agent windows show the proposed change in place too.
+                    let window_id = context
+                        .strip_prefix("agent #")
+                        .and_then(|id| id.parse::<usize>().ok());
+                    if let Some(win) = self
+                        .agent_windows
+                        .iter_mut()
+                        .find(|w| Some(w.id) == window_id)
+                    {
+                        win.output_lines
+                            .push(format!(">> Approval #{approval_id}: {tool}"));
+                        win.output_lines.extend(diff.iter().cloned());
+                        win.output_lines.push(format!(">> {}", hint.trim()));
+                    }
+                    self.log(LogLevel::Info, hint);
+                    self.notify(
+                        NotifyEvent::Waiting,
+                        format!("Approve {tool}?"),
+                        format!("{context}: {rule}"),
+                    );
+                    self.post_to_sinks(
+                        SinkEvent::Waiting,
+                        Vec::new(),
+                        format!("Approve {tool}?"),
+                        format!("{context}: {rule}"),
+                    );
+                }
+                AgentEvent::McpDeviceAuthFinished { server_id, result } => {
+                    self.finish_mcp_device_auth(server_id, result);
+                }
+                AgentEvent::SamplingRequested { request, reply } => {
+                    self.handle_sampling_request(request, reply);
+                }
+                AgentEvent::PolicyDenied {
+                    tool,
+                    context,
+                    reason,
+                } => {
+                    self.log(
+                        LogLevel::Warn,
+                        format!("⛔ Policy blocked {tool} ({context}): {reason}"),
+                    );
+                }
             }
         }
     }
@@ -1278,7 +2080,9 @@ impl App {
 
         // Reconnect Rice.
         self.log(LogLevel::Info, "⟳ Reconnecting to Rice…".to_string());
-        self.rice = self.runtime.block_on(RiceStore::connect());
+        self.rice = self
+            .runtime
+            .block_on(RiceStore::open(self.backends.memory.clone()));
         match &self.rice.status {
             crate::rice::RiceStatus::Connected => {
                 self.log(
@@ -1304,18 +2108,20 @@ impl App {
         status_line: Option<String>,
         clear_waiting_input: bool,
     ) -> bool {
-        let Some((persona, skill_context, mcp_snapshots, coordination_key)) = self
-            .agent_windows
-            .iter()
-            .find(|w| w.id == window_id)
-            .map(|w| {
-                (
-                    w.persona.clone(),
-                    w.skill_context.clone(),
-                    w.mcp_snapshots.clone(),
-                    w.coordination_key.clone(),
-                )
-            })
+        let Some((persona, skill_context, mcp_snapshots, coordination_key, restrictions, thread)) =
+            self.agent_windows
+                .iter()
+                .find(|w| w.id == window_id)
+                .map(|w| {
+                    (
+                        w.persona.clone(),
+                        w.skill_context.clone(),
+                        w.mcp_snapshots.clone(),
+                        w.coordination_key.clone(),
+                        w.restrictions.clone(),
+                        w.thread.clone(),
+                    )
+                })
         else {
             return false;
         };
@@ -1338,14 +2144,16 @@ impl App {
         let tx = self.daemon_tx.clone();
         let openai = self.openai.clone();
         let key = self.openai_key.clone();
-        let rice_handle = self.runtime.spawn(RiceStore::connect());
+        let rice_handle = self.spawn_rice_handle();
 
         if mcp_snapshots.is_empty() {
             daemon::spawn_agent_window(
                 window_id,
                 persona,
                 prompt,
+                thread,
                 skill_context,
+                restrictions,
                 tx,
                 openai,
                 key,
@@ -1358,7 +2166,9 @@ impl App {
                 coordination_key,
                 persona,
                 prompt,
+                thread,
                 skill_context,
+                restrictions,
                 mcp_snapshots,
                 tx,
                 openai,
@@ -1430,44 +2240,35 @@ impl App {
         started
     }
 
-    /// Reply to a focused agent window that is waiting for input.
-    /// Returns true if the reply was handled, false if no window was waiting.
+    /// Send a message to an agent window that is waiting for input or has
+    /// finished; the window's thread carries the earlier turns.  Returns
+    /// false if the window is busy, cancelled or unknown.
     pub(crate) fn reply_to_agent_window(&mut self, window_id: usize, reply: &str) -> bool {
-        let waiting = self
-            .agent_windows
-            .iter()
-            .any(|w| w.id == window_id && w.status == AgentWindowStatus::WaitingForInput);
+        if let Some(handled) = self.reply_to_checkpoint(window_id, reply) {
+            return handled;
+        }
+        let open = self.agent_windows.iter().any(|w| {
+            w.id == window_id
+                && matches!(
+                    w.status,
+                    AgentWindowStatus::WaitingForInput | AgentWindowStatus::Done
+                )
+        });
 
-        if !waiting {
+        if !open {
             return false;
         }
 
-        // Update the window.
         if let Some(win) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
             win.output_lines.push(format!(">> You: {reply}"));
-            win.status = AgentWindowStatus::Thinking;
-            win.pending_question = None;
         }
-        self.dequeue_waiting_window(window_id);
-
-        let _persona = self
-            .agent_windows
-            .iter()
-            .find(|w| w.id == window_id)
-            .map(|w| w.label.clone())
-            .unwrap_or_default();
-
-        let prompt = format!(
-            "The user replied to your question: \"{reply}\"\n\
-             Continue working on the original task."
-        );
 
         self.log(
             LogLevel::Info,
             format!("Replied to agent window {window_id}: {reply}"),
         );
 
-        self.spawn_agent_window_run(window_id, prompt, None, true)
+        self.spawn_agent_window_run(window_id, reply.to_string(), None, true)
     }
 
     /// Spawn a background daemon task, connecting it to the shared channel.
@@ -1477,7 +2278,7 @@ impl App {
         let key = self.openai_key.clone();
 
         // Each daemon task gets its own Rice connection (async).
-        let rice_handle = self.runtime.spawn(RiceStore::connect());
+        let rice_handle = self.spawn_rice_handle();
 
         let handle = daemon::spawn_task(
             def,
@@ -1494,7 +2295,11 @@ impl App {
                 handle.def.name, handle.def.interval_secs
             ),
         );
+        let watches_files = !handle.def.trigger_paths.is_empty();
         self.daemon_handles.push(handle);
+        if watches_files {
+            self.ensure_file_watcher();
+        }
     }
 
     /// Fire a one-shot background run of a daemon task definition.
@@ -1502,7 +2307,7 @@ impl App {
         let tx = self.daemon_tx.clone();
         let openai = self.openai.clone();
         let key = self.openai_key.clone();
-        let rice_handle = self.runtime.spawn(RiceStore::connect());
+        let rice_handle = self.spawn_rice_handle();
 
         self.log(LogLevel::Info, format!("Running '{}' now...", def.name));
         daemon::spawn_oneshot(
@@ -1526,9 +2331,10 @@ impl App {
 
         let run_id = self.rice.active_run_id();
         let tx = self.daemon_tx.clone();
+        let memory = self.backends.memory.clone();
         let handle = self.runtime.handle().clone().spawn(async move {
             loop {
-                let mut rice = RiceStore::connect().await;
+                let mut rice = RiceStore::open(memory.clone()).await;
                 if matches!(rice.status, RiceStatus::Disabled(_)) {
                     tokio::time::sleep(Duration::from_secs(5)).await;
                     continue;
@@ -1652,14 +2458,8 @@ impl App {
                 &payload,
             );
             let def = daemon::DaemonTaskDef {
-                name: recipe.name.clone(),
-                persona: recipe.persona.clone(),
                 prompt,
-                interval_secs: recipe.interval_secs,
-                trigger_events: recipe.trigger_events.clone(),
-                trigger_variables: recipe.trigger_variables.clone(),
-                tools: recipe.tools.clone(),
-                paused: true,
+                ..Self::daemon_def_from_recipe(&recipe, true)
             };
             self.run_daemon_oneshot(def);
             started_spawned += 1;
@@ -1684,4 +2484,209 @@ impl App {
             );
         }
     }
+
+    /// Bind the webhook listener if `MEMINI_WEBHOOK_PORT` is set.
+    fn start_webhook_listener(&mut self) {
+        let Some(raw) = env_first(&["MEMINI_WEBHOOK_PORT"]) else {
+            return;
+        };
+        let Ok(port) = raw.trim().parse::<u16>() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Ignoring invalid MEMINI_WEBHOOK_PORT: {raw}")
+            );
+            return;
+        };
+        match webhooks::spawn_listener(port, self.daemon_tx.clone()) {
+            Ok(()) => {
+                self.webhook_port = Some(port);
+                self.log(
+                    LogLevel::Info,
+                    format!("Webhook listener on 127.0.0.1:{port} (see /hooks)."),
+                );
+            }
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("Webhooks disabled: {err:#}"));
+            }
+        }
+    }
+
+    /// Run the named task immediately with the webhook body as context.
+    fn handle_webhook_event(&mut self, task_name: String, body: String) {
+        let active = self
+            .daemon_handles
+            .iter()
+            .find(|handle| handle.def.name.eq_ignore_ascii_case(&task_name))
+            .map(|handle| handle.def.clone());
+
+        let mut def = if let Some(def) = active {
+            def
+        } else if let Some(def) = daemon::builtin_tasks()
+            .into_iter()
+            .find(|task| task.name.eq_ignore_ascii_case(&task_name))
+        {
+            def
+        } else {
+            let recipe = agent_recipes::load_agent_recipes()
+                .unwrap_or_default()
+                .into_iter()
+                .find(|recipe| recipe.name.eq_ignore_ascii_case(&task_name));
+            let Some(recipe) = recipe else {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Webhook fired for unknown task '{task_name}'.")
+                );
+                return;
+            };
+            Self::daemon_def_from_recipe(&recipe, true)
+        };
+
+        let run_id = self.rice.active_run_id();
+        def.prompt =
+            active_daemon_trigger_prompt(&def.prompt, &run_id, "Webhook", "webhook", None, &body);
+        self.trigger_last_run
+            .insert(def.name.clone(), Instant::now());
+        self.log(
+            LogLevel::Info,
+            format!("Webhook fired: {} ({} byte body).", def.name, body.len()),
+        );
+        self.run_daemon_oneshot(def);
+    }
+
+    /// Start the workspace watcher once any task declares `trigger_paths`.
+    pub(crate) fn ensure_file_watcher(&mut self) {
+        if self.file_watcher.is_some() {
+            return;
+        }
+        let wanted = self
+            .daemon_handles
+            .iter()
+            .any(|handle| !handle.def.trigger_paths.is_empty())
+            || agent_recipes::load_agent_recipes()
+                .unwrap_or_default()
+                .iter()
+                .any(|recipe| !recipe.trigger_paths.is_empty());
+        if !wanted {
+            return;
+        }
+
+        let root = crate::local_tools::workspace_root();
+        match file_watch::spawn_workspace_watcher(root, self.daemon_tx.clone()) {
+            Ok(watcher) => {
+                self.log(
+                    LogLevel::Info,
+                    format!("Watching {} for trigger_paths.", watcher.root.display()),
+                );
+                self.file_watcher = Some(watcher);
+            }
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("File triggers disabled: {err:#}")
+                );
+            }
+        }
+    }
+
+    /// Record changed paths against every task whose `trigger_paths` match.
+    ///
+    /// Runs are deferred until the task has seen no new changes for
+    /// [`FILE_TRIGGER_DEBOUNCE_MS`]; see [`App::flush_file_triggers`].
+    fn queue_file_triggers(&mut self, paths: Vec<String>) {
+        let mut candidates: Vec<(String, Vec<String>)> = self
+            .daemon_handles
+            .iter()
+            .filter(|handle| !handle.def.trigger_paths.is_empty())
+            .map(|handle| (handle.def.name.clone(), handle.def.trigger_paths.clone()))
+            .collect();
+        for recipe in agent_recipes::load_agent_recipes().unwrap_or_default() {
+            if !recipe.auto_start || recipe.trigger_paths.is_empty() {
+                continue;
+            }
+            if candidates
+                .iter()
+                .any(|(name, _)| name.eq_ignore_ascii_case(&recipe.name))
+            {
+                continue;
+            }
+            candidates.push((recipe.name, recipe.trigger_paths));
+        }
+
+        for (name, patterns) in candidates {
+            let matched = file_watch::matching_paths(&patterns, &paths);
+            if matched.is_empty() {
+                continue;
+            }
+            let entry = self
+                .pending_file_triggers
+                .entry(name.to_ascii_lowercase())
+                .or_insert_with(|| (Instant::now(), Vec::new()));
+            entry.0 = Instant::now();
+            for path in matched {
+                if !entry.1.contains(&path) {
+                    entry.1.push(path);
+                }
+            }
+        }
+    }
+
+    /// Fire file-triggered runs whose debounce window has elapsed.
+    fn flush_file_triggers(&mut self) {
+        let debounce = Duration::from_millis(FILE_TRIGGER_DEBOUNCE_MS);
+        let ready: Vec<String> = self
+            .pending_file_triggers
+            .iter()
+            .filter(|(_, (last_change, _))| last_change.elapsed() >= debounce)
+            .map(|(name, _)| name.clone())
+            .collect();
+
+        for name in ready {
+            let Some((_, paths)) = self.pending_file_triggers.remove(&name) else {
+                continue;
+            };
+            let active = self
+                .daemon_handles
+                .iter()
+                .find(|handle| handle.def.name.eq_ignore_ascii_case(&name))
+                .map(|handle| handle.def.clone());
+            let mut def = match active {
+                Some(def) => def,
+                None => {
+                    let recipe = agent_recipes::load_agent_recipes()
+                        .unwrap_or_default()
+                        .into_iter()
+                        .find(|recipe| recipe.name.eq_ignore_ascii_case(&name));
+                    let Some(recipe) = recipe else {
+                        continue;
+                    };
+                    Self::daemon_def_from_recipe(&recipe, true)
+                }
+            };
+
+            let run_id = self.rice.active_run_id();
+            let payload = serde_json::json!({ "changed_paths": paths }).to_string();
+            def.prompt = active_daemon_trigger_prompt(
+                &def.prompt,
+                &run_id,
+                "FileChange",
+                "file-watch",
+                None,
+                &payload,
+            );
+            self.trigger_last_run
+                .insert(def.name.clone(), Instant::now());
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "File trigger fired: {} ({} changed path(s)).",
+                    def.name,
+                    paths.len()
+                ),
+            );
+            self.run_daemon_oneshot(def);
+        }
+    }
 }
@@ -0,0 +1,158 @@
+//! Desktop notifications and terminal-bell alerts for background events.
+//!
+//! Each [`NotifyEvent`] has its own [`NotifyMode`] so that, for example,
+//! agents waiting for input can pop a desktop notification while daemon
+//! results only ring the bell.  Desktop delivery goes through `notify-rust`
+//! on a blocking thread and falls back to the bell if it fails.
+
+use std::io::Write;
+
+use serde::{Deserialize, Serialize};
+
+/// Background events that can raise a notification.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NotifyEvent {
+    /// A live agent window is waiting for user input.
+    Waiting,
+    /// A live agent window finished its run.
+    AgentDone,
+    /// A background daemon produced a result.
+    Daemon,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 3] = [
+        NotifyEvent::Waiting,
+        NotifyEvent::AgentDone,
+        NotifyEvent::Daemon,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            NotifyEvent::Waiting => "waiting",
+            NotifyEvent::AgentDone => "agent",
+            NotifyEvent::Daemon => "daemon",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "waiting" | "input" => Some(NotifyEvent::Waiting),
+            "agent" | "agents" | "done" => Some(NotifyEvent::AgentDone),
+            "daemon" | "daemons" | "auto" => Some(NotifyEvent::Daemon),
+            _ => None,
+        }
+    }
+}
+
+/// How a notification is delivered.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyMode {
+    #[default]
+    Off,
+    Bell,
+    Desktop,
+}
+
+impl NotifyMode {
+    pub fn label(self) -> &'static str {
+        match self {
+            NotifyMode::Off => "off",
+            NotifyMode::Bell => "bell",
+            NotifyMode::Desktop => "desktop",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(NotifyMode::Off),
+            "bell" => Some(NotifyMode::Bell),
+            "desktop" | "on" => Some(NotifyMode::Desktop),
+            _ => None,
+        }
+    }
+}
+
+/// Per-event notification settings (persisted to Rice).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotifySettings {
+    #[serde(default)]
+    pub waiting: NotifyMode,
+    #[serde(default)]
+    pub agent_done: NotifyMode,
+    #[serde(default)]
+    pub daemon: NotifyMode,
+}
+
+impl NotifySettings {
+    pub fn mode(&self, event: NotifyEvent) -> NotifyMode {
+        match event {
+            NotifyEvent::Waiting => self.waiting,
+            NotifyEvent::AgentDone => self.agent_done,
+            NotifyEvent::Daemon => self.daemon,
+        }
+    }
+
+    pub fn set_mode(&mut self, event: NotifyEvent, mode: NotifyMode) {
+        match event {
+            NotifyEvent::Waiting => self.waiting = mode,
+            NotifyEvent::AgentDone => self.agent_done = mode,
+            NotifyEvent::Daemon => self.daemon = mode,
+        }
+    }
+}
+
+/// Deliver a notification using `mode`; never blocks the UI thread.
+pub fn deliver(runtime: &tokio::runtime::Handle, mode: NotifyMode, summary: String, body: String) {
+    match mode {
+        NotifyMode::Off => {}
+        NotifyMode::Bell => ring_bell(),
+        NotifyMode::Desktop => {
+            runtime.spawn_blocking(move || {
+                let shown = notify_rust::Notification::new()
+                    .appname(crate::constants::APP_NAME)
+                    .summary(&summary)
+                    .body(&body)
+                    .show();
+                if shown.is_err() {
+                    ring_bell();
+                }
+            });
+        }
+    }
+}
+
+/// Emit the terminal bell (BEL) on stdout.
+fn ring_bell() {
+    let mut stdout = std::io::stdout();
+    let _ = stdout.write_all(b"\x07");
+    let _ = stdout.flush();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn settings_default_to_off_and_roundtrip() {
+        let mut settings = NotifySettings::default();
+        for event in NotifyEvent::ALL {
+            assert_eq!(settings.mode(event), NotifyMode::Off);
+        }
+        settings.set_mode(NotifyEvent::Waiting, NotifyMode::Desktop);
+        let json = serde_json::to_value(&settings).expect("serialize");
+        assert_eq!(json["waiting"], "desktop");
+        let parsed: NotifySettings = serde_json::from_value(json).expect("deserialize");
+        assert_eq!(parsed, settings);
+    }
+
+    #[test]
+    fn parses_event_and_mode_aliases() {
+        assert_eq!(NotifyEvent::parse("Input"), Some(NotifyEvent::Waiting));
+        assert_eq!(NotifyEvent::parse("daemons"), Some(NotifyEvent::Daemon));
+        assert_eq!(NotifyEvent::parse("nope"), None);
+        assert_eq!(NotifyMode::parse("on"), Some(NotifyMode::Desktop));
+        assert_eq!(NotifyMode::parse("BELL"), Some(NotifyMode::Bell));
+    }
+}
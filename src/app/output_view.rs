@@ -0,0 +1,244 @@
+//! Output mode for the agent session view — line cursor, visual
+//! selection, `/` search, and yank-to-clipboard.
+//!
+//! Entered with `Ctrl+O` inside an agent session.  Keys:
+//! `j`/`k` move, `g`/`G` jump, `v` toggles selection, `/` searches,
+//! `n`/`N` repeat the search, `y` copies, `t` shows or hides the model's
+//! reasoning, `Esc` leaves output mode.
+
+use anyhow::{Context, Result};
+use crossterm::event::{KeyCode, KeyEvent};
+
+use super::App;
+use super::log_src;
+use super::logging::LogLevel;
+
+/// Cursor/selection state for one agent window's output.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct OutputView {
+    pub window_id: usize,
+    /// Line index of the cursor.
+    pub cursor: usize,
+    /// Start of the visual selection, if one is active.
+    pub anchor: Option<usize>,
+    /// Last submitted search query.
+    pub query: Option<String>,
+    /// Search query being typed after `/`.
+    pub search_input: Option<String>,
+}
+
+impl OutputView {
+    pub fn new(window_id: usize, line_count: usize) -> Self {
+        OutputView {
+            window_id,
+            cursor: line_count.saturating_sub(1),
+            ..OutputView::default()
+        }
+    }
+
+    /// Inclusive line range covered by the selection (or the cursor line).
+    pub fn selected_range(&self) -> (usize, usize) {
+        let anchor = self.anchor.unwrap_or(self.cursor);
+        (anchor.min(self.cursor), anchor.max(self.cursor))
+    }
+
+    pub fn is_selected(&self, idx: usize) -> bool {
+        let (start, end) = self.selected_range();
+        self.anchor.is_some() && idx >= start && idx <= end
+    }
+
+    pub fn selected_text(&self, lines: &[String]) -> String {
+        if lines.is_empty() {
+            return String::new();
+        }
+        let (start, end) = self.selected_range();
+        let end = end.min(lines.len() - 1);
+        lines[start.min(end)..=end].join("\n")
+    }
+
+    pub fn matches(&self, line: &str) -> bool {
+        self.query
+            .as_deref()
+            .is_some_and(|query| contains_ignore_case(line, query))
+    }
+}
+
+fn contains_ignore_case(haystack: &str, needle: &str) -> bool {
+    haystack
+        .to_ascii_lowercase()
+        .contains(&needle.to_ascii_lowercase())
+}
+
+/// Next line matching `query`, wrapping around, excluding `from` itself.
+pub(crate) fn find_match(
+    lines: &[String],
+    query: &str,
+    from: usize,
+    forward: bool,
+) -> Option<usize> {
+    let len = lines.len();
+    if len == 0 || query.is_empty() {
+        return None;
+    }
+    (1..=len)
+        .map(|step| {
+            if forward {
+                (from + step) % len
+            } else {
+                (from + len * 2 - step) % len
+            }
+        })
+        .find(|&idx| contains_ignore_case(&lines[idx], query))
+}
+
+/// Place `text` on the system clipboard.
+pub(crate) fn copy_to_clipboard(text: &str) -> Result<()> {
+    let mut clipboard = arboard::Clipboard::new().context("open clipboard")?;
+    clipboard
+        .set_text(text.to_string())
+        .context("write clipboard")?;
+    Ok(())
+}
+
+impl App {
+    /// Enter output mode for the given window.
+    pub(crate) fn enter_output_view(&mut self, window_id: usize) {
+        let line_count = self
+            .agent_windows
+            .iter()
+            .find(|w| w.id == window_id)
+            .map_or(0, |w| w.output_lines.len());
+        self.output_view = Some(OutputView::new(window_id, line_count));
+    }
+
+    /// Expand or collapse a window's reasoning summaries (`t` in output
+    /// mode, `Ctrl+T` in the session view).
+    pub(crate) fn toggle_reasoning(&mut self, window_id: usize) {
+        if let Some(window) = self.agent_windows.iter_mut().find(|w| w.id == window_id) {
+            window.show_reasoning = !window.show_reasoning;
+        }
+    }
+
+    /// Copy text to the clipboard, logging the outcome.
+    pub(crate) fn copy_output(&mut self, text: &str) {
+        if text.is_empty() {
+            self.log(LogLevel::Info, "Nothing to copy.".to_string());
+            return;
+        }
+        match copy_to_clipboard(text) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!("Copied {} line(s) to clipboard.", text.lines().count()),
+            ),
+            Err(err) => log_src!(self, LogLevel::Error, format!("Copy failed: {err:#}")),
+        }
+    }
+
+    /// Key handling while output mode is active.
+    pub(crate) fn handle_output_view_key(&mut self, key: KeyEvent) {
+        let Some(mut view) = self.output_view.take() else {
+            return;
+        };
+        let lines = self
+            .agent_windows
+            .iter()
+            .find(|w| w.id == view.window_id)
+            .map(|w| w.output_lines.clone())
+            .unwrap_or_default();
+        let last = lines.len().saturating_sub(1);
+
+        // Typing a search query.
+        if let Some(mut query) = view.search_input.take() {
+            match key.code {
+                KeyCode::Enter => {
+                    if let Some(idx) = find_match(&lines, &query, view.cursor, true) {
+                        view.cursor = idx;
+                    } else if !query.is_empty() {
+                        self.log(LogLevel::Info, format!("No match for '{query}'."));
+                    }
+                    view.query = Some(query).filter(|q| !q.is_empty());
+                }
+                KeyCode::Esc => {}
+                KeyCode::Backspace => {
+                    query.pop();
+                    view.search_input = Some(query);
+                }
+                KeyCode::Char(ch) => {
+                    query.push(ch);
+                    view.search_input = Some(query);
+                }
+                _ => view.search_input = Some(query),
+            }
+            self.output_view = Some(view);
+            return;
+        }
+
+        match key.code {
+            KeyCode::Esc | KeyCode::Char('q') => {
+                if view.anchor.take().is_some() {
+                    self.output_view = Some(view);
+                }
+                return;
+            }
+            KeyCode::Char('j') | KeyCode::Down => view.cursor = (view.cursor + 1).min(last),
+            KeyCode::Char('k') | KeyCode::Up => view.cursor = view.cursor.saturating_sub(1),
+            KeyCode::PageDown => view.cursor = (view.cursor + 10).min(last),
+            KeyCode::PageUp => view.cursor = view.cursor.saturating_sub(10),
+            KeyCode::Char('g') | KeyCode::Home => view.cursor = 0,
+            KeyCode::Char('G') | KeyCode::End => view.cursor = last,
+            KeyCode::Char('v') | KeyCode::Char('V') => {
+                view.anchor = match view.anchor {
+                    Some(_) => None,
+                    None => Some(view.cursor),
+                };
+            }
+            KeyCode::Char('t') => self.toggle_reasoning(view.window_id),
+            KeyCode::Char('/') => view.search_input = Some(String::new()),
+            KeyCode::Char(ch @ ('n' | 'N')) => {
+                if let Some(query) = view.query.clone() {
+                    if let Some(idx) = find_match(&lines, &query, view.cursor, ch == 'n') {
+                        view.cursor = idx;
+                    }
+                }
+            }
+            KeyCode::Char('y') | KeyCode::Enter => {
+                let text = view.selected_text(&lines);
+                self.copy_output(&text);
+                return;
+            }
+            _ => {}
+        }
+        self.output_view = Some(view);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn find_match_wraps_both_directions() {
+        let output = lines(&["alpha", "Beta", "gamma", "beta two"]);
+        assert_eq!(find_match(&output, "beta", 1, true), Some(3));
+        assert_eq!(find_match(&output, "beta", 3, true), Some(1));
+        assert_eq!(find_match(&output, "beta", 1, false), Some(3));
+        assert_eq!(find_match(&output, "delta", 0, true), None);
+    }
+
+    #[test]
+    fn selection_covers_anchor_to_cursor() {
+        let output = lines(&["a", "b", "c", "d"]);
+        let mut view = OutputView::new(1, output.len());
+        assert_eq!(view.cursor, 3);
+        assert_eq!(view.selected_text(&output), "d");
+        view.anchor = Some(3);
+        view.cursor = 1;
+        assert_eq!(view.selected_text(&output), "b\nc\nd");
+        assert!(view.is_selected(2));
+        assert!(!view.is_selected(0));
+    }
+}
@@ -0,0 +1,168 @@
+//! Pinned context for `/pin` and `/pins`.
+//!
+//! Pins are short texts or files the user wants in front of the model on
+//! every chat turn (specs, constraints, naming rules).  They are stored in
+//! `pins.json` next to the session file and rendered into one system
+//! message per turn.  File pins are re-read each turn, so edits show up
+//! without re-pinning.  The whole block is capped at
+//! [`PINNED_CONTEXT_TOKENS`]; pins past the cap are left out and reported.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{APP_NAME, PINNED_CONTEXT_TOKENS};
+
+/// One pinned item: literal text or a file path.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind", content = "value")]
+pub enum Pin {
+    Text(String),
+    File(PathBuf),
+}
+
+impl Pin {
+    /// One-line description for `/pins`.
+    pub fn label(&self) -> String {
+        match self {
+            Pin::Text(text) => {
+                let line = text.lines().next().unwrap_or("");
+                if line.chars().count() > 60 || text.lines().nth(1).is_some() {
+                    format!("\"{}…\"", line.chars().take(59).collect::<String>())
+                } else {
+                    format!("\"{line}\"")
+                }
+            }
+            Pin::File(path) => format!("file {}", path.display()),
+        }
+    }
+
+    fn body(&self) -> String {
+        match self {
+            Pin::Text(text) => text.clone(),
+            Pin::File(path) => match fs::read_to_string(path) {
+                Ok(contents) => format!("File {}:\n{}", path.display(), contents.trim_end()),
+                Err(err) => format!("File {} (unreadable: {err})", path.display()),
+            },
+        }
+    }
+}
+
+/// Persisted pins, in the order they were added.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PinStore {
+    #[serde(default)]
+    pub pins: Vec<Pin>,
+}
+
+/// Rendered pin block for one turn.
+#[derive(Debug, Default)]
+pub struct PinnedContext {
+    /// System message content; empty when nothing is pinned.
+    pub content: String,
+    /// 1-based numbers of pins left out (or cut short) for the budget.
+    pub over_budget: Vec<usize>,
+}
+
+pub(crate) fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+impl PinStore {
+    /// Render pins in order until the token budget runs out.
+    pub fn render(&self) -> PinnedContext {
+        render_with_budget(&self.pins, PINNED_CONTEXT_TOKENS)
+    }
+}
+
+fn render_with_budget(pins: &[Pin], budget: usize) -> PinnedContext {
+    let mut context = PinnedContext::default();
+    if pins.is_empty() {
+        return context;
+    }
+    let mut parts = Vec::new();
+    let mut used = 0usize;
+    for (index, pin) in pins.iter().enumerate() {
+        let body = pin.body();
+        let cost = estimate_tokens(&body);
+        if used + cost <= budget {
+            used += cost;
+            parts.push(body);
+            continue;
+        }
+        let remaining_chars = budget.saturating_sub(used) * 4;
+        if parts.is_empty() && remaining_chars > 0 {
+            // A single oversized pin still gets its head in.
+            let head: String = body.chars().take(remaining_chars).collect();
+            parts.push(format!("{head}\n… (truncated)"));
+            used = budget;
+        }
+        context.over_budget.push(index + 1);
+    }
+    context.content = format!(
+        "Pinned by the user. Treat these as standing instructions and reference material for the whole conversation:\n\n{}",
+        parts.join("\n\n---\n\n")
+    );
+    context
+}
+
+/// Returns the platform-specific path for the pin file.
+fn pins_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME).map(|dirs| dirs.config_dir().join("pins.json"))
+}
+
+/// Load pins from disk, falling back to none.
+pub fn load_pins() -> PinStore {
+    let Some(path) = pins_path() else {
+        return PinStore::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return PinStore::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_default()
+}
+
+/// Persist pins to disk.
+pub fn persist_pins(store: &PinStore) -> Result<()> {
+    let Some(path) = pins_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create config dir")?;
+    }
+    let contents = serde_json::to_string_pretty(store).context("serialize pins")?;
+    fs::write(&path, contents).context("write pins")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_in_order_within_budget() {
+        let pins = vec![
+            Pin::Text("Use British spelling.".to_string()),
+            Pin::Text("x".repeat(400)),
+            Pin::Text("Never touch prod.".to_string()),
+        ];
+        let context = render_with_budget(&pins, 20);
+        assert!(context.content.contains("British spelling"));
+        assert!(context.content.contains("Never touch prod"));
+        assert!(!context.content.contains("xxxx"));
+        assert_eq!(context.over_budget, vec![2]);
+        assert!(render_with_budget(&[], 20).content.is_empty());
+    }
+
+    #[test]
+    fn oversized_single_pin_is_cut_and_missing_files_noted() {
+        let context = render_with_budget(&[Pin::Text("y".repeat(400))], 10);
+        assert!(context.content.contains("… (truncated)"));
+        assert_eq!(context.over_budget, vec![1]);
+        let missing = Pin::File(PathBuf::from("/nonexistent/memini-spec.md"));
+        assert!(missing.body().contains("unreadable"));
+    }
+}
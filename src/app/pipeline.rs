@@ -0,0 +1,396 @@
+//! Daemon pipelines — tasks linked with `depends_on` / `pipe_output_to`
+//! form a DAG (e.g. collector → summarizer → publisher).
+//!
+//! When a task finishes, its output is delivered to every downstream task.
+//! A task with several upstreams waits until each has delivered, then runs
+//! once with all upstream outputs appended to its prompt.  `/daemon run`
+//! on a downstream task starts the pipeline from its source tasks.
+
+use std::collections::HashMap;
+
+use super::App;
+use super::agent_recipes;
+use super::daemon::{self, DaemonTaskDef};
+use super::log_src;
+use super::logging::LogLevel;
+
+/// Longest upstream output passed into a downstream prompt.
+const MAX_UPSTREAM_CHARS: usize = 8_000;
+
+/// Pending upstream outputs per downstream task (lower-cased name).
+pub(crate) type PipelineInbox = HashMap<String, Vec<(String, String)>>;
+
+/// Directed edges between task names (`upstream → downstream`).
+#[derive(Debug, Default)]
+pub(crate) struct PipelineGraph {
+    edges: Vec<(String, String)>,
+}
+
+impl PipelineGraph {
+    /// Collect edges from both `depends_on` and `pipe_output_to`.
+    pub fn from_defs(defs: &[DaemonTaskDef]) -> Self {
+        let canonical = |name: &str| -> String {
+            defs.iter()
+                .find(|def| def.name.eq_ignore_ascii_case(name.trim()))
+                .map(|def| def.name.clone())
+                .unwrap_or_else(|| name.trim().to_string())
+        };
+        let mut graph = PipelineGraph::default();
+        for def in defs {
+            for upstream in &def.depends_on {
+                graph.add_edge(canonical(upstream), def.name.clone());
+            }
+            for downstream in &def.pipe_output_to {
+                graph.add_edge(def.name.clone(), canonical(downstream));
+            }
+        }
+        graph
+    }
+
+    fn add_edge(&mut self, from: String, to: String) {
+        if from.is_empty() || to.is_empty() {
+            return;
+        }
+        let exists = self
+            .edges
+            .iter()
+            .any(|(a, b)| a.eq_ignore_ascii_case(&from) && b.eq_ignore_ascii_case(&to));
+        if !exists {
+            self.edges.push((from, to));
+        }
+    }
+
+    pub fn upstream(&self, name: &str) -> Vec<String> {
+        self.edges
+            .iter()
+            .filter(|(_, to)| to.eq_ignore_ascii_case(name))
+            .map(|(from, _)| from.clone())
+            .collect()
+    }
+
+    pub fn downstream(&self, name: &str) -> Vec<String> {
+        self.edges
+            .iter()
+            .filter(|(from, _)| from.eq_ignore_ascii_case(name))
+            .map(|(_, to)| to.clone())
+            .collect()
+    }
+
+    /// Every task `name` transitively depends on, upstream-first.
+    pub fn ancestors(&self, name: &str) -> Vec<String> {
+        let mut order = Vec::new();
+        self.visit_upstream(name, &mut Vec::new(), &mut order);
+        order
+    }
+
+    fn visit_upstream(&self, name: &str, path: &mut Vec<String>, order: &mut Vec<String>) {
+        path.push(name.to_ascii_lowercase());
+        for upstream in self.upstream(name) {
+            let key = upstream.to_ascii_lowercase();
+            if path.contains(&key) || order.iter().any(|n| n.eq_ignore_ascii_case(&upstream)) {
+                continue;
+            }
+            self.visit_upstream(&upstream, path, order);
+            order.push(upstream);
+        }
+        path.pop();
+    }
+
+    /// A cycle through `name`, if the graph has one.
+    pub fn cycle_through(&self, name: &str) -> Option<Vec<String>> {
+        let mut path = vec![name.to_string()];
+        self.find_path_back(name, name, &mut path).then_some(path)
+    }
+
+    fn find_path_back(&self, start: &str, current: &str, path: &mut Vec<String>) -> bool {
+        for next in self.downstream(current) {
+            if next.eq_ignore_ascii_case(start) {
+                path.push(next);
+                return true;
+            }
+            if path.iter().any(|n| n.eq_ignore_ascii_case(&next)) {
+                continue;
+            }
+            path.push(next.clone());
+            if self.find_path_back(start, &next, path) {
+                return true;
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// Tree view of every pipeline, one line per task.
+    pub fn render(&self) -> Vec<String> {
+        let mut roots: Vec<String> = self
+            .edges
+            .iter()
+            .map(|(from, _)| from.clone())
+            .filter(|from| self.upstream(from).is_empty())
+            .collect();
+        roots.sort();
+        roots.dedup();
+        let mut lines = Vec::new();
+        for root in &roots {
+            lines.push(format!("  {root}"));
+            self.render_children(root, 1, &mut vec![root.to_ascii_lowercase()], &mut lines);
+        }
+        if roots.is_empty() && !self.edges.is_empty() {
+            lines.push("  (cycle — no source task)".to_string());
+        }
+        lines
+    }
+
+    fn render_children(
+        &self,
+        name: &str,
+        depth: usize,
+        path: &mut Vec<String>,
+        lines: &mut Vec<String>,
+    ) {
+        for child in self.downstream(name) {
+            let indent = "   ".repeat(depth);
+            let upstream = self.upstream(&child);
+            let waits = if upstream.len() > 1 {
+                format!("  (waits for {})", upstream.join(", "))
+            } else {
+                String::new()
+            };
+            let key = child.to_ascii_lowercase();
+            if path.contains(&key) {
+                lines.push(format!("  {indent}└▶ {child}  (cycle!)"));
+                continue;
+            }
+            lines.push(format!("  {indent}└▶ {child}{waits}"));
+            path.push(key);
+            self.render_children(&child, depth + 1, path, lines);
+            path.pop();
+        }
+    }
+}
+
+/// Downstream prompt with upstream outputs appended as context.
+pub(crate) fn pipeline_prompt(base_prompt: &str, inputs: &[(String, String)]) -> String {
+    let mut prompt = base_prompt.to_string();
+    for (upstream, output) in inputs {
+        let mut text: String = output.chars().take(MAX_UPSTREAM_CHARS).collect();
+        if output.chars().count() > MAX_UPSTREAM_CHARS {
+            text.push_str("\n… (truncated)");
+        }
+        prompt.push_str(&format!(
+            "\n\n--- Output from upstream task `{upstream}` ---\n{text}"
+        ));
+    }
+    prompt.push_str("\n\nUse the upstream output above as the input for this run.");
+    prompt
+}
+
+// ── App integration ──────────────────────────────────────────────────
+
+impl App {
+    /// Every task definition the scheduler knows: running tasks first, then
+    /// built-ins and recipes not already running.
+    pub(crate) fn known_task_defs(&self) -> Vec<DaemonTaskDef> {
+        let mut defs: Vec<DaemonTaskDef> =
+            self.daemon_handles.iter().map(|h| h.def.clone()).collect();
+        let recipes = agent_recipes::load_agent_recipes().unwrap_or_default();
+        let others = daemon::builtin_tasks().into_iter().chain(
+            recipes
+                .iter()
+                .map(|recipe| Self::daemon_def_from_recipe(recipe, true)),
+        );
+        for def in others {
+            if !defs.iter().any(|d| d.name.eq_ignore_ascii_case(&def.name)) {
+                defs.push(def);
+            }
+        }
+        defs
+    }
+
+    /// Deliver a finished task's output to its downstream tasks and run
+    /// those whose upstreams have all delivered.
+    pub(crate) fn advance_pipelines(&mut self, task_name: &str, output: &str) {
+        let defs = self.known_task_defs();
+        let graph = PipelineGraph::from_defs(&defs);
+        let downstream = graph.downstream(task_name);
+        if downstream.is_empty() {
+            return;
+        }
+        if let Some(cycle) = graph.cycle_through(task_name) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Pipeline cycle ({}); not running downstream tasks.",
+                    cycle.join(" → ")
+                )
+            );
+            return;
+        }
+        if output.starts_with("Error:") || output.starts_with("No OpenAI key") {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Pipeline stopped: '{task_name}' failed, skipping {}.",
+                    downstream.join(", ")
+                )
+            );
+            return;
+        }
+
+        for next in downstream {
+            let inbox = self
+                .pipeline_inbox
+                .entry(next.to_ascii_lowercase())
+                .or_default();
+            inbox.retain(|(upstream, _)| !upstream.eq_ignore_ascii_case(task_name));
+            inbox.push((task_name.to_string(), output.to_string()));
+
+            let missing: Vec<String> = graph
+                .upstream(&next)
+                .into_iter()
+                .filter(|up| !inbox.iter().any(|(done, _)| done.eq_ignore_ascii_case(up)))
+                .collect();
+            if !missing.is_empty() {
+                self.log(
+                    LogLevel::Info,
+                    format!(
+                        "Pipeline: {task_name} → {next} (waiting for {}).",
+                        missing.join(", ")
+                    ),
+                );
+                continue;
+            }
+
+            let inputs = self
+                .pipeline_inbox
+                .remove(&next.to_ascii_lowercase())
+                .unwrap_or_default();
+            let Some(mut def) = defs
+                .iter()
+                .find(|d| d.name.eq_ignore_ascii_case(&next))
+                .cloned()
+            else {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Pipeline target '{next}' is not a known task.")
+                );
+                continue;
+            };
+            def.prompt = pipeline_prompt(&def.prompt, &inputs);
+            self.log(LogLevel::Info, format!("Pipeline: {task_name} → {next}"));
+            self.run_daemon_oneshot(def);
+        }
+    }
+
+    /// Start `name`'s pipeline from its source tasks.  Returns `false`
+    /// when the task has no upstream dependencies.
+    pub(crate) fn run_pipeline_for(&mut self, name: &str) -> bool {
+        let defs = self.known_task_defs();
+        let graph = PipelineGraph::from_defs(&defs);
+        let ancestors = graph.ancestors(name);
+        if ancestors.is_empty() {
+            return false;
+        }
+        if let Some(cycle) = graph.cycle_through(name) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "Pipeline cycle ({}); fix depends_on/pipe_output_to.",
+                    cycle.join(" → ")
+                )
+            );
+            return true;
+        }
+
+        self.pipeline_inbox.remove(&name.to_ascii_lowercase());
+        for ancestor in &ancestors {
+            self.pipeline_inbox.remove(&ancestor.to_ascii_lowercase());
+        }
+        self.log(
+            LogLevel::Info,
+            format!("Running pipeline: {} → {name}", ancestors.join(" → ")),
+        );
+        let sources: Vec<DaemonTaskDef> = ancestors
+            .iter()
+            .filter(|ancestor| graph.upstream(ancestor).is_empty())
+            .filter_map(|source| defs.iter().find(|d| d.name.eq_ignore_ascii_case(source)))
+            .cloned()
+            .collect();
+        for def in sources {
+            self.run_daemon_oneshot(def);
+        }
+        true
+    }
+
+    /// `/daemon list` section describing configured pipelines.
+    pub(crate) fn pipeline_lines(&self) -> Vec<String> {
+        PipelineGraph::from_defs(&self.known_task_defs()).render()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(name: &str, depends_on: &[&str], pipe_output_to: &[&str]) -> DaemonTaskDef {
+        DaemonTaskDef {
+            name: name.to_string(),
+            prompt: format!("run {name}"),
+            interval_secs: 60,
+            depends_on: depends_on.iter().map(|s| s.to_string()).collect(),
+            pipe_output_to: pipe_output_to.iter().map(|s| s.to_string()).collect(),
+            paused: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn builds_edges_from_both_directions() {
+        let defs = vec![
+            def("collector", &[], &["Summarizer"]),
+            def("summarizer", &[], &[]),
+            def("publisher", &["summarizer", "audit"], &[]),
+            def("audit", &[], &[]),
+        ];
+        let graph = PipelineGraph::from_defs(&defs);
+        assert_eq!(graph.downstream("collector"), vec!["summarizer"]);
+        assert_eq!(graph.upstream("publisher"), vec!["summarizer", "audit"]);
+        assert_eq!(
+            graph.ancestors("publisher"),
+            vec!["collector", "summarizer", "audit"]
+        );
+        assert!(graph.cycle_through("collector").is_none());
+        assert_eq!(
+            graph.render(),
+            vec![
+                "  audit",
+                "     └▶ publisher  (waits for summarizer, audit)",
+                "  collector",
+                "     └▶ summarizer",
+                "        └▶ publisher  (waits for summarizer, audit)",
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_cycles() {
+        let defs = vec![def("a", &["b"], &[]), def("b", &["a"], &[])];
+        let graph = PipelineGraph::from_defs(&defs);
+        assert_eq!(graph.cycle_through("a").unwrap(), vec!["a", "b", "a"]);
+        assert_eq!(graph.render(), vec!["  (cycle — no source task)"]);
+    }
+
+    #[test]
+    fn appends_upstream_output_to_prompt() {
+        let prompt = pipeline_prompt(
+            "Summarize.",
+            &[("collector".to_string(), "3 new issues".to_string())],
+        );
+        assert!(prompt.starts_with("Summarize."));
+        assert!(prompt.contains("upstream task `collector` ---\n3 new issues"));
+    }
+}
@@ -0,0 +1,590 @@
+//! Tool permission policy — central allow/deny/approval rules for every
+//! tool call made by chat, daemons, and spawned agents.
+//!
+//! Rules are declared in the `[policy]` section of `memini.toml`:
+//!
+//! ```toml
+//! [policy]
+//! default = "allow"            # or "deny" to whitelist with allow rules
+//! rules = [
+//!   'deny workspace_run_command matching "rm -rf"',
+//!   'allow mcp:github.* read-only',
+//!   'require-approval workspace_write_file',
+//! ]
+//! ```
+//!
+//! Tool patterns are globs over the tool name.  MCP tools are matched as
+//! `mcp:<server>.<tool>` (as well as their namespaced `server__tool` form).
+//! `matching` is a plain substring test over the call's JSON arguments, not
+//! a shell parser: `rm -r -f`, extra spaces or a script that runs `rm`
+//! all slip past `matching "rm -rf"`.  Use `default = "deny"` with allow
+//! rules when a command must never run.
+//!
+//! A policy that fails to load fails closed: every tool call is denied
+//! until the file parses again (`/policy reload`).
+//! Denials and approval outcomes are appended to `policy_audit.log` in the
+//! config directory and surfaced in the activity log.
+
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, OnceLock, RwLock};
+use std::time::Duration;
+
+use anyhow::{Result, anyhow, bail};
+use directories::ProjectDirs;
+use globset::GlobBuilder;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::{mpsc, oneshot};
+
+use crate::constants::{APP_NAME, POLICY_APPROVAL_TIMEOUT_SECS};
+use crate::mcp;
+use crate::openai::ToolCall;
+
+use super::daemon::AgentEvent;
+
+/// Name fragments that mark a tool as mutating for `read-only` rules.
+const WRITE_VERBS: &[&str] = &[
+    "create", "update", "delete", "write", "remove", "post", "put", "patch", "push", "merge",
+    "send", "add", "set", "edit", "move", "rename", "archive", "close", "comment", "upload",
+];
+
+/// What a rule does when it matches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Effect {
+    Allow,
+    Deny,
+    RequireApproval,
+}
+
+impl Effect {
+    pub fn label(self) -> &'static str {
+        match self {
+            Effect::Allow => "allow",
+            Effect::Deny => "deny",
+            Effect::RequireApproval => "require-approval",
+        }
+    }
+}
+
+/// One parsed policy rule.
+#[derive(Clone, Debug)]
+pub struct PolicyRule {
+    pub effect: Effect,
+    pub tool_pattern: String,
+    pub matching: Option<String>,
+    pub read_only: bool,
+    pub source: String,
+}
+
+/// Outcome of evaluating a tool call against the policy.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Decision {
+    Allow,
+    Deny(String),
+    NeedsApproval(String),
+}
+
+/// The active rule set.
+#[derive(Clone, Debug)]
+pub struct Policy {
+    pub default_allow: bool,
+    pub rules: Vec<PolicyRule>,
+    pub source: Option<PathBuf>,
+    /// Why the policy file could not be loaded; every call is denied while set.
+    pub load_error: Option<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Policy {
+            default_allow: true,
+            rules: Vec::new(),
+            source: None,
+            load_error: None,
+        }
+    }
+}
+
+/// The `[policy]` section of `memini.toml`, before its rules are parsed.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct PolicySection {
+    #[serde(default)]
+    default: Option<String>,
+    #[serde(default)]
+    rules: Vec<String>,
+}
+
+impl PolicyRule {
+    /// Parse `<effect> <tool-glob> [matching "<text>"] [read-only]`.
+    pub fn parse(line: &str) -> Result<Self> {
+        let source = line.trim().to_string();
+        let (effect_raw, rest) = source
+            .split_once(char::is_whitespace)
+            .ok_or_else(|| anyhow!("rule needs an effect and a tool pattern: {source}"))?;
+        let effect = match effect_raw.to_ascii_lowercase().as_str() {
+            "allow" => Effect::Allow,
+            "deny" => Effect::Deny,
+            "require-approval" | "require_approval" | "approve" => Effect::RequireApproval,
+            other => bail!("unknown policy effect '{other}'"),
+        };
+
+        let rest = rest.trim();
+        let (tool_pattern, mut tail) = match rest.split_once(char::is_whitespace) {
+            Some((tool, tail)) => (tool.to_string(), tail.trim()),
+            None => (rest.to_string(), ""),
+        };
+
+        let mut matching = None;
+        let mut read_only = false;
+        while !tail.is_empty() {
+            if let Some(after) = tail.strip_prefix("matching") {
+                let after = after.trim_start();
+                let (text, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+                    let end = quoted
+                        .find('"')
+                        .ok_or_else(|| anyhow!("unterminated quote in rule: {source}"))?;
+                    (&quoted[..end], &quoted[end + 1..])
+                } else {
+                    after.split_once(char::is_whitespace).unwrap_or((after, ""))
+                };
+                matching = Some(text.to_string());
+                tail = remaining.trim();
+            } else if let Some(after) = tail
+                .strip_prefix("read-only")
+                .or_else(|| tail.strip_prefix("readonly"))
+            {
+                read_only = true;
+                tail = after.trim();
+            } else {
+                bail!("unexpected text in rule: {tail}");
+            }
+        }
+
+        Ok(PolicyRule {
+            effect,
+            tool_pattern,
+            matching,
+            read_only,
+            source,
+        })
+    }
+
+    fn matches(&self, tool_name: &str, arguments: &str) -> bool {
+        let matcher = match GlobBuilder::new(&self.tool_pattern)
+            .case_insensitive(true)
+            .build()
+        {
+            Ok(glob) => glob.compile_matcher(),
+            Err(_) => return false,
+        };
+        if !tool_aliases(tool_name)
+            .iter()
+            .any(|alias| matcher.is_match(alias))
+        {
+            return false;
+        }
+        match &self.matching {
+            Some(text) => arguments.contains(text.as_str()),
+            None => true,
+        }
+    }
+}
+
+impl Policy {
+    /// Build the policy from its `memini.toml` section.
+    pub fn from_section(section: &PolicySection) -> Result<Self> {
+        let default_allow = match section.default.as_deref().map(str::trim) {
+            None | Some("allow") => true,
+            Some("deny") => false,
+            Some(other) => bail!("policy default must be allow|deny, got '{other}'"),
+        };
+        let rules = section
+            .rules
+            .iter()
+            .map(|line| PolicyRule::parse(line))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Policy {
+            default_allow,
+            rules,
+            source: None,
+            load_error: None,
+        })
+    }
+
+    /// The policy in force while the file does not load: deny everything.
+    pub fn fail_closed(err: &anyhow::Error) -> Self {
+        Policy {
+            default_allow: false,
+            rules: Vec::new(),
+            source: crate::config::path().map(Path::to_path_buf),
+            load_error: Some(format!("{err:#}")),
+        }
+    }
+
+    /// Evaluate a tool call: deny rules win, then approval, then allow.
+    /// Calls the rules allow may still need approval built in (SQLite writes).
+    pub fn evaluate(&self, tool_name: &str, arguments: &Value) -> Decision {
+        if let Some(err) = &self.load_error {
+            return Decision::Deny(format!(
+                "denied: tool policy does not load ({err}); fix memini.toml and /policy reload"
+            ));
+        }
+        let decision = self.evaluate_rules(tool_name, arguments);
+        if decision == Decision::Allow
+            && tool_name == crate::sqlite_tool::SQLITE_QUERY_TOOL
+            && crate::sqlite_tool::wants_write(arguments)
+        {
+            return Decision::NeedsApproval(format!("built-in: {tool_name} write: true"));
+        }
+        decision
+    }
+
+    fn evaluate_rules(&self, tool_name: &str, arguments: &Value) -> Decision {
+        let args = arguments.to_string();
+        let matched: Vec<&PolicyRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(tool_name, &args))
+            .collect();
+
+        if let Some(rule) = matched.iter().find(|rule| rule.effect == Effect::Deny) {
+            return Decision::Deny(format!("denied by policy: {}", rule.source));
+        }
+        if let Some(rule) = matched
+            .iter()
+            .find(|rule| rule.effect == Effect::RequireApproval)
+        {
+            return Decision::NeedsApproval(rule.source.clone());
+        }
+
+        let allows: Vec<&&PolicyRule> = matched
+            .iter()
+            .filter(|rule| rule.effect == Effect::Allow)
+            .collect();
+        if allows.is_empty() {
+            if self.default_allow {
+                return Decision::Allow;
+            }
+            return Decision::Deny("denied by policy default (no allow rule matched)".to_string());
+        }
+        if allows.iter().any(|rule| !rule.read_only) || !is_mutating_tool(tool_name) {
+            return Decision::Allow;
+        }
+        Decision::Deny(format!(
+            "read-only policy blocks mutating tool: {}",
+            allows[0].source
+        ))
+    }
+}
+
+/// Names a tool can be matched under (`server__tool` is also `mcp:server.tool`).
+fn tool_aliases(tool_name: &str) -> Vec<String> {
+    let mut aliases = vec![tool_name.to_string()];
+    if let Some((server, tool)) = mcp::split_namespaced_tool_name(tool_name) {
+        aliases.push(format!("mcp:{server}.{tool}"));
+    }
+    aliases
+}
+
+fn is_mutating_tool(tool_name: &str) -> bool {
+    let name = mcp::split_namespaced_tool_name(tool_name)
+        .map(|(_, tool)| tool)
+        .unwrap_or(tool_name)
+        .to_ascii_lowercase();
+    let tokens: Vec<&str> = name
+        .split(|ch: char| !ch.is_ascii_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .collect();
+    // Exact verb tokens (`delete_page`), or a camelCase leading verb (`createIssue`).
+    WRITE_VERBS.iter().any(|verb| {
+        tokens.iter().any(|token| token == verb)
+            || tokens.first().is_some_and(|first| first.starts_with(verb))
+    })
+}
+
+// ── Loading ──────────────────────────────────────────────────────────
+
+fn policy_cell() -> &'static RwLock<Policy> {
+    static POLICY: OnceLock<RwLock<Policy>> = OnceLock::new();
+    POLICY.get_or_init(|| {
+        let loaded = crate::config::checked().and_then(|config| {
+            let mut policy = Policy::from_section(&config.policy)?;
+            policy.source = crate::config::path().map(Path::to_path_buf);
+            Ok(policy)
+        });
+        RwLock::new(loaded.unwrap_or_else(|err| Policy::fail_closed(&err)))
+    })
+}
+
+/// Read the policy from a fresh copy of `memini.toml` (empty policy when
+/// the file is absent).
+fn load_policy() -> Result<Policy> {
+    let mut policy = Policy::from_section(&crate::config::read()?.policy)?;
+    policy.source = crate::util::memini_config_path();
+    Ok(policy)
+}
+
+/// Snapshot of the active policy.
+pub fn current() -> Policy {
+    policy_cell()
+        .read()
+        .map(|policy| policy.clone())
+        .unwrap_or_default()
+}
+
+/// Re-read `memini.toml` and swap in the new policy.  On error the
+/// deny-everything policy is swapped in instead and the error returned.
+pub fn reload() -> Result<Policy> {
+    let loaded = load_policy();
+    let policy = match &loaded {
+        Ok(policy) => policy.clone(),
+        Err(err) => Policy::fail_closed(err),
+    };
+    if let Ok(mut guard) = policy_cell().write() {
+        *guard = policy;
+    }
+    loaded
+}
+
+// ── Approvals ────────────────────────────────────────────────────────
+
+fn pending_approvals() -> &'static Mutex<HashMap<usize, oneshot::Sender<bool>>> {
+    static PENDING: OnceLock<Mutex<HashMap<usize, oneshot::Sender<bool>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+static NEXT_APPROVAL_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Answer a pending approval request. Returns false if the id is unknown.
+pub fn resolve_approval(approval_id: usize, approved: bool) -> bool {
+    let sender = pending_approvals()
+        .lock()
+        .ok()
+        .and_then(|mut pending| pending.remove(&approval_id));
+    match sender {
+        Some(sender) => sender.send(approved).is_ok(),
+        None => false,
+    }
+}
+
+/// Ask the user to approve a tool call and wait for the answer.
+async fn request_approval(
+    call: &ToolCall,
+    context: &str,
+    rule: &str,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> bool {
+    let diff = crate::local_tools::preview_write(call)
+        .map(|change| change.diff_lines())
+        .unwrap_or_default();
+    await_approval(tx, |approval_id| AgentEvent::ApprovalRequested {
+        approval_id,
+        tool: call.name.clone(),
+        context: context.to_string(),
+        arguments: call.arguments.to_string(),
+        rule: rule.to_string(),
+        diff,
+    })
+    .await
+}
+
+/// Ask the user to let MCP server `server_id` run a model completion
+/// (`sampling/createMessage`); `preview` is the prompt it sent.
+pub async fn request_sampling_approval(
+    server_id: &str,
+    preview: &str,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> bool {
+    await_approval(tx, |approval_id| AgentEvent::ApprovalRequested {
+        approval_id,
+        tool: "sampling/createMessage".to_string(),
+        context: format!("mcp:{server_id}"),
+        arguments: preview.to_string(),
+        rule: "sampling mode ask".to_string(),
+        diff: Vec::new(),
+    })
+    .await
+}
+
+/// Register a pending approval, announce it with `event` and wait for
+/// `/policy approve|deny` (denied on timeout).
+async fn await_approval(
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+    event: impl FnOnce(usize) -> AgentEvent,
+) -> bool {
+    let approval_id = NEXT_APPROVAL_ID.fetch_add(1, Ordering::Relaxed);
+    let (sender, receiver) = oneshot::channel();
+    if let Ok(mut pending) = pending_approvals().lock() {
+        pending.insert(approval_id, sender);
+    }
+    let _ = tx.send(event(approval_id));
+
+    let timeout = Duration::from_secs(POLICY_APPROVAL_TIMEOUT_SECS);
+    let approved = matches!(tokio::time::timeout(timeout, receiver).await, Ok(Ok(true)));
+    if let Ok(mut pending) = pending_approvals().lock() {
+        pending.remove(&approval_id);
+    }
+    approved
+}
+
+// ── Enforcement ──────────────────────────────────────────────────────
+
+/// Gate a tool call. Returns `Some(error_json)` when the call must not run.
+pub async fn enforce(
+    call: &ToolCall,
+    context: &str,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Option<String> {
+    let reason = match current().evaluate(&call.name, &call.arguments) {
+        Decision::Allow => return None,
+        Decision::Deny(reason) => reason,
+        Decision::NeedsApproval(rule) => {
+            if request_approval(call, context, &rule, tx).await {
+                audit("approved", call, context, &rule);
+                return None;
+            }
+            format!("not approved: {rule}")
+        }
+    };
+
+    audit("denied", call, context, &reason);
+    let _ = tx.send(AgentEvent::PolicyDenied {
+        tool: call.name.clone(),
+        context: context.to_string(),
+        reason: reason.clone(),
+    });
+    Some(json!({ "error": format!("Tool '{}' {reason}", call.name) }).to_string())
+}
+
+/// Path of the append-only policy audit log.
+pub fn audit_log_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME)
+        .map(|dirs| dirs.config_dir().join("policy_audit.log"))
+}
+
+fn audit(outcome: &str, call: &ToolCall, context: &str, reason: &str) {
+    let Some(path) = audit_log_path() else {
+        return;
+    };
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let args_preview: String = call.arguments.to_string().chars().take(500).collect();
+    let line = json!({
+        "timestamp": chrono::Local::now().to_rfc3339(),
+        "outcome": outcome,
+        "tool": call.name,
+        "context": context,
+        "reason": reason,
+        "arguments": args_preview,
+    });
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "{line}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    fn policy(rules: &[&str]) -> Policy {
+        Policy {
+            default_allow: true,
+            rules: rules
+                .iter()
+                .map(|rule| PolicyRule::parse(rule).expect("parse rule"))
+                .collect(),
+            source: None,
+            load_error: None,
+        }
+    }
+
+    #[test]
+    fn parses_rule_forms() {
+        let rule =
+            PolicyRule::parse(r#"deny workspace_run_command matching "rm -rf""#).expect("parse");
+        assert_eq!(rule.effect, Effect::Deny);
+        assert_eq!(rule.tool_pattern, "workspace_run_command");
+        assert_eq!(rule.matching.as_deref(), Some("rm -rf"));
+
+        let rule = PolicyRule::parse("allow mcp:github.* read-only").expect("parse");
+        assert_eq!(rule.effect, Effect::Allow);
+        assert!(rule.read_only);
+
+        assert!(PolicyRule::parse("maybe foo").is_err());
+    }
+
+    #[test]
+    fn deny_matching_only_blocks_matching_arguments() {
+        let policy = policy(&[r#"deny workspace_run_command matching "rm -rf""#]);
+        assert!(matches!(
+            policy.evaluate("workspace_run_command", &json!({"command": "rm -rf /"})),
+            Decision::Deny(_)
+        ));
+        assert_eq!(
+            policy.evaluate("workspace_run_command", &json!({"command": "ls"})),
+            Decision::Allow
+        );
+    }
+
+    #[test]
+    fn read_only_allow_blocks_mutating_mcp_tools() {
+        let policy = policy(&["allow mcp:github.* read-only"]);
+        assert_eq!(
+            policy.evaluate("github__search_issues", &json!({})),
+            Decision::Allow
+        );
+        assert!(matches!(
+            policy.evaluate("github__create_issue", &json!({})),
+            Decision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn require_approval_and_default_deny() {
+        let policy = policy(&["require-approval workspace_write_file"]);
+        assert!(matches!(
+            policy.evaluate("workspace_write_file", &json!({})),
+            Decision::NeedsApproval(_)
+        ));
+        assert_eq!(
+            policy.evaluate("workspace_sqlite_query", &json!({"write": false})),
+            Decision::Allow
+        );
+        assert!(matches!(
+            policy.evaluate("workspace_sqlite_query", &json!({"write": true})),
+            Decision::NeedsApproval(_)
+        ));
+
+        let strict = Policy::from_section(
+            &MeminiToml::parse(
+                "[policy]\ndefault = \"deny\"\nrules = ['allow workspace_read_file']\n",
+            )
+            .expect("parse toml")
+            .policy,
+        )
+        .expect("parse policy");
+        assert_eq!(
+            strict.evaluate("workspace_read_file", &json!({})),
+            Decision::Allow
+        );
+        assert!(matches!(
+            strict.evaluate("workspace_run_command", &json!({})),
+            Decision::Deny(_)
+        ));
+    }
+
+    #[test]
+    fn unloadable_policy_denies_every_call() {
+        let broken = Policy::fail_closed(&anyhow!("parse memini.toml"));
+        assert!(matches!(
+            broken.evaluate("workspace_read_file", &json!({})),
+            Decision::Deny(_)
+        ));
+    }
+}
@@ -0,0 +1,392 @@
+//! Background processes for agent windows: `process_spawn`,
+//! `process_list` and `process_kill`.
+//!
+//! `workspace_run_command` waits for its command to end, which rules out
+//! dev servers and watchers.  `process_spawn` starts a command and returns
+//! at once, so an agent can start a server, check it with
+//! `http_request` and stop it again.  Processes are built by
+//! [`sandbox::build_command`] (the `[sandbox]` backend and
+//! `allowed_binaries` apply) and keep the last [`PROCESS_OUTPUT_LINES`]
+//! lines of their merged stdout and stderr.
+//!
+//! Every process belongs to the window that spawned it and outlives the
+//! window's runs.  They are force-killed by `process_kill`, `/kill` on the
+//! window, and on app exit.  On Unix each process leads its own process
+//! group, and the whole group is killed, so a server started through
+//! `npm run` or `cd … &&` goes down with its shell.
+
+use std::collections::{HashMap, VecDeque};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::Child;
+use tokio::task::JoinHandle;
+
+use crate::constants::{MAX_PROCESSES_PER_WINDOW, PROCESS_OUTPUT_LINES};
+use crate::local_tools::{resolve_workspace_path, to_workspace_relative};
+use crate::openai::ToolCall;
+use crate::sandbox;
+
+pub const PROCESS_TOOLS: &[&str] = &["process_spawn", "process_list", "process_kill"];
+
+/// Output lines returned per process by `process_list` and `process_spawn`.
+const OUTPUT_TAIL: usize = 20;
+
+/// Longest `process_spawn` may watch a new process before returning.
+const MAX_STARTUP_WAIT_SECS: u64 = 30;
+
+/// How long an ended process's output readers get to drain its pipes.
+/// Only a child that escaped the process group can hold them open longer.
+const DRAIN_TIMEOUT: Duration = Duration::from_secs(2);
+
+static NEXT_PROCESS: AtomicUsize = AtomicUsize::new(1);
+
+type Output = Arc<Mutex<VecDeque<String>>>;
+
+struct Process {
+    id: usize,
+    command: String,
+    workdir: String,
+    child: Child,
+    output: Output,
+    /// The stdout and stderr readers, awaited once the process ends.
+    readers: Vec<JoinHandle<()>>,
+    started: Instant,
+}
+
+impl Process {
+    /// `"running"`, or how the process ended.
+    fn status(&mut self) -> (&'static str, Option<i32>) {
+        match self.child.try_wait() {
+            Ok(None) => ("running", None),
+            Ok(Some(status)) => ("exited", status.code()),
+            Err(_) => ("unknown", None),
+        }
+    }
+
+    /// SIGKILL the process and, on Unix, its process group.
+    fn force_kill(&mut self) {
+        #[cfg(unix)]
+        if let Some(pid) = self.child.id() {
+            use nix::sys::signal::{Signal, killpg};
+            use nix::unistd::Pid;
+            let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+        }
+        let _ = self.child.start_kill();
+    }
+
+    fn tail(&self, lines: usize) -> String {
+        let output = self.output.lock().unwrap_or_else(|err| err.into_inner());
+        let skip = output.len().saturating_sub(lines);
+        output
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    fn describe(&mut self) -> Value {
+        let (status, exit_code) = self.status();
+        json!({
+            "id": self.id,
+            "pid": self.child.id(),
+            "command": self.command,
+            "workdir": self.workdir,
+            "status": status,
+            "exit_code": exit_code,
+            "uptime_seconds": self.started.elapsed().as_secs(),
+            "output": self.tail(OUTPUT_TAIL),
+        })
+    }
+}
+
+fn registry() -> &'static Mutex<HashMap<usize, Vec<Process>>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<usize, Vec<Process>>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn with_processes<T>(window_id: usize, f: impl FnOnce(&mut Vec<Process>) -> T) -> T {
+    let mut registry = registry().lock().unwrap_or_else(|err| err.into_inner());
+    f(registry.entry(window_id).or_default())
+}
+
+/// Force-kill every process `window_id` spawned; returns how many there were.
+pub fn close_window(window_id: usize) -> usize {
+    let processes = registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .remove(&window_id)
+        .unwrap_or_default();
+    let count = processes.len();
+    for mut process in processes {
+        process.force_kill();
+        for reader in &process.readers {
+            reader.abort();
+        }
+    }
+    count
+}
+
+/// Force-kill every tracked process (app exit).
+pub fn kill_all() {
+    let windows: Vec<usize> = registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .keys()
+        .copied()
+        .collect();
+    for window_id in windows {
+        close_window(window_id);
+    }
+}
+
+pub fn tool_defs() -> Vec<Value> {
+    vec![
+        json!({
+            "type": "function",
+            "name": "process_spawn",
+            "description": "Start a long-running command in the background (a dev server, a watcher) and return its process id without waiting for it to finish. Watches it for wait_seconds first and returns its early output, or its exit code if it already ended. Stop it with process_kill when done.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "Shell command to start."
+                    },
+                    "workdir": {
+                        "type": "string",
+                        "description": "Relative working directory (default workspace root)."
+                    },
+                    "wait_seconds": {
+                        "type": "integer",
+                        "description": "Seconds to watch the process before returning (default 2, max 30)."
+                    }
+                },
+                "required": ["command"]
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "process_list",
+            "description": "List the background processes this agent started, with status, exit code, uptime and the last lines of output.",
+            "parameters": { "type": "object", "properties": {} }
+        }),
+        json!({
+            "type": "function",
+            "name": "process_kill",
+            "description": "Force-kill a background process started with process_spawn and return its last output.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "id": {
+                        "type": "integer",
+                        "description": "Process id returned by process_spawn."
+                    }
+                },
+                "required": ["id"]
+            }
+        }),
+    ]
+}
+
+/// Handle a process tool call from window `window_id`; `None` for other
+/// tools.
+pub async fn handle_tool_call(window_id: usize, call: &ToolCall) -> Option<String> {
+    let result = match call.name.as_str() {
+        "process_spawn" => spawn(window_id, &call.arguments).await,
+        "process_list" => Ok(list(window_id)),
+        "process_kill" => kill(window_id, &call.arguments).await,
+        _ => return None,
+    };
+    Some(
+        result
+            .unwrap_or_else(|err| json!({ "error": format!("{err:#}") }))
+            .to_string(),
+    )
+}
+
+/// Wait for `readers` to reach the end of the pipes, so the output holds
+/// everything the process printed.
+async fn drain(readers: Vec<JoinHandle<()>>) {
+    for reader in readers {
+        let abort = reader.abort_handle();
+        if tokio::time::timeout(DRAIN_TIMEOUT, reader).await.is_err() {
+            abort.abort();
+        }
+    }
+}
+
+/// Append lines read from `pipe` to `output` until it closes.
+fn capture(pipe: impl AsyncRead + Unpin + Send + 'static, output: Output) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(pipe).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let mut output = output.lock().unwrap_or_else(|err| err.into_inner());
+            if output.len() >= PROCESS_OUTPUT_LINES {
+                output.pop_front();
+            }
+            output.push_back(line);
+        }
+    })
+}
+
+async fn spawn(window_id: usize, args: &Value) -> Result<Value> {
+    let command = args
+        .get("command")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .ok_or_else(|| anyhow!("command is required"))?;
+    let wait_seconds = args
+        .get("wait_seconds")
+        .and_then(Value::as_u64)
+        .unwrap_or(2)
+        .min(MAX_STARTUP_WAIT_SECS);
+    let running = with_processes(window_id, |processes| {
+        processes
+            .iter_mut()
+            .filter(|process| process.status().0 == "running")
+            .count()
+    });
+    if running >= MAX_PROCESSES_PER_WINDOW {
+        bail!(
+            "{running} processes are already running (max {MAX_PROCESSES_PER_WINDOW}); stop one with process_kill first"
+        );
+    }
+
+    let workdir_arg = args.get("workdir").and_then(Value::as_str).unwrap_or(".");
+    let (root, workdir) = resolve_workspace_path(workdir_arg)?;
+    if !workdir.is_dir() {
+        bail!(
+            "Working directory is not a directory: {}",
+            workdir.display()
+        );
+    }
+    let config = sandbox::load_config()?;
+    let mut cmd = sandbox::build_command(&config, command, &root, &workdir, &[])?;
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Start process")?;
+    let output = Output::default();
+    let readers = vec![
+        capture(
+            child.stdout.take().context("process stdout")?,
+            output.clone(),
+        ),
+        capture(
+            child.stderr.take().context("process stderr")?,
+            output.clone(),
+        ),
+    ];
+
+    let id = NEXT_PROCESS.fetch_add(1, Ordering::Relaxed);
+    with_processes(window_id, |processes| {
+        processes.push(Process {
+            id,
+            command: command.to_string(),
+            workdir: to_workspace_relative(&workdir, &root),
+            child,
+            output,
+            readers,
+            started: Instant::now(),
+        })
+    });
+
+    // Watch the start-up so a server that fails at once is reported.
+    let deadline = Instant::now() + Duration::from_secs(wait_seconds);
+    while Instant::now() < deadline {
+        let exited = with_processes(window_id, |processes| {
+            processes
+                .iter_mut()
+                .find(|process| process.id == id)
+                .is_none_or(|process| process.status().0 != "running")
+        });
+        if exited {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let ended_readers = with_processes(window_id, |processes| {
+        processes
+            .iter_mut()
+            .find(|process| process.id == id)
+            .filter(|process| process.status().0 != "running")
+            .map(|process| std::mem::take(&mut process.readers))
+            .unwrap_or_default()
+    });
+    drain(ended_readers).await;
+    with_processes(window_id, |processes| {
+        processes
+            .iter_mut()
+            .find(|process| process.id == id)
+            .map(Process::describe)
+    })
+    .context("process was killed while starting")
+}
+
+fn list(window_id: usize) -> Value {
+    let processes = with_processes(window_id, |processes| {
+        processes
+            .iter_mut()
+            .map(Process::describe)
+            .collect::<Vec<_>>()
+    });
+    json!({ "processes": processes })
+}
+
+async fn kill(window_id: usize, args: &Value) -> Result<Value> {
+    let id = args
+        .get("id")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| anyhow!("id is required"))? as usize;
+    let mut process = with_processes(window_id, |processes| {
+        let index = processes.iter().position(|process| process.id == id)?;
+        Some(processes.remove(index))
+    })
+    .ok_or_else(|| anyhow!("No process {id}; see process_list"))?;
+    let was_running = process.status().0 == "running";
+    process.force_kill();
+    let exit_code = process
+        .child
+        .wait()
+        .await
+        .ok()
+        .and_then(|status| status.code());
+    drain(std::mem::take(&mut process.readers)).await;
+    Ok(json!({
+        "id": id,
+        "status": if was_running { "killed" } else { "exited" },
+        "exit_code": exit_code,
+        "output": process.tail(OUTPUT_TAIL),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn output_keeps_the_latest_lines() {
+        let output = Output::default();
+        let text: String = (0..PROCESS_OUTPUT_LINES + 5)
+            .map(|n| format!("line {n}\n"))
+            .collect();
+        capture(std::io::Cursor::new(text.into_bytes()), output.clone())
+            .await
+            .expect("capture");
+        let output = output.lock().expect("output");
+        assert_eq!(output.len(), PROCESS_OUTPUT_LINES);
+        assert_eq!(output.front().map(String::as_str), Some("line 5"));
+    }
+}
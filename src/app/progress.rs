@@ -0,0 +1,254 @@
+//! Progress indicators for long operations: connecting MCP servers,
+//! importing skills, waiting for the model.
+//!
+//! [`start`] registers an operation and returns a guard that unregisters
+//! it when dropped; the status bar shows what is registered, with a
+//! spinner and the elapsed time.  Guards are `Send`, so background tasks
+//! (the chat turn) register their waits the same way.
+//!
+//! Work that blocks the UI thread goes through [`block_on`] instead.  The
+//! main loop cannot draw while it waits, so a ticker thread paints the
+//! same line on the terminal's bottom row until the work ends, and the
+//! next frame is repainted in full ([`take_repaint`]).  The ticker only
+//! runs once [`attach_terminal`] is called, so tests never write to the
+//! terminal.
+
+use std::future::Future;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crossterm::QueueableCommand;
+use crossterm::{cursor, style, terminal};
+use tokio::runtime::Runtime;
+
+/// Spinner frames shared by every progress indicator.
+pub const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+/// Operations that end sooner than this never show, so quick calls do not
+/// flicker.
+const SHOW_AFTER: Duration = Duration::from_millis(300);
+
+/// How often the ticker repaints.
+const TICKER_INTERVAL: Duration = Duration::from_millis(100);
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+static TERMINAL: AtomicBool = AtomicBool::new(false);
+static TICKING: AtomicBool = AtomicBool::new(false);
+static REPAINT: AtomicBool = AtomicBool::new(false);
+
+struct Entry {
+    id: usize,
+    label: String,
+    detail: Option<String>,
+    started: Instant,
+}
+
+fn registry() -> &'static Mutex<Vec<Entry>> {
+    static REGISTRY: OnceLock<Mutex<Vec<Entry>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(Vec::new()))
+}
+
+fn with_entries<T>(f: impl FnOnce(&mut Vec<Entry>) -> T) -> T {
+    f(&mut registry().lock().unwrap_or_else(|err| err.into_inner()))
+}
+
+/// A registered operation; dropping it ends the operation.
+pub struct Progress {
+    id: usize,
+    ticker: Option<Ticker>,
+}
+
+impl Progress {
+    /// Show `detail` after the label (`2/3`, `call 4`).
+    pub fn set_detail(&self, detail: impl Into<String>) {
+        let detail = detail.into();
+        with_entries(|entries| {
+            if let Some(entry) = entries.iter_mut().find(|entry| entry.id == self.id) {
+                entry.detail = Some(detail);
+            }
+        });
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        with_entries(|entries| entries.retain(|entry| entry.id != self.id));
+        if let Some(ticker) = self.ticker.take() {
+            ticker.stop();
+        }
+    }
+}
+
+/// Register an operation until the returned guard is dropped.
+pub fn start(label: impl Into<String>) -> Progress {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    with_entries(|entries| {
+        entries.push(Entry {
+            id,
+            label: label.into(),
+            detail: None,
+            started: Instant::now(),
+        })
+    });
+    Progress { id, ticker: None }
+}
+
+/// Like [`start`], for work that blocks the UI thread: the ticker paints
+/// the operation while the main loop cannot.
+pub fn start_blocking(label: impl Into<String>) -> Progress {
+    let mut progress = start(label);
+    progress.ticker = Ticker::start();
+    progress
+}
+
+/// Run `future` to completion on `runtime`, showing `label` meanwhile.
+pub fn block_on<F: Future>(runtime: &Runtime, label: impl Into<String>, future: F) -> F::Output {
+    let _progress = start_blocking(label);
+    runtime.block_on(future)
+}
+
+/// An operation as the status bar shows it.
+#[derive(Clone, Debug)]
+pub struct Active {
+    pub label: String,
+    pub detail: Option<String>,
+    pub elapsed: Duration,
+}
+
+impl Active {
+    /// `Connecting github · 2/3 · 4s`
+    pub fn describe(&self) -> String {
+        let mut text = self.label.clone();
+        if let Some(detail) = &self.detail {
+            text.push_str(" · ");
+            text.push_str(detail);
+        }
+        text.push_str(&format!(" · {}s", self.elapsed.as_secs()));
+        text
+    }
+}
+
+/// Operations running longer than [`SHOW_AFTER`], oldest first.
+pub fn active() -> Vec<Active> {
+    with_entries(|entries| {
+        entries
+            .iter()
+            .map(|entry| Active {
+                label: entry.label.clone(),
+                detail: entry.detail.clone(),
+                elapsed: entry.started.elapsed(),
+            })
+            .filter(|active| active.elapsed >= SHOW_AFTER)
+            .collect()
+    })
+}
+
+/// The status-bar text for [`active`]: the oldest operation and how many
+/// more there are, or `None` when nothing is running.
+pub fn summary() -> Option<String> {
+    let active = active();
+    let first = active.first()?;
+    Some(match active.len() {
+        1 => first.describe(),
+        count => format!("{} (+{})", first.describe(), count - 1),
+    })
+}
+
+/// Let [`start_blocking`] paint on the terminal (called once the TUI is up).
+pub fn attach_terminal() {
+    TERMINAL.store(true, Ordering::SeqCst);
+}
+
+/// Whether the ticker painted over the last frame since the last call.
+pub fn take_repaint() -> bool {
+    REPAINT.swap(false, Ordering::SeqCst)
+}
+
+/// Paints [`summary`] on the bottom row while UI-thread work blocks.
+struct Ticker {
+    stop: Arc<AtomicBool>,
+    thread: JoinHandle<()>,
+}
+
+impl Ticker {
+    /// `None` without a terminal or when an outer operation already ticks
+    /// (it paints the nested operations too).
+    fn start() -> Option<Self> {
+        if !TERMINAL.load(Ordering::SeqCst) || TICKING.swap(true, Ordering::SeqCst) {
+            return None;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let flag = stop.clone();
+        let thread = std::thread::spawn(move || {
+            let started = Instant::now();
+            while !flag.load(Ordering::SeqCst) {
+                std::thread::sleep(TICKER_INTERVAL);
+                let Some(text) = summary() else {
+                    continue;
+                };
+                let frame = (started.elapsed().as_millis() / TICKER_INTERVAL.as_millis()) as usize;
+                if paint(&format!(" {} {text}", SPINNER[frame % SPINNER.len()])).is_ok() {
+                    REPAINT.store(true, Ordering::SeqCst);
+                }
+            }
+        });
+        Some(Ticker { stop, thread })
+    }
+
+    fn stop(self) {
+        self.stop.store(true, Ordering::SeqCst);
+        let _ = self.thread.join();
+        TICKING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Overwrite the terminal's bottom row with `line`.
+fn paint(line: &str) -> io::Result<()> {
+    let (columns, rows) = terminal::size()?;
+    let line: String = line.chars().take(columns as usize).collect();
+    let mut stdout = io::stdout();
+    stdout
+        .queue(cursor::SavePosition)?
+        .queue(cursor::MoveTo(0, rows.saturating_sub(1)))?
+        .queue(terminal::Clear(terminal::ClearType::CurrentLine))?
+        .queue(style::Print(line))?
+        .queue(cursor::RestorePosition)?;
+    stdout.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards_show_while_alive() {
+        let progress = start("Importing skill");
+        progress.set_detail("acme/review");
+        with_entries(|entries| {
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.id == progress.id)
+                .expect("registered");
+            entry.started -= SHOW_AFTER;
+        });
+        let shown = active()
+            .into_iter()
+            .find(|active| active.label == "Importing skill")
+            .expect("shown");
+        assert!(
+            shown
+                .describe()
+                .starts_with("Importing skill · acme/review · ")
+        );
+
+        drop(progress);
+        assert!(
+            active()
+                .iter()
+                .all(|active| active.label != "Importing skill")
+        );
+    }
+}
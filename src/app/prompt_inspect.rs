@@ -0,0 +1,173 @@
+//! Record of exactly what the last main-chat turn sent to the model, for
+//! `/prompt show` with no template name and for `/dry` runs.
+//!
+//! The chat task labels every input message as it builds the request
+//! (system prompt, memory context, skills, thread history, …) and hands the
+//! snapshot back through [`super::daemon::AgentEvent::ChatPromptSent`].
+
+use serde_json::Value;
+
+#[derive(Clone, Debug, Default)]
+pub struct PromptSnapshot {
+    pub agent_name: String,
+    pub model: String,
+    /// Local time the request was built.
+    pub timestamp: String,
+    pub sections: Vec<PromptSection>,
+    /// Names of the tools offered to the model.
+    pub tools: Vec<String>,
+    /// Serialized size of those tools' JSON schemas.
+    pub tool_schema_chars: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct PromptSection {
+    /// What this message is, e.g. `system prompt` or `thread history`.
+    pub label: String,
+    pub role: String,
+    pub content: String,
+}
+
+impl PromptSnapshot {
+    pub fn new(agent_name: &str) -> Self {
+        Self {
+            agent_name: agent_name.to_string(),
+            timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Append `message` to the request `input` and record it under `label`.
+    pub fn push(&mut self, input: &mut Vec<Value>, label: &str, message: Value) {
+        self.sections.push(PromptSection {
+            label: label.to_string(),
+            role: message
+                .get("role")
+                .and_then(Value::as_str)
+                .unwrap_or("item")
+                .to_string(),
+            content: content_text(message.get("content").unwrap_or(&message)),
+        });
+        input.push(message);
+    }
+
+    /// Record the tools offered to the model.
+    pub fn set_tools(&mut self, tools: &[Value]) {
+        self.tools = tool_names(tools);
+        self.tool_schema_chars = tools.iter().map(|tool| tool.to_string().len()).sum();
+    }
+
+    /// Rough input token count: ~4 characters per token for messages and
+    /// tool schemas, plus a few tokens of framing per message.
+    pub fn estimated_tokens(&self) -> usize {
+        (self.total_chars() + self.tool_schema_chars).div_ceil(4) + 4 * self.sections.len()
+    }
+
+    pub fn total_chars(&self) -> usize {
+        self.sections
+            .iter()
+            .map(|s| s.content.chars().count())
+            .sum()
+    }
+
+    /// Markdown rendering for the activity log.
+    pub fn markdown(&self) -> String {
+        let mut out = format!(
+            "**Agent:** {} · **Model:** {} · **Built:** {} · {} messages, {} chars · ≈{} input tokens\n",
+            self.agent_name,
+            self.model,
+            self.timestamp,
+            self.sections.len(),
+            self.total_chars(),
+            self.estimated_tokens()
+        );
+        if self.tools.is_empty() {
+            out.push_str("\n**Tools:** none\n");
+        } else {
+            out.push_str(&format!(
+                "\n**Tools ({}, {} schema chars):** {}\n",
+                self.tools.len(),
+                self.tool_schema_chars,
+                self.tools.join(", ")
+            ));
+        }
+        for (index, section) in self.sections.iter().enumerate() {
+            out.push_str(&format!(
+                "\n### {}. {} · {} ({} chars)\n\n```text\n{}\n```\n",
+                index + 1,
+                section.label,
+                section.role,
+                section.content.chars().count(),
+                section.content.replace("```", "'''")
+            ));
+        }
+        out
+    }
+}
+
+/// Plain text of a message's `content`: strings as-is, content-part arrays
+/// joined with image parts shown as placeholders.
+fn content_text(content: &Value) -> String {
+    match content {
+        Value::String(text) => text.clone(),
+        Value::Array(parts) => parts
+            .iter()
+            .map(|part| match part.get("type").and_then(Value::as_str) {
+                Some("input_image") => "[image]".to_string(),
+                _ => part
+                    .get("text")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .unwrap_or_else(|| part.to_string()),
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        other => other.to_string(),
+    }
+}
+
+/// Names of Responses-API tool definitions.
+fn tool_names(tools: &[Value]) -> Vec<String> {
+    tools
+        .iter()
+        .filter_map(|tool| tool.get("name").and_then(Value::as_str))
+        .map(str::to_string)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn records_labelled_sections_alongside_input() {
+        let mut input = Vec::new();
+        let mut snapshot = PromptSnapshot::new("memini");
+        snapshot.push(
+            &mut input,
+            "system prompt",
+            json!({"role": "system", "content": "Be brief."}),
+        );
+        snapshot.push(
+            &mut input,
+            "user message",
+            json!({"role": "user", "content": [
+                {"type": "input_text", "text": "what is this?"},
+                {"type": "input_image", "image_url": "data:image/png;base64,AAAA"},
+            ]}),
+        );
+        snapshot.set_tools(&[json!({"type": "function", "name": "rice_memories"})]);
+
+        assert_eq!(input.len(), 2);
+        assert_eq!(snapshot.sections[1].role, "user");
+        assert_eq!(snapshot.sections[1].content, "what is this?\n[image]");
+        let markdown = snapshot.markdown();
+        assert!(markdown.contains("### 1. system prompt · system (9 chars)"));
+        assert!(markdown.contains("**Tools (1, 42 schema chars):** rice_memories"));
+        // 30 message chars + 42 schema chars ≈ 18 tokens, plus framing.
+        assert_eq!(snapshot.estimated_tokens(), 18 + 8);
+        assert!(!markdown.contains("base64"));
+    }
+}
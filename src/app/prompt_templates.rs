@@ -0,0 +1,265 @@
+//! File-backed prompt templates for `/prompt`, stored in
+//! `$MEMINI_HOME/prompts` alongside the prompt overrides.
+//!
+//! Templates are Markdown with optional front matter and `{{variable}}`
+//! placeholders:
+//!
+//! ```text
+//! ---
+//! description: triage a bug report
+//! model: gpt-5
+//! persona: coder
+//! ---
+//! Triage this bug in {{component}} with severity {{severity}}:
+//! {{report}}
+//! ```
+//!
+//! `persona` names a custom agent, or is used verbatim as the persona.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+
+use super::agent_recipes::{sanitize_name, split_front_matter, yaml_quote};
+use crate::util::memini_home;
+
+#[derive(Clone, Debug)]
+pub struct PromptTemplate {
+    pub name: String,
+    pub description: String,
+    /// Model for runs of this template, overriding routing.
+    pub model: Option<String>,
+    /// Agent name or persona text for runs of this template.
+    pub persona: Option<String>,
+    pub body: String,
+    pub path: PathBuf,
+}
+
+impl PromptTemplate {
+    /// Placeholder names in order of first use.
+    pub fn variables(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let name = after[..end].trim().to_string();
+            if is_variable_name(&name) && !names.contains(&name) {
+                names.push(name);
+            }
+            rest = &after[end + 2..];
+        }
+        names
+    }
+
+    /// Substitute every `{{variable}}`; fails listing any left unset.
+    pub fn render(&self, values: &HashMap<String, String>) -> Result<String> {
+        let missing: Vec<String> = self
+            .variables()
+            .into_iter()
+            .filter(|name| !values.contains_key(name))
+            .collect();
+        if !missing.is_empty() {
+            bail!("missing value(s) for {}", missing.join(", "));
+        }
+
+        let mut out = String::new();
+        let mut rest = self.body.as_str();
+        while let Some(start) = rest.find("{{") {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                out.push_str(&rest[start..]);
+                return Ok(out);
+            };
+            let name = after[..end].trim();
+            match values.get(name) {
+                Some(value) if is_variable_name(name) => out.push_str(value),
+                _ => out.push_str(&rest[start..start + 2 + end + 2]),
+            }
+            rest = &after[end + 2..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+pub fn prompts_dir() -> PathBuf {
+    memini_home().join("prompts")
+}
+
+/// All templates in the prompts dir, sorted by name.
+pub fn load_templates() -> Result<Vec<PromptTemplate>> {
+    let dir = prompts_dir();
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut templates = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Read {}", dir.display()))? {
+        let path = entry?.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        if !file_name.ends_with(".md") || crate::prompts::is_override_file(file_name) {
+            continue;
+        }
+        let raw = fs::read_to_string(&path).with_context(|| format!("Read {}", path.display()))?;
+        templates.push(parse_template(path.clone(), &raw));
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+pub fn find_template(name: &str) -> Result<Option<PromptTemplate>> {
+    let name = sanitize_name(name)?;
+    Ok(load_templates()?
+        .into_iter()
+        .find(|template| template.name == name))
+}
+
+/// Write a template; refuses to clobber an existing one unless `overwrite`.
+pub fn save_template(
+    name: &str,
+    body: &str,
+    model: Option<&str>,
+    persona: Option<&str>,
+    overwrite: bool,
+) -> Result<PathBuf> {
+    let name = sanitize_name(name)?;
+    if body.trim().is_empty() {
+        bail!("template body cannot be empty");
+    }
+    let file_name = format!("{name}.md");
+    if crate::prompts::is_override_file(&file_name) {
+        bail!("'{name}' is reserved for a prompt override");
+    }
+    let dir = prompts_dir();
+    fs::create_dir_all(&dir).with_context(|| format!("Create {}", dir.display()))?;
+    let path = dir.join(file_name);
+    if path.exists() && !overwrite {
+        bail!(
+            "Prompt template already exists: {} (use --force)",
+            path.display()
+        );
+    }
+
+    let mut front = String::new();
+    if let Some(model) = model {
+        front.push_str(&format!("model: {}\n", yaml_quote(model)));
+    }
+    if let Some(persona) = persona {
+        front.push_str(&format!("persona: {}\n", yaml_quote(persona)));
+    }
+    let content = if front.is_empty() {
+        format!("{}\n", body.trim())
+    } else {
+        format!("---\n{front}---\n{}\n", body.trim())
+    };
+    fs::write(&path, content).with_context(|| format!("Write {}", path.display()))?;
+    Ok(path)
+}
+
+pub fn remove_template(name: &str) -> Result<Option<PathBuf>> {
+    let Some(template) = find_template(name)? else {
+        return Ok(None);
+    };
+    fs::remove_file(&template.path)
+        .with_context(|| format!("Remove {}", template.path.display()))?;
+    Ok(Some(template.path))
+}
+
+/// Parse `key=value` arguments.  Words without `=` continue the previous
+/// value, so `title=login page broken` needs no quoting.
+pub fn parse_assignments(args: &[&str]) -> Result<HashMap<String, String>> {
+    let mut values: HashMap<String, String> = HashMap::new();
+    let mut current: Option<String> = None;
+    for arg in args {
+        match arg.split_once('=') {
+            Some((key, value)) if is_variable_name(key) => {
+                values.insert(key.to_string(), value.to_string());
+                current = Some(key.to_string());
+            }
+            _ => {
+                let Some(key) = &current else {
+                    bail!("expected key=value, got '{arg}'");
+                };
+                if let Some(value) = values.get_mut(key) {
+                    value.push(' ');
+                    value.push_str(arg);
+                }
+            }
+        }
+    }
+    for value in values.values_mut() {
+        let trimmed = value.trim();
+        let unquoted = trimmed
+            .strip_prefix('"')
+            .and_then(|inner| inner.strip_suffix('"'))
+            .unwrap_or(trimmed);
+        *value = unquoted.to_string();
+    }
+    Ok(values)
+}
+
+fn parse_template(path: PathBuf, raw: &str) -> PromptTemplate {
+    let (front_matter, body) = split_front_matter(raw);
+    let name = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("prompt")
+        .to_string();
+    let field = |key: &str| {
+        front_matter
+            .get(key)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    };
+    PromptTemplate {
+        name,
+        description: field("description").unwrap_or_default(),
+        model: field("model"),
+        persona: field("persona"),
+        body: body.trim().to_string(),
+        path,
+    }
+}
+
+fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-' || ch == '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_variables_and_reports_missing() {
+        let template = parse_template(
+            PathBuf::from("bug-triage.md"),
+            "---\nmodel: gpt-5\npersona: coder\n---\nTriage {{component}} ({{ severity }}): {{component}} is down. {{not a var}}\n",
+        );
+        assert_eq!(template.name, "bug-triage");
+        assert_eq!(template.model.as_deref(), Some("gpt-5"));
+        assert_eq!(template.persona.as_deref(), Some("coder"));
+        assert_eq!(template.variables(), vec!["component", "severity"]);
+
+        let values = parse_assignments(&["component=login", "page", "severity=high"]).unwrap();
+        assert_eq!(
+            template.render(&values).unwrap(),
+            "Triage login page (high): login page is down. {{not a var}}"
+        );
+
+        let err = template
+            .render(&parse_assignments(&["component=api"]).unwrap())
+            .unwrap_err();
+        assert!(err.to_string().contains("severity"));
+        assert!(parse_assignments(&["stray"]).is_err());
+    }
+}
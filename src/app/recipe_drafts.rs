@@ -0,0 +1,327 @@
+//! `/daemon generate` — draft a recipe from a plain-language description.
+//!
+//! The model is asked for JSON matching [`recipe_schema`] (the fields of
+//! [`NewAgentRecipe`]), which is checked like any `/json` reply.  The result
+//! is kept as the pending draft and shown as the Markdown file it would
+//! become.  `/daemon generate set <field> <value>` edits it,
+//! `/daemon generate save [--start]` writes it with
+//! [`agent_recipes::write_recipe_file`], and `/daemon generate discard`
+//! drops it.  Nothing touches `$MEMINI_HOME/agents` until `save`.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use crate::routing::TaskKind;
+
+use super::App;
+use super::agent_recipes::{self, NewAgentRecipe};
+use super::daemon::{self, AgentEvent};
+use super::log_src;
+use super::logging::LogLevel;
+use super::schemas::{self, JsonSchema};
+
+const USAGE: &str =
+    "Usage: /daemon generate <description> | show | set <field> <value> | save [--start] | discard";
+
+/// Fields `/daemon generate set` accepts.
+pub(crate) const FIELDS: &[&str] = &[
+    "name",
+    "description",
+    "interval_secs",
+    "auto_start",
+    "tools",
+    "persona",
+    "instructions",
+];
+
+/// Strict `json_schema` for one recipe.
+fn recipe_schema() -> JsonSchema {
+    JsonSchema {
+        name: "agent_recipe".to_string(),
+        schema: json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "description": "kebab-case recipe name"},
+                "description": {"type": "string", "description": "one line shown in /daemon"},
+                "interval_secs": {"type": "integer", "description": "seconds between runs"},
+                "auto_start": {"type": "boolean"},
+                "tools": {"type": "array", "items": {"type": "string"}},
+                "persona": {"type": "string"},
+                "instructions": {"type": "string", "description": "what each run does, in Markdown"},
+            },
+            "required": FIELDS,
+            "additionalProperties": false,
+        }),
+        path: PathBuf::new(),
+    }
+}
+
+fn generation_input(description: &str, tool_names: &[String]) -> Vec<Value> {
+    let system = format!(
+        "You write recipes for memini background agents. A recipe runs on a fixed \
+         interval with the listed tools and follows its instructions each run.\n\
+         - name: short kebab-case, unique and descriptive.\n\
+         - interval_secs: how often it runs; at least 60, default 1800.\n\
+         - auto_start: false unless the user asks for it to run at startup.\n\
+         - tools: \"local\" for every workspace tool, \"none\" for no tools, or a \
+         subset of: {}.\n\
+         - persona: one or two sentences of role and tone.\n\
+         - instructions: concrete steps for a single run and the shape of its report.",
+        tool_names.join(", ")
+    );
+    vec![
+        json!({"role": "system", "content": system}),
+        json!({"role": "user", "content": description}),
+    ]
+}
+
+/// Turn the model's JSON into a recipe, rejecting names and intervals the
+/// recipe loader would not accept.
+fn recipe_from_json(value: &Value) -> Result<NewAgentRecipe> {
+    let text = |key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .unwrap_or_default()
+    };
+    let recipe = NewAgentRecipe {
+        name: agent_recipes::sanitize_name(&text("name"))?,
+        description: text("description"),
+        interval_secs: value
+            .get("interval_secs")
+            .and_then(Value::as_u64)
+            .filter(|secs| *secs > 0)
+            .ok_or_else(|| anyhow!("interval_secs must be a positive integer"))?,
+        auto_start: value
+            .get("auto_start")
+            .and_then(Value::as_bool)
+            .unwrap_or(false),
+        tools: value
+            .get("tools")
+            .and_then(Value::as_array)
+            .map(|tools| {
+                tools
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(|tool| tool.trim().to_string())
+                    .filter(|tool| !tool.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        persona: text("persona"),
+        instructions: text("instructions"),
+    };
+    if recipe.instructions.is_empty() {
+        bail!("instructions cannot be empty");
+    }
+    Ok(recipe)
+}
+
+/// Apply `/daemon generate set <field> <value>`.
+fn set_field(recipe: &mut NewAgentRecipe, field: &str, value: &str) -> Result<()> {
+    let value = value.trim();
+    match field {
+        "name" => recipe.name = agent_recipes::sanitize_name(value)?,
+        "description" => recipe.description = value.to_string(),
+        "interval" | "interval_secs" => {
+            recipe.interval_secs = value
+                .parse::<u64>()
+                .ok()
+                .filter(|secs| *secs > 0)
+                .with_context(|| format!("interval_secs: expected seconds, got '{value}'"))?
+        }
+        "auto_start" => {
+            recipe.auto_start = match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "on" => true,
+                "false" | "no" | "off" => false,
+                other => bail!("auto_start: expected true or false, got '{other}'"),
+            }
+        }
+        "tools" => {
+            recipe.tools = value
+                .split(',')
+                .map(|tool| tool.trim().to_string())
+                .filter(|tool| !tool.is_empty())
+                .collect()
+        }
+        "persona" => recipe.persona = value.to_string(),
+        "instructions" => {
+            if value.is_empty() {
+                bail!("instructions cannot be empty");
+            }
+            recipe.instructions = value.to_string()
+        }
+        other => bail!("unknown field '{other}' (one of {})", FIELDS.join(", ")),
+    }
+    Ok(())
+}
+
+impl App {
+    pub(crate) fn handle_daemon_generate(&mut self, args: &[&str]) {
+        match args {
+            [] => log_src!(self, LogLevel::Warn, USAGE.to_string()),
+            ["show"] => self.show_recipe_draft(),
+            ["set", field, value @ ..] if !value.is_empty() => {
+                let value = value.join(" ");
+                let Some(draft) = self.recipe_draft.as_mut() else {
+                    log_src!(self, LogLevel::Warn, "No recipe draft.".to_string());
+                    return;
+                };
+                match set_field(draft, field, &value) {
+                    Ok(()) => self.show_recipe_draft(),
+                    Err(err) => log_src!(self, LogLevel::Warn, format!("{err:#}")),
+                }
+            }
+            ["save"] => self.save_recipe_draft(false),
+            ["save", "--start"] => self.save_recipe_draft(true),
+            ["discard"] => {
+                if self.recipe_draft.take().is_some() {
+                    self.log(LogLevel::Info, "Recipe draft discarded.".to_string());
+                } else {
+                    log_src!(self, LogLevel::Warn, "No recipe draft.".to_string());
+                }
+            }
+            ["set" | "save", ..] => log_src!(self, LogLevel::Warn, USAGE.to_string()),
+            description => self.generate_recipe_draft(&description.join(" ")),
+        }
+    }
+
+    fn generate_recipe_draft(&mut self, description: &str) {
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err}"));
+                return;
+            }
+        };
+        let model = self.openai.model_for(TaskKind::Chat);
+        let tool_names: Vec<String> = crate::local_tools::tool_defs()
+            .iter()
+            .filter_map(|tool| tool.get("name").and_then(Value::as_str))
+            .map(str::to_string)
+            .collect();
+        let input = generation_input(description, &tool_names);
+        self.log(
+            LogLevel::Info,
+            format!("📝 Drafting a recipe with {model}…"),
+        );
+        let openai = self.openai.clone();
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            let result = schemas::generate(&openai, &key, &model, input, &recipe_schema())
+                .await
+                .and_then(|value| recipe_from_json(&value))
+                .map_err(|err| format!("{err:#}"));
+            let _ = tx.send(AgentEvent::RecipeDrafted { result });
+        });
+    }
+
+    /// [`AgentEvent::RecipeDrafted`]: keep the draft and show it.
+    pub(crate) fn receive_recipe_draft(&mut self, result: Result<NewAgentRecipe, String>) {
+        match result {
+            Ok(recipe) => {
+                self.recipe_draft = Some(recipe);
+                self.show_recipe_draft();
+            }
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Recipe generation failed: {err}")
+            ),
+        }
+    }
+
+    fn show_recipe_draft(&mut self) {
+        let Some(draft) = &self.recipe_draft else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "No recipe draft. /daemon generate <description> makes one.".to_string()
+            );
+            return;
+        };
+        let body = format!(
+            "```markdown\n{}```\n\n`/daemon generate save [--start]` writes `{}.md`; \
+             `/daemon generate set <field> <value>` edits it first.",
+            agent_recipes::render_new_recipe(draft),
+            draft.name
+        );
+        self.log_markdown("Recipe draft".to_string(), body);
+    }
+
+    fn save_recipe_draft(&mut self, start: bool) {
+        let Some(draft) = self.recipe_draft.clone() else {
+            log_src!(self, LogLevel::Warn, "No recipe draft.".to_string());
+            return;
+        };
+        if daemon::builtin_tasks()
+            .iter()
+            .any(|task| task.name.eq_ignore_ascii_case(&draft.name))
+        {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!(
+                    "'{}' is a built-in task; /daemon generate set name <new-name>.",
+                    draft.name
+                )
+            );
+            return;
+        }
+        match agent_recipes::write_recipe_file(&draft) {
+            Ok(path) => {
+                self.recipe_draft = None;
+                self.log(
+                    LogLevel::Info,
+                    format!("Saved recipe '{}' at {}.", draft.name, path.display()),
+                );
+                if start {
+                    self.start_daemon(&draft.name);
+                }
+            }
+            Err(err) => log_src!(
+                self,
+                LogLevel::Error,
+                format!("Failed to save recipe '{}': {err:#}", draft.name)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn model_json_becomes_an_editable_recipe() {
+        let value = json!({
+            "name": "CI Watch",
+            "description": "Flag failing CI runs",
+            "interval_secs": 900,
+            "auto_start": false,
+            "tools": ["workspace_run_command", " "],
+            "persona": "You watch CI.",
+            "instructions": "Run the test suite and report failures.",
+        });
+        let mut recipe = recipe_from_json(&value).expect("recipe");
+        assert_eq!(recipe.name, "ci-watch");
+        assert_eq!(recipe.tools, vec!["workspace_run_command".to_string()]);
+
+        set_field(&mut recipe, "interval", "3600").expect("interval");
+        set_field(&mut recipe, "tools", "local, mcp:github.*").expect("tools");
+        assert_eq!(recipe.interval_secs, 3600);
+        assert_eq!(recipe.tools, vec!["local", "mcp:github.*"]);
+        assert!(set_field(&mut recipe, "auto_start", "maybe").is_err());
+        assert!(set_field(&mut recipe, "schedule", "daily").is_err());
+
+        let rendered = agent_recipes::render_new_recipe(&recipe);
+        assert!(rendered.starts_with("---\nname: ci-watch\n"));
+        assert!(rendered.contains("interval_secs: 3600\n"));
+
+        let empty = json!({"name": "x", "interval_secs": 60, "instructions": " "});
+        assert!(recipe_from_json(&empty).is_err());
+    }
+}
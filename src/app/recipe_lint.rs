@@ -0,0 +1,388 @@
+//! `/daemon lint` — check recipe files before they run.
+//!
+//! [`lint_recipe`] walks the front matter line by line so every problem
+//! points at `file:line`: unknown keys (with a "did you mean"), values the
+//! loader would reject or silently replace with a default, trigger patterns
+//! that can never match, tool names no daemon run resolves, and intervals
+//! outside [`MIN_INTERVAL_SECS`]..=[`MAX_INTERVAL_SECS`].
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+
+use globset::Glob;
+
+use super::agent_recipes::{self, FRONT_MATTER_KEYS};
+use super::checkpoints::Checkpoint;
+use super::mail;
+use super::schemas;
+use super::secrets;
+
+/// Shorter intervals are flagged; each run is a model call.
+pub const MIN_INTERVAL_SECS: u64 = 60;
+/// Longer intervals are flagged; a restart resets the countdown anyway.
+pub const MAX_INTERVAL_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Rice events the trigger subscription delivers.
+const TRIGGER_EVENTS: &[&str] = &["VariableUpdate", "Commit"];
+
+/// `tools:` selectors that stand for every local tool (or none).
+const TOOL_GROUPS: &[&str] = &["local", "workspace", "all", "*", "none"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    /// The recipe fails to load or a setting is ignored.
+    Error,
+    /// The recipe loads but probably does not do what was meant.
+    Warning,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LintIssue {
+    pub line: usize,
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl fmt::Display for LintIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {label}: {}", self.line, self.message)
+    }
+}
+
+/// What a recipe may refer to besides itself.
+pub struct LintContext {
+    /// Local tool names (`workspace_read_file`, …).
+    pub tools: Vec<String>,
+    /// Recipe and built-in task names, for `depends_on` / `pipe_output_to`.
+    pub tasks: Vec<String>,
+    /// `[sinks.<name>]` names from `memini.toml`, for `notify`.
+    pub sinks: Vec<String>,
+}
+
+struct Linter {
+    issues: Vec<LintIssue>,
+}
+
+impl Linter {
+    fn error(&mut self, line: usize, message: String) {
+        self.issues.push(LintIssue {
+            line,
+            severity: Severity::Error,
+            message,
+        });
+    }
+
+    fn warn(&mut self, line: usize, message: String) {
+        self.issues.push(LintIssue {
+            line,
+            severity: Severity::Warning,
+            message,
+        });
+    }
+}
+
+/// Every problem in the recipe file at `path`, in line order.
+pub fn lint_recipe(path: &Path, raw: &str, context: &LintContext) -> Vec<LintIssue> {
+    let mut lint = Linter { issues: Vec::new() };
+    let lines: Vec<&str> = raw.lines().collect();
+
+    let mut body_line = 1;
+    let mut has_instructions_key = false;
+    if lines.first().map(|line| line.trim()) == Some("---") {
+        match lines.iter().skip(1).position(|line| line.trim() == "---") {
+            None => lint.error(
+                1,
+                "front matter has no closing `---`; the whole file is read as instructions"
+                    .to_string(),
+            ),
+            Some(offset) => {
+                let closing = offset + 1;
+                body_line = closing + 2;
+                let mut seen = HashSet::new();
+                for (index, line) in lines.iter().enumerate().take(closing).skip(1) {
+                    let number = index + 1;
+                    let trimmed = line.trim();
+                    if trimmed.is_empty() || trimmed.starts_with('#') {
+                        continue;
+                    }
+                    let Some((key, value)) = trimmed.split_once(':') else {
+                        lint.error(number, format!("expected `key: value`, got `{trimmed}`"));
+                        continue;
+                    };
+                    let key = key.trim().to_ascii_lowercase();
+                    if !seen.insert(key.clone()) {
+                        lint.warn(number, format!("`{key}` repeats; the last value wins"));
+                    }
+                    has_instructions_key |= matches!(key.as_str(), "instructions" | "prompt");
+                    check_value(&mut lint, number, &key, value.trim(), context);
+                }
+            }
+        }
+    }
+
+    let body = lines
+        .get(body_line.saturating_sub(1)..)
+        .unwrap_or_default()
+        .join("\n");
+    if body.trim().is_empty() && !has_instructions_key {
+        lint.error(
+            body_line.min(lines.len().max(1)),
+            "missing instructions: add a Markdown body after the front matter".to_string(),
+        );
+    }
+
+    // Anything the checks above do not cover still stops the loader.
+    if !lint
+        .issues
+        .iter()
+        .any(|issue| issue.severity == Severity::Error)
+    {
+        if let Err(err) = agent_recipes::parse_recipe_file(path, raw) {
+            lint.error(1, format!("{err:#}"));
+        }
+    }
+
+    lint.issues.sort_by_key(|issue| issue.line);
+    lint.issues
+}
+
+fn check_value(lint: &mut Linter, line: usize, key: &str, value: &str, context: &LintContext) {
+    let value = value.trim_matches(|ch| ch == '"' || ch == '\'');
+    match key {
+        "interval_secs" | "interval" => match value.parse::<u64>() {
+            Ok(secs) if secs < MIN_INTERVAL_SECS => lint.warn(
+                line,
+                format!("runs every {secs}s; use at least {MIN_INTERVAL_SECS}s"),
+            ),
+            Ok(secs) if secs > MAX_INTERVAL_SECS => {
+                lint.warn(line, format!("runs every {secs}s, more than a week apart"))
+            }
+            Ok(_) => {}
+            Err(_) => lint.error(
+                line,
+                format!("`{value}` is not a number of seconds; the default interval is used"),
+            ),
+        },
+        "auto_start" | "autostart" => {
+            if agent_recipes::parse_bool(value).is_none() {
+                lint.error(
+                    line,
+                    format!("`{value}` is not true or false; treated as false"),
+                );
+            }
+        }
+        "trigger_events" | "events" => {
+            for event in agent_recipes::parse_csv(value) {
+                if !TRIGGER_EVENTS
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(&event))
+                {
+                    lint.error(
+                        line,
+                        format!(
+                            "unknown trigger event `{event}` (one of {})",
+                            TRIGGER_EVENTS.join(", ")
+                        ),
+                    );
+                }
+            }
+        }
+        "trigger_variables" | "trigger_vars" | "trigger_keys" => {
+            for pattern in agent_recipes::parse_csv(value) {
+                if pattern.trim_end_matches('*').contains('*') {
+                    lint.error(
+                        line,
+                        format!("`{pattern}` never matches: only a trailing `*` is supported"),
+                    );
+                }
+            }
+        }
+        "trigger_paths" | "trigger_globs" | "watch" => {
+            for pattern in agent_recipes::parse_csv(value) {
+                if let Err(err) = Glob::new(&pattern) {
+                    lint.error(line, format!("invalid glob `{pattern}`: {err}"));
+                }
+            }
+        }
+        "tools" => {
+            for tool in agent_recipes::parse_csv(value) {
+                let lower = tool.to_ascii_lowercase();
+                if TOOL_GROUPS.contains(&lower.as_str())
+                    || context.tools.iter().any(|known| *known == lower)
+                {
+                    continue;
+                }
+                let hint = if lower.starts_with("mcp:") {
+                    " (daemon runs only get local tools)".to_string()
+                } else {
+                    did_you_mean(&lower, context.tools.iter().map(String::as_str))
+                };
+                lint.error(line, format!("unknown tool `{tool}`{hint}"));
+            }
+        }
+        "depends_on" | "after" | "pipe_output_to" | "pipe_to" => {
+            for task in agent_recipes::parse_csv(value) {
+                if !context
+                    .tasks
+                    .iter()
+                    .any(|known| known.eq_ignore_ascii_case(&task))
+                {
+                    let hint = did_you_mean(&task, context.tasks.iter().map(String::as_str));
+                    lint.warn(line, format!("no recipe or task named `{task}`{hint}"));
+                }
+            }
+        }
+        "notify" => {
+            for sink in agent_recipes::parse_csv(value) {
+                if !sink.eq_ignore_ascii_case("none")
+                    && !context
+                        .sinks
+                        .iter()
+                        .any(|known| known.eq_ignore_ascii_case(&sink))
+                {
+                    let hint = did_you_mean(&sink, context.sinks.iter().map(String::as_str));
+                    lint.warn(line, format!("no sink named `{sink}` in memini.toml{hint}"));
+                }
+            }
+        }
+        "deliver" => {
+            for target in agent_recipes::parse_csv(value) {
+                if let Err(err) = mail::validate_target(&target) {
+                    lint.error(line, format!("{err:#}"));
+                }
+            }
+        }
+        "max_tool_loops" => {
+            if !matches!(value.parse::<usize>(), Ok(rounds) if rounds > 0) {
+                lint.error(
+                    line,
+                    format!("`{value}` is not a positive number of rounds"),
+                );
+            }
+        }
+        "checkpoints" => {
+            for checkpoint in agent_recipes::parse_csv(value) {
+                if let Err(err) = Checkpoint::parse(&checkpoint) {
+                    lint.error(line, format!("{err:#}"));
+                }
+            }
+        }
+        "thinking" | "reasoning" => {
+            if !value.is_empty() && crate::openai::parse_reasoning_setting(value).is_none() {
+                lint.error(line, format!("`{value}` is not off, low, medium or high"));
+            }
+        }
+        "schema" if !value.is_empty() => {
+            if let Err(err) = schemas::find_schema(value) {
+                lint.error(line, format!("{err:#}"));
+            }
+        }
+        "env" => {
+            for name in agent_recipes::parse_csv(value) {
+                if let Err(err) = secrets::validate_name(&name) {
+                    lint.error(line, format!("{err:#}"));
+                }
+            }
+        }
+        "writes" => {
+            if !matches!(
+                value.to_ascii_lowercase().as_str(),
+                "" | "direct" | "propose" | "review"
+            ) {
+                lint.error(line, format!("`{value}` is not direct or propose"));
+            }
+        }
+        key if !FRONT_MATTER_KEYS.contains(&key) => {
+            let hint = did_you_mean(key, FRONT_MATTER_KEYS.iter().copied());
+            lint.error(line, format!("unknown key `{key}` is ignored{hint}"));
+        }
+        _ => {}
+    }
+}
+
+/// ` — did you mean `x`?` for the closest candidate within two edits.
+fn did_you_mean<'a>(word: &str, candidates: impl Iterator<Item = &'a str>) -> String {
+    candidates
+        .map(|candidate| (edit_distance(word, candidate), candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| format!(" — did you mean `{candidate}`?"))
+        .unwrap_or_default()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != *cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn context() -> LintContext {
+        LintContext {
+            tools: vec![
+                "workspace_read_file".to_string(),
+                "workspace_run_command".to_string(),
+            ],
+            tasks: vec!["repo-collector".to_string()],
+            sinks: vec!["team".to_string()],
+        }
+    }
+
+    #[test]
+    fn issues_point_at_their_line() {
+        let raw = "---\nname: ci-watch\nintervl_secs: 600\ninterval_secs: 5\n\
+                   trigger_events: VariableUpdate, Deploy\ntrigger_variables: ci.*.status\n\
+                   tools: workspace_read_fil, local\ndepends_on: repo-collector\nnotify: teem\n---\n\
+                   Check CI.\n";
+        let issues = lint_recipe(Path::new("ci-watch.md"), raw, &context());
+        let lines: Vec<(usize, Severity)> = issues
+            .iter()
+            .map(|issue| (issue.line, issue.severity))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                (3, Severity::Error),
+                (4, Severity::Warning),
+                (5, Severity::Error),
+                (6, Severity::Error),
+                (7, Severity::Error),
+                (9, Severity::Warning),
+            ]
+        );
+        assert!(issues[0].message.contains("did you mean `interval_secs`"));
+        assert!(
+            issues[4]
+                .message
+                .contains("did you mean `workspace_read_file`")
+        );
+        assert!(issues[5].message.contains("did you mean `team`"));
+    }
+
+    #[test]
+    fn clean_recipe_has_no_issues_and_empty_body_is_reported() {
+        let clean = "---\nname: ok\ninterval_secs: 900\ntools: workspace_run_command\n---\nRun the tests.\n";
+        assert!(lint_recipe(Path::new("ok.md"), clean, &context()).is_empty());
+
+        let empty = "---\nname: empty\n---\n";
+        let issues = lint_recipe(Path::new("empty.md"), empty, &context());
+        assert_eq!(issues.len(), 1);
+        assert!(issues[0].message.starts_with("missing instructions"));
+    }
+}
@@ -0,0 +1,330 @@
+//! Session recordings for `memini replay`.
+//!
+//! While the TUI runs, every activity-log entry and every agent window's
+//! output and status changes are appended to
+//! `$MEMINI_HOME/recordings/<YYYYmmdd-HHMMSS>.jsonl`, one [`Entry`] per
+//! line, after a `session` header.  The recorder diffs the app state once
+//! per tick ([`App::record_session`]) instead of hooking every place that
+//! writes a line, so whatever the screen showed is what gets recorded.
+//!
+//! Text passes through the `/redact` rules before it is written, nothing
+//! is recorded while `/incognito` is on, and only the newest
+//! [`RECORDINGS_KEPT`] files are kept.  `MEMINI_RECORD=0` turns recording
+//! off.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::constants::{APP_VERSION, RECORDINGS_KEPT};
+use crate::util::env_first;
+
+use super::App;
+use super::daemon::AgentWindowStatus;
+use super::logging::{LogContent, LogLevel};
+
+/// Format version written in the `session` header.
+pub const RECORDING_VERSION: u32 = 1;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// One line of a recording.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Entry {
+    /// RFC 3339 time the change was seen.
+    pub at: String,
+    #[serde(flatten)]
+    pub record: Record,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Record {
+    Session {
+        version: u32,
+        app_version: String,
+    },
+    /// A plain activity-log line; `level` is `info`, `warn` or `error`.
+    Log {
+        level: String,
+        text: String,
+    },
+    /// A markdown activity-log entry (model answers, agent results).
+    Markdown {
+        label: String,
+        body: String,
+    },
+    WindowOpened {
+        window: usize,
+        label: String,
+        prompt: String,
+    },
+    WindowOutput {
+        window: usize,
+        lines: Vec<String>,
+    },
+    WindowStatus {
+        window: usize,
+        status: AgentWindowStatus,
+    },
+    WindowClosed {
+        window: usize,
+    },
+}
+
+/// Record sessions started after this call (the TUI, not tests or
+/// `--dry-run`), unless `MEMINI_RECORD` turns it off.
+pub fn enable() {
+    let off = env_first(&["MEMINI_RECORD"])
+        .is_some_and(|value| matches!(value.trim(), "0" | "false" | "no" | "off"));
+    ENABLED.store(!off, Ordering::SeqCst);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn recordings_dir() -> PathBuf {
+    crate::util::memini_home().join("recordings")
+}
+
+/// Recordings, newest first.
+pub fn list() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(recordings_dir()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "jsonl"))
+        .collect();
+    files.sort();
+    files.reverse();
+    files
+}
+
+/// `target` as a path, or a recording name (with or without `.jsonl`);
+/// `None` picks the newest recording.
+pub fn resolve(target: Option<&str>) -> Result<PathBuf> {
+    let Some(target) = target else {
+        return list()
+            .into_iter()
+            .next()
+            .context("No recordings yet; they are written while memini runs");
+    };
+    let path = PathBuf::from(target);
+    if path.is_file() {
+        return Ok(path);
+    }
+    let dir = recordings_dir();
+    [dir.join(target), dir.join(format!("{target}.jsonl"))]
+        .into_iter()
+        .find(|path| path.is_file())
+        .with_context(|| format!("No recording at {target} or in {}", dir.display()))
+}
+
+/// Read a recording; lines that do not parse are skipped so a recording
+/// cut short by a crash still plays.
+pub fn read(path: &Path) -> Result<Vec<Entry>> {
+    let file = File::open(path).with_context(|| format!("open {}", path.display()))?;
+    let mut entries = Vec::new();
+    for line in BufReader::new(file).lines() {
+        let line = line.with_context(|| format!("read {}", path.display()))?;
+        if let Ok(entry) = serde_json::from_str::<Entry>(&line) {
+            entries.push(entry);
+        }
+    }
+    match entries.first().map(|entry| &entry.record) {
+        Some(Record::Session { version, .. }) if *version > RECORDING_VERSION => bail!(
+            "{} is recording version {version}; this memini reads up to {RECORDING_VERSION}",
+            path.display()
+        ),
+        Some(Record::Session { .. }) => Ok(entries),
+        _ => bail!("{} is not a memini recording", path.display()),
+    }
+}
+
+/// Appends [`Entry`] lines for one session.
+pub(crate) struct Recorder {
+    out: BufWriter<File>,
+    /// `logs.pushed()` when the log was last recorded.
+    log_seen: usize,
+    /// Output lines and status recorded per window.
+    windows: HashMap<usize, (usize, AgentWindowStatus)>,
+}
+
+impl Recorder {
+    /// Open a new recording and prune old ones.
+    pub fn start() -> Result<Self> {
+        let dir = recordings_dir();
+        fs::create_dir_all(&dir).with_context(|| format!("create {}", dir.display()))?;
+        for old in list().into_iter().skip(RECORDINGS_KEPT.saturating_sub(1)) {
+            let _ = fs::remove_file(old);
+        }
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let path = dir.join(format!("{stamp}.jsonl"));
+        let file = File::create(&path).with_context(|| format!("create {}", path.display()))?;
+        let mut recorder = Recorder {
+            out: BufWriter::new(file),
+            log_seen: 0,
+            windows: HashMap::new(),
+        };
+        recorder.write(Record::Session {
+            version: RECORDING_VERSION,
+            app_version: APP_VERSION.to_string(),
+        })?;
+        recorder.out.flush()?;
+        Ok(recorder)
+    }
+
+    fn write_all(&mut self, records: Vec<Record>) -> Result<()> {
+        for record in records {
+            self.write(record)?;
+        }
+        self.out.flush()?;
+        Ok(())
+    }
+
+    fn write(&mut self, record: Record) -> Result<()> {
+        let entry = Entry {
+            at: chrono::Local::now().to_rfc3339(),
+            record,
+        };
+        serde_json::to_writer(&mut self.out, &entry)?;
+        self.out.write_all(b"\n")?;
+        Ok(())
+    }
+}
+
+fn redact(text: &str) -> String {
+    crate::redact::redactor().apply(text).text
+}
+
+impl App {
+    /// Start recording this session (called by [`App::new`] when
+    /// [`enabled`]).
+    pub(crate) fn start_recording(&mut self) {
+        match Recorder::start() {
+            Ok(recorder) => self.recorder = Some(recorder),
+            Err(err) => self.log(LogLevel::Warn, format!("Session recording off: {err:#}")),
+        }
+    }
+
+    /// Append what changed since the last tick to the recording.
+    pub(crate) fn record_session(&mut self) {
+        let Some(mut recorder) = self.recorder.take() else {
+            return;
+        };
+        let records = if crate::rice::incognito() {
+            // Skip, but move past what happened meanwhile.
+            let _ = self.session_changes(&mut recorder);
+            Vec::new()
+        } else {
+            self.session_changes(&mut recorder)
+        };
+        match recorder.write_all(records) {
+            Ok(()) => self.recorder = Some(recorder),
+            Err(err) => self.log(
+                LogLevel::Warn,
+                format!("Session recording stopped: {err:#}"),
+            ),
+        }
+    }
+
+    fn session_changes(&self, recorder: &mut Recorder) -> Vec<Record> {
+        let mut records: Vec<Record> = self
+            .logs
+            .since(recorder.log_seen)
+            .map(|line| match &line.content {
+                LogContent::Plain(text) => Record::Log {
+                    level: match line.level {
+                        LogLevel::Info => "info",
+                        LogLevel::Warn => "warn",
+                        LogLevel::Error => "error",
+                    }
+                    .to_string(),
+                    text: redact(text),
+                },
+                LogContent::Markdown { label, body } => Record::Markdown {
+                    label: label.clone(),
+                    body: redact(body),
+                },
+            })
+            .collect();
+        recorder.log_seen = self.logs.pushed();
+
+        for window in &self.agent_windows {
+            let (seen, status) = recorder.windows.entry(window.id).or_insert_with(|| {
+                records.push(Record::WindowOpened {
+                    window: window.id,
+                    label: window.label.clone(),
+                    prompt: redact(&window.prompt),
+                });
+                records.push(Record::WindowStatus {
+                    window: window.id,
+                    status: window.status.clone(),
+                });
+                (0, window.status.clone())
+            });
+            if window.output_lines.len() > *seen {
+                records.push(Record::WindowOutput {
+                    window: window.id,
+                    lines: window.output_lines[*seen..]
+                        .iter()
+                        .map(|line| redact(line))
+                        .collect(),
+                });
+                *seen = window.output_lines.len();
+            }
+            if window.status != *status {
+                *status = window.status.clone();
+                records.push(Record::WindowStatus {
+                    window: window.id,
+                    status: window.status.clone(),
+                });
+            }
+        }
+        recorder.windows.retain(|id, _| {
+            let open = self.agent_windows.iter().any(|window| window.id == *id);
+            if !open {
+                records.push(Record::WindowClosed { window: *id });
+            }
+            open
+        });
+        records
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::harness::Harness;
+    use super::*;
+
+    #[test]
+    fn records_log_lines_and_window_changes_once() {
+        let path =
+            std::env::temp_dir().join(format!("memini-recording-{}.jsonl", std::process::id()));
+        let mut harness = Harness::new();
+        let mut recorder = Recorder {
+            out: BufWriter::new(File::create(&path).expect("temp file")),
+            log_seen: harness.app.logs.pushed(),
+            windows: HashMap::new(),
+        };
+        harness.mocks.llm.reply_text("Hello there.");
+        harness.submit("hi");
+        let records = harness.app.session_changes(&mut recorder);
+        assert!(matches!(&records[0], Record::Log { text, .. } if text == "› hi"));
+        assert!(
+            records
+                .iter()
+                .any(|record| matches!(record, Record::Markdown { body, .. } if body.contains("Hello there.")))
+        );
+        assert!(harness.app.session_changes(&mut recorder).is_empty());
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,494 @@
+//! `memini replay [file]` — step through a recorded session
+//! ([`super::recording`]) in the terminal: the activity log as it was,
+//! and beside it the agent windows with their output and status.
+//!
+//! Playback keeps the recorded pacing, but never pauses longer than
+//! [`MAX_GAP`] between two entries, so the idle hours of an overnight run
+//! pass at once.
+//!
+//! | Key | Action |
+//! |-----|--------|
+//! | `Space` | Play / pause |
+//! | `→` / `←` (`l` / `h`) | Step one entry forward / back |
+//! | `PgDn` / `PgUp` | Seek a minute of session time |
+//! | `Home` / `End` | Start / end of the recording |
+//! | `+` / `-` | Faster / slower |
+//! | `Tab` | Next agent window (`Shift+Tab`: follow the latest) |
+//! | `q` / `Esc` | Quit |
+
+use std::cell::Cell;
+use std::io;
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use chrono::{DateTime, FixedOffset};
+use crossterm::event::{self, Event, KeyCode, KeyEvent};
+use ratatui::Frame;
+use ratatui::Terminal;
+use ratatui::backend::CrosstermBackend;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Borders, LineGauge, Paragraph, Wrap};
+
+use crate::constants::MAX_LOGS;
+use crate::i18n::t;
+
+use super::daemon::AgentWindowStatus;
+use super::log_store::LogStore;
+use super::logging::{LogContent, LogLevel, LogLine};
+use super::recording::{Entry, Record};
+use super::theme;
+use super::ui::render_agent_output;
+
+/// Playback speeds `+` and `-` step through.
+const SPEEDS: &[u32] = &[1, 2, 4, 8, 16, 32, 64];
+
+/// Longest pause between two entries at 1×.
+const MAX_GAP: Duration = Duration::from_secs(2);
+
+/// Session time `PgUp` / `PgDn` seek by.
+const SEEK_SECS: i64 = 60;
+
+struct ReplayWindow {
+    id: usize,
+    label: String,
+    status: AgentWindowStatus,
+    lines: Vec<String>,
+    closed: bool,
+}
+
+struct Replay {
+    name: String,
+    entries: Vec<Entry>,
+    times: Vec<DateTime<FixedOffset>>,
+    /// Entries applied so far.
+    position: usize,
+    playing: bool,
+    speed: usize,
+    last_step: Instant,
+    logs: LogStore,
+    windows: Vec<ReplayWindow>,
+    /// Window picked with `Tab`; `None` follows the latest output.
+    selected: Option<usize>,
+    /// Window that changed last.
+    latest: Option<usize>,
+}
+
+/// Play `entries` (read from the recording `name`) until the user quits.
+pub fn run(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    name: &str,
+    entries: Vec<Entry>,
+) -> Result<()> {
+    let mut replay = Replay::new(name, entries);
+    loop {
+        replay.tick();
+        terminal.draw(|frame| replay.draw(frame))?;
+        if event::poll(Duration::from_millis(50))? {
+            if let Event::Key(key) = event::read()? {
+                if replay.handle_key(key) {
+                    break;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Replay {
+    fn new(name: &str, entries: Vec<Entry>) -> Self {
+        let mut last = DateTime::<FixedOffset>::default();
+        let times = entries
+            .iter()
+            .map(|entry| {
+                // Keep the order monotonic even if a line has a bad stamp.
+                if let Ok(at) = DateTime::parse_from_rfc3339(&entry.at) {
+                    last = last.max(at);
+                }
+                last
+            })
+            .collect();
+        Replay {
+            name: name.to_string(),
+            entries,
+            times,
+            position: 0,
+            playing: true,
+            speed: 0,
+            last_step: Instant::now(),
+            logs: LogStore::new(MAX_LOGS),
+            windows: Vec::new(),
+            selected: None,
+            latest: None,
+        }
+    }
+
+    /// Step forward when the recorded gap to the next entry has passed.
+    fn tick(&mut self) {
+        if !self.playing {
+            return;
+        }
+        if self.position >= self.entries.len() {
+            self.playing = false;
+            return;
+        }
+        if self.last_step.elapsed() >= self.next_gap() {
+            self.seek(self.position + 1);
+        }
+    }
+
+    /// Real time to wait before applying the next entry.
+    fn next_gap(&self) -> Duration {
+        let Some(previous) = self.position.checked_sub(1) else {
+            return Duration::ZERO;
+        };
+        let gap = (self.times[self.position] - self.times[previous])
+            .to_std()
+            .unwrap_or_default()
+            .min(MAX_GAP);
+        gap / SPEEDS[self.speed]
+    }
+
+    /// Show the state after the first `position` entries.
+    fn seek(&mut self, position: usize) {
+        let position = position.min(self.entries.len());
+        if position < self.position {
+            self.logs = LogStore::new(MAX_LOGS);
+            self.windows.clear();
+            self.latest = None;
+            self.position = 0;
+        }
+        for index in self.position..position {
+            self.apply(index);
+        }
+        self.position = position;
+        self.last_step = Instant::now();
+    }
+
+    /// Seek `secs` of session time from the current entry.
+    fn seek_by(&mut self, secs: i64) {
+        let Some(now) = self.position.checked_sub(1).map(|index| self.times[index]) else {
+            if secs > 0 {
+                self.seek(1);
+            }
+            return;
+        };
+        let target = now + chrono::Duration::seconds(secs);
+        let position = self.times.partition_point(|at| *at <= target);
+        let position = if secs > 0 {
+            position.max(self.position + 1)
+        } else {
+            position.min(self.position.saturating_sub(1))
+        };
+        self.seek(position);
+    }
+
+    fn apply(&mut self, index: usize) {
+        let timestamp = self.times[index].format("%H:%M:%S").to_string();
+        let (level, content) = match self.entries[index].record.clone() {
+            Record::Session { .. } => return,
+            Record::Log { level, text } => {
+                let level = match level.as_str() {
+                    "warn" => LogLevel::Warn,
+                    "error" => LogLevel::Error,
+                    _ => LogLevel::Info,
+                };
+                (level, LogContent::Plain(text))
+            }
+            Record::Markdown { label, body } => {
+                (LogLevel::Info, LogContent::Markdown { label, body })
+            }
+            Record::WindowOpened { window, label, .. } => {
+                self.windows.push(ReplayWindow {
+                    id: window,
+                    label,
+                    status: AgentWindowStatus::Thinking,
+                    lines: Vec::new(),
+                    closed: false,
+                });
+                self.latest = Some(window);
+                return;
+            }
+            Record::WindowOutput { window, lines } => {
+                if let Some(replayed) = self.window_mut(window) {
+                    replayed.lines.extend(lines);
+                }
+                self.latest = Some(window);
+                return;
+            }
+            Record::WindowStatus { window, status } => {
+                if let Some(replayed) = self.window_mut(window) {
+                    replayed.status = status;
+                }
+                return;
+            }
+            Record::WindowClosed { window } => {
+                if let Some(replayed) = self.window_mut(window) {
+                    replayed.closed = true;
+                }
+                return;
+            }
+        };
+        self.logs.push(LogLine {
+            timestamp,
+            level,
+            content,
+            height_cache: Cell::new(None),
+        });
+    }
+
+    fn window_mut(&mut self, id: usize) -> Option<&mut ReplayWindow> {
+        self.windows.iter_mut().find(|window| window.id == id)
+    }
+
+    /// Returns `true` to quit.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Char(' ') => {
+                if self.position >= self.entries.len() {
+                    self.seek(0);
+                }
+                self.playing = !self.playing;
+                self.last_step = Instant::now();
+            }
+            KeyCode::Right | KeyCode::Char('l') => {
+                self.playing = false;
+                self.seek(self.position + 1);
+            }
+            KeyCode::Left | KeyCode::Char('h') => {
+                self.playing = false;
+                self.seek(self.position.saturating_sub(1));
+            }
+            KeyCode::PageDown => self.seek_by(SEEK_SECS),
+            KeyCode::PageUp => self.seek_by(-SEEK_SECS),
+            KeyCode::Home => self.seek(0),
+            KeyCode::End => self.seek(self.entries.len()),
+            KeyCode::Char('+') | KeyCode::Char('=') => {
+                self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+            }
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1),
+            KeyCode::Tab => {
+                let current = self.shown_window().map(|window| window.id);
+                let next = self
+                    .windows
+                    .iter()
+                    .position(|window| Some(window.id) == current)
+                    .map_or(0, |index| (index + 1) % self.windows.len().max(1));
+                self.selected = self.windows.get(next).map(|window| window.id);
+            }
+            KeyCode::BackTab => self.selected = None,
+            _ => {}
+        }
+        false
+    }
+
+    fn shown_window(&self) -> Option<&ReplayWindow> {
+        let id = self.selected.or(self.latest)?;
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn draw(&self, frame: &mut Frame<'_>) {
+        let theme = theme::current();
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1), // status
+                Constraint::Min(1),    // log + window
+                Constraint::Length(1), // timeline
+                Constraint::Length(1), // keys
+            ])
+            .split(frame.area());
+
+        let icon = if self.playing { "▶" } else { "⏸" };
+        let time = self
+            .position
+            .checked_sub(1)
+            .map(|index| self.times[index].format("%Y-%m-%d %H:%M:%S").to_string())
+            .unwrap_or_else(|| "start".to_string());
+        let status = format!(
+            " {icon} replay {} · {time} · {}/{} · {}× ",
+            self.name,
+            self.position,
+            self.entries.len(),
+            SPEEDS[self.speed]
+        );
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                status,
+                Style::default()
+                    .fg(theme.brand)
+                    .add_modifier(Modifier::BOLD),
+            )),
+            rows[0],
+        );
+
+        let panes = if self.windows.is_empty() {
+            vec![rows[1]]
+        } else {
+            Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(55), Constraint::Percentage(45)])
+                .split(rows[1])
+                .to_vec()
+        };
+        self.draw_log(frame, panes[0]);
+        if let Some(area) = panes.get(1) {
+            self.draw_window(frame, *area);
+        }
+
+        let ratio = match self.entries.len() {
+            0 => 0.0,
+            total => self.position as f64 / total as f64,
+        };
+        frame.render_widget(
+            LineGauge::default()
+                .filled_style(Style::default().fg(theme.primary))
+                .unfilled_style(Style::default().fg(theme.dim))
+                .ratio(ratio),
+            rows[2],
+        );
+        frame.render_widget(
+            Paragraph::new(Span::styled(
+                " Space play/pause · ←/→ step · PgUp/PgDn ±1 min · Home/End · +/- speed · Tab window · q quit ",
+                Style::default().fg(theme.muted),
+            )),
+            rows[3],
+        );
+    }
+
+    fn draw_log(&self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
+        let slice = self.logs.visible_slice(
+            area.width.saturating_sub(2),
+            area.height.saturating_sub(2) as usize,
+            0,
+        );
+        let panel = Paragraph::new(Text::from(slice.lines))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.border))
+                    .title(" Activity log "),
+            )
+            .scroll((slice.top_row, 0));
+        frame.render_widget(panel, area);
+    }
+
+    fn draw_window(&self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
+        let Some(window) = self.shown_window() else {
+            return;
+        };
+        let status = match window.status {
+            AgentWindowStatus::Thinking => t("working"),
+            AgentWindowStatus::Done => t("done"),
+            AgentWindowStatus::WaitingForInput => t("needs input"),
+            AgentWindowStatus::TimedOut => t("timed out"),
+            AgentWindowStatus::Killed => t("killed"),
+        };
+        let closed = if window.closed { " · closed" } else { "" };
+        let follow = if self.selected.is_some() {
+            "Shift+Tab: follow latest"
+        } else {
+            "following latest"
+        };
+        let title = format!(
+            " #{} {} — {status}{closed} [{}/{} · {follow}] ",
+            window.id,
+            window.label,
+            self.windows
+                .iter()
+                .position(|other| other.id == window.id)
+                .map_or(0, |index| index + 1),
+            self.windows.len()
+        );
+        let visible = area.height.saturating_sub(2) as usize;
+        let rendered = render_agent_output(&window.lines, &theme);
+        let skip = rendered.len().saturating_sub(visible);
+        let lines: Vec<Line> = rendered.into_iter().skip(skip).collect();
+        let panel = Paragraph::new(Text::from(lines))
+            .wrap(Wrap { trim: false })
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(theme.accent(window.id)))
+                    .title(title),
+            );
+        frame.render_widget(panel, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(at: &str, record: Record) -> Entry {
+        Entry {
+            at: format!("2026-10-16T{at}+00:00"),
+            record,
+        }
+    }
+
+    fn log(text: &str) -> Record {
+        Record::Log {
+            level: "info".to_string(),
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn seeking_rebuilds_the_recorded_state() {
+        let entries = vec![
+            entry(
+                "01:00:00",
+                Record::Session {
+                    version: 1,
+                    app_version: "test".to_string(),
+                },
+            ),
+            entry("01:00:01", log("› fix the build")),
+            entry(
+                "01:00:02",
+                Record::WindowOpened {
+                    window: 3,
+                    label: "builder".to_string(),
+                    prompt: "fix the build".to_string(),
+                },
+            ),
+            entry(
+                "01:00:03",
+                Record::WindowOutput {
+                    window: 3,
+                    lines: vec!["cargo build".to_string()],
+                },
+            ),
+            entry(
+                "03:00:00",
+                Record::WindowStatus {
+                    window: 3,
+                    status: AgentWindowStatus::Done,
+                },
+            ),
+        ];
+        let mut replay = Replay::new("test", entries);
+        replay.seek(5);
+        assert_eq!(replay.windows[0].status, AgentWindowStatus::Done);
+        assert_eq!(replay.windows[0].lines, ["cargo build"]);
+
+        replay.seek(2);
+        assert!(replay.windows.is_empty());
+        assert_eq!(replay.logs.pushed(), 1);
+
+        // Two idle hours play back as the capped gap.
+        replay.seek(4);
+        assert_eq!(replay.next_gap(), MAX_GAP);
+
+        replay.seek_by(SEEK_SECS);
+        assert_eq!(replay.position, 5);
+        replay.seek_by(-SEEK_SECS);
+        assert_eq!(replay.position, 4);
+    }
+}
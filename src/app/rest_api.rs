@@ -0,0 +1,407 @@
+//! Local REST API for driving a running memini from editors and scripts.
+//!
+//! When `MEMINI_API_PORT` and `MEMINI_API_TOKEN` are both set, a
+//! `tiny_http` server listens on `127.0.0.1:<port>`.  Every request must
+//! carry `Authorization: Bearer <token>`; bodies over `MAX_API_BODY_BYTES`
+//! are refused with 413.  A fixed pool of `API_WORKER_THREADS` threads
+//! serves requests, so a burst queues instead of spawning a thread each.
+//! Requests are parsed on a worker thread and forwarded to the TUI as
+//! [`AgentEvent::ApiRequest`]; the main loop answers them from live state,
+//! so the API sees exactly what the dashboard shows.
+//!
+//! The server is `tiny_http` rather than axum: the webhook listener and
+//! `serve-mcp --http` already run on it, and a handful of local routes do
+//! not warrant a second HTTP stack.
+//!
+//! | Method | Path                  | Body                              |
+//! |--------|-----------------------|-----------------------------------|
+//! | GET    | `/chat`               | —  (busy flag + last reply)       |
+//! | POST   | `/chat`               | `{"message": "…", "wait": bool}`  |
+//! | GET    | `/agents`             | —                                 |
+//! | POST   | `/agents`             | `{"prompt": "…"}`                 |
+//! | GET    | `/agents/<id>`        | —  (status + output lines)        |
+//! | POST   | `/agents/<id>/reply`  | `{"message": "…"}`                |
+//! | GET    | `/daemons/results`    | —                                 |
+
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::mpsc as std_mpsc;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use serde_json::{Value, json};
+use tiny_http::{Header, Method, Response, Server};
+use tokio::sync::mpsc;
+
+use crate::constants::{API_CHAT_WAIT_SECS, API_WORKER_THREADS, MAX_API_BODY_BYTES};
+use crate::util::constant_time_eq;
+
+use super::App;
+use super::daemon::{AgentEvent, AgentWindow, AgentWindowStatus};
+use super::halt;
+use super::log_src;
+use super::logging::LogLevel;
+
+/// A parsed, authenticated API call.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApiRequest {
+    ChatStatus,
+    Chat { message: String, wait: bool },
+    ListAgents,
+    SpawnAgent { prompt: String },
+    AgentStatus { id: usize },
+    ReplyToAgent { id: usize, message: String },
+    DaemonResults,
+}
+
+/// Status code and JSON body sent back to the client.
+#[derive(Clone, Debug)]
+pub struct ApiReply {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl ApiReply {
+    fn ok(body: Value) -> Self {
+        ApiReply { status: 200, body }
+    }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        ApiReply {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// Bind the API and start the worker threads, so a waiting chat call
+/// holds one worker without blocking the others.
+pub fn spawn_listener(
+    port: u16,
+    token: String,
+    tx: mpsc::UnboundedSender<AgentEvent>,
+) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|err| anyhow!("bind REST API on port {port}: {err}"))?;
+    let server = Arc::new(server);
+    for _ in 0..API_WORKER_THREADS {
+        let server = Arc::clone(&server);
+        let token = token.clone();
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                serve(request, &token, &tx);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn serve(mut request: tiny_http::Request, token: &str, tx: &mpsc::UnboundedSender<AgentEvent>) {
+    let reply = match accept_request(&mut request, token) {
+        Ok(api_request) => forward(api_request, tx),
+        Err(reply) => reply,
+    };
+    let content_type =
+        Header::from_bytes("Content-Type", "application/json").expect("static header is valid");
+    let _ = request.respond(
+        Response::from_string(reply.body.to_string())
+            .with_status_code(reply.status)
+            .with_header(content_type),
+    );
+}
+
+/// Hand the request to the TUI and wait for its answer.
+fn forward(request: ApiRequest, tx: &mpsc::UnboundedSender<AgentEvent>) -> ApiReply {
+    let wait = match &request {
+        ApiRequest::Chat { wait: true, .. } => Duration::from_secs(API_CHAT_WAIT_SECS),
+        _ => Duration::from_secs(10),
+    };
+    let (reply_tx, reply_rx) = std_mpsc::channel();
+    if tx
+        .send(AgentEvent::ApiRequest {
+            request,
+            reply: reply_tx,
+        })
+        .is_err()
+    {
+        return ApiReply::error(503, "memini is shutting down");
+    }
+    reply_rx
+        .recv_timeout(wait)
+        .unwrap_or_else(|_| ApiReply::error(504, "timed out waiting for memini"))
+}
+
+/// Check the token and parse the route and body.
+fn accept_request(
+    request: &mut tiny_http::Request,
+    token: &str,
+) -> std::result::Result<ApiRequest, ApiReply> {
+    let expected = format!("Bearer {token}");
+    let authorized = request.headers().iter().any(|header| {
+        header.field.equiv("Authorization")
+            && constant_time_eq(header.value.as_str().as_bytes(), expected.as_bytes())
+    });
+    if !authorized {
+        return Err(ApiReply::error(401, "missing or invalid bearer token"));
+    }
+    let too_large = || {
+        ApiReply::error(
+            413,
+            format!("body is larger than {MAX_API_BODY_BYTES} bytes"),
+        )
+    };
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_API_BODY_BYTES)
+    {
+        return Err(too_large());
+    }
+    let mut raw = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_API_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut raw)
+        .map_err(|err| ApiReply::error(400, format!("could not read body: {err}")))?;
+    if raw.len() > MAX_API_BODY_BYTES {
+        return Err(too_large());
+    }
+    let body = if raw.iter().all(u8::is_ascii_whitespace) {
+        Value::Null
+    } else {
+        serde_json::from_slice(&raw)
+            .map_err(|err| ApiReply::error(400, format!("body is not JSON: {err}")))?
+    };
+    let path = request.url().split('?').next().unwrap_or("").to_string();
+    parse_route(request.method(), &path, &body)
+}
+
+fn parse_route(
+    method: &Method,
+    path: &str,
+    body: &Value,
+) -> std::result::Result<ApiRequest, ApiReply> {
+    let text = |field: &str| -> std::result::Result<String, ApiReply> {
+        body.get(field)
+            .and_then(Value::as_str)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+            .ok_or_else(|| ApiReply::error(400, format!("\"{field}\" is required")))
+    };
+    let segments: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+    let window_id = |raw: &str| {
+        raw.parse::<usize>()
+            .map_err(|_| ApiReply::error(404, format!("no agent window '{raw}'")))
+    };
+    match (method, segments.as_slice()) {
+        (Method::Get, ["chat"]) => Ok(ApiRequest::ChatStatus),
+        (Method::Post, ["chat"]) => Ok(ApiRequest::Chat {
+            message: text("message")?,
+            wait: body.get("wait").and_then(Value::as_bool).unwrap_or(false),
+        }),
+        (Method::Get, ["agents"]) => Ok(ApiRequest::ListAgents),
+        (Method::Post, ["agents"]) => Ok(ApiRequest::SpawnAgent {
+            prompt: text("prompt")?,
+        }),
+        (Method::Get, ["agents", id]) => Ok(ApiRequest::AgentStatus { id: window_id(id)? }),
+        (Method::Post, ["agents", id, "reply"]) => Ok(ApiRequest::ReplyToAgent {
+            id: window_id(id)?,
+            message: text("message")?,
+        }),
+        (Method::Get, ["daemons", "results"]) => Ok(ApiRequest::DaemonResults),
+        (
+            _,
+            ["chat"] | ["agents"] | ["agents", _] | ["agents", _, "reply"] | ["daemons", "results"],
+        ) => Err(ApiReply::error(405, "method not allowed")),
+        _ => Err(ApiReply::error(404, "not found")),
+    }
+}
+
+fn status_label(status: &AgentWindowStatus) -> &'static str {
+    match status {
+        AgentWindowStatus::Thinking => "thinking",
+        AgentWindowStatus::Done => "done",
+        AgentWindowStatus::WaitingForInput => "waiting_for_input",
+        AgentWindowStatus::TimedOut => "timed_out",
+        AgentWindowStatus::Killed => "killed",
+    }
+}
+
+fn window_summary(window: &AgentWindow) -> Value {
+    json!({
+        "id": window.id,
+        "label": window.label,
+        "prompt": window.prompt,
+        "status": status_label(&window.status),
+        "pending_question": window.pending_question,
+    })
+}
+
+// ── App side ─────────────────────────────────────────────────────────
+
+impl App {
+    /// Bind the REST API if `MEMINI_API_PORT` is set.
+    pub(crate) fn start_rest_api(&mut self) {
+        let Some(raw) = crate::util::env_first(&["MEMINI_API_PORT"]) else {
+            return;
+        };
+        let Ok(port) = raw.trim().parse::<u16>() else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Ignoring invalid MEMINI_API_PORT: {raw}")
+            );
+            return;
+        };
+        let Some(token) =
+            crate::util::env_first(&["MEMINI_API_TOKEN"]).filter(|token| !token.trim().is_empty())
+        else {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                "REST API disabled: set MEMINI_API_TOKEN as well as MEMINI_API_PORT.".to_string()
+            );
+            return;
+        };
+        match spawn_listener(port, token.trim().to_string(), self.daemon_tx.clone()) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!(
+                    "REST API on http://127.0.0.1:{port} (bearer token from MEMINI_API_TOKEN)."
+                ),
+            ),
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("REST API disabled: {err:#}"));
+            }
+        }
+    }
+
+    /// Answer an API call.  `POST /chat` with `wait` parks the reply
+    /// channel until the turn's `ChatFinished`.
+    pub(crate) fn handle_api_request(
+        &mut self,
+        request: ApiRequest,
+        reply: std_mpsc::Sender<ApiReply>,
+    ) {
+        let answer = match request {
+            ApiRequest::ChatStatus => ApiReply::ok(json!({
+                "busy": self.chat_busy,
+                "last": self.last_chat_reply.as_ref().map(|(message, output)| {
+                    json!({ "message": message, "output": output })
+                }),
+            })),
+            ApiRequest::Chat { message, wait } => {
+                if self.chat_busy {
+                    ApiReply::error(409, "a chat turn is already running")
+                } else {
+                    self.log(LogLevel::Info, format!("› {message}  (api)"));
+                    self.chat_busy = true;
+                    self.handle_chat_message(&message, false);
+                    if wait {
+                        self.api_chat_waiters.push(reply);
+                        return;
+                    }
+                    ApiReply {
+                        status: 202,
+                        body: json!({ "status": "submitted" }),
+                    }
+                }
+            }
+            ApiRequest::ListAgents => ApiReply::ok(Value::Array(
+                self.agent_windows.iter().map(window_summary).collect(),
+            )),
+            ApiRequest::SpawnAgent { .. } if halt::active() => ApiReply::error(
+                409,
+                "autonomous work is halted; /resume-auto re-enables it",
+            ),
+            ApiRequest::SpawnAgent { prompt } => {
+                let id = self.spawn_prompt_window(&prompt, None);
+                self.log(LogLevel::Info, format!("Spawned Agent #{id} (api)."));
+                ApiReply {
+                    status: 201,
+                    body: json!({ "id": id }),
+                }
+            }
+            ApiRequest::AgentStatus { id } => {
+                match self.agent_windows.iter().find(|window| window.id == id) {
+                    Some(window) => {
+                        let mut body = window_summary(window);
+                        body["output"] = json!(window.output_lines);
+                        ApiReply::ok(body)
+                    }
+                    None => ApiReply::error(404, format!("no agent window {id}")),
+                }
+            }
+            ApiRequest::ReplyToAgent { id, message } => {
+                if self.reply_to_agent_window(id, &message) {
+                    ApiReply {
+                        status: 202,
+                        body: json!({ "status": "replied" }),
+                    }
+                } else {
+                    ApiReply::error(409, format!("agent window {id} is still working or was cancelled"))
+                }
+            }
+            ApiRequest::DaemonResults => ApiReply::ok(Value::Array(
+                self.daemon_results
+                    .iter()
+                    .map(|(task, message, timestamp)| {
+                        json!({ "task": task, "message": message, "timestamp": timestamp })
+                    })
+                    .collect(),
+            )),
+        };
+        let _ = reply.send(answer);
+    }
+
+    /// Record a finished chat turn and answer any `wait`ing API calls.
+    pub(crate) fn finish_api_chat(&mut self, message: String, output: String) {
+        for waiter in self.api_chat_waiters.drain(..) {
+            let _ = waiter.send(ApiReply::ok(
+                json!({ "message": message, "output": output }),
+            ));
+        }
+        self.last_chat_reply = Some((message, output));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_parse_with_their_bodies() {
+        let chat = parse_route(
+            &Method::Post,
+            "/chat",
+            &json!({"message": "hi", "wait": true}),
+        );
+        assert_eq!(
+            chat.ok(),
+            Some(ApiRequest::Chat {
+                message: "hi".to_string(),
+                wait: true
+            })
+        );
+        assert_eq!(
+            parse_route(&Method::Get, "/agents/3/", &Value::Null).ok(),
+            Some(ApiRequest::AgentStatus { id: 3 })
+        );
+        assert_eq!(
+            parse_route(&Method::Get, "/daemons/results", &Value::Null).ok(),
+            Some(ApiRequest::DaemonResults)
+        );
+    }
+
+    #[test]
+    fn bad_requests_map_to_status_codes() {
+        let status = |method: Method, path: &str, body: Value| {
+            parse_route(&method, path, &body).unwrap_err().status
+        };
+        assert_eq!(status(Method::Post, "/chat", json!({})), 400);
+        assert_eq!(status(Method::Delete, "/chat", Value::Null), 405);
+        assert_eq!(status(Method::Get, "/agents/abc", Value::Null), 404);
+        assert_eq!(status(Method::Get, "/nope", Value::Null), 404);
+    }
+}
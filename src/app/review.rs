@@ -0,0 +1,208 @@
+//! Review queue for file writes proposed by daemons.
+//!
+//! A task with `writes: propose` in its recipe (or every task under
+//! `MEMINI_DAEMON_WRITES=propose`) never writes to the workspace: each
+//! `workspace_write_file` call is stored as a [`PendingChange`] in
+//! `$MEMINI_HOME/review/<id>.json` and the model is told it was queued.
+//! `/review list|diff|apply|discard` works through the queue.  Applying
+//! checks that the file still holds what the task saw, so a proposal is
+//! never written over edits made since.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::diff::FileChange;
+use crate::openai::ToolCall;
+
+/// A file write waiting for `/review apply` or `/review discard`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PendingChange {
+    pub id: u64,
+    /// Daemon task that proposed the write.
+    pub task: String,
+    /// Workspace-relative path.
+    pub path: String,
+    /// File content when the write was proposed; `None` if it did not exist.
+    pub before: Option<String>,
+    pub after: String,
+    pub proposed_at: String,
+}
+
+impl PendingChange {
+    pub fn file_change(&self) -> FileChange {
+        FileChange {
+            path: self.path.clone(),
+            before: self.before.clone(),
+            after: self.after.clone(),
+        }
+    }
+}
+
+/// The on-disk queue, one JSON file per pending change.
+pub(crate) struct ReviewQueue {
+    dir: PathBuf,
+}
+
+impl ReviewQueue {
+    /// `$MEMINI_HOME/review`.
+    pub fn open() -> Self {
+        ReviewQueue {
+            dir: crate::util::memini_home().join("review"),
+        }
+    }
+
+    fn entry_path(&self, id: u64) -> PathBuf {
+        self.dir.join(format!("{id}.json"))
+    }
+
+    /// Queue `change` under the next free id.
+    pub fn propose(&self, task: &str, change: FileChange) -> Result<PendingChange> {
+        fs::create_dir_all(&self.dir).with_context(|| format!("Create {}", self.dir.display()))?;
+        let mut id = self.list().last().map_or(1, |last| last.id + 1);
+        loop {
+            let pending = PendingChange {
+                id,
+                task: task.to_string(),
+                path: change.path.clone(),
+                before: change.before.clone(),
+                after: change.after.clone(),
+                proposed_at: Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+            };
+            // `create_new` keeps two daemons from claiming the same id.
+            let file = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(self.entry_path(id));
+            match file {
+                Ok(mut file) => {
+                    file.write_all(serde_json::to_string_pretty(&pending)?.as_bytes())
+                        .with_context(|| format!("Write {}", self.entry_path(id).display()))?;
+                    return Ok(pending);
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => id += 1,
+                Err(err) => {
+                    return Err(err)
+                        .with_context(|| format!("Create {}", self.entry_path(id).display()));
+                }
+            }
+        }
+    }
+
+    /// Pending changes, oldest first.
+    pub fn list(&self) -> Vec<PendingChange> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut pending: Vec<PendingChange> = entries
+            .flatten()
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "json"))
+            .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+            .filter_map(|raw| serde_json::from_str(&raw).ok())
+            .collect();
+        pending.sort_by_key(|change| change.id);
+        pending
+    }
+
+    pub fn get(&self, id: u64) -> Result<PendingChange> {
+        let path = self.entry_path(id);
+        let Ok(raw) = fs::read_to_string(&path) else {
+            bail!("no pending change #{id} (see /review list)");
+        };
+        serde_json::from_str(&raw).with_context(|| format!("Parse {}", path.display()))
+    }
+
+    /// Write change `id` under `workspace_root` and drop it from the queue.
+    /// Fails if the file changed since the task proposed the write.
+    pub fn apply(&self, id: u64, workspace_root: &Path) -> Result<PendingChange> {
+        let pending = self.get(id)?;
+        let path = workspace_root.join(&pending.path);
+        if fs::read_to_string(&path).ok() != pending.before {
+            bail!(
+                "{} changed since '{}' proposed #{id}; /review discard {id} and re-run the task",
+                pending.path,
+                pending.task
+            );
+        }
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("Create {}", parent.display()))?;
+        }
+        fs::write(&path, &pending.after).with_context(|| format!("Write {}", path.display()))?;
+        fs::remove_file(self.entry_path(id))?;
+        Ok(pending)
+    }
+
+    pub fn discard(&self, id: u64) -> Result<PendingChange> {
+        let pending = self.get(id)?;
+        fs::remove_file(self.entry_path(id))?;
+        Ok(pending)
+    }
+}
+
+/// `workspace_write_file` for a task in propose mode: queue the write and
+/// tell the model the workspace is unchanged.  Returns the tool output and
+/// the queued change.
+pub(crate) fn handle_tool_call(task: &str, call: &ToolCall) -> (String, Option<PendingChange>) {
+    let Some(change) = crate::local_tools::preview_write(call) else {
+        let error = json!({ "error": "path (inside the workspace) and content are required" });
+        return (error.to_string(), None);
+    };
+    let (added, removed) = change.stats();
+    match ReviewQueue::open().propose(task, change) {
+        Ok(pending) => {
+            let output = json!({
+                "status": "proposed",
+                "id": pending.id,
+                "path": pending.path,
+                "lines_added": added,
+                "lines_removed": removed,
+                "note": "Queued for user review; the workspace is unchanged until it is applied.",
+            });
+            (output.to_string(), Some(pending))
+        }
+        Err(err) => (json!({ "error": format!("{err:#}") }).to_string(), None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposals_apply_only_over_the_content_they_saw() {
+        let root = std::env::temp_dir().join(format!("memini-review-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        let queue = ReviewQueue {
+            dir: root.join("review"),
+        };
+        let workspace = root.join("workspace");
+        fs::create_dir_all(&workspace).unwrap();
+        fs::write(workspace.join("notes.md"), "old\n").unwrap();
+
+        let change = |after: &str| FileChange {
+            path: "notes.md".to_string(),
+            before: Some("old\n".to_string()),
+            after: after.to_string(),
+        };
+        let first = queue.propose("digest", change("new\n")).unwrap();
+        let second = queue.propose("digest", change("other\n")).unwrap();
+        assert_eq!((first.id, second.id), (1, 2));
+        assert_eq!(queue.list().len(), 2);
+
+        queue.apply(first.id, &workspace).unwrap();
+        assert_eq!(
+            fs::read_to_string(workspace.join("notes.md")).unwrap(),
+            "new\n"
+        );
+        // The second proposal saw "old" and must not clobber "new".
+        assert!(queue.apply(second.id, &workspace).is_err());
+        queue.discard(second.id).unwrap();
+        assert!(queue.list().is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -0,0 +1,129 @@
+//! Answering MCP `sampling/createMessage` requests with the configured model.
+//!
+//! [`install_hook`] points every MCP connection (see
+//! [`crate::mcp::sampling`]) at the TUI: a request arrives as
+//! [`AgentEvent::SamplingRequested`], is logged, and — in `ask` mode — waits
+//! for `/policy approve <id>` like any other approval before the model runs.
+
+use std::sync::Arc;
+
+use anyhow::{Result, anyhow};
+use tokio::sync::mpsc;
+
+use crate::mcp::sampling::{self, Rejected, SamplingMode, SamplingReply, SamplingRequest};
+use crate::openai::{self, OpenAiClient};
+use crate::routing::TaskKind;
+
+use super::App;
+use super::daemon::AgentEvent;
+use super::log_src;
+use super::logging::LogLevel;
+use super::policy;
+
+/// Send sampling requests from MCP servers to the app over `tx`.
+pub(crate) fn install_hook(tx: mpsc::UnboundedSender<AgentEvent>) {
+    sampling::install(Arc::new(move |request| {
+        let tx = tx.clone();
+        Box::pin(async move {
+            let (reply, mut replies) = mpsc::unbounded_channel();
+            tx.send(AgentEvent::SamplingRequested { request, reply })
+                .map_err(|_| anyhow!("memini is shutting down"))?;
+            replies
+                .recv()
+                .await
+                .unwrap_or_else(|| Err(anyhow!("sampling request dropped")))
+        })
+    }));
+}
+
+impl App {
+    /// Run a sampling request in the background and answer on `reply`.
+    pub(crate) fn handle_sampling_request(
+        &mut self,
+        request: SamplingRequest,
+        reply: mpsc::UnboundedSender<Result<SamplingReply>>,
+    ) {
+        let key = match self.ensure_openai_key() {
+            Ok(key) => key,
+            Err(err) => {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("Sampling request from {} refused: {err}", request.server_id)
+                );
+                let _ = reply.send(Err(err));
+                return;
+            }
+        };
+        let model = request
+            .model
+            .clone()
+            .unwrap_or_else(|| self.openai.model_for(TaskKind::Chat));
+        self.log(
+            LogLevel::Info,
+            format!(
+                "🧪 MCP {} asks {model} for a completion (max {} tokens).",
+                request.server_id, request.max_tokens
+            ),
+        );
+        let openai = self.openai.clone();
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            let _ = reply.send(sample(&openai, &key, &model, &request, &tx).await);
+        });
+    }
+}
+
+async fn sample(
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    request: &SamplingRequest,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Result<SamplingReply> {
+    if request.mode == SamplingMode::Ask
+        && !policy::request_sampling_approval(&request.server_id, &request.preview, tx).await
+    {
+        return Err(Rejected.into());
+    }
+    let response = openai.response(key, model, &request.input, None).await?;
+    let text = openai::extract_output_text(&openai::extract_output_items(&response));
+    Ok(SamplingReply {
+        model: openai::answering_model(&response, model),
+        text,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::harness::Harness;
+    use serde_json::json;
+
+    #[test]
+    fn allowed_requests_are_answered_by_the_model() {
+        let mut harness = Harness::new();
+        harness.mocks.llm.reply_text("Paris");
+        let (reply, mut replies) = mpsc::unbounded_channel();
+        harness.app.handle_sampling_request(
+            SamplingRequest {
+                server_id: "geo".to_string(),
+                mode: SamplingMode::Allow,
+                model: Some("gpt-4o-mini".to_string()),
+                input: vec![json!({"role": "user", "content": "Capital of France?"})],
+                max_tokens: 20,
+                preview: "Capital of France?".to_string(),
+            },
+            reply,
+        );
+        let answer = harness
+            .app
+            .runtime
+            .block_on(replies.recv())
+            .expect("reply")
+            .expect("sampled");
+        assert_eq!(answer.text, "Paris");
+        let requests = harness.mocks.llm.requests("responses");
+        assert_eq!(requests[0]["model"], "gpt-4o-mini");
+    }
+}
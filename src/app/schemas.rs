@@ -0,0 +1,266 @@
+//! JSON schemas for structured output (`/json` and recipe `schema:`),
+//! stored as `$MEMINI_HOME/schemas/<name>.json`.
+//!
+//! A request with a schema asks the Responses API for `json_schema` output.
+//! Strict mode is on when the schema's top level sets
+//! `"additionalProperties": false`, since OpenAI rejects strict schemas
+//! that allow extra keys.  The reply is parsed and checked against the
+//! schema here as well (types, `required`, `properties`, `items`, `enum`,
+//! `additionalProperties`).  A reply that does not match is sent back
+//! with the errors, up to [`MAX_SCHEMA_RETRIES`] times.
+
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde_json::{Value, json};
+
+use super::agent_recipes::sanitize_name;
+use crate::constants::MAX_SCHEMA_RETRIES;
+use crate::openai::{self, OpenAiClient};
+use crate::util::memini_home;
+
+#[derive(Clone, Debug)]
+pub struct JsonSchema {
+    pub name: String,
+    pub schema: Value,
+    pub path: PathBuf,
+}
+
+impl JsonSchema {
+    /// `text.format` object for a Responses API request.
+    pub fn text_format(&self) -> Value {
+        let strict = self.schema.get("additionalProperties") == Some(&Value::Bool(false));
+        json!({
+            "type": "json_schema",
+            "name": self.name,
+            "schema": self.schema,
+            "strict": strict,
+        })
+    }
+}
+
+pub fn schemas_dir() -> PathBuf {
+    memini_home().join("schemas")
+}
+
+/// All schemas in the schemas dir, sorted by name.
+pub fn load_schemas() -> Result<Vec<JsonSchema>> {
+    let dir = schemas_dir();
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut schemas = Vec::new();
+    for entry in fs::read_dir(&dir).with_context(|| format!("Read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().and_then(|ext| ext.to_str()) != Some("json") {
+            continue;
+        }
+        let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) else {
+            continue;
+        };
+        let raw = fs::read_to_string(&path).with_context(|| format!("Read {}", path.display()))?;
+        let schema: Value =
+            serde_json::from_str(&raw).with_context(|| format!("Parse {}", path.display()))?;
+        schemas.push(JsonSchema {
+            name: name.to_string(),
+            schema,
+            path: path.clone(),
+        });
+    }
+    schemas.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(schemas)
+}
+
+pub fn find_schema(name: &str) -> Result<JsonSchema> {
+    let name = sanitize_name(name)?;
+    load_schemas()?
+        .into_iter()
+        .find(|schema| schema.name == name)
+        .ok_or_else(|| {
+            anyhow!(
+                "no schema '{name}' (add {}/{name}.json)",
+                schemas_dir().display()
+            )
+        })
+}
+
+/// Ask `model` for JSON matching `schema`, retrying with the validation
+/// errors when the reply does not match.  Returns the parsed object.
+pub(crate) async fn generate(
+    openai: &OpenAiClient,
+    key: &str,
+    model: &str,
+    mut input: Vec<Value>,
+    schema: &JsonSchema,
+) -> Result<Value> {
+    let format = schema.text_format();
+    let mut problems = Vec::new();
+    for _ in 0..=MAX_SCHEMA_RETRIES {
+        let response = openai
+            .structured_response(key, model, &input, &format)
+            .await?;
+        let items = openai::extract_output_items(&response);
+        let text = openai::extract_output_text(&items);
+        problems = match serde_json::from_str::<Value>(strip_fence(&text)) {
+            Ok(value) => {
+                let errors = validate(&schema.schema, &value);
+                if errors.is_empty() {
+                    return Ok(value);
+                }
+                errors
+            }
+            Err(err) => vec![format!("not valid JSON: {err}")],
+        };
+        input.push(json!({"role": "assistant", "content": text}));
+        input.push(json!({
+            "role": "user",
+            "content": format!(
+                "That reply does not match the `{}` schema:\n- {}\nReply again with only the corrected JSON.",
+                schema.name,
+                problems.join("\n- ")
+            ),
+        }));
+    }
+    bail!(
+        "reply did not match schema '{}' after {} attempt(s): {}",
+        schema.name,
+        MAX_SCHEMA_RETRIES + 1,
+        problems.join("; ")
+    )
+}
+
+/// Drop a ```` ```json ```` fence some models wrap around JSON.
+fn strip_fence(text: &str) -> &str {
+    let trimmed = text.trim();
+    trimmed
+        .strip_prefix("```json")
+        .or_else(|| trimmed.strip_prefix("```"))
+        .and_then(|rest| rest.strip_suffix("```"))
+        .map(str::trim)
+        .unwrap_or(trimmed)
+}
+
+/// Schema violations in `value`, as `$.path: problem` lines.
+pub fn validate(schema: &Value, value: &Value) -> Vec<String> {
+    let mut errors = Vec::new();
+    check(schema, value, "$", &mut errors);
+    errors
+}
+
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<String>) {
+    if let Some(expected) = schema.get("type") {
+        let allowed: Vec<&str> = match expected {
+            Value::String(name) => vec![name.as_str()],
+            Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|name| has_type(value, name)) {
+            errors.push(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+            return;
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            errors.push(format!(
+                "{path}: must be one of {}",
+                Value::Array(options.clone())
+            ));
+        }
+    }
+    if let Value::Object(fields) = value {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for name in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !fields.contains_key(name) {
+                errors.push(format!("{path}: missing required field '{name}'"));
+            }
+        }
+        for (name, field) in fields {
+            match properties.and_then(|properties| properties.get(name)) {
+                Some(field_schema) => check(field_schema, field, &format!("{path}.{name}"), errors),
+                None if schema.get("additionalProperties") == Some(&Value::Bool(false)) => {
+                    errors.push(format!("{path}: unexpected field '{name}'"));
+                }
+                None => {}
+            }
+        }
+    }
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{path}[{index}]"), errors);
+        }
+    }
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ticket_schema() -> Value {
+        json!({
+            "type": "object",
+            "additionalProperties": false,
+            "required": ["title", "severity", "tags"],
+            "properties": {
+                "title": {"type": "string"},
+                "severity": {"type": "string", "enum": ["low", "high"]},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            }
+        })
+    }
+
+    #[test]
+    fn accepts_matching_objects() {
+        let value = json!({"title": "Login broken", "severity": "high", "tags": ["auth"]});
+        assert!(validate(&ticket_schema(), &value).is_empty());
+    }
+
+    #[test]
+    fn reports_each_violation_with_its_path() {
+        let value = json!({"title": 3, "severity": "urgent", "tags": ["ok", 1], "extra": true});
+        let errors = validate(&ticket_schema(), &value);
+        assert!(errors.contains(&"$.title: expected string, got number".to_string()));
+        assert!(
+            errors
+                .iter()
+                .any(|e| e.starts_with("$.severity: must be one of"))
+        );
+        assert!(errors.contains(&"$.tags[1]: expected string, got number".to_string()));
+        assert!(errors.contains(&"$: unexpected field 'extra'".to_string()));
+        assert_eq!(strip_fence("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+    }
+}
@@ -0,0 +1,398 @@
+//! `/run <file.mm>` — replay a file of commands and chat prompts.
+//!
+//! One step per line, run in order from the main loop:
+//!
+//! ```text
+//! # connect, import, fan out
+//! /mcp connect github
+//! if failed stop
+//! /skills import https://skills.sh/acme/review
+//! sleep 2s
+//! Summarise the open PRs in acme/api, then \
+//! spawn an agent per PR to review it.
+//! if ok /notify test
+//! ```
+//!
+//! Lines starting with `/` are commands and anything else is sent as a chat
+//! message; the next step waits until the reply has finished.  `#` starts a
+//! comment, a trailing `\` joins the next line, `sleep <n>[ms|s|m]` pauses,
+//! and `stop` ends the script.  `if ok <step>` / `if failed <step>` run a
+//! step only when the previous one did (or did not) log a warning or error.
+
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+
+use super::App;
+use super::log_src;
+use super::logging::LogLevel;
+use crate::constants::SCRIPT_STEPS_PER_TICK;
+
+/// File extension for scripts.
+const SCRIPT_EXTENSION: &str = "mm";
+
+/// What a step does.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Action {
+    /// A `/command` or chat message, exactly as if typed.
+    Input(String),
+    Sleep(Duration),
+    Stop,
+}
+
+/// One parsed line.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Step {
+    /// 1-based line in the file, for messages.
+    pub line: usize,
+    /// `Some(true)` for `if ok`, `Some(false)` for `if failed`.
+    pub when: Option<bool>,
+    pub action: Action,
+}
+
+/// Parse a script; errors name the offending line.
+pub(crate) fn parse(source: &str) -> Result<Vec<Step>> {
+    let mut steps = Vec::new();
+    let mut pending: Option<(usize, String)> = None;
+    for (index, raw) in source.lines().enumerate() {
+        let text = raw.trim();
+        let (line, mut joined) = match pending.take() {
+            Some((start, mut joined)) => {
+                joined.push('\n');
+                joined.push_str(text);
+                (start, joined)
+            }
+            None if text.is_empty() || text.starts_with('#') => continue,
+            None => (index + 1, text.to_string()),
+        };
+        if let Some(head) = joined.strip_suffix('\\') {
+            joined = head.trim_end().to_string();
+            pending = Some((line, joined));
+            continue;
+        }
+        steps.push(parse_step(line, &joined)?);
+    }
+    if let Some((line, joined)) = pending {
+        steps.push(parse_step(line, &joined)?);
+    }
+    Ok(steps)
+}
+
+fn parse_step(line: usize, text: &str) -> Result<Step> {
+    let (when, body) = if let Some(rest) = text.strip_prefix("if ok ") {
+        (Some(true), rest.trim())
+    } else if let Some(rest) = text.strip_prefix("if failed ") {
+        (Some(false), rest.trim())
+    } else {
+        (None, text)
+    };
+    let mut words = body.split_whitespace();
+    let action = match (words.next(), words.next(), words.next()) {
+        (Some("stop"), None, _) => Action::Stop,
+        // `sleep 2s`, but "sleep on it" is still a chat message.
+        (Some("sleep"), Some(amount), None) if amount.starts_with(|c: char| c.is_ascii_digit()) => {
+            Action::Sleep(
+                parse_duration(amount)
+                    .with_context(|| format!("line {line}: usage is sleep <n>[ms|s|m]"))?,
+            )
+        }
+        (Some("/run"), _, _) => bail!("line {line}: scripts cannot /run other scripts"),
+        (None, _, _) => bail!("line {line}: nothing after `if`"),
+        _ => Action::Input(body.to_string()),
+    };
+    Ok(Step { line, when, action })
+}
+
+/// `500ms`, `2s`, `1m`, or bare seconds.
+fn parse_duration(amount: &str) -> Result<Duration> {
+    let split = amount
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(amount.len());
+    let (number, unit) = amount.split_at(split);
+    let value: f64 = number
+        .parse()
+        .map_err(|_| anyhow!("`{amount}` is not a duration"))?;
+    let seconds = match unit {
+        "ms" => value / 1000.0,
+        "" | "s" => value,
+        "m" => value * 60.0,
+        other => bail!("unknown unit `{other}` (use ms, s or m)"),
+    };
+    Ok(Duration::from_secs_f64(seconds))
+}
+
+/// Find `name` as given, with `.mm` added, or under `$MEMINI_HOME/scripts`.
+pub(crate) fn resolve(name: &str) -> Option<PathBuf> {
+    let given = PathBuf::from(name);
+    let mut candidates = vec![given.clone(), given.with_extension(SCRIPT_EXTENSION)];
+    let scripts_dir = given
+        .is_relative()
+        .then(|| crate::util::memini_home().join("scripts"));
+    if let Some(dir) = scripts_dir {
+        candidates.push(dir.join(&given));
+        candidates.push(dir.join(&given).with_extension(SCRIPT_EXTENSION));
+    }
+    candidates.into_iter().find(|path| path.is_file())
+}
+
+/// What the running script is waiting on.
+#[derive(Debug)]
+enum Wait {
+    Ready,
+    Until(Instant),
+    /// A chat turn; `warnings` is the count from before it started.
+    Chat {
+        warnings: u64,
+    },
+}
+
+/// A script in progress.
+#[derive(Debug)]
+pub(crate) struct ScriptRun {
+    pub name: String,
+    steps: VecDeque<Step>,
+    total: usize,
+    failed: usize,
+    last_ok: bool,
+    wait: Wait,
+}
+
+impl ScriptRun {
+    pub fn new(path: &Path, steps: Vec<Step>) -> Self {
+        ScriptRun {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+            total: steps.len(),
+            steps: steps.into(),
+            failed: 0,
+            last_ok: true,
+            wait: Wait::Ready,
+        }
+    }
+
+    /// `step n/total` for status lines.
+    pub fn progress(&self) -> String {
+        format!(
+            "step {}/{}",
+            self.total - self.steps.len(),
+            self.total.max(1)
+        )
+    }
+}
+
+// ── Runner ───────────────────────────────────────────────────────────
+
+impl App {
+    /// Load `name` and start running it.
+    pub(crate) fn start_script(&mut self, name: &str) {
+        if let Some(run) = &self.script {
+            let message = format!(
+                "{} is still running ({}). /run stop cancels it.",
+                run.name,
+                run.progress()
+            );
+            log_src!(self, LogLevel::Warn, message);
+            return;
+        }
+        let Some(path) = resolve(name) else {
+            log_src!(self, LogLevel::Warn, format!("Script not found: {name}"));
+            return;
+        };
+        let steps = match std::fs::read_to_string(&path)
+            .with_context(|| format!("read {}", path.display()))
+            .and_then(|source| parse(&source))
+        {
+            Ok(steps) => steps,
+            Err(err) => {
+                log_src!(self, LogLevel::Error, format!("Script error: {err:#}"));
+                return;
+            }
+        };
+        let run = ScriptRun::new(&path, steps);
+        self.log(
+            LogLevel::Info,
+            format!("Running {} ({} steps).", run.name, run.total),
+        );
+        self.script = Some(run);
+        self.advance_script();
+    }
+
+    /// Cancel the running script, if any.
+    pub(crate) fn stop_script(&mut self) {
+        match self.script.take() {
+            Some(run) => self.log(
+                LogLevel::Info,
+                format!("Stopped {} at {}.", run.name, run.progress()),
+            ),
+            None => self.log(LogLevel::Info, "No script is running.".to_string()),
+        }
+    }
+
+    /// Run ready steps until one has to wait (called every tick).
+    pub(crate) fn advance_script(&mut self) {
+        for _ in 0..SCRIPT_STEPS_PER_TICK {
+            let Some(run) = self.script.as_mut() else {
+                return;
+            };
+            match run.wait {
+                Wait::Until(at) if Instant::now() < at => return,
+                Wait::Chat { .. } if self.chat_busy => return,
+                Wait::Chat { warnings } => {
+                    run.last_ok = self.warnings_logged == warnings;
+                    run.failed += usize::from(!run.last_ok);
+                }
+                _ => {}
+            }
+            run.wait = Wait::Ready;
+            // A chat typed meanwhile finishes before the next step.
+            if self.chat_busy {
+                return;
+            }
+
+            if self.tool_form.is_some() || self.rice_setup_step.is_some() {
+                let name = run.name.clone();
+                self.script = None;
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!("{name} stopped: a step is waiting for typed input.")
+                );
+                return;
+            }
+
+            let Some(step) = run.steps.pop_front() else {
+                let run = self.script.take().expect("script is running");
+                let outcome = match run.failed {
+                    0 => "no failures".to_string(),
+                    n => format!("{n} failed"),
+                };
+                self.log(
+                    LogLevel::Info,
+                    format!("Finished {} ({} steps, {outcome}).", run.name, run.total),
+                );
+                return;
+            };
+            if step.when.is_some_and(|when| when != run.last_ok) {
+                continue;
+            }
+            match step.action {
+                Action::Sleep(duration) => run.wait = Wait::Until(Instant::now() + duration),
+                Action::Stop => {
+                    let name = run.name.clone();
+                    self.script = None;
+                    self.log(
+                        LogLevel::Info,
+                        format!("{name} stopped at line {}.", step.line),
+                    );
+                    return;
+                }
+                Action::Input(text) => {
+                    let prefix = format!("{}:{}", run.name, step.line);
+                    self.run_script_input(&prefix, &text);
+                }
+            }
+        }
+    }
+
+    /// Execute one command or chat message and record how it went.
+    fn run_script_input(&mut self, prefix: &str, text: &str) {
+        let warnings = self.warnings_logged;
+        if text.starts_with('/') {
+            self.log(LogLevel::Info, format!("▸ {prefix} {text}"));
+            if let Err(err) = self.handle_command(text) {
+                log_src!(self, LogLevel::Error, format!("{text}: {err:#}"));
+            }
+        } else {
+            self.log(LogLevel::Info, format!("› {text}"));
+            self.chat_busy = true;
+            self.handle_chat_message(text, false);
+        }
+        let Some(run) = self.script.as_mut() else {
+            return;
+        };
+        if self.chat_busy {
+            run.wait = Wait::Chat { warnings };
+        } else {
+            run.last_ok = self.warnings_logged == warnings;
+            run.failed += usize::from(!run.last_ok);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::harness::Harness;
+
+    #[test]
+    fn parses_comments_continuations_and_conditions() {
+        let steps = parse(
+            "# setup\n\n/mcp connect github\nif failed stop\nsleep 250ms\nSummarise this \\\n  and that\nif ok /stats\n",
+        )
+        .unwrap();
+        let actions: Vec<(usize, Option<bool>, Action)> = steps
+            .into_iter()
+            .map(|step| (step.line, step.when, step.action))
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                (3, None, Action::Input("/mcp connect github".into())),
+                (4, Some(false), Action::Stop),
+                (5, None, Action::Sleep(Duration::from_millis(250))),
+                (6, None, Action::Input("Summarise this\nand that".into())),
+                (8, Some(true), Action::Input("/stats".into())),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_lines() {
+        assert!(
+            parse("sleep 5x")
+                .unwrap_err()
+                .to_string()
+                .contains("line 1")
+        );
+        assert_eq!(
+            parse("sleep on it").unwrap()[0].action,
+            Action::Input("sleep on it".into())
+        );
+        assert!(parse("# x\n/run other.mm").is_err());
+        assert_eq!(parse_duration("1m").unwrap(), Duration::from_secs(60));
+        assert_eq!(parse_duration("3").unwrap(), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn runs_steps_and_branches_on_failures() {
+        let path = std::env::temp_dir().join(format!("memini-run-{}.mm", std::process::id()));
+        std::fs::write(
+            &path,
+            "/stats bogus\nif ok /stats reset\nif failed Hello there\n/stats reset\n",
+        )
+        .unwrap();
+        let mut harness = Harness::new();
+        harness.mocks.llm.reply_text("Hi from the script.");
+
+        harness.submit(&format!("/run {}", path.display()));
+        while harness.app.script.is_some() {
+            harness.settle();
+            harness.app.advance_script();
+        }
+        let _ = std::fs::remove_file(&path);
+
+        assert!(harness.logged("Hi from the script."));
+        let cleared = harness
+            .logs()
+            .iter()
+            .filter(|line| line.as_str() == "Stats cleared.")
+            .count();
+        assert_eq!(cleared, 1, "the `if ok` step is skipped");
+        assert!(harness.logged("(4 steps, 1 failed)"));
+        assert_eq!(harness.mocks.llm.requests("responses").len(), 1);
+    }
+}
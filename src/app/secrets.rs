@@ -0,0 +1,116 @@
+//! Named secrets for recipe `env:` front matter.
+//!
+//! `/secret set GITHUB_TOKEN …` stores a value in a JSON file next to the
+//! local MCP store (mode 0600 on Unix).  A recipe that lists
+//! `env: GITHUB_TOKEN` gets that variable in its `workspace_run_command`
+//! processes, and only for that task's runs; nothing is added to memini's
+//! own environment.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::APP_NAME;
+
+/// Persisted secrets, keyed by environment variable name.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct SecretStore {
+    pub values: BTreeMap<String, String>,
+}
+
+impl SecretStore {
+    /// Split `names` into `(name, value)` pairs and the names with no value.
+    pub fn resolve(&self, names: &[String]) -> (Vec<(String, String)>, Vec<String>) {
+        let mut found = Vec::new();
+        let mut missing = Vec::new();
+        for name in names {
+            match self.values.get(name) {
+                Some(value) => found.push((name.clone(), value.clone())),
+                None => missing.push(name.clone()),
+            }
+        }
+        (found, missing)
+    }
+}
+
+/// Environment variable names: letters, digits and `_`, not starting with
+/// a digit.
+pub fn validate_name(name: &str) -> Result<()> {
+    let valid = name
+        .chars()
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+    if !valid {
+        bail!("`{name}` is not a valid environment variable name");
+    }
+    Ok(())
+}
+
+/// Returns the platform-specific path for the secret store file.
+fn secret_store_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME).map(|dirs| dirs.config_dir().join("secrets.json"))
+}
+
+/// Load secrets from disk, falling back to an empty store.
+pub fn load_secret_store() -> SecretStore {
+    let Some(path) = secret_store_path() else {
+        return SecretStore::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return SecretStore::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_default()
+}
+
+/// Persist secrets to disk, readable by the owner only.
+pub fn persist_secret_store(store: &SecretStore) -> Result<()> {
+    let Some(path) = secret_store_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create config dir")?;
+    }
+    let contents = serde_json::to_string_pretty(store).context("serialize secret store")?;
+    fs::write(&path, contents).context("write secret store")?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o600))
+            .context("restrict secret store permissions")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolves_only_the_listed_names() {
+        let mut store = SecretStore::default();
+        store.values.insert("GITHUB_TOKEN".into(), "ghp_x".into());
+        store.values.insert("OTHER".into(), "y".into());
+        let (found, missing) = store.resolve(&["GITHUB_TOKEN".into(), "NPM_TOKEN".into()]);
+        assert_eq!(
+            found,
+            vec![("GITHUB_TOKEN".to_string(), "ghp_x".to_string())]
+        );
+        assert_eq!(missing, vec!["NPM_TOKEN".to_string()]);
+    }
+
+    #[test]
+    fn validates_variable_names() {
+        assert!(validate_name("GITHUB_TOKEN").is_ok());
+        assert!(validate_name("_x1").is_ok());
+        assert!(validate_name("1X").is_err());
+        assert!(validate_name("A-B").is_err());
+        assert!(validate_name("").is_err());
+    }
+}
@@ -0,0 +1,181 @@
+//! Named conversation sessions for `/session save|load|list`, and the
+//! thread surgery behind `/branch` and `/rewind`.
+//!
+//! A session captures the conversation thread, the active persona, and the
+//! set of connected MCP servers.  Sessions are written to a local JSON file
+//! (so they work without Rice) and mirrored to Rice State when available.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::APP_NAME;
+
+/// A saved snapshot of the chat state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SavedSession {
+    pub name: String,
+    pub saved_at: String,
+    #[serde(default)]
+    pub thread: Vec<Value>,
+    pub agent_name: String,
+    #[serde(default)]
+    pub mcp_ids: Vec<String>,
+    #[serde(default)]
+    pub active_mcp: Option<String>,
+}
+
+/// All saved sessions, keyed by lower-cased name.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SessionIndex {
+    #[serde(default)]
+    pub sessions: BTreeMap<String, SavedSession>,
+}
+
+impl SessionIndex {
+    pub fn get(&self, name: &str) -> Option<&SavedSession> {
+        self.sessions.get(&name.to_ascii_lowercase())
+    }
+
+    pub fn insert(&mut self, session: SavedSession) {
+        self.sessions
+            .insert(session.name.to_ascii_lowercase(), session);
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<SavedSession> {
+        self.sessions.remove(&name.to_ascii_lowercase())
+    }
+
+    /// Merge `other` into `self`, keeping the most recently saved entry.
+    pub fn merge(&mut self, other: SessionIndex) {
+        for (key, session) in other.sessions {
+            let newer = self
+                .sessions
+                .get(&key)
+                .is_none_or(|existing| existing.saved_at < session.saved_at);
+            if newer {
+                self.sessions.insert(key, session);
+            }
+        }
+    }
+}
+
+/// Text of a thread entry's user message, if it is one.
+fn user_text(entry: &Value) -> Option<&str> {
+    (entry.get("role").and_then(Value::as_str) == Some("user"))
+        .then(|| entry.get("content").and_then(Value::as_str))
+        .flatten()
+}
+
+/// User messages in `thread`, most recent first, with their thread index.
+/// Position `i` in the result is what `/rewind <i + 1>` goes back to.
+pub fn user_turns(thread: &[Value]) -> Vec<(usize, &str)> {
+    thread
+        .iter()
+        .enumerate()
+        .rev()
+        .filter_map(|(index, entry)| user_text(entry).map(|text| (index, text)))
+        .collect()
+}
+
+/// Drop the last `turns` user turns (and everything after them) from
+/// `thread`.  Returns the earliest dropped user message, or `None` when
+/// the thread has fewer turns.
+pub fn rewind(thread: &mut Vec<Value>, turns: usize) -> Option<String> {
+    let (cut, text) = user_turns(thread)
+        .get(turns.checked_sub(1)?)
+        .map(|(index, text)| (*index, text.to_string()))?;
+    thread.truncate(cut);
+    Some(text)
+}
+
+/// Session name for an automatically saved branch.
+pub fn branch_name() -> String {
+    format!("branch-{}", chrono::Local::now().format("%Y%m%d-%H%M%S"))
+}
+
+/// Returns the platform-specific path for the local session file.
+fn sessions_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME).map(|dirs| dirs.config_dir().join("sessions.json"))
+}
+
+/// Load locally saved sessions, falling back to an empty index.
+pub fn load_local_sessions() -> SessionIndex {
+    let Some(path) = sessions_path() else {
+        return SessionIndex::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return SessionIndex::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_default()
+}
+
+/// Persist the session index to disk.
+pub fn persist_local_sessions(index: &SessionIndex) -> Result<()> {
+    let Some(path) = sessions_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create config dir")?;
+    }
+    let contents = serde_json::to_string_pretty(index).context("serialize sessions")?;
+    fs::write(&path, contents).context("write sessions")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session(name: &str, saved_at: &str) -> SavedSession {
+        SavedSession {
+            name: name.to_string(),
+            saved_at: saved_at.to_string(),
+            thread: Vec::new(),
+            agent_name: "memini".to_string(),
+            mcp_ids: Vec::new(),
+            active_mcp: None,
+        }
+    }
+
+    #[test]
+    fn rewind_drops_the_last_turns() {
+        let mut thread = vec![
+            serde_json::json!({"role": "user", "content": "first"}),
+            serde_json::json!({"role": "assistant", "content": "a1"}),
+            serde_json::json!({"role": "system", "content": "sub-agent result"}),
+            serde_json::json!({"role": "user", "content": "second"}),
+            serde_json::json!({"role": "assistant", "content": "a2"}),
+        ];
+        let turns: Vec<&str> = user_turns(&thread).iter().map(|(_, t)| *t).collect();
+        assert_eq!(turns, vec!["second", "first"]);
+        assert_eq!(rewind(&mut thread, 3), None);
+        assert_eq!(rewind(&mut thread, 0), None);
+        assert_eq!(thread.len(), 5);
+        assert_eq!(rewind(&mut thread, 1).as_deref(), Some("second"));
+        assert_eq!(thread.len(), 3);
+        assert_eq!(rewind(&mut thread, 1).as_deref(), Some("first"));
+        assert!(thread.is_empty());
+    }
+
+    #[test]
+    fn merge_keeps_newest_session() {
+        let mut local = SessionIndex::default();
+        local.insert(session("Bug-Hunt", "2026-01-02T00:00:00Z"));
+        let mut remote = SessionIndex::default();
+        remote.insert(session("bug-hunt", "2026-01-01T00:00:00Z"));
+        remote.insert(session("perf", "2026-01-01T00:00:00Z"));
+
+        local.merge(remote);
+        assert_eq!(local.sessions.len(), 2);
+        assert_eq!(
+            local.get("BUG-HUNT").map(|s| s.saved_at.as_str()),
+            Some("2026-01-02T00:00:00Z")
+        );
+    }
+}
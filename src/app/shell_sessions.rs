@@ -0,0 +1,453 @@
+//! Persistent shell sessions for agent windows: `shell_start`,
+//! `shell_exec` and `shell_close`.
+//!
+//! `workspace_run_command` starts a fresh shell per call, so `cd`,
+//! `export` and activated virtualenvs are gone by the next one.  A session
+//! keeps one `sh` per window alive between calls and between runs of the
+//! window.  It is built by [`sandbox::build_shell`], so the `[sandbox]`
+//! backend applies and every command is checked against
+//! `allowed_binaries`.  The shell runs on a pseudo-terminal, so programs
+//! see a terminal and stderr arrives with stdout; echo and the prompt are
+//! turned off so the output holds only what commands print.  Commands
+//! still must not wait for input.
+//!
+//! Each command's end is found by a marker line carrying its exit code
+//! and the shell's working directory.  Output goes into a scrollback of
+//! the last [`SHELL_SCROLLBACK_LINES`] lines.  A session is closed by
+//! `shell_close`, `/kill`, a command that times out, or
+//! [`SHELL_IDLE_TIMEOUT_SECS`] without a call.
+
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use portable_pty::{Child, MasterPty, PtySize, native_pty_system};
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+
+use crate::constants::{SHELL_IDLE_TIMEOUT_SECS, SHELL_SCROLLBACK_LINES};
+use crate::local_tools::{
+    MAX_COMMAND_TIMEOUT_SECS, MAX_OUTPUT_CHARS, resolve_workspace_path, to_workspace_relative,
+    trim_chars, workspace_root,
+};
+use crate::openai::ToolCall;
+use crate::sandbox;
+
+use super::daemon::AgentEvent;
+
+pub const SHELL_TOOLS: &[&str] = &["shell_start", "shell_exec", "shell_close"];
+
+/// Lines of scrollback returned by `shell_start` for a running session.
+const SCROLLBACK_TAIL: usize = 40;
+
+/// Size of the session's terminal; wide so programs do not wrap output.
+const TERMINAL_SIZE: PtySize = PtySize {
+    rows: 50,
+    cols: 200,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+/// How long `shell_start` waits for the shell to take its settings.
+const SETUP_TIMEOUT_SECS: u64 = 10;
+
+static NEXT_SESSION: AtomicUsize = AtomicUsize::new(1);
+
+struct Session {
+    id: usize,
+    /// Taken when the session is dropped, to be killed off the runtime.
+    child: Option<Box<dyn Child + Send + Sync>>,
+    _terminal: Box<dyn MasterPty + Send>,
+    input: Box<dyn Write + Send>,
+    output: mpsc::UnboundedReceiver<String>,
+    scrollback: VecDeque<String>,
+    cwd: PathBuf,
+    commands: usize,
+    last_used: Instant,
+}
+
+impl Session {
+    fn write(&mut self, script: &str) -> Result<()> {
+        self.input
+            .write_all(script.as_bytes())
+            .and_then(|()| self.input.flush())
+            .context("Write to shell")
+    }
+
+    fn remember(&mut self, line: &str) {
+        if self.scrollback.len() >= SHELL_SCROLLBACK_LINES {
+            self.scrollback.pop_front();
+        }
+        self.scrollback.push_back(line.to_string());
+    }
+
+    fn tail(&self, lines: usize) -> String {
+        let skip = self.scrollback.len().saturating_sub(lines);
+        self.scrollback
+            .iter()
+            .skip(skip)
+            .cloned()
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Drop for Session {
+    /// Killing waits for the shell to exit, so it runs on its own thread
+    /// rather than stalling the async worker that closed the session.
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            std::thread::spawn(move || {
+                let _ = child.kill();
+                let _ = child.wait();
+            });
+        }
+    }
+}
+
+type Shared = Arc<tokio::sync::Mutex<Session>>;
+
+fn registry() -> &'static Mutex<HashMap<usize, Shared>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<usize, Shared>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn session_for(window_id: usize) -> Option<Shared> {
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .get(&window_id)
+        .cloned()
+}
+
+/// Close the shell session of `window_id`, if it has one.  Dropping the
+/// session kills the shell.
+pub fn close_window(window_id: usize) -> bool {
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .remove(&window_id)
+        .is_some()
+}
+
+pub fn tool_defs() -> Vec<Value> {
+    vec![
+        json!({
+            "type": "function",
+            "name": "shell_start",
+            "description": "Start this agent's persistent shell session in the workspace. cd, exports and activated environments carry over between shell_exec calls. Returns the recent scrollback if a session is already running.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "workdir": {
+                        "type": "string",
+                        "description": "Relative directory the shell starts in (default workspace root)."
+                    }
+                }
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "shell_exec",
+            "description": "Run a command in this agent's shell session (started automatically) and return its combined stdout/stderr, exit code and the shell's working directory afterwards. Commands must not wait for input; a command that times out closes the session.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "command": {
+                        "type": "string",
+                        "description": "Shell command to run."
+                    },
+                    "timeout_seconds": {
+                        "type": "integer",
+                        "description": "Timeout seconds (default 60, max 300)."
+                    }
+                },
+                "required": ["command"]
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "shell_close",
+            "description": "Close this agent's shell session.",
+            "parameters": { "type": "object", "properties": {} }
+        }),
+    ]
+}
+
+/// Handle a shell session tool call from window `window_id`; `None` for
+/// other tools.
+pub async fn handle_tool_call(
+    window_id: usize,
+    call: &ToolCall,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Option<String> {
+    let result = match call.name.as_str() {
+        "shell_start" => start(window_id, &call.arguments, tx).await,
+        "shell_exec" => exec(window_id, &call.arguments, tx).await,
+        "shell_close" => {
+            let status = if close_window(window_id) {
+                "closed"
+            } else {
+                "no session"
+            };
+            Ok(json!({ "status": status }))
+        }
+        _ => return None,
+    };
+    Some(
+        result
+            .unwrap_or_else(|err| json!({ "error": format!("{err:#}") }))
+            .to_string(),
+    )
+}
+
+/// Forward lines read from the terminal until the shell exits.  Reads
+/// block, so this runs on its own thread.
+fn pump(terminal: Box<dyn Read + Send>, tx: mpsc::UnboundedSender<String>) {
+    std::thread::spawn(move || {
+        let mut reader = BufReader::new(terminal);
+        let mut raw = Vec::new();
+        loop {
+            raw.clear();
+            match reader.read_until(b'\n', &mut raw) {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {}
+            }
+            let line = String::from_utf8_lossy(&raw);
+            let line = line.trim_end_matches(['\n', '\r']).to_string();
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+}
+
+async fn start(
+    window_id: usize,
+    args: &Value,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Result<Value> {
+    if let Some(shared) = session_for(window_id) {
+        let session = shared.lock().await;
+        return Ok(json!({
+            "status": "already running",
+            "cwd": to_workspace_relative(&session.cwd, &workspace_root()),
+            "commands_run": session.commands,
+            "scrollback": session.tail(SCROLLBACK_TAIL),
+        }));
+    }
+
+    let workdir_arg = args.get("workdir").and_then(Value::as_str).unwrap_or(".");
+    let (root, workdir) = resolve_workspace_path(workdir_arg)?;
+    if !workdir.is_dir() {
+        bail!(
+            "Working directory is not a directory: {}",
+            workdir.display()
+        );
+    }
+    let config = sandbox::load_config()?;
+    let mut shell = sandbox::build_shell(&config, &root, &workdir, &[])?;
+    shell.env("TERM", "dumb");
+    let pair = native_pty_system()
+        .openpty(TERMINAL_SIZE)
+        .context("Open a terminal for the shell session")?;
+    let child = pair
+        .slave
+        .spawn_command(shell)
+        .context("Start shell session")?;
+    drop(pair.slave);
+    let (lines_tx, output) = mpsc::unbounded_channel();
+    pump(pair.master.try_clone_reader()?, lines_tx);
+    let input = pair.master.take_writer()?;
+
+    let id = NEXT_SESSION.fetch_add(1, Ordering::Relaxed);
+    let mut session = Session {
+        id,
+        child: Some(child),
+        _terminal: pair.master,
+        input,
+        output,
+        scrollback: VecDeque::new(),
+        cwd: workdir.clone(),
+        commands: 0,
+        last_used: Instant::now(),
+    };
+    // Silence echo and the prompt, then drop everything printed before the
+    // settings took hold.
+    let marker = end_marker(id, 0);
+    session.write(&format!(
+        "stty -echo 2>/dev/null; PS1=''; PS2=''; printf '\\n{marker} 0 %s\\n' \"$PWD\"\n"
+    ))?;
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(SETUP_TIMEOUT_SECS);
+    loop {
+        match tokio::time::timeout_at(deadline, session.output.recv()).await {
+            Ok(Some(line)) if parse_marker(&line, &marker).is_some() => break,
+            Ok(Some(_)) => {}
+            Ok(None) => bail!("The shell exited while starting"),
+            Err(_) => bail!("The shell did not start within {SETUP_TIMEOUT_SECS}s"),
+        }
+    }
+    let session = Arc::new(tokio::sync::Mutex::new(session));
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(window_id, session);
+    watch_idle(window_id, id, tx.clone());
+
+    Ok(json!({
+        "status": "started",
+        "cwd": to_workspace_relative(&workdir, &root),
+        "idle_timeout_seconds": SHELL_IDLE_TIMEOUT_SECS,
+    }))
+}
+
+/// Close session `id` of `window_id` once it sits idle too long.
+fn watch_idle(window_id: usize, id: usize, tx: mpsc::UnboundedSender<AgentEvent>) {
+    let idle_limit = Duration::from_secs(SHELL_IDLE_TIMEOUT_SECS);
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(idle_limit / 10).await;
+            let Some(shared) = session_for(window_id) else {
+                return;
+            };
+            // A session busy running a command is not idle.
+            let Ok(session) = shared.try_lock() else {
+                continue;
+            };
+            if session.id != id {
+                return;
+            }
+            if session.last_used.elapsed() >= idle_limit {
+                drop(session);
+                close_window(window_id);
+                let _ = tx.send(AgentEvent::Progress {
+                    window_id,
+                    line: format!("Shell session closed after {SHELL_IDLE_TIMEOUT_SECS}s idle."),
+                });
+                return;
+            }
+        }
+    });
+}
+
+/// `printf` line that ends a command's output: marker, exit code, `$PWD`.
+fn end_marker(id: usize, nonce: usize) -> String {
+    format!("__MEMINI_SHELL_{id}_{nonce}__")
+}
+
+/// Exit code and working directory from a marker line.
+fn parse_marker(line: &str, marker: &str) -> Option<(Option<i32>, PathBuf)> {
+    let rest = line.strip_prefix(marker)?.strip_prefix(' ')?;
+    let (code, cwd) = rest.split_once(' ').unwrap_or((rest, ""));
+    Some((code.parse().ok(), PathBuf::from(cwd)))
+}
+
+async fn exec(
+    window_id: usize,
+    args: &Value,
+    tx: &mpsc::UnboundedSender<AgentEvent>,
+) -> Result<Value> {
+    let command = args
+        .get("command")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|command| !command.is_empty())
+        .ok_or_else(|| anyhow!("command is required"))?;
+    let timeout_seconds = args
+        .get("timeout_seconds")
+        .and_then(Value::as_u64)
+        .unwrap_or(60)
+        .clamp(1, MAX_COMMAND_TIMEOUT_SECS);
+    sandbox::load_config()?.check_command(command)?;
+
+    if session_for(window_id).is_none() {
+        start(window_id, &json!({}), tx).await?;
+    }
+    let shared = session_for(window_id).context("shell session closed")?;
+    let mut session = shared.lock().await;
+    session.commands += 1;
+    let marker = end_marker(session.id, session.commands);
+    let script = format!("{command}\nprintf '\\n{marker} %s %s\\n' \"$?\" \"$PWD\"\n");
+    session.write(&script)?;
+
+    let root = workspace_root();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(timeout_seconds);
+    let mut lines = Vec::new();
+    let mut timed_out = false;
+    let ending = loop {
+        match tokio::time::timeout_at(deadline, session.output.recv()).await {
+            Ok(Some(line)) => match parse_marker(&line, &marker) {
+                Some(end) => break Some(end),
+                None => {
+                    session.remember(&line);
+                    lines.push(line);
+                }
+            },
+            Ok(None) => break None,
+            Err(_) => {
+                timed_out = true;
+                break None;
+            }
+        }
+    };
+    session.last_used = Instant::now();
+    // The marker's leading newline leaves one empty line behind.
+    if lines.last().is_some_and(|line| line.is_empty()) {
+        lines.pop();
+        session.scrollback.pop_back();
+    }
+    let output = trim_chars(&lines.join("\n"), MAX_OUTPUT_CHARS);
+
+    match ending {
+        Some((exit_code, cwd)) => {
+            if !cwd.as_os_str().is_empty() {
+                session.cwd = cwd;
+            }
+            Ok(json!({
+                "command": command,
+                "exit_code": exit_code,
+                "success": exit_code == Some(0),
+                "cwd": to_workspace_relative(&session.cwd, &root),
+                "output": output,
+            }))
+        }
+        None => {
+            drop(session);
+            close_window(window_id);
+            let note = if timed_out {
+                format!("Command timed out after {timeout_seconds}s; the shell session was closed.")
+            } else {
+                "The shell exited; the session was closed.".to_string()
+            };
+            Ok(json!({
+                "command": command,
+                "timed_out": timed_out,
+                "exited": !timed_out,
+                "exit_code": Value::Null,
+                "output": output,
+                "note": note,
+            }))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn marker_lines_carry_exit_code_and_cwd() {
+        let marker = end_marker(3, 7);
+        assert_eq!(marker, "__MEMINI_SHELL_3_7__");
+        assert_eq!(
+            parse_marker("__MEMINI_SHELL_3_7__ 2 /ws/my dir", &marker),
+            Some((Some(2), PathBuf::from("/ws/my dir")))
+        );
+        assert_eq!(parse_marker("__MEMINI_SHELL_3_6__ 0 /ws", &marker), None);
+        assert_eq!(parse_marker("echo __MEMINI_SHELL_3_7__", &marker), None);
+    }
+}
@@ -0,0 +1,385 @@
+//! Graceful shutdown: cancel background work at safe points and keep
+//! unfinished agent windows for `/spawn resume`.
+//!
+//! Quitting raises a process-wide flag.  Daemon loops stop sleeping, and
+//! chat, daemon and agent tool loops stop before their next model call,
+//! then commit what they have to Rice as usual.  [`App::begin_shutdown`] waits
+//! (bounded by [`SHUTDOWN_DRAIN_SECS`]) for tracked tasks to finish, then
+//! saves windows that were still working or waiting for input to a local
+//! JSON file.
+//!
+//! [`App::begin_shutdown`]: super::App::begin_shutdown
+//! [`SHUTDOWN_DRAIN_SECS`]: crate::constants::SHUTDOWN_DRAIN_SECS
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::sync::Notify;
+
+use crate::constants::{APP_NAME, SHUTDOWN_DRAIN_SECS};
+
+use super::App;
+use super::daemon::{AgentWindow, AgentWindowStatus};
+use super::log_src;
+use super::logging::LogLevel;
+use super::store::persist_local_mcp_store;
+
+/// Output lines kept per saved window, as context for the resumed run.
+const SAVED_OUTPUT_LINES: usize = 40;
+
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+fn notify() -> &'static Notify {
+    static NOTIFY: OnceLock<Notify> = OnceLock::new();
+    NOTIFY.get_or_init(Notify::new)
+}
+
+/// Ask every background task to stop at its next safe point.
+pub fn request() {
+    REQUESTED.store(true, Ordering::SeqCst);
+    notify().notify_waiters();
+}
+
+/// Whether shutdown has been requested.
+pub fn requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
+
+/// Resolves once shutdown is requested.
+pub async fn wait() {
+    let notified = notify().notified();
+    if requested() {
+        return;
+    }
+    notified.await;
+}
+
+/// Marks a task as running until dropped; shutdown waits for these.
+pub struct Running(());
+
+impl Drop for Running {
+    fn drop(&mut self) {
+        RUNNING.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+pub fn track() -> Running {
+    RUNNING.fetch_add(1, Ordering::SeqCst);
+    Running(())
+}
+
+/// Tasks still running.
+pub fn running() -> usize {
+    RUNNING.load(Ordering::SeqCst)
+}
+
+// ── Unfinished agent windows ─────────────────────────────────────────
+
+/// An agent window that was still working (or waiting for input) at exit.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedWindow {
+    pub label: String,
+    pub prompt: String,
+    pub persona: String,
+    #[serde(default)]
+    pub skill_context: String,
+    #[serde(default)]
+    pub coordination_key: String,
+    /// Tool allowlist inherited from the spawning persona.
+    #[serde(default)]
+    pub tools: Vec<String>,
+    /// MCP server allowlist inherited from the spawning persona.
+    #[serde(default)]
+    pub mcp_servers: Vec<String>,
+    /// MCP servers the window was using; reconnected by id on resume (no
+    /// credentials are saved).
+    #[serde(default)]
+    pub mcp_ids: Vec<String>,
+    /// Set when the window was waiting for a reply rather than working.
+    #[serde(default)]
+    pub pending_question: Option<String>,
+    /// Tail of the window's output.
+    #[serde(default)]
+    pub output_lines: Vec<String>,
+    /// Conversation so far, so a resumed window keeps its context.
+    #[serde(default)]
+    pub thread: Vec<Value>,
+    pub saved_at: String,
+}
+
+impl SavedWindow {
+    /// Keep only the last [`SAVED_OUTPUT_LINES`] output lines.
+    pub fn trim_output(mut self) -> Self {
+        let skip = self.output_lines.len().saturating_sub(SAVED_OUTPUT_LINES);
+        self.output_lines.drain(..skip);
+        self
+    }
+
+    /// Prompt for re-running a window that was cut off mid-task.
+    pub fn resume_prompt(&self) -> String {
+        if self.output_lines.is_empty() {
+            return self.prompt.clone();
+        }
+        format!(
+            "{}\n\nYou were interrupted by an application restart before finishing. Your output so far:\n{}\n\nContinue from where you stopped; do not redo finished steps.",
+            self.prompt,
+            self.output_lines.join("\n")
+        )
+    }
+}
+
+fn windows_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME)
+        .map(|dirs| dirs.config_dir().join("unfinished_agents.json"))
+}
+
+/// Windows saved at the last exit.
+pub fn load_saved_windows() -> Vec<SavedWindow> {
+    windows_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+/// Replace the saved windows (an empty list removes the file).
+pub fn persist_saved_windows(windows: &[SavedWindow]) -> Result<()> {
+    let Some(path) = windows_path() else {
+        return Ok(());
+    };
+    if windows.is_empty() {
+        if path.exists() {
+            fs::remove_file(&path).context("remove unfinished agents")?;
+        }
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create config dir")?;
+    }
+    let contents = serde_json::to_string_pretty(windows).context("serialize unfinished agents")?;
+    fs::write(&path, contents).context("write unfinished agents")?;
+    Ok(())
+}
+
+// ── App shutdown & resume ────────────────────────────────────────────
+
+impl App {
+    /// Start shutting down: signal background work and set the drain
+    /// deadline.  Call [`App::shutdown_drained`] until it returns true.
+    pub fn begin_shutdown(&mut self) {
+        request();
+        self.shutdown_deadline = Some(Instant::now() + Duration::from_secs(SHUTDOWN_DRAIN_SECS));
+        let count = running();
+        if count > 0 {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "Shutting down — letting {count} running task(s) reach a safe point (up to {SHUTDOWN_DRAIN_SECS}s; press Esc again to quit now)…"
+                ),
+            );
+        }
+    }
+
+    /// Route events from finishing tasks; true once nothing is running or
+    /// the drain deadline has passed.
+    pub fn shutdown_drained(&mut self) -> bool {
+        self.drain_daemon_events();
+        running() == 0
+            || self
+                .shutdown_deadline
+                .is_none_or(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Save what is left: unfinished agent windows, the conversation
+    /// thread and the local MCP store.  Stops any remaining daemons and
+    /// kills agents' background processes.
+    pub fn finish_shutdown(&mut self) {
+        self.drain_daemon_events();
+        // Windows saved last time and never resumed are kept.
+        let mut saved = load_saved_windows();
+        saved.extend(
+            self.agent_windows
+                .iter()
+                .filter(|window| window.status.is_open())
+                // A checkpoint's run does not outlive memini.
+                .filter(|window| !self.checkpoint_windows.contains_key(&window.id))
+                .map(|window| saved_window(window).trim_output()),
+        );
+        if let Err(err) = persist_saved_windows(&saved) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Unfinished agents not saved: {err:#}")
+            );
+        }
+        self.save_conversation_thread();
+        let _ = persist_local_mcp_store(&self.local_mcp_store);
+        for handle in self.daemon_handles.drain(..) {
+            handle.abort.abort();
+        }
+        super::processes::kill_all();
+        super::worktrees::discard_all();
+        crate::telemetry::shutdown();
+    }
+
+    /// Mention windows saved at the last exit (called from bootstrap).
+    pub(crate) fn announce_saved_windows(&mut self) {
+        let count = load_saved_windows().len();
+        if count > 0 {
+            self.log(
+                LogLevel::Info,
+                format!(
+                    "{count} agent window(s) were unfinished at last exit. /spawn resume restarts them."
+                ),
+            );
+        }
+    }
+
+    /// `/spawn resume`: reopen windows saved at the last exit.  Windows that
+    /// were working run again with their earlier output as context; windows
+    /// that were waiting for a reply wait again.
+    pub(crate) fn resume_saved_windows(&mut self) {
+        let saved = load_saved_windows();
+        if saved.is_empty() {
+            self.log(
+                LogLevel::Info,
+                "No unfinished agent windows to resume.".to_string(),
+            );
+            return;
+        }
+        if let Err(err) = persist_saved_windows(&[]) {
+            log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Could not clear saved agents: {err:#}")
+            );
+        }
+
+        for window in saved {
+            let window_id = self.next_window_id.fetch_add(1, Ordering::SeqCst);
+            let mcp_snapshots = self
+                .build_mcp_snapshots(None)
+                .into_iter()
+                .filter(|snap| window.mcp_ids.contains(&snap.server.id))
+                .collect::<Vec<_>>();
+            if mcp_snapshots.len() < window.mcp_ids.len() {
+                log_src!(
+                    self,
+                    LogLevel::Warn,
+                    format!(
+                        "{}: not every MCP server it used is connected; resuming without the missing ones.",
+                        window.label
+                    )
+                );
+            }
+            let mut output_lines = window.output_lines.clone();
+            output_lines.push(format!("-- resumed (saved {}) --", window.saved_at));
+            let status = if window.pending_question.is_some() {
+                AgentWindowStatus::WaitingForInput
+            } else {
+                AgentWindowStatus::Thinking
+            };
+            self.agent_windows.push(AgentWindow {
+                output_lines,
+                pending_question: window.pending_question.clone(),
+                persona: window.persona.clone(),
+                skill_context: window.skill_context.clone(),
+                mcp_snapshots,
+                coordination_key: window.coordination_key.clone(),
+                restrictions: super::agents::ToolRestrictions {
+                    tools: window.tools.clone(),
+                    mcp_servers: window.mcp_servers.clone(),
+                },
+                thread: window.thread.clone(),
+                ..AgentWindow::new(
+                    window_id,
+                    window.label.clone(),
+                    window.prompt.clone(),
+                    status,
+                )
+            });
+
+            match &window.pending_question {
+                Some(question) => {
+                    self.enqueue_waiting_window(window_id);
+                    self.log(
+                        LogLevel::Info,
+                        format!(
+                            "↻ {} (#{window_id}) is waiting for your reply: {question}",
+                            window.label
+                        ),
+                    );
+                }
+                None => {
+                    self.spawn_agent_window_run(window_id, window.resume_prompt(), None, false);
+                    self.log(
+                        LogLevel::Info,
+                        format!("↻ Resumed {} as #{window_id}.", window.label),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// What `/spawn resume` and snapshots keep of `window`.
+pub(super) fn saved_window(window: &AgentWindow) -> SavedWindow {
+    SavedWindow {
+        label: window.label.clone(),
+        prompt: window.prompt.clone(),
+        persona: window.persona.clone(),
+        skill_context: window.skill_context.clone(),
+        coordination_key: window.coordination_key.clone(),
+        tools: window.restrictions.tools.clone(),
+        mcp_servers: window.restrictions.mcp_servers.clone(),
+        mcp_ids: window
+            .mcp_snapshots
+            .iter()
+            .map(|snap| snap.server.id.clone())
+            .collect(),
+        pending_question: (window.status == AgentWindowStatus::WaitingForInput)
+            .then(|| window.pending_question.clone())
+            .flatten(),
+        output_lines: window.output_lines.clone(),
+        thread: window.thread.clone(),
+        saved_at: chrono::Local::now().to_rfc3339(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resume_prompt_carries_the_output_tail() {
+        let window = SavedWindow {
+            label: "Research".to_string(),
+            prompt: "Summarize the open issues.".to_string(),
+            persona: "memini".to_string(),
+            skill_context: String::new(),
+            coordination_key: String::new(),
+            tools: Vec::new(),
+            mcp_servers: Vec::new(),
+            mcp_ids: Vec::new(),
+            pending_question: None,
+            output_lines: (0..50).map(|n| format!("line {n}")).collect(),
+            thread: Vec::new(),
+            saved_at: "2026-10-16T12:00:00Z".to_string(),
+        }
+        .trim_output();
+
+        assert_eq!(window.output_lines.len(), SAVED_OUTPUT_LINES);
+        assert_eq!(window.output_lines[0], "line 10");
+        let prompt = window.resume_prompt();
+        assert!(prompt.starts_with("Summarize the open issues."));
+        assert!(prompt.contains("line 49"));
+        assert!(!prompt.contains("line 9\n"));
+    }
+}
@@ -0,0 +1,290 @@
+//! Outbound notification sinks: Slack and Discord incoming webhooks.
+//!
+//! Each sink is a webhook URL in `memini.toml` with the events it receives:
+//!
+//! ```toml
+//! [sinks.team]
+//! url = "https://hooks.slack.com/services/T000/B000/XXXX"
+//! events = ["daemon", "waiting", "digest"]  # the default
+//!
+//! [sinks.ops]
+//! url = "https://discord.com/api/webhooks/123/abc"
+//! kind = "discord"  # optional; guessed from the URL
+//! events = ["waiting"]
+//! ```
+//!
+//! A recipe's `notify:` front matter overrides the `daemon` event for its
+//! own runs: `notify: ops` posts them only to `ops`, whatever its `events`,
+//! and `notify: none` posts them nowhere.  Posts run off the UI thread and
+//! a failing sink only logs a warning.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+
+use crate::constants::{DISCORD_MESSAGE_CHARS, SINK_POST_TIMEOUT_SECS, SLACK_MESSAGE_CHARS};
+
+use super::App;
+use super::daemon::{AgentEvent, ChatLogLevel};
+
+/// Events a sink can receive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkEvent {
+    /// A background daemon finished a run.
+    Daemon,
+    /// An agent window is waiting for input or a tool call for approval.
+    Waiting,
+    /// A `/digest` (or the built-in `digest` task) was written.
+    Digest,
+}
+
+impl SinkEvent {
+    pub fn label(self) -> &'static str {
+        match self {
+            SinkEvent::Daemon => "daemon",
+            SinkEvent::Waiting => "waiting",
+            SinkEvent::Digest => "digest",
+        }
+    }
+}
+
+/// Message format a webhook expects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    Slack,
+    Discord,
+}
+
+impl SinkKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            SinkKind::Slack => "slack",
+            SinkKind::Discord => "discord",
+        }
+    }
+}
+
+fn all_events() -> Vec<SinkEvent> {
+    vec![SinkEvent::Daemon, SinkEvent::Waiting, SinkEvent::Digest]
+}
+
+/// One `[sinks.<name>]` table.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct Sink {
+    pub url: String,
+    #[serde(default)]
+    pub kind: Option<SinkKind>,
+    #[serde(default = "all_events")]
+    pub events: Vec<SinkEvent>,
+}
+
+impl Sink {
+    /// The configured `kind`, else the one the URL belongs to.
+    pub fn kind(&self) -> Option<SinkKind> {
+        self.kind.or_else(|| {
+            let url = self.url.to_ascii_lowercase();
+            if url.contains("hooks.slack.com") {
+                Some(SinkKind::Slack)
+            } else if url.contains("discord.com/api/webhooks")
+                || url.contains("discordapp.com/api/webhooks")
+            {
+                Some(SinkKind::Discord)
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Host of the webhook URL; the path holds its secret.
+    pub fn host(&self) -> String {
+        reqwest::Url::parse(&self.url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .unwrap_or_else(|| "invalid url".to_string())
+    }
+}
+
+/// The `[sinks]` section of `memini.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+#[serde(transparent)]
+pub(crate) struct SinksConfig {
+    pub sinks: BTreeMap<String, Sink>,
+}
+
+impl SinksConfig {
+    /// Sinks that receive `event`.  A non-empty `notify` (a recipe's
+    /// override) names them instead; `none` names no sink.
+    pub fn targets(&self, event: SinkEvent, notify: &[String]) -> Vec<(&str, &Sink)> {
+        if notify.iter().any(|name| name.eq_ignore_ascii_case("none")) {
+            return Vec::new();
+        }
+        self.sinks
+            .iter()
+            .filter(|(name, sink)| {
+                if notify.is_empty() {
+                    sink.events.contains(&event)
+                } else {
+                    notify
+                        .iter()
+                        .any(|wanted| wanted.eq_ignore_ascii_case(name))
+                }
+            })
+            .map(|(name, sink)| (name.as_str(), sink))
+            .collect()
+    }
+}
+
+/// The `[sinks]` section of `memini.toml`.
+pub(crate) fn config() -> &'static SinksConfig {
+    &crate::config::get().sinks
+}
+
+fn clip(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// JSON body for one webhook post.
+fn payload(kind: SinkKind, title: &str, body: &str) -> Value {
+    match kind {
+        SinkKind::Slack => json!({
+            "text": clip(&format!("*{title}*\n{body}"), SLACK_MESSAGE_CHARS),
+        }),
+        SinkKind::Discord => json!({
+            "content": clip(&format!("**{title}**\n{body}"), DISCORD_MESSAGE_CHARS),
+        }),
+    }
+}
+
+async fn post(client: &reqwest::Client, sink: &Sink, title: &str, body: &str) -> Result<()> {
+    let Some(kind) = sink.kind() else {
+        bail!(
+            "can't tell Slack from Discord for {}; set `kind`",
+            sink.host()
+        );
+    };
+    let response = client
+        .post(&sink.url)
+        .json(&payload(kind, title, body))
+        .send()
+        .await
+        .with_context(|| format!("post to {}", sink.host()))?;
+    let status = response.status();
+    if !status.is_success() {
+        bail!("{} answered HTTP {status}", sink.host());
+    }
+    Ok(())
+}
+
+/// Post `title` and `body` to every sink that receives `event`.  Returns
+/// one message per sink that failed.
+pub(crate) async fn publish(
+    event: SinkEvent,
+    notify: &[String],
+    title: &str,
+    body: &str,
+) -> Vec<String> {
+    let targets = config().targets(event, notify);
+    if targets.is_empty() {
+        return Vec::new();
+    }
+    let client = match reqwest::Client::builder()
+        .timeout(Duration::from_secs(SINK_POST_TIMEOUT_SECS))
+        .build()
+    {
+        Ok(client) => client,
+        Err(err) => return vec![format!("Notification sinks: {err}")],
+    };
+    let mut failures = Vec::new();
+    for (name, sink) in targets {
+        if let Err(err) = post(&client, sink, title, body).await {
+            failures.push(format!("Sink '{name}' failed: {err:#}"));
+        }
+    }
+    failures
+}
+
+impl App {
+    /// Post to the sinks that receive `event` without blocking the UI;
+    /// failures come back as chat warnings.
+    pub(crate) fn post_to_sinks(
+        &self,
+        event: SinkEvent,
+        notify: Vec<String>,
+        title: String,
+        body: String,
+    ) {
+        if config().targets(event, &notify).is_empty() {
+            return;
+        }
+        let tx = self.daemon_tx.clone();
+        self.runtime.spawn(async move {
+            for line in publish(event, &notify, &title, &body).await {
+                let _ = tx.send(AgentEvent::ChatProgress {
+                    line,
+                    level: ChatLogLevel::Warn,
+                });
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    const TOML: &str = r#"
+[sinks.team]
+url = "https://hooks.slack.com/services/T0/B0/secret"
+
+[sinks.ops]
+url = "https://discord.com/api/webhooks/1/secret"
+events = ["waiting"]
+"#;
+
+    #[test]
+    fn events_and_recipe_overrides_pick_the_sinks() {
+        let config = MeminiToml::parse(TOML).expect("parse").sinks;
+        let names = |targets: Vec<(&str, &Sink)>| {
+            targets
+                .into_iter()
+                .map(|(name, _)| name.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names(config.targets(SinkEvent::Daemon, &[])), vec!["team"]);
+        assert_eq!(
+            names(config.targets(SinkEvent::Waiting, &[])),
+            vec!["ops", "team"]
+        );
+        assert_eq!(
+            names(config.targets(SinkEvent::Daemon, &["ops".to_string()])),
+            vec!["ops"]
+        );
+        assert!(
+            config
+                .targets(SinkEvent::Daemon, &["none".to_string()])
+                .is_empty()
+        );
+        assert_eq!(config.sinks["ops"].kind(), Some(SinkKind::Discord));
+        assert_eq!(config.sinks["team"].host(), "hooks.slack.com");
+    }
+
+    #[test]
+    fn discord_messages_fit_its_limit() {
+        let body = "x".repeat(5_000);
+        let value = payload(SinkKind::Discord, "repo-watch finished", &body);
+        let content = value["content"].as_str().expect("content");
+        assert!(content.starts_with("**repo-watch finished**\n"));
+        assert!(content.chars().count() <= DISCORD_MESSAGE_CHARS + 1);
+        assert!(payload(SinkKind::Slack, "t", "b")["text"].is_string());
+    }
+}
@@ -0,0 +1,312 @@
+//! Whole-app snapshots: [`App::snapshot`] and [`App::restore`], written to
+//! one JSON file by `/snapshot save <file>` and loaded by `/snapshot load`
+//! or `memini --load-snapshot <file>`.
+//!
+//! A snapshot holds the conversation thread, the agent windows (output,
+//! threads and reasoning included), the daemon task definitions and
+//! daemon results, and the persona, theme, notification and memory-limit
+//! settings.  It exists to reproduce a state exactly — in integration
+//! tests, demos and bug reports — so restoring starts nothing: windows
+//! that were working come back as killed, daemons come back paused, and
+//! nothing is written to Rice.  MCP connections and secrets are not part
+//! of a snapshot.
+
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::Ordering;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::agents::{Agent, ToolRestrictions};
+use super::daemon::{AgentWindow, AgentWindowStatus, DaemonTaskDef};
+use super::log_src;
+use super::logging::LogLevel;
+use super::notifications::NotifySettings;
+use super::processes;
+use super::shell_sessions;
+use super::shutdown::{SavedWindow, saved_window};
+use super::theme;
+use super::{App, ViewMode};
+
+/// Format version written to every snapshot; newer files are refused.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Everything [`App::restore`] needs to rebuild a state.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AppSnapshot {
+    pub version: u32,
+    pub saved_at: String,
+    #[serde(default)]
+    pub thread: Vec<Value>,
+    #[serde(default)]
+    pub windows: Vec<WindowSnapshot>,
+    #[serde(default)]
+    pub daemons: Vec<DaemonTaskDef>,
+    /// `(task, message, timestamp)` of finished daemon runs.
+    #[serde(default)]
+    pub daemon_results: Vec<(String, String, String)>,
+    #[serde(default)]
+    pub settings: SnapshotSettings,
+}
+
+/// An agent window with its id and status; the rest is what `/spawn
+/// resume` saves, with the full output instead of its tail.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct WindowSnapshot {
+    pub id: usize,
+    pub status: AgentWindowStatus,
+    #[serde(default)]
+    pub reasoning: Vec<String>,
+    #[serde(flatten)]
+    pub window: SavedWindow,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotSettings {
+    /// Active persona name (`memini` for the default).
+    #[serde(default)]
+    pub persona: String,
+    #[serde(default)]
+    pub custom_agents: Vec<Agent>,
+    #[serde(default)]
+    pub theme: Option<String>,
+    #[serde(default)]
+    pub notify: NotifySettings,
+    #[serde(default)]
+    pub memory_limit: Option<u64>,
+    #[serde(default)]
+    pub show_side_panel: bool,
+}
+
+/// Write `snapshot` to `path` as pretty JSON.
+pub fn write(path: &Path, snapshot: &AppSnapshot) -> Result<()> {
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent).context("create snapshot directory")?;
+    }
+    let contents = serde_json::to_string_pretty(snapshot).context("serialize snapshot")?;
+    fs::write(path, contents).with_context(|| format!("write {}", path.display()))
+}
+
+/// Read a snapshot written by [`write`].
+pub fn read(path: &Path) -> Result<AppSnapshot> {
+    let raw = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    let snapshot: AppSnapshot = serde_json::from_str(&raw)
+        .with_context(|| format!("{} is not a memini snapshot", path.display()))?;
+    if snapshot.version > SNAPSHOT_VERSION {
+        bail!(
+            "{} is snapshot version {}; this memini reads up to {SNAPSHOT_VERSION}",
+            path.display(),
+            snapshot.version
+        );
+    }
+    Ok(snapshot)
+}
+
+impl App {
+    /// Capture the current state.
+    pub fn snapshot(&self) -> AppSnapshot {
+        AppSnapshot {
+            version: SNAPSHOT_VERSION,
+            saved_at: chrono::Local::now().to_rfc3339(),
+            thread: self.conversation_thread.clone(),
+            windows: self
+                .agent_windows
+                .iter()
+                .map(|window| WindowSnapshot {
+                    id: window.id,
+                    status: window.status.clone(),
+                    reasoning: window.reasoning.clone(),
+                    window: saved_window(window),
+                })
+                .collect(),
+            daemons: self
+                .daemon_handles
+                .iter()
+                .map(|handle| handle.def.clone())
+                .collect(),
+            daemon_results: self.daemon_results.clone(),
+            settings: SnapshotSettings {
+                persona: self.active_agent.name.clone(),
+                custom_agents: self.custom_agents.clone(),
+                theme: Some(theme::current().name.clone()),
+                notify: self.notify_settings.clone(),
+                memory_limit: Some(self.memory_limit),
+                show_side_panel: self.show_side_panel,
+            },
+        }
+    }
+
+    /// Replace the current state with `snapshot`.  Refused while a chat
+    /// turn or an agent window run is in flight.
+    pub fn restore(&mut self, snapshot: AppSnapshot) -> Result<()> {
+        if self.chat_busy {
+            bail!("a chat turn is running; wait for it to finish");
+        }
+        if self.window_active_runs.values().any(|runs| *runs > 0) {
+            bail!("agent windows are running; wait for them or /kill them first");
+        }
+
+        let settings = snapshot.settings;
+        let theme = settings.theme.as_deref().map(theme::resolve).transpose()?;
+        self.custom_agents = settings.custom_agents;
+        self.active_agent = self
+            .custom_agents
+            .iter()
+            .find(|agent| agent.name == settings.persona)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(theme) = theme {
+            theme::set(theme);
+        }
+        self.notify_settings = settings.notify;
+        if let Some(limit) = settings.memory_limit {
+            self.memory_limit = limit;
+        }
+        self.show_side_panel = settings.show_side_panel;
+
+        self.conversation_thread = snapshot.thread;
+        self.daemon_results = snapshot.daemon_results;
+
+        for handle in self.daemon_handles.drain(..) {
+            handle.abort.abort();
+        }
+        for mut def in snapshot.daemons {
+            def.paused = true;
+            self.spawn_daemon_task(def);
+        }
+
+        for window in self.agent_windows.drain(..) {
+            shell_sessions::close_window(window.id);
+            processes::close_window(window.id);
+        }
+        self.pending_input_queue.clear();
+        self.view_mode = ViewMode::Dashboard;
+        self.focused_window = None;
+        self.split_window = None;
+        self.output_view = None;
+        self.grid_selected = 0;
+        let mut next_id = 1;
+        for saved in snapshot.windows {
+            next_id = next_id.max(saved.id + 1);
+            let window = restored_window(saved);
+            let waiting = window.status == AgentWindowStatus::WaitingForInput;
+            let window_id = window.id;
+            self.agent_windows.push(window);
+            if waiting {
+                self.enqueue_waiting_window(window_id);
+            }
+        }
+        self.next_window_id.store(next_id, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Restore `snapshot`, logging what came back or why it could not.
+    pub fn load_snapshot(&mut self, snapshot: AppSnapshot) {
+        let saved_at = snapshot.saved_at.clone();
+        let (windows, daemons) = (snapshot.windows.len(), snapshot.daemons.len());
+        match self.restore(snapshot) {
+            Ok(()) => self.log(
+                LogLevel::Info,
+                format!(
+                    "Restored snapshot from {saved_at}: {windows} agent window(s), {daemons} task(s) (paused)."
+                ),
+            ),
+            Err(err) => log_src!(
+                self,
+                LogLevel::Warn,
+                format!("Snapshot not restored: {err:#}")
+            ),
+        }
+    }
+}
+
+/// An idle [`AgentWindow`] for `saved`; a window that was working is
+/// marked killed, since its run is not resumed.
+fn restored_window(saved: WindowSnapshot) -> AgentWindow {
+    let window = saved.window;
+    let mut output_lines = window.output_lines;
+    let status = match saved.status {
+        AgentWindowStatus::Thinking => {
+            output_lines.push("-- restored from snapshot; the run was not resumed --".to_string());
+            AgentWindowStatus::Killed
+        }
+        status => status,
+    };
+    AgentWindow {
+        output_lines,
+        pending_question: window.pending_question,
+        persona: window.persona,
+        skill_context: window.skill_context,
+        coordination_key: window.coordination_key,
+        restrictions: ToolRestrictions {
+            tools: window.tools,
+            mcp_servers: window.mcp_servers,
+        },
+        thread: window.thread,
+        reasoning: saved.reasoning,
+        ..AgentWindow::new(saved.id, window.label, window.prompt, status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::super::harness::Harness;
+    use super::*;
+
+    #[test]
+    fn snapshots_round_trip_through_a_file() {
+        let mut harness = Harness::new();
+        harness.mocks.llm.reply_text("Noted.");
+        harness.submit("Remember the launch date.");
+        let mut window = restored_window(WindowSnapshot {
+            id: 4,
+            status: AgentWindowStatus::WaitingForInput,
+            reasoning: vec!["Need the date.".to_string()],
+            window: SavedWindow {
+                label: "planner".to_string(),
+                prompt: "Plan the launch".to_string(),
+                persona: "memini".to_string(),
+                skill_context: String::new(),
+                coordination_key: String::new(),
+                tools: Vec::new(),
+                mcp_servers: Vec::new(),
+                mcp_ids: Vec::new(),
+                pending_question: Some("Which date?".to_string()),
+                output_lines: vec!["Drafting…".to_string()],
+                thread: vec![json!({"role": "user", "content": "Plan the launch"})],
+                saved_at: String::new(),
+            },
+        });
+        harness.app.agent_windows.push(window.clone());
+        window.id = 5;
+        window.status = AgentWindowStatus::Thinking;
+        harness.app.agent_windows.push(window);
+
+        let path =
+            std::env::temp_dir().join(format!("memini-snapshot-{}.json", std::process::id()));
+        write(&path, &harness.app.snapshot()).expect("write snapshot");
+        let snapshot = read(&path).expect("read snapshot");
+        let _ = fs::remove_file(&path);
+
+        let mut restored = Harness::new();
+        restored.app.restore(snapshot).expect("restore");
+        assert_eq!(
+            restored.app.conversation_thread,
+            harness.app.conversation_thread
+        );
+        let windows = &restored.app.agent_windows;
+        assert_eq!(windows.len(), 2);
+        assert_eq!(windows[0].status, AgentWindowStatus::WaitingForInput);
+        assert_eq!(windows[0].reasoning, ["Need the date."]);
+        assert_eq!(windows[1].status, AgentWindowStatus::Killed);
+        assert_eq!(restored.app.pending_input_queue, [4]);
+        assert_eq!(restored.app.next_window_id.load(Ordering::SeqCst), 6);
+    }
+}
@@ -0,0 +1,228 @@
+//! Hand-off of sub-agent results back to the conversation that spawned
+//! them.
+//!
+//! Windows started by the chat's `spawn_agent` tool are tracked here.
+//! The main thread records each outcome as the window's `Finished` /
+//! `NeedsInput` event arrives.  The background chat task can block on
+//! one with the `wait_for_agent` tool.  Outcomes nobody waited for are
+//! injected into the parent thread as summaries once the chat is idle.
+
+use std::collections::HashMap;
+use std::pin::pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{Value, json};
+use tokio::sync::Notify;
+
+/// Longest output handed back to the parent LLM via `wait_for_agent`.
+const MAX_TOOL_OUTPUT_CHARS: usize = 6_000;
+/// Longest summary injected into the conversation thread.
+const MAX_SUMMARY_CHARS: usize = 1_500;
+
+/// Where a tracked sub-agent currently stands.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubAgentState {
+    Running,
+    Done,
+    NeedsInput,
+    /// Timed out or killed before finishing.
+    Stopped,
+}
+
+#[derive(Clone, Debug)]
+pub struct SubAgentOutcome {
+    pub label: String,
+    pub state: SubAgentState,
+    /// Final output (or the question asked, for `NeedsInput`).
+    pub output: String,
+    /// Returned to the parent through `wait_for_agent`.
+    pub collected: bool,
+    /// Added to the conversation thread as a summary.
+    pub injected: bool,
+}
+
+/// Shared registry of chat-spawned sub-agents.  Cheap to clone.
+#[derive(Clone, Debug, Default)]
+pub struct SubAgentResults {
+    entries: Arc<Mutex<HashMap<usize, SubAgentOutcome>>>,
+    changed: Arc<Notify>,
+}
+
+impl SubAgentResults {
+    /// Start tracking a window spawned by the chat.
+    pub fn track(&self, window_id: usize, label: &str) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(
+                window_id,
+                SubAgentOutcome {
+                    label: label.to_string(),
+                    state: SubAgentState::Running,
+                    output: String::new(),
+                    collected: false,
+                    injected: false,
+                },
+            );
+        }
+    }
+
+    /// Record a finished run.  Returns `false` for untracked windows.
+    pub fn record(&self, window_id: usize, state: SubAgentState, output: &str) -> bool {
+        let Ok(mut entries) = self.entries.lock() else {
+            return false;
+        };
+        let Some(entry) = entries.get_mut(&window_id) else {
+            return false;
+        };
+        entry.state = state;
+        entry.output = output.to_string();
+        entry.collected = false;
+        entry.injected = false;
+        drop(entries);
+        self.changed.notify_waiters();
+        true
+    }
+
+    /// Wait up to `timeout` for a tracked window to stop running.
+    pub async fn wait(&self, window_id: usize, timeout: Duration) -> Value {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let mut notified = pin!(self.changed.notified());
+            notified.as_mut().enable();
+            match self.try_collect(window_id) {
+                Some(result) => return result,
+                None if !self.is_tracked(window_id) => {
+                    return json!({
+                        "error": format!("Agent #{window_id} was not spawned by this conversation")
+                    });
+                }
+                None => {}
+            }
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                let label = self.label(window_id).unwrap_or_default();
+                return json!({
+                    "window_id": window_id,
+                    "label": label,
+                    "status": "running",
+                    "timed_out": true,
+                });
+            }
+        }
+    }
+
+    fn is_tracked(&self, window_id: usize) -> bool {
+        self.entries
+            .lock()
+            .map(|entries| entries.contains_key(&window_id))
+            .unwrap_or(false)
+    }
+
+    fn label(&self, window_id: usize) -> Option<String> {
+        let entries = self.entries.lock().ok()?;
+        entries.get(&window_id).map(|entry| entry.label.clone())
+    }
+
+    fn try_collect(&self, window_id: usize) -> Option<Value> {
+        let mut entries = self.entries.lock().ok()?;
+        let entry = entries.get_mut(&window_id)?;
+        let status = match entry.state {
+            SubAgentState::Running => return None,
+            SubAgentState::Done => "done",
+            SubAgentState::NeedsInput => "needs_input",
+            SubAgentState::Stopped => "stopped",
+        };
+        entry.collected = true;
+        Some(json!({
+            "window_id": window_id,
+            "label": entry.label,
+            "status": status,
+            "output": truncate(&entry.output, MAX_TOOL_OUTPUT_CHARS),
+        }))
+    }
+
+    /// Thread entries summarising finished runs nobody collected yet.
+    pub fn take_summaries(&self) -> Vec<(String, Value)> {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let mut ids: Vec<usize> = entries
+            .iter()
+            .filter(|(_, entry)| {
+                entry.state != SubAgentState::Running && !entry.collected && !entry.injected
+            })
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter()
+            .filter_map(|id| {
+                let entry = entries.get_mut(&id)?;
+                entry.injected = true;
+                let heading = match entry.state {
+                    SubAgentState::NeedsInput => "is waiting for input",
+                    SubAgentState::Stopped => "was stopped",
+                    _ => "finished",
+                };
+                let content = format!(
+                    "Sub-agent {} (#{id}) {heading}:\n{}",
+                    entry.label,
+                    truncate(&entry.output, MAX_SUMMARY_CHARS)
+                );
+                Some((
+                    entry.label.clone(),
+                    json!({ "role": "system", "content": content }),
+                ))
+            })
+            .collect()
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max_chars).collect();
+    out.push_str("\n… (truncated)");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn wait_returns_once_recorded() {
+        let results = SubAgentResults::default();
+        results.track(4, "Research");
+        let waiter = {
+            let results = results.clone();
+            tokio::spawn(async move { results.wait(4, Duration::from_secs(5)).await })
+        };
+        tokio::task::yield_now().await;
+        assert!(results.record(4, SubAgentState::Done, "found 3 papers"));
+        let value = waiter.await.expect("join");
+        assert_eq!(value["status"], "done");
+        assert_eq!(value["output"], "found 3 papers");
+        // Collected results are not injected again.
+        assert!(results.take_summaries().is_empty());
+    }
+
+    #[tokio::test]
+    async fn times_out_and_summarises_uncollected() {
+        let results = SubAgentResults::default();
+        results.track(2, "Review");
+        let value = results.wait(2, Duration::from_millis(10)).await;
+        assert_eq!(value["timed_out"], true);
+        assert!(!results.record(9, SubAgentState::Done, "untracked"));
+
+        results.record(2, SubAgentState::Done, "looks good");
+        let summaries = results.take_summaries();
+        assert_eq!(summaries.len(), 1);
+        assert!(
+            summaries[0].1["content"]
+                .as_str()
+                .unwrap()
+                .contains("looks good")
+        );
+        assert!(results.take_summaries().is_empty());
+    }
+}
@@ -0,0 +1,505 @@
+//! Color themes for the TUI — built-in `dark`, `light`, `high-contrast`
+//! and `basic`, plus a user-defined `theme.toml`.
+//!
+//! Colors are addressed by role (`primary`, `warning`, `muted`, …) rather
+//! than by value.  The active theme is process-global so renderers without
+//! access to [`App`](super::App) (log lines, markdown) can read it.
+//!
+//! In plain mode (`--plain`, `MEMINI_UI=basic`) [`set_basic_colors`] limits
+//! every theme to the 16 ANSI colors: RGB and 256-color values are mapped
+//! to the nearest one with [`ansi16`].
+//!
+//! `theme.toml` lives in `$MEMINI_HOME` (default `~/Memini`):
+//!
+//! ```toml
+//! base = "light"                 # built-in theme to start from
+//! code_theme = "InspiredGitHub"  # syntect theme for code blocks
+//! accents = ["#005f87", "#870087"]
+//!
+//! [colors]
+//! primary = "#005f87"
+//! muted = "gray"
+//! ```
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock, RwLock};
+
+use anyhow::{Context, Result, anyhow, bail};
+use ratatui::style::Color;
+use serde::Deserialize;
+
+/// Names accepted by `/theme`.
+pub const BUILTIN_THEMES: &[&str] = &["dark", "light", "high-contrast", "basic"];
+/// Name used for the theme loaded from `theme.toml`.
+pub const CUSTOM_THEME: &str = "custom";
+
+/// Role-based palette used by every renderer.
+#[derive(Clone, Debug)]
+pub struct Theme {
+    pub name: String,
+    /// Body text.
+    pub text: Color,
+    /// Key-help descriptions, separators, idle hints.
+    pub muted: Color,
+    /// Secondary text (previews, empty states).
+    pub subtle: Color,
+    /// Timestamps and rules.
+    pub dim: Color,
+    /// Key labels, live-agent chrome, list markers.
+    pub primary: Color,
+    pub success: Color,
+    pub warning: Color,
+    pub error: Color,
+    /// Agents waiting for input.
+    pub attention: Color,
+    /// Persona name, minor headings.
+    pub brand: Color,
+    /// `INFO` log level.
+    pub info: Color,
+    /// Markdown response labels.
+    pub label: Color,
+    pub border: Color,
+    pub link: Color,
+    pub code_fg: Color,
+    pub code_bg: Color,
+    pub selection_bg: Color,
+    pub match_fg: Color,
+    pub match_bg: Color,
+    /// Per-window accent colors (cards, session borders).
+    pub accents: Vec<Color>,
+    /// syntect theme for fenced code blocks.
+    pub code_theme: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme::dark()
+    }
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Theme {
+            name: "dark".to_string(),
+            text: Color::White,
+            muted: Color::Rgb(80, 80, 80),
+            subtle: Color::Rgb(120, 120, 120),
+            dim: Color::DarkGray,
+            primary: Color::Rgb(0, 210, 255),
+            success: Color::Rgb(0, 255, 136),
+            warning: Color::Yellow,
+            error: Color::Red,
+            attention: Color::Rgb(255, 105, 180),
+            brand: Color::Rgb(138, 43, 226),
+            info: Color::Cyan,
+            label: Color::Magenta,
+            border: Color::Rgb(60, 60, 60),
+            link: Color::Rgb(100, 149, 237),
+            code_fg: Color::Rgb(255, 165, 0),
+            code_bg: Color::Rgb(40, 40, 40),
+            selection_bg: Color::Rgb(60, 60, 110),
+            match_fg: Color::Black,
+            match_bg: Color::Yellow,
+            accents: vec![
+                Color::Rgb(0, 255, 136),   // green
+                Color::Rgb(0, 210, 255),   // cyan
+                Color::Rgb(138, 43, 226),  // purple
+                Color::Rgb(255, 165, 0),   // orange
+                Color::Rgb(255, 105, 180), // pink
+                Color::Rgb(64, 224, 208),  // turquoise
+                Color::Rgb(255, 215, 0),   // gold
+                Color::Rgb(100, 149, 237), // cornflower
+                Color::Rgb(220, 20, 60),   // crimson
+            ],
+            code_theme: "base16-ocean.dark".to_string(),
+        }
+    }
+
+    pub fn light() -> Self {
+        Theme {
+            name: "light".to_string(),
+            text: Color::Black,
+            muted: Color::Rgb(120, 120, 120),
+            subtle: Color::Rgb(90, 90, 90),
+            dim: Color::Rgb(140, 140, 140),
+            primary: Color::Rgb(0, 95, 135),
+            success: Color::Rgb(0, 120, 60),
+            warning: Color::Rgb(170, 90, 0),
+            error: Color::Rgb(180, 0, 0),
+            attention: Color::Rgb(175, 0, 95),
+            brand: Color::Rgb(95, 0, 175),
+            info: Color::Rgb(0, 95, 135),
+            label: Color::Rgb(135, 0, 135),
+            border: Color::Rgb(180, 180, 180),
+            link: Color::Rgb(0, 60, 190),
+            code_fg: Color::Rgb(150, 70, 0),
+            code_bg: Color::Rgb(235, 235, 235),
+            selection_bg: Color::Rgb(200, 215, 255),
+            match_fg: Color::Black,
+            match_bg: Color::Rgb(255, 215, 0),
+            accents: vec![
+                Color::Rgb(0, 120, 60),
+                Color::Rgb(0, 95, 135),
+                Color::Rgb(95, 0, 175),
+                Color::Rgb(170, 90, 0),
+                Color::Rgb(175, 0, 95),
+                Color::Rgb(0, 120, 120),
+                Color::Rgb(135, 110, 0),
+                Color::Rgb(0, 60, 190),
+                Color::Rgb(180, 0, 40),
+            ],
+            code_theme: "InspiredGitHub".to_string(),
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Theme {
+            name: "high-contrast".to_string(),
+            text: Color::White,
+            muted: Color::Gray,
+            subtle: Color::White,
+            dim: Color::Gray,
+            primary: Color::LightCyan,
+            success: Color::LightGreen,
+            warning: Color::LightYellow,
+            error: Color::LightRed,
+            attention: Color::LightMagenta,
+            brand: Color::LightBlue,
+            info: Color::LightCyan,
+            label: Color::LightMagenta,
+            border: Color::White,
+            link: Color::LightBlue,
+            code_fg: Color::LightYellow,
+            code_bg: Color::Black,
+            selection_bg: Color::Blue,
+            match_fg: Color::Black,
+            match_bg: Color::LightYellow,
+            accents: vec![
+                Color::LightGreen,
+                Color::LightCyan,
+                Color::LightMagenta,
+                Color::LightYellow,
+                Color::LightBlue,
+                Color::LightRed,
+                Color::White,
+            ],
+            code_theme: "base16-eighties.dark".to_string(),
+        }
+    }
+
+    /// 16 ANSI colors on the terminal's own background; the default in
+    /// plain mode.
+    pub fn basic() -> Self {
+        Theme {
+            name: "basic".to_string(),
+            text: Color::Reset,
+            muted: Color::DarkGray,
+            subtle: Color::Gray,
+            dim: Color::DarkGray,
+            primary: Color::Cyan,
+            success: Color::Green,
+            warning: Color::Yellow,
+            error: Color::Red,
+            attention: Color::Magenta,
+            brand: Color::Blue,
+            info: Color::Cyan,
+            label: Color::Magenta,
+            border: Color::DarkGray,
+            link: Color::Blue,
+            code_fg: Color::Yellow,
+            code_bg: Color::Reset,
+            selection_bg: Color::Blue,
+            match_fg: Color::Black,
+            match_bg: Color::Yellow,
+            accents: vec![
+                Color::Green,
+                Color::Cyan,
+                Color::Magenta,
+                Color::Yellow,
+                Color::Blue,
+                Color::Red,
+            ],
+            code_theme: "base16-ocean.dark".to_string(),
+        }
+    }
+
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Theme::dark()),
+            "light" => Some(Theme::light()),
+            "high-contrast" | "contrast" => Some(Theme::high_contrast()),
+            "basic" => Some(Theme::basic()),
+            _ => None,
+        }
+    }
+
+    /// This theme with every color mapped through [`ansi16`].
+    pub fn to_ansi16(mut self) -> Self {
+        for slot in [
+            &mut self.text,
+            &mut self.muted,
+            &mut self.subtle,
+            &mut self.dim,
+            &mut self.primary,
+            &mut self.success,
+            &mut self.warning,
+            &mut self.error,
+            &mut self.attention,
+            &mut self.brand,
+            &mut self.info,
+            &mut self.label,
+            &mut self.border,
+            &mut self.link,
+            &mut self.code_fg,
+            &mut self.code_bg,
+            &mut self.selection_bg,
+            &mut self.match_fg,
+            &mut self.match_bg,
+        ] {
+            *slot = ansi16(*slot);
+        }
+        self.accents = self.accents.into_iter().map(ansi16).collect();
+        self
+    }
+
+    /// Accent color for a window/card index.
+    pub fn accent(&self, idx: usize) -> Color {
+        if self.accents.is_empty() {
+            return self.primary;
+        }
+        self.accents[idx % self.accents.len()]
+    }
+
+    /// Parse a `theme.toml` document on top of its `base` theme.
+    pub fn from_toml(raw: &str) -> Result<Self> {
+        let file: ThemeFile = toml::from_str(raw).context("parse theme.toml")?;
+        let base = file.base.as_deref().unwrap_or("dark");
+        let mut theme =
+            Theme::builtin(base).ok_or_else(|| anyhow!("Unknown base theme '{base}'"))?;
+        theme.name = CUSTOM_THEME.to_string();
+        if let Some(code_theme) = file.code_theme {
+            theme.code_theme = code_theme;
+        }
+        if let Some(accents) = file.accents {
+            theme.accents = accents
+                .iter()
+                .map(|raw| parse_color(raw))
+                .collect::<Result<_>>()?;
+        }
+        for (role, raw) in &file.colors {
+            let color = parse_color(raw)?;
+            let slot = match role.as_str() {
+                "text" => &mut theme.text,
+                "muted" => &mut theme.muted,
+                "subtle" => &mut theme.subtle,
+                "dim" => &mut theme.dim,
+                "primary" => &mut theme.primary,
+                "success" => &mut theme.success,
+                "warning" => &mut theme.warning,
+                "error" => &mut theme.error,
+                "attention" => &mut theme.attention,
+                "brand" => &mut theme.brand,
+                "info" => &mut theme.info,
+                "label" => &mut theme.label,
+                "border" => &mut theme.border,
+                "link" => &mut theme.link,
+                "code_fg" => &mut theme.code_fg,
+                "code_bg" => &mut theme.code_bg,
+                "selection_bg" => &mut theme.selection_bg,
+                "match_fg" => &mut theme.match_fg,
+                "match_bg" => &mut theme.match_bg,
+                other => bail!("Unknown theme color role '{other}'"),
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ThemeFile {
+    base: Option<String>,
+    code_theme: Option<String>,
+    accents: Option<Vec<String>>,
+    #[serde(default)]
+    colors: HashMap<String, String>,
+}
+
+/// Accepts names (`red`, `lightblue`), `#rrggbb`, and ANSI indices.
+fn parse_color(raw: &str) -> Result<Color> {
+    Color::from_str(raw.trim()).map_err(|_| anyhow!("Invalid color '{raw}'"))
+}
+
+/// Path of the user theme file.
+pub fn theme_file_path() -> PathBuf {
+    crate::util::memini_home().join("theme.toml")
+}
+
+/// Load `theme.toml`.
+pub fn load_custom() -> Result<Theme> {
+    let path = theme_file_path();
+    let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+    Theme::from_toml(&raw)
+}
+
+/// Resolve a theme name (`custom` reads `theme.toml`).
+pub fn resolve(name: &str) -> Result<Theme> {
+    if name == CUSTOM_THEME {
+        return load_custom();
+    }
+    Theme::builtin(name).ok_or_else(|| {
+        anyhow!(
+            "Unknown theme '{name}'. Available: {}, {CUSTOM_THEME}",
+            BUILTIN_THEMES.join(", ")
+        )
+    })
+}
+
+// ── 16-color mapping ─────────────────────────────────────────────────
+
+/// The 16 ANSI colors with their xterm default values.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::Red, (128, 0, 0)),
+    (Color::Green, (0, 128, 0)),
+    (Color::Yellow, (128, 128, 0)),
+    (Color::Blue, (0, 0, 128)),
+    (Color::Magenta, (128, 0, 128)),
+    (Color::Cyan, (0, 128, 128)),
+    (Color::Gray, (192, 192, 192)),
+    (Color::DarkGray, (128, 128, 128)),
+    (Color::LightRed, (255, 0, 0)),
+    (Color::LightGreen, (0, 255, 0)),
+    (Color::LightYellow, (255, 255, 0)),
+    (Color::LightBlue, (0, 0, 255)),
+    (Color::LightMagenta, (255, 0, 255)),
+    (Color::LightCyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// Nearest ANSI color for RGB and 256-color values; named colors and
+/// `Reset` are returned unchanged.
+pub fn ansi16(color: Color) -> Color {
+    let (r, g, b) = match color {
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) if index < 16 => return ANSI16[index as usize].0,
+        Color::Indexed(index) if index >= 232 => {
+            let level = 8 + 10 * (index - 232);
+            (level, level, level)
+        }
+        Color::Indexed(index) => {
+            const LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+            let cube = index - 16;
+            (
+                LEVELS[(cube / 36) as usize],
+                LEVELS[(cube / 6 % 6) as usize],
+                LEVELS[(cube % 6) as usize],
+            )
+        }
+        other => return other,
+    };
+    let distance = |(cr, cg, cb): (u8, u8, u8)| {
+        [(r, cr), (g, cg), (b, cb)]
+            .iter()
+            .map(|&(x, y)| (x as i32 - y as i32).pow(2))
+            .sum::<i32>()
+    };
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance(*rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(color)
+}
+
+static BASIC_COLORS: AtomicBool = AtomicBool::new(false);
+
+/// Restrict the active theme, and every later one, to 16 colors.
+pub fn set_basic_colors(on: bool) {
+    BASIC_COLORS.store(on, Ordering::Relaxed);
+    set(current().as_ref().clone());
+}
+
+/// `color` as it should be drawn: mapped through [`ansi16`] in plain mode.
+pub fn fit(color: Color) -> Color {
+    if BASIC_COLORS.load(Ordering::Relaxed) {
+        ansi16(color)
+    } else {
+        color
+    }
+}
+
+// ── Active theme ─────────────────────────────────────────────────────
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn active() -> &'static RwLock<Arc<Theme>> {
+    static ACTIVE: OnceLock<RwLock<Arc<Theme>>> = OnceLock::new();
+    ACTIVE.get_or_init(|| RwLock::new(Arc::new(Theme::dark())))
+}
+
+/// The theme renderers should use right now.
+pub fn current() -> Arc<Theme> {
+    active()
+        .read()
+        .map(|theme| Arc::clone(&theme))
+        .unwrap_or_else(|_| Arc::new(Theme::dark()))
+}
+
+/// Replace the active theme.
+pub fn set(theme: Theme) {
+    let theme = if BASIC_COLORS.load(Ordering::Relaxed) {
+        theme.to_ansi16()
+    } else {
+        theme
+    };
+    if let Ok(mut slot) = active().write() {
+        *slot = Arc::new(theme);
+        GENERATION.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Bumped on every [`set`]; used to invalidate render caches.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn custom_theme_overrides_base() {
+        let theme = Theme::from_toml(
+            "base = \"light\"\naccents = [\"red\", \"#00ff00\"]\n[colors]\nprimary = \"#102030\"\n",
+        )
+        .expect("parse");
+        assert_eq!(theme.name, CUSTOM_THEME);
+        assert_eq!(theme.primary, Color::Rgb(16, 32, 48));
+        assert_eq!(theme.text, Color::Black);
+        assert_eq!(theme.accent(1), Color::Rgb(0, 255, 0));
+    }
+
+    #[test]
+    fn rejects_unknown_roles_and_bases() {
+        assert!(Theme::from_toml("[colors]\nsparkle = \"red\"\n").is_err());
+        assert!(Theme::from_toml("base = \"neon\"\n").is_err());
+        assert!(resolve("high-contrast").is_ok());
+        assert!(resolve("neon").is_err());
+    }
+
+    #[test]
+    fn maps_rich_colors_to_ansi16() {
+        assert_eq!(ansi16(Color::Rgb(0, 210, 255)), Color::LightCyan);
+        assert_eq!(ansi16(Color::Rgb(80, 80, 80)), Color::DarkGray);
+        assert_eq!(ansi16(Color::Indexed(196)), Color::LightRed);
+        assert_eq!(ansi16(Color::Indexed(4)), Color::Blue);
+        assert_eq!(ansi16(Color::Reset), Color::Reset);
+        let light = Theme::light().to_ansi16();
+        assert!(!matches!(light.primary, Color::Rgb(..)));
+        assert!(light.accents.iter().all(|c| !matches!(c, Color::Rgb(..))));
+    }
+}
@@ -0,0 +1,289 @@
+//! Timeline of the last main-chat turn for `/trace last`.
+//!
+//! The chat task times every model call and every tool call it makes and
+//! hands the result back through [`super::daemon::AgentEvent::ChatTimeline`].
+//! Tools hang under the model call that requested them, so the tree reads
+//! in the order the turn actually ran:
+//!
+//! ```text
+//! "why is CI red?" · 41.2s · 2 model call(s) · 4 tool(s)
+//! ├─ ▾ model #1 gpt-4o · 2.1s · 1200→80 tok
+//! │  ├─ github/list_runs branch=main · 12.4s · 18.2 KB
+//! │  └─ workspace_read_file path=ci.yml · 0.0s · 3.1 KB
+//! └─ ▸ model #2 gpt-4o · 1.8s · 2400→95 tok · 2 tool(s) · 20.1s
+//! ```
+
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+use crate::openai::ToolCall;
+
+/// Longest argument summary shown for a tool call.
+const ARGS_SUMMARY_CHARS: usize = 60;
+
+#[derive(Clone, Debug)]
+pub struct TurnTimeline {
+    pub message: String,
+    /// Local time the turn started.
+    pub timestamp: String,
+    started: Instant,
+    pub total: Duration,
+    pub calls: Vec<ModelCall>,
+}
+
+/// One request to the model and the tools its answer asked for.
+#[derive(Clone, Debug)]
+pub struct ModelCall {
+    pub model: String,
+    pub duration: Duration,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub error: Option<String>,
+    pub tools: Vec<ToolStep>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ToolStep {
+    /// `server/tool` for MCP tools.
+    pub name: String,
+    pub args: String,
+    pub duration: Duration,
+    /// Size of the raw result, before any `[tool_output]` limit.
+    pub result_bytes: usize,
+    /// Size sent back to the model when the result was cut or summarized.
+    pub sent_bytes: Option<usize>,
+    pub error: bool,
+}
+
+/// Which model calls `/trace last` shows with their tools.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expand {
+    None,
+    All,
+    Call(usize),
+}
+
+impl TurnTimeline {
+    pub fn start(message: &str) -> Self {
+        TurnTimeline {
+            message: message.to_string(),
+            timestamp: chrono::Local::now().format("%H:%M:%S").to_string(),
+            started: Instant::now(),
+            total: Duration::ZERO,
+            calls: Vec::new(),
+        }
+    }
+
+    /// A model call that began at `started` and answered with `response`.
+    pub fn model_call(&mut self, started: Instant, model: &str, response: &Value) {
+        let usage = |key: &str| {
+            response
+                .pointer(&format!("/usage/{key}"))
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        };
+        self.calls.push(ModelCall {
+            model: crate::openai::answering_model(response, model),
+            duration: started.elapsed(),
+            input_tokens: usage("input_tokens"),
+            output_tokens: usage("output_tokens"),
+            error: None,
+            tools: Vec::new(),
+        });
+    }
+
+    pub fn model_failed(&mut self, started: Instant, model: &str, error: &anyhow::Error) {
+        self.calls.push(ModelCall {
+            model: model.to_string(),
+            duration: started.elapsed(),
+            input_tokens: 0,
+            output_tokens: 0,
+            error: Some(format!("{error:#}")),
+            tools: Vec::new(),
+        });
+    }
+
+    /// A tool call that began at `started`; `raw` is its result before
+    /// size limits and `sent` what went back to the model.
+    pub fn tool_call(&mut self, call: &ToolCall, started: Instant, raw: &str, sent: &str) {
+        let name = match crate::mcp::split_namespaced_tool_name(&call.name) {
+            Some((server, tool)) => format!("{server}/{tool}"),
+            None => call.name.clone(),
+        };
+        let error = serde_json::from_str::<Value>(raw)
+            .is_ok_and(|value| value.get("error").is_some_and(|error| !error.is_null()));
+        let step = ToolStep {
+            name,
+            args: summarize_args(&call.arguments),
+            duration: started.elapsed(),
+            result_bytes: raw.len(),
+            sent_bytes: (sent.len() != raw.len()).then_some(sent.len()),
+            error,
+        };
+        if let Some(last) = self.calls.last_mut() {
+            last.tools.push(step);
+        }
+    }
+
+    /// Stop the clock.
+    pub fn finish(mut self) -> Self {
+        self.total = self.started.elapsed();
+        self
+    }
+
+    /// The tree as plain lines.
+    pub fn render(&self, expand: Expand) -> Vec<String> {
+        let tools: usize = self.calls.iter().map(|call| call.tools.len()).sum();
+        let mut lines = vec![format!(
+            "\"{}\" · {} · {} model call(s) · {tools} tool(s)",
+            truncate(&self.message, ARGS_SUMMARY_CHARS),
+            seconds(self.total),
+            self.calls.len()
+        )];
+        for (index, call) in self.calls.iter().enumerate() {
+            let number = index + 1;
+            let last = number == self.calls.len();
+            let (branch, stem) = if last {
+                ("└─", "   ")
+            } else {
+                ("├─", "│  ")
+            };
+            let open = !call.tools.is_empty()
+                && match expand {
+                    Expand::All => true,
+                    Expand::Call(n) => n == number,
+                    Expand::None => false,
+                };
+            let marker = match (call.tools.is_empty(), open) {
+                (true, _) => "•",
+                (false, true) => "▾",
+                (false, false) => "▸",
+            };
+            let mut head = format!(
+                "{branch} {marker} model #{number} {} · {}",
+                call.model,
+                seconds(call.duration)
+            );
+            match &call.error {
+                Some(error) => head.push_str(&format!(" · ✗ {}", truncate(error, 80))),
+                None => head.push_str(&format!(
+                    " · {}→{} tok",
+                    call.input_tokens, call.output_tokens
+                )),
+            }
+            if !call.tools.is_empty() && !open {
+                let spent: Duration = call.tools.iter().map(|tool| tool.duration).sum();
+                head.push_str(&format!(
+                    " · {} tool(s) · {}",
+                    call.tools.len(),
+                    seconds(spent)
+                ));
+            }
+            lines.push(head);
+            if !open {
+                continue;
+            }
+            for (tool_index, tool) in call.tools.iter().enumerate() {
+                let twig = if tool_index + 1 == call.tools.len() {
+                    "└─"
+                } else {
+                    "├─"
+                };
+                let mut line = format!("{stem}{twig} {}", tool.name);
+                if !tool.args.is_empty() {
+                    line.push(' ');
+                    line.push_str(&tool.args);
+                }
+                line.push_str(&format!(
+                    " · {} · {}",
+                    seconds(tool.duration),
+                    size(tool.result_bytes)
+                ));
+                if let Some(sent) = tool.sent_bytes {
+                    line.push_str(&format!(" (sent {})", size(sent)));
+                }
+                if tool.error {
+                    line.push_str(" · ✗ error");
+                }
+                lines.push(line);
+            }
+        }
+        lines
+    }
+}
+
+/// `key=value` pairs from a tool's JSON arguments, cut to a short line.
+fn summarize_args(arguments: &Value) -> String {
+    let summary = match arguments {
+        Value::Object(map) => map
+            .iter()
+            .map(|(key, value)| match value {
+                Value::String(text) => format!("{key}={text}"),
+                other => format!("{key}={other}"),
+            })
+            .collect::<Vec<_>>()
+            .join(" "),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    };
+    truncate(&summary.replace('\n', " "), ARGS_SUMMARY_CHARS)
+}
+
+fn truncate(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let cut: String = text.chars().take(max).collect();
+    format!("{cut}…")
+}
+
+fn seconds(duration: Duration) -> String {
+    format!("{:.1}s", duration.as_secs_f64())
+}
+
+fn size(bytes: usize) -> String {
+    if bytes < 1024 {
+        format!("{bytes} B")
+    } else {
+        format!("{:.1} KB", bytes as f64 / 1024.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn tools_nest_under_the_model_call_that_asked_for_them() {
+        let mut timeline = TurnTimeline::start("why is CI red?");
+        let started = Instant::now();
+        timeline.model_call(
+            started,
+            "gpt-4o",
+            &json!({"usage": {"input_tokens": 1200, "output_tokens": 80}}),
+        );
+        let call = ToolCall {
+            name: crate::mcp::namespaced_tool_name("github", "list_runs"),
+            arguments: json!({"branch": "main"}),
+            call_id: "call_1".to_string(),
+        };
+        let raw = "x".repeat(4096);
+        timeline.tool_call(&call, started, &raw, "summary");
+        timeline.model_call(started, "gpt-4o", &json!({}));
+        let timeline = timeline.finish();
+
+        let collapsed = timeline.render(Expand::None);
+        assert_eq!(collapsed.len(), 3);
+        assert!(collapsed[0].contains("2 model call(s) · 1 tool(s)"));
+        assert!(collapsed[1].starts_with("├─ ▸ model #1 gpt-4o"));
+        assert!(collapsed[1].contains("1200→80 tok · 1 tool(s)"));
+        assert!(collapsed[2].starts_with("└─ • model #2"));
+
+        let expanded = timeline.render(Expand::Call(1));
+        assert_eq!(expanded.len(), 4);
+        assert!(expanded[2].starts_with("│  └─ github/list_runs branch=main · "));
+        assert!(expanded[2].ends_with("4.0 KB (sent 7 B)"));
+    }
+}
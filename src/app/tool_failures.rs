@@ -0,0 +1,217 @@
+//! Failed tool calls in a tool loop: error envelopes, one retry for
+//! transient MCP failures, and the per-turn summary.
+//!
+//! A failed MCP call goes back to the model as
+//! `{"error": {"message": …, "kind": "transient"|"permanent", "retryable": …,
+//! "retried": …}}` so it can tell a flaky server from a bad request.
+//! Transient failures (timeouts, dropped connections, 429/5xx, rate limits)
+//! are retried once after [`MCP_RETRY_DELAY_MS`] before that.  Every failed
+//! call of a turn, from any tool, is listed in one activity-log line when
+//! the turn ends.
+
+use std::time::Duration;
+
+use anyhow::Result;
+use serde_json::{Value, json};
+
+use crate::constants::MCP_RETRY_DELAY_MS;
+use crate::mcp::{self, McpConnection};
+
+/// Message fragments of failures worth retrying.
+const TRANSIENT_MARKERS: &[&str] = &[
+    "timed out",
+    "timeout",
+    "connection reset",
+    "connection refused",
+    "connection closed",
+    "transport closed",
+    "broken pipe",
+    "unexpected eof",
+    "temporarily",
+    "unavailable",
+    "rate limit",
+    "too many requests",
+    "429",
+    "502",
+    "503",
+    "504",
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FailureKind {
+    /// Might work if tried again.
+    Transient,
+    /// Will fail the same way: bad arguments, unknown tool, denied.
+    Permanent,
+}
+
+impl FailureKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            FailureKind::Transient => "transient",
+            FailureKind::Permanent => "permanent",
+        }
+    }
+}
+
+pub(crate) fn classify(message: &str) -> FailureKind {
+    let message = message.to_ascii_lowercase();
+    if TRANSIENT_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+    {
+        FailureKind::Transient
+    } else {
+        FailureKind::Permanent
+    }
+}
+
+/// The error envelope handed back to the model.
+pub(crate) fn envelope(message: &str, retried: bool) -> String {
+    let kind = classify(message);
+    json!({
+        "error": {
+            "message": message,
+            "kind": kind.label(),
+            "retryable": kind == FailureKind::Transient,
+            "retried": retried,
+        }
+    })
+    .to_string()
+}
+
+/// Error text of a `{"error": …}` MCP result (`isError: true`).
+fn result_error(value: &Value) -> Option<String> {
+    if value.get("isError").and_then(Value::as_bool) != Some(true) {
+        return None;
+    }
+    let text = value
+        .get("content")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|block| block.get("text").and_then(Value::as_str))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(text)
+}
+
+/// Call an MCP tool, retrying once when the call or its result fails
+/// transiently.  `Err` carries the envelope for the model.
+pub(crate) async fn call_mcp_tool(
+    connection: &McpConnection,
+    tool: &str,
+    args: Value,
+) -> Result<Value, String> {
+    let first = mcp::call_tool(connection, tool, args.clone()).await;
+    let transient = match &first {
+        Ok(value) => {
+            result_error(value).is_some_and(|text| classify(&text) == FailureKind::Transient)
+        }
+        Err(err) => classify(&format!("{err:#}")) == FailureKind::Transient,
+    };
+    let (result, retried) = if transient {
+        tracing::info!(server = %connection.server.id, tool, "retrying transient MCP failure");
+        tokio::time::sleep(Duration::from_millis(MCP_RETRY_DELAY_MS)).await;
+        (mcp::call_tool(connection, tool, args).await, true)
+    } else {
+        (first, false)
+    };
+    result.map_err(|err| envelope(&format!("{err:#}"), retried))
+}
+
+#[derive(Clone, Debug)]
+struct Failure {
+    tool: String,
+    message: String,
+    kind: FailureKind,
+    retried: bool,
+}
+
+/// Tool calls of one turn and the ones that failed.
+#[derive(Debug, Default)]
+pub(crate) struct FailedCalls {
+    calls: usize,
+    failures: Vec<Failure>,
+}
+
+impl FailedCalls {
+    /// Count a call to `tool` that returned `output`; outputs with an
+    /// `error` field count as failures.
+    pub fn record(&mut self, tool: &str, output: &str) {
+        self.calls += 1;
+        let Ok(value) = serde_json::from_str::<Value>(output) else {
+            return;
+        };
+        let (message, retried) = match value.get("error") {
+            Some(Value::String(message)) => (message.clone(), false),
+            Some(Value::Object(error)) => (
+                error
+                    .get("message")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default()
+                    .to_string(),
+                error.get("retried").and_then(Value::as_bool) == Some(true),
+            ),
+            _ => return,
+        };
+        self.failures.push(Failure {
+            tool: tool.to_string(),
+            kind: classify(&message),
+            message,
+            retried,
+        });
+    }
+
+    /// One line listing the failed calls; `None` when all succeeded.
+    pub fn summary(&self) -> Option<String> {
+        if self.failures.is_empty() {
+            return None;
+        }
+        let listed: Vec<String> = self
+            .failures
+            .iter()
+            .map(|failure| {
+                let retried = if failure.retried { ", retried" } else { "" };
+                let message: String = failure.message.chars().take(120).collect();
+                format!(
+                    "{} ({}{retried}): {message}",
+                    failure.tool,
+                    failure.kind.label()
+                )
+            })
+            .collect();
+        Some(format!(
+            "⚠ {} of {} tool call(s) failed this turn — {}",
+            self.failures.len(),
+            self.calls,
+            listed.join("; ")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn envelopes_classify_and_the_summary_lists_failures() {
+        let timeout: Value =
+            serde_json::from_str(&envelope("request timed out", true)).expect("json");
+        assert_eq!(timeout["error"]["kind"], "transient");
+        assert_eq!(timeout["error"]["retryable"], true);
+        let invalid: Value =
+            serde_json::from_str(&envelope("-32602: invalid params: path", false)).expect("json");
+        assert_eq!(invalid["error"]["kind"], "permanent");
+
+        let mut failed = FailedCalls::default();
+        failed.record("github__search", &envelope("HTTP 503 from upstream", true));
+        failed.record("workspace_read_file", "file contents");
+        failed.record("workspace_run_command", r#"{"error":"Unknown tool 'x'"}"#);
+        let summary = failed.summary().expect("failures");
+        assert!(summary.starts_with("⚠ 2 of 3 tool call(s) failed"));
+        assert!(summary.contains("github__search (transient, retried)"));
+        assert!(summary.contains("workspace_run_command (permanent)"));
+        assert!(FailedCalls::default().summary().is_none());
+    }
+}
@@ -0,0 +1,350 @@
+//! Interactive argument entry for `/mcp call` — walks a tool's
+//! `input_schema`, prompting for each required field in turn.
+//!
+//! Started when `/mcp call <tool>` is given no JSON and the tool has
+//! required parameters.  Each answer is parsed according to the field's
+//! JSON-schema type (an empty answer takes the default), the finished
+//! object is validated against the schema, and the call is executed.
+//! `/cancel` or `Esc` abandons the form.
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Value};
+
+use super::App;
+use super::log_src;
+use super::logging::LogLevel;
+
+/// One schema property to prompt for.
+#[derive(Clone, Debug)]
+pub(crate) struct FormField {
+    pub name: String,
+    /// JSON-schema `type` (`string`, `integer`, …); `any` when absent.
+    pub kind: String,
+    pub description: Option<String>,
+    pub default: Option<Value>,
+    /// Allowed values from `enum`, if restricted.
+    pub choices: Vec<Value>,
+}
+
+impl FormField {
+    fn from_property(name: &str, property: &Value) -> Self {
+        FormField {
+            name: name.to_string(),
+            kind: schema_type(property).unwrap_or("any").to_string(),
+            description: property
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            default: property.get("default").cloned(),
+            choices: property
+                .get("enum")
+                .and_then(Value::as_array)
+                .cloned()
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Prompt line shown in the log, e.g. `query (string, default "x"): …`.
+    pub fn prompt(&self) -> String {
+        let mut hints = vec![self.kind.clone()];
+        if !self.choices.is_empty() {
+            let choices: Vec<String> = self.choices.iter().map(compact).collect();
+            hints.push(format!("one of {}", choices.join("|")));
+        }
+        if let Some(default) = &self.default {
+            hints.push(format!("default {}", compact(default)));
+        }
+        let mut line = format!("{} ({})", self.name, hints.join(", "));
+        if let Some(description) = &self.description {
+            line.push_str(&format!(" — {description}"));
+        }
+        line
+    }
+
+    /// Parse one typed answer into a JSON value for this field.
+    pub fn parse(&self, raw: &str) -> Result<Value> {
+        let raw = raw.trim();
+        if raw.is_empty() {
+            return self
+                .default
+                .clone()
+                .ok_or_else(|| anyhow!("'{}' is required", self.name));
+        }
+        let value = match self.kind.as_str() {
+            "string" => Value::String(raw.to_string()),
+            "integer" => raw
+                .parse::<i64>()
+                .map(Value::from)
+                .map_err(|_| anyhow!("'{}' expects an integer", self.name))?,
+            "number" => raw
+                .parse::<f64>()
+                .ok()
+                .and_then(|n| serde_json::Number::from_f64(n).map(Value::Number))
+                .ok_or_else(|| anyhow!("'{}' expects a number", self.name))?,
+            "boolean" => match raw.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "y" | "1" => Value::Bool(true),
+                "false" | "no" | "n" | "0" => Value::Bool(false),
+                _ => bail!("'{}' expects true or false", self.name),
+            },
+            // Arrays accept JSON or a comma-separated list of strings.
+            "array" if !raw.starts_with('[') => Value::Array(
+                raw.split(',')
+                    .map(|item| Value::String(item.trim().to_string()))
+                    .collect(),
+            ),
+            "array" | "object" | "any" => serde_json::from_str(raw)
+                .or_else(|err| {
+                    if self.kind == "any" {
+                        Ok(Value::String(raw.to_string()))
+                    } else {
+                        Err(err)
+                    }
+                })
+                .map_err(|err| anyhow!("'{}' expects JSON: {err}", self.name))?,
+            _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_string())),
+        };
+        check_property(&self.name, &self.schema_view(), &value)?;
+        Ok(value)
+    }
+
+    fn schema_view(&self) -> Value {
+        let mut schema = Map::new();
+        if self.kind != "any" {
+            schema.insert("type".to_string(), Value::String(self.kind.clone()));
+        }
+        if !self.choices.is_empty() {
+            schema.insert("enum".to_string(), Value::Array(self.choices.clone()));
+        }
+        Value::Object(schema)
+    }
+}
+
+/// In-progress argument form for one tool call.
+#[derive(Clone, Debug)]
+pub(crate) struct ToolForm {
+    /// Namespaced tool name (`server__tool`).
+    pub tool: String,
+    pub schema: Map<String, Value>,
+    pub fields: Vec<FormField>,
+    pub index: usize,
+    pub values: Map<String, Value>,
+}
+
+impl ToolForm {
+    /// Build a form over the schema's required properties.
+    pub fn new(tool: &str, schema: &Map<String, Value>) -> Self {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let fields = required_fields(schema)
+            .into_iter()
+            .map(|name| {
+                let property = properties
+                    .and_then(|props| props.get(name))
+                    .unwrap_or(&Value::Null);
+                FormField::from_property(name, property)
+            })
+            .collect();
+        ToolForm {
+            tool: tool.to_string(),
+            schema: schema.clone(),
+            fields,
+            index: 0,
+            values: Map::new(),
+        }
+    }
+
+    pub fn current(&self) -> Option<&FormField> {
+        self.fields.get(self.index)
+    }
+
+    /// Record an answer for the current field; `Ok(true)` once complete.
+    pub fn answer(&mut self, raw: &str) -> Result<bool> {
+        let Some(field) = self.current() else {
+            return Ok(true);
+        };
+        let value = field.parse(raw)?;
+        let name = field.name.clone();
+        self.values.insert(name, value);
+        self.index += 1;
+        Ok(self.index >= self.fields.len())
+    }
+}
+
+fn required_fields(schema: &Map<String, Value>) -> Vec<&str> {
+    schema
+        .get("required")
+        .and_then(Value::as_array)
+        .map(|names| names.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default()
+}
+
+fn schema_type(property: &Value) -> Option<&str> {
+    match property.get("type")? {
+        Value::String(kind) => Some(kind.as_str()),
+        // `["string", "null"]` — prompt for the first non-null type.
+        Value::Array(kinds) => kinds
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|kind| *kind != "null"),
+        _ => None,
+    }
+}
+
+fn compact(value: &Value) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+/// Check `args` against an object schema: required keys, property types
+/// and `enum` values.  Unknown keys are left to the server.
+pub(crate) fn validate(schema: &Map<String, Value>, args: &Value) -> Result<()> {
+    let Some(object) = args.as_object() else {
+        bail!("Tool arguments must be a JSON object");
+    };
+    let missing: Vec<&str> = required_fields(schema)
+        .into_iter()
+        .filter(|name| !object.contains_key(*name))
+        .collect();
+    if !missing.is_empty() {
+        bail!("Missing required argument(s): {}", missing.join(", "));
+    }
+    if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+        for (name, value) in object {
+            if let Some(property) = properties.get(name) {
+                check_property(name, property, value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn check_property(name: &str, property: &Value, value: &Value) -> Result<()> {
+    let kinds: Vec<&str> = match property.get("type") {
+        Some(Value::String(kind)) => vec![kind.as_str()],
+        Some(Value::Array(kinds)) => kinds.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !kinds.is_empty() && !kinds.iter().any(|kind| type_matches(kind, value)) {
+        bail!("'{name}' must be {}", kinds.join(" or "));
+    }
+    if let Some(choices) = property.get("enum").and_then(Value::as_array) {
+        if !choices.contains(value) {
+            let choices: Vec<String> = choices.iter().map(compact).collect();
+            bail!("'{name}' must be one of {}", choices.join(", "));
+        }
+    }
+    Ok(())
+}
+
+fn type_matches(kind: &str, value: &Value) -> bool {
+    match kind {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+// ── App integration ──────────────────────────────────────────────────
+
+impl App {
+    /// Begin prompting for `tool`'s required arguments.
+    pub(crate) fn start_tool_form(&mut self, form: ToolForm) {
+        self.log(
+            LogLevel::Info,
+            format!(
+                "🛠 {} needs {} argument(s). Enter each value (/cancel to abort):",
+                form.tool,
+                form.fields.len()
+            ),
+        );
+        if let Some(field) = form.current() {
+            self.log(LogLevel::Info, format!("  {}", field.prompt()));
+        }
+        self.tool_form = Some(form);
+    }
+
+    /// Handle one line of input while a tool form is active.
+    pub(crate) fn handle_tool_form_input(&mut self, line: &str) {
+        let Some(mut form) = self.tool_form.take() else {
+            return;
+        };
+        if line.trim() == "/cancel" {
+            self.log(LogLevel::Info, format!("Call to {} cancelled.", form.tool));
+            return;
+        }
+        match form.answer(line) {
+            Ok(false) => {
+                if let Some(field) = form.current() {
+                    self.log(LogLevel::Info, format!("  {}", field.prompt()));
+                }
+                self.tool_form = Some(form);
+            }
+            Ok(true) => {
+                let args = Value::Object(form.values);
+                self.execute_mcp_call(&form.tool, &form.schema, args);
+            }
+            Err(err) => {
+                log_src!(self, LogLevel::Warn, format!("{err:#} — try again:"));
+                if let Some(field) = form.current() {
+                    self.log(LogLevel::Info, format!("  {}", field.prompt()));
+                }
+                self.tool_form = Some(form);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> Map<String, Value> {
+        json!({
+            "type": "object",
+            "properties": {
+                "query": { "type": "string" },
+                "limit": { "type": "integer", "default": 10 },
+                "mode": { "type": "string", "enum": ["fast", "deep"] },
+                "tags": { "type": "array" }
+            },
+            "required": ["query", "limit", "mode"]
+        })
+        .as_object()
+        .cloned()
+        .unwrap()
+    }
+
+    #[test]
+    fn prompts_required_fields_in_order() {
+        let mut form = ToolForm::new("docs__search", &schema());
+        assert_eq!(form.fields.len(), 3);
+        assert!(!form.answer("rust async").unwrap());
+        assert!(form.answer("many").is_err());
+        assert!(!form.answer("").unwrap());
+        assert!(form.answer("medium").is_err());
+        assert!(form.answer("deep").unwrap());
+        assert_eq!(
+            Value::Object(form.values),
+            json!({ "query": "rust async", "limit": 10, "mode": "deep" })
+        );
+    }
+
+    #[test]
+    fn validates_raw_json() {
+        let schema = schema();
+        assert!(
+            validate(
+                &schema,
+                &json!({ "query": "x", "limit": 1, "mode": "fast" })
+            )
+            .is_ok()
+        );
+        assert!(validate(&schema, &json!({ "query": "x", "limit": 1 })).is_err());
+        assert!(validate(&schema, &json!({ "query": 3, "limit": 1, "mode": "fast" })).is_err());
+        assert!(validate(&schema, &json!([1, 2])).is_err());
+    }
+}
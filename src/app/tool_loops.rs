@@ -0,0 +1,151 @@
+//! Tool-loop limits: how many tool-call rounds a turn may take, and
+//! loop detection for a model that keeps making the same call.
+//!
+//! A call with the same tool and arguments as `repeat_limit` earlier calls
+//! in the same turn is not run; the model gets an error telling it to use
+//! the results it already has instead.  Set in `memini.toml`:
+//!
+//! ```toml
+//! [tool_loops]
+//! max_rounds = 6     # tool-call round trips per chat turn or task run
+//! repeat_limit = 3   # identical calls allowed before they are refused
+//! ```
+//!
+//! A recipe's `max_tool_loops:` overrides `max_rounds` for its runs.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::constants::{MAX_TOOL_LOOPS, TOOL_REPEAT_LIMIT};
+use crate::openai::ToolCall;
+
+/// The `[tool_loops]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ToolLoopConfig {
+    #[serde(default = "default_max_rounds")]
+    pub max_rounds: usize,
+    #[serde(default = "default_repeat_limit")]
+    pub repeat_limit: usize,
+}
+
+fn default_max_rounds() -> usize {
+    MAX_TOOL_LOOPS
+}
+
+fn default_repeat_limit() -> usize {
+    TOOL_REPEAT_LIMIT
+}
+
+impl Default for ToolLoopConfig {
+    fn default() -> Self {
+        ToolLoopConfig {
+            max_rounds: MAX_TOOL_LOOPS,
+            repeat_limit: TOOL_REPEAT_LIMIT,
+        }
+    }
+}
+
+/// The `[tool_loops]` section of `memini.toml`.
+pub(crate) fn config() -> &'static ToolLoopConfig {
+    &crate::config::get().tool_loops
+}
+
+/// Round and repeat bookkeeping for one turn's tool loop.
+#[derive(Debug)]
+pub(crate) struct LoopGuard {
+    max_rounds: usize,
+    repeat_limit: usize,
+    rounds: usize,
+    /// Calls made so far, keyed by tool name and arguments.
+    seen: HashMap<String, usize>,
+}
+
+impl LoopGuard {
+    /// A guard with the `memini.toml` limits, `max_rounds` overriding the
+    /// round limit (a recipe's `max_tool_loops:`).
+    pub fn new(max_rounds: Option<usize>) -> Self {
+        let config = config();
+        LoopGuard::with_limits(max_rounds.unwrap_or(config.max_rounds), config.repeat_limit)
+    }
+
+    fn with_limits(max_rounds: usize, repeat_limit: usize) -> Self {
+        LoopGuard {
+            max_rounds,
+            repeat_limit: repeat_limit.max(1),
+            rounds: 0,
+            seen: HashMap::new(),
+        }
+    }
+
+    pub fn max_rounds(&self) -> usize {
+        self.max_rounds
+    }
+
+    /// Start another round; `false` once the round limit is reached.
+    pub fn next_round(&mut self) -> bool {
+        if self.rounds >= self.max_rounds {
+            return false;
+        }
+        self.rounds += 1;
+        true
+    }
+
+    /// Count `call`.  Returns the error to hand back to the model instead
+    /// of running it when it repeats `repeat_limit` identical calls.
+    pub fn check(&mut self, call: &ToolCall) -> Option<String> {
+        let key = format!("{}\u{0}{}", call.name, call.arguments);
+        let count = self.seen.entry(key).or_insert(0);
+        *count += 1;
+        if *count <= self.repeat_limit {
+            return None;
+        }
+        Some(
+            json!({
+                "error": format!(
+                    "Loop detected: {} was already called {} times with these exact arguments \
+                     in this turn, so it was not run again. Use the results you already have, \
+                     try different arguments or another tool, or give your answer.",
+                    call.name, self.repeat_limit
+                )
+            })
+            .to_string(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    fn call(args: serde_json::Value) -> ToolCall {
+        ToolCall {
+            name: "workspace_search".to_string(),
+            arguments: args,
+            call_id: "c1".to_string(),
+        }
+    }
+
+    #[test]
+    fn repeats_past_the_limit_are_refused_and_rounds_are_capped() {
+        let mut guard = LoopGuard::with_limits(2, 2);
+        let same = call(json!({"query": "todo"}));
+        assert!(guard.check(&same).is_none());
+        assert!(guard.check(&call(json!({"query": "fixme"}))).is_none());
+        assert!(guard.check(&same).is_none());
+        let refused = guard.check(&same).expect("third identical call");
+        assert!(refused.contains("Loop detected: workspace_search"));
+
+        assert!(guard.next_round());
+        assert!(guard.next_round());
+        assert!(!guard.next_round());
+
+        let config = MeminiToml::parse("[tool_loops]\nmax_rounds = 12\n")
+            .expect("parse")
+            .tool_loops;
+        assert_eq!(config.max_rounds, 12);
+        assert_eq!(config.repeat_limit, TOOL_REPEAT_LIMIT);
+    }
+}
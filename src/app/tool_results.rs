@@ -0,0 +1,227 @@
+//! Size limits for tool results fed back into a tool loop.
+//!
+//! A result longer than its limit is saved in full as an artifact, split
+//! into chunks, and each chunk summarized with the `summary` model route
+//! (see [`crate::routing`]).  The model gets the joined summaries plus the
+//! artifact path instead of the raw dump.  Limits come from `memini.toml`:
+//!
+//! ```toml
+//! [tool_output]
+//! max_chars = 24000        # default for every tool
+//! summarize = true         # false: truncate instead of summarizing
+//!
+//! [tool_output.tools]
+//! workspace_read_file = 60000
+//! "search__*" = 8000       # every tool on the `search` MCP server
+//! ```
+
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::artifacts::ArtifactRun;
+use crate::constants::{
+    DEFAULT_TOOL_OUTPUT_MAX_CHARS, MAX_TOOL_SUMMARY_CHUNKS, TOOL_SUMMARY_CHUNK_CHARS,
+};
+use crate::openai::{self, OpenAiClient, ToolCall};
+use crate::routing::TaskKind;
+
+/// The `[tool_output]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct ToolOutputLimits {
+    #[serde(default = "default_max_chars")]
+    pub max_chars: usize,
+    #[serde(default = "default_summarize")]
+    pub summarize: bool,
+    /// Per-tool overrides; a trailing `*` matches a name prefix.
+    #[serde(default)]
+    pub tools: BTreeMap<String, usize>,
+}
+
+fn default_max_chars() -> usize {
+    DEFAULT_TOOL_OUTPUT_MAX_CHARS
+}
+
+fn default_summarize() -> bool {
+    true
+}
+
+impl Default for ToolOutputLimits {
+    fn default() -> Self {
+        ToolOutputLimits {
+            max_chars: DEFAULT_TOOL_OUTPUT_MAX_CHARS,
+            summarize: true,
+            tools: BTreeMap::new(),
+        }
+    }
+}
+
+impl ToolOutputLimits {
+    /// Limit for `tool`: an exact entry, else the longest matching
+    /// `prefix*` entry, else `max_chars`.
+    pub fn limit_for(&self, tool: &str) -> usize {
+        if let Some(limit) = self.tools.get(tool) {
+            return *limit;
+        }
+        self.tools
+            .iter()
+            .filter_map(|(pattern, limit)| {
+                let prefix = pattern.strip_suffix('*')?;
+                tool.starts_with(prefix).then_some((prefix.len(), *limit))
+            })
+            .max_by_key(|(len, _)| *len)
+            .map(|(_, limit)| limit)
+            .unwrap_or(self.max_chars)
+    }
+}
+
+/// The `[tool_output]` section of `memini.toml`.
+fn limits() -> &'static ToolOutputLimits {
+    &crate::config::get().tool_output
+}
+
+/// Redact secrets from `output`, then bring it within the limit for
+/// `call`, summarizing if needed.  The full text is kept in `artifacts`
+/// under `tool-results/`.
+pub(crate) async fn fit(
+    openai: &OpenAiClient,
+    key: &str,
+    call: &ToolCall,
+    output: String,
+    artifacts: &ArtifactRun,
+) -> String {
+    let output = crate::redact::redact(&output);
+    let limits = limits();
+    let limit = limits.limit_for(&call.name);
+    let total = output.chars().count();
+    if total <= limit {
+        return output;
+    }
+
+    let name = format!(
+        "tool-results/{}-{}.txt",
+        sanitize(&call.name),
+        sanitize(&call.call_id)
+    );
+    let saved = artifacts
+        .save(&name, &output)
+        .map(|path| path.display().to_string())
+        .ok();
+    tracing::info!(tool = %call.name, chars = total, limit, "oversized tool result");
+
+    let summary = if limits.summarize {
+        summarize(openai, key, call, &output, limit).await.ok()
+    } else {
+        None
+    };
+    let (mode, content) = match summary {
+        Some(summary) => ("summarized", clip(&summary, limit)),
+        None => ("truncated", clip(&output, limit)),
+    };
+    json!({
+        "result": mode,
+        "original_chars": total,
+        "full_output": saved,
+        "content": content,
+    })
+    .to_string()
+}
+
+async fn summarize(
+    openai: &OpenAiClient,
+    key: &str,
+    call: &ToolCall,
+    output: &str,
+    limit: usize,
+) -> Result<String> {
+    let model = openai.model_for(TaskKind::Summary);
+    let parts = chunks(output, TOOL_SUMMARY_CHUNK_CHARS, MAX_TOOL_SUMMARY_CHUNKS);
+    let budget = (limit / parts.len()).max(200);
+    let mut summaries = Vec::with_capacity(parts.len());
+    for (index, part) in parts.iter().enumerate() {
+        let prompt = format!(
+            "Part {} of {} of the result of tool `{}` called with {}.\n\
+             Summarize it in at most {budget} characters for an agent that will \
+             continue the task. Keep identifiers, names, numbers, paths, URLs and \
+             errors verbatim; drop repetition and boilerplate.\n\n{part}",
+            index + 1,
+            parts.len(),
+            call.name,
+            call.arguments,
+        );
+        let input = [json!({"role": "user", "content": prompt})];
+        let response = openai.response(key, &model, &input, None).await?;
+        let text = openai::extract_output_text(&openai::extract_output_items(&response));
+        summaries.push(text.trim().to_string());
+    }
+    Ok(summaries.join("\n\n"))
+}
+
+/// Split `text` into at most `max_chunks` pieces of at least `chunk_chars`
+/// characters (the last may be shorter).
+fn chunks(text: &str, chunk_chars: usize, max_chunks: usize) -> Vec<String> {
+    let total = text.chars().count();
+    let size = chunk_chars.max(total.div_ceil(max_chunks.max(1))).max(1);
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(size)
+        .map(|piece| piece.iter().collect())
+        .collect()
+}
+
+fn clip(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let mut out: String = text.chars().take(max_chars).collect();
+    out.push_str("\n… (truncated)");
+    out
+}
+
+fn sanitize(raw: &str) -> String {
+    raw.chars()
+        .map(|ch| {
+            if ch.is_ascii_alphanumeric() || ch == '-' || ch == '_' {
+                ch
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn per_tool_limits_and_prefix_patterns() {
+        let limits = MeminiToml::parse(
+            "[tool_output]\nmax_chars = 1000\n\n[tool_output.tools]\n\"search__*\" = 50\n\"search__web*\" = 70\nworkspace_read_file = 5000\n",
+        )
+        .expect("parse").tool_output;
+        assert_eq!(limits.limit_for("workspace_read_file"), 5000);
+        assert_eq!(limits.limit_for("search__news"), 50);
+        assert_eq!(limits.limit_for("search__web_search"), 70);
+        assert_eq!(limits.limit_for("notion__search"), 1000);
+        assert!(limits.summarize);
+        assert_eq!(
+            MeminiToml::parse("").expect("empty").tool_output.max_chars,
+            DEFAULT_TOOL_OUTPUT_MAX_CHARS
+        );
+    }
+
+    #[test]
+    fn chunks_cover_the_text_within_the_cap() {
+        let text = "line of text\n".repeat(1000);
+        let parts = chunks(&text, 1000, 4);
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts.concat(), text);
+        let parts = chunks(&text, 5000, 8);
+        assert!(parts.iter().all(|part| part.chars().count() <= 5000));
+        assert_eq!(parts.concat(), text);
+    }
+}
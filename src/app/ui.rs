@@ -4,30 +4,19 @@ use ratatui::Frame;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Position, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span, Text};
-use ratatui::widgets::{Block, Borders, Paragraph, Wrap};
+use ratatui::widgets::{Block, BorderType, Borders, Clear, Paragraph, Wrap};
 
+use crate::i18n::t;
 use crate::rice::RiceStatus;
 
 use super::App;
 use super::RiceSetupStep;
 use super::ViewMode;
-use super::daemon::AgentWindowStatus;
-
-/// Animated spinner frames for the thinking indicator.
-const SPINNER: &[&str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
-
-/// Accent colors for dashboard agent rows/cards.
-const ACCENT_COLORS: &[Color] = &[
-    Color::Rgb(0, 255, 136),   // green
-    Color::Rgb(0, 210, 255),   // cyan
-    Color::Rgb(138, 43, 226),  // purple
-    Color::Rgb(255, 165, 0),   // orange
-    Color::Rgb(255, 105, 180), // pink
-    Color::Rgb(64, 224, 208),  // turquoise
-    Color::Rgb(255, 215, 0),   // gold
-    Color::Rgb(100, 149, 237), // cornflower
-    Color::Rgb(220, 20, 60),   // crimson
-];
+use super::daemon::{AgentWindow, AgentWindowStatus};
+use super::markdown;
+use super::missions::MissionStatus;
+use super::progress::{self, SPINNER};
+use super::theme::{self, Theme};
 
 /// Fun idle messages for empty live-agent states.
 const EMPTY_HINTS: &[&str] = &[
@@ -44,6 +33,13 @@ const EMPTY_HINTS: &[&str] = &[
 
 const MAX_INPUT_VISIBLE_LINES: u16 = 6;
 
+/// Rows shown in the tab-completion popup before it scrolls.
+const MAX_COMPLETION_ROWS: usize = 8;
+
+/// Smallest live-agent card that still shows a useful preview.
+const MIN_CARD_WIDTH: u16 = 28;
+const MIN_CARD_HEIGHT: u16 = 5;
+
 impl App {
     /// Get the current spinner frame based on tick count.
     fn spinner_frame(&self) -> &'static str {
@@ -52,7 +48,7 @@ impl App {
 
     /// Get a gradient accent color for a given index.
     fn accent_color(&self, idx: usize) -> Color {
-        ACCENT_COLORS[idx % ACCENT_COLORS.len()]
+        theme::current().accent(idx)
     }
 
     fn input_panel_height(&self) -> u16 {
@@ -69,7 +65,7 @@ impl App {
         let cursor_col = before
             .rsplit('\n')
             .next()
-            .map(|segment| segment.len())
+            .map(|segment| segment.chars().count())
             .unwrap_or(0);
         let visible_height = inner_height.max(1) as usize;
         let visible_width = inner_width.max(1) as usize;
@@ -85,12 +81,111 @@ impl App {
             ViewMode::Dashboard => self.draw_dashboard(frame),
             ViewMode::AgentSession(window_id) => self.draw_agent_session(frame, window_id),
         }
+        self.draw_http_inspector(frame);
+    }
+
+    /// `/debug http show`: captured exchanges on the left, the selected
+    /// one's request and response on the right.
+    fn draw_http_inspector(&self, frame: &mut Frame<'_>) {
+        let Some(view) = self.http_inspector.as_ref() else {
+            return;
+        };
+        let theme = theme::current();
+        let screen = frame.area();
+        let width = (screen.width * 9 / 10).max(20).min(screen.width);
+        let height = (screen.height * 9 / 10).max(6).min(screen.height);
+        let area = Rect::new(
+            screen.x + (screen.width - width) / 2,
+            screen.y + (screen.height - height) / 2,
+            width,
+            height,
+        );
+        frame.render_widget(Clear, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.primary))
+            .title(Span::styled(
+                format!(" HTTP capture · {} ", view.entries.len()),
+                Style::default().fg(theme.text).add_modifier(Modifier::BOLD),
+            ))
+            .title_bottom(Span::styled(
+                " j/k select · PgUp/PgDn scroll · r reload · d dump · Esc close ",
+                Style::default().fg(theme.muted),
+            ));
+        let inner = block.inner(area);
+        frame.render_widget(block, area);
+        if view.entries.is_empty() {
+            let hint = if crate::capture::enabled() {
+                "Nothing captured yet. Chat or call a tool, then press r."
+            } else {
+                "Capture is off. Close this and run /debug http on."
+            };
+            frame.render_widget(
+                Paragraph::new(Span::styled(hint, Style::default().fg(theme.muted))),
+                inner,
+            );
+            return;
+        }
+
+        let cols = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+            .split(inner);
+        let rows = cols[0].height.max(1) as usize;
+        let first = view.selected.saturating_sub(rows.saturating_sub(1));
+        let list: Vec<Line> = view
+            .entries
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .map(|(idx, exchange)| {
+                let time = exchange.at.get(11..19).unwrap_or(&exchange.at);
+                let status_color = if exchange.succeeded() {
+                    theme.success
+                } else {
+                    theme.error
+                };
+                let base = if idx == view.selected {
+                    Style::default().fg(theme.text).bg(theme.selection_bg)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(vec![
+                    Span::styled(format!("{time} "), base.fg(theme.muted)),
+                    Span::styled(format!("{:<6} ", exchange.channel.label()), base),
+                    Span::styled(format!("{:>5} ", exchange.status), base.fg(status_color)),
+                    Span::styled(exchange.target.clone(), base),
+                ])
+            })
+            .collect();
+        frame.render_widget(Paragraph::new(Text::from(list)), cols[0]);
+
+        let detail: Vec<Line> = view
+            .current()
+            .map(super::http_inspector::detail_lines)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|line| Line::from(Span::styled(line, Style::default().fg(theme.text))))
+            .collect();
+        let detail_block = Block::default()
+            .borders(Borders::LEFT)
+            .border_style(Style::default().fg(theme.border));
+        frame.render_widget(
+            Paragraph::new(Text::from(detail))
+                .block(detail_block)
+                .wrap(Wrap { trim: false })
+                .scroll((view.scroll, 0)),
+            cols[1],
+        );
     }
 
     // ── Dashboard view ───────────────────────────────────────────────
 
     /// Home screen: status bar, activity log, agent panels, input prompt, footer.
     fn draw_dashboard(&mut self, frame: &mut Frame<'_>) {
+        let theme = theme::current();
         let input_height = self.input_panel_height();
         let rows = Layout::default()
             .direction(Direction::Vertical)
@@ -106,7 +201,26 @@ impl App {
         self.draw_status_bar(frame, rows[0]);
 
         // ── Main area: activity log (left) + agent overview (right) ──
+        if self
+            .split_window
+            .is_some_and(|id| !self.agent_windows.iter().any(|w| w.id == id))
         {
+            self.split_window = None;
+        }
+        if let Some(window_id) = self.split_window {
+            // Split mode: chat on the left, one agent streaming on the right.
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(rows[1]);
+            self.draw_activity_log(frame, cols[0]);
+            self.draw_agent_output(
+                frame,
+                cols[1],
+                window_id,
+                "Ctrl+S: close split · Tab: next agent",
+            );
+        } else {
             let cols = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
@@ -129,27 +243,31 @@ impl App {
                 RiceSetupStep::StorageUrl => " 📦 Rice Storage URL ",
                 RiceSetupStep::StorageToken => " 🔑 Rice Storage Token ",
             };
+            (label.to_string(), Style::default().fg(theme.primary))
+        } else if let Some(form) = &self.tool_form {
+            let field = form.current().map(|f| f.name.as_str()).unwrap_or("");
             (
-                label.to_string(),
-                Style::default().fg(Color::Rgb(0, 210, 255)),
+                format!(" 🛠 {} › {field} ", form.tool),
+                Style::default().fg(theme.primary),
             )
         } else if self.chat_busy {
             let spinner = self.spinner_frame();
             // Can't interpolate a dynamic spinner into a static str, so we use a fixed label.
             let _ = spinner;
-            (
-                " ⟳ Thinking… ".to_string(),
-                Style::default().fg(Color::Yellow),
-            )
+            let label = match self.chat_queue.len() {
+                0 => " ⟳ Thinking… ".to_string(),
+                queued => format!(" ⟳ Thinking… · {queued} queued "),
+            };
+            (label, Style::default().fg(theme.warning))
         } else if waiting_count > 0 {
             (
                 format!(" ◈ {waiting_count} ask(s) pending — Enter sends to oldest "),
-                Style::default().fg(Color::Rgb(255, 105, 180)),
+                Style::default().fg(theme.attention),
             )
         } else {
             (
                 " ❯ memini by ag\\i ".to_string(),
-                Style::default().fg(Color::Rgb(0, 255, 136)),
+                Style::default().fg(theme.success),
             )
         };
 
@@ -167,6 +285,7 @@ impl App {
                     .border_style(prompt_style),
             );
         frame.render_widget(input_panel, rows[2]);
+        self.draw_completion_popup(frame, rows[2]);
 
         let input_width = rows[2].width.saturating_sub(2);
         let cursor_x = if input_width == 0 {
@@ -188,12 +307,73 @@ impl App {
         self.draw_footer(frame, rows[3]);
     }
 
+    /// Tab-completion suggestions, floating above the input box.
+    fn draw_completion_popup(&self, frame: &mut Frame<'_>, input_area: Rect) {
+        let Some(completion) = self.completion.as_ref() else {
+            return;
+        };
+        let theme = theme::current();
+        let total = completion.candidates.len();
+        let rows = total.min(MAX_COMPLETION_ROWS);
+        let selected = completion.selected.unwrap_or(0);
+        let first = selected.saturating_sub(rows.saturating_sub(1));
+
+        let widest = completion
+            .candidates
+            .iter()
+            .map(|c| c.chars().count())
+            .max()
+            .unwrap_or(0);
+        let width = (widest as u16 + 4)
+            .max(16)
+            .min(frame.area().width.saturating_sub(input_area.x));
+        let height = (rows as u16 + 2).min(input_area.y);
+        if width < 4 || height < 3 {
+            return;
+        }
+        let col = input_area.x + 1 + completion.start.min(u16::MAX as usize) as u16;
+        let x = col.min(frame.area().width.saturating_sub(width));
+        let area = Rect::new(x, input_area.y - height, width, height);
+
+        let lines: Vec<Line> = completion
+            .candidates
+            .iter()
+            .enumerate()
+            .skip(first)
+            .take(rows)
+            .map(|(idx, candidate)| {
+                let style = if completion.selected == Some(idx) {
+                    Style::default()
+                        .fg(theme.text)
+                        .bg(theme.selection_bg)
+                        .add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(theme.text)
+                };
+                Line::from(Span::styled(format!(" {candidate} "), style))
+            })
+            .collect();
+        let title = if total > rows {
+            format!(" {}/{total} ", selected + 1)
+        } else {
+            String::new()
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(BorderType::Rounded)
+            .border_style(Style::default().fg(theme.primary))
+            .title(Span::styled(title, Style::default().fg(theme.muted)));
+
+        frame.render_widget(Clear, area);
+        frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+    }
+
     // ── Agent Overview (dashboard right pane) ───────────────────────
 
     /// Render the right dashboard pane: running background agents + live windows.
-    fn draw_agent_overview(&self, frame: &mut Frame<'_>, area: Rect) {
+    fn draw_agent_overview(&mut self, frame: &mut Frame<'_>, area: Rect) {
         if self.daemon_handles.is_empty() || area.height < 8 {
-            self.draw_live_agent_rows(frame, area);
+            self.draw_live_agent_grid(frame, area);
             return;
         }
 
@@ -208,21 +388,22 @@ impl App {
             .split(area);
 
         self.draw_background_agent_rows(frame, split[0]);
-        self.draw_live_agent_rows(frame, split[1]);
+        self.draw_live_agent_grid(frame, split[1]);
     }
 
     fn draw_background_agent_rows(&self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
         let inner_height = area.height.saturating_sub(2) as usize;
         let mut lines: Vec<Line> = Vec::new();
 
         if self.daemon_handles.is_empty() {
             lines.push(Line::from(Span::styled(
                 " No running background agents.",
-                Style::default().fg(Color::Rgb(120, 120, 120)),
+                Style::default().fg(theme.subtle),
             )));
             lines.push(Line::from(Span::styled(
                 " Use /auto templates or /auto scaffold repo-watch",
-                Style::default().fg(Color::Rgb(80, 80, 80)),
+                Style::default().fg(theme.muted),
             )));
         } else {
             let mut tasks: Vec<_> = self
@@ -245,11 +426,7 @@ impl App {
                     break;
                 }
                 let icon = if paused { "⏸" } else { "▶" };
-                let color = if paused {
-                    Color::DarkGray
-                } else {
-                    Color::Yellow
-                };
+                let color = if paused { theme.dim } else { theme.warning };
                 let tools_label = if tools.is_empty() {
                     "local(default)".to_string()
                 } else if tools.len() == 1 {
@@ -274,7 +451,7 @@ impl App {
                 if let Some(last) = lines.last_mut() {
                     *last = Line::from(Span::styled(
                         format!(" … and {hidden} more"),
-                        Style::default().fg(Color::DarkGray),
+                        Style::default().fg(theme.dim),
                     ));
                 }
             }
@@ -282,99 +459,231 @@ impl App {
 
         let block = Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Yellow))
+            .border_style(Style::default().fg(theme.warning))
             .title(Span::styled(
                 format!(" ⚙ Auto Agents ({}) ", self.daemon_handles.len()),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.warning)
                     .add_modifier(Modifier::BOLD),
             ));
         frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
     }
 
-    fn draw_live_agent_rows(&self, frame: &mut Frame<'_>, area: Rect) {
-        let inner_height = area.height.saturating_sub(2) as usize;
-        let mut lines: Vec<Line> = Vec::new();
-        let mut selected_summary = String::new();
+    /// Live agent windows as a grid of cards sized to the pane, paged
+    /// when there are more windows than cards fit.
+    fn draw_live_agent_grid(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
+        let outer = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.primary));
+        let inner = outer.inner(area);
 
         if self.agent_windows.is_empty() {
             let hint = EMPTY_HINTS[(self.tick_count as usize / 10) % EMPTY_HINTS.len()];
-            lines.push(Line::from(Span::styled(
-                " No live agent windows yet.",
-                Style::default().fg(Color::Rgb(120, 120, 120)),
-            )));
-            lines.push(Line::from(Span::styled(
-                " Use /spawn <prompt> to launch one.",
-                Style::default().fg(Color::Rgb(0, 210, 255)),
-            )));
-            lines.push(Line::from(Span::styled(
-                format!(" Hint: {hint}"),
-                Style::default().fg(Color::Rgb(80, 80, 80)),
-            )));
+            let lines = vec![
+                Line::from(Span::styled(
+                    " No live agent windows yet.",
+                    Style::default().fg(theme.subtle),
+                )),
+                Line::from(Span::styled(
+                    " Use /spawn <prompt> to launch one.",
+                    Style::default().fg(theme.primary),
+                )),
+                Line::from(Span::styled(
+                    format!(" Hint: {hint}"),
+                    Style::default().fg(theme.muted),
+                )),
+            ];
+            let block = outer.title(Span::styled(
+                " ▣ Live Agents (0) ",
+                Style::default()
+                    .fg(theme.primary)
+                    .add_modifier(Modifier::BOLD),
+            ));
+            frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+            return;
+        }
+
+        if self.plain_ui {
+            self.draw_live_agent_list(frame, area);
+            return;
+        }
+
+        let count = self.agent_windows.len();
+        let (cols, rows) = grid_dims(inner.width, inner.height, count);
+        let page_size = cols * rows;
+        self.grid_page_size = page_size;
+        let selected_idx = self.grid_selected.min(count - 1);
+        let page = selected_idx / page_size;
+        let pages = count.div_ceil(page_size);
+
+        let page_label = if pages > 1 {
+            format!(" page {}/{}", page + 1, pages)
         } else {
-            let selected_idx = self.grid_selected.min(self.agent_windows.len() - 1);
-            selected_summary = format!(" [{} / {}]", selected_idx + 1, self.agent_windows.len());
-            let start = selected_idx.saturating_sub(inner_height.saturating_sub(1));
-            for (idx, window) in self
-                .agent_windows
+            String::new()
+        };
+        let mut block = outer.title(Span::styled(
+            format!(
+                " ▣ Live Agents ({count}) [{}/{count}]{page_label} [Tab:select Ctrl+PgUp/PgDn:page Enter:open] ",
+                selected_idx + 1
+            ),
+            Style::default()
+                .fg(theme.primary)
+                .add_modifier(Modifier::BOLD),
+        ));
+        if let Some(mission) = self.mission_title(&theme) {
+            block = block.title_bottom(mission);
+        }
+        frame.render_widget(block, area);
+
+        let row_areas = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Ratio(1, rows as u32); rows])
+            .split(inner);
+        let cells: Vec<Rect> = row_areas
+            .iter()
+            .flat_map(|row| {
+                Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints(vec![Constraint::Ratio(1, cols as u32); cols])
+                    .split(*row)
+                    .to_vec()
+            })
+            .collect();
+
+        for (cell, (idx, window)) in cells.into_iter().zip(
+            self.agent_windows
                 .iter()
                 .enumerate()
-                .skip(start)
-                .take(inner_height)
-            {
-                let (status_icon, status_color, status_text) = match window.status {
-                    AgentWindowStatus::Thinking => {
-                        (self.spinner_frame(), Color::Yellow, "thinking")
-                    }
-                    AgentWindowStatus::Done => ("✓", Color::Rgb(0, 255, 136), "done"),
-                    AgentWindowStatus::WaitingForInput => {
-                        ("◈", Color::Rgb(255, 105, 180), "needs-input")
+                .skip(page * page_size)
+                .take(page_size),
+        ) {
+            self.draw_agent_card(frame, cell, window, idx == selected_idx);
+        }
+    }
+
+    /// Plain-mode stand-in for the grid: one line per window with its
+    /// status and latest output, paged like the grid.
+    fn draw_live_agent_list(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
+        let count = self.agent_windows.len();
+        let page_size = (area.height.saturating_sub(2) as usize).max(1);
+        self.grid_page_size = page_size;
+        let selected_idx = self.grid_selected.min(count - 1);
+        let page = selected_idx / page_size;
+
+        let lines: Vec<Line> = self
+            .agent_windows
+            .iter()
+            .enumerate()
+            .skip(page * page_size)
+            .take(page_size)
+            .map(|(idx, window)| {
+                let (status, color) = match window.status {
+                    AgentWindowStatus::Thinking => (t("working"), theme.warning),
+                    AgentWindowStatus::Done if critic_flagged(window) => {
+                        (t("done, flagged"), theme.warning)
                     }
+                    AgentWindowStatus::Done => (t("done"), theme.success),
+                    AgentWindowStatus::WaitingForInput => (t("needs input"), theme.attention),
+                    AgentWindowStatus::TimedOut => (t("timed out"), theme.error),
+                    AgentWindowStatus::Killed => (t("killed"), theme.error),
                 };
-                let preview: String = window.prompt.chars().take(44).collect();
-                let ellipsis = if window.prompt.chars().count() > 44 {
-                    "…"
-                } else {
-                    ""
-                };
-                let row = format!(
-                    " #{} {} {} [{}] — {}{}",
-                    window.id, status_icon, window.label, status_text, preview, ellipsis
-                );
-                let style = if idx == selected_idx {
-                    Style::default()
-                        .fg(self.accent_color(window.id))
-                        .add_modifier(Modifier::BOLD)
-                } else {
-                    Style::default().fg(status_color)
-                };
-                lines.push(Line::from(Span::styled(row, style)));
-            }
-
-            if self.agent_windows.len() > start + lines.len() && !lines.is_empty() {
-                let hidden = self.agent_windows.len() - (start + lines.len());
-                if let Some(last) = lines.last_mut() {
-                    *last = Line::from(Span::styled(
-                        format!(" … and {hidden} more live windows"),
-                        Style::default().fg(Color::DarkGray),
-                    ));
+                let latest = window
+                    .output_lines
+                    .last()
+                    .map(String::as_str)
+                    .unwrap_or_else(|| window.prompt.lines().next().unwrap_or(""));
+                let marker = if idx == selected_idx { ">" } else { " " };
+                let mut style = Style::default().fg(color);
+                if idx == selected_idx {
+                    style = style.add_modifier(Modifier::BOLD);
                 }
-            }
-        }
-
-        let block = Block::default()
+                Line::from(vec![
+                    Span::styled(format!("{marker} #{} [{status}] ", window.id), style),
+                    Span::styled(latest.to_string(), Style::default().fg(theme.text)),
+                ])
+            })
+            .collect();
+
+        let mut block = Block::default()
             .borders(Borders::ALL)
-            .border_style(Style::default().fg(Color::Rgb(0, 210, 255)))
+            .border_style(Style::default().fg(theme.primary))
             .title(Span::styled(
                 format!(
-                    " ▣ Live Agents ({}){} [Tab/Shift-Tab or PgUp/PgDn:scroll Enter:open] ",
-                    self.agent_windows.len(),
-                    selected_summary
+                    " Live Agents ({count}) [{}/{count}] [Tab:select Enter:open] ",
+                    selected_idx + 1
                 ),
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ));
+        if let Some(mission) = self.mission_title(&theme) {
+            block = block.title_bottom(mission);
+        }
+        frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+    }
+
+    /// Bottom title of the agent pane: the mission shown on the dashboard.
+    fn mission_title(&self, theme: &Theme) -> Option<Line<'static>> {
+        let run = self.dashboard_mission()?;
+        let color = match run.status {
+            MissionStatus::Running | MissionStatus::Checking(_) => theme.warning,
+            MissionStatus::Succeeded => theme.success,
+            MissionStatus::Failed(_) => theme.error,
+        };
+        Some(Line::from(Span::styled(
+            format!(" ◎ {} ", run.summary()),
+            Style::default().fg(color).add_modifier(Modifier::BOLD),
+        )))
+    }
+
+    fn draw_agent_card(
+        &self,
+        frame: &mut Frame<'_>,
+        area: Rect,
+        window: &AgentWindow,
+        selected: bool,
+    ) {
+        let theme = theme::current();
+        let (status_icon, status_color) = match window.status {
+            AgentWindowStatus::Thinking => (self.spinner_frame(), theme.warning),
+            AgentWindowStatus::Done if critic_flagged(window) => ("⚑", theme.warning),
+            AgentWindowStatus::Done => ("✓", theme.success),
+            AgentWindowStatus::WaitingForInput => ("◈", theme.attention),
+            AgentWindowStatus::TimedOut => ("⏱", theme.error),
+            AgentWindowStatus::Killed => ("✗", theme.error),
+        };
+        let accent = self.accent_color(window.id);
+        let (border_type, title_style) = if selected {
+            (
+                BorderType::Thick,
+                Style::default().fg(accent).add_modifier(Modifier::BOLD),
+            )
+        } else {
+            (BorderType::Plain, Style::default().fg(status_color))
+        };
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_type(border_type)
+            .border_style(Style::default().fg(if selected { accent } else { status_color }))
+            .title(Span::styled(
+                format!(" #{} {} {} ", window.id, status_icon, window.label),
+                title_style,
+            ));
+
+        let body_height = area.height.saturating_sub(3) as usize;
+        let mut lines = vec![Line::from(Span::styled(
+            format!(" {}", window.prompt.lines().next().unwrap_or("")),
+            Style::default().fg(theme.subtle),
+        ))];
+        let skip = window.output_lines.len().saturating_sub(body_height);
+        lines.extend(window.output_lines.iter().skip(skip).map(|line| {
+            Line::from(Span::styled(
+                format!(" {line}"),
+                Style::default().fg(output_line_color(line, &theme)),
+            ))
+        }));
         frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
     }
 
@@ -397,72 +706,12 @@ impl App {
         self.draw_status_bar(frame, rows[0]);
 
         // ── Agent output (full width) ────────────────────────────────
-        if let Some(window) = self.agent_windows.iter().find(|w| w.id == window_id) {
-            let accent = self.accent_color(window.id);
-
-            let (status_label, status_color) = match window.status {
-                AgentWindowStatus::Thinking => {
-                    (format!("{} thinking…", self.spinner_frame()), Color::Yellow)
-                }
-                AgentWindowStatus::Done => ("✓ done".to_string(), Color::Rgb(0, 255, 136)),
-                AgentWindowStatus::WaitingForInput => {
-                    ("◈ needs input".to_string(), Color::Rgb(255, 105, 180))
-                }
-            };
-
-            let title = format!(
-                " #{} {} — {} [Esc: back] ",
-                window.id, window.label, status_label
-            );
-
-            let inner_height = rows[1].height.saturating_sub(2) as usize;
-            let display_lines: Vec<Line> = window
-                .output_lines
-                .iter()
-                .rev()
-                .take(inner_height.max(1))
-                .collect::<Vec<_>>()
-                .into_iter()
-                .rev()
-                .map(|s| {
-                    let color = if s.starts_with(">>") {
-                        Color::Rgb(255, 105, 180)
-                    } else if s.starts_with("--") {
-                        Color::Rgb(80, 80, 80)
-                    } else if s.starts_with("Thinking")
-                        || s.starts_with("Recalling")
-                        || s.starts_with("Saving")
-                        || s.starts_with("Found")
-                    {
-                        Color::Yellow
-                    } else {
-                        Color::White
-                    };
-                    Line::from(Span::styled(format!(" {s}"), Style::default().fg(color)))
-                })
-                .collect();
-
-            let panel = Paragraph::new(Text::from(display_lines))
-                .block(
-                    Block::default()
-                        .borders(Borders::ALL)
-                        .border_style(Style::default().fg(accent))
-                        .title(Span::styled(
-                            title,
-                            Style::default()
-                                .fg(status_color)
-                                .add_modifier(Modifier::BOLD),
-                        )),
-                )
-                .wrap(Wrap { trim: false });
-            frame.render_widget(panel, rows[1]);
-        } else {
-            // Window no longer exists — show message.
-            let msg = Paragraph::new("Agent window not found. Press Esc to return.")
-                .alignment(Alignment::Center)
-                .block(Block::default().borders(Borders::ALL));
-            frame.render_widget(msg, rows[1]);
-        }
+        self.draw_agent_output(
+            frame,
+            rows[1],
+            window_id,
+            "PgUp/PgDn: scroll · Ctrl+O: output mode · Ctrl+T: reasoning · Esc: back",
+        );
 
         // ── Input prompt ─────────────────────────────────────────────
         let prompt_label = if self
@@ -479,7 +728,16 @@ impl App {
         let input_inner_width = rows[2].width.saturating_sub(2);
         let (scroll_y, scroll_x, cursor_x_raw, cursor_y_raw) =
             self.input_scroll_state(input_inner_width, input_inner_height);
-        let input_panel = Paragraph::new(self.input.as_str())
+        let search_input = self
+            .output_view
+            .as_ref()
+            .and_then(|view| view.search_input.as_ref())
+            .map(|query| format!("/{query}"));
+        let (input_text, prompt_label) = match &search_input {
+            Some(query) => (query.as_str(), " Search output ".to_string()),
+            None => (self.input.as_str(), prompt_label),
+        };
+        let input_panel = Paragraph::new(input_text)
             .scroll((scroll_y, scroll_x))
             .wrap(Wrap { trim: false })
             .block(
@@ -489,6 +747,7 @@ impl App {
                     .border_style(Style::default().fg(self.accent_color(window_id))),
             );
         frame.render_widget(input_panel, rows[2]);
+        self.draw_completion_popup(frame, rows[2]);
 
         let input_width = rows[2].width.saturating_sub(2);
         let cursor_x = if input_width == 0 {
@@ -510,9 +769,129 @@ impl App {
         self.draw_footer(frame, rows[3]);
     }
 
+    /// Streamed output panel for one agent window (session view and
+    /// split pane).  `hint` is the key help shown in the title.
+    fn draw_agent_output(&self, frame: &mut Frame<'_>, area: Rect, window_id: usize, hint: &str) {
+        let theme = theme::current();
+        if let Some(window) = self.agent_windows.iter().find(|w| w.id == window_id) {
+            let accent = self.accent_color(window.id);
+
+            let (status_label, status_color) = match window.status {
+                AgentWindowStatus::Thinking => (
+                    format!("{} {}", self.spinner_frame(), t("thinking…")),
+                    theme.warning,
+                ),
+                AgentWindowStatus::Done if critic_flagged(window) => {
+                    (format!("⚑ {}", t("done, flagged")), theme.warning)
+                }
+                AgentWindowStatus::Done => (format!("✓ {}", t("done")), theme.success),
+                AgentWindowStatus::WaitingForInput => {
+                    (format!("◈ {}", t("needs input")), theme.attention)
+                }
+                AgentWindowStatus::TimedOut => (format!("⏱ {}", t("timed out")), theme.error),
+                AgentWindowStatus::Killed => (format!("✗ {}", t("killed")), theme.error),
+            };
+            let status_label = match &window.running_command {
+                Some(running) => format!(
+                    "{status_label} · running `{}` ({} out, {} err · /cancel {})",
+                    truncate_command(&running.command),
+                    running.stdout_lines,
+                    running.stderr_lines,
+                    window.id
+                ),
+                None => status_label,
+            };
+
+            let output_view = self
+                .output_view
+                .as_ref()
+                .filter(|view| view.window_id == window.id);
+            let title = if output_view.is_some() {
+                format!(
+                    " #{} {} — {} [j/k move · v select · / search · y copy · t reasoning · Esc exit] ",
+                    window.id, window.label, status_label
+                )
+            } else {
+                format!(
+                    " #{} {} — {} [{hint}] ",
+                    window.id, window.label, status_label
+                )
+            };
+
+            let inner_height = area.height.saturating_sub(2) as usize;
+            let toggle_key = if output_view.is_some() { "t" } else { "Ctrl+T" };
+            let reasoning = reasoning_section(window, &theme, inner_height / 3, toggle_key);
+            let visible = inner_height.saturating_sub(reasoning.len()).max(1);
+            // Rendered markdown tail by default; raw lines around the
+            // cursor in output mode so selections map 1:1 to the source.
+            let display_lines: Vec<Line> = match output_view {
+                Some(view) => {
+                    let total = window.output_lines.len();
+                    let start = view
+                        .cursor
+                        .saturating_sub(visible / 2)
+                        .min(total.saturating_sub(visible));
+                    window
+                        .output_lines
+                        .iter()
+                        .enumerate()
+                        .skip(start)
+                        .take(visible)
+                        .map(|(idx, s)| {
+                            let mut style = Style::default().fg(output_line_color(s, &theme));
+                            if view.matches(s) {
+                                style = style.fg(theme.match_fg).bg(theme.match_bg);
+                            }
+                            if view.is_selected(idx) {
+                                style = style.bg(theme.selection_bg);
+                            }
+                            if idx == view.cursor {
+                                style = style.add_modifier(Modifier::REVERSED);
+                            }
+                            Line::from(Span::styled(format!(" {s}"), style))
+                        })
+                        .collect()
+                }
+                None => {
+                    let rendered = render_agent_output(window.visible_output(), &theme);
+                    let bottom = rendered.len().saturating_sub(window.scroll as usize);
+                    let bottom = bottom.max(visible.min(rendered.len()));
+                    let skip = bottom.saturating_sub(visible);
+                    rendered.into_iter().take(bottom).skip(skip).collect()
+                }
+            };
+
+            let display_lines: Vec<Line> = reasoning.into_iter().chain(display_lines).collect();
+            let mut block = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(accent))
+                .title(Span::styled(
+                    title,
+                    Style::default()
+                        .fg(status_color)
+                        .add_modifier(Modifier::BOLD),
+                ));
+            if output_view.is_none() && window.scroll > 0 {
+                block =
+                    block.title_bottom(new_below_indicator(window.unread_lines(), "lines", &theme));
+            }
+            let panel = Paragraph::new(Text::from(display_lines))
+                .block(block)
+                .wrap(Wrap { trim: false });
+            frame.render_widget(panel, area);
+        } else {
+            // Window no longer exists — show message.
+            let msg = Paragraph::new("Agent window not found.")
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            frame.render_widget(msg, area);
+        }
+    }
+
     // ── Status bar ───────────────────────────────────────────────────
 
     fn draw_status_bar(&self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
         let thread_turns = self.conversation_thread.len() / 2;
         let daemon_count = self.daemon_handles.len();
         let window_count = self.agent_windows.len();
@@ -531,46 +910,56 @@ impl App {
             Span::styled(
                 " ◆ ",
                 Style::default()
-                    .fg(Color::Rgb(0, 255, 136))
+                    .fg(theme.success)
                     .add_modifier(Modifier::BOLD),
             ),
             Span::styled(
                 self.active_agent.name.clone(),
                 Style::default()
-                    .fg(Color::Rgb(138, 43, 226))
+                    .fg(theme.brand)
                     .add_modifier(Modifier::BOLD),
             ),
         ];
-        if let Some(ws) = &self.rice.shared_run_id {
+        if super::halt::active() {
             spans.push(Span::styled(
-                "  ⊞ ",
-                Style::default().fg(Color::Rgb(80, 80, 80)),
+                "  ⏹ halted",
+                Style::default()
+                    .fg(theme.error)
+                    .add_modifier(Modifier::BOLD),
             ));
+        }
+        if crate::rice::incognito() {
             spans.push(Span::styled(
-                ws.clone(),
-                Style::default().fg(Color::Rgb(0, 255, 136)),
+                "  🕶 incognito",
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
             ));
         }
+        if let Some(ws) = &self.rice.shared_run_id {
+            spans.push(Span::styled("  ⊞ ", Style::default().fg(theme.muted)));
+            spans.push(Span::styled(ws.clone(), Style::default().fg(theme.success)));
+        }
         spans.extend([
-            Span::styled("  ⚡ ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled("  ⚡ ", Style::default().fg(theme.muted)),
             Span::styled(
                 self.mcp_status_label(),
                 Style::default().fg(self.mcp_status_color()),
             ),
-            Span::styled("  ⬡ ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled("  ⬡ ", Style::default().fg(theme.muted)),
             Span::styled(
                 self.rice.status_label(),
                 Style::default().fg(self.rice_status_color()),
             ),
             Span::styled(
                 format!("  ↩ {thread_turns}"),
-                Style::default().fg(Color::Rgb(100, 100, 100)),
+                Style::default().fg(theme.subtle),
             ),
         ]);
         if daemon_count > 0 {
             spans.push(Span::styled(
                 format!("  ⚙ {daemon_count}"),
-                Style::default().fg(Color::Yellow),
+                Style::default().fg(theme.warning),
             ));
         }
         if window_count > 0 {
@@ -584,36 +973,60 @@ impl App {
             }
             spans.push(Span::styled(
                 agent_label,
-                Style::default().fg(Color::Rgb(0, 210, 255)),
+                Style::default().fg(theme.primary),
             ));
         }
-        if self.chat_busy {
+        if let Some(summary) = progress::summary() {
+            spans.push(Span::styled(
+                format!("  {} {summary}", self.spinner_frame()),
+                Style::default()
+                    .fg(theme.warning)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        } else if self.chat_busy {
             spans.push(Span::styled(
                 format!("  {} Thinking…", self.spinner_frame()),
                 Style::default()
-                    .fg(Color::Yellow)
+                    .fg(theme.warning)
                     .add_modifier(Modifier::BOLD),
             ));
         }
+        if !self.chat_queue.is_empty() {
+            spans.push(Span::styled(
+                format!("  ⧗ {} queued", self.chat_queue.len()),
+                Style::default().fg(theme.attention),
+            ));
+        }
         frame.render_widget(Paragraph::new(Line::from(spans)), area);
     }
 
     // ── Activity log ─────────────────────────────────────────────────
 
     fn draw_activity_log(&mut self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
         let inner_width = area.width.saturating_sub(2);
         let inner_height = area.height.saturating_sub(2) as usize;
 
-        let log_lines: Vec<Line> = self.logs.iter().flat_map(|l| l.render()).collect();
-        let log_paragraph = Paragraph::new(Text::from(log_lines)).wrap(Wrap { trim: false });
-
-        let total_visual = log_paragraph.line_count(inner_width);
-        let max_scroll = total_visual.saturating_sub(inner_height);
-
-        if (self.scroll_offset as usize) > max_scroll {
-            self.scroll_offset = max_scroll as u16;
+        // Scroll lock: while scrolled up, new entries push the offset up
+        // by their height so the rows being read stay put.
+        let pushed = self.logs.pushed();
+        if self.scroll_offset > 0 && pushed > self.log_seen {
+            let rows = self.logs.rows_after(self.log_seen, inner_width);
+            self.scroll_offset = self.scroll_offset.saturating_add(rows);
+            self.log_unread += pushed - self.log_seen;
+        }
+        self.log_seen = pushed;
+
+        let slice = self
+            .logs
+            .visible_slice(inner_width, inner_height, self.scroll_offset);
+        let top_row = slice.top_row;
+        let scroll_offset = slice.scroll_offset;
+        let log_paragraph = Paragraph::new(Text::from(slice.lines)).wrap(Wrap { trim: false });
+        self.scroll_offset = scroll_offset;
+        if self.scroll_offset == 0 {
+            self.log_unread = 0;
         }
-        let top_row = max_scroll.saturating_sub(self.scroll_offset as usize) as u16;
 
         let scroll_indicator = if self.scroll_offset > 0 {
             format!(" ◆ memini by ag\\i [↑{}] ", self.scroll_offset)
@@ -621,19 +1034,19 @@ impl App {
             " ◆ memini by ag\\i ".to_string()
         };
 
-        let panel = log_paragraph
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(Style::default().fg(Color::Rgb(60, 60, 60)))
-                    .title(Span::styled(
-                        scroll_indicator,
-                        Style::default()
-                            .fg(Color::Rgb(0, 255, 136))
-                            .add_modifier(Modifier::BOLD),
-                    )),
-            )
-            .scroll((top_row, 0));
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(theme.border))
+            .title(Span::styled(
+                scroll_indicator,
+                Style::default()
+                    .fg(theme.success)
+                    .add_modifier(Modifier::BOLD),
+            ));
+        if self.scroll_offset > 0 {
+            block = block.title_bottom(new_below_indicator(self.log_unread, "messages", &theme));
+        }
+        let panel = log_paragraph.block(block).scroll((top_row, 0));
         frame.render_widget(panel, area);
     }
 
@@ -671,12 +1084,13 @@ impl App {
     }
 
     fn mcp_status_color(&self) -> Color {
+        let theme = theme::current();
         if !self.mcp_connections.is_empty() {
-            Color::Rgb(0, 255, 136)
+            theme.success
         } else if self.active_mcp.is_some() {
-            Color::Yellow
+            theme.warning
         } else {
-            Color::Rgb(80, 80, 80)
+            theme.muted
         }
     }
 
@@ -690,81 +1104,305 @@ impl App {
 
     #[allow(dead_code)]
     fn openai_status_color(&self) -> Color {
+        let theme = theme::current();
         if self.openai_key_hint.is_some() {
-            Color::Rgb(0, 255, 136)
+            theme.success
         } else {
-            Color::Rgb(80, 80, 80)
+            theme.muted
         }
     }
 
     fn rice_status_color(&self) -> Color {
+        let theme = theme::current();
         match self.rice.status {
-            RiceStatus::Connected => Color::Rgb(0, 255, 136),
-            RiceStatus::Disabled(_) => Color::Rgb(80, 80, 80),
+            RiceStatus::Connected => theme.success,
+            RiceStatus::Disabled(_) => theme.muted,
         }
     }
 
     // ── Footer bar ───────────────────────────────────────────────────
 
     fn draw_footer(&self, frame: &mut Frame<'_>, area: Rect) {
+        let theme = theme::current();
         let keys = vec![
             Span::styled(
                 " /help",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" commands  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" commands  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "Tab",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" cycle  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" cycle  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "Enter",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" send/open  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" send/open  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "Alt+Enter",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" newline  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" newline  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "PgUp/PgDn",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" log scroll  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" log scroll  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "Esc",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" back  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" back  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "Ctrl+C",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" quit  ", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" quit  ", Style::default().fg(theme.muted)),
             Span::styled(
                 "/rice setup",
                 Style::default()
-                    .fg(Color::Rgb(0, 210, 255))
+                    .fg(theme.primary)
                     .add_modifier(Modifier::BOLD),
             ),
-            Span::styled(" configure", Style::default().fg(Color::Rgb(80, 80, 80))),
+            Span::styled(" configure", Style::default().fg(theme.muted)),
         ];
         frame.render_widget(Paragraph::new(Line::from(keys)), area);
     }
 }
+
+/// Grid columns × rows for `count` cards in a `width`×`height` pane.
+fn grid_dims(width: u16, height: u16, count: usize) -> (usize, usize) {
+    let max_cols = (width / MIN_CARD_WIDTH).max(1) as usize;
+    let max_rows = (height / MIN_CARD_HEIGHT).max(1) as usize;
+    let cols = max_cols.min(count.max(1));
+    let rows = max_rows.min(count.div_ceil(cols).max(1));
+    (cols, rows)
+}
+
+/// Whether the `[critic]` flagged the window's last result.
+fn critic_flagged(window: &AgentWindow) -> bool {
+    window
+        .critique
+        .as_ref()
+        .is_some_and(|critique| !critique.passed)
+}
+
+/// `command` on one line, cut to 40 characters for a window title.
+fn truncate_command(command: &str) -> String {
+    let command = command.replace('\n', " ");
+    if command.chars().count() <= 40 {
+        return command;
+    }
+    let cut: String = command.chars().take(40).collect();
+    format!("{cut}…")
+}
+
+/// The dimmed reasoning header of an agent window, followed by the last
+/// `max_lines` summary lines when expanded.  Empty without reasoning.
+fn reasoning_section(
+    window: &AgentWindow,
+    theme: &Theme,
+    max_lines: usize,
+    toggle_key: &str,
+) -> Vec<Line<'static>> {
+    let summary: Vec<&String> = window
+        .reasoning
+        .iter()
+        .rev()
+        .skip_while(|line| line.is_empty())
+        .collect();
+    if summary.is_empty() {
+        return Vec::new();
+    }
+    let dim = Style::default()
+        .fg(theme.dim)
+        .add_modifier(Modifier::ITALIC);
+    if !window.show_reasoning {
+        return vec![Line::from(Span::styled(
+            format!(" ▸ reasoning · {} lines ({toggle_key})", summary.len()),
+            dim,
+        ))];
+    }
+    let shown = max_lines.saturating_sub(1).max(1).min(summary.len());
+    let mut lines = vec![Line::from(Span::styled(
+        format!(" ▾ reasoning ({toggle_key} to hide)"),
+        dim,
+    ))];
+    lines.extend(
+        summary[..shown]
+            .iter()
+            .rev()
+            .map(|line| Line::from(Span::styled(format!(" │ {line}"), dim))),
+    );
+    lines
+}
+
+/// Render agent output: status lines keep their prefix colors, runs of
+/// everything else are rendered as markdown.
+/// Bottom-border hint of a scrolled-up view: how much arrived below and
+/// how to get back down.
+fn new_below_indicator(unread: usize, noun: &str, theme: &Theme) -> Line<'static> {
+    let (text, color) = match unread {
+        0 => (" End: back to bottom ".to_string(), theme.muted),
+        count => (
+            format!(" ▼ {count} new {noun} below · End to jump "),
+            theme.attention,
+        ),
+    };
+    Line::from(Span::styled(
+        text,
+        Style::default().fg(color).add_modifier(Modifier::BOLD),
+    ))
+    .right_aligned()
+}
+
+pub(super) fn render_agent_output(output: &[String], theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+    let mut in_diff = false;
+    for raw in output {
+        // File-write diffs are colored line by line instead of going
+        // through the markdown renderer.
+        if !in_fence && raw.trim_start() == "```diff" {
+            in_diff = true;
+            if !block.is_empty() {
+                lines.extend(indent(markdown::render(&block.join("\n"))));
+                block.clear();
+            }
+            continue;
+        }
+        if in_diff {
+            if raw.trim_start() == "```" {
+                in_diff = false;
+            } else {
+                lines.push(Line::from(Span::styled(
+                    format!(" {raw}"),
+                    Style::default().fg(diff_line_color(raw, theme)),
+                )));
+            }
+            continue;
+        }
+        if raw.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+        }
+        let status_color = output_status_color(raw, theme);
+        if in_fence || raw.trim_start().starts_with("```") {
+            block.push(raw);
+            continue;
+        }
+        let Some(color) = status_color else {
+            block.push(raw);
+            continue;
+        };
+        if !block.is_empty() {
+            lines.extend(indent(markdown::render(&block.join("\n"))));
+            block.clear();
+        }
+        lines.push(Line::from(Span::styled(
+            format!(" {raw}"),
+            Style::default().fg(color),
+        )));
+    }
+    if !block.is_empty() {
+        lines.extend(indent(markdown::render(&block.join("\n"))));
+    }
+    lines
+}
+
+fn indent(lines: Vec<Line<'static>>) -> impl Iterator<Item = Line<'static>> {
+    lines.into_iter().map(|mut line| {
+        line.spans.insert(0, Span::raw(" "));
+        line
+    })
+}
+
+/// Color for agent status lines, keyed off their prefix; `None` for body text.
+fn output_status_color(line: &str, theme: &Theme) -> Option<Color> {
+    if line.starts_with(">>") {
+        Some(theme.attention)
+    } else if line.starts_with("--") {
+        Some(theme.muted)
+    } else if line.starts_with("Δ ") {
+        Some(theme.info)
+    } else if line.starts_with("Thinking")
+        || line.starts_with("Recalling")
+        || line.starts_with("Saving")
+        || line.starts_with("Found")
+    {
+        Some(theme.warning)
+    } else {
+        None
+    }
+}
+
+/// Foreground color for a line inside a ```` ```diff ```` fence.
+fn diff_line_color(line: &str, theme: &Theme) -> Color {
+    if line.starts_with("@@") {
+        theme.muted
+    } else if line.starts_with('+') {
+        theme.success
+    } else if line.starts_with('-') {
+        theme.error
+    } else {
+        theme.text
+    }
+}
+
+/// Foreground color for a raw agent output line.
+fn output_line_color(line: &str, theme: &Theme) -> Color {
+    output_status_color(line, theme).unwrap_or(theme.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grid_adapts_to_pane_and_count() {
+        // Wide pane, few windows: one row, no empty cards.
+        assert_eq!(grid_dims(120, 30, 3), (3, 1));
+        // Ten windows fit on a single 4×3 page.
+        assert_eq!(grid_dims(120, 30, 10), (4, 3));
+        // Small pane pages: 2×2 cards per page.
+        assert_eq!(grid_dims(60, 10, 10), (2, 2));
+        // Tiny pane still shows one card.
+        assert_eq!(grid_dims(10, 3, 5), (1, 1));
+    }
+
+    #[test]
+    fn diff_fences_are_colored_per_line() {
+        let theme = Theme::default();
+        let output: Vec<String> = [
+            "Δ a.txt changed (+1 -1)",
+            "```diff",
+            "@@ -1 +1 @@",
+            "-old",
+            "+new",
+            "```",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        let lines = render_agent_output(&output, &theme);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[2].spans[0].style.fg, Some(theme.error));
+        assert_eq!(lines[3].spans[0].style.fg, Some(theme.success));
+    }
+}
@@ -0,0 +1,203 @@
+//! Microphone capture for `/voice` dictation.
+//!
+//! Audio is recorded from the default input device on a dedicated thread
+//! (cpal streams are not `Send`), downmixed to mono 16-bit PCM and wrapped
+//! in a WAV container for the OpenAI transcription endpoint.  Capture needs
+//! the `voice` feature (on by default).
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+
+use anyhow::{Result, anyhow};
+
+/// Recordings stop on their own after this long, keeping the upload well
+/// under the transcription endpoint's 25 MB limit.
+pub const MAX_RECORDING_SECS: u64 = 240;
+
+/// A recording in progress; [`Recorder::finish`] stops it.
+pub struct Recorder {
+    stop: Arc<AtomicBool>,
+    handle: JoinHandle<Result<Vec<u8>>>,
+}
+
+impl Recorder {
+    /// Stop recording and return the audio as a WAV file.
+    pub fn finish(self) -> Result<Vec<u8>> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .join()
+            .map_err(|_| anyhow!("recording thread panicked"))?
+    }
+
+    /// Whether the recording stopped on its own (time limit or error).
+    pub fn is_finished(&self) -> bool {
+        self.handle.is_finished()
+    }
+}
+
+/// Start recording from the default microphone.
+#[cfg(feature = "voice")]
+pub fn start() -> Result<Recorder> {
+    use std::sync::Mutex;
+    use std::sync::mpsc;
+    use std::time::{Duration, Instant};
+
+    use anyhow::{Context, bail};
+    use cpal::SampleFormat;
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let (ready_tx, ready_rx) = mpsc::channel::<Result<()>>();
+
+    let handle = std::thread::spawn(move || -> Result<Vec<u8>> {
+        let samples: Arc<Mutex<Vec<i16>>> = Arc::new(Mutex::new(Vec::new()));
+        let setup = || -> Result<(cpal::Stream, u32)> {
+            let device = cpal::default_host()
+                .default_input_device()
+                .context("no microphone found")?;
+            let config = device
+                .default_input_config()
+                .context("read microphone config")?;
+            let sample_rate = config.sample_rate().0;
+            let channels = config.channels() as usize;
+            // Printing would corrupt the TUI; a broken stream just yields
+            // a short or silent recording.
+            let on_error = |_err: cpal::StreamError| {};
+            let sink = samples.clone();
+            let push = move |mono: Vec<i16>| {
+                if let Ok(mut buffer) = sink.lock() {
+                    buffer.extend(mono);
+                }
+            };
+            let stream = match config.sample_format() {
+                SampleFormat::F32 => device.build_input_stream(
+                    &config.into(),
+                    move |data: &[f32], _: &_| {
+                        push(downmix(data, channels, |s: f32| {
+                            (s.clamp(-1.0, 1.0) * 32767.0) as i16
+                        }))
+                    },
+                    on_error,
+                    None,
+                ),
+                SampleFormat::I16 => device.build_input_stream(
+                    &config.into(),
+                    move |data: &[i16], _: &_| push(downmix(data, channels, |s: i16| s)),
+                    on_error,
+                    None,
+                ),
+                SampleFormat::U16 => device.build_input_stream(
+                    &config.into(),
+                    move |data: &[u16], _: &_| {
+                        push(downmix(data, channels, |s: u16| (s as i32 - 32768) as i16))
+                    },
+                    on_error,
+                    None,
+                ),
+                other => bail!("unsupported microphone sample format {other:?}"),
+            }
+            .context("open microphone stream")?;
+            stream.play().context("start microphone stream")?;
+            Ok((stream, sample_rate))
+        };
+
+        let (stream, sample_rate) = match setup() {
+            Ok(started) => {
+                let _ = ready_tx.send(Ok(()));
+                started
+            }
+            Err(err) => {
+                let message = format!("{err:#}");
+                let _ = ready_tx.send(Err(err));
+                bail!(message);
+            }
+        };
+
+        let started = Instant::now();
+        while !thread_stop.load(Ordering::Relaxed)
+            && started.elapsed() < Duration::from_secs(MAX_RECORDING_SECS)
+        {
+            std::thread::sleep(Duration::from_millis(50));
+        }
+        drop(stream);
+
+        let samples = samples
+            .lock()
+            .map_err(|_| anyhow!("recording buffer poisoned"))?;
+        Ok(encode_wav(&samples, sample_rate))
+    });
+
+    match ready_rx.recv() {
+        Ok(Ok(())) => Ok(Recorder { stop, handle }),
+        Ok(Err(err)) => Err(err),
+        Err(_) => Err(anyhow!("recording thread exited before starting")),
+    }
+}
+
+#[cfg(not(feature = "voice"))]
+pub fn start() -> Result<Recorder> {
+    Err(anyhow!("memini was built without the `voice` feature"))
+}
+
+/// Average interleaved frames down to one channel.
+#[cfg(feature = "voice")]
+fn downmix<T: Copy>(data: &[T], channels: usize, to_i16: impl Fn(T) -> i16) -> Vec<i16> {
+    data.chunks(channels.max(1))
+        .map(|frame| {
+            let sum: i32 = frame.iter().map(|sample| to_i16(*sample) as i32).sum();
+            (sum / frame.len() as i32) as i16
+        })
+        .collect()
+}
+
+/// Wrap mono 16-bit PCM in a WAV container.
+pub fn encode_wav(samples: &[i16], sample_rate: u32) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for sample in samples {
+        wav.extend_from_slice(&sample.to_le_bytes());
+    }
+    wav
+}
+
+/// Seconds of audio in a WAV produced by [`encode_wav`].
+pub fn wav_duration_secs(wav: &[u8]) -> f64 {
+    let Some(rate) = wav.get(24..28) else {
+        return 0.0;
+    };
+    let rate = u32::from_le_bytes([rate[0], rate[1], rate[2], rate[3]]);
+    if rate == 0 {
+        return 0.0;
+    }
+    wav.len().saturating_sub(44) as f64 / (rate as f64 * 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_mono_pcm_wav() {
+        let wav = encode_wav(&[0, 1, -1, i16::MAX], 16_000);
+        assert_eq!(&wav[..4], b"RIFF");
+        assert_eq!(&wav[8..16], b"WAVEfmt ");
+        assert_eq!(wav.len(), 44 + 8);
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 8);
+        assert_eq!(&wav[46..48], &1i16.to_le_bytes());
+        assert!((wav_duration_secs(&encode_wav(&[0; 8_000], 16_000)) - 0.5).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,226 @@
+//! Local webhook listener that lets external services wake daemons.
+//!
+//! When `MEMINI_WEBHOOK_PORT` is set, a small `tiny_http` server listens on
+//! `127.0.0.1:<port>`.  A `POST /hook/<task-name>` carrying the task's
+//! secret (as `?secret=` or an `X-Memini-Secret` header) is forwarded to the
+//! TUI as an [`AgentEvent::WebhookFired`] and the request body becomes the
+//! trigger context for that run.  Bodies over `MAX_WEBHOOK_BODY_BYTES` are
+//! refused with 413 rather than cut short.
+//!
+//! Per-task secrets live in a JSON file next to the local MCP store so that
+//! generated URLs survive restarts.  The file is read once; `/hooks` edits
+//! go through [`persist_webhook_store`], which also updates the cached copy
+//! the listener checks requests against.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Context, Result, anyhow};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use directories::ProjectDirs;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use tiny_http::{Header, Method, Response, Server};
+use tokio::sync::mpsc;
+use url::Url;
+
+use crate::constants::{APP_NAME, MAX_WEBHOOK_BODY_BYTES};
+use crate::util::constant_time_eq;
+
+use super::daemon::AgentEvent;
+
+/// Header alternative to the `?secret=` query parameter.
+const SECRET_HEADER: &str = "X-Memini-Secret";
+
+/// Persisted webhook secrets, keyed by lower-cased task name.
+#[derive(Default, Serialize, Deserialize, Clone)]
+pub struct WebhookStore {
+    pub secrets: HashMap<String, String>,
+}
+
+impl WebhookStore {
+    /// Look up the secret for a task (case-insensitive).
+    pub fn secret_for(&self, task_name: &str) -> Option<&str> {
+        self.secrets
+            .get(&task_name.to_ascii_lowercase())
+            .map(String::as_str)
+    }
+}
+
+/// Returns the platform-specific path for the webhook secret file.
+fn webhook_store_path() -> Option<PathBuf> {
+    ProjectDirs::from("com", APP_NAME, APP_NAME).map(|dirs| dirs.config_dir().join("webhooks.json"))
+}
+
+/// The process-wide copy of the secret file, read on first use.
+fn cached_store() -> &'static RwLock<WebhookStore> {
+    static STORE: OnceLock<RwLock<WebhookStore>> = OnceLock::new();
+    STORE.get_or_init(|| RwLock::new(read_webhook_store()))
+}
+
+/// Read webhook secrets from disk, falling back to an empty store.
+fn read_webhook_store() -> WebhookStore {
+    let Some(path) = webhook_store_path() else {
+        return WebhookStore::default();
+    };
+    let Ok(contents) = fs::read_to_string(&path) else {
+        return WebhookStore::default();
+    };
+    serde_json::from_str(&contents).unwrap_or_default()
+}
+
+/// Current webhook secrets.
+pub fn load_webhook_store() -> WebhookStore {
+    cached_store()
+        .read()
+        .map(|store| store.clone())
+        .unwrap_or_default()
+}
+
+/// Persist webhook secrets to disk and replace the cached copy.
+pub fn persist_webhook_store(store: &WebhookStore) -> Result<()> {
+    if let Ok(mut cached) = cached_store().write() {
+        *cached = store.clone();
+    }
+    let Some(path) = webhook_store_path() else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create config dir")?;
+    }
+    let contents = serde_json::to_string_pretty(store).context("serialize webhook store")?;
+    fs::write(&path, contents).context("write webhook store")?;
+    Ok(())
+}
+
+/// Generate a fresh URL-safe secret.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// Full URL an external service should POST to for `task_name`.
+pub fn hook_url(port: u16, task_name: &str, secret: &str) -> String {
+    format!("http://127.0.0.1:{port}/hook/{task_name}?secret={secret}")
+}
+
+/// Bind the listener and spawn a blocking thread that serves requests.
+pub fn spawn_listener(port: u16, tx: mpsc::UnboundedSender<AgentEvent>) -> Result<()> {
+    let server = Server::http(("127.0.0.1", port))
+        .map_err(|err| anyhow!("bind webhook listener on port {port}: {err}"))?;
+    std::thread::spawn(move || {
+        for mut request in server.incoming_requests() {
+            let (status, message) = match accept_request(&mut request) {
+                Ok((task_name, body)) => {
+                    let _ = tx.send(AgentEvent::WebhookFired { task_name, body });
+                    (202, "accepted")
+                }
+                Err((status, message)) => (status, message),
+            };
+            let _ = request.respond(Response::from_string(message).with_status_code(status));
+        }
+    });
+    Ok(())
+}
+
+/// Validate a request and extract `(task_name, body)`, or an HTTP error.
+fn accept_request(
+    request: &mut tiny_http::Request,
+) -> std::result::Result<(String, String), (u16, &'static str)> {
+    if *request.method() != Method::Post {
+        return Err((405, "method not allowed"));
+    }
+    let url = Url::parse(&format!("http://localhost{}", request.url()))
+        .map_err(|_| (400, "bad request"))?;
+    let Some(task_name) = parse_hook_path(url.path()) else {
+        return Err((404, "not found"));
+    };
+
+    let provided = url
+        .query_pairs()
+        .find(|(key, _)| key == "secret")
+        .map(|(_, value)| value.to_string())
+        .or_else(|| header_value(request.headers(), SECRET_HEADER));
+
+    let expected = cached_store()
+        .read()
+        .ok()
+        .and_then(|store| store.secret_for(&task_name).map(str::to_string));
+    let Some(expected) = expected else {
+        return Err((404, "no webhook configured for this task"));
+    };
+    let matches = provided
+        .as_deref()
+        .is_some_and(|provided| constant_time_eq(provided.as_bytes(), expected.as_bytes()));
+    if !matches {
+        return Err((401, "invalid secret"));
+    }
+
+    if request
+        .body_length()
+        .is_some_and(|length| length > MAX_WEBHOOK_BODY_BYTES)
+    {
+        return Err((413, "payload too large"));
+    }
+    let mut raw = Vec::new();
+    request
+        .as_reader()
+        .take(MAX_WEBHOOK_BODY_BYTES as u64 + 1)
+        .read_to_end(&mut raw)
+        .map_err(|_| (400, "could not read body"))?;
+    if raw.len() > MAX_WEBHOOK_BODY_BYTES {
+        return Err((413, "payload too large"));
+    }
+    Ok((task_name, String::from_utf8_lossy(&raw).to_string()))
+}
+
+fn header_value(headers: &[Header], name: &str) -> Option<String> {
+    headers
+        .iter()
+        .find(|header| header.field.equiv(name))
+        .map(|header| header.value.as_str().to_string())
+}
+
+/// Extract the task name from `/hook/<task-name>`.
+fn parse_hook_path(path: &str) -> Option<String> {
+    let name = path.strip_prefix("/hook/")?.trim_end_matches('/');
+    if name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_hook_path_extracts_task_name() {
+        assert_eq!(
+            parse_hook_path("/hook/repo-watch").as_deref(),
+            Some("repo-watch")
+        );
+        assert_eq!(
+            parse_hook_path("/hook/repo-watch/").as_deref(),
+            Some("repo-watch")
+        );
+        assert_eq!(parse_hook_path("/hook/"), None);
+        assert_eq!(parse_hook_path("/hook/a/b"), None);
+        assert_eq!(parse_hook_path("/other/a"), None);
+    }
+
+    #[test]
+    fn secret_lookup_is_case_insensitive() {
+        let mut store = WebhookStore::default();
+        store
+            .secrets
+            .insert("repo-watch".to_string(), "s3cret".to_string());
+        assert_eq!(store.secret_for("Repo-Watch"), Some("s3cret"));
+        assert_eq!(store.secret_for("other"), None);
+    }
+}
@@ -0,0 +1,185 @@
+//! Run limits for agent windows: a maximum runtime per run, `/kill`, and
+//! `/cancel` for the shell command a run is waiting on.
+//!
+//! Every window run is wrapped in [`supervise`], which races the run
+//! against the window's timeout, its kill switch and `/halt`.  The timeout comes
+//! from `/spawn --timeout <duration>`, else `MEMINI_AGENT_TIMEOUT`, else
+//! [`DEFAULT_AGENT_TIMEOUT_SECS`].  A run that loses the race is dropped
+//! at its next await point (in-flight requests are cancelled and the
+//! concurrency slot is released) and the TUI gets
+//! [`AgentEvent::TimedOut`] or [`AgentEvent::Killed`].
+//!
+//! The registry is process-global so the spawn helpers in `daemon` can
+//! read a window's limit without it being threaded through every call.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Result, bail};
+use tokio::sync::{Notify, mpsc};
+
+use crate::constants::DEFAULT_AGENT_TIMEOUT_SECS;
+use crate::util::env_first;
+
+use super::daemon::AgentEvent;
+use super::halt;
+
+#[derive(Default)]
+struct Control {
+    timeout: Option<Duration>,
+    kill: Arc<Notify>,
+    cancel_command: Arc<Notify>,
+}
+
+fn registry() -> &'static Mutex<HashMap<usize, Control>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<usize, Control>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn with_control<T>(window_id: usize, f: impl FnOnce(&mut Control) -> T) -> T {
+    let mut registry = registry().lock().unwrap_or_else(|err| err.into_inner());
+    f(registry.entry(window_id).or_default())
+}
+
+/// Timeout for windows without their own: `MEMINI_AGENT_TIMEOUT` or
+/// [`DEFAULT_AGENT_TIMEOUT_SECS`].
+pub fn default_timeout() -> Duration {
+    env_first(&["MEMINI_AGENT_TIMEOUT"])
+        .and_then(|raw| parse_duration(&raw).ok())
+        .unwrap_or(Duration::from_secs(DEFAULT_AGENT_TIMEOUT_SECS))
+}
+
+/// Give `window_id` its own maximum runtime per run.
+pub fn set_timeout(window_id: usize, timeout: Duration) {
+    with_control(window_id, |control| control.timeout = Some(timeout));
+}
+
+pub fn timeout_for(window_id: usize) -> Duration {
+    with_control(window_id, |control| control.timeout).unwrap_or_else(default_timeout)
+}
+
+/// Cancel every in-flight run of `window_id`.
+pub fn kill(window_id: usize) {
+    with_control(window_id, |control| control.kill.notify_waiters());
+}
+
+/// Signal that stops `window_id`'s running `workspace_run_command`.
+pub fn command_cancel(window_id: usize) -> Arc<Notify> {
+    with_control(window_id, |control| control.cancel_command.clone())
+}
+
+/// Stop the command `window_id` is running; the run itself continues.
+pub fn cancel_command(window_id: usize) {
+    with_control(window_id, |control| control.cancel_command.notify_waiters());
+}
+
+/// Run `task` for `window_id` unless it times out or is killed (or
+/// halted) first.
+pub fn supervise(
+    window_id: usize,
+    tx: mpsc::UnboundedSender<AgentEvent>,
+    task: impl Future<Output = ()>,
+) -> impl Future<Output = ()> {
+    let timeout = timeout_for(window_id);
+    let kill = with_control(window_id, |control| control.kill.clone());
+    async move {
+        let killed = kill.notified();
+        tokio::select! {
+            _ = task => {}
+            _ = tokio::time::sleep(timeout) => {
+                let _ = tx.send(AgentEvent::TimedOut { window_id, timeout });
+            }
+            _ = killed => {
+                let _ = tx.send(AgentEvent::Killed { window_id });
+            }
+            _ = halt::wait() => {
+                let _ = tx.send(AgentEvent::Killed { window_id });
+            }
+        }
+    }
+}
+
+/// `90s`, `10m`, `1h`, `1h30m` or plain seconds.
+pub fn parse_duration(raw: &str) -> Result<Duration> {
+    let raw = raw.trim();
+    if let Ok(secs) = raw.parse::<u64>() {
+        return Ok(Duration::from_secs(secs));
+    }
+    let mut total = 0u64;
+    let mut number = String::new();
+    for ch in raw.chars() {
+        if ch.is_ascii_digit() {
+            number.push(ch);
+            continue;
+        }
+        let unit = match ch {
+            's' => 1,
+            'm' => 60,
+            'h' => 3600,
+            _ => bail!("invalid duration '{raw}' (use e.g. 90s, 10m, 1h)"),
+        };
+        let Ok(value) = number.parse::<u64>() else {
+            bail!("invalid duration '{raw}' (use e.g. 90s, 10m, 1h)");
+        };
+        total += value * unit;
+        number.clear();
+    }
+    if !number.is_empty() || total == 0 {
+        bail!("invalid duration '{raw}' (use e.g. 90s, 10m, 1h)");
+    }
+    Ok(Duration::from_secs(total))
+}
+
+/// `1h 30m`, `10m` or `45s` for status lines.
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match (secs / 3600, secs % 3600 / 60, secs % 60) {
+        (0, 0, s) => format!("{s}s"),
+        (0, m, 0) => format!("{m}m"),
+        (0, m, s) => format!("{m}m {s}s"),
+        (h, 0, _) => format!("{h}h"),
+        (h, m, _) => format!("{h}h {m}m"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_parse_and_format() {
+        assert_eq!(parse_duration("10m").unwrap(), Duration::from_secs(600));
+        assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+        assert_eq!(parse_duration("45").unwrap(), Duration::from_secs(45));
+        assert!(parse_duration("10x").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert_eq!(format_duration(Duration::from_secs(600)), "10m");
+        assert_eq!(format_duration(Duration::from_secs(5400)), "1h 30m");
+    }
+
+    #[tokio::test]
+    async fn slow_runs_time_out_and_killed_runs_stop() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        set_timeout(9_001, Duration::from_millis(20));
+        supervise(9_001, tx.clone(), std::future::pending()).await;
+        assert!(matches!(
+            rx.recv().await,
+            Some(AgentEvent::TimedOut {
+                window_id: 9_001,
+                ..
+            })
+        ));
+
+        let run = tokio::spawn(supervise(9_002, tx, std::future::pending()));
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        kill(9_002);
+        run.await.expect("supervised run");
+        assert!(matches!(
+            rx.recv().await,
+            Some(AgentEvent::Killed { window_id: 9_002 })
+        ));
+    }
+}
@@ -0,0 +1,611 @@
+//! Isolated workspaces for `/spawn --isolated`.
+//!
+//! An isolated agent window works in its own checkout instead of the
+//! shared workspace root, so parallel agents editing code do not trip over
+//! each other.  Inside a git repository that is a detached `git worktree`
+//! of `HEAD` with the workspace's uncommitted tracked changes applied;
+//! otherwise (or when git fails) it is a copy of the workspace without
+//! `.git`, `target` and `node_modules`.  Every run of the window is wrapped
+//! in [`scope`], which points [`crate::local_tools::workspace_root`] at the
+//! isolated tree for the tools the run calls.
+//!
+//! `/worktree diff|merge|discard` act on what the agent changed since the
+//! tree was made or last merged.  A worktree merges through `git apply`
+//! (falling back to `--3way`); a copy writes each changed file back unless
+//! the workspace's own file changed meanwhile, which is reported as a
+//! conflict.  Trees live in the system temp directory and are removed by
+//! `/worktree discard` and on exit.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+
+use crate::constants::MAX_DIFF_LINES;
+use crate::diff::FileChange;
+use crate::local_tools::{TRASH_DIR, with_workspace_root, workspace_root};
+use crate::platform::normalize_path;
+
+use super::progress;
+
+/// Directories a copy leaves out.
+const SKIPPED_DIRS: &[&str] = &[".git", "target", "node_modules", TRASH_DIR];
+
+enum Kind {
+    /// A `git worktree`; `base` is the tree object changes are measured
+    /// from.
+    Worktree { repo: PathBuf, base: String },
+    /// A plain copy; `baseline` holds each file's hash when it was copied
+    /// or last merged.
+    Copy { baseline: BTreeMap<String, Vec<u8>> },
+}
+
+struct Isolated {
+    /// The agent's workspace root.
+    root: PathBuf,
+    /// The directory made for the window (the worktree or the copy).
+    tree: PathBuf,
+    /// The shared workspace root it was made from.
+    origin: PathBuf,
+    kind: Kind,
+}
+
+/// An isolated workspace as `/worktree list` shows it.
+#[derive(Clone, Debug)]
+pub struct Summary {
+    pub window_id: usize,
+    pub root: PathBuf,
+    /// `git worktree` or `copy`.
+    pub method: &'static str,
+}
+
+/// A file the agent added (`A`), modified (`M`) or deleted (`D`).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Change {
+    pub status: char,
+    /// Workspace-relative path.
+    pub path: String,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.status, self.path)
+    }
+}
+
+/// What [`merge`] wrote back, and what it left alone.
+#[derive(Debug, Default)]
+pub struct Merged {
+    pub applied: Vec<Change>,
+    /// Files the workspace changed too; only copies report these, a
+    /// worktree merge fails instead.
+    pub conflicts: Vec<Change>,
+}
+
+fn registry() -> &'static Mutex<HashMap<usize, Isolated>> {
+    static REGISTRY: OnceLock<Mutex<HashMap<usize, Isolated>>> = OnceLock::new();
+    REGISTRY.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn with_isolated<T>(window_id: usize, f: impl FnOnce(&mut Isolated) -> Result<T>) -> Result<T> {
+    let mut registry = registry().lock().unwrap_or_else(|err| err.into_inner());
+    let Some(isolated) = registry.get_mut(&window_id) else {
+        bail!("Agent #{window_id} has no isolated workspace (spawn with /spawn --isolated)");
+    };
+    f(isolated)
+}
+
+/// Make an isolated copy of the workspace root for `window_id`.
+pub fn create(window_id: usize) -> Result<Summary> {
+    let origin = workspace_root();
+    let tree =
+        std::env::temp_dir().join(format!("memini-agent-{}-{window_id}", std::process::id()));
+    if tree.exists() {
+        fs::remove_dir_all(&tree).with_context(|| format!("remove {}", tree.display()))?;
+    }
+    let _progress = progress::start_blocking("Preparing isolated workspace");
+    let (root, kind) = match create_worktree(&origin, &tree) {
+        Ok(made) => made,
+        Err(_) => {
+            remove_worktree(&origin, &tree);
+            create_copy(&origin, &tree).inspect_err(|_| {
+                let _ = fs::remove_dir_all(&tree);
+            })?
+        }
+    };
+    let isolated = Isolated {
+        root,
+        tree,
+        origin,
+        kind,
+    };
+    let summary = isolated.summary(window_id);
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .insert(window_id, isolated);
+    Ok(summary)
+}
+
+pub fn is_isolated(window_id: usize) -> bool {
+    registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .contains_key(&window_id)
+}
+
+/// Isolated workspaces, by window id.
+pub fn list() -> Vec<Summary> {
+    let registry = registry().lock().unwrap_or_else(|err| err.into_inner());
+    let mut summaries: Vec<Summary> = registry
+        .iter()
+        .map(|(window_id, isolated)| isolated.summary(*window_id))
+        .collect();
+    summaries.sort_by_key(|summary| summary.window_id);
+    summaries
+}
+
+/// Run `task` (a run of `window_id`) in the window's isolated workspace,
+/// if it has one.
+pub fn scope<F: Future>(window_id: usize, task: F) -> impl Future<Output = F::Output> {
+    let root = registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .get(&window_id)
+        .map(|isolated| isolated.root.clone());
+    async move {
+        match root {
+            Some(root) => with_workspace_root(root, task).await,
+            None => task.await,
+        }
+    }
+}
+
+/// Files the agent changed since the tree was made or last merged.
+pub fn changes(window_id: usize) -> Result<Vec<Change>> {
+    with_isolated(window_id, Isolated::changes)
+}
+
+/// A diff of [`changes`] for the activity log.
+pub fn diff_lines(window_id: usize) -> Result<Vec<String>> {
+    with_isolated(window_id, Isolated::diff_lines)
+}
+
+/// Apply [`changes`] to the shared workspace.
+pub fn merge(window_id: usize) -> Result<Merged> {
+    with_isolated(window_id, Isolated::merge)
+}
+
+/// Remove `window_id`'s isolated tree; unmerged changes are lost.
+pub fn discard(window_id: usize) -> Result<()> {
+    let isolated = registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .remove(&window_id);
+    let Some(isolated) = isolated else {
+        bail!("Agent #{window_id} has no isolated workspace");
+    };
+    isolated.remove();
+    Ok(())
+}
+
+/// Remove every isolated tree (app exit).
+pub fn discard_all() {
+    let drained: Vec<Isolated> = registry()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .drain()
+        .map(|(_, isolated)| isolated)
+        .collect();
+    for isolated in drained {
+        isolated.remove();
+    }
+}
+
+impl Isolated {
+    fn summary(&self, window_id: usize) -> Summary {
+        Summary {
+            window_id,
+            root: self.root.clone(),
+            method: match self.kind {
+                Kind::Worktree { .. } => "git worktree",
+                Kind::Copy { .. } => "copy",
+            },
+        }
+    }
+
+    fn changes(&mut self) -> Result<Vec<Change>> {
+        match &self.kind {
+            Kind::Worktree { base, .. } => {
+                stage(&self.root)?;
+                let listing = git(
+                    &self.root,
+                    &[
+                        "diff",
+                        "--cached",
+                        "--name-status",
+                        "--no-renames",
+                        "--relative",
+                        base,
+                        "--",
+                        ".",
+                        &trash_exclude(),
+                    ],
+                )?;
+                Ok(listing
+                    .lines()
+                    .filter_map(|line| {
+                        let (status, path) = line.split_once('\t')?;
+                        Some(Change {
+                            status: status.chars().next()?,
+                            path: path.to_string(),
+                        })
+                    })
+                    .collect())
+            }
+            Kind::Copy { baseline } => {
+                let current = hashes(&self.root)?;
+                let mut changes: Vec<Change> = current
+                    .iter()
+                    .filter_map(|(path, digest)| {
+                        let status = match baseline.get(path) {
+                            None => 'A',
+                            Some(old) if old != digest => 'M',
+                            Some(_) => return None,
+                        };
+                        Some(Change {
+                            status,
+                            path: path.clone(),
+                        })
+                    })
+                    .chain(
+                        baseline
+                            .keys()
+                            .filter(|path| !current.contains_key(*path))
+                            .map(|path| Change {
+                                status: 'D',
+                                path: path.clone(),
+                            }),
+                    )
+                    .collect();
+                changes.sort_by(|a, b| a.path.cmp(&b.path));
+                Ok(changes)
+            }
+        }
+    }
+
+    fn diff_lines(&mut self) -> Result<Vec<String>> {
+        let changes = self.changes()?;
+        match &self.kind {
+            Kind::Worktree { base, .. } => {
+                let patch = git(
+                    &self.root,
+                    &[
+                        "diff",
+                        "--cached",
+                        "--relative",
+                        base,
+                        "--",
+                        ".",
+                        &trash_exclude(),
+                    ],
+                )?;
+                let body: Vec<&str> = patch.lines().collect();
+                let hidden = body.len().saturating_sub(MAX_DIFF_LINES);
+                let mut lines = vec!["```diff".to_string()];
+                lines.extend(body.into_iter().take(MAX_DIFF_LINES).map(str::to_string));
+                if hidden > 0 {
+                    lines.push(format!("@@ … {hidden} more diff line(s) @@"));
+                }
+                lines.push("```".to_string());
+                Ok(lines)
+            }
+            Kind::Copy { .. } => {
+                let mut lines = Vec::new();
+                for change in changes {
+                    if change.status == 'D' {
+                        lines.push(format!("Δ {} deleted", change.path));
+                        continue;
+                    }
+                    match fs::read_to_string(self.root.join(&change.path)) {
+                        Ok(after) => lines.extend(
+                            FileChange {
+                                before: fs::read_to_string(self.origin.join(&change.path)).ok(),
+                                path: change.path,
+                                after,
+                            }
+                            .diff_lines(),
+                        ),
+                        Err(_) => lines.push(format!("Δ {} changed (binary)", change.path)),
+                    }
+                }
+                Ok(lines)
+            }
+        }
+    }
+
+    fn merge(&mut self) -> Result<Merged> {
+        let changes = self.changes()?;
+        if changes.is_empty() {
+            return Ok(Merged::default());
+        }
+        match &mut self.kind {
+            Kind::Worktree { repo, base } => {
+                let patch = git_bytes(
+                    &self.root,
+                    &[
+                        "diff",
+                        "--cached",
+                        "--binary",
+                        base,
+                        "--",
+                        ".",
+                        &trash_exclude(),
+                    ],
+                    None,
+                )?;
+                if git_bytes(repo, &["apply", "--whitespace=nowarn"], Some(&patch)).is_err() {
+                    git_bytes(
+                        repo,
+                        &["apply", "--3way", "--whitespace=nowarn"],
+                        Some(&patch),
+                    )
+                    .context("The changes do not apply cleanly; see git status in the workspace")?;
+                }
+                *base = git(&self.root, &["write-tree"])?;
+                Ok(Merged {
+                    applied: changes,
+                    conflicts: Vec::new(),
+                })
+            }
+            Kind::Copy { baseline } => {
+                let mut merged = Merged::default();
+                for change in changes {
+                    let source = self.root.join(&change.path);
+                    let target = self.origin.join(&change.path);
+                    let wanted = file_hash(&source);
+                    let theirs = file_hash(&target);
+                    if theirs != wanted && theirs.as_ref() != baseline.get(&change.path) {
+                        merged.conflicts.push(change);
+                        continue;
+                    }
+                    match &wanted {
+                        Some(digest) => {
+                            if theirs.as_ref() != Some(digest) {
+                                if let Some(parent) = target.parent() {
+                                    fs::create_dir_all(parent)
+                                        .with_context(|| format!("create {}", parent.display()))?;
+                                }
+                                fs::copy(&source, &target)
+                                    .with_context(|| format!("write {}", target.display()))?;
+                            }
+                            baseline.insert(change.path.clone(), digest.clone());
+                        }
+                        None => {
+                            if target.exists() {
+                                fs::remove_file(&target)
+                                    .with_context(|| format!("delete {}", target.display()))?;
+                            }
+                            baseline.remove(&change.path);
+                        }
+                    }
+                    merged.applied.push(change);
+                }
+                Ok(merged)
+            }
+        }
+    }
+
+    fn remove(self) {
+        match &self.kind {
+            Kind::Worktree { repo, .. } => remove_worktree(repo, &self.tree),
+            Kind::Copy { .. } => {
+                let _ = fs::remove_dir_all(&self.tree);
+            }
+        }
+    }
+}
+
+/// `git worktree add` of `HEAD` at `tree`, plus the uncommitted tracked
+/// changes of the repository `origin` is in.
+fn create_worktree(origin: &Path, tree: &Path) -> Result<(PathBuf, Kind)> {
+    let repo = PathBuf::from(git(origin, &["rev-parse", "--show-toplevel"])?);
+    let prefix = git(origin, &["rev-parse", "--show-prefix"])?;
+    git(
+        &repo,
+        &[
+            "worktree",
+            "add",
+            "--detach",
+            &tree.to_string_lossy(),
+            "HEAD",
+        ],
+    )?;
+    let pending = git_bytes(&repo, &["diff", "--binary", "HEAD"], None)?;
+    if !pending.is_empty() {
+        git_bytes(tree, &["apply", "--whitespace=nowarn"], Some(&pending))?;
+    }
+    let root = normalize_path(&tree.join(prefix));
+    stage(&root)?;
+    let base = git(&root, &["write-tree"])?;
+    Ok((root, Kind::Worktree { repo, base }))
+}
+
+/// Force-remove the worktree at `tree` (and whatever is left of it).
+fn remove_worktree(repo: &Path, tree: &Path) {
+    let _ = git(
+        repo,
+        &["worktree", "remove", "--force", &tree.to_string_lossy()],
+    );
+    let _ = fs::remove_dir_all(tree);
+    let _ = git(repo, &["worktree", "prune"]);
+}
+
+/// Copy `origin`'s files (not [`SKIPPED_DIRS`]) to `tree`.
+fn create_copy(origin: &Path, tree: &Path) -> Result<(PathBuf, Kind)> {
+    fs::create_dir_all(tree).with_context(|| format!("create {}", tree.display()))?;
+    let mut baseline = BTreeMap::new();
+    for path in files(origin)? {
+        let target = tree.join(&path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        fs::copy(origin.join(&path), &target)
+            .with_context(|| format!("copy {} to {}", path, tree.display()))?;
+        if let Some(digest) = file_hash(&target) {
+            baseline.insert(path, digest);
+        }
+    }
+    Ok((normalize_path(tree), Kind::Copy { baseline }))
+}
+
+/// Regular files under `root`, `/`-separated and relative to it.
+fn files(root: &Path) -> Result<Vec<String>> {
+    let mut found = Vec::new();
+    let mut pending = vec![PathBuf::new()];
+    while let Some(dir) = pending.pop() {
+        let entries = fs::read_dir(root.join(&dir))
+            .with_context(|| format!("read {}", root.join(&dir).display()))?;
+        for entry in entries.flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if !SKIPPED_DIRS.contains(&name.as_str()) {
+                    pending.push(dir.join(&name));
+                }
+            } else if file_type.is_file() {
+                found.push(dir.join(&name).to_string_lossy().replace('\\', "/"));
+            }
+        }
+    }
+    found.sort();
+    Ok(found)
+}
+
+fn hashes(root: &Path) -> Result<BTreeMap<String, Vec<u8>>> {
+    Ok(files(root)?
+        .into_iter()
+        .filter_map(|path| Some((path.clone(), file_hash(&root.join(&path))?)))
+        .collect())
+}
+
+fn file_hash(path: &Path) -> Option<Vec<u8>> {
+    fs::read(path)
+        .ok()
+        .map(|bytes| Sha256::digest(bytes).to_vec())
+}
+
+/// Pathspec that keeps `workspace_delete`'s trash out of diffs.
+fn trash_exclude() -> String {
+    format!(":(exclude){TRASH_DIR}")
+}
+
+/// Stage everything under `root` so new files show up in `diff --cached`.
+fn stage(root: &Path) -> Result<()> {
+    git(root, &["add", "-A", "--", ".", &trash_exclude()]).map(|_| ())
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = git_bytes(dir, args, None)?;
+    Ok(String::from_utf8_lossy(&output).trim_end().to_string())
+}
+
+/// Run git in `dir`, feeding it `input`; returns stdout.
+fn git_bytes(dir: &Path, args: &[&str], input: Option<&[u8]>) -> Result<Vec<u8>> {
+    let mut child = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(if input.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("run git")?;
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input).context("write to git")?;
+    }
+    let output = child.wait_with_output().context("run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {}: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(output.stdout)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn copies_merge_back_and_report_conflicts() {
+        let base = std::env::temp_dir().join(format!("memini-worktrees-{}", std::process::id()));
+        let origin = base.join("origin");
+        fs::create_dir_all(origin.join("src")).expect("origin");
+        fs::create_dir_all(origin.join("target")).expect("target");
+        fs::write(origin.join("src/main.rs"), "fn main() {}\n").expect("main");
+        fs::write(origin.join("notes.md"), "one\n").expect("notes");
+        fs::write(origin.join("gone.txt"), "bye\n").expect("gone");
+        fs::write(origin.join("target/out"), "built").expect("build output");
+
+        let (root, kind) = create_copy(&origin, &base.join("tree")).expect("copy");
+        assert!(!root.join("target").exists());
+        let mut isolated = Isolated {
+            root: root.clone(),
+            tree: root.clone(),
+            origin: origin.clone(),
+            kind,
+        };
+        fs::write(root.join("src/main.rs"), "fn main() { run() }\n").expect("edit");
+        fs::write(root.join("src/lib.rs"), "pub fn run() {}\n").expect("add");
+        fs::write(root.join("notes.md"), "agent\n").expect("edit notes");
+        fs::remove_file(root.join("gone.txt")).expect("delete");
+        // The workspace edits the same file meanwhile.
+        fs::write(origin.join("notes.md"), "user\n").expect("user edit");
+
+        let listed: Vec<String> = isolated
+            .changes()
+            .expect("changes")
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            listed,
+            ["D gone.txt", "M notes.md", "A src/lib.rs", "M src/main.rs"]
+        );
+
+        let merged = isolated.merge().expect("merge");
+        assert_eq!(merged.applied.len(), 3);
+        assert_eq!(merged.conflicts[0].path, "notes.md");
+        assert_eq!(
+            fs::read_to_string(origin.join("src/main.rs")).expect("merged"),
+            "fn main() { run() }\n"
+        );
+        assert!(origin.join("src/lib.rs").exists());
+        assert!(!origin.join("gone.txt").exists());
+        assert_eq!(
+            fs::read_to_string(origin.join("notes.md")).expect("kept"),
+            "user\n"
+        );
+        assert_eq!(isolated.changes().expect("after merge").len(), 1);
+
+        isolated.remove();
+        assert!(!root.exists());
+        let _ = fs::remove_dir_all(&base);
+    }
+}
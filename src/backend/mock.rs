@@ -0,0 +1,280 @@
+//! In-memory backends for tests: scripted model replies, a fake MCP server
+//! and a Rice stand-in, each recording what the app sent.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+use anyhow::{Result, anyhow};
+use rice::rice_state::proto::Trace;
+use rmcp::model::{CallToolResult, Content, JsonObject, Tool as McpTool};
+use serde_json::{Value, json};
+
+use super::{Backends, BoxFuture, LlmBackend, McpBackend, McpSession, MemoryBackend};
+use crate::mcp::McpConnection;
+use crate::mcp::config::McpServer;
+use crate::rice::StateEvent;
+
+/// Mock backends plus handles to inspect them.
+pub struct Mocks {
+    pub llm: Arc<MockLlm>,
+    pub mcp: Arc<MockMcp>,
+    pub memory: Arc<MockMemory>,
+}
+
+impl Mocks {
+    pub fn new() -> Self {
+        Mocks {
+            llm: Arc::new(MockLlm::default()),
+            mcp: Arc::new(MockMcp::default()),
+            memory: Arc::new(MockMemory::default()),
+        }
+    }
+
+    pub fn backends(&self) -> Backends {
+        Backends {
+            llm: self.llm.clone(),
+            mcp: self.mcp.clone(),
+            memory: Some(self.memory.clone()),
+        }
+    }
+}
+
+// ── Model ────────────────────────────────────────────────────────────
+
+/// Replies to `responses` from a script (then with "ok"), and to
+/// `embeddings` with a fixed vector.
+#[derive(Default)]
+pub struct MockLlm {
+    replies: Mutex<VecDeque<Value>>,
+    requests: Mutex<Vec<(String, Value)>>,
+}
+
+impl MockLlm {
+    /// Queue a plain-text answer.
+    pub fn reply_text(&self, text: &str) {
+        self.push(json!({"output": [{
+            "type": "message",
+            "content": [{"type": "output_text", "text": text}],
+        }]}));
+    }
+
+    /// Queue a function call.
+    pub fn reply_tool_call(&self, name: &str, arguments: Value) {
+        let call_id = format!("call_{}", self.replies.lock().unwrap().len() + 1);
+        self.push(json!({"output": [{
+            "type": "function_call",
+            "name": name,
+            "arguments": arguments.to_string(),
+            "call_id": call_id,
+        }]}));
+    }
+
+    fn push(&self, reply: Value) {
+        self.replies.lock().unwrap().push_back(reply);
+    }
+
+    /// Bodies sent to `path`, oldest first.
+    pub fn requests(&self, path: &str) -> Vec<Value> {
+        self.requests
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(sent_to, _)| sent_to == path)
+            .map(|(_, body)| body.clone())
+            .collect()
+    }
+}
+
+impl LlmBackend for MockLlm {
+    fn post_json<'a>(
+        &'a self,
+        _key: &'a str,
+        path: &'a str,
+        body: Value,
+    ) -> BoxFuture<'a, Result<Value>> {
+        self.requests.lock().unwrap().push((path.to_string(), body));
+        let reply = match path {
+            "embeddings" => Ok(json!({"data": [{"embedding": [0.0, 0.5, 1.0]}]})),
+            "responses" => Ok(self.replies.lock().unwrap().pop_front().unwrap_or_else(|| {
+                json!({"output": [{
+                    "type": "message",
+                    "content": [{"type": "output_text", "text": "ok"}],
+                }]})
+            })),
+            other => Err(anyhow!("mock model has no endpoint {other}")),
+        };
+        Box::pin(async move { reply })
+    }
+}
+
+// ── MCP ──────────────────────────────────────────────────────────────
+
+/// Every server it connects to offers `echo`, which returns its arguments.
+#[derive(Debug, Default)]
+pub struct MockMcp {
+    calls: Arc<Mutex<Vec<(String, String, Value)>>>,
+}
+
+impl MockMcp {
+    /// `(server id, tool, arguments)` for each call, oldest first.
+    pub fn calls(&self) -> Vec<(String, String, Value)> {
+        self.calls.lock().unwrap().clone()
+    }
+}
+
+impl McpBackend for MockMcp {
+    fn connect<'a>(
+        &'a self,
+        server: &'a McpServer,
+        _bearer: Option<String>,
+    ) -> BoxFuture<'a, Result<McpConnection>> {
+        let connection = McpConnection {
+            server: server.clone(),
+            client: Box::new(MockSession {
+                server_id: server.id.clone(),
+                calls: self.calls.clone(),
+            }),
+            tool_cache: Vec::new(),
+        };
+        Box::pin(async move { Ok(connection) })
+    }
+}
+
+struct MockSession {
+    server_id: String,
+    calls: Arc<Mutex<Vec<(String, String, Value)>>>,
+}
+
+impl McpSession for MockSession {
+    fn list_tools(&self) -> BoxFuture<'_, Result<Vec<McpTool>>> {
+        let schema = json!({
+            "type": "object",
+            "properties": {"text": {"type": "string"}},
+            "required": ["text"],
+        });
+        let Value::Object(schema) = schema else {
+            unreachable!()
+        };
+        let tool = McpTool::new("echo", "Echo the arguments back.", Arc::new(schema));
+        Box::pin(async move { Ok(vec![tool]) })
+    }
+
+    fn call_tool<'a>(
+        &'a self,
+        name: &'a str,
+        arguments: Option<JsonObject>,
+    ) -> BoxFuture<'a, Result<CallToolResult>> {
+        let arguments = Value::Object(arguments.unwrap_or_default());
+        self.calls.lock().unwrap().push((
+            self.server_id.clone(),
+            name.to_string(),
+            arguments.clone(),
+        ));
+        let result = CallToolResult::success(vec![Content::text(arguments.to_string())]);
+        Box::pin(async move { Ok(result) })
+    }
+
+    fn notify_roots_changed(&self) -> BoxFuture<'_, Result<()>> {
+        self.calls.lock().unwrap().push((
+            self.server_id.clone(),
+            "notifications/roots/list_changed".to_string(),
+            Value::Null,
+        ));
+        Box::pin(async move { Ok(()) })
+    }
+}
+
+// ── Memory ───────────────────────────────────────────────────────────
+
+/// Variables and traces kept in maps; `subscribe` ends immediately.
+#[derive(Default)]
+pub struct MockMemory {
+    variables: Mutex<HashMap<(String, String), String>>,
+    traces: Mutex<Vec<Trace>>,
+}
+
+impl MockMemory {
+    /// A variable's value in any run.
+    pub fn variable(&self, name: &str) -> Option<Value> {
+        self.variables
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|((_, var), _)| var == name)
+            .and_then(|(_, value_json)| serde_json::from_str(value_json).ok())
+    }
+
+    pub fn traces(&self) -> Vec<Trace> {
+        self.traces.lock().unwrap().clone()
+    }
+}
+
+impl MemoryBackend for MockMemory {
+    fn set_variable<'a>(
+        &'a self,
+        run_id: String,
+        name: String,
+        value_json: String,
+        _source: String,
+    ) -> BoxFuture<'a, Result<()>> {
+        self.variables
+            .lock()
+            .unwrap()
+            .insert((run_id, name), value_json);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn get_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<String>> {
+        let value = self
+            .variables
+            .lock()
+            .unwrap()
+            .get(&(run_id, name))
+            .cloned()
+            .unwrap_or_default();
+        Box::pin(async move { Ok(value) })
+    }
+
+    fn delete_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<()>> {
+        self.variables.lock().unwrap().remove(&(run_id, name));
+        Box::pin(async { Ok(()) })
+    }
+
+    fn focus<'a>(&'a self, _run_id: String, _content: String) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+
+    fn reminisce<'a>(
+        &'a self,
+        run_id: String,
+        _embedding: Vec<f32>,
+        limit: u64,
+        _query: String,
+    ) -> BoxFuture<'a, Result<Vec<Trace>>> {
+        let traces: Vec<Trace> = self
+            .traces
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .filter(|trace| trace.run_id == run_id)
+            .take(limit as usize)
+            .cloned()
+            .collect();
+        Box::pin(async move { Ok(traces) })
+    }
+
+    fn commit<'a>(&'a self, trace: Trace) -> BoxFuture<'a, Result<()>> {
+        self.traces.lock().unwrap().push(trace);
+        Box::pin(async { Ok(()) })
+    }
+
+    fn subscribe<'a>(
+        &'a self,
+        _run_id: String,
+        _event_types: Vec<String>,
+        _on_event: &'a mut (dyn FnMut(StateEvent) + Send),
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async { Ok(()) })
+    }
+}
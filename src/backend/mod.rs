@@ -0,0 +1,120 @@
+//! Seams between the app and the network: the model API, MCP servers, and
+//! Rice memory.
+//!
+//! [`crate::app::App`] holds a [`Backends`] set and threads it into every
+//! background task, so tests can swap the real clients for the mocks in
+//! [`mock`] and drive the command layer without a network.  The real
+//! implementations live next to the clients they wrap:
+//! [`crate::openai::HttpLlm`], [`crate::mcp::StreamableHttp`] and
+//! [`crate::rice::RiceBackend`].
+
+#[cfg(test)]
+pub mod mock;
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use anyhow::Result;
+use rice::rice_state::proto::Trace;
+use rmcp::model::{CallToolResult, JsonObject, Tool as McpTool};
+use serde_json::Value;
+
+use crate::mcp::McpConnection;
+use crate::mcp::config::McpServer;
+use crate::rice::StateEvent;
+
+/// Boxed future, so the traits stay object-safe.
+pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
+
+/// The OpenAI-compatible JSON API.
+pub trait LlmBackend: Send + Sync {
+    /// POST `body` to `path` (`responses`, `embeddings`) and return the
+    /// parsed reply; non-2xx statuses are errors.
+    fn post_json<'a>(
+        &'a self,
+        key: &'a str,
+        path: &'a str,
+        body: Value,
+    ) -> BoxFuture<'a, Result<Value>>;
+}
+
+/// Opens MCP connections.
+pub trait McpBackend: Send + Sync + std::fmt::Debug {
+    fn connect<'a>(
+        &'a self,
+        server: &'a McpServer,
+        bearer: Option<String>,
+    ) -> BoxFuture<'a, Result<McpConnection>>;
+}
+
+/// One open MCP connection.
+pub trait McpSession: Send + Sync {
+    fn list_tools(&self) -> BoxFuture<'_, Result<Vec<McpTool>>>;
+
+    fn call_tool<'a>(
+        &'a self,
+        name: &'a str,
+        arguments: Option<JsonObject>,
+    ) -> BoxFuture<'a, Result<CallToolResult>>;
+
+    /// Tell the server the exposed roots changed (see [`crate::mcp::roots`]).
+    fn notify_roots_changed(&self) -> BoxFuture<'_, Result<()>>;
+}
+
+/// Rice State operations, scoped by run id.  Values are JSON strings, as
+/// Rice stores them; a missing variable reads as an empty string.
+pub trait MemoryBackend: Send + Sync {
+    fn set_variable<'a>(
+        &'a self,
+        run_id: String,
+        name: String,
+        value_json: String,
+        source: String,
+    ) -> BoxFuture<'a, Result<()>>;
+
+    fn get_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<String>>;
+
+    fn delete_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<()>>;
+
+    fn focus<'a>(&'a self, run_id: String, content: String) -> BoxFuture<'a, Result<()>>;
+
+    fn reminisce<'a>(
+        &'a self,
+        run_id: String,
+        embedding: Vec<f32>,
+        limit: u64,
+        query: String,
+    ) -> BoxFuture<'a, Result<Vec<Trace>>>;
+
+    fn commit<'a>(&'a self, trace: Trace) -> BoxFuture<'a, Result<()>>;
+
+    /// Deliver state events to `on_event` until the stream ends.
+    fn subscribe<'a>(
+        &'a self,
+        run_id: String,
+        event_types: Vec<String>,
+        on_event: &'a mut (dyn FnMut(StateEvent) + Send),
+    ) -> BoxFuture<'a, Result<()>>;
+}
+
+/// The backends an [`crate::app::App`] talks to.
+#[derive(Clone)]
+pub struct Backends {
+    pub llm: Arc<dyn LlmBackend>,
+    pub mcp: Arc<dyn McpBackend>,
+    /// Shared by every Rice handle the app opens.  `None` connects each
+    /// handle to Rice from the environment.
+    pub memory: Option<Arc<dyn MemoryBackend>>,
+}
+
+impl Backends {
+    /// The network-backed defaults.
+    pub fn live() -> Self {
+        Backends {
+            llm: Arc::new(crate::openai::HttpLlm::from_env()),
+            mcp: Arc::new(crate::mcp::StreamableHttp),
+            memory: None,
+        }
+    }
+}
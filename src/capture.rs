@@ -0,0 +1,191 @@
+//! Request/response capture for `/debug http`.
+//!
+//! While capture is on, the OpenAI client and MCP calls record each
+//! exchange — the JSON body sent, the status and the JSON that came back —
+//! into a ring of the last [`DEBUG_HTTP_CAPTURES`] entries.  Bodies are
+//! masked on the way in: the `/redact` rules run over every string, and
+//! fields named like credentials (`api_key`, `authorization`, `token`, …)
+//! are blanked whatever their value.  Headers are never captured.
+//!
+//! Capture is off by default and costs one atomic load per request then.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::constants::DEBUG_HTTP_CAPTURES;
+
+/// Field-name fragments whose values are always blanked.
+const SECRET_KEYS: &[&str] = &[
+    "api_key",
+    "apikey",
+    "authorization",
+    "password",
+    "secret",
+    "token",
+];
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Which client made the request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Channel {
+    OpenAi,
+    Mcp,
+}
+
+impl Channel {
+    pub fn label(self) -> &'static str {
+        match self {
+            Channel::OpenAi => "openai",
+            Channel::Mcp => "mcp",
+        }
+    }
+}
+
+/// One captured request and its outcome.
+#[derive(Clone, Debug, Serialize)]
+pub struct Exchange {
+    /// Local time the request was sent, RFC 3339.
+    pub at: String,
+    pub channel: Channel,
+    /// API path (`/responses`) or MCP `server/tool`.
+    pub target: String,
+    /// HTTP status, `ok`, or `error` when nothing came back.
+    pub status: String,
+    pub elapsed_ms: u128,
+    pub request: Value,
+    pub response: Value,
+}
+
+impl Exchange {
+    /// `true` unless the status is an error.
+    pub fn succeeded(&self) -> bool {
+        self.status == "ok" || self.status.starts_with('2')
+    }
+}
+
+fn ring() -> &'static Mutex<VecDeque<Exchange>> {
+    static RING: OnceLock<Mutex<VecDeque<Exchange>>> = OnceLock::new();
+    RING.get_or_init(|| Mutex::new(VecDeque::with_capacity(DEBUG_HTTP_CAPTURES)))
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+pub fn set_enabled(on: bool) {
+    ENABLED.store(on, Ordering::Relaxed);
+}
+
+fn is_secret_key(key: &str) -> bool {
+    let key = key.to_ascii_lowercase().replace('-', "_");
+    SECRET_KEYS.iter().any(|fragment| key.contains(fragment))
+}
+
+/// Blank credential-named fields and apply the `/redact` rules.
+fn mask(mut value: Value) -> Value {
+    fn blank_secret_keys(value: &mut Value) {
+        match value {
+            Value::Object(fields) => {
+                for (key, field) in fields.iter_mut() {
+                    if is_secret_key(key) && !field.is_object() && !field.is_array() {
+                        *field = Value::String("[MASKED]".to_string());
+                    } else {
+                        blank_secret_keys(field);
+                    }
+                }
+            }
+            Value::Array(items) => items.iter_mut().for_each(blank_secret_keys),
+            _ => {}
+        }
+    }
+    blank_secret_keys(&mut value);
+    crate::redact::mask_value(&mut value);
+    value
+}
+
+/// Record one exchange when capture is on; the oldest entry drops out
+/// once the ring is full.
+pub fn record(
+    channel: Channel,
+    target: impl Into<String>,
+    request: &Value,
+    status: impl Into<String>,
+    response: &Value,
+    elapsed: Duration,
+) {
+    if !enabled() {
+        return;
+    }
+    let exchange = Exchange {
+        at: chrono::Local::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, false),
+        channel,
+        target: target.into(),
+        status: status.into(),
+        elapsed_ms: elapsed.as_millis(),
+        request: mask(request.clone()),
+        response: mask(response.clone()),
+    };
+    let mut ring = ring().lock().unwrap_or_else(|err| err.into_inner());
+    while ring.len() >= DEBUG_HTTP_CAPTURES {
+        ring.pop_front();
+    }
+    ring.push_back(exchange);
+}
+
+/// Captured exchanges, oldest first.
+pub fn snapshot() -> Vec<Exchange> {
+    ring()
+        .lock()
+        .unwrap_or_else(|err| err.into_inner())
+        .iter()
+        .cloned()
+        .collect()
+}
+
+pub fn clear() {
+    ring().lock().unwrap_or_else(|err| err.into_inner()).clear();
+}
+
+/// Write the captured exchanges to `path` as a JSON array; returns how
+/// many were written.
+pub fn dump(path: &Path) -> Result<usize> {
+    let exchanges = snapshot();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+    }
+    let json = serde_json::to_string_pretty(&exchanges).context("serialize captures")?;
+    fs::write(path, json).with_context(|| format!("write {}", path.display()))?;
+    Ok(exchanges.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn credential_fields_are_masked_at_any_depth() {
+        let masked = mask(json!({
+            "model": "gpt-5",
+            "api_key": "sk-live",
+            "headers": {"Authorization": "Bearer abc", "Accept": "json"},
+            "tools": [{"name": "search", "access-token": "t0k"}],
+        }));
+        assert_eq!(masked["model"], "gpt-5");
+        assert_eq!(masked["api_key"], "[MASKED]");
+        assert_eq!(masked["headers"]["Authorization"], "[MASKED]");
+        assert_eq!(masked["headers"]["Accept"], "json");
+        assert_eq!(masked["tools"][0]["access-token"], "[MASKED]");
+        assert_eq!(masked["tools"][0]["name"], "search");
+    }
+}
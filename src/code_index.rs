@@ -0,0 +1,605 @@
+//! Semantic code search over the workspace.
+//!
+//! The indexer splits text files under the workspace root into overlapping
+//! line windows, embeds them with the `embedding` model route, and keeps
+//! the vectors in `code-index/<root hash>.json` in the config dir.  Passes
+//! are incremental: files whose size and mtime are unchanged keep their
+//! vectors, so the background refresh only pays for edits.
+//! `workspace_semantic_search` embeds the query and returns the closest
+//! chunks by cosine similarity.  Settings come from `memini.toml`:
+//!
+//! ```toml
+//! [index]
+//! auto = true              # index at startup and refresh in the background
+//! refresh_minutes = 10
+//! ```
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::UNIX_EPOCH;
+
+use anyhow::{Context, Result, anyhow, bail};
+use chrono::{DateTime, Local};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::constants::{
+    APP_NAME, DEFAULT_INDEX_REFRESH_MINUTES, INDEX_CHUNK_LINES, INDEX_CHUNK_OVERLAP,
+    INDEX_EMBED_BATCH, MAX_INDEX_FILE_BYTES, MAX_INDEX_FILES,
+};
+use crate::openai::{OpenAiClient, ToolCall};
+use crate::routing::TaskKind;
+
+pub const SEMANTIC_SEARCH_TOOL: &str = "workspace_semantic_search";
+
+/// Directory names never indexed.
+const IGNORED_DIRS: &[&str] = &[".git", "target", "node_modules"];
+/// Longest chunk text sent for embedding (minified files have huge lines).
+const MAX_CHUNK_CHARS: usize = 6_000;
+/// Longest snippet returned per search hit.
+const MAX_SNIPPET_CHARS: usize = 1_500;
+
+/// The `[index]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct IndexConfig {
+    /// Index at startup and refresh every `refresh_minutes`.
+    #[serde(default = "default_auto")]
+    pub auto: bool,
+    #[serde(default = "default_refresh_minutes")]
+    pub refresh_minutes: u64,
+}
+
+fn default_auto() -> bool {
+    true
+}
+
+fn default_refresh_minutes() -> u64 {
+    DEFAULT_INDEX_REFRESH_MINUTES
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        IndexConfig {
+            auto: true,
+            refresh_minutes: DEFAULT_INDEX_REFRESH_MINUTES,
+        }
+    }
+}
+
+/// The `[index]` section of `memini.toml`.
+pub fn config() -> &'static IndexConfig {
+    &crate::config::get().index
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct Chunk {
+    start_line: usize,
+    end_line: usize,
+    text: String,
+    vector: Vec<f32>,
+}
+
+/// One file's chunks; binary files are kept with no chunks so they are
+/// not re-read on every pass.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct IndexedFile {
+    size: u64,
+    modified: u64,
+    chunks: Vec<Chunk>,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+struct CodeIndex {
+    root: PathBuf,
+    /// Embedding model the vectors came from; a different model means a
+    /// full rebuild.
+    model: String,
+    #[serde(default)]
+    files: BTreeMap<String, IndexedFile>,
+}
+
+impl CodeIndex {
+    fn text_files(&self) -> usize {
+        self.files.values().filter(|f| !f.chunks.is_empty()).count()
+    }
+
+    fn chunk_count(&self) -> usize {
+        self.files.values().map(|f| f.chunks.len()).sum()
+    }
+}
+
+/// What the indexer is doing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum IndexState {
+    #[default]
+    Idle,
+    Building {
+        done: usize,
+        total: usize,
+    },
+    Failed(String),
+}
+
+/// Snapshot for `/index status`.
+#[derive(Clone, Debug, Default)]
+pub struct IndexStatus {
+    pub state: IndexState,
+    pub root: PathBuf,
+    pub files: usize,
+    pub chunks: usize,
+    /// End of the last successful pass in this process.
+    pub updated: Option<DateTime<Local>>,
+}
+
+/// Result of one indexing pass.
+#[derive(Clone, Debug, Default)]
+pub struct BuildSummary {
+    pub files: usize,
+    pub chunks: usize,
+    /// Files (re-)embedded this pass.
+    pub embedded: usize,
+    pub removed: usize,
+}
+
+/// One search result.
+#[derive(Clone, Debug)]
+pub struct Hit {
+    pub path: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    pub score: f32,
+    pub text: String,
+}
+
+fn progress() -> &'static Mutex<(IndexState, Option<DateTime<Local>>)> {
+    static PROGRESS: OnceLock<Mutex<(IndexState, Option<DateTime<Local>>)>> = OnceLock::new();
+    PROGRESS.get_or_init(|| Mutex::new((IndexState::Idle, None)))
+}
+
+fn set_state(state: IndexState) {
+    if let Ok(mut progress) = progress().lock() {
+        if state == IndexState::Idle {
+            progress.1 = Some(Local::now());
+        }
+        progress.0 = state;
+    }
+}
+
+fn cache() -> &'static Mutex<Option<Arc<CodeIndex>>> {
+    static CACHE: OnceLock<Mutex<Option<Arc<CodeIndex>>>> = OnceLock::new();
+    CACHE.get_or_init(|| Mutex::new(None))
+}
+
+/// The index for `root`, from memory or disk.
+fn current(root: &Path) -> Option<Arc<CodeIndex>> {
+    let mut cached = cache().lock().ok()?;
+    if let Some(index) = cached.as_ref().filter(|index| index.root == root) {
+        return Some(index.clone());
+    }
+    let raw = fs::read_to_string(index_path(root)?).ok()?;
+    let index: CodeIndex = serde_json::from_str(&raw).ok()?;
+    let index = Arc::new(index);
+    *cached = Some(index.clone());
+    Some(index)
+}
+
+/// Current state plus counts from the stored index.
+pub fn status() -> IndexStatus {
+    let root = crate::local_tools::workspace_root();
+    let (state, updated) = progress()
+        .lock()
+        .map(|progress| progress.clone())
+        .unwrap_or_default();
+    let index = current(&root);
+    IndexStatus {
+        state,
+        files: index.as_ref().map_or(0, |index| index.text_files()),
+        chunks: index.as_ref().map_or(0, |index| index.chunk_count()),
+        root,
+        updated,
+    }
+}
+
+/// `code-index/<hash of root>.json` in the config dir.
+fn index_path(root: &Path) -> Option<PathBuf> {
+    let digest = Sha256::digest(root.display().to_string().as_bytes());
+    let name: String = digest
+        .iter()
+        .take(8)
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    ProjectDirs::from("com", APP_NAME, APP_NAME).map(|dirs| {
+        dirs.config_dir()
+            .join("code-index")
+            .join(format!("{name}.json"))
+    })
+}
+
+fn persist(index: &CodeIndex) -> Result<()> {
+    let Some(path) = index_path(&index.root) else {
+        return Ok(());
+    };
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("create index dir")?;
+    }
+    let contents = serde_json::to_string(index).context("serialize index")?;
+    fs::write(&path, contents).with_context(|| format!("write {}", path.display()))?;
+    Ok(())
+}
+
+/// Clears the running flag even if the pass is aborted mid-way.
+struct RunningGuard;
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+
+impl Drop for RunningGuard {
+    fn drop(&mut self) {
+        RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Run one indexing pass over the workspace.  `force` drops the stored
+/// vectors and re-embeds everything.
+pub async fn build(openai: &OpenAiClient, key: &str, force: bool) -> Result<BuildSummary> {
+    if RUNNING.swap(true, Ordering::SeqCst) {
+        bail!("indexing is already running");
+    }
+    let _guard = RunningGuard;
+    let result = build_pass(openai, key, force).await;
+    match &result {
+        Ok(_) => set_state(IndexState::Idle),
+        Err(err) => set_state(IndexState::Failed(format!("{err:#}"))),
+    }
+    result
+}
+
+async fn build_pass(openai: &OpenAiClient, key: &str, force: bool) -> Result<BuildSummary> {
+    let root = crate::local_tools::workspace_root();
+    let model = openai.model_for(TaskKind::Embedding);
+    let mut index = match current(&root) {
+        Some(existing) if !force && existing.model == model => (*existing).clone(),
+        _ => CodeIndex {
+            root: root.clone(),
+            model,
+            files: BTreeMap::new(),
+        },
+    };
+
+    let candidates = collect_files(&root);
+    let present: HashSet<&str> = candidates.iter().map(|c| c.rel.as_str()).collect();
+    let before = index.files.len();
+    index.files.retain(|rel, _| present.contains(rel.as_str()));
+    let removed = before - index.files.len();
+
+    let stale: Vec<&Candidate> = candidates
+        .iter()
+        .filter(|c| {
+            index
+                .files
+                .get(&c.rel)
+                .is_none_or(|f| f.size != c.size || f.modified != c.modified)
+        })
+        .collect();
+
+    let mut embedded = 0usize;
+    let mut failure = None;
+    for (done, candidate) in stale.iter().enumerate() {
+        set_state(IndexState::Building {
+            done,
+            total: stale.len(),
+        });
+        match embed_file(openai, key, candidate).await {
+            Ok(file) => {
+                index.files.insert(candidate.rel.clone(), file);
+                embedded += 1;
+            }
+            Err(err) => {
+                failure = Some(err.context(format!("index {}", candidate.rel)));
+                break;
+            }
+        }
+    }
+
+    // Keep partial progress so the next pass resumes where this one stopped.
+    if embedded > 0 || removed > 0 || force {
+        persist(&index)?;
+    }
+    let summary = BuildSummary {
+        files: index.text_files(),
+        chunks: index.chunk_count(),
+        embedded,
+        removed,
+    };
+    if let Ok(mut cached) = cache().lock() {
+        *cached = Some(Arc::new(index));
+    }
+    match failure {
+        Some(err) => Err(err),
+        None => Ok(summary),
+    }
+}
+
+struct Candidate {
+    rel: String,
+    path: PathBuf,
+    size: u64,
+    modified: u64,
+}
+
+/// Regular files under `root`, skipping ignored and hidden directories and
+/// files over [`MAX_INDEX_FILE_BYTES`], sorted by path.
+fn collect_files(root: &Path) -> Vec<Candidate> {
+    let mut files = Vec::new();
+    let mut dirs = vec![root.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            let name = entry.file_name().to_string_lossy().to_string();
+            let path = entry.path();
+            if file_type.is_dir() {
+                if !name.starts_with('.') && !IGNORED_DIRS.contains(&name.as_str()) {
+                    dirs.push(path);
+                }
+                continue;
+            }
+            if !file_type.is_file() {
+                continue;
+            }
+            let Ok(meta) = entry.metadata() else {
+                continue;
+            };
+            if meta.len() == 0 || meta.len() > MAX_INDEX_FILE_BYTES {
+                continue;
+            }
+            let Ok(rel) = path.strip_prefix(root) else {
+                continue;
+            };
+            let rel: Vec<String> = rel
+                .components()
+                .map(|part| part.as_os_str().to_string_lossy().to_string())
+                .collect();
+            let modified = meta
+                .modified()
+                .ok()
+                .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |since| since.as_secs());
+            files.push(Candidate {
+                rel: rel.join("/"),
+                path,
+                size: meta.len(),
+                modified,
+            });
+            if files.len() >= MAX_INDEX_FILES {
+                break;
+            }
+        }
+        if files.len() >= MAX_INDEX_FILES {
+            break;
+        }
+    }
+    files.sort_by(|a, b| a.rel.cmp(&b.rel));
+    files
+}
+
+async fn embed_file(
+    openai: &OpenAiClient,
+    key: &str,
+    candidate: &Candidate,
+) -> Result<IndexedFile> {
+    let mut file = IndexedFile {
+        size: candidate.size,
+        modified: candidate.modified,
+        chunks: Vec::new(),
+    };
+    let Ok(text) = fs::read_to_string(&candidate.path) else {
+        return Ok(file);
+    };
+    if text.contains('\0') {
+        return Ok(file);
+    }
+    let pieces = chunk_lines(&text);
+    for batch in pieces.chunks(INDEX_EMBED_BATCH) {
+        let inputs: Vec<String> = batch
+            .iter()
+            .map(|(_, _, text)| format!("{}\n{text}", candidate.rel))
+            .collect();
+        let vectors = openai.embeddings(key, &inputs).await?;
+        for ((start_line, end_line, text), vector) in batch.iter().zip(vectors) {
+            file.chunks.push(Chunk {
+                start_line: *start_line,
+                end_line: *end_line,
+                text: text.clone(),
+                vector,
+            });
+        }
+    }
+    Ok(file)
+}
+
+/// Overlapping windows of [`INDEX_CHUNK_LINES`] lines as
+/// `(first line, last line, text)`, 1-based; blank windows are dropped.
+fn chunk_lines(text: &str) -> Vec<(usize, usize, String)> {
+    let lines: Vec<&str> = text.lines().collect();
+    let step = INDEX_CHUNK_LINES - INDEX_CHUNK_OVERLAP;
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < lines.len() {
+        let end = (start + INDEX_CHUNK_LINES).min(lines.len());
+        let body = lines[start..end].join("\n");
+        if !body.trim().is_empty() {
+            chunks.push((start + 1, end, body.chars().take(MAX_CHUNK_CHARS).collect()));
+        }
+        if end == lines.len() {
+            break;
+        }
+        start += step;
+    }
+    chunks
+}
+
+fn cosine(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm = |v: &[f32]| v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let denominator = norm(a) * norm(b);
+    if denominator == 0.0 {
+        0.0
+    } else {
+        dot / denominator
+    }
+}
+
+/// The `limit` chunks closest to `query`, best first.
+fn rank(index: &CodeIndex, query: &[f32], limit: usize) -> Vec<Hit> {
+    let mut hits: Vec<Hit> = index
+        .files
+        .iter()
+        .flat_map(|(path, file)| {
+            file.chunks.iter().map(move |chunk| Hit {
+                path: path.clone(),
+                start_line: chunk.start_line,
+                end_line: chunk.end_line,
+                score: cosine(query, &chunk.vector),
+                text: chunk.text.clone(),
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| b.score.total_cmp(&a.score));
+    hits.truncate(limit);
+    hits
+}
+
+/// Search the stored index for `query`.
+pub async fn search(
+    openai: &OpenAiClient,
+    key: &str,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<Hit>> {
+    let index = current(&crate::local_tools::workspace_root())
+        .filter(|index| index.chunk_count() > 0)
+        .ok_or_else(|| anyhow!("the workspace index is empty; run /index rebuild first"))?;
+    let vector = openai.embedding(key, query).await?;
+    Ok(rank(&index, &vector, limit))
+}
+
+/// Tool definition, listed with the other workspace tools.
+pub fn tool_def() -> Value {
+    json!({
+        "type": "function",
+        "name": SEMANTIC_SEARCH_TOOL,
+        "description": "Search workspace files by meaning and return the most relevant snippets with paths and line ranges. Use this to find where something is implemented before listing or reading files.",
+        "parameters": {
+            "type": "object",
+            "properties": {
+                "query": {
+                    "type": "string",
+                    "description": "What you are looking for, in plain words (e.g. 'where MCP tokens are refreshed')."
+                },
+                "limit": {
+                    "type": "integer",
+                    "description": "Number of snippets to return (default 8, max 20)."
+                }
+            },
+            "required": ["query"]
+        }
+    })
+}
+
+/// Handle a `workspace_semantic_search` call.
+pub async fn handle_tool_call(call: &ToolCall, openai: &OpenAiClient, key: &str) -> String {
+    let Some(query) = call
+        .arguments
+        .get("query")
+        .and_then(Value::as_str)
+        .map(str::trim)
+        .filter(|query| !query.is_empty())
+    else {
+        return json!({"error": "query is required"}).to_string();
+    };
+    let limit = call
+        .arguments
+        .get("limit")
+        .and_then(Value::as_u64)
+        .unwrap_or(8)
+        .clamp(1, 20) as usize;
+    let indexing = matches!(status().state, IndexState::Building { .. });
+    match search(openai, key, query, limit).await {
+        Ok(hits) => {
+            let results: Vec<Value> = hits
+                .iter()
+                .map(|hit| {
+                    json!({
+                        "path": hit.path,
+                        "start_line": hit.start_line,
+                        "end_line": hit.end_line,
+                        "score": (hit.score * 1000.0).round() / 1000.0,
+                        "snippet": hit.text.chars().take(MAX_SNIPPET_CHARS).collect::<String>(),
+                    })
+                })
+                .collect();
+            json!({"query": query, "indexing": indexing, "results": results}).to_string()
+        }
+        Err(err) => json!({"error": format!("{err:#}")}).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn chunks_overlap_and_cover_the_file() {
+        let text: String = (1..=100).map(|n| format!("line {n}\n")).collect();
+        let chunks = chunk_lines(&text);
+        assert_eq!(chunks[0].0, 1);
+        assert_eq!(chunks[0].1, INDEX_CHUNK_LINES);
+        assert_eq!(chunks[1].0, INDEX_CHUNK_LINES - INDEX_CHUNK_OVERLAP + 1);
+        assert_eq!(chunks.last().map(|c| c.1), Some(100));
+        assert!(chunk_lines("\n\n  \n").is_empty());
+    }
+
+    #[test]
+    fn ranks_by_cosine_similarity() {
+        let chunk = |vector: Vec<f32>| Chunk {
+            start_line: 1,
+            end_line: 1,
+            text: String::new(),
+            vector,
+        };
+        let file = |chunks| IndexedFile {
+            size: 1,
+            modified: 0,
+            chunks,
+        };
+        let mut index = CodeIndex::default();
+        index
+            .files
+            .insert("a.rs".to_string(), file(vec![chunk(vec![0.0, 1.0])]));
+        index
+            .files
+            .insert("b.rs".to_string(), file(vec![chunk(vec![1.0, 0.1])]));
+        let hits = rank(&index, &[1.0, 0.0], 1);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].path, "b.rs");
+        assert_eq!(cosine(&[0.0, 0.0], &[1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn parses_index_section() {
+        let config = MeminiToml::parse("[index]\nauto = false\n").unwrap().index;
+        assert!(!config.auto);
+        assert_eq!(config.refresh_minutes, DEFAULT_INDEX_REFRESH_MINUTES);
+        assert!(MeminiToml::parse("").unwrap().index.auto);
+    }
+}
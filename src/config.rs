@@ -0,0 +1,160 @@
+//! `memini.toml` — located, read and parsed once per process.
+//!
+//! Every feature's settings are one typed section of [`MeminiToml`] and
+//! are read through [`get`] (`config::get().critic`).  A file that does not
+//! load leaves every section at its defaults; the error is kept for
+//! [`load_error`], reported at startup, and makes [`checked`] fail so the
+//! policy and the command sandbox can refuse to run instead of silently
+//! dropping their rules.  `/policy reload` and `/route reset` take a fresh
+//! copy with [`read`].
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+
+use crate::app::{
+    context_files, critic, digest, live_reload, mail, memory_scope, policy, sinks, tool_loops,
+    tool_results,
+};
+use crate::code_index::IndexConfig;
+use crate::constants::DEFAULT_MAX_CONCURRENT_AGENTS;
+use crate::http::NetworkConfig;
+use crate::http_tool::HttpToolConfig;
+use crate::redact::RedactConfig;
+use crate::routing::{FallbackChain, ModelRoutes};
+use crate::sandbox::SandboxConfig;
+
+/// Every section of `memini.toml`; a missing section is its default.
+#[derive(Debug, Default, Deserialize)]
+pub struct MeminiToml {
+    #[serde(default)]
+    pub max_concurrent_agents: Option<usize>,
+    #[serde(default)]
+    pub policy: policy::PolicySection,
+    #[serde(default)]
+    pub sandbox: SandboxConfig,
+    #[serde(default)]
+    pub routing: ModelRoutes,
+    #[serde(default)]
+    pub fallback: FallbackChain,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub http_tool: HttpToolConfig,
+    #[serde(default)]
+    pub index: IndexConfig,
+    #[serde(default)]
+    pub redact: RedactConfig,
+    #[serde(default)]
+    pub live_reload: live_reload::LiveReload,
+    #[serde(default)]
+    pub digest: digest::DigestConfig,
+    #[serde(default)]
+    pub tool_loops: tool_loops::ToolLoopConfig,
+    #[serde(default)]
+    pub tool_output: tool_results::ToolOutputLimits,
+    #[serde(default)]
+    pub context_files: context_files::ContextFilesConfig,
+    #[serde(default)]
+    pub window_memory: memory_scope::WindowMemory,
+    #[serde(default)]
+    pub critic: critic::CriticConfig,
+    #[serde(default)]
+    pub smtp: Option<mail::SmtpConfig>,
+    #[serde(default)]
+    pub sinks: sinks::SinksConfig,
+}
+
+impl MeminiToml {
+    /// Parse a whole `memini.toml` document.
+    pub fn parse(raw: &str) -> Result<Self> {
+        toml::from_str(raw).context("parse memini.toml")
+    }
+
+    /// `max_concurrent_agents`, never below one.
+    pub fn max_concurrent_agents(&self) -> usize {
+        self.max_concurrent_agents
+            .map(|limit| limit.max(1))
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_AGENTS)
+    }
+}
+
+struct Loaded {
+    path: Option<PathBuf>,
+    config: MeminiToml,
+    error: Option<String>,
+}
+
+fn loaded() -> &'static Loaded {
+    static LOADED: OnceLock<Loaded> = OnceLock::new();
+    LOADED.get_or_init(|| {
+        let path = crate::util::memini_config_path();
+        match read_from(path.as_deref()) {
+            Ok(config) => Loaded {
+                path,
+                config,
+                error: None,
+            },
+            Err(err) => Loaded {
+                path,
+                config: MeminiToml::default(),
+                error: Some(format!("{err:#}")),
+            },
+        }
+    })
+}
+
+fn read_from(path: Option<&Path>) -> Result<MeminiToml> {
+    let Some(path) = path else {
+        return Ok(MeminiToml::default());
+    };
+    let raw = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    MeminiToml::parse(&raw).with_context(|| path.display().to_string())
+}
+
+/// Settings as loaded at startup (defaults when the file did not load).
+pub fn get() -> &'static MeminiToml {
+    &loaded().config
+}
+
+/// Settings as loaded at startup, or why the file did not load.
+pub fn checked() -> Result<&'static MeminiToml> {
+    let loaded = loaded();
+    match &loaded.error {
+        Some(err) => Err(anyhow!("{err}")),
+        None => Ok(&loaded.config),
+    }
+}
+
+/// Why `memini.toml` did not load at startup.
+pub fn load_error() -> Option<&'static str> {
+    loaded().error.as_deref()
+}
+
+/// The `memini.toml` in use, if one was found.
+pub fn path() -> Option<&'static Path> {
+    loaded().path.as_deref()
+}
+
+/// Read `memini.toml` again, for commands that pick up edits.
+pub fn read() -> Result<MeminiToml> {
+    read_from(crate::util::memini_config_path().as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_sections_default_and_bad_sections_fail_the_file() {
+        let config = MeminiToml::parse("[critic]\nenabled = true\n").expect("parse");
+        assert!(config.critic.enabled);
+        assert!(config.live_reload.enabled);
+        assert!(config.smtp.is_none());
+
+        assert!(MeminiToml::parse("[sandbox]\nbackend = \"chroot\"\n").is_err());
+    }
+}
@@ -3,8 +3,9 @@
 /// Application name used for config directories, Rice agent IDs, etc.
 pub const APP_NAME: &str = "memini";
 /// Application version injected from `Cargo.toml` at compile time.
-#[allow(dead_code)]
 pub const APP_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// GitHub `owner/repo` whose releases `/update` installs from.
+pub const DEFAULT_UPDATE_REPO: &str = "botent/agi-knowledge-base";
 
 /// Rice variable key for the persisted OpenAI API key.
 pub const OPENAI_KEY_VAR: &str = "openai_api_key";
@@ -12,8 +13,18 @@ pub const OPENAI_KEY_VAR: &str = "openai_api_key";
 pub const OPENAI_MODEL_VAR: &str = "openai_model";
 /// Rice variable key for reasoning effort (`low|medium|high`).
 pub const OPENAI_REASONING_EFFORT_VAR: &str = "openai_reasoning_effort";
+/// Rice variable key for the `/provider` choice (OpenAI or Azure OpenAI).
+pub const PROVIDER_VAR: &str = "memini_provider";
 /// Rice variable key for the last-used MCP server.
 pub const ACTIVE_MCP_VAR: &str = "active_mcp";
+/// Rice variable key for the ids of the connected MCP servers.
+pub const CONNECTED_MCPS_VAR: &str = "connected_mcps";
+/// Rice variable key for per-event notification settings.
+pub const NOTIFY_SETTINGS_VAR: &str = "memini_notify_settings";
+/// Rice variable key for `/route` model routes.
+pub const MODEL_ROUTES_VAR: &str = "memini_model_routes";
+/// Rice variable key for the selected `/theme`.
+pub const THEME_VAR: &str = "memini_theme";
 
 /// Default Rice run-ID when `MEMINI_RUN_ID` is not set.
 pub const DEFAULT_RUN_ID: &str = "memini";
@@ -21,20 +32,47 @@ pub const DEFAULT_RUN_ID: &str = "memini";
 pub const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini";
 /// Default OpenAI API base URL.
 pub const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+/// `api-version` sent to Azure OpenAI when none is configured.
+pub const DEFAULT_AZURE_API_VERSION: &str = "2025-04-01-preview";
 
-/// Maximum number of tool-call round-trips per chat turn.
+/// Default tool-call round trips per chat turn or task run
+/// (`[tool_loops] max_rounds`).
 pub const MAX_TOOL_LOOPS: usize = 6;
+/// Default identical tool calls allowed in one turn before they are
+/// refused as a loop (`[tool_loops] repeat_limit`).
+pub const TOOL_REPEAT_LIMIT: usize = 3;
+/// Pause before the one retry of an MCP call that failed transiently.
+pub const MCP_RETRY_DELAY_MS: u64 = 1_000;
 /// Default number of Rice memory traces to recall.
 pub const DEFAULT_MEMORY_LIMIT: u64 = 6;
+/// Most traces `/memory export` asks Rice for in one dump.
+pub const MAX_MEMORY_EXPORT_TRACES: u64 = 10_000;
+/// Most traces a `/digest` scans for its period.
+pub const MAX_DIGEST_TRACES: u64 = 2_000;
+/// Longest input or outcome of one trace in a digest's activity log.
+pub const DIGEST_ENTRY_CHARS: usize = 300;
+/// How many times `limit` traces recall asks Rice for before re-ranking
+/// by importance and recency.
+pub const RECALL_OVERFETCH: u64 = 3;
+/// Default OpenAI speech-to-text model for `/voice`.
+pub const DEFAULT_TRANSCRIPTION_MODEL: &str = "whisper-1";
+/// Default OpenAI embedding model (used when re-embedding imported traces).
+pub const DEFAULT_EMBEDDING_MODEL: &str = "text-embedding-3-small";
 /// Maximum number of log entries kept in the activity panel.
-pub const MAX_LOGS: usize = 1000;
+pub const MAX_LOGS: usize = 50_000;
 
 /// Rice variable key for the conversation thread.
 pub const CONVERSATION_THREAD_VAR: &str = "conversation_thread";
+/// Rice variable key for fingerprints of traces hidden by `/memory compact`.
+pub const PRUNED_TRACES_VAR: &str = "memini_pruned_traces";
+/// Rice variable key for distilled semantic facts (see `facts`).
+pub const SEMANTIC_FACTS_VAR: &str = "memini_semantic_facts";
 /// Rice variable key for the active agent name.
 pub const ACTIVE_AGENT_VAR: &str = "active_agent_name";
 /// Rice variable key for user-created agents.
 pub const CUSTOM_AGENTS_VAR: &str = "custom_agents";
+/// Rice variable key for named conversation sessions (`/session`).
+pub const SESSIONS_VAR: &str = "memini_sessions";
 /// Maximum number of messages kept in the conversation thread.
 pub const MAX_THREAD_MESSAGES: usize = 30;
 
@@ -47,8 +85,155 @@ pub const DAEMON_SCHEDULE_VAR: &str = "memini_daemon_schedule";
 pub const DEFAULT_AGENT_INTERVAL_SECS: u64 = 1800; // 30 minutes
 /// How many most-recent daemon results to keep in memory.
 pub const MAX_DAEMON_RESULTS: usize = 50;
+/// Default `max_concurrent_agents`: daemon runs, agent windows and chat
+/// tool loops that may call the model at once.
+pub const DEFAULT_MAX_CONCURRENT_AGENTS: usize = 4;
+
+/// Longest an agent window run may take before it is cancelled
+/// (`/spawn --timeout`, `MEMINI_AGENT_TIMEOUT`).
+pub const DEFAULT_AGENT_TIMEOUT_SECS: u64 = 30 * 60;
+/// Longest agent-window summary promoted into the parent memory scope.
+pub const PROMOTED_SUMMARY_CHARS: usize = 400;
+/// How long quitting waits for running agents to reach a safe point.
+pub const SHUTDOWN_DRAIN_SECS: u64 = 10;
+/// Quiet period before a `trigger_paths` change burst fires a daemon run.
+pub const FILE_TRIGGER_DEBOUNCE_MS: u64 = 750;
+/// Quiet period after a recipe or skill edit before it is reloaded.
+pub const LIVE_RELOAD_DEBOUNCE_MS: u64 = 500;
+/// Recent span durations kept per operation for `/stats` percentiles.
+pub const STATS_SAMPLE_WINDOW: usize = 512;
+/// OpenAI / MCP exchanges kept by `/debug http on`.
+pub const DEBUG_HTTP_CAPTURES: usize = 50;
+/// Largest file a `save_artifact` call may write.
+pub const MAX_ARTIFACT_BYTES: usize = 5 * 1024 * 1024;
+/// Most `/run` script steps executed in one main-loop tick.
+pub const SCRIPT_STEPS_PER_TICK: usize = 32;
+/// Default `[tool_output] max_chars`: longer tool results are summarized.
+pub const DEFAULT_TOOL_OUTPUT_MAX_CHARS: usize = 24_000;
+/// Characters per chunk when summarizing an oversized tool result.
+pub const TOOL_SUMMARY_CHUNK_CHARS: usize = 16_000;
+/// Most summary requests spent on one tool result; chunks grow to fit.
+pub const MAX_TOOL_SUMMARY_CHUNKS: usize = 8;
+/// Token budget (≈ chars / 4) for `/pin` content in each chat turn.
+pub const PINNED_CONTEXT_TOKENS: usize = 4_000;
+/// Default token budget for workspace `MEMINI.md` / `AGENTS.md` /
+/// `CLAUDE.md` in each chat turn and agent-window run.
+pub const CONTEXT_FILES_TOKENS: usize = 4_000;
+/// Unchanged lines kept around each hunk of a file-write diff.
+pub const DIFF_CONTEXT_LINES: usize = 3;
+/// Diff lines shown per file write before the rest is elided.
+pub const MAX_DIFF_LINES: usize = 200;
+/// Extra attempts when a structured reply does not match its JSON schema.
+pub const MAX_SCHEMA_RETRIES: usize = 2;
+/// Default time limit for a `$MEMINI_HOME/tools` plugin call.
+pub const DEFAULT_PLUGIN_TIMEOUT_SECS: u64 = 30;
+/// Plugin stdout kept per call; the rest is dropped.
+pub const MAX_PLUGIN_OUTPUT_CHARS: usize = 50_000;
 
 // ── Shared workspace constants ───────────────────────────────────────
 
 /// Rice variable key for the last-joined shared workspace.
 pub const SHARED_WORKSPACE_VAR: &str = "memini_shared_workspace";
+
+// ── Webhook constants ────────────────────────────────────────────────
+
+/// Largest webhook request body; bigger ones are refused with 413.
+pub const MAX_WEBHOOK_BODY_BYTES: usize = 64 * 1024;
+
+// ── REST API constants ───────────────────────────────────────────────
+
+/// Maximum request body accepted by the REST API (`MEMINI_API_PORT`).
+pub const MAX_API_BODY_BYTES: usize = 256 * 1024;
+
+/// Maximum JSON-RPC request body accepted by `serve-mcp --http`.
+pub const MAX_MCP_BODY_BYTES: usize = 1024 * 1024;
+
+/// How long `POST /chat` with `"wait": true` holds the request open.
+pub const API_CHAT_WAIT_SECS: u64 = 300;
+
+/// Threads serving REST API requests; further requests queue.
+pub const API_WORKER_THREADS: usize = 4;
+
+// ── Tool policy constants ────────────────────────────────────────────
+
+/// How long a `require-approval` tool call waits for `/policy approve`.
+pub const POLICY_APPROVAL_TIMEOUT_SECS: u64 = 300;
+
+// ── Status report constants ──────────────────────────────────────────
+
+/// How long `/status` waits for each MCP server to answer its ping.
+pub const STATUS_PING_TIMEOUT_SECS: u64 = 5;
+
+// ── Notification sink constants ──────────────────────────────────────
+
+/// How long a Slack or Discord webhook post may take.
+pub const SINK_POST_TIMEOUT_SECS: u64 = 10;
+/// Longest message body posted to Slack (its `text` limit is far higher).
+pub const SLACK_MESSAGE_CHARS: usize = 3_000;
+/// Longest message posted to Discord, which rejects `content` over 2000.
+pub const DISCORD_MESSAGE_CHARS: usize = 1_900;
+
+// ── Workspace index constants ────────────────────────────────────────
+
+/// Lines per embedded chunk of a workspace file.
+pub const INDEX_CHUNK_LINES: usize = 40;
+/// Lines shared by consecutive chunks so matches at a boundary survive.
+pub const INDEX_CHUNK_OVERLAP: usize = 8;
+/// Files larger than this are left out of the workspace index.
+pub const MAX_INDEX_FILE_BYTES: u64 = 256 * 1024;
+/// Most files indexed per workspace.
+pub const MAX_INDEX_FILES: usize = 5_000;
+/// Chunks sent per embeddings request.
+pub const INDEX_EMBED_BATCH: usize = 64;
+/// Default `[index] refresh_minutes` between incremental re-index passes.
+pub const DEFAULT_INDEX_REFRESH_MINUTES: u64 = 10;
+
+// ── Workspace file constants ─────────────────────────────────────────
+
+/// Bytes read from the start of a file to sniff its type.
+pub const FILE_SNIFF_BYTES: usize = 8 * 1024;
+/// Bytes `workspace_file_info` dumps when `hexdump` asks for no length.
+pub const HEXDUMP_DEFAULT_BYTES: u64 = 256;
+/// Most bytes one `workspace_file_info` hexdump may show.
+pub const HEXDUMP_MAX_BYTES: u64 = 4 * 1024;
+
+// ── SQLite tool constants ────────────────────────────────────────────
+
+/// Rows `workspace_sqlite_query` returns when the call names no limit.
+pub const SQLITE_DEFAULT_ROWS: usize = 100;
+/// Most rows one `workspace_sqlite_query` call may return.
+pub const SQLITE_MAX_ROWS: usize = 1_000;
+/// Serialized size of the returned rows before the rest are cut off.
+pub const SQLITE_MAX_RESULT_CHARS: usize = 40_000;
+/// How long one SQL statement may run before it is interrupted.
+pub const SQLITE_QUERY_TIMEOUT_SECS: u64 = 10;
+
+// ── HTTP tool constants ──────────────────────────────────────────────
+
+/// Default `[http_tool] max_response_bytes`: body bytes kept per response.
+pub const HTTP_TOOL_MAX_RESPONSE_BYTES: usize = 200_000;
+/// Default `[http_tool] timeout_secs` for one request, redirects included.
+pub const HTTP_TOOL_TIMEOUT_SECS: u64 = 30;
+
+// ── Shell session constants ──────────────────────────────────────────
+
+/// Idle seconds before an agent's shell session is closed.
+pub const SHELL_IDLE_TIMEOUT_SECS: u64 = 600;
+/// Output lines kept per shell session.
+pub const SHELL_SCROLLBACK_LINES: usize = 2_000;
+
+// ── Background process constants ─────────────────────────────────────
+
+/// Background processes one agent window may have running at once.
+pub const MAX_PROCESSES_PER_WINDOW: usize = 8;
+/// Output lines kept per background process.
+pub const PROCESS_OUTPUT_LINES: usize = 500;
+
+/// Session recordings kept in `$MEMINI_HOME/recordings`.
+pub const RECORDINGS_KEPT: usize = 20;
+
+// ── Command streaming constants ──────────────────────────────────────
+
+/// Output lines of one `workspace_run_command` streamed into an agent
+/// window; later lines only update the line counts.
+pub const COMMAND_STREAM_LINES: usize = 400;
@@ -0,0 +1,109 @@
+//! Line diffs of file writes, shown in agent windows and approval prompts.
+//!
+//! [`FileChange::diff_lines`] produces a capped unified diff wrapped in a
+//! ```` ```diff ```` fence; the agent output renderer in `app/ui.rs` colors
+//! the `+`/`-`/`@@` lines inside such fences.
+
+use similar::{ChangeTag, TextDiff};
+
+use crate::constants::{DIFF_CONTEXT_LINES, MAX_DIFF_LINES};
+
+/// Content of a file before and after a write.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileChange {
+    /// Workspace-relative path.
+    pub path: String,
+    /// `None` when the write creates the file.
+    pub before: Option<String>,
+    pub after: String,
+}
+
+impl FileChange {
+    /// `(added, removed)` line counts.
+    pub fn stats(&self) -> (usize, usize) {
+        let before = self.before.as_deref().unwrap_or("");
+        let diff = TextDiff::from_lines(before, &self.after);
+        diff.iter_all_changes()
+            .fold((0, 0), |(added, removed), change| match change.tag() {
+                ChangeTag::Insert => (added + 1, removed),
+                ChangeTag::Delete => (added, removed + 1),
+                ChangeTag::Equal => (added, removed),
+            })
+    }
+
+    /// Header plus a fenced unified diff, at most [`MAX_DIFF_LINES`] body
+    /// lines.
+    pub fn diff_lines(&self) -> Vec<String> {
+        let (added, removed) = self.stats();
+        let verb = if self.before.is_none() {
+            "created"
+        } else {
+            "changed"
+        };
+        let mut lines = vec![format!("Δ {} {verb} (+{added} -{removed})", self.path)];
+        if added == 0 && removed == 0 {
+            return lines;
+        }
+        let body = unified(self.before.as_deref().unwrap_or(""), &self.after);
+        let hidden = body.len().saturating_sub(MAX_DIFF_LINES);
+        lines.push("```diff".to_string());
+        lines.extend(body.into_iter().take(MAX_DIFF_LINES));
+        if hidden > 0 {
+            lines.push(format!("@@ … {hidden} more diff line(s) @@"));
+        }
+        lines.push("```".to_string());
+        lines
+    }
+}
+
+/// Unified diff body: `@@` hunk headers and ` `/`+`/`-` prefixed lines.
+pub fn unified(before: &str, after: &str) -> Vec<String> {
+    let diff = TextDiff::from_lines(before, after);
+    let mut lines = Vec::new();
+    for hunk in diff
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT_LINES)
+        .iter_hunks()
+    {
+        lines.push(hunk.header().to_string());
+        for change in hunk.iter_changes() {
+            let sign = match change.tag() {
+                ChangeTag::Delete => '-',
+                ChangeTag::Insert => '+',
+                ChangeTag::Equal => ' ',
+            };
+            let text = change.value().trim_end_matches(['\n', '\r']);
+            lines.push(format!("{sign}{text}"));
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unified_marks_changed_lines() {
+        let lines = unified("a\nb\nc\n", "a\nB\nc\nd\n");
+        assert_eq!(lines[0], "@@ -1,3 +1,4 @@");
+        assert!(lines.contains(&"-b".to_string()));
+        assert!(lines.contains(&"+B".to_string()));
+        assert!(lines.contains(&"+d".to_string()));
+        assert!(lines.contains(&" a".to_string()));
+    }
+
+    #[test]
+    fn new_files_are_all_additions() {
+        let change = FileChange {
+            path: "notes.md".to_string(),
+            before: None,
+            after: "one\ntwo\n".to_string(),
+        };
+        assert_eq!(change.stats(), (2, 0));
+        let lines = change.diff_lines();
+        assert_eq!(lines[0], "Δ notes.md created (+2 -0)");
+        assert_eq!(lines[1], "```diff");
+        assert_eq!(lines.last().map(String::as_str), Some("```"));
+    }
+}
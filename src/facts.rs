@@ -0,0 +1,182 @@
+//! Semantic memory — short, durable facts distilled from episodic traces.
+//!
+//! Memory has two lanes: raw episodic traces (every chat, daemon and agent
+//! run, recalled by similarity) and the semantic facts kept here ("user
+//! prefers tabs", "project uses axum 0.7").  Facts live in the
+//! `memini_semantic_facts` Rice variable and are injected into every system
+//! prompt.
+//!
+//! The built-in `facts` daemon rewrites the distilled facts from recent
+//! traces; facts added or edited with `/memory facts` are marked `manual`
+//! and never touched by distillation.
+
+use std::collections::HashSet;
+
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+
+/// Name of the built-in daemon task that distills facts.
+pub const FACTS_TASK: &str = "facts";
+/// Most facts kept; the oldest distilled ones go first.
+pub const MAX_FACTS: usize = 40;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Fact {
+    pub text: String,
+    /// `distilled` (from the facts daemon) or `manual` (from `/memory facts`).
+    #[serde(default = "distilled")]
+    pub source: String,
+    #[serde(default)]
+    pub updated_at: String,
+}
+
+fn distilled() -> String {
+    "distilled".to_string()
+}
+
+impl Fact {
+    pub fn manual(text: &str) -> Self {
+        Fact {
+            text: clean(text),
+            source: "manual".to_string(),
+            updated_at: Local::now().to_rfc3339(),
+        }
+    }
+
+    pub fn is_manual(&self) -> bool {
+        self.source == "manual"
+    }
+}
+
+/// System prompt block listing the known facts (empty when there are none).
+pub fn format_facts(facts: &[Fact]) -> String {
+    if facts.is_empty() {
+        return String::new();
+    }
+    let mut lines = vec![
+        "Known facts about the user and their projects (semantic memory; these are \
+         current and win over older episodic memories):"
+            .to_string(),
+    ];
+    lines.extend(facts.iter().map(|fact| format!("- {}", fact.text)));
+    lines.join("\n")
+}
+
+/// Bullet lines of a distillation run, or `None` when the output is not a
+/// usable fact list (errors, prose).  `NONE` means "no facts".
+pub fn parse_distilled(output: &str) -> Option<Vec<String>> {
+    let output = output.trim();
+    if output.starts_with("Error:") {
+        return None;
+    }
+    if output.eq_ignore_ascii_case("none") {
+        return Some(Vec::new());
+    }
+    let facts: Vec<String> = output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            line.strip_prefix("- ")
+                .or_else(|| line.strip_prefix("* "))
+                .map(clean)
+        })
+        .filter(|fact| !fact.is_empty())
+        .collect();
+    (!facts.is_empty()).then_some(facts)
+}
+
+/// Replace the distilled facts with a new distillation, keeping manual
+/// facts and the timestamps of facts that did not change.
+pub fn apply_distilled(existing: &[Fact], lines: Vec<String>) -> Vec<Fact> {
+    let now = Local::now().to_rfc3339();
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut facts: Vec<Fact> = existing
+        .iter()
+        .filter(|fact| fact.is_manual())
+        .filter(|fact| seen.insert(key(&fact.text)))
+        .cloned()
+        .collect();
+    for text in lines {
+        if !seen.insert(key(&text)) {
+            continue;
+        }
+        let updated_at = existing
+            .iter()
+            .find(|fact| key(&fact.text) == key(&text))
+            .map(|fact| fact.updated_at.clone())
+            .unwrap_or_else(|| now.clone());
+        facts.push(Fact {
+            text,
+            source: distilled(),
+            updated_at,
+        });
+    }
+    while facts.len() > MAX_FACTS {
+        let oldest = facts
+            .iter()
+            .enumerate()
+            .filter(|(_, fact)| !fact.is_manual())
+            .min_by(|a, b| a.1.updated_at.cmp(&b.1.updated_at))
+            .map(|(index, _)| index);
+        match oldest {
+            Some(index) => facts.remove(index),
+            None => break,
+        };
+    }
+    facts
+}
+
+/// Case- and punctuation-insensitive identity of a fact.
+fn key(text: &str) -> String {
+    text.chars()
+        .filter(|ch| ch.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn clean(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distillation_replaces_distilled_facts_only() {
+        let existing = vec![
+            Fact {
+                text: "User prefers tabs".to_string(),
+                source: "distilled".to_string(),
+                updated_at: "2026-10-01T00:00:00+00:00".to_string(),
+            },
+            Fact {
+                text: "Deploys happen on Fridays".to_string(),
+                source: "distilled".to_string(),
+                updated_at: "2026-10-02T00:00:00+00:00".to_string(),
+            },
+            Fact::manual("Project uses  axum 0.7"),
+        ];
+        let lines = parse_distilled(
+            "Here you go:\n- user prefers tabs.\n* Project uses axum 0.7\n- CI runs on GitHub Actions\n",
+        )
+        .unwrap();
+        let facts = apply_distilled(&existing, lines);
+
+        let texts: Vec<&str> = facts.iter().map(|fact| fact.text.as_str()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Project uses axum 0.7",
+                "user prefers tabs.",
+                "CI runs on GitHub Actions"
+            ]
+        );
+        assert_eq!(facts[1].updated_at, "2026-10-01T00:00:00+00:00");
+        assert!(facts[0].is_manual());
+
+        assert_eq!(parse_distilled("NONE"), Some(Vec::new()));
+        assert_eq!(parse_distilled("Error: rate limited"), None);
+        assert_eq!(parse_distilled("I could not find anything."), None);
+    }
+}
@@ -0,0 +1,150 @@
+//! Binary detection, MIME sniffing and hexdumps for workspace files.
+//!
+//! `workspace_read_file` refuses files that [`is_binary`] flags instead of
+//! decoding them into replacement characters; `workspace_file_info` reports
+//! the sniffed type and can show a bounded [`hexdump`] of the raw bytes.
+
+/// Magic numbers checked by [`sniff_mime`], in order.
+const MAGIC: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"BM", "image/bmp"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\xfd7zXZ\x00", "application/x-xz"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"\x7fELF", "application/x-elf"),
+    (b"MZ", "application/x-msdownload"),
+    (b"\xcf\xfa\xed\xfe", "application/x-mach-binary"),
+    (b"\x00asm", "application/wasm"),
+    (b"SQLite format 3\x00", "application/vnd.sqlite3"),
+    (b"OggS", "audio/ogg"),
+    (b"ID3", "audio/mpeg"),
+    (b"fLaC", "audio/flac"),
+];
+
+/// Text types by file extension, for content that is not binary.
+const TEXT_EXTENSIONS: &[(&str, &str)] = &[
+    ("json", "application/json"),
+    ("md", "text/markdown"),
+    ("html", "text/html"),
+    ("htm", "text/html"),
+    ("css", "text/css"),
+    ("csv", "text/csv"),
+    ("xml", "application/xml"),
+    ("svg", "image/svg+xml"),
+    ("js", "text/javascript"),
+    ("toml", "application/toml"),
+    ("yaml", "application/yaml"),
+    ("yml", "application/yaml"),
+];
+
+/// Whether `sample` (the start of a file) looks like binary data: it has
+/// a NUL byte, is not UTF-8, or over a tenth of it is control characters.
+pub fn is_binary(sample: &[u8]) -> bool {
+    if sample.contains(&0) {
+        return true;
+    }
+    if let Err(err) = std::str::from_utf8(sample) {
+        // A multi-byte character cut off by the end of the sample is fine.
+        if err.error_len().is_some() {
+            return true;
+        }
+    }
+    let control = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\n' | b'\r' | b'\t' | 0x0c | 0x1b))
+        .count();
+    control * 10 > sample.len()
+}
+
+/// MIME type of a file from its first bytes: by extension for text, by
+/// magic number for binary data, `application/octet-stream` when unknown.
+pub fn sniff_mime(extension: Option<&str>, sample: &[u8]) -> &'static str {
+    if !is_binary(sample) {
+        // Short magics like `BM` or `MZ` also start ordinary text.
+        let extension = extension.map(str::to_ascii_lowercase);
+        return TEXT_EXTENSIONS
+            .iter()
+            .find(|(ext, _)| extension.as_deref() == Some(*ext))
+            .map(|(_, mime)| *mime)
+            .unwrap_or("text/plain");
+    }
+    if let Some((_, mime)) = MAGIC.iter().find(|(magic, _)| sample.starts_with(magic)) {
+        return mime;
+    }
+    if sample.len() >= 12 && &sample[..4] == b"RIFF" {
+        match &sample[8..12] {
+            b"WEBP" => return "image/webp",
+            b"WAVE" => return "audio/wav",
+            _ => {}
+        }
+    }
+    if sample.len() > 262 && &sample[257..262] == b"ustar" {
+        return "application/x-tar";
+    }
+    "application/octet-stream"
+}
+
+/// `xxd`-style dump of `bytes`, numbering lines from `offset`:
+/// `00000010: 8950 4e47 0d0a 1a0a  .PNG....`.
+pub fn hexdump(bytes: &[u8], offset: u64) -> String {
+    let mut out = String::new();
+    for (row, chunk) in bytes.chunks(16).enumerate() {
+        let mut hex = String::new();
+        for (i, byte) in chunk.iter().enumerate() {
+            if i > 0 && i % 2 == 0 {
+                hex.push(' ');
+            }
+            hex.push_str(&format!("{byte:02x}"));
+        }
+        let ascii: String = chunk
+            .iter()
+            .map(|&b| {
+                if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                }
+            })
+            .collect();
+        out.push_str(&format!(
+            "{:08x}: {hex:<39}  {ascii}\n",
+            offset + (row * 16) as u64
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_binary_and_text_and_dumps_bytes() {
+        let png = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR";
+        assert!(is_binary(png));
+        assert_eq!(sniff_mime(Some("png"), png), "image/png");
+        assert!(!is_binary("héllo\nwörld\n".as_bytes()));
+        // A UTF-8 character split at the end of the sample is still text.
+        assert!(!is_binary(&"naïve".as_bytes()[..3]));
+        assert_eq!(sniff_mime(Some("MD"), b"# Title\n"), "text/markdown");
+        assert_eq!(sniff_mime(None, b"BMW service notes\n"), "text/plain");
+        assert_eq!(
+            sniff_mime(None, b"\x01\x02\x03\x04garbage"),
+            "application/octet-stream"
+        );
+
+        let dump = hexdump(png, 0x10);
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines[0],
+            "00000010: 8950 4e47 0d0a 1a0a 0000 000d 4948 4452  .PNG........IHDR"
+        );
+    }
+}
@@ -0,0 +1,232 @@
+//! Proxy and TLS settings for outgoing HTTP.
+//!
+//! The reqwest clients for the model provider, MCP servers (transport and
+//! OAuth), skill imports and the `http_request` tool are built here so corporate proxies and private
+//! CAs apply to all of them.  `memini.toml`:
+//!
+//! ```toml
+//! [network]
+//! proxy = "http://proxy.corp:3128"   # every request; "none" ignores HTTPS_PROXY
+//! no_proxy = "localhost,.corp"
+//! ca_cert = "/etc/ssl/corp-root.pem"  # extra root certificates (PEM)
+//!
+//! [network.openai]                    # also [network.mcp], [network.skills], [network.tools]
+//! proxy = "none"
+//! ```
+//!
+//! Without a `proxy`, reqwest's own `HTTPS_PROXY` / `NO_PROXY` handling
+//! applies.  MCP servers can add their own `ca_cert`, `proxy` and
+//! `insecure_skip_verify` in `mcp.json` (see [`mcp_client`]).
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use reqwest::{Certificate, ClientBuilder, NoProxy, Proxy};
+use serde::Deserialize;
+
+use crate::mcp::config::McpServer;
+
+/// Parts of memini that make HTTP requests with their own settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interface {
+    /// The OpenAI / Azure OpenAI API.
+    OpenAi,
+    /// MCP Streamable HTTP and OAuth.
+    Mcp,
+    /// `/skills import` downloads.
+    Skills,
+    /// The agents' `http_request` tool.
+    Tools,
+}
+
+/// One `[network.<interface>]` table.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct InterfaceNetwork {
+    #[serde(default)]
+    pub proxy: Option<String>,
+}
+
+/// The `[network]` section of `memini.toml`.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct NetworkConfig {
+    #[serde(default)]
+    pub proxy: Option<String>,
+    #[serde(default)]
+    pub no_proxy: Option<String>,
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+    #[serde(default)]
+    pub openai: InterfaceNetwork,
+    #[serde(default)]
+    pub mcp: InterfaceNetwork,
+    #[serde(default)]
+    pub skills: InterfaceNetwork,
+    #[serde(default)]
+    pub tools: InterfaceNetwork,
+}
+
+impl NetworkConfig {
+    /// The proxy for `interface`: its own setting, else the global one.
+    pub fn proxy_for(&self, interface: Interface) -> Option<&str> {
+        let own = match interface {
+            Interface::OpenAi => &self.openai.proxy,
+            Interface::Mcp => &self.mcp.proxy,
+            Interface::Skills => &self.skills.proxy,
+            Interface::Tools => &self.tools.proxy,
+        };
+        own.as_deref().or(self.proxy.as_deref())
+    }
+}
+
+/// The `[network]` section of `memini.toml`.
+pub fn config() -> &'static NetworkConfig {
+    &crate::config::get().network
+}
+
+/// `none` / `off` / `direct`: connect without any proxy.
+fn is_direct(proxy: &str) -> bool {
+    matches!(
+        proxy.trim().to_ascii_lowercase().as_str(),
+        "none" | "off" | "direct"
+    )
+}
+
+fn with_proxy(
+    builder: ClientBuilder,
+    proxy: Option<&str>,
+    no_proxy: Option<&str>,
+) -> Result<ClientBuilder> {
+    let Some(proxy) = proxy.map(str::trim).filter(|proxy| !proxy.is_empty()) else {
+        return Ok(builder);
+    };
+    if is_direct(proxy) {
+        return Ok(builder.no_proxy());
+    }
+    let proxy = Proxy::all(proxy)
+        .with_context(|| format!("invalid proxy '{proxy}'"))?
+        .no_proxy(no_proxy.and_then(NoProxy::from_string));
+    Ok(builder.proxy(proxy))
+}
+
+fn with_ca_cert(builder: ClientBuilder, path: &str) -> Result<ClientBuilder> {
+    let pem = fs::read(path).with_context(|| format!("read ca_cert {path}"))?;
+    let certs = Certificate::from_pem_bundle(&pem)
+        .with_context(|| format!("ca_cert {path} is not a PEM certificate bundle"))?;
+    Ok(certs
+        .into_iter()
+        .fold(builder, |builder, cert| builder.add_root_certificate(cert)))
+}
+
+/// A client builder with the `[network]` proxy and CA for `interface`.
+pub fn client_builder(interface: Interface) -> Result<ClientBuilder> {
+    let config = config();
+    let mut builder = with_proxy(
+        reqwest::Client::builder(),
+        config.proxy_for(interface),
+        config.no_proxy.as_deref(),
+    )?;
+    if let Some(path) = &config.ca_cert {
+        builder = with_ca_cert(builder, path)?;
+    }
+    Ok(builder)
+}
+
+/// A client for `interface`.
+pub fn client(interface: Interface) -> Result<reqwest::Client> {
+    client_builder(interface)?
+        .build()
+        .context("build HTTP client")
+}
+
+/// [`client`], falling back to reqwest's defaults when the `[network]`
+/// settings are broken (the error is traced and reported by
+/// [`config_problems`]).
+pub fn client_or_default(interface: Interface) -> reqwest::Client {
+    client(interface).unwrap_or_else(|err| {
+        tracing::warn!("{err:#}; using default HTTP settings");
+        reqwest::Client::new()
+    })
+}
+
+/// A client for one MCP server: `[network]` plus the server's own `proxy`,
+/// `ca_cert` and `insecure_skip_verify`.
+pub fn mcp_client(server: &McpServer) -> Result<reqwest::Client> {
+    let mut builder = client_builder(Interface::Mcp)?;
+    if server.proxy.is_some() {
+        builder = with_proxy(
+            builder,
+            server.proxy.as_deref(),
+            config().no_proxy.as_deref(),
+        )?;
+    }
+    if let Some(path) = &server.ca_cert {
+        builder = with_ca_cert(builder, path)?;
+    }
+    if server.insecure_skip_verify {
+        tracing::warn!(
+            server = %server.id,
+            "TLS certificate verification is disabled (insecure_skip_verify)"
+        );
+        builder = builder.danger_accept_invalid_certs(true);
+    }
+    builder
+        .build()
+        .with_context(|| format!("build HTTP client for MCP server '{}'", server.id))
+}
+
+/// Problems with the `[network]` settings, one line each, for the startup
+/// log.
+pub fn config_problems() -> Vec<String> {
+    [
+        Interface::OpenAi,
+        Interface::Mcp,
+        Interface::Skills,
+        Interface::Tools,
+    ]
+    .into_iter()
+    .filter_map(|interface| client(interface).err())
+    .map(|err| format!("[network] in memini.toml: {err:#}"))
+    .fold(Vec::new(), |mut problems, problem| {
+        if !problems.contains(&problem) {
+            problems.push(problem);
+        }
+        problems
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn interface_proxy_overrides_the_global_one() {
+        let config = MeminiToml::parse(
+            "[network]\nproxy = \"http://proxy.corp:3128\"\n\n[network.openai]\nproxy = \"none\"\n",
+        )
+        .expect("parse")
+        .network;
+        assert_eq!(
+            config.proxy_for(Interface::Mcp),
+            Some("http://proxy.corp:3128")
+        );
+        assert_eq!(config.proxy_for(Interface::OpenAi), Some("none"));
+        assert!(is_direct("None"));
+        assert!(
+            with_proxy(
+                reqwest::Client::builder(),
+                Some("http://proxy.corp:3128"),
+                None
+            )
+            .is_ok()
+        );
+        assert!(with_proxy(reqwest::Client::builder(), Some("::not a url"), None).is_err());
+        assert!(
+            MeminiToml::parse("")
+                .expect("parse")
+                .network
+                .proxy
+                .is_none()
+        );
+    }
+}
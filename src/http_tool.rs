@@ -0,0 +1,273 @@
+//! `http_request` — a generic HTTP tool for agents, limited to the domains
+//! listed in `memini.toml`:
+//!
+//! ```toml
+//! [http_tool]
+//! allow_domains = ["api.internal.corp", "*.example.com"]
+//! max_response_bytes = 200000   # body bytes kept (default 200 KB)
+//! timeout_secs = 30
+//! ```
+//!
+//! `*.example.com` matches subdomains of `example.com`, not the bare domain.
+//! The tool is only offered while `allow_domains` is non-empty, redirects
+//! must stay on allowed domains, and the `[network]` proxy and CA settings
+//! apply (see [`crate::http`]).
+
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::Method;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use url::Url;
+
+use crate::constants::{HTTP_TOOL_MAX_RESPONSE_BYTES, HTTP_TOOL_TIMEOUT_SECS};
+use crate::http::Interface;
+
+pub const HTTP_REQUEST_TOOL: &str = "http_request";
+
+/// Most redirects followed per request.
+const MAX_REDIRECTS: usize = 5;
+
+/// The `[http_tool]` section of `memini.toml`.
+#[derive(Clone, Debug, Deserialize)]
+pub struct HttpToolConfig {
+    #[serde(default)]
+    pub allow_domains: Vec<String>,
+    #[serde(default = "default_max_response_bytes")]
+    pub max_response_bytes: usize,
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_max_response_bytes() -> usize {
+    HTTP_TOOL_MAX_RESPONSE_BYTES
+}
+
+fn default_timeout_secs() -> u64 {
+    HTTP_TOOL_TIMEOUT_SECS
+}
+
+impl Default for HttpToolConfig {
+    fn default() -> Self {
+        HttpToolConfig {
+            allow_domains: Vec::new(),
+            max_response_bytes: HTTP_TOOL_MAX_RESPONSE_BYTES,
+            timeout_secs: HTTP_TOOL_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl HttpToolConfig {
+    /// Whether `host` is on the allowlist.
+    pub fn allows(&self, host: &str) -> bool {
+        let host = host.trim_end_matches('.').to_ascii_lowercase();
+        self.allow_domains.iter().any(|pattern| {
+            let pattern = pattern.trim().to_ascii_lowercase();
+            match pattern.strip_prefix("*.") {
+                Some(suffix) => host
+                    .strip_suffix(suffix)
+                    .is_some_and(|rest| rest.ends_with('.')),
+                None => host == pattern,
+            }
+        })
+    }
+
+    /// The URL parsed, refused unless it is http(s) on an allowed domain.
+    fn check_url(&self, raw: &str) -> Result<Url> {
+        let url = Url::parse(raw).with_context(|| format!("invalid URL '{raw}'"))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            bail!("only http and https URLs are supported");
+        }
+        let host = url.host_str().ok_or_else(|| anyhow!("URL has no host"))?;
+        if !self.allows(host) {
+            bail!("domain '{host}' is not in [http_tool] allow_domains in memini.toml");
+        }
+        Ok(url)
+    }
+}
+
+/// The `[http_tool]` section of `memini.toml`.
+pub fn config() -> &'static HttpToolConfig {
+    &crate::config::get().http_tool
+}
+
+/// The tool definition, or `None` while no domain is allowed.
+pub fn tool_def() -> Option<Value> {
+    let config = config();
+    if config.allow_domains.is_empty() {
+        return None;
+    }
+    Some(json!({
+        "type": "function",
+        "name": HTTP_REQUEST_TOOL,
+        "description": format!(
+            "Make an HTTP request and return the status, headers and body. Only these domains are allowed: {}.",
+            config.allow_domains.join(", ")
+        ),
+        "parameters": {
+            "type": "object",
+            "properties": {
+                "method": {
+                    "type": "string",
+                    "description": "HTTP method (default GET)."
+                },
+                "url": {
+                    "type": "string",
+                    "description": "Full http(s) URL."
+                },
+                "headers": {
+                    "type": "object",
+                    "description": "Request headers as name/value strings.",
+                    "additionalProperties": { "type": "string" }
+                },
+                "body": {
+                    "type": "string",
+                    "description": "Request body, sent as-is. Set a Content-Type header for JSON."
+                }
+            },
+            "required": ["url"]
+        }
+    }))
+}
+
+fn client(config: &HttpToolConfig) -> Result<reqwest::Client> {
+    let allowed = config.clone();
+    let redirects = reqwest::redirect::Policy::custom(move |attempt| {
+        if attempt.previous().len() >= MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else if attempt
+            .url()
+            .host_str()
+            .is_some_and(|host| allowed.allows(host))
+        {
+            attempt.follow()
+        } else {
+            let target = attempt.url().to_string();
+            attempt.error(format!("redirect to {target} leaves the allowed domains"))
+        }
+    });
+    crate::http::client_builder(Interface::Tools)?
+        .redirect(redirects)
+        .timeout(Duration::from_secs(config.timeout_secs.max(1)))
+        .build()
+        .context("build HTTP client")
+}
+
+fn headers(args: &Value) -> Result<HeaderMap> {
+    let mut map = HeaderMap::new();
+    let Some(fields) = args.get("headers").and_then(Value::as_object) else {
+        return Ok(map);
+    };
+    for (name, value) in fields {
+        let value = value
+            .as_str()
+            .ok_or_else(|| anyhow!("header '{name}' must be a string"))?;
+        map.insert(
+            HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("invalid header name '{name}'"))?,
+            HeaderValue::from_str(value)
+                .with_context(|| format!("invalid value for header '{name}'"))?,
+        );
+    }
+    Ok(map)
+}
+
+/// Handle an `http_request` call.
+pub async fn handle_tool_call(args: &Value) -> Result<Value> {
+    let config = config();
+    let url = args
+        .get("url")
+        .and_then(Value::as_str)
+        .ok_or_else(|| anyhow!("url is required"))?;
+    let url = config.check_url(url)?;
+    let method = args
+        .get("method")
+        .and_then(Value::as_str)
+        .unwrap_or("GET")
+        .to_ascii_uppercase();
+    let method = Method::from_bytes(method.as_bytes())
+        .with_context(|| format!("invalid method '{method}'"))?;
+
+    let mut request = client(config)?
+        .request(method.clone(), url.clone())
+        .headers(headers(args)?);
+    if let Some(body) = args.get("body").and_then(Value::as_str) {
+        request = request.body(body.to_string());
+    }
+    let mut response = request
+        .send()
+        .await
+        .with_context(|| format!("{method} {url} failed"))?;
+
+    let status = response.status();
+    let response_headers: serde_json::Map<String, Value> = response
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), json!(value.to_str().ok()?))))
+        .collect();
+    let mut body = Vec::new();
+    let mut truncated = false;
+    while let Some(chunk) = response.chunk().await.context("read response body")? {
+        let room = config.max_response_bytes.saturating_sub(body.len());
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            truncated = true;
+            break;
+        }
+        body.extend_from_slice(&chunk);
+    }
+    let body = match String::from_utf8(body) {
+        Ok(text) => text,
+        // A cut can split a multi-byte character; anything else is binary.
+        Err(err) if truncated && err.utf8_error().error_len().is_none() => {
+            let valid = err.utf8_error().valid_up_to();
+            String::from_utf8_lossy(&err.as_bytes()[..valid]).to_string()
+        }
+        Err(err) => format!("<{} bytes of binary data>", err.as_bytes().len()),
+    };
+
+    Ok(json!({
+        "url": response.url().to_string(),
+        "status": status.as_u16(),
+        "headers": response_headers,
+        "body": body,
+        "truncated": truncated,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn allowlist_matches_exact_hosts_and_subdomain_wildcards() {
+        let config = MeminiToml::parse(
+            "[http_tool]\nallow_domains = [\"api.internal.corp\", \"*.example.com\"]\n",
+        )
+        .expect("parse")
+        .http_tool;
+        assert_eq!(config.timeout_secs, HTTP_TOOL_TIMEOUT_SECS);
+        assert!(config.allows("API.internal.corp"));
+        assert!(config.allows("v2.example.com"));
+        assert!(!config.allows("example.com"));
+        assert!(!config.allows("badexample.com"));
+        assert!(!config.allows("internal.corp"));
+
+        assert!(config.check_url("https://v2.example.com/items?q=1").is_ok());
+        let err = config
+            .check_url("https://evil.test/")
+            .expect_err("not allowed");
+        assert!(err.to_string().contains("allow_domains"));
+        assert!(config.check_url("file:///etc/passwd").is_err());
+        assert!(
+            MeminiToml::parse("")
+                .expect("parse")
+                .http_tool
+                .allow_domains
+                .is_empty()
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! UI string catalogs, chosen by `MEMINI_LANG` or `/lang`.
+//!
+//! A catalog is a TOML bundle keyed by the English text it translates:
+//!
+//! ```toml
+//! name = "Deutsch"
+//!
+//! [strings]
+//! "Cancel an agent window's run" = "Den Lauf eines Agentenfensters abbrechen"
+//! "needs input" = "wartet auf Eingabe"
+//! ```
+//!
+//! English needs no bundle: [`t`] returns its input when the active
+//! catalog has no entry, so a partial translation falls back line by line.
+//! Bundles ship in `locales/` and can be added or overridden in
+//! `$MEMINI_HOME/locales/<lang>.toml`; user entries win over shipped ones.
+//! Catalogs cover help output, window status labels and log level
+//! prefixes; model prompts and tool output stay English.
+
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+/// The language every string is written in.
+pub const DEFAULT_LANG: &str = "en";
+
+/// Bundles compiled into the binary.
+const BUILTIN: &[(&str, &str)] = &[("de", include_str!("../locales/de.toml"))];
+
+#[derive(Debug, Deserialize)]
+struct Bundle {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    strings: HashMap<String, String>,
+}
+
+/// Translations for one language.
+#[derive(Debug)]
+pub struct Catalog {
+    pub lang: String,
+    /// Display name from the bundle (`Deutsch`), else the code.
+    pub name: String,
+    strings: HashMap<String, String>,
+}
+
+impl Catalog {
+    fn english() -> Self {
+        Catalog {
+            lang: DEFAULT_LANG.to_string(),
+            name: "English".to_string(),
+            strings: HashMap::new(),
+        }
+    }
+
+    /// `text` translated, or `text` itself.
+    pub fn get<'a>(&'a self, text: &'a str) -> &'a str {
+        self.strings.get(text).map(String::as_str).unwrap_or(text)
+    }
+
+    /// Number of translated strings.
+    pub fn translated(&self) -> usize {
+        self.strings.len()
+    }
+
+    fn merge(&mut self, raw: &str, origin: &str) -> Result<()> {
+        let bundle: Bundle = toml::from_str(raw).with_context(|| format!("parse {origin}"))?;
+        if let Some(name) = bundle.name {
+            self.name = name;
+        }
+        self.strings.extend(bundle.strings);
+        Ok(())
+    }
+}
+
+fn active() -> &'static RwLock<&'static Catalog> {
+    static ACTIVE: OnceLock<RwLock<&'static Catalog>> = OnceLock::new();
+    ACTIVE.get_or_init(|| RwLock::new(Box::leak(Box::new(Catalog::english()))))
+}
+
+/// The active catalog.
+pub fn current() -> &'static Catalog {
+    active()
+        .read()
+        .map(|catalog| *catalog)
+        .unwrap_or_else(|err| *err.into_inner())
+}
+
+/// `text` in the active language, or `text` itself.
+pub fn t(text: &str) -> &str {
+    current().get(text)
+}
+
+/// Make `catalog` active.  Catalogs are small and only replaced by
+/// `/lang`, so the old one is simply leaked.
+pub fn set(catalog: Catalog) {
+    let catalog: &'static Catalog = Box::leak(Box::new(catalog));
+    match active().write() {
+        Ok(mut slot) => *slot = catalog,
+        Err(err) => *err.into_inner() = catalog,
+    }
+}
+
+/// `de_DE.UTF-8`, `de-AT` and `DE` all mean `de`.
+pub fn normalize(lang: &str) -> String {
+    lang.trim()
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Directory of user bundles.
+pub fn locales_dir() -> PathBuf {
+    crate::util::memini_home().join("locales")
+}
+
+/// Load the catalog for `lang`: the shipped bundle, then the user's.
+pub fn load(lang: &str) -> Result<Catalog> {
+    let lang = normalize(lang);
+    if lang.is_empty() {
+        bail!("No language given");
+    }
+    if lang == DEFAULT_LANG {
+        return Ok(Catalog::english());
+    }
+    let mut catalog = Catalog {
+        lang: lang.clone(),
+        name: lang.clone(),
+        strings: HashMap::new(),
+    };
+    let mut found = false;
+    if let Some((_, raw)) = BUILTIN.iter().find(|(code, _)| *code == lang) {
+        catalog.merge(raw, &format!("built-in {lang} bundle"))?;
+        found = true;
+    }
+    let path = locales_dir().join(format!("{lang}.toml"));
+    if path.is_file() {
+        let raw = fs::read_to_string(&path).with_context(|| format!("read {}", path.display()))?;
+        catalog.merge(&raw, &path.display().to_string())?;
+        found = true;
+    }
+    if !found {
+        bail!(
+            "No strings for '{lang}'. Available: {}",
+            available().join(", ")
+        );
+    }
+    Ok(catalog)
+}
+
+/// Language codes with a shipped or user bundle, English first.
+pub fn available() -> Vec<String> {
+    let mut codes: BTreeSet<String> = BUILTIN.iter().map(|(code, _)| code.to_string()).collect();
+    if let Ok(entries) = fs::read_dir(locales_dir()) {
+        codes.extend(entries.flatten().filter_map(|entry| {
+            let path = entry.path();
+            (path.extension()? == "toml")
+                .then(|| path.file_stem()?.to_str().map(normalize))
+                .flatten()
+        }));
+    }
+    codes.remove(DEFAULT_LANG);
+    std::iter::once(DEFAULT_LANG.to_string())
+        .chain(codes)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookups_fall_back_to_english() {
+        assert_eq!(normalize("de_DE.UTF-8"), "de");
+        assert_eq!(normalize(" PT-br "), "pt");
+
+        let mut catalog = Catalog::english();
+        catalog
+            .merge(
+                "name = \"Test\"\n[strings]\n\"done\" = \"fertig\"\n",
+                "test",
+            )
+            .expect("bundle");
+        assert_eq!(catalog.name, "Test");
+        assert_eq!(catalog.get("done"), "fertig");
+        assert_eq!(catalog.get("killed"), "killed");
+
+        let german = load("de").expect("built-in bundle");
+        assert_eq!(german.get("WARN"), "WARNUNG");
+        assert!(load("xx").is_err());
+    }
+}
@@ -0,0 +1,418 @@
+//! Memory importance scoring and recency decay for Rice traces.
+//!
+//! Every committed trace is scored with a cheap heuristic (explicit "remember
+//! this" requests and substantive answers score high, small talk and errors
+//! low).  The score and commit time travel in the trace's `reasoning` field
+//! as a small JSON object, since the Rice `Trace` has no metadata slot:
+//!
+//! ```json
+//! {"importance":0.8,"committed_at":"2026-10-16T09:30:00+00:00"}
+//! ```
+//!
+//! Traces from model runs also carry their provenance there (model, tools,
+//! tokens, duration; see [`crate::provenance`]).
+//!
+//! Recall over-fetches from Rice and re-ranks by
+//! `relevance × importance × decay`, where decay halves every
+//! [`HALF_LIFE_DAYS`].  `/memory compact` uses [`plan_compaction`] to merge
+//! repeated traces and prune ones whose value has decayed away; Rice cannot
+//! delete traces, so pruned ones are remembered by [`fingerprint`] and
+//! filtered out of recall.
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, FixedOffset, Local};
+use rice::rice_state::proto::Trace;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::provenance::{Critique, TokenUsage};
+
+/// Days for a trace's recency weight to halve.
+pub const HALF_LIFE_DAYS: f64 = 14.0;
+/// Recency weight never drops below this, so old but important traces
+/// can still surface.
+const MIN_DECAY: f64 = 0.1;
+/// Decay assumed for traces committed before scoring existed.
+const LEGACY_DECAY: f64 = 0.5;
+/// Importance assumed for traces committed before scoring existed.
+const LEGACY_IMPORTANCE: f64 = 0.5;
+/// Traces whose `importance × decay` falls below this are pruned.
+pub const PRUNE_THRESHOLD: f64 = 0.08;
+/// Longest outcome a merged trace keeps.
+const MAX_MERGED_OUTCOME: usize = 4_000;
+
+/// Score, commit time and provenance stored alongside a trace.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TraceMeta {
+    pub importance: f64,
+    pub committed_at: String,
+    /// Model that produced the outcome, when known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Tools called on the way to the outcome (`server/tool` for MCP).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tools: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tokens: Option<TokenUsage>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+    /// The critic model's review, when `[critic]` is enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub critique: Option<Critique>,
+}
+
+impl TraceMeta {
+    /// Score a trace about to be committed now.
+    pub fn score(input: &str, outcome: &str, action: &str) -> Self {
+        TraceMeta {
+            importance: score(input, outcome, action),
+            committed_at: Local::now().to_rfc3339(),
+            ..TraceMeta::default()
+        }
+    }
+
+    pub fn with_model(mut self, model: &str) -> Self {
+        self.model = Some(model.to_string());
+        self
+    }
+
+    /// Read the metadata back out of a trace's `reasoning` field.
+    pub fn parse(reasoning: &str) -> Option<Self> {
+        serde_json::from_str(reasoning.trim()).ok()
+    }
+
+    pub fn encode(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    pub fn committed_at(&self) -> Option<DateTime<FixedOffset>> {
+        DateTime::parse_from_rfc3339(&self.committed_at).ok()
+    }
+}
+
+/// Heuristic importance in `0.05..=1.0`.
+pub fn score(input: &str, outcome: &str, action: &str) -> f64 {
+    let input_lower = input.to_ascii_lowercase();
+    let outcome = outcome.trim();
+    let mut score: f64 = 0.4;
+
+    const SALIENT: &[&str] = &[
+        "remember",
+        "don't forget",
+        "important",
+        "always",
+        "never",
+        "my name",
+        "i prefer",
+        "prefer ",
+        "deadline",
+        "decided",
+        "decision",
+        "from now on",
+        "todo",
+    ];
+    if SALIENT.iter().any(|marker| input_lower.contains(marker)) {
+        score += 0.3;
+    }
+    if action.contains("memory_commit") {
+        score += 0.3;
+    }
+
+    const SMALL_TALK: &[&str] = &[
+        "hi",
+        "hello",
+        "hey",
+        "thanks",
+        "thank you",
+        "ok",
+        "okay",
+        "cool",
+        "nice",
+        "bye",
+    ];
+    let bare = input_lower.trim().trim_end_matches(['!', '.', '?']);
+    if SMALL_TALK.contains(&bare) {
+        score -= 0.3;
+    }
+
+    if outcome.is_empty() {
+        score -= 0.3;
+    } else if outcome.starts_with("Error:") {
+        score -= 0.2;
+    } else if outcome.len() < 40 {
+        score -= 0.1;
+    } else if outcome.len() > 400 {
+        score += 0.1;
+    }
+
+    score.clamp(0.05, 1.0)
+}
+
+/// Recency weight for a trace committed at `committed_at`.
+pub fn decay(committed_at: DateTime<FixedOffset>, now: DateTime<FixedOffset>) -> f64 {
+    let age_days = (now - committed_at).num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(age_days / HALF_LIFE_DAYS).max(MIN_DECAY)
+}
+
+/// `importance × decay` of a trace, with neutral values for unscored ones.
+pub fn value(trace: &Trace, now: DateTime<FixedOffset>) -> f64 {
+    match TraceMeta::parse(&trace.reasoning) {
+        Some(meta) => {
+            let recency = meta
+                .committed_at()
+                .map(|at| decay(at, now))
+                .unwrap_or(LEGACY_DECAY);
+            meta.importance * recency
+        }
+        None => LEGACY_IMPORTANCE * LEGACY_DECAY,
+    }
+}
+
+/// Re-rank traces Rice returned in relevance order and keep the best `limit`.
+pub fn rank(traces: Vec<Trace>, limit: usize, now: DateTime<FixedOffset>) -> Vec<Trace> {
+    let mut scored: Vec<(f64, Trace)> = traces
+        .into_iter()
+        .enumerate()
+        .map(|(position, trace)| {
+            let relevance = 1.0 / (1.0 + position as f64 * 0.25);
+            (relevance * value(&trace, now), trace)
+        })
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .take(limit)
+        .map(|(_, trace)| trace)
+        .collect()
+}
+
+/// Stable identity of a trace, used to hide pruned ones from recall.
+pub fn fingerprint(trace: &Trace) -> String {
+    let mut hasher = Sha256::new();
+    for part in [&trace.agent_id, &trace.action, &trace.input, &trace.outcome] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    hasher.finalize()[..16]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// A trace that replaces several repeats of the same request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergedTrace {
+    pub input: String,
+    pub action: String,
+    pub agent_id: String,
+    pub outcome: String,
+    pub meta: TraceMeta,
+    /// How many traces were folded into this one.
+    pub sources: usize,
+}
+
+/// What `/memory compact` would do.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompactionPlan {
+    pub merges: Vec<MergedTrace>,
+    /// Fingerprints of every trace to hide, merged sources included.
+    pub removed: Vec<String>,
+    /// Traces pruned for low value (not merged).
+    pub pruned: usize,
+    /// Repeats dropped in favour of an identical newer trace.
+    pub duplicates: usize,
+    pub kept: usize,
+}
+
+/// Merge traces that repeat the same request and prune low-value ones.
+pub fn plan_compaction(traces: &[Trace], now: DateTime<FixedOffset>) -> CompactionPlan {
+    let mut groups: BTreeMap<(String, String, String), Vec<&Trace>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for trace in traces {
+        if !seen.insert(fingerprint(trace)) {
+            continue;
+        }
+        let key = (
+            trace.agent_id.clone(),
+            trace.action.clone(),
+            normalize(&trace.input),
+        );
+        groups.entry(key).or_default().push(trace);
+    }
+
+    let mut plan = CompactionPlan::default();
+    for mut group in groups.into_values() {
+        if group.len() == 1 {
+            let trace = group[0];
+            let scored = TraceMeta::parse(&trace.reasoning).is_some();
+            if trace.outcome.trim().is_empty() || (scored && value(trace, now) < PRUNE_THRESHOLD) {
+                plan.removed.push(fingerprint(trace));
+                plan.pruned += 1;
+            } else {
+                plan.kept += 1;
+            }
+            continue;
+        }
+
+        // Newest first, so the merged outcome leads with the latest answer.
+        group.sort_by_key(|trace| {
+            std::cmp::Reverse(
+                TraceMeta::parse(&trace.reasoning)
+                    .map(|meta| meta.committed_at)
+                    .unwrap_or_default(),
+            )
+        });
+        let mut outcomes: Vec<&str> = Vec::new();
+        for trace in &group {
+            let outcome = trace.outcome.trim();
+            if !outcome.is_empty() && !outcomes.contains(&outcome) {
+                outcomes.push(outcome);
+            }
+        }
+        // Repeats that all said the same thing: keep the newest as is
+        // (a merged copy would share its fingerprint and be hidden too).
+        if outcomes.len() <= 1 && group[0].outcome.trim() == outcomes.first().copied().unwrap_or("")
+        {
+            plan.removed
+                .extend(group[1..].iter().map(|trace| fingerprint(trace)));
+            plan.duplicates += group.len() - 1;
+            plan.kept += 1;
+            continue;
+        }
+        let mut outcome = outcomes.join("\n\n");
+        if outcome.len() > MAX_MERGED_OUTCOME {
+            let cut = (0..=MAX_MERGED_OUTCOME)
+                .rev()
+                .find(|index| outcome.is_char_boundary(*index))
+                .unwrap_or(0);
+            outcome.truncate(cut);
+            outcome.push('…');
+        }
+        let importance = group
+            .iter()
+            .map(|trace| {
+                TraceMeta::parse(&trace.reasoning)
+                    .map(|meta| meta.importance)
+                    .unwrap_or(LEGACY_IMPORTANCE)
+            })
+            .fold(0.0, f64::max);
+        let first = TraceMeta::parse(&group[0].reasoning);
+        let committed_at = first
+            .as_ref()
+            .map(|meta| meta.committed_at.clone())
+            .unwrap_or_else(|| now.to_rfc3339());
+
+        plan.removed
+            .extend(group.iter().map(|trace| fingerprint(trace)));
+        plan.merges.push(MergedTrace {
+            input: group[0].input.clone(),
+            action: group[0].action.clone(),
+            agent_id: group[0].agent_id.clone(),
+            outcome,
+            meta: TraceMeta {
+                importance,
+                committed_at,
+                ..first.unwrap_or_default()
+            },
+            sources: group.len(),
+        });
+    }
+    plan
+}
+
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trace(input: &str, outcome: &str, meta: Option<TraceMeta>) -> Trace {
+        Trace {
+            input: input.to_string(),
+            reasoning: meta.map(|meta| meta.encode()).unwrap_or_default(),
+            action: "chat".to_string(),
+            outcome: outcome.to_string(),
+            agent_id: "memini@ana".to_string(),
+            embedding: Vec::new(),
+            run_id: "memini".to_string(),
+        }
+    }
+
+    fn meta(importance: f64, committed_at: &str) -> Option<TraceMeta> {
+        Some(TraceMeta {
+            importance,
+            committed_at: committed_at.to_string(),
+            ..TraceMeta::default()
+        })
+    }
+
+    #[test]
+    fn scores_and_ranks_by_importance_and_recency() {
+        let answer = "Postgres 16 is the production database; migrations run via sqlx.";
+        assert!(score("remember that we use Postgres", answer, "chat") > score("hi", "", "chat"));
+        assert!(score("thanks!", "You're welcome", "chat") < 0.2);
+
+        let now = DateTime::parse_from_rfc3339("2026-10-16T12:00:00+00:00").unwrap();
+        let fresh = DateTime::parse_from_rfc3339("2026-10-16T00:00:00+00:00").unwrap();
+        let month_old = DateTime::parse_from_rfc3339("2026-09-16T12:00:00+00:00").unwrap();
+        assert!(decay(fresh, now) > 0.95);
+        assert!(decay(month_old, now) < 0.25);
+
+        let stale = trace(
+            "old chatter",
+            answer,
+            meta(0.3, "2026-06-01T00:00:00+00:00"),
+        );
+        let recent = trace("db choice", answer, meta(0.9, "2026-10-15T00:00:00+00:00"));
+        let ranked = rank(vec![stale, recent], 1, now);
+        assert_eq!(ranked[0].input, "db choice");
+    }
+
+    #[test]
+    fn plans_merges_and_prunes() {
+        let now = DateTime::parse_from_rfc3339("2026-10-16T12:00:00+00:00").unwrap();
+        let traces = vec![
+            trace(
+                "What is the DB?",
+                "Postgres.",
+                meta(0.4, "2026-10-01T00:00:00+00:00"),
+            ),
+            trace(
+                "what is  the db?",
+                "Postgres 16.",
+                meta(0.7, "2026-10-10T00:00:00+00:00"),
+            ),
+            trace("hi", "Hello!", meta(0.1, "2026-07-01T00:00:00+00:00")),
+            trace(
+                "deploy steps",
+                "Run make deploy.",
+                meta(0.6, "2026-10-14T00:00:00+00:00"),
+            ),
+            trace("legacy", "kept without metadata", None),
+            trace(
+                "Deploy steps",
+                "Run make deploy.",
+                meta(0.5, "2026-10-02T00:00:00+00:00"),
+            ),
+        ];
+        let plan = plan_compaction(&traces, now);
+
+        assert_eq!(plan.merges.len(), 1);
+        let merged = &plan.merges[0];
+        assert_eq!(merged.sources, 2);
+        assert_eq!(merged.outcome, "Postgres 16.\n\nPostgres.");
+        assert_eq!(merged.meta.importance, 0.7);
+        assert_eq!(plan.pruned, 1);
+        assert_eq!(plan.duplicates, 1);
+        assert_eq!(plan.kept, 2);
+        assert_eq!(plan.removed.len(), 4);
+        assert!(plan.removed.contains(&fingerprint(&traces[5])));
+        assert!(plan.removed.contains(&fingerprint(&traces[2])));
+    }
+}
@@ -1,25 +1,54 @@
 //! Local workspace tools for autonomous agent actions.
 //!
 //! These tools let spawned agents inspect and modify files in the current
-//! workspace, and run shell commands in that workspace.
+//! workspace, and run shell commands in that workspace.  Plugin tools from
+//! `$MEMINI_HOME/tools` (see [`crate::plugins`]) are listed and dispatched
+//! alongside them.
 
 use std::collections::VecDeque;
 use std::env;
 use std::fs;
-use std::path::{Component, Path, PathBuf};
+use std::future::Future;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
 
 use anyhow::{Context, Result, anyhow, bail};
 use serde_json::{Value, json};
-use tokio::time::{Duration, timeout};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::sync::{Notify, mpsc};
+use tokio::time::{Duration, Instant};
 
+use crate::constants::{FILE_SNIFF_BYTES, HEXDUMP_DEFAULT_BYTES, HEXDUMP_MAX_BYTES};
+use crate::diff::FileChange;
+use crate::filetype;
 use crate::openai::ToolCall;
+use crate::platform::{self, normalize_path};
+use crate::sandbox;
 
 const MAX_LIST_ENTRIES: usize = 1000;
 const MAX_READ_CHARS: usize = 50_000;
-const MAX_COMMAND_TIMEOUT_SECS: u64 = 300;
-const MAX_OUTPUT_CHARS: usize = 12_000;
+pub(crate) const MAX_COMMAND_TIMEOUT_SECS: u64 = 300;
+pub(crate) const MAX_OUTPUT_CHARS: usize = 12_000;
+/// Where `workspace_delete` moves things, under the workspace root.
+pub const TRASH_DIR: &str = ".memini-trash";
+/// Tools that rearrange the workspace besides `workspace_write_file`.
+pub const REORGANIZE_TOOLS: &[&str] = &[
+    "workspace_move",
+    "workspace_copy",
+    "workspace_delete",
+    "workspace_mkdir",
+];
+/// Tools that run a shell command from their `command` argument.
+pub const COMMAND_TOOLS: &[&str] = &["workspace_run_command", "shell_exec", "process_spawn"];
 
 pub fn tool_defs() -> Vec<Value> {
+    let shell = sandbox::load_config()
+        .ok()
+        .and_then(|config| config.shell)
+        .unwrap_or_else(platform::Shell::native)
+        .label();
     vec![
         json!({
             "type": "function",
@@ -46,7 +75,7 @@ pub fn tool_defs() -> Vec<Value> {
         json!({
             "type": "function",
             "name": "workspace_read_file",
-            "description": "Read a UTF-8 text file from the local workspace.",
+            "description": "Read a UTF-8 text file from the local workspace. Binary files are refused; use workspace_file_info for those.",
             "parameters": {
                 "type": "object",
                 "properties": {
@@ -62,6 +91,33 @@ pub fn tool_defs() -> Vec<Value> {
                 "required": ["path"]
             }
         }),
+        json!({
+            "type": "function",
+            "name": "workspace_file_info",
+            "description": "Describe a workspace file without reading it all: size, sniffed MIME type, whether it is binary, and line count for text. Set hexdump to see raw bytes.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Relative path to the file inside the workspace."
+                    },
+                    "hexdump": {
+                        "type": "boolean",
+                        "description": "Include a hexdump of the file's bytes (default false)."
+                    },
+                    "offset": {
+                        "type": "integer",
+                        "description": "Byte offset the hexdump starts at (default 0)."
+                    },
+                    "length": {
+                        "type": "integer",
+                        "description": "Bytes to dump (default 256, max 4096)."
+                    }
+                },
+                "required": ["path"]
+            }
+        }),
         json!({
             "type": "function",
             "name": "workspace_write_file",
@@ -89,10 +145,86 @@ pub fn tool_defs() -> Vec<Value> {
                 "required": ["path", "content"]
             }
         }),
+        json!({
+            "type": "function",
+            "name": "workspace_move",
+            "description": "Move or rename a file or directory inside the local workspace.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Relative path of the file or directory to move."
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Relative path it should have afterwards."
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "When true, replace an existing destination file (default false)."
+                    }
+                },
+                "required": ["source", "destination"]
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "workspace_copy",
+            "description": "Copy a file or directory (recursively) inside the local workspace.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "source": {
+                        "type": "string",
+                        "description": "Relative path of the file or directory to copy."
+                    },
+                    "destination": {
+                        "type": "string",
+                        "description": "Relative path of the copy."
+                    },
+                    "overwrite": {
+                        "type": "boolean",
+                        "description": "When true, replace an existing destination file (default false)."
+                    }
+                },
+                "required": ["source", "destination"]
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "workspace_delete",
+            "description": format!("Delete a file or directory from the local workspace. It is moved to {TRASH_DIR}/ in the workspace root, so the user can restore it."),
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Relative path of the file or directory to delete."
+                    }
+                },
+                "required": ["path"]
+            }
+        }),
+        json!({
+            "type": "function",
+            "name": "workspace_mkdir",
+            "description": "Create a directory (and missing parents) in the local workspace.",
+            "parameters": {
+                "type": "object",
+                "properties": {
+                    "path": {
+                        "type": "string",
+                        "description": "Relative path of the directory to create."
+                    }
+                },
+                "required": ["path"]
+            }
+        }),
         json!({
             "type": "function",
             "name": "workspace_run_command",
-            "description": "Run a shell command in the local workspace and return exit code/stdout/stderr.",
+            "description": format!("Run a shell command ({shell}) in the local workspace and return exit code/stdout/stderr."),
             "parameters": {
                 "type": "object",
                 "properties": {
@@ -112,16 +244,44 @@ pub fn tool_defs() -> Vec<Value> {
                 "required": ["command"]
             }
         }),
+        crate::code_index::tool_def(),
+        crate::sqlite_tool::tool_def(),
     ]
+    .into_iter()
+    .chain(crate::http_tool::tool_def())
+    .chain(crate::plugins::tool_defs())
+    .collect()
 }
 
 pub async fn handle_tool_call(call: &ToolCall) -> Option<String> {
+    handle_tool_call_with_env(call, &[]).await
+}
+
+/// Like [`handle_tool_call`], with `env` added to `workspace_run_command`
+/// processes (a recipe's `env:` secrets).
+pub async fn handle_tool_call_with_env(
+    call: &ToolCall,
+    env: &[(String, String)],
+) -> Option<String> {
     let output = match call.name.as_str() {
         "workspace_list_files" => to_output(handle_workspace_list_files(&call.arguments)),
         "workspace_read_file" => to_output(handle_workspace_read_file(&call.arguments)),
+        "workspace_file_info" => to_output(handle_workspace_file_info(&call.arguments)),
         "workspace_write_file" => to_output(handle_workspace_write_file(&call.arguments)),
-        "workspace_run_command" => to_output(handle_workspace_run_command(&call.arguments).await),
-        _ => return None,
+        "workspace_move" => to_output(handle_workspace_move(&call.arguments)),
+        "workspace_copy" => to_output(handle_workspace_copy(&call.arguments)),
+        "workspace_delete" => to_output(handle_workspace_delete(&call.arguments)),
+        "workspace_mkdir" => to_output(handle_workspace_mkdir(&call.arguments)),
+        "workspace_run_command" => {
+            to_output(handle_workspace_run_command(&call.arguments, env, None).await)
+        }
+        crate::sqlite_tool::SQLITE_QUERY_TOOL => {
+            to_output(crate::sqlite_tool::handle_tool_call(&call.arguments).await)
+        }
+        crate::http_tool::HTTP_REQUEST_TOOL => {
+            to_output(crate::http_tool::handle_tool_call(&call.arguments).await)
+        }
+        _ => return crate::plugins::handle_tool_call(call).await,
     };
     Some(output)
 }
@@ -230,9 +390,30 @@ fn handle_workspace_read_file(args: &Value) -> Result<Value> {
         bail!("Path is a directory: {}", path.display());
     }
 
-    let bytes = fs::read(&path).with_context(|| format!("Read {}", path.display()))?;
+    let size = fs::metadata(&path)
+        .with_context(|| format!("Stat {}", path.display()))?
+        .len();
+    let mut file = fs::File::open(&path).with_context(|| format!("Open {}", path.display()))?;
+    let mut bytes = Vec::with_capacity(FILE_SNIFF_BYTES);
+    (&mut file)
+        .take(FILE_SNIFF_BYTES as u64)
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Read {}", path.display()))?;
+    if filetype::is_binary(&bytes) {
+        bail!(
+            "{} is a binary file ({}, {} bytes), not text. Use workspace_file_info to inspect it, with hexdump: true for its raw bytes.",
+            to_workspace_relative(&path, &workspace_root),
+            filetype::sniff_mime(path.extension().and_then(|ext| ext.to_str()), &bytes),
+            size
+        );
+    }
+    // `max_chars` characters take at most four bytes each; read no more.
+    let limit = (max_chars * 4) as u64;
+    file.take(limit.saturating_sub(bytes.len() as u64))
+        .read_to_end(&mut bytes)
+        .with_context(|| format!("Read {}", path.display()))?;
     let text = String::from_utf8_lossy(&bytes).to_string();
-    let truncated = text.chars().count() > max_chars;
+    let truncated = text.chars().count() > max_chars || size > bytes.len() as u64;
     let content = if truncated {
         format!(
             "{}\n...[truncated]",
@@ -244,12 +425,77 @@ fn handle_workspace_read_file(args: &Value) -> Result<Value> {
 
     Ok(json!({
         "path": to_workspace_relative(&path, &workspace_root),
-        "size_bytes": bytes.len(),
+        "size_bytes": size,
         "truncated": truncated,
         "content": content,
     }))
 }
 
+fn handle_workspace_file_info(args: &Value) -> Result<Value> {
+    let (workspace_root, path) = resolve_workspace_path(required_path(args, "path")?)?;
+    if !path.exists() {
+        bail!("File does not exist: {}", path.display());
+    }
+    if path.is_dir() {
+        bail!("Path is a directory: {}", path.display());
+    }
+    let size = fs::metadata(&path)
+        .with_context(|| format!("Stat {}", path.display()))?
+        .len();
+    let mut file = fs::File::open(&path).with_context(|| format!("Open {}", path.display()))?;
+    let mut sample = Vec::with_capacity(FILE_SNIFF_BYTES);
+    (&mut file)
+        .take(FILE_SNIFF_BYTES as u64)
+        .read_to_end(&mut sample)
+        .with_context(|| format!("Read {}", path.display()))?;
+    let binary = filetype::is_binary(&sample);
+    let mime = filetype::sniff_mime(path.extension().and_then(|ext| ext.to_str()), &sample);
+
+    let mut info = json!({
+        "path": to_workspace_relative(&path, &workspace_root),
+        "size_bytes": size,
+        "mime": mime,
+        "binary": binary,
+    });
+    if !binary {
+        let mut lines = 0usize;
+        let mut last = None;
+        let mut buf = [0u8; 64 * 1024];
+        file.seek(SeekFrom::Start(0))?;
+        loop {
+            let read = file.read(&mut buf)?;
+            if read == 0 {
+                break;
+            }
+            lines += buf[..read].iter().filter(|&&b| b == b'\n').count();
+            last = Some(buf[read - 1]);
+        }
+        // A final line without a newline still counts.
+        if last.is_some_and(|b| b != b'\n') {
+            lines += 1;
+        }
+        info["line_count"] = json!(lines);
+    }
+    if args
+        .get("hexdump")
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+    {
+        let offset = args.get("offset").and_then(Value::as_u64).unwrap_or(0);
+        let length = args
+            .get("length")
+            .and_then(Value::as_u64)
+            .unwrap_or(HEXDUMP_DEFAULT_BYTES)
+            .clamp(1, HEXDUMP_MAX_BYTES);
+        let mut bytes = Vec::new();
+        file.seek(SeekFrom::Start(offset))?;
+        (&mut file).take(length).read_to_end(&mut bytes)?;
+        info["hexdump"] = json!(filetype::hexdump(&bytes, offset));
+        info["hexdump_truncated"] = json!(offset.saturating_add(bytes.len() as u64) < size);
+    }
+    Ok(info)
+}
+
 fn handle_workspace_write_file(args: &Value) -> Result<Value> {
     let path_arg = args
         .get("path")
@@ -281,16 +527,227 @@ fn handle_workspace_write_file(args: &Value) -> Result<Value> {
         }
     }
 
+    let change = FileChange {
+        path: to_workspace_relative(&path, &workspace_root),
+        before: fs::read_to_string(&path).ok(),
+        after: content.to_string(),
+    };
     fs::write(&path, content.as_bytes()).with_context(|| format!("Write {}", path.display()))?;
+    let (added, removed) = change.stats();
 
     Ok(json!({
-        "path": to_workspace_relative(&path, &workspace_root),
+        "path": change.path,
         "bytes_written": content.len(),
+        "created": change.before.is_none(),
+        "lines_added": added,
+        "lines_removed": removed,
         "status": "ok",
     }))
 }
 
-async fn handle_workspace_run_command(args: &Value) -> Result<Value> {
+fn required_path<'a>(args: &'a Value, key: &str) -> Result<&'a str> {
+    args.get(key)
+        .and_then(Value::as_str)
+        .filter(|value| !value.trim().is_empty())
+        .ok_or_else(|| anyhow!("{key} is required"))
+}
+
+/// Source and destination of a move or copy, both inside the workspace.
+fn resolve_transfer(args: &Value) -> Result<(PathBuf, PathBuf, PathBuf, bool)> {
+    let (workspace_root, source) = resolve_workspace_path(required_path(args, "source")?)?;
+    let (_, destination) = resolve_workspace_path(required_path(args, "destination")?)?;
+    let overwrite = args
+        .get("overwrite")
+        .and_then(Value::as_bool)
+        .unwrap_or(false);
+    Ok((workspace_root, source, destination, overwrite))
+}
+
+/// Refuse a move or copy that cannot be done safely.
+fn check_transfer(
+    workspace_root: &Path,
+    source: &Path,
+    destination: &Path,
+    overwrite: bool,
+) -> Result<()> {
+    if source == workspace_root {
+        bail!("Refusing to move or copy the workspace root");
+    }
+    let meta = fs::symlink_metadata(source)
+        .with_context(|| format!("Source does not exist: {}", source.display()))?;
+    if meta.file_type().is_symlink() {
+        bail!("Refusing to move or copy a symlink: {}", source.display());
+    }
+    if destination.starts_with(source) {
+        bail!(
+            "Destination {} is inside the source {}",
+            destination.display(),
+            source.display()
+        );
+    }
+    if destination.exists() && (!overwrite || destination.is_dir()) {
+        bail!("Destination already exists: {}", destination.display());
+    }
+    Ok(())
+}
+
+fn create_parent(path: &Path) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).with_context(|| format!("Create {}", parent.display()))?;
+    }
+    Ok(())
+}
+
+/// Copy a file, or a directory tree skipping symlinks; returns the number
+/// of files copied.
+fn copy_recursive(source: &Path, destination: &Path) -> Result<usize> {
+    if !source.is_dir() {
+        fs::copy(source, destination)
+            .with_context(|| format!("Copy {} to {}", source.display(), destination.display()))?;
+        return Ok(1);
+    }
+    fs::create_dir_all(destination).with_context(|| format!("Create {}", destination.display()))?;
+    let mut files = 0;
+    for entry in
+        fs::read_dir(source).with_context(|| format!("Read directory {}", source.display()))?
+    {
+        let entry = entry?;
+        if entry.file_type()?.is_symlink() {
+            continue;
+        }
+        files += copy_recursive(&entry.path(), &destination.join(entry.file_name()))?;
+    }
+    Ok(files)
+}
+
+/// `.memini-trash/<stamp>/<path relative to the root>`.
+fn trash_path(workspace_root: &Path, target: &Path, stamp: &str) -> PathBuf {
+    let relative = target.strip_prefix(workspace_root).unwrap_or(target);
+    workspace_root.join(TRASH_DIR).join(stamp).join(relative)
+}
+
+fn handle_workspace_move(args: &Value) -> Result<Value> {
+    let (workspace_root, source, destination, overwrite) = resolve_transfer(args)?;
+    check_transfer(&workspace_root, &source, &destination, overwrite)?;
+    create_parent(&destination)?;
+    fs::rename(&source, &destination)
+        .with_context(|| format!("Move {} to {}", source.display(), destination.display()))?;
+    Ok(json!({
+        "source": to_workspace_relative(&source, &workspace_root),
+        "destination": to_workspace_relative(&destination, &workspace_root),
+        "status": "ok",
+    }))
+}
+
+fn handle_workspace_copy(args: &Value) -> Result<Value> {
+    let (workspace_root, source, destination, overwrite) = resolve_transfer(args)?;
+    check_transfer(&workspace_root, &source, &destination, overwrite)?;
+    create_parent(&destination)?;
+    let files = copy_recursive(&source, &destination)?;
+    Ok(json!({
+        "source": to_workspace_relative(&source, &workspace_root),
+        "destination": to_workspace_relative(&destination, &workspace_root),
+        "files_copied": files,
+        "status": "ok",
+    }))
+}
+
+fn handle_workspace_delete(args: &Value) -> Result<Value> {
+    let (workspace_root, target) = resolve_workspace_path(required_path(args, "path")?)?;
+    if target == workspace_root {
+        bail!("Refusing to delete the workspace root");
+    }
+    if target.starts_with(workspace_root.join(TRASH_DIR)) {
+        bail!("{} is already in {TRASH_DIR}", target.display());
+    }
+    fs::symlink_metadata(&target)
+        .with_context(|| format!("Path does not exist: {}", target.display()))?;
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f").to_string();
+    let trashed = trash_path(&workspace_root, &target, &stamp);
+    create_parent(&trashed)?;
+    fs::rename(&target, &trashed)
+        .with_context(|| format!("Move {} to {TRASH_DIR}", target.display()))?;
+    Ok(json!({
+        "path": to_workspace_relative(&target, &workspace_root),
+        "trashed_to": to_workspace_relative(&trashed, &workspace_root),
+        "status": "ok",
+    }))
+}
+
+fn handle_workspace_mkdir(args: &Value) -> Result<Value> {
+    let (workspace_root, path) = resolve_workspace_path(required_path(args, "path")?)?;
+    if path.exists() && !path.is_dir() {
+        bail!("A file already exists at {}", path.display());
+    }
+    let created = !path.exists();
+    fs::create_dir_all(&path).with_context(|| format!("Create {}", path.display()))?;
+    Ok(json!({
+        "path": to_workspace_relative(&path, &workspace_root),
+        "created": created,
+        "status": "ok",
+    }))
+}
+
+/// What a `workspace_write_file` call would change, read before it runs.
+/// `None` for other tools and for paths outside the workspace.
+pub fn preview_write(call: &ToolCall) -> Option<FileChange> {
+    if call.name != "workspace_write_file" {
+        return None;
+    }
+    let path_arg = call.arguments.get("path").and_then(Value::as_str)?;
+    let content = call.arguments.get("content").and_then(Value::as_str)?;
+    let (workspace_root, path) = resolve_workspace_path(path_arg).ok()?;
+    Some(FileChange {
+        path: to_workspace_relative(&path, &workspace_root),
+        before: fs::read_to_string(&path).ok(),
+        after: content.to_string(),
+    })
+}
+
+/// Live output of a `workspace_run_command` call, for agent windows.
+pub struct CommandStream<'a> {
+    /// Called with each output line, whether it came from stderr, and the
+    /// stdout and stderr line counts so far.
+    pub on_line: &'a (dyn Fn(&str, bool, usize, usize) + Send + Sync),
+    /// Stops the command when notified; the call returns what it printed.
+    pub cancel: Arc<Notify>,
+}
+
+/// Run a `workspace_run_command` call, reporting its output as it arrives.
+pub async fn run_command_streaming(call: &ToolCall, stream: &CommandStream<'_>) -> String {
+    to_output(handle_workspace_run_command(&call.arguments, &[], Some(stream)).await)
+}
+
+/// Send each line of `pipe` (lossily decoded) to `tx`, tagged `stderr`.
+fn forward_lines(
+    pipe: impl AsyncRead + Unpin + Send + 'static,
+    stderr: bool,
+    tx: mpsc::UnboundedSender<(bool, String)>,
+) {
+    tokio::spawn(async move {
+        let mut reader = BufReader::new(pipe);
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            match reader.read_until(b'\n', &mut buf).await {
+                Ok(0) | Err(_) => break,
+                Ok(_) => {
+                    let line = String::from_utf8_lossy(&buf);
+                    let line = line.trim_end_matches(['\n', '\r']).to_string();
+                    if tx.send((stderr, line)).is_err() {
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+async fn handle_workspace_run_command(
+    args: &Value,
+    env: &[(String, String)],
+    stream: Option<&CommandStream<'_>>,
+) -> Result<Value> {
     let command = args
         .get("command")
         .and_then(Value::as_str)
@@ -315,42 +772,147 @@ async fn handle_workspace_run_command(args: &Value) -> Result<Value> {
         );
     }
 
-    let mut cmd = tokio::process::Command::new("sh");
-    cmd.arg("-lc")
-        .arg(command)
-        .current_dir(&workdir)
-        .kill_on_drop(true);
-
-    let timed = timeout(Duration::from_secs(timeout_seconds), cmd.output()).await;
-    let output = match timed {
-        Ok(result) => result.context("Run command")?,
-        Err(_) => {
-            return Ok(json!({
-                "command": command,
-                "workdir": to_workspace_relative(&workdir, &workspace_root),
-                "timed_out": true,
-                "timeout_seconds": timeout_seconds,
-                "exit_code": Value::Null,
-                "stdout": "",
-                "stderr": "Command timed out.",
-            }));
+    let sandbox = sandbox::load_config()?;
+    let mut cmd = sandbox::build_command(&sandbox, command, &workspace_root, &workdir, env)?;
+    #[cfg(unix)]
+    cmd.process_group(0);
+    let mut child = cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("Run command")?;
+    let (lines_tx, mut lines) = mpsc::unbounded_channel();
+    forward_lines(
+        child.stdout.take().context("stdout")?,
+        false,
+        lines_tx.clone(),
+    );
+    forward_lines(child.stderr.take().context("stderr")?, true, lines_tx);
+
+    let deadline = Instant::now() + Duration::from_secs(timeout_seconds);
+    let cancel = stream.map(|stream| stream.cancel.clone());
+    let cancelled_signal = async {
+        match &cancel {
+            Some(cancel) => cancel.notified().await,
+            None => std::future::pending().await,
         }
     };
-
-    let stdout = trim_chars(&String::from_utf8_lossy(&output.stdout), MAX_OUTPUT_CHARS);
-    let stderr = trim_chars(&String::from_utf8_lossy(&output.stderr), MAX_OUTPUT_CHARS);
+    tokio::pin!(cancelled_signal);
+    let (mut stdout, mut stderr) = (CapturedOutput::default(), CapturedOutput::default());
+    let (mut timed_out, mut cancelled) = (false, false);
+    loop {
+        tokio::select! {
+            line = lines.recv() => {
+                let Some((is_stderr, line)) = line else {
+                    break;
+                };
+                let line = redact_env(&line, env);
+                if is_stderr {
+                    stderr.push(&line);
+                } else {
+                    stdout.push(&line);
+                }
+                if let Some(stream) = stream {
+                    (stream.on_line)(&line, is_stderr, stdout.lines, stderr.lines);
+                }
+            }
+            _ = tokio::time::sleep_until(deadline) => {
+                timed_out = true;
+                break;
+            }
+            _ = &mut cancelled_signal => {
+                cancelled = true;
+                break;
+            }
+        }
+    }
+    // Pipes close before the process is reaped; a child that closed them
+    // early still gets the rest of the timeout.
+    let status = if timed_out || cancelled {
+        kill_command(&mut child).await;
+        None
+    } else {
+        match tokio::time::timeout_at(deadline, child.wait()).await {
+            Ok(status) => Some(status.context("Run command")?),
+            Err(_) => {
+                timed_out = true;
+                kill_command(&mut child).await;
+                None
+            }
+        }
+    };
+    let mut stderr = stderr.text();
+    let notes = [
+        timed_out.then(|| format!("Command timed out after {timeout_seconds}s.")),
+        cancelled.then(|| "Command cancelled by the user.".to_string()),
+    ];
+    for note in notes.into_iter().flatten() {
+        if !stderr.is_empty() {
+            stderr.push('\n');
+        }
+        stderr.push_str(&note);
+    }
 
     Ok(json!({
         "command": command,
         "workdir": to_workspace_relative(&workdir, &workspace_root),
-        "timed_out": false,
-        "exit_code": output.status.code(),
-        "success": output.status.success(),
-        "stdout": stdout,
+        "timed_out": timed_out,
+        "cancelled": cancelled,
+        "timeout_seconds": timeout_seconds,
+        "exit_code": status.and_then(|status| status.code()),
+        "success": status.is_some_and(|status| status.success()),
+        "stdout": stdout.text(),
         "stderr": stderr,
     }))
 }
 
+/// One output stream of a command: the lines that fit in the tool result
+/// and how many lines there were in all.
+#[derive(Default)]
+struct CapturedOutput {
+    kept: Vec<String>,
+    chars: usize,
+    lines: usize,
+}
+
+impl CapturedOutput {
+    /// Count `line`, keeping it while the result still has room, so a
+    /// chatty command does not grow the buffer without bound.
+    fn push(&mut self, line: &str) {
+        self.lines += 1;
+        if self.chars <= MAX_OUTPUT_CHARS {
+            self.chars += line.chars().count() + 1;
+            self.kept.push(line.to_string());
+        }
+    }
+
+    fn text(&self) -> String {
+        trim_chars(&self.kept.join("\n"), MAX_OUTPUT_CHARS)
+    }
+}
+
+/// Kill `child` and, on Unix, the process group it leads, so what it
+/// started (a build, a test runner) stops too and releases the pipes.
+async fn kill_command(child: &mut tokio::process::Child) {
+    #[cfg(unix)]
+    if let Some(pid) = child.id() {
+        use nix::sys::signal::{Signal, killpg};
+        use nix::unistd::Pid;
+        let _ = killpg(Pid::from_raw(pid as i32), Signal::SIGKILL);
+    }
+    let _ = child.kill().await;
+}
+
+/// Replace injected secret values echoed by a command with their names.
+fn redact_env(text: &str, env: &[(String, String)]) -> String {
+    env.iter()
+        .filter(|(_, value)| value.len() >= 4)
+        .fold(text.to_string(), |text, (name, value)| {
+            text.replace(value.as_str(), &format!("[secret {name}]"))
+        })
+}
+
 fn to_output(result: Result<Value>) -> String {
     let payload = match result {
         Ok(value) => value,
@@ -360,7 +922,7 @@ fn to_output(result: Result<Value>) -> String {
         .unwrap_or_else(|_| r#"{"error":"serialize failed"}"#.to_string())
 }
 
-fn resolve_workspace_path(raw: &str) -> Result<(PathBuf, PathBuf)> {
+pub(crate) fn resolve_workspace_path(raw: &str) -> Result<(PathBuf, PathBuf)> {
     let workspace_root = workspace_root();
     let target = if raw.trim().is_empty() {
         workspace_root.clone()
@@ -373,7 +935,7 @@ fn resolve_workspace_path(raw: &str) -> Result<(PathBuf, PathBuf)> {
         }
     };
 
-    if !target.starts_with(&workspace_root) {
+    if !platform::is_within(&target, &workspace_root) {
         bail!(
             "Path escapes workspace root. root={} path={}",
             workspace_root.display(),
@@ -384,7 +946,21 @@ fn resolve_workspace_path(raw: &str) -> Result<(PathBuf, PathBuf)> {
     Ok((workspace_root, target))
 }
 
-fn workspace_root() -> PathBuf {
+tokio::task_local! {
+    /// Workspace root of the running task, when it differs from the app's
+    /// (agent windows spawned with `/spawn --isolated`).
+    static TASK_WORKSPACE_ROOT: PathBuf;
+}
+
+/// Run `future` with [`workspace_root`] answering `root`.
+pub async fn with_workspace_root<F: Future>(root: PathBuf, future: F) -> F::Output {
+    TASK_WORKSPACE_ROOT.scope(root, future).await
+}
+
+pub fn workspace_root() -> PathBuf {
+    if let Ok(root) = TASK_WORKSPACE_ROOT.try_with(Clone::clone) {
+        return root;
+    }
     if let Ok(raw) = env::var("MEMINI_WORKSPACE_ROOT") {
         if !raw.trim().is_empty() {
             let path = PathBuf::from(raw.trim());
@@ -405,27 +981,7 @@ fn workspace_root() -> PathBuf {
     }
 }
 
-fn normalize_path(path: &Path) -> PathBuf {
-    let mut normalized = PathBuf::new();
-    for comp in path.components() {
-        match comp {
-            Component::Prefix(prefix) => normalized.push(prefix.as_os_str()),
-            Component::RootDir => normalized.push(Path::new("/")),
-            Component::CurDir => {}
-            Component::ParentDir => {
-                let _ = normalized.pop();
-            }
-            Component::Normal(seg) => normalized.push(seg),
-        }
-    }
-    if normalized.as_os_str().is_empty() {
-        PathBuf::from(".")
-    } else {
-        normalized
-    }
-}
-
-fn to_workspace_relative(path: &Path, workspace_root: &Path) -> String {
+pub(crate) fn to_workspace_relative(path: &Path, workspace_root: &Path) -> String {
     match path.strip_prefix(workspace_root) {
         Ok(rel) if rel.as_os_str().is_empty() => ".".to_string(),
         Ok(rel) => rel.display().to_string(),
@@ -433,7 +989,7 @@ fn to_workspace_relative(path: &Path, workspace_root: &Path) -> String {
     }
 }
 
-fn trim_chars(input: &str, max_chars: usize) -> String {
+pub(crate) fn trim_chars(input: &str, max_chars: usize) -> String {
     if input.chars().count() <= max_chars {
         return input.to_string();
     }
@@ -442,3 +998,66 @@ fn trim_chars(input: &str, max_chars: usize) -> String {
         input.chars().take(max_chars).collect::<String>()
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_output_stops_growing_past_the_result_size() {
+        let mut output = CapturedOutput::default();
+        let line = "x".repeat(100);
+        for _ in 0..(MAX_OUTPUT_CHARS / 100) * 3 {
+            output.push(&line);
+        }
+        assert_eq!(output.lines, (MAX_OUTPUT_CHARS / 100) * 3);
+        assert!(output.kept.len() <= MAX_OUTPUT_CHARS / 100 + 1);
+        assert!(output.text().ends_with("...[truncated]"));
+    }
+
+    #[test]
+    fn copies_trees_and_refuses_unsafe_transfers() {
+        let root = env::temp_dir().join(format!("memini-local-tools-{}", std::process::id()));
+        let src = root.join("docs");
+        fs::create_dir_all(src.join("guides")).expect("create");
+        fs::write(src.join("a.md"), "a").expect("write");
+        fs::write(src.join("guides/b.md"), "b").expect("write");
+
+        let copy = root.join("backup/docs");
+        check_transfer(&root, &src, &copy, false).expect("safe copy");
+        create_parent(&copy).expect("parent");
+        assert_eq!(copy_recursive(&src, &copy).expect("copy"), 2);
+        assert_eq!(
+            fs::read_to_string(copy.join("guides/b.md")).expect("read"),
+            "b"
+        );
+
+        assert!(check_transfer(&root, &src, &src.join("nested"), false).is_err());
+        assert!(check_transfer(&root, &src, &copy, false).is_err());
+        assert!(check_transfer(&root, &root, &copy, false).is_err());
+        assert_eq!(
+            trash_path(&root, &src.join("a.md"), "20260101-120000.000"),
+            root.join(TRASH_DIR).join("20260101-120000.000/docs/a.md")
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[tokio::test]
+    async fn forwards_output_line_by_line() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        forward_lines(&b"one\r\ntwo \xff\nlast"[..], true, tx);
+        let mut lines = Vec::new();
+        while let Some(line) = rx.recv().await {
+            lines.push(line);
+        }
+        assert_eq!(
+            lines,
+            [
+                (true, "one".to_string()),
+                (true, "two \u{fffd}".to_string()),
+                (true, "last".to_string()),
+            ]
+        );
+    }
+}
@@ -5,23 +5,46 @@
 //! for all application logic, and tears the terminal down on exit.
 
 mod app;
+mod backend;
+mod capture;
+mod code_index;
+mod config;
 mod constants;
+mod diff;
+mod facts;
+mod filetype;
+mod http;
+mod http_tool;
+mod i18n;
+mod importance;
 mod local_tools;
 mod mcp;
 mod openai;
+mod platform;
+mod plugins;
 mod prompts;
+mod provenance;
+mod provider;
+mod redact;
 mod rice;
+mod routing;
+mod sandbox;
 mod skills;
+mod sqlite_tool;
+mod telemetry;
+mod update;
+mod usage;
 mod util;
 
 use std::io;
 use std::time::Duration;
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use crossterm::ExecutableCommand;
 use crossterm::event;
 use crossterm::event::DisableMouseCapture;
 use crossterm::event::EnableMouseCapture;
+use crossterm::event::{DisableBracketedPaste, EnableBracketedPaste};
 use crossterm::terminal::{self, EnterAlternateScreen, LeaveAlternateScreen};
 use ratatui::Terminal;
 use ratatui::backend::CrosstermBackend;
@@ -33,34 +56,160 @@ use crate::app::App;
 fn main() -> Result<()> {
     dotenvy::dotenv().ok();
 
-    let mut terminal = setup_terminal()?;
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let plain = plain_ui_requested(&mut args);
+    let snapshot = snapshot_requested(&mut args)?;
+    if args.first().map(String::as_str) == Some("serve-mcp") {
+        return app::mcp_server::serve(&args[1..]);
+    }
+    if matches!(args.first().map(String::as_str), Some("--version" | "-V")) {
+        println!("{} {}", constants::APP_NAME, constants::APP_VERSION);
+        return Ok(());
+    }
+    if args.first().map(String::as_str) == Some("update") {
+        return run_update_cli(&args[1..]);
+    }
+    if args.first().map(String::as_str) == Some("replay") {
+        return run_replay_cli(&args[1..], plain);
+    }
+    if args.first().map(String::as_str) == Some("--dry-run") {
+        let message = args[1..].join(" ");
+        if message.trim().is_empty() {
+            bail!("Usage: memini --dry-run <message>");
+        }
+        let mut app = App::new()?;
+        println!("{}", app.dry_run_report(&message)?);
+        return Ok(());
+    }
+
+    let mut terminal = setup_terminal(plain)?;
+    app::progress::attach_terminal();
+    app::recording::enable();
     let mut app = App::new()?;
+    if plain {
+        app.use_plain_ui();
+    }
+    if let Some(snapshot) = snapshot {
+        app.load_snapshot(snapshot);
+    }
 
     let run_result = run_app(&mut terminal, &mut app);
+    let shutdown_result = shutdown_app(&mut terminal, &mut app);
 
-    restore_terminal()?;
-    run_result
+    restore_terminal(plain)?;
+    run_result.and(shutdown_result)
+}
+
+/// `--plain` (removed from `args`) or `MEMINI_UI=basic`: no alternate
+/// screen or mouse capture, 16 colors, and a list instead of the agent
+/// grid — for tmux panes, SSH sessions and limited terminals.
+fn plain_ui_requested(args: &mut Vec<String>) -> bool {
+    let before = args.len();
+    args.retain(|arg| arg != "--plain");
+    let from_env = util::env_first(&["MEMINI_UI"])
+        .is_some_and(|mode| matches!(mode.trim(), "basic" | "plain"));
+    args.len() != before || from_env
+}
+
+/// `--load-snapshot <file>` (removed from `args`): the snapshot to
+/// restore once the app is up, read before the terminal is taken over so
+/// a bad file fails cleanly.
+fn snapshot_requested(args: &mut Vec<String>) -> Result<Option<app::snapshot::AppSnapshot>> {
+    let Some(index) = args.iter().position(|arg| arg == "--load-snapshot") else {
+        return Ok(None);
+    };
+    if index + 1 >= args.len() {
+        bail!("Usage: memini --load-snapshot <file>");
+    }
+    let path = args.remove(index + 1);
+    args.remove(index);
+    app::snapshot::read(std::path::Path::new(&path)).map(Some)
+}
+
+/// `memini replay [file]`: play a session recording (the newest one
+/// without an argument) in the terminal.
+fn run_replay_cli(args: &[String], plain: bool) -> Result<()> {
+    let path = app::recording::resolve(args.first().map(String::as_str))?;
+    let entries = app::recording::read(&path)?;
+    let name = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let mut terminal = setup_terminal(plain)?;
+    let result = app::replay::run(&mut terminal, &name, entries);
+    restore_terminal(plain)?;
+    result
+}
+
+/// `memini update [--check]` outside the TUI.
+fn run_update_cli(args: &[String]) -> Result<()> {
+    let check_only = args.iter().any(|arg| arg == "--check");
+    let runtime = tokio::runtime::Runtime::new().context("start tokio runtime")?;
+    let outcome = runtime.block_on(update::run(check_only, |line| println!("{line}")))?;
+    match outcome {
+        update::UpdateOutcome::UpToDate => {
+            println!("memini {} is up to date.", constants::APP_VERSION);
+        }
+        update::UpdateOutcome::Available(release) => {
+            println!(
+                "memini {} is available (you have {}). Run `memini update` to install.",
+                release.version(),
+                constants::APP_VERSION
+            );
+        }
+        update::UpdateOutcome::Installed { release, path } => {
+            println!(
+                "Installed memini {} at {}.",
+                release.version(),
+                path.display()
+            );
+            if let Some(notes) = release
+                .body
+                .as_deref()
+                .filter(|notes| !notes.trim().is_empty())
+            {
+                println!("\n{}", notes.trim());
+            }
+        }
+    }
+    Ok(())
 }
 
 // ── Terminal lifecycle ───────────────────────────────────────────────
 
 /// Enable raw mode, switch to the alternate screen, and create the backend.
-fn setup_terminal() -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
+/// Plain mode draws on the main screen and leaves the mouse alone.
+fn setup_terminal(plain: bool) -> Result<Terminal<CrosstermBackend<io::Stdout>>> {
     terminal::enable_raw_mode().context("enable raw mode")?;
     let mut stdout = io::stdout();
-    stdout.execute(EnterAlternateScreen)?;
-    stdout.execute(EnableMouseCapture)?;
+    if !plain {
+        stdout.execute(EnterAlternateScreen)?;
+        stdout.execute(EnableMouseCapture)?;
+    }
+    // Pastes arrive as one `Event::Paste` instead of keystrokes, so an
+    // embedded newline no longer submits half a snippet.
+    stdout.execute(EnableBracketedPaste)?;
     let backend = CrosstermBackend::new(stdout);
-    let terminal = Terminal::new(backend)?;
+    let mut terminal = Terminal::new(backend)?;
+    if plain {
+        terminal.clear()?;
+    }
     Ok(terminal)
 }
 
 /// Restore the terminal to its original state.
-fn restore_terminal() -> Result<()> {
+fn restore_terminal(plain: bool) -> Result<()> {
     terminal::disable_raw_mode().context("disable raw mode")?;
     let mut stdout = io::stdout();
-    stdout.execute(DisableMouseCapture)?;
-    stdout.execute(LeaveAlternateScreen)?;
+    stdout.execute(DisableBracketedPaste)?;
+    if plain {
+        // There is no alternate screen to leave; wipe the last frame instead.
+        stdout.execute(terminal::Clear(terminal::ClearType::All))?;
+        stdout.execute(crossterm::cursor::MoveTo(0, 0))?;
+    } else {
+        stdout.execute(DisableMouseCapture)?;
+        stdout.execute(LeaveAlternateScreen)?;
+    }
     Ok(())
 }
 
@@ -71,6 +220,10 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App)
         // real-time even when no keyboard/mouse input is arriving.
         app.tick();
 
+        // A blocking operation's ticker painted over the last frame.
+        if app::progress::take_repaint() {
+            terminal.clear()?;
+        }
         terminal.draw(|frame| app.draw(frame))?;
 
         if app.should_quit() {
@@ -85,3 +238,27 @@ fn run_app(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, app: &mut App)
 
     Ok(())
 }
+
+/// Let background agents stop at a safe point and save state, redrawing
+/// while they drain.  Esc or Ctrl+C skips the wait.
+fn shutdown_app(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    app: &mut App,
+) -> Result<()> {
+    app.begin_shutdown();
+    while !app.shutdown_drained() {
+        terminal.draw(|frame| app.draw(frame))?;
+        let pressed = event::poll(Duration::from_millis(50))?
+            .then(event::read)
+            .transpose()?;
+        if let Some(event::Event::Key(key)) = pressed {
+            let ctrl_c = key.code == event::KeyCode::Char('c')
+                && key.modifiers.contains(event::KeyModifiers::CONTROL);
+            if key.code == event::KeyCode::Esc || ctrl_c {
+                break;
+            }
+        }
+    }
+    app.finish_shutdown();
+    Ok(())
+}
@@ -1,38 +1,73 @@
 //! MCP server configuration — loading, lookup, and serialisation.
 
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
 use crate::constants::APP_NAME;
+use crate::mcp::rate_limit::RateLimit;
+use crate::mcp::sampling::SamplingConfig;
 
 /// Top-level MCP configuration containing one or more server entries.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct McpConfig {
     pub servers: Vec<McpServer>,
+    /// Reconnect the servers that were connected last time at startup,
+    /// whether or not a token is stored for them.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub reconnect_on_startup: bool,
 }
 
 /// A single MCP server entry with connection and auth details.
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct McpServer {
     pub id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    pub url: String,
+    /// Endpoint for `http` servers; empty for `stdio` ones.
     #[serde(default)]
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sse_url: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub transport: Option<String>,
-    #[serde(default)]
-    pub headers: Option<std::collections::HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub headers: Option<HashMap<String, String>>,
+    /// Program to launch for `stdio` servers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<HashMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auth: Option<McpAuth>,
+    /// Upstream quota to stay under; calls beyond it wait their turn.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+    /// How `sampling/createMessage` requests from the server are handled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingConfig>,
+    /// PEM file with extra root certificates for this server.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert: Option<String>,
+    /// Accept any TLS certificate.  For test servers only; every connect
+    /// logs a warning.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub insecure_skip_verify: bool,
+    /// Proxy for this server, overriding `[network]`; `none` connects
+    /// directly.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
 }
 
 /// Authentication configuration for a single MCP server.
-#[derive(Clone, Debug, Deserialize, Serialize)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct McpAuth {
     #[serde(rename = "type")]
     pub auth_type: String,
@@ -59,6 +94,8 @@ pub struct McpAuth {
     #[serde(default)]
     pub registration_endpoint: Option<String>,
     #[serde(default)]
+    pub device_authorization_endpoint: Option<String>,
+    #[serde(default)]
     pub redirect_uri: Option<String>,
 }
 
@@ -66,6 +103,69 @@ impl McpServer {
     pub fn display_name(&self) -> String {
         self.name.clone().unwrap_or_else(|| self.id.clone())
     }
+
+    /// Where the server lives — its URL, or the launch command for stdio.
+    pub fn endpoint(&self) -> String {
+        match &self.command {
+            Some(command) if self.url.is_empty() => std::iter::once(command.as_str())
+                .chain(self.args.iter().map(String::as_str))
+                .collect::<Vec<_>>()
+                .join(" "),
+            _ => self.url.clone(),
+        }
+    }
+
+    /// Check the entry is complete enough to connect to.
+    pub fn validate(&self) -> Result<()> {
+        if self.id.is_empty()
+            || !self
+                .id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            bail!(
+                "Server id '{}' must be letters, digits, '-' or '_'",
+                self.id
+            );
+        }
+        if self.id.contains(crate::mcp::MCP_TOOL_NAMESPACE_SEP) {
+            bail!("Server id '{}' must not contain '__'", self.id);
+        }
+        match self.transport.as_deref().unwrap_or("http") {
+            "http" => {
+                if !(self.url.starts_with("http://") || self.url.starts_with("https://")) {
+                    bail!("'{}' is not an http(s) URL", self.url);
+                }
+            }
+            "stdio" => {
+                if self.command.as_deref().is_none_or(str::is_empty) {
+                    bail!("stdio server '{}' needs a command", self.id);
+                }
+            }
+            other => bail!("Unknown transport '{other}' (expected http or stdio)"),
+        }
+        if self
+            .rate_limit
+            .is_some_and(|limit| limit.requests_per_minute == 0 || limit.burst == Some(0))
+        {
+            bail!(
+                "rate_limit for '{}' must allow at least one request",
+                self.id
+            );
+        }
+        if self
+            .sampling
+            .as_ref()
+            .and_then(|sampling| sampling.rate_limit)
+            .is_some_and(|limit| limit.requests_per_minute == 0 || limit.burst == Some(0))
+        {
+            bail!(
+                "sampling.rate_limit for '{}' must allow at least one request",
+                self.id
+            );
+        }
+        Ok(())
+    }
 }
 
 /// Where the MCP configuration was loaded from.
@@ -110,6 +210,44 @@ impl McpConfig {
         Ok((embedded, McpSource::Embedded))
     }
 
+    /// File that edits to a config loaded from `source` should be written
+    /// to.  Embedded defaults are copied to the user config directory.
+    pub fn writable_path(source: &McpSource) -> Option<PathBuf> {
+        match source {
+            McpSource::File(path) => Some(path.clone()),
+            McpSource::Embedded => config_dir_file("mcp.json"),
+        }
+    }
+
+    /// Write the config as pretty JSON, creating parent directories.
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).with_context(|| format!("create {}", parent.display()))?;
+        }
+        let mut contents = serde_json::to_string_pretty(self).context("serialise mcp config")?;
+        contents.push('\n');
+        fs::write(path, contents)
+            .with_context(|| format!("write mcp config to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Validate and append a server, rejecting duplicate ids.
+    pub fn add_server(&mut self, server: McpServer) -> Result<()> {
+        server.validate()?;
+        if self.find_by_id_or_name(&server.id).is_some() {
+            bail!("An MCP server named '{}' already exists", server.id);
+        }
+        self.servers.push(server);
+        Ok(())
+    }
+
+    /// Remove a server by id or name, returning the removed entry.
+    pub fn remove_server(&mut self, query: &str) -> Option<McpServer> {
+        let id = self.find_by_id_or_name(query)?.id;
+        let index = self.servers.iter().position(|server| server.id == id)?;
+        Some(self.servers.remove(index))
+    }
+
     fn load_from_path(path: &Path) -> Result<Self> {
         let contents = fs::read_to_string(path)
             .with_context(|| format!("read mcp config from {}", path.display()))?;
@@ -138,3 +276,62 @@ fn config_dir_file(filename: &str) -> Option<PathBuf> {
     let proj_dirs = ProjectDirs::from("com", APP_NAME, APP_NAME)?;
     Some(proj_dirs.config_dir().join(filename))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn server(id: &str, url: &str) -> McpServer {
+        McpServer {
+            id: id.to_string(),
+            name: None,
+            url: url.to_string(),
+            sse_url: None,
+            transport: None,
+            headers: None,
+            command: None,
+            args: Vec::new(),
+            env: None,
+            auth: None,
+            rate_limit: None,
+            sampling: None,
+            ca_cert: None,
+            insecure_skip_verify: false,
+            proxy: None,
+        }
+    }
+
+    #[test]
+    fn add_and_remove_servers() {
+        let mut config = McpConfig {
+            servers: vec![server("notion", "https://mcp.notion.com/mcp")],
+            reconnect_on_startup: false,
+        };
+        assert!(
+            config
+                .add_server(server("NOTION", "https://x.dev/mcp"))
+                .is_err()
+        );
+        assert!(
+            config
+                .add_server(server("bad id", "https://x.dev/mcp"))
+                .is_err()
+        );
+        assert!(config.add_server(server("linear", "ftp://x.dev")).is_err());
+        config
+            .add_server(server("linear", "https://mcp.linear.app/mcp"))
+            .unwrap();
+
+        let mut stdio = server("fs", "");
+        stdio.transport = Some("stdio".to_string());
+        assert!(config.add_server(stdio.clone()).is_err());
+        stdio.command = Some("npx".to_string());
+        stdio.args = vec!["-y".to_string(), "server-fs".to_string()];
+        assert_eq!(stdio.endpoint(), "npx -y server-fs");
+        config.add_server(stdio).unwrap();
+
+        assert_eq!(config.remove_server("linear").unwrap().id, "linear");
+        assert!(config.remove_server("linear").is_none());
+        assert_eq!(config.servers.len(), 2);
+    }
+}
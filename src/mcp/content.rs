@@ -0,0 +1,171 @@
+//! Turn an MCP `CallToolResult` into text a model can use.
+//!
+//! Serializing the whole result would hand the model JSON wrappers and
+//! base64 blobs.  Instead text blocks are passed through as-is, binary
+//! content (images, audio, blob resources) is saved to a file and replaced
+//! by a one-line reference to it, embedded text resources keep their URI as
+//! a header, and resource links become a single descriptive line.
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD;
+use serde_json::{Value, json};
+
+/// Render `result` (a serialized `CallToolResult`).  `save` stores decoded
+/// binary content under a file name and returns where it went.  Results
+/// flagged `isError` come back as `{"error": "..."}`.
+pub fn render_result(
+    result: &Value,
+    mut save: impl FnMut(&str, &[u8]) -> Result<PathBuf>,
+) -> String {
+    let mut parts = Vec::new();
+    let blocks = result
+        .get("content")
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default();
+    for (index, block) in blocks.iter().enumerate() {
+        let str_field = |key: &str| block.get(key).and_then(Value::as_str).unwrap_or_default();
+        let part = match str_field("type") {
+            "text" => str_field("text").to_string(),
+            kind @ ("image" | "audio") => {
+                let mime = str_field("mimeType");
+                let name = format!("mcp-{kind}-{}.{}", index + 1, extension(mime));
+                binary_reference(kind, mime, str_field("data"), &name, &mut save)
+            }
+            "resource" => {
+                let resource = block.get("resource").unwrap_or(&Value::Null);
+                embedded_resource(resource, index, &mut save)
+            }
+            "resource_link" => resource_link(block),
+            other => format!("[unsupported {other} content]"),
+        };
+        parts.push(part);
+    }
+    // Servers that only return structured content (no text mirror).
+    if parts.is_empty() {
+        if let Some(structured) = result.get("structuredContent").filter(|v| !v.is_null()) {
+            parts.push(structured.to_string());
+        }
+    }
+    let text = parts.join("\n\n");
+    if result.get("isError").and_then(Value::as_bool) == Some(true) {
+        return json!({ "error": text }).to_string();
+    }
+    text
+}
+
+fn embedded_resource(
+    resource: &Value,
+    index: usize,
+    save: &mut impl FnMut(&str, &[u8]) -> Result<PathBuf>,
+) -> String {
+    let field = |key: &str| resource.get(key).and_then(Value::as_str);
+    let uri = field("uri").unwrap_or("(no uri)");
+    if let Some(text) = field("text") {
+        return format!("[resource {uri}]\n{text}");
+    }
+    let mime = field("mimeType").unwrap_or("application/octet-stream");
+    let file = uri
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && !name.contains(':'))
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("mcp-resource-{}.{}", index + 1, extension(mime)));
+    let reference = binary_reference("resource", mime, field("blob").unwrap_or(""), &file, save);
+    format!("{reference} from {uri}")
+}
+
+fn resource_link(block: &Value) -> String {
+    let field = |key: &str| block.get(key).and_then(Value::as_str);
+    let mut line = format!(
+        "[resource link] {} <{}>",
+        field("name").unwrap_or("resource"),
+        field("uri").unwrap_or("")
+    );
+    if let Some(mime) = field("mimeType") {
+        line.push_str(&format!(" ({mime})"));
+    }
+    if let Some(description) = field("description").filter(|d| !d.is_empty()) {
+        line.push_str(&format!(" — {description}"));
+    }
+    line
+}
+
+/// Decode base64 `data`, save it and describe where it went.
+fn binary_reference(
+    kind: &str,
+    mime: &str,
+    data: &str,
+    name: &str,
+    save: &mut impl FnMut(&str, &[u8]) -> Result<PathBuf>,
+) -> String {
+    let bytes = match STANDARD.decode(data.trim()) {
+        Ok(bytes) => bytes,
+        Err(err) => return format!("[{kind} {mime}: invalid base64 ({err})]"),
+    };
+    let size = format!("{:.1} KB", bytes.len() as f64 / 1024.0);
+    match save(name, &bytes) {
+        Ok(path) => format!("[{kind} {mime}, {size}, saved to {}]", path.display()),
+        Err(err) => format!("[{kind} {mime}, {size}, not saved: {err:#}]"),
+    }
+}
+
+fn extension(mime: &str) -> &'static str {
+    match mime {
+        "image/png" => "png",
+        "image/jpeg" => "jpg",
+        "image/gif" => "gif",
+        "image/webp" => "webp",
+        "image/svg+xml" => "svg",
+        "audio/wav" | "audio/x-wav" => "wav",
+        "audio/mpeg" => "mp3",
+        "application/pdf" => "pdf",
+        "application/json" => "json",
+        "text/plain" => "txt",
+        _ => "bin",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binary_blocks_become_file_references() {
+        let result = json!({
+            "content": [
+                {"type": "text", "text": "Screenshot of the dashboard:"},
+                {"type": "image", "mimeType": "image/png", "data": STANDARD.encode([0u8; 2048])},
+                {"type": "resource", "resource": {"uri": "file:///notes/todo.md", "text": "- ship it"}},
+                {"type": "resource_link", "uri": "https://example.com/r/1", "name": "report",
+                 "mimeType": "application/pdf", "description": "Weekly report"}
+            ],
+            "isError": false
+        });
+        let mut saved = Vec::new();
+        let text = render_result(&result, |name, bytes| {
+            saved.push((name.to_string(), bytes.len()));
+            Ok(PathBuf::from("/artifacts").join(name))
+        });
+        assert_eq!(saved, vec![("mcp-image-2.png".to_string(), 2048)]);
+        assert_eq!(
+            text,
+            "Screenshot of the dashboard:\n\n\
+             [image image/png, 2.0 KB, saved to /artifacts/mcp-image-2.png]\n\n\
+             [resource file:///notes/todo.md]\n- ship it\n\n\
+             [resource link] report <https://example.com/r/1> (application/pdf) — Weekly report"
+        );
+        assert!(!text.contains("AAAA"));
+    }
+
+    #[test]
+    fn error_results_keep_the_error_shape() {
+        let result =
+            json!({"content": [{"type": "text", "text": "rate limited"}], "isError": true});
+        let text = render_result(&result, |_, _| unreachable!());
+        assert_eq!(text, r#"{"error":"rate limited"}"#);
+    }
+}
@@ -0,0 +1,187 @@
+//! Import of MCP server definitions from other clients' configs — Claude
+//! Desktop's `claude_desktop_config.json` and Cursor's `mcp.json`.
+//!
+//! Both use the same `mcpServers` map: stdio entries carry `command`,
+//! `args` and `env`, remote entries a `url` (plus optional `headers` and
+//! a `type` of `http`/`sse`).  Each is mapped onto an [`McpServer`].
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use directories::BaseDirs;
+use serde::Deserialize;
+
+use super::config::McpServer;
+
+/// Client whose config is being imported.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImportSource {
+    Claude,
+    Cursor,
+}
+
+impl ImportSource {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.to_ascii_lowercase().as_str() {
+            "claude" | "claude-desktop" => Some(ImportSource::Claude),
+            "cursor" => Some(ImportSource::Cursor),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportSource::Claude => "Claude Desktop",
+            ImportSource::Cursor => "Cursor",
+        }
+    }
+
+    /// Where the client keeps its config, most specific first.
+    pub fn default_paths(self) -> Vec<PathBuf> {
+        let Some(dirs) = BaseDirs::new() else {
+            return Vec::new();
+        };
+        match self {
+            // `config_dir` is ~/Library/Application Support on macOS,
+            // ~/.config on Linux and %APPDATA% on Windows.
+            ImportSource::Claude => vec![
+                dirs.config_dir()
+                    .join("Claude")
+                    .join("claude_desktop_config.json"),
+            ],
+            ImportSource::Cursor => vec![
+                PathBuf::from(".cursor").join("mcp.json"),
+                dirs.home_dir().join(".cursor").join("mcp.json"),
+            ],
+        }
+    }
+
+    /// First existing default config path.
+    pub fn find_config(self) -> Option<PathBuf> {
+        self.default_paths().into_iter().find(|path| path.exists())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalConfig {
+    #[serde(default, rename = "mcpServers")]
+    mcp_servers: HashMap<String, ExternalServer>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExternalServer {
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default, alias = "serverUrl")]
+    server_url: Option<String>,
+    #[serde(default)]
+    headers: Option<HashMap<String, String>>,
+    #[serde(default, rename = "type", alias = "transport")]
+    kind: Option<String>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+/// Read and convert the servers in an external config file.
+pub fn load_external(path: &Path) -> Result<Vec<McpServer>> {
+    let contents = fs::read_to_string(path).with_context(|| format!("read {}", path.display()))?;
+    parse_external(&contents).with_context(|| format!("parse {}", path.display()))
+}
+
+/// Convert an `mcpServers` document into server entries, sorted by id.
+/// Disabled entries are skipped; entries without a command or URL fail.
+pub fn parse_external(contents: &str) -> Result<Vec<McpServer>> {
+    let config: ExternalConfig = serde_json::from_str(contents)?;
+    let mut servers = Vec::new();
+    for (name, entry) in &config.mcp_servers {
+        if entry.disabled {
+            continue;
+        }
+        let url = entry.url.clone().or_else(|| entry.server_url.clone());
+        let (transport, url, sse_url, command) = match (&entry.command, url) {
+            (_, Some(url)) if entry.kind.as_deref() == Some("sse") => {
+                ("http", url.clone(), Some(url), None)
+            }
+            (_, Some(url)) => ("http", url, None, None),
+            (Some(command), None) => ("stdio", String::new(), None, Some(command.clone())),
+            (None, None) => bail!("server '{name}' has neither a command nor a url"),
+        };
+        servers.push(McpServer {
+            id: slug(name),
+            name: (slug(name) != *name).then(|| name.clone()),
+            url,
+            sse_url,
+            transport: Some(transport.to_string()),
+            headers: entry.headers.clone().filter(|h| !h.is_empty()),
+            command,
+            args: entry.args.clone(),
+            env: entry.env.clone().filter(|e| !e.is_empty()),
+            auth: None,
+            rate_limit: None,
+            sampling: None,
+            ca_cert: None,
+            insecure_skip_verify: false,
+            proxy: None,
+        });
+    }
+    servers.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(servers)
+}
+
+/// Turn a free-form server name into a valid memini id.
+fn slug(name: &str) -> String {
+    let mut id = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() || c == '-' {
+            id.push(c.to_ascii_lowercase());
+        } else if !id.ends_with('-') && !id.is_empty() {
+            id.push('-');
+        }
+    }
+    id.trim_end_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_stdio_and_remote_entries() {
+        let servers = parse_external(
+            r#"{
+                "mcpServers": {
+                    "filesystem": {
+                        "command": "npx",
+                        "args": ["-y", "@modelcontextprotocol/server-filesystem", "/tmp"],
+                        "env": { "DEBUG": "1" }
+                    },
+                    "Linear Issues": { "url": "https://mcp.linear.app/sse", "type": "sse" },
+                    "old": { "command": "old-server", "disabled": true }
+                }
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(servers.len(), 2);
+
+        assert_eq!(servers[0].id, "filesystem");
+        assert_eq!(servers[0].transport.as_deref(), Some("stdio"));
+        assert_eq!(servers[0].command.as_deref(), Some("npx"));
+        assert_eq!(servers[0].args.len(), 3);
+        assert!(servers[0].validate().is_ok());
+
+        assert_eq!(servers[1].id, "linear-issues");
+        assert_eq!(servers[1].name.as_deref(), Some("Linear Issues"));
+        assert_eq!(servers[1].transport.as_deref(), Some("http"));
+        assert!(servers[1].sse_url.is_some());
+
+        assert!(parse_external(r#"{ "mcpServers": { "x": {} } }"#).is_err());
+    }
+}
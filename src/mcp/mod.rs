@@ -2,17 +2,28 @@
 //! conversion helpers.
 
 pub mod config;
+pub mod content;
+pub mod import;
 pub mod oauth;
+pub mod rate_limit;
+pub mod roots;
+pub mod sampling;
+pub mod tool_changes;
+
+use std::time::Instant;
 
 use anyhow::{Context, Result, anyhow};
-use rmcp::model::{CallToolRequestParam, CallToolResult, Tool as McpTool};
+use rmcp::model::{CallToolRequestParam, CallToolResult, JsonObject, Tool as McpTool};
 use rmcp::service::RunningService;
 use rmcp::transport::StreamableHttpClientTransport;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
 use rmcp::{RoleClient, ServiceExt};
 use serde_json::{Value, json};
 
+use crate::backend::{BoxFuture, McpBackend, McpSession};
+use crate::capture::{self, Channel};
 use crate::mcp::config::McpServer;
+use crate::mcp::sampling::MeminiClient;
 use crate::util::normalize_url;
 
 pub const MCP_TOOL_NAMESPACE_SEP: &str = "__";
@@ -20,11 +31,61 @@ pub const MCP_TOOL_NAMESPACE_SEP: &str = "__";
 /// An active connection to a single MCP server.
 pub struct McpConnection {
     pub server: McpServer,
-    pub client: RunningService<RoleClient, ()>,
+    pub client: Box<dyn McpSession>,
     pub tool_cache: Vec<McpTool>,
 }
 
+/// Connects over Streamable HTTP (see [`connect_http`]).
+#[derive(Debug)]
+pub struct StreamableHttp;
+
+impl McpBackend for StreamableHttp {
+    fn connect<'a>(
+        &'a self,
+        server: &'a McpServer,
+        bearer: Option<String>,
+    ) -> BoxFuture<'a, Result<McpConnection>> {
+        Box::pin(connect_http(server, bearer))
+    }
+}
+
+/// An rmcp client session.
+struct RmcpSession(RunningService<RoleClient, MeminiClient>);
+
+impl McpSession for RmcpSession {
+    fn list_tools(&self) -> BoxFuture<'_, Result<Vec<McpTool>>> {
+        Box::pin(async move { self.0.list_all_tools().await.context("list MCP tools") })
+    }
+
+    fn call_tool<'a>(
+        &'a self,
+        name: &'a str,
+        arguments: Option<JsonObject>,
+    ) -> BoxFuture<'a, Result<CallToolResult>> {
+        Box::pin(async move {
+            self.0
+                .call_tool(CallToolRequestParam {
+                    name: name.to_string().into(),
+                    arguments,
+                })
+                .await
+                .context("call MCP tool")
+        })
+    }
+
+    fn notify_roots_changed(&self) -> BoxFuture<'_, Result<()>> {
+        Box::pin(async move {
+            self.0
+                .peer()
+                .notify_roots_list_changed()
+                .await
+                .context("send roots/list_changed")
+        })
+    }
+}
+
 /// Open a Streamable-HTTP connection to the given MCP server.
+#[tracing::instrument(name = "mcp.connect", skip_all, fields(server = %server.id))]
 pub async fn connect_http(server: &McpServer, bearer: Option<String>) -> Result<McpConnection> {
     let url = normalize_url(&server.url);
 
@@ -41,29 +102,49 @@ pub async fn connect_http(server: &McpServer, bearer: Option<String>) -> Result<
         config.auth_header = Some(raw_token);
     }
 
-    let transport = StreamableHttpClientTransport::from_config(config);
+    let http_client = crate::http::mcp_client(server)?;
+    let transport = StreamableHttpClientTransport::with_client(http_client, config);
 
-    let client = ().serve(transport).await.with_context(|| format!("connect MCP at {url}"))?;
+    let client = MeminiClient::new(server)
+        .serve(transport)
+        .await
+        .with_context(|| format!("connect MCP at {url}"))?;
 
     Ok(McpConnection {
         server: server.clone(),
-        client,
+        client: Box::new(RmcpSession(client)),
         tool_cache: Vec::new(),
     })
 }
 
 /// Fetch the latest tool list from the connected MCP server.
+#[tracing::instrument(name = "mcp.list_tools", skip_all, fields(server = %connection.server.id))]
 pub async fn refresh_tools(connection: &mut McpConnection) -> Result<Vec<McpTool>> {
-    let tools = connection
-        .client
-        .list_all_tools()
-        .await
-        .context("list MCP tools")?;
+    let started = Instant::now();
+    let listed = connection.client.list_tools().await;
+    if capture::enabled() {
+        let response = match &listed {
+            Ok(tools) => json!({ "tools": tools }),
+            Err(err) => json!({ "error": format!("{err:#}") }),
+        };
+        let status = if listed.is_ok() { "ok" } else { "error" };
+        capture::record(
+            Channel::Mcp,
+            format!("{}/tools/list", connection.server.id),
+            &Value::Null,
+            status,
+            &response,
+            started.elapsed(),
+        );
+    }
+    let tools = listed?;
     connection.tool_cache = tools.clone();
     Ok(tools)
 }
 
-/// Invoke a named tool on the MCP server with the given JSON arguments.
+/// Invoke a named tool on the MCP server with the given JSON arguments,
+/// waiting first if the server's `rate_limit` is exhausted.
+#[tracing::instrument(name = "mcp.call_tool", skip_all, fields(server = %connection.server.id, tool = %tool))]
 pub async fn call_tool(connection: &McpConnection, tool: &str, args: Value) -> Result<Value> {
     let arguments = match args {
         Value::Null => None,
@@ -71,16 +152,42 @@ pub async fn call_tool(connection: &McpConnection, tool: &str, args: Value) -> R
         other => return Err(anyhow!("Tool args must be JSON object, got {other}")),
     };
 
-    let result: CallToolResult = connection
-        .client
-        .call_tool(CallToolRequestParam {
-            name: tool.to_string().into(),
-            arguments,
-        })
-        .await
-        .context("call MCP tool")?;
+    if let Some(limit) = connection.server.rate_limit {
+        rate_limit::acquire(&connection.server.id, limit).await;
+    }
+    let request = capture::enabled().then(|| json!({ "name": tool, "arguments": &arguments }));
+    let started = Instant::now();
+    let called = connection.client.call_tool(tool, arguments).await;
+    let target = format!("{}/{tool}", connection.server.id);
+    let result: CallToolResult = match called {
+        Ok(result) => result,
+        Err(err) => {
+            if let Some(request) = &request {
+                let response = json!({ "error": format!("{err:#}") });
+                capture::record(
+                    Channel::Mcp,
+                    target,
+                    request,
+                    "error",
+                    &response,
+                    started.elapsed(),
+                );
+            }
+            return Err(err);
+        }
+    };
 
     let value = serde_json::to_value(&result).context("serialize tool result")?;
+    if let Some(request) = &request {
+        capture::record(
+            Channel::Mcp,
+            target,
+            request,
+            "ok",
+            &value,
+            started.elapsed(),
+        );
+    }
     Ok(value)
 }
 
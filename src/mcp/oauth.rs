@@ -1,5 +1,6 @@
-//! OAuth 2.0 browser flow for MCP servers — discovery, PKCE, token exchange,
-//! and local callback handling.
+//! OAuth 2.0 flows for MCP servers — discovery, PKCE, token exchange,
+//! local callback handling, and the device authorization grant (RFC 8628)
+//! for machines without a browser.
 
 use std::net::TcpListener;
 use std::sync::mpsc;
@@ -22,7 +23,7 @@ use crate::util::normalize_url;
 
 const MCP_PROTOCOL_VERSION: &str = "2024-11-05";
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 #[allow(dead_code)]
 pub struct OAuthToken {
     pub access_token: String,
@@ -46,6 +47,22 @@ pub struct PendingOAuth {
     pub resource_value: String,
 }
 
+/// An in-progress device authorization: the code the user enters at
+/// `verification_uri`, plus what is needed to poll for the token.
+#[derive(Clone, Debug)]
+pub struct DeviceAuthorization {
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: Option<String>,
+    pub expires_in: u64,
+    pub interval: u64,
+    device_code: String,
+    client_id: String,
+    client_secret: Option<String>,
+    token_endpoint: String,
+    resource_value: String,
+}
+
 #[derive(Debug, Deserialize)]
 struct AuthServerMetadata {
     authorization_endpoint: String,
@@ -53,6 +70,8 @@ struct AuthServerMetadata {
     #[serde(default)]
     registration_endpoint: Option<String>,
     #[serde(default)]
+    device_authorization_endpoint: Option<String>,
+    #[serde(default)]
     #[allow(dead_code)]
     scopes_supported: Option<Vec<String>>,
 }
@@ -95,6 +114,37 @@ struct TokenResponse {
     token_type: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct DeviceAuthorizationResponse {
+    device_code: String,
+    user_code: String,
+    #[serde(alias = "verification_url")]
+    verification_uri: String,
+    #[serde(default)]
+    verification_uri_complete: Option<String>,
+    #[serde(default)]
+    expires_in: Option<u64>,
+    #[serde(default)]
+    interval: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    #[serde(default)]
+    error_description: Option<String>,
+}
+
+/// Endpoints and parameters resolved by discovery, shared by both flows.
+struct ResolvedEndpoints {
+    authorization_endpoint: String,
+    token_endpoint: String,
+    registration_endpoint: Option<String>,
+    device_authorization_endpoint: Option<String>,
+    scopes: Option<String>,
+    resource_value: String,
+}
+
 #[derive(Debug)]
 struct OAuthCallback {
     code: Option<String>,
@@ -129,6 +179,7 @@ async fn discover_auth_metadata(
             authorization_endpoint: auth_url.clone(),
             token_endpoint: token_url.clone(),
             registration_endpoint: auth.registration_endpoint.clone(),
+            device_authorization_endpoint: auth.device_authorization_endpoint.clone(),
             scopes_supported: auth.scopes.clone(),
         });
     }
@@ -378,7 +429,7 @@ struct AuthChallenge {
 }
 
 async fn probe_www_authenticate(resource: &Url) -> Result<Option<AuthChallenge>> {
-    let client = Client::builder()
+    let client = crate::http::client_builder(crate::http::Interface::Mcp)?
         .redirect(reqwest::redirect::Policy::none())
         .build()
         .context("build probe client")?;
@@ -508,13 +559,21 @@ async fn register_client(
     http: &Client,
     registration_endpoint: &str,
     redirect_uris: &[String],
+    grant_types: &[&str],
 ) -> Result<(String, Option<String>)> {
     let mut errors = Vec::new();
 
     // Prefer public-client registration for native apps (PKCE + loopback redirect).
     // Some IdPs also support confidential registration; fall back if needed.
     for method in ["none", "client_secret_post"] {
-        match register_client_with_method(http, registration_endpoint, redirect_uris, method).await
+        match register_client_with_method(
+            http,
+            registration_endpoint,
+            redirect_uris,
+            grant_types,
+            method,
+        )
+        .await
         {
             Ok(result) => return Ok(result),
             Err(err) => errors.push(format!("{method}: {err:#}")),
@@ -531,14 +590,20 @@ async fn register_client_with_method(
     http: &Client,
     registration_endpoint: &str,
     redirect_uris: &[String],
+    grant_types: &[&str],
     token_endpoint_auth_method: &str,
 ) -> Result<(String, Option<String>)> {
+    let response_types: &[&str] = if redirect_uris.is_empty() {
+        &[]
+    } else {
+        &["code"]
+    };
     let body = serde_json::json!({
         "client_name": APP_NAME,
         "redirect_uris": redirect_uris,
         "token_endpoint_auth_method": token_endpoint_auth_method,
-        "grant_types": ["authorization_code", "refresh_token"],
-        "response_types": ["code"],
+        "grant_types": grant_types,
+        "response_types": response_types,
         "application_type": "native",
     });
 
@@ -695,7 +760,7 @@ pub async fn wait_for_oauth_callback(
         }
     }
 
-    let http = Client::new();
+    let http = crate::http::client_or_default(crate::http::Interface::Mcp);
     let token = exchange_code(
         &http,
         &pending.token_endpoint,
@@ -718,16 +783,14 @@ pub async fn wait_for_oauth_callback(
     })
 }
 
-/// Prepare an OAuth flow: discover endpoints, register client, build the authorize URL,
-/// and return the pending state along with the URL to open.
-pub async fn prepare_auth<F>(
+/// Discover the authorization server for `server` and resolve endpoints,
+/// scopes and the resource indicator, honouring overrides in `auth`.
+async fn resolve_endpoints<F>(
     http: &Client,
     server: &McpServer,
     auth: &McpAuth,
-    client_id_hint: Option<String>,
-    client_secret_hint: Option<String>,
-    mut log: F,
-) -> Result<(String, PendingOAuth)>
+    log: &mut F,
+) -> Result<ResolvedEndpoints>
 where
     F: FnMut(String),
 {
@@ -762,6 +825,10 @@ where
         .clone()
         .or_else(|| metadata.registration_endpoint.clone())
         .or_else(|| default_registration_endpoint(&authorization_endpoint));
+    let device_authorization_endpoint = auth
+        .device_authorization_endpoint
+        .clone()
+        .or_else(|| metadata.device_authorization_endpoint.clone());
 
     let scopes = resolve_scopes(
         auth,
@@ -776,6 +843,38 @@ where
         .or_else(|| discovery.resource_hint.clone())
         .unwrap_or_else(|| resource.to_string());
 
+    Ok(ResolvedEndpoints {
+        authorization_endpoint,
+        token_endpoint,
+        registration_endpoint,
+        device_authorization_endpoint,
+        scopes,
+        resource_value,
+    })
+}
+
+/// Prepare an OAuth flow: discover endpoints, register client, build the authorize URL,
+/// and return the pending state along with the URL to open.
+pub async fn prepare_auth<F>(
+    http: &Client,
+    server: &McpServer,
+    auth: &McpAuth,
+    client_id_hint: Option<String>,
+    client_secret_hint: Option<String>,
+    mut log: F,
+) -> Result<(String, PendingOAuth)>
+where
+    F: FnMut(String),
+{
+    let ResolvedEndpoints {
+        authorization_endpoint,
+        token_endpoint,
+        registration_endpoint,
+        scopes,
+        resource_value,
+        ..
+    } = resolve_endpoints(http, server, auth, &mut log).await?;
+
     // Use a fixed port so the redirect_uri registered with the auth server
     // stays consistent across retries.
     let listener = TcpListener::bind("127.0.0.1:0").context("bind localhost for OAuth")?;
@@ -791,8 +890,13 @@ where
         Some(client_id) => (client_id, client_secret_hint),
         None => {
             if let Some(endpoint) = registration_endpoint {
-                let (client_id, client_secret) =
-                    register_client(http, &endpoint, &redirect_uris).await?;
+                let (client_id, client_secret) = register_client(
+                    http,
+                    &endpoint,
+                    &redirect_uris,
+                    &["authorization_code", "refresh_token"],
+                )
+                .await?;
                 log("Registered OAuth client dynamically.".to_string());
                 (client_id, client_secret.or(client_secret_hint))
             } else {
@@ -833,6 +937,153 @@ where
     Ok((auth_url.to_string(), pending))
 }
 
+// ── Device authorization grant ───────────────────────────────────────
+
+const DEVICE_CODE_GRANT: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Start a device-code flow: discover endpoints, register a client if
+/// needed, and request a user code to show.
+pub async fn start_device_auth<F>(
+    http: &Client,
+    server: &McpServer,
+    auth: &McpAuth,
+    client_id_hint: Option<String>,
+    client_secret_hint: Option<String>,
+    mut log: F,
+) -> Result<DeviceAuthorization>
+where
+    F: FnMut(String),
+{
+    let endpoints = resolve_endpoints(http, server, auth, &mut log).await?;
+    let device_endpoint = endpoints.device_authorization_endpoint.ok_or_else(|| {
+        anyhow!(
+            "The authorization server does not advertise a device_authorization_endpoint. \
+            Set auth.device_authorization_endpoint in mcp.json or use the browser flow."
+        )
+    })?;
+
+    let (client_id, client_secret) = match client_id_hint {
+        Some(client_id) => (client_id, client_secret_hint),
+        None => {
+            let endpoint = endpoints.registration_endpoint.ok_or_else(|| {
+                anyhow!("No client_id available and dynamic registration not supported.")
+            })?;
+            let (client_id, client_secret) =
+                register_client(http, &endpoint, &[], &[DEVICE_CODE_GRANT, "refresh_token"])
+                    .await?;
+            log("Registered OAuth client dynamically.".to_string());
+            (client_id, client_secret.or(client_secret_hint))
+        }
+    };
+
+    let mut params = vec![
+        ("client_id", client_id.clone()),
+        ("resource", endpoints.resource_value.clone()),
+    ];
+    if let Some(scope) = endpoints.scopes.filter(|scope| !scope.is_empty()) {
+        params.push(("scope", scope));
+    }
+    if let Some(secret) = &client_secret {
+        params.push(("client_secret", secret.clone()));
+    }
+
+    let response = http
+        .post(&device_endpoint)
+        .form(&params)
+        .send()
+        .await
+        .context("request device code")?;
+    let status = response.status();
+    let text = response
+        .text()
+        .await
+        .context("read device authorization response")?;
+    if !status.is_success() {
+        return Err(anyhow!(
+            "Device authorization failed: HTTP {status}: {text}"
+        ));
+    }
+    let parsed: DeviceAuthorizationResponse =
+        serde_json::from_str(&text).context("parse device authorization response")?;
+
+    Ok(DeviceAuthorization {
+        user_code: parsed.user_code,
+        verification_uri: parsed.verification_uri,
+        verification_uri_complete: parsed.verification_uri_complete,
+        expires_in: parsed.expires_in.unwrap_or(600),
+        interval: parsed.interval.unwrap_or(5).max(1),
+        device_code: parsed.device_code,
+        client_id,
+        client_secret,
+        token_endpoint: endpoints.token_endpoint,
+        resource_value: endpoints.resource_value,
+    })
+}
+
+/// Poll the token endpoint until the user approves the device, the code
+/// expires, or access is denied.
+pub async fn poll_device_token(http: &Client, device: &DeviceAuthorization) -> Result<OAuthToken> {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(device.expires_in);
+    let mut interval = device.interval;
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if tokio::time::Instant::now() >= deadline {
+            return Err(anyhow!("Device code expired before it was approved"));
+        }
+
+        let mut params = vec![
+            ("grant_type", DEVICE_CODE_GRANT.to_string()),
+            ("device_code", device.device_code.clone()),
+            ("client_id", device.client_id.clone()),
+            ("resource", device.resource_value.clone()),
+        ];
+        if let Some(secret) = &device.client_secret {
+            params.push(("client_secret", secret.clone()));
+        }
+
+        let response = http
+            .post(&device.token_endpoint)
+            .form(&params)
+            .send()
+            .await
+            .context("poll device token")?;
+        let status = response.status();
+        let text = response.text().await.context("read token response")?;
+
+        if status.is_success() {
+            let token: TokenResponse =
+                serde_json::from_str(&text).context("parse token response")?;
+            return Ok(OAuthToken {
+                access_token: token.access_token,
+                refresh_token: token.refresh_token,
+                expires_in: token.expires_in,
+                scope: token.scope,
+                token_type: token.token_type,
+                client_id: Some(device.client_id.clone()),
+            });
+        }
+
+        let error: TokenErrorResponse = serde_json::from_str(&text)
+            .map_err(|_| anyhow!("Token polling failed: HTTP {status}: {text}"))?;
+        // RFC 8628 §3.5: keep polling while pending, back off on slow_down.
+        match error.error.as_str() {
+            "authorization_pending" => {}
+            "slow_down" => interval += 5,
+            _ => {
+                return Err(anyhow!(
+                    "Device authorization failed: {}{}",
+                    error.error,
+                    error
+                        .error_description
+                        .map(|desc| format!(" ({desc})"))
+                        .unwrap_or_default()
+                ));
+            }
+        }
+    }
+}
+
 fn pkce_pair() -> (String, String) {
     let mut verifier_bytes = [0u8; 32];
     rand::thread_rng().fill_bytes(&mut verifier_bytes);
@@ -0,0 +1,187 @@
+//! Per-server call throttling for MCP tools.
+//!
+//! A server entry may carry `"rate_limit": {"requests_per_minute": 30,
+//! "burst": 5}`.  Each limited server gets a token bucket shared by every
+//! caller in the process (chat, daemons, agent windows, `/mcp call`), so
+//! concurrent tool loops queue behind each other instead of tripping the
+//! upstream quota.  [`acquire`] waits for a token; [`status`] reports what
+//! the bucket has been doing for `/mcp status`.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+/// `rate_limit` block of an `mcp.json` server entry.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub struct RateLimit {
+    pub requests_per_minute: u32,
+    /// Calls allowed back to back before throttling starts; defaults to 1.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burst: Option<u32>,
+}
+
+impl RateLimit {
+    fn capacity(&self) -> f64 {
+        f64::from(self.burst.unwrap_or(1).max(1))
+    }
+
+    fn interval(&self) -> Duration {
+        Duration::from_secs_f64(60.0 / f64::from(self.requests_per_minute.max(1)))
+    }
+}
+
+/// Snapshot of one server's bucket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThrottleStatus {
+    /// Whole tokens available right now.
+    pub available: u32,
+    /// Calls currently waiting for a token.
+    pub queued: usize,
+    pub calls: u64,
+    /// Calls that had to wait at all.
+    pub throttled: u64,
+    pub total_wait: Duration,
+}
+
+struct Bucket {
+    limit: RateLimit,
+    tokens: f64,
+    refilled_at: Instant,
+    queued: usize,
+    calls: u64,
+    throttled: u64,
+    total_wait: Duration,
+}
+
+impl Bucket {
+    fn new(limit: RateLimit, now: Instant) -> Self {
+        Bucket {
+            limit,
+            tokens: limit.capacity(),
+            refilled_at: now,
+            queued: 0,
+            calls: 0,
+            throttled: 0,
+            total_wait: Duration::ZERO,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at);
+        let gained = elapsed.as_secs_f64() / self.limit.interval().as_secs_f64();
+        self.tokens = (self.tokens + gained).min(self.limit.capacity());
+        self.refilled_at = now;
+    }
+
+    /// Take a token, or say how long until one is available.
+    fn try_take(&mut self, now: Instant) -> Result<(), Duration> {
+        self.refill(now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        let missing = 1.0 - self.tokens;
+        Err(self.limit.interval().mul_f64(missing))
+    }
+}
+
+fn buckets() -> &'static Mutex<HashMap<String, Bucket>> {
+    static BUCKETS: OnceLock<Mutex<HashMap<String, Bucket>>> = OnceLock::new();
+    BUCKETS.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Wait until `server_id` may make another call under `limit`.
+///
+/// Callers are served roughly in arrival order: each waiter sleeps until
+/// its share of the refill is due, then retries.
+pub async fn acquire(server_id: &str, limit: RateLimit) {
+    let started = Instant::now();
+    let mut waited = false;
+    loop {
+        let delay = {
+            let mut map = buckets().lock().unwrap_or_else(|e| e.into_inner());
+            let bucket = map
+                .entry(server_id.to_string())
+                .or_insert_with(|| Bucket::new(limit, started));
+            if bucket.limit != limit {
+                // mcp.json was edited and reloaded; start over with the new shape.
+                *bucket = Bucket::new(limit, Instant::now());
+            }
+            match bucket.try_take(Instant::now()) {
+                Ok(()) => {
+                    bucket.calls += 1;
+                    if waited {
+                        bucket.queued = bucket.queued.saturating_sub(1);
+                        bucket.throttled += 1;
+                        bucket.total_wait += started.elapsed();
+                    }
+                    return;
+                }
+                Err(delay) => {
+                    if !waited {
+                        bucket.queued += 1;
+                        waited = true;
+                    }
+                    // Later arrivals wait behind the ones already queued.
+                    delay + bucket.limit.interval() * (bucket.queued as u32 - 1)
+                }
+            }
+        };
+        tracing::debug!(
+            server = server_id,
+            wait_ms = delay.as_millis() as u64,
+            "mcp throttled"
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Current throttle state for `server_id`, if it has made a limited call.
+pub fn status(server_id: &str) -> Option<ThrottleStatus> {
+    let mut map = buckets().lock().unwrap_or_else(|e| e.into_inner());
+    let bucket = map.get_mut(server_id)?;
+    bucket.refill(Instant::now());
+    Some(ThrottleStatus {
+        available: bucket.tokens.floor() as u32,
+        queued: bucket.queued,
+        calls: bucket.calls,
+        throttled: bucket.throttled,
+        total_wait: bucket.total_wait,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn burst_then_refill_at_the_configured_rate() {
+        let limit = RateLimit {
+            requests_per_minute: 60,
+            burst: Some(2),
+        };
+        let start = Instant::now();
+        let mut bucket = Bucket::new(limit, start);
+        assert!(bucket.try_take(start).is_ok());
+        assert!(bucket.try_take(start).is_ok());
+        let wait = bucket.try_take(start).unwrap_err();
+        assert!(wait > Duration::from_millis(990) && wait <= Duration::from_secs(1));
+        let later = start + Duration::from_millis(500);
+        let wait = bucket.try_take(later).unwrap_err();
+        assert!(wait <= Duration::from_millis(501));
+        assert!(bucket.try_take(start + Duration::from_secs(1)).is_ok());
+        // Idle time never banks more than the burst.
+        bucket.refill(start + Duration::from_secs(600));
+        assert_eq!(bucket.tokens, 2.0);
+    }
+
+    #[test]
+    fn parses_from_server_config() {
+        let limit: RateLimit =
+            serde_json::from_str(r#"{"requests_per_minute": 30}"#).expect("parse");
+        assert_eq!(limit.capacity(), 1.0);
+        assert_eq!(limit.interval(), Duration::from_secs(2));
+    }
+}
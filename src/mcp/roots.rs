@@ -0,0 +1,98 @@
+//! Filesystem roots exposed to MCP servers (`roots/list`).
+//!
+//! The set starts as the workspace root and is shared by every connection
+//! in the process.  `/mcp roots add|remove` edits it for the session and
+//! then sends `notifications/roots/list_changed` so servers re-fetch it.
+
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Context, Result, bail};
+use rmcp::model::Root;
+
+use crate::local_tools::workspace_root;
+
+fn roots_cell() -> &'static RwLock<Vec<PathBuf>> {
+    static ROOTS: OnceLock<RwLock<Vec<PathBuf>>> = OnceLock::new();
+    ROOTS.get_or_init(|| {
+        let root = workspace_root();
+        RwLock::new(vec![root.canonicalize().unwrap_or(root)])
+    })
+}
+
+/// Directories currently exposed, in the order they were added.
+pub fn current() -> Vec<PathBuf> {
+    roots_cell()
+        .read()
+        .map(|roots| roots.clone())
+        .unwrap_or_default()
+}
+
+/// Expose `path` (resolved against the workspace root).  Returns the
+/// canonical directory, or `None` when it was already exposed.
+pub fn add(path: &str) -> Result<Option<PathBuf>> {
+    let dir = resolve(path)?;
+    if !dir.is_dir() {
+        bail!("{} is not a directory", dir.display());
+    }
+    let Ok(mut roots) = roots_cell().write() else {
+        bail!("roots lock poisoned");
+    };
+    if roots.contains(&dir) {
+        return Ok(None);
+    }
+    roots.push(dir.clone());
+    Ok(Some(dir))
+}
+
+/// Stop exposing `path`.  Returns the removed directory, if it was exposed.
+pub fn remove(path: &str) -> Option<PathBuf> {
+    let dir = resolve(path).ok()?;
+    let mut roots = roots_cell().write().ok()?;
+    let index = roots.iter().position(|root| *root == dir)?;
+    Some(roots.remove(index))
+}
+
+/// The exposed set as MCP `Root`s (`file://` URIs named after the folder).
+pub fn list() -> Vec<Root> {
+    current().iter().filter_map(|dir| to_root(dir)).collect()
+}
+
+fn to_root(dir: &Path) -> Option<Root> {
+    let uri = url::Url::from_directory_path(dir).ok()?;
+    Some(Root {
+        uri: uri.to_string(),
+        name: dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned()),
+    })
+}
+
+fn resolve(path: &str) -> Result<PathBuf> {
+    let path = PathBuf::from(path);
+    let absolute = if path.is_absolute() {
+        path
+    } else {
+        workspace_root().join(path)
+    };
+    absolute
+        .canonicalize()
+        .with_context(|| format!("resolve {}", absolute.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roots_are_directory_uris() {
+        let dir = std::env::temp_dir().canonicalize().expect("temp dir");
+        let root = to_root(&dir).expect("root");
+        assert!(root.uri.starts_with("file:///"));
+        assert!(root.uri.ends_with('/'));
+        assert_eq!(
+            root.name.as_deref(),
+            dir.file_name().and_then(|name| name.to_str())
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! `sampling/createMessage` — MCP servers asking memini's model for a
+//! completion.
+//!
+//! Every connection is served by a [`MeminiClient`], which advertises the
+//! sampling capability unless the server entry sets `"sampling": {"mode":
+//! "deny"}`.  A request is throttled per server, converted to Responses API
+//! input and handed to the hook the app installs at startup ([`install`]);
+//! the app asks the user to approve it (mode `ask`, the default), runs the
+//! model and returns the answer as the server's `CreateMessageResult`.
+
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Result, anyhow};
+use rmcp::model::{
+    ClientCapabilities, ClientInfo, Content, CreateMessageRequestParam, CreateMessageResult,
+    ErrorCode, ListRootsResult, RawContent, Role, SamplingMessage,
+};
+use rmcp::service::{NotificationContext, RequestContext};
+use rmcp::{ClientHandler, ErrorData, RoleClient};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+
+use crate::backend::BoxFuture;
+use crate::mcp::config::McpServer;
+use crate::mcp::rate_limit::{self, RateLimit};
+use crate::mcp::{roots, tool_changes};
+
+/// Limit applied when a server entry sets no `sampling.rate_limit`.
+const DEFAULT_SAMPLING_RATE_LIMIT: RateLimit = RateLimit {
+    requests_per_minute: 10,
+    burst: Some(2),
+};
+
+/// JSON-RPC code the MCP spec uses for a sampling request the user declined.
+const USER_REJECTED: ErrorCode = ErrorCode(-1);
+
+/// How a server's sampling requests are handled.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SamplingMode {
+    /// Each request waits for `/policy approve <id>`.
+    #[default]
+    Ask,
+    Allow,
+    /// Sampling is not advertised and requests are refused.
+    Deny,
+}
+
+/// `sampling` block of an `mcp.json` server entry.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct SamplingConfig {
+    #[serde(default)]
+    pub mode: SamplingMode,
+    /// Model to answer with; defaults to the active chat model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Defaults to 10 requests per minute with a burst of 2.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimit>,
+}
+
+/// A sampling request ready for the model.
+#[derive(Clone, Debug)]
+pub struct SamplingRequest {
+    pub server_id: String,
+    pub mode: SamplingMode,
+    pub model: Option<String>,
+    /// Responses API input: the system prompt first, then the messages.
+    pub input: Vec<Value>,
+    pub max_tokens: u32,
+    /// Short text shown when asking for approval.
+    pub preview: String,
+}
+
+/// The model's answer.
+#[derive(Clone, Debug)]
+pub struct SamplingReply {
+    pub model: String,
+    pub text: String,
+}
+
+/// Answers sampling requests; installed by the app.
+pub type SamplingHook =
+    Arc<dyn Fn(SamplingRequest) -> BoxFuture<'static, Result<SamplingReply>> + Send + Sync>;
+
+fn hook_cell() -> &'static RwLock<Option<SamplingHook>> {
+    static HOOK: RwLock<Option<SamplingHook>> = RwLock::new(None);
+    &HOOK
+}
+
+/// Route sampling requests from every MCP connection to `hook`.
+pub fn install(hook: SamplingHook) {
+    if let Ok(mut slot) = hook_cell().write() {
+        *slot = Some(hook);
+    }
+}
+
+fn hook() -> Option<SamplingHook> {
+    hook_cell().read().ok().and_then(|slot| slot.clone())
+}
+
+/// The rmcp client handler for one server connection: sampling requests,
+/// `roots/list` (see [`roots`]) and tool list changes (see [`tool_changes`]).
+#[derive(Clone, Debug)]
+pub struct MeminiClient {
+    server_id: String,
+    sampling: SamplingConfig,
+}
+
+impl MeminiClient {
+    pub fn new(server: &McpServer) -> Self {
+        MeminiClient {
+            server_id: server.id.clone(),
+            sampling: server.sampling.clone().unwrap_or_default(),
+        }
+    }
+
+    async fn sample(&self, params: CreateMessageRequestParam) -> Result<SamplingReply> {
+        let hook = hook().ok_or_else(|| anyhow!("sampling is not available"))?;
+        let limit = self
+            .sampling
+            .rate_limit
+            .unwrap_or(DEFAULT_SAMPLING_RATE_LIMIT);
+        rate_limit::acquire(&format!("{}/sampling", self.server_id), limit).await;
+        hook(SamplingRequest {
+            server_id: self.server_id.clone(),
+            mode: self.sampling.mode,
+            model: self.sampling.model.clone(),
+            input: to_responses_input(&params),
+            max_tokens: params.max_tokens,
+            preview: preview(&params),
+        })
+        .await
+    }
+}
+
+impl ClientHandler for MeminiClient {
+    fn create_message(
+        &self,
+        params: CreateMessageRequestParam,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<CreateMessageResult, ErrorData>> + Send + '_ {
+        async move {
+            if self.sampling.mode == SamplingMode::Deny {
+                return Err(ErrorData::invalid_request(
+                    format!("sampling is disabled for '{}'", self.server_id),
+                    None,
+                ));
+            }
+            match self.sample(params).await {
+                Ok(reply) => Ok(CreateMessageResult {
+                    model: reply.model,
+                    stop_reason: Some(CreateMessageResult::STOP_REASON_END_TURN.to_string()),
+                    message: SamplingMessage {
+                        role: Role::Assistant,
+                        content: Content::text(reply.text),
+                    },
+                }),
+                Err(err) if err.is::<Rejected>() => Err(ErrorData::new(
+                    USER_REJECTED,
+                    "User rejected sampling request",
+                    None,
+                )),
+                Err(err) => Err(ErrorData::internal_error(format!("{err:#}"), None)),
+            }
+        }
+    }
+
+    fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> impl Future<Output = Result<ListRootsResult, ErrorData>> + Send + '_ {
+        std::future::ready(Ok(ListRootsResult {
+            roots: roots::list(),
+        }))
+    }
+
+    fn on_tool_list_changed(
+        &self,
+        context: NotificationContext<RoleClient>,
+    ) -> impl Future<Output = ()> + Send + '_ {
+        async move {
+            match context.peer.list_all_tools().await {
+                Ok(tools) => tool_changes::stash(&self.server_id, tools),
+                Err(err) => tracing::warn!(
+                    server = %self.server_id,
+                    "re-list tools after list_changed failed: {err}"
+                ),
+            }
+        }
+    }
+
+    fn get_info(&self) -> ClientInfo {
+        let mut capabilities = ClientCapabilities::builder()
+            .enable_roots()
+            .enable_roots_list_changed()
+            .build();
+        if self.sampling.mode != SamplingMode::Deny {
+            capabilities.sampling = Some(Default::default());
+        }
+        ClientInfo {
+            capabilities,
+            ..ClientInfo::default()
+        }
+    }
+}
+
+/// Returned by the hook when the user declines a request.
+#[derive(Debug)]
+pub struct Rejected;
+
+impl std::fmt::Display for Rejected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("sampling request rejected")
+    }
+}
+
+impl std::error::Error for Rejected {}
+
+/// Responses API input items for a sampling request.
+fn to_responses_input(params: &CreateMessageRequestParam) -> Vec<Value> {
+    let mut input = Vec::new();
+    if let Some(system) = params.system_prompt.as_deref().filter(|s| !s.is_empty()) {
+        input.push(json!({"role": "system", "content": system}));
+    }
+    for message in &params.messages {
+        let (role, text_type) = match message.role {
+            Role::User => ("user", "input_text"),
+            Role::Assistant => ("assistant", "output_text"),
+        };
+        let part = match &*message.content {
+            RawContent::Text(text) => json!({"type": text_type, "text": text.text}),
+            RawContent::Image(image) if message.role == Role::User => json!({
+                "type": "input_image",
+                "image_url": format!("data:{};base64,{}", image.mime_type, image.data),
+            }),
+            _ => json!({"type": text_type, "text": "[unsupported content]"}),
+        };
+        input.push(json!({"role": role, "content": [part]}));
+    }
+    input
+}
+
+/// The system prompt and the last user message, for the approval prompt.
+fn preview(params: &CreateMessageRequestParam) -> String {
+    let last = params
+        .messages
+        .iter()
+        .rev()
+        .find(|message| message.role == Role::User)
+        .and_then(|message| message.content.as_text())
+        .map(|text| text.text.as_str())
+        .unwrap_or("");
+    match params.system_prompt.as_deref().filter(|s| !s.is_empty()) {
+        Some(system) => format!("[system] {system}\n{last}"),
+        None => last.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn messages_become_responses_input() {
+        let params: CreateMessageRequestParam = serde_json::from_value(json!({
+            "systemPrompt": "Answer in one word.",
+            "maxTokens": 50,
+            "messages": [
+                {"role": "user", "content": {"type": "text", "text": "Capital of France?"}},
+                {"role": "assistant", "content": {"type": "text", "text": "Paris"}},
+                {"role": "user", "content": {"type": "image", "mimeType": "image/png", "data": "AAAA"}}
+            ]
+        }))
+        .expect("params");
+        let input = to_responses_input(&params);
+        assert_eq!(input.len(), 4);
+        assert_eq!(
+            input[0],
+            json!({"role": "system", "content": "Answer in one word."})
+        );
+        assert_eq!(
+            input[2]["content"][0],
+            json!({"type": "output_text", "text": "Paris"})
+        );
+        assert_eq!(
+            input[3]["content"][0]["image_url"],
+            "data:image/png;base64,AAAA"
+        );
+        assert_eq!(preview(&params), "[system] Answer in one word.\n");
+    }
+}
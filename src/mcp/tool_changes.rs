@@ -0,0 +1,98 @@
+//! `notifications/tools/list_changed` handling.
+//!
+//! When a server says its tools changed, the connection's client handler
+//! re-fetches the list right away and [`stash`]es it here.  The app picks
+//! stashed lists up on its next tick ([`take`]), swaps them into the live
+//! connection's `tool_cache` and logs the [`diff`].
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+use rmcp::model::Tool as McpTool;
+
+fn pending() -> &'static Mutex<HashMap<String, Vec<McpTool>>> {
+    static PENDING: OnceLock<Mutex<HashMap<String, Vec<McpTool>>>> = OnceLock::new();
+    PENDING.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// Record the fresh tool list of `server_id`; a newer list replaces one the
+/// app has not picked up yet.
+pub fn stash(server_id: &str, tools: Vec<McpTool>) {
+    if let Ok(mut pending) = pending().lock() {
+        pending.insert(server_id.to_string(), tools);
+    }
+}
+
+/// Every list stashed since the last call, by server id.
+pub fn take() -> Vec<(String, Vec<McpTool>)> {
+    pending()
+        .lock()
+        .map(|mut pending| pending.drain().collect())
+        .unwrap_or_default()
+}
+
+/// Tool names that appeared, disappeared, or kept their name but changed
+/// description or schema.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ToolListDiff {
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub changed: Vec<String>,
+}
+
+impl ToolListDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+pub fn diff(old: &[McpTool], new: &[McpTool]) -> ToolListDiff {
+    let mut result = ToolListDiff::default();
+    for tool in new {
+        match old.iter().find(|previous| previous.name == tool.name) {
+            None => result.added.push(tool.name.to_string()),
+            Some(previous)
+                if previous.description != tool.description
+                    || previous.input_schema != tool.input_schema =>
+            {
+                result.changed.push(tool.name.to_string())
+            }
+            Some(_) => {}
+        }
+    }
+    for tool in old {
+        if !new.iter().any(|current| current.name == tool.name) {
+            result.removed.push(tool.name.to_string());
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    fn tool(name: &'static str, description: &'static str) -> McpTool {
+        McpTool::new(name, description, Arc::new(serde_json::Map::new()))
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_changed_tools() {
+        let old = [tool("search", "Search"), tool("fetch", "Fetch a page")];
+        let new = [
+            tool("search", "Search the index"),
+            tool("summarize", "Summarize"),
+        ];
+        assert_eq!(
+            diff(&old, &new),
+            ToolListDiff {
+                added: vec!["summarize".to_string()],
+                removed: vec!["fetch".to_string()],
+                changed: vec!["search".to_string()],
+            }
+        );
+        assert!(diff(&new, &new).is_empty());
+    }
+}
@@ -1,11 +1,21 @@
-//! OpenAI API client — chat responses, embeddings, and response helpers.
+//! OpenAI API client — chat responses, embeddings, transcription, and
+//! response helpers.
+
+use std::sync::Arc;
+use std::time::Instant;
 
 use anyhow::{Context, Result, anyhow};
 use reqwest::Client as HttpClient;
-use serde::Serialize;
 use serde_json::{Value, json};
 
-use crate::constants::{DEFAULT_OPENAI_BASE_URL, DEFAULT_OPENAI_MODEL, MAX_TOOL_LOOPS};
+use crate::backend::{BoxFuture, LlmBackend};
+use crate::capture::{self, Channel};
+
+use crate::constants::{
+    DEFAULT_EMBEDDING_MODEL, DEFAULT_OPENAI_MODEL, DEFAULT_TRANSCRIPTION_MODEL,
+};
+use crate::provider;
+use crate::routing::{FallbackChain, ModelRoutes, TaskKind};
 use crate::util::env_first;
 
 /// A single tool-call extracted from an OpenAI response.
@@ -19,14 +29,25 @@ pub struct ToolCall {
 /// Thin wrapper around the OpenAI HTTP API.
 #[derive(Clone)]
 pub struct OpenAiClient {
+    /// Active `/model`; the fallback for every task kind without a route.
     pub model: String,
+    /// Per-task model overrides (see [`crate::routing`]).
+    pub routes: ModelRoutes,
+    /// Models tried when a request fails with a provider error.
+    pub fallback: FallbackChain,
     pub reasoning_effort: Option<String>,
-    pub base_url: String,
     http_client: HttpClient,
+    /// JSON requests go through here (see [`crate::backend`]).
+    backend: Arc<dyn LlmBackend>,
 }
 
 impl OpenAiClient {
     pub fn new() -> Self {
+        Self::with_backend(Arc::new(HttpLlm::from_env()))
+    }
+
+    /// A client whose JSON requests go to `backend`.
+    pub fn with_backend(backend: Arc<dyn LlmBackend>) -> Self {
         let model = env_first(&["OPENAI_MODEL", "MEMINI_OPENAI_MODEL"])
             .unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string());
         let reasoning_effort = env_first(&[
@@ -37,33 +58,141 @@ impl OpenAiClient {
         ])
         .and_then(|raw| parse_reasoning_setting(&raw))
         .flatten();
-        let base_url = env_first(&["OPENAI_BASE_URL", "OPENAI_API_BASE"])
-            .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string());
         OpenAiClient {
             model,
+            routes: crate::config::get().routing.clone(),
+            fallback: crate::config::get().fallback.clone(),
             reasoning_effort,
-            base_url: base_url.trim_end_matches('/').to_string(),
-            http_client: HttpClient::new(),
+            http_client: crate::http::client_or_default(crate::http::Interface::OpenAi),
+            backend,
         }
     }
 
+    /// This client with a different reasoning effort (`None` turns it off).
+    pub fn with_reasoning(&self, effort: Option<String>) -> Self {
+        OpenAiClient {
+            reasoning_effort: effort,
+            ..self.clone()
+        }
+    }
+
+    /// Model that should handle `task`.
+    pub fn model_for(&self, task: TaskKind) -> String {
+        if let Some(model) = self.routes.get(task) {
+            return model.to_string();
+        }
+        match task {
+            TaskKind::Embedding => env_first(&["OPENAI_EMBEDDING_MODEL", "MEMINI_EMBEDDING_MODEL"])
+                .unwrap_or_else(|| DEFAULT_EMBEDDING_MODEL.to_string()),
+            _ => self.model.clone(),
+        }
+    }
+
+    /// Run a Responses API request against `model`, walking the
+    /// `[fallback]` chain when the provider rejects it (unknown model,
+    /// overload, content filter).  A response from a fallback model carries
+    /// a `memini_fallback` object; see [`answering_model`] and
+    /// [`fallback_note`].
+    #[tracing::instrument(name = "openai.response", skip_all, fields(model = %model, input_items = input.len()))]
     pub async fn response(
         &self,
         key: &str,
+        model: &str,
         input: &[Value],
         tools: Option<&[Value]>,
+    ) -> Result<Value> {
+        self.response_with(key, model, input, tools, None).await
+    }
+
+    /// Like [`Self::response`] without tools, asking for output in `format`
+    /// (a `text.format` object such as `{"type": "json_schema", ...}`).
+    #[tracing::instrument(name = "openai.structured_response", skip_all, fields(model = %model, input_items = input.len()))]
+    pub async fn structured_response(
+        &self,
+        key: &str,
+        model: &str,
+        input: &[Value],
+        format: &Value,
+    ) -> Result<Value> {
+        self.response_with(key, model, input, None, Some(format))
+            .await
+    }
+
+    async fn response_with(
+        &self,
+        key: &str,
+        model: &str,
+        input: &[Value],
+        tools: Option<&[Value]>,
+        format: Option<&Value>,
+    ) -> Result<Value> {
+        let fallbacks = self.fallback.after(model);
+        let mut failures: Vec<String> = Vec::new();
+        let mut last_err = None;
+        for (attempt, candidate) in std::iter::once(model.to_string())
+            .chain(fallbacks.iter().cloned())
+            .enumerate()
+        {
+            let last = attempt == fallbacks.len();
+            let result = self
+                .response_once(key, &candidate, input, tools, format)
+                .await;
+            crate::usage::record(&candidate, &result);
+            match result {
+                Ok(response) if !last && content_filtered(&response) => {
+                    failures.push(format!("{candidate}: content filter"));
+                }
+                Ok(mut response) => {
+                    if attempt > 0 {
+                        tracing::warn!(requested = %model, answered = %candidate, "model fallback");
+                        response["memini_fallback"] = json!({
+                            "requested": model,
+                            "model": candidate,
+                            "failures": failures,
+                        });
+                    }
+                    return Ok(response);
+                }
+                Err(err) if !last && is_fallback_error(&err) => {
+                    failures.push(format!("{candidate}: {}", short_error(&err)));
+                    last_err = Some(err);
+                }
+                Err(err) if attempt > 0 => {
+                    failures.push(format!("{candidate}: {}", short_error(&err)));
+                    return Err(err.context(format!(
+                        "every fallback model failed ({})",
+                        failures.join("; ")
+                    )));
+                }
+                Err(err) => return Err(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| anyhow!("no model to try")))
+    }
+
+    async fn response_once(
+        &self,
+        key: &str,
+        model: &str,
+        input: &[Value],
+        tools: Option<&[Value]>,
+        format: Option<&Value>,
     ) -> Result<Value> {
         let mut body = json!({
-            "model": self.model,
+            "model": model,
             "input": input,
         });
+        crate::redact::mask_value(&mut body["input"]);
+        if let Some(format) = format {
+            body["text"] = json!({ "format": format });
+        }
         if let Some(effort) = &self.reasoning_effort {
-            body["reasoning"] = json!({ "effort": effort });
+            body["reasoning"] = json!({ "effort": effort, "summary": "auto" });
         }
         if let Some(tools) = tools {
             body["tools"] = Value::Array(tools.to_vec());
         }
-        match self.request(key, "responses", body.clone()).await {
+        match self.backend.post_json(key, "responses", body.clone()).await {
             Ok(value) => Ok(value),
             Err(err) => {
                 let should_retry_without_reasoning = body.get("reasoning").is_some() && {
@@ -73,43 +202,252 @@ impl OpenAiClient {
                 if !should_retry_without_reasoning {
                     return Err(err);
                 }
+                // Summaries need a verified organization; keep the effort
+                // when only they were refused.
+                let summaries_refused = err.to_string().to_ascii_lowercase().contains("summar");
                 let mut fallback_body = body;
-                if let Some(obj) = fallback_body.as_object_mut() {
+                if summaries_refused {
+                    if let Some(reasoning) = fallback_body["reasoning"].as_object_mut() {
+                        reasoning.remove("summary");
+                    }
+                } else if let Some(obj) = fallback_body.as_object_mut() {
                     obj.remove("reasoning");
                 }
-                self.request(key, "responses", fallback_body).await
+                self.backend
+                    .post_json(key, "responses", fallback_body)
+                    .await
             }
         }
     }
 
-    async fn request(&self, key: &str, path: &str, body: Value) -> Result<Value> {
-        let client = self.http_client.clone();
-        let base_url = self.base_url.clone();
-        let url = format!(
-            "{}/{}",
-            base_url.trim_end_matches('/'),
-            path.trim_start_matches('/')
-        );
-        let payload = body;
-        let key = key.to_string();
+    /// Embed `text` with the `embedding` route, else `OPENAI_EMBEDDING_MODEL`
+    /// (default `text-embedding-3-small`).
+    #[tracing::instrument(name = "openai.embedding", skip_all)]
+    pub async fn embedding(&self, key: &str, text: &str) -> Result<Vec<f32>> {
+        let model = self.model_for(TaskKind::Embedding);
+        let body = json!({ "model": model, "input": text });
+        let response = self.backend.post_json(key, "embeddings", body).await?;
+        let vector = response
+            .pointer("/data/0/embedding")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("OpenAI embedding response had no vector"))?;
+        Ok(vector
+            .iter()
+            .filter_map(Value::as_f64)
+            .map(|value| value as f32)
+            .collect())
+    }
+
+    /// Embed several texts in one request, in input order.
+    #[tracing::instrument(name = "openai.embeddings", skip_all, fields(count = texts.len()))]
+    pub async fn embeddings(&self, key: &str, texts: &[String]) -> Result<Vec<Vec<f32>>> {
+        let model = self.model_for(TaskKind::Embedding);
+        let mut body = json!({ "model": model, "input": texts });
+        crate::redact::mask_value(&mut body["input"]);
+        let response = self.backend.post_json(key, "embeddings", body).await?;
+        let data = response
+            .get("data")
+            .and_then(Value::as_array)
+            .ok_or_else(|| anyhow!("OpenAI embedding response had no data"))?;
+        let mut vectors = vec![Vec::new(); texts.len()];
+        for (position, item) in data.iter().enumerate() {
+            let index = item
+                .get("index")
+                .and_then(Value::as_u64)
+                .map_or(position, |index| index as usize);
+            let vector = item
+                .get("embedding")
+                .and_then(Value::as_array)
+                .ok_or_else(|| anyhow!("OpenAI embedding response had no vector"))?;
+            if let Some(slot) = vectors.get_mut(index) {
+                *slot = vector
+                    .iter()
+                    .filter_map(Value::as_f64)
+                    .map(|value| value as f32)
+                    .collect();
+            }
+        }
+        if vectors.iter().any(Vec::is_empty) {
+            return Err(anyhow!("OpenAI embedding response was missing vectors"));
+        }
+        Ok(vectors)
+    }
 
-        let response = client
-            .post(url)
-            .bearer_auth(key)
-            .json(&payload)
+    /// Transcribe a WAV recording with the audio transcription endpoint.
+    #[tracing::instrument(name = "openai.transcribe", skip_all, fields(bytes = wav.len()))]
+    pub async fn transcribe(&self, key: &str, wav: Vec<u8>) -> Result<String> {
+        let model = env_first(&["OPENAI_TRANSCRIPTION_MODEL", "MEMINI_TRANSCRIPTION_MODEL"])
+            .unwrap_or_else(|| DEFAULT_TRANSCRIPTION_MODEL.to_string());
+        let file = reqwest::multipart::Part::bytes(wav)
+            .file_name("voice.wav")
+            .mime_str("audio/wav")
+            .context("build transcription upload")?;
+        let provider = provider::current();
+        let url = provider.url("audio/transcriptions", &model);
+        let form = reqwest::multipart::Form::new()
+            .text("model", model)
+            .part("file", file);
+        let response = provider
+            .authorize(self.http_client.post(url), key)
+            .multipart(form)
             .send()
             .await
-            .context("send OpenAI request")?;
+            .context("send transcription request")?;
         let status = response.status();
-        let text = response.text().await.context("read OpenAI response")?;
+        let text = response
+            .text()
+            .await
+            .context("read transcription response")?;
         let json: Value = serde_json::from_str(&text).unwrap_or_else(|_| json!({"raw": text}));
         if !status.is_success() {
             return Err(anyhow!("OpenAI error {status}: {json}"));
         }
-        Ok(json)
+        json.get("text")
+            .and_then(Value::as_str)
+            .map(|text| text.trim().to_string())
+            .ok_or_else(|| anyhow!("OpenAI transcription response had no text"))
+    }
+}
+
+/// The OpenAI HTTP API of the current [`provider`].
+pub struct HttpLlm {
+    http_client: HttpClient,
+}
+
+impl HttpLlm {
+    pub fn from_env() -> Self {
+        HttpLlm {
+            http_client: crate::http::client_or_default(crate::http::Interface::OpenAi),
+        }
+    }
+}
+
+impl LlmBackend for HttpLlm {
+    fn post_json<'a>(
+        &'a self,
+        key: &'a str,
+        path: &'a str,
+        mut body: Value,
+    ) -> BoxFuture<'a, Result<Value>> {
+        Box::pin(async move {
+            let provider = provider::current();
+            let model = body.get("model").and_then(Value::as_str).unwrap_or("");
+            let url = provider.url(path, model);
+            provider.prepare_body(&mut body);
+            let started = Instant::now();
+            let response = match provider
+                .authorize(self.http_client.post(url), key)
+                .json(&body)
+                .send()
+                .await
+            {
+                Ok(response) => response,
+                Err(err) => {
+                    capture::record(
+                        Channel::OpenAi,
+                        path,
+                        &body,
+                        "error",
+                        &json!({"error": err.to_string()}),
+                        started.elapsed(),
+                    );
+                    return Err(err).context("send OpenAI request");
+                }
+            };
+            let status = response.status();
+            let text = response.text().await.context("read OpenAI response")?;
+            let json: Value = serde_json::from_str(&text).unwrap_or_else(|_| json!({"raw": text}));
+            capture::record(
+                Channel::OpenAi,
+                path,
+                &body,
+                status.as_u16().to_string(),
+                &json,
+                started.elapsed(),
+            );
+            if !status.is_success() {
+                return Err(anyhow!("OpenAI error {status}: {json}"));
+            }
+            Ok(json)
+        })
     }
 }
 
+/// Error markers that make a request worth retrying on another model.
+const FALLBACK_ERROR_MARKERS: &[&str] = &[
+    "model_not_found",
+    "does not exist",
+    "overloaded",
+    "content_filter",
+    "content filter",
+    "server_error",
+    "openai error 429",
+    "openai error 500",
+    "openai error 502",
+    "openai error 503",
+    "openai error 529",
+];
+
+/// `true` when another model might succeed where this request failed.
+pub fn is_fallback_error(err: &anyhow::Error) -> bool {
+    let message = format!("{err:#}").to_ascii_lowercase();
+    FALLBACK_ERROR_MARKERS
+        .iter()
+        .any(|marker| message.contains(marker))
+}
+
+/// `true` when the response was cut off by the content filter.
+fn content_filtered(response: &Value) -> bool {
+    response
+        .pointer("/incomplete_details/reason")
+        .and_then(Value::as_str)
+        == Some("content_filter")
+}
+
+/// The provider's error code or message, else the first line of `err`.
+fn short_error(err: &anyhow::Error) -> String {
+    let message = err.to_string();
+    let detail = message
+        .split_once(": ")
+        .and_then(|(_, body)| serde_json::from_str::<Value>(body).ok())
+        .and_then(|body| {
+            let error = body.get("error")?;
+            error
+                .get("code")
+                .and_then(Value::as_str)
+                .or_else(|| error.get("message").and_then(Value::as_str))
+                .map(str::to_string)
+        });
+    detail.unwrap_or_else(|| message.lines().next().unwrap_or("").to_string())
+}
+
+/// The model that produced `response`: the fallback that answered, else
+/// `requested`.
+pub fn answering_model(response: &Value, requested: &str) -> String {
+    response
+        .pointer("/memini_fallback/model")
+        .and_then(Value::as_str)
+        .unwrap_or(requested)
+        .to_string()
+}
+
+/// One-line notice when a fallback model answered, e.g. `gpt-5 failed
+/// (model_not_found); answered by gpt-5-mini`.
+pub fn fallback_note(response: &Value) -> Option<String> {
+    let fallback = response.get("memini_fallback")?;
+    let requested = fallback.get("requested").and_then(Value::as_str)?;
+    let model = fallback.get("model").and_then(Value::as_str)?;
+    let failures: Vec<&str> = fallback
+        .get("failures")
+        .and_then(Value::as_array)
+        .map(|items| items.iter().filter_map(Value::as_str).collect())
+        .unwrap_or_default();
+    Some(format!(
+        "↪ {requested} unavailable ({}); answered by {model}",
+        failures.join("; ")
+    ))
+}
+
 /// Pull the top-level `output` array from an OpenAI response.
 pub fn extract_output_items(response: &Value) -> Vec<Value> {
     response
@@ -142,6 +480,21 @@ pub fn extract_output_text(output_items: &[Value]) -> String {
     parts.join("\n")
 }
 
+/// Concatenate the reasoning summaries among the output items (sent when
+/// a reasoning effort is set).  Display only: never part of the answer.
+pub fn extract_reasoning_summary(output_items: &[Value]) -> String {
+    output_items
+        .iter()
+        .filter(|item| item.get("type").and_then(Value::as_str) == Some("reasoning"))
+        .filter_map(|item| item.get("summary").and_then(Value::as_array))
+        .flatten()
+        .filter_map(|part| part.get("text").and_then(Value::as_str))
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
 /// Collect all `function_call` items into structured [`ToolCall`] values.
 pub fn extract_tool_calls(output_items: &[Value]) -> Vec<ToolCall> {
     let mut calls = Vec::new();
@@ -172,11 +525,6 @@ pub fn extract_tool_calls(output_items: &[Value]) -> Vec<ToolCall> {
     calls
 }
 
-/// Returns `true` when the tool-call loop has hit the configured ceiling.
-pub fn tool_loop_limit_reached(tool_loops: usize) -> bool {
-    tool_loops >= MAX_TOOL_LOOPS
-}
-
 /// Parse model "thinking" / reasoning setting from user or environment input.
 ///
 /// Returns:
@@ -194,7 +542,26 @@ pub fn parse_reasoning_setting(raw: &str) -> Option<Option<String>> {
     Some(setting)
 }
 
-/// Pretty-print any serialisable value as JSON, with a safe fallback.
-pub fn format_json<T: Serialize>(value: T) -> String {
-    serde_json::to_string_pretty(&value).unwrap_or_else(|_| "<unrenderable>".to_string())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reasoning_summaries_stay_out_of_the_answer() {
+        let items = vec![
+            json!({"type": "reasoning", "summary": [
+                {"type": "summary_text", "text": "Check the failing job first."},
+                {"type": "summary_text", "text": "Then read ci.yml."},
+            ]}),
+            json!({"type": "message", "content": [
+                {"type": "output_text", "text": "CI fails on lint."},
+            ]}),
+        ];
+        assert_eq!(
+            extract_reasoning_summary(&items),
+            "Check the failing job first.\n\nThen read ci.yml."
+        );
+        assert_eq!(extract_output_text(&items), "CI fails on lint.");
+        assert_eq!(extract_reasoning_summary(&items[1..]), "");
+    }
 }
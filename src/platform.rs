@@ -0,0 +1,161 @@
+//! Platform differences for the workspace tools: which shell runs
+//! `workspace_run_command`, and how paths are normalized and compared.
+//!
+//! Unix runs commands with `sh -lc`.  Windows defaults to `cmd /C`;
+//! `[sandbox] shell = "powershell"` (or `"pwsh"`) switches to PowerShell.
+//! Windows paths may carry a drive (`C:`), UNC (`\\server\share`) or
+//! verbatim (`\\?\C:`) prefix and compare case-insensitively, so workspace
+//! containment checks go through [`is_within`] rather than
+//! [`Path::starts_with`].
+
+use std::path::{Component, Path, PathBuf};
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+/// Shell used for `workspace_run_command`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Shell {
+    Sh,
+    Cmd,
+    #[serde(alias = "pwsh")]
+    Powershell,
+}
+
+impl Shell {
+    /// `cmd` on Windows, `sh` elsewhere.
+    pub fn native() -> Self {
+        if cfg!(windows) { Shell::Cmd } else { Shell::Sh }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Shell::Sh => "sh",
+            Shell::Cmd => "cmd",
+            Shell::Powershell => "powershell",
+        }
+    }
+
+    /// Process running `command` through this shell.
+    pub fn command(self, command: &str) -> Command {
+        match self {
+            Shell::Sh => {
+                let mut cmd = Command::new("sh");
+                cmd.arg("-lc").arg(command);
+                cmd
+            }
+            Shell::Cmd => {
+                let mut cmd = Command::new("cmd");
+                cmd.arg("/C").arg(command);
+                cmd
+            }
+            Shell::Powershell => {
+                let program = if cfg!(windows) { "powershell" } else { "pwsh" };
+                let mut cmd = Command::new(program);
+                cmd.args(["-NoProfile", "-NonInteractive", "-Command"])
+                    .arg(command);
+                cmd
+            }
+        }
+    }
+}
+
+/// Lexically resolve `.` and `..`, keeping any drive or UNC prefix and the
+/// root.  A verbatim prefix (`\\?\C:\x`) is reduced to its plain form so it
+/// compares equal to paths from `current_dir`.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let path = strip_verbatim(path);
+    let mut normalized = PathBuf::new();
+    for comp in path.components() {
+        match comp {
+            Component::Prefix(_) | Component::RootDir => normalized.push(comp.as_os_str()),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                let _ = normalized.pop();
+            }
+            Component::Normal(seg) => normalized.push(seg),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        PathBuf::from(".")
+    } else {
+        normalized
+    }
+}
+
+/// `\\?\C:\x` → `C:\x` and `\\?\UNC\server\share\x` → `\\server\share\x`;
+/// other paths are returned unchanged.
+pub fn strip_verbatim(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(rest) = raw.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{rest}"));
+    }
+    match raw.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => PathBuf::from(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Whether `path` is `root` or inside it.  Case-insensitive on Windows.
+pub fn is_within(path: &Path, root: &Path) -> bool {
+    if !cfg!(windows) {
+        return path.starts_with(root);
+    }
+    let key = |path: &Path| -> Vec<String> {
+        strip_verbatim(path)
+            .components()
+            .map(|comp| comp.as_os_str().to_string_lossy().to_lowercase())
+            .collect()
+    };
+    let (path, root) = (key(path), key(root));
+    path.len() >= root.len() && path[..root.len()] == root[..]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn verbatim_prefixes_are_stripped() {
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\C:\work\repo")),
+            PathBuf::from(r"C:\work\repo")
+        );
+        assert_eq!(
+            strip_verbatim(Path::new(r"\\?\UNC\server\share\repo")),
+            PathBuf::from(r"\\server\share\repo")
+        );
+        assert_eq!(strip_verbatim(Path::new("repo")), PathBuf::from("repo"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn unix_paths_normalize_under_root() {
+        assert_eq!(
+            normalize_path(Path::new("/ws/src/../docs/./a.md")),
+            PathBuf::from("/ws/docs/a.md")
+        );
+        assert_eq!(normalize_path(Path::new("/../etc")), PathBuf::from("/etc"));
+        assert!(is_within(Path::new("/ws/docs"), Path::new("/ws")));
+        assert!(!is_within(Path::new("/wsx"), Path::new("/ws")));
+        assert_eq!(Shell::native(), Shell::Sh);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn windows_paths_keep_drive_and_unc_prefixes() {
+        assert_eq!(
+            normalize_path(Path::new(r"C:\ws\src\..\docs")),
+            PathBuf::from(r"C:\ws\docs")
+        );
+        assert_eq!(
+            normalize_path(Path::new(r"\\server\share\ws\..\x")),
+            PathBuf::from(r"\\server\share\x")
+        );
+        assert!(is_within(Path::new(r"c:\WS\docs"), Path::new(r"C:\ws")));
+        assert!(is_within(Path::new(r"\\?\C:\ws\a"), Path::new(r"C:\ws")));
+        assert!(!is_within(Path::new(r"D:\ws"), Path::new(r"C:\ws")));
+        assert_eq!(Shell::native(), Shell::Cmd);
+    }
+}
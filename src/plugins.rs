@@ -0,0 +1,318 @@
+//! Plugin tools from `$MEMINI_HOME/tools`.
+//!
+//! Each `*.json` file there is a manifest for one tool:
+//!
+//! ```json
+//! {
+//!   "name": "jira_search",
+//!   "description": "Search Jira issues by JQL.",
+//!   "command": "jira-search",
+//!   "args": ["--json"],
+//!   "timeout_secs": 30,
+//!   "parameters": {
+//!     "type": "object",
+//!     "properties": { "jql": { "type": "string" } },
+//!     "required": ["jql"]
+//!   }
+//! }
+//! ```
+//!
+//! `command` is an executable, resolved against the tools directory first
+//! and then `PATH`.  A `.wasm` command runs under a WASI runner
+//! (`wasmtime run`, or `MEMINI_WASM_RUNNER`) with the workspace mounted.
+//! The tool call's arguments are written to stdin as JSON; stdout is the
+//! result (JSON passes through, other text is wrapped as `{"output": ...}`).
+//! Plugins appear next to the workspace tools in [`crate::local_tools`],
+//! so tool selectors, policy rules and approvals apply to them as well.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tokio::time::{Duration, timeout};
+
+use crate::constants::{DEFAULT_PLUGIN_TIMEOUT_SECS, MAX_PLUGIN_OUTPUT_CHARS};
+use crate::openai::ToolCall;
+use crate::util::memini_home;
+
+/// Names a plugin may not take, besides the process and shell tools.
+const RESERVED_NAMES: &[&str] = &[
+    "workspace_list_files",
+    "workspace_read_file",
+    "workspace_file_info",
+    "workspace_write_file",
+    "workspace_move",
+    "workspace_copy",
+    "workspace_delete",
+    "workspace_mkdir",
+    "workspace_run_command",
+    crate::code_index::SEMANTIC_SEARCH_TOOL,
+    crate::sqlite_tool::SQLITE_QUERY_TOOL,
+    crate::http_tool::HTTP_REQUEST_TOOL,
+    "save_artifact",
+    "rice_memories",
+    "rice_state_get",
+    "spawn_agent",
+    "wait_for_agent",
+    "collect_results",
+];
+
+/// Whether `name` belongs to a built-in tool or looks like an MCP tool.
+fn is_reserved(name: &str) -> bool {
+    RESERVED_NAMES.contains(&name)
+        || crate::app::processes::PROCESS_TOOLS.contains(&name)
+        || crate::app::shell_sessions::SHELL_TOOLS.contains(&name)
+        || name.contains("__")
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct PluginTool {
+    pub name: String,
+    pub description: String,
+    pub command: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default = "default_parameters")]
+    pub parameters: Value,
+    /// Manifest file this tool came from.
+    #[serde(skip)]
+    pub manifest: PathBuf,
+}
+
+fn default_parameters() -> Value {
+    json!({"type": "object", "properties": {}})
+}
+
+/// Loaded plugins plus the manifests that were skipped and why.
+#[derive(Debug, Default)]
+pub struct PluginSet {
+    pub tools: Vec<PluginTool>,
+    pub errors: Vec<String>,
+}
+
+pub fn tools_dir() -> PathBuf {
+    memini_home().join("tools")
+}
+
+/// Read every manifest in [`tools_dir`].  Manifests are re-read on each
+/// call, so new plugins show up on the next request.
+pub fn load() -> PluginSet {
+    let dir = tools_dir();
+    let mut set = PluginSet::default();
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return set;
+    };
+    let mut paths: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("json"))
+        .collect();
+    paths.sort();
+    for path in paths {
+        match parse_manifest(&path) {
+            Ok(tool) if set.tools.iter().any(|known| known.name == tool.name) => set.errors.push(
+                format!("{}: duplicate tool '{}'", path.display(), tool.name),
+            ),
+            Ok(tool) => set.tools.push(tool),
+            Err(err) => set.errors.push(format!("{}: {err:#}", path.display())),
+        }
+    }
+    set
+}
+
+fn parse_manifest(path: &Path) -> Result<PluginTool> {
+    let raw = fs::read_to_string(path).with_context(|| format!("Read {}", path.display()))?;
+    let mut tool: PluginTool = serde_json::from_str(&raw).context("parse manifest")?;
+    validate(&tool)?;
+    tool.manifest = path.to_path_buf();
+    Ok(tool)
+}
+
+fn validate(tool: &PluginTool) -> Result<()> {
+    let valid_name = !tool.name.is_empty()
+        && tool.name.len() <= 64
+        && tool
+            .name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_' || ch == '-');
+    if !valid_name {
+        bail!("name must be 1-64 letters, digits, '_' or '-'");
+    }
+    if is_reserved(&tool.name) {
+        bail!("name '{}' is reserved", tool.name);
+    }
+    if tool.command.trim().is_empty() {
+        bail!("command is required");
+    }
+    if tool.parameters.get("type").and_then(Value::as_str) != Some("object") {
+        bail!("parameters must be a JSON schema with \"type\": \"object\"");
+    }
+    Ok(())
+}
+
+impl PluginTool {
+    /// OpenAI function definition.
+    pub fn tool_def(&self) -> Value {
+        json!({
+            "type": "function",
+            "name": self.name,
+            "description": self.description,
+            "parameters": self.parameters,
+        })
+    }
+
+    /// Program and arguments to spawn; `.wasm` modules go through the
+    /// WASI runner.
+    fn invocation(&self, workspace_root: &Path) -> (String, Vec<String>) {
+        let dir = self
+            .manifest
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(tools_dir);
+        let local = dir.join(&self.command);
+        let program = if local.is_file() {
+            local.display().to_string()
+        } else {
+            self.command.clone()
+        };
+        if !program.ends_with(".wasm") {
+            return (program, self.args.clone());
+        }
+        let runner = crate::util::env_first(&["MEMINI_WASM_RUNNER"])
+            .unwrap_or_else(|| "wasmtime".to_string());
+        let mut args = vec![
+            "run".to_string(),
+            "--dir".to_string(),
+            workspace_root.display().to_string(),
+            program,
+        ];
+        args.extend(self.args.iter().cloned());
+        (runner, args)
+    }
+
+    async fn run(&self, arguments: &Value) -> Result<Value> {
+        let workspace_root = crate::local_tools::workspace_root();
+        let (program, args) = self.invocation(&workspace_root);
+        let mut child = Command::new(&program)
+            .args(&args)
+            .current_dir(&workspace_root)
+            .env("MEMINI_WORKSPACE_ROOT", &workspace_root)
+            .env("MEMINI_TOOL_NAME", &self.name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("start {program}"))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            let input = serde_json::to_vec(arguments).context("serialize arguments")?;
+            stdin.write_all(&input).await.context("write stdin")?;
+        }
+        let secs = self.timeout_secs.unwrap_or(DEFAULT_PLUGIN_TIMEOUT_SECS);
+        let output = timeout(Duration::from_secs(secs), child.wait_with_output())
+            .await
+            .map_err(|_| anyhow!("plugin timed out after {secs}s"))?
+            .context("wait for plugin")?;
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout: String = stdout.chars().take(MAX_PLUGIN_OUTPUT_CHARS).collect();
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            bail!(
+                "plugin exited with {}: {}",
+                output.status,
+                stderr.trim().chars().take(2_000).collect::<String>()
+            );
+        }
+        Ok(parse_output(&stdout))
+    }
+}
+
+/// JSON output passes through; anything else becomes `{"output": text}`.
+fn parse_output(stdout: &str) -> Value {
+    match serde_json::from_str::<Value>(stdout.trim()) {
+        Ok(value) if value.is_object() || value.is_array() => value,
+        _ => json!({ "output": stdout.trim_end() }),
+    }
+}
+
+/// Definitions for every loaded plugin.
+pub fn tool_defs() -> Vec<Value> {
+    load().tools.iter().map(PluginTool::tool_def).collect()
+}
+
+/// Run `call` if it names a plugin.
+pub async fn handle_tool_call(call: &ToolCall) -> Option<String> {
+    let tool = load()
+        .tools
+        .into_iter()
+        .find(|tool| tool.name == call.name)?;
+    let payload = match tool.run(&call.arguments).await {
+        Ok(value) => value,
+        Err(err) => json!({ "error": format!("{err:#}") }),
+    };
+    Some(
+        serde_json::to_string(&payload)
+            .unwrap_or_else(|_| r#"{"error":"serialize failed"}"#.to_string()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(raw: &str) -> Result<PluginTool> {
+        let tool: PluginTool = serde_json::from_str(raw)?;
+        validate(&tool)?;
+        Ok(tool)
+    }
+
+    #[test]
+    fn manifests_are_validated() {
+        let tool =
+            manifest(r#"{"name": "jira_search", "description": "Search", "command": "jira"}"#)
+                .expect("valid");
+        assert_eq!(tool.tool_def()["parameters"]["type"], "object");
+        assert!(
+            manifest(r#"{"name": "workspace_read_file", "description": "x", "command": "y"}"#)
+                .is_err()
+        );
+        assert!(
+            manifest(r#"{"name": "process_spawn", "description": "x", "command": "y"}"#).is_err()
+        );
+        assert!(manifest(r#"{"name": "shell_exec", "description": "x", "command": "y"}"#).is_err());
+        assert!(manifest(r#"{"name": "bad name", "description": "x", "command": "y"}"#).is_err());
+        assert!(
+            manifest(r#"{"name": "t", "description": "x", "command": "y", "parameters": {"type": "string"}}"#)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn wasm_modules_run_through_the_runner() {
+        let mut tool =
+            manifest(r#"{"name": "wc", "description": "x", "command": "wc.wasm", "args": ["-l"]}"#)
+                .expect("valid");
+        tool.manifest = PathBuf::from("/plugins/wc.json");
+        let (program, args) = tool.invocation(Path::new("/ws"));
+        assert_eq!(program, "wasmtime");
+        assert_eq!(args, vec!["run", "--dir", "/ws", "wc.wasm", "-l"]);
+
+        assert_eq!(parse_output("{\"n\": 3}"), json!({"n": 3}));
+        assert_eq!(parse_output("3 lines\n"), json!({"output": "3 lines"}));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn arguments_arrive_on_stdin() {
+        let tool =
+            manifest(r#"{"name": "echo", "description": "x", "command": "cat"}"#).expect("valid");
+        let output = tool.run(&json!({"q": "hi"})).await.expect("run");
+        assert_eq!(output, json!({"q": "hi"}));
+    }
+}
@@ -4,12 +4,18 @@
 //! 1. `$MEMINI_PROMPTS_DIR/*.md` (if set)
 //! 2. `$MEMINI_HOME/prompts/*.md` (defaults to `~/Memini/prompts`)
 //! 3. Bundled repository defaults in `./prompts/*.md`
+//!
+//! Any other `*.md` file in `$MEMINI_HOME/prompts` is a `/prompt` template.
+//!
+//! `system.md` replaces the whole main-chat system prompt; a
+//! `<agent>/system.md` subdirectory does the same for one persona.  Both
+//! may use `{{persona}}` and `{{now}}`.
 
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
-use directories::BaseDirs;
+use crate::util::memini_home;
 
 const DEFAULT_MEMINI_PERSONA_MD: &str = include_str!("../prompts/default_memini_persona.md");
 const EXECUTION_STYLE_MD: &str = include_str!("../prompts/execution_style.md");
@@ -19,17 +25,30 @@ const DAEMON_BRIEFING_PERSONA_MD: &str = include_str!("../prompts/daemon_briefin
 const DAEMON_BRIEFING_PROMPT_MD: &str = include_str!("../prompts/daemon_briefing_prompt.md");
 const DAEMON_DIGEST_PERSONA_MD: &str = include_str!("../prompts/daemon_digest_persona.md");
 const DAEMON_DIGEST_PROMPT_MD: &str = include_str!("../prompts/daemon_digest_prompt.md");
-
-fn memini_home() -> PathBuf {
-    if let Ok(value) = env::var("MEMINI_HOME") {
-        if !value.trim().is_empty() {
-            return PathBuf::from(value);
-        }
-    }
-    if let Some(base_dirs) = BaseDirs::new() {
-        return base_dirs.home_dir().join("Memini");
-    }
-    PathBuf::from("Memini")
+const DAEMON_FACTS_PERSONA_MD: &str = include_str!("../prompts/daemon_facts_persona.md");
+const DAEMON_FACTS_PROMPT_MD: &str = include_str!("../prompts/daemon_facts_prompt.md");
+
+/// Override file names; everything else in the prompts dir is a template.
+const OVERRIDE_FILES: &[&str] = &[
+    "default_memini_persona.md",
+    "execution_style.md",
+    "orchestration_rules.md",
+    "needs_input_rule.md",
+    "daemon_briefing_persona.md",
+    "daemon_briefing_prompt.md",
+    "daemon_digest_persona.md",
+    "daemon_digest_prompt.md",
+    "daemon_facts_persona.md",
+    "daemon_facts_prompt.md",
+    SYSTEM_OVERRIDE_FILE,
+];
+
+/// Full main-chat system prompt override (global or per agent).
+const SYSTEM_OVERRIDE_FILE: &str = "system.md";
+
+/// Whether `file_name` overrides a bundled prompt.
+pub fn is_override_file(file_name: &str) -> bool {
+    OVERRIDE_FILES.contains(&file_name)
 }
 
 fn prompt_override_dirs() -> Vec<PathBuf> {
@@ -44,19 +63,29 @@ fn prompt_override_dirs() -> Vec<PathBuf> {
 }
 
 fn load_prompt(file_name: &str, bundled: &str) -> String {
-    for dir in prompt_override_dirs() {
-        let path = dir.join(file_name);
-        if !path.is_file() {
-            continue;
-        }
-        if let Ok(raw) = fs::read_to_string(&path) {
-            let trimmed = raw.trim();
-            if !trimmed.is_empty() {
-                return trimmed.to_string();
-            }
-        }
-    }
-    bundled.trim().to_string()
+    read_override(file_name).unwrap_or_else(|| bundled.trim().to_string())
+}
+
+/// First non-empty `relative` file across the override dirs.
+fn read_override(relative: &str) -> Option<String> {
+    prompt_override_dirs().into_iter().find_map(|dir| {
+        let raw = fs::read_to_string(dir.join(relative)).ok()?;
+        let trimmed = raw.trim();
+        (!trimmed.is_empty()).then(|| trimmed.to_string())
+    })
+}
+
+/// The `system.md` override for `agent_name`, falling back to the global
+/// one, with `{{persona}}` / `{{now}}` filled in.
+pub fn system_override(agent_name: &str, persona: &str, now: &str) -> Option<String> {
+    let agent_file = format!("{agent_name}/{SYSTEM_OVERRIDE_FILE}");
+    let agent_specific =
+        !agent_name.is_empty() && !agent_name.contains(['/', '\\']) && !agent_name.starts_with('.');
+    let raw = agent_specific
+        .then(|| read_override(&agent_file))
+        .flatten()
+        .or_else(|| read_override(SYSTEM_OVERRIDE_FILE))?;
+    Some(raw.replace("{{persona}}", persona).replace("{{now}}", now))
 }
 
 pub fn default_memini_persona() -> String {
@@ -79,6 +108,14 @@ pub fn daemon_digest_prompt() -> String {
     load_prompt("daemon_digest_prompt.md", DAEMON_DIGEST_PROMPT_MD)
 }
 
+pub fn daemon_facts_persona() -> String {
+    load_prompt("daemon_facts_persona.md", DAEMON_FACTS_PERSONA_MD)
+}
+
+pub fn daemon_facts_prompt() -> String {
+    load_prompt("daemon_facts_prompt.md", DAEMON_FACTS_PROMPT_MD)
+}
+
 pub fn custom_persona(name: &str, description: &str) -> String {
     format!(
         "You are {name}, a specialized execution-first AI assistant. \
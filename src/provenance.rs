@@ -0,0 +1,148 @@
+//! How a trace's outcome was produced: tools called, tokens spent and
+//! wall time.
+//!
+//! Chat turns, daemon runs and agent windows fill a [`Provenance`] as their
+//! tool loop runs and pass it to [`RiceStore::commit_model_trace`], which
+//! stores it in the trace's [`TraceMeta`] next to the model.  `/memory
+//! list` and recalled-memory context show it via [`summary`].
+//!
+//! [`RiceStore::commit_model_trace`]: crate::rice::RiceStore::commit_model_trace
+
+use std::time::Instant;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::importance::TraceMeta;
+
+/// Model tokens spent on one outcome, summed over every request.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TokenUsage {
+    pub input: u64,
+    pub output: u64,
+}
+
+/// A critic model's review of an outcome against the request that led to it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Critique {
+    pub model: String,
+    /// `false` when the critic flagged unsupported claims or unfinished steps.
+    pub passed: bool,
+    pub notes: String,
+}
+
+/// Collects tool calls and token usage while a run is in progress.
+#[derive(Clone, Debug)]
+pub struct Provenance {
+    started: Instant,
+    /// Distinct tools in first-call order; MCP tools as `server/tool`.
+    tools: Vec<String>,
+    tokens: TokenUsage,
+    critique: Option<Critique>,
+}
+
+impl Provenance {
+    pub fn start() -> Self {
+        Provenance {
+            started: Instant::now(),
+            tools: Vec::new(),
+            tokens: TokenUsage::default(),
+            critique: None,
+        }
+    }
+
+    /// Add the `usage` of a Responses API reply.
+    pub fn record_response(&mut self, response: &Value) {
+        let Some(usage) = response.get("usage") else {
+            return;
+        };
+        let count = |key: &str| usage.get(key).and_then(Value::as_u64).unwrap_or(0);
+        self.tokens.input += count("input_tokens");
+        self.tokens.output += count("output_tokens");
+    }
+
+    /// Note a tool call by its (possibly MCP-namespaced) function name.
+    pub fn record_tool(&mut self, name: &str) {
+        let label = match crate::mcp::split_namespaced_tool_name(name) {
+            Some((server, tool)) => format!("{server}/{tool}"),
+            None => name.to_string(),
+        };
+        if !self.tools.contains(&label) {
+            self.tools.push(label);
+        }
+    }
+
+    /// Attach the critic's review of the outcome.
+    pub fn record_critique(&mut self, critique: Critique) {
+        self.critique = Some(critique);
+    }
+
+    /// Copy what was collected into `meta`, timing the run up to now.
+    pub fn apply(&self, mut meta: TraceMeta) -> TraceMeta {
+        meta.tools = self.tools.clone();
+        meta.tokens = (self.tokens != TokenUsage::default()).then_some(self.tokens);
+        meta.duration_ms = Some(self.started.elapsed().as_millis() as u64);
+        meta.critique = self.critique.clone();
+        meta
+    }
+}
+
+/// `gpt-4o · github/search_issues, workspace_read_file · 1834 tokens · 4.2s`,
+/// or `None` for traces committed without provenance.
+pub fn summary(meta: &TraceMeta) -> Option<String> {
+    let mut parts = Vec::new();
+    if let Some(model) = &meta.model {
+        parts.push(model.clone());
+    }
+    if !meta.tools.is_empty() {
+        parts.push(meta.tools.join(", "));
+    }
+    if let Some(tokens) = meta.tokens {
+        parts.push(format!("{} tokens", tokens.input + tokens.output));
+    }
+    if let Some(ms) = meta.duration_ms {
+        parts.push(format!("{:.1}s", ms as f64 / 1000.0));
+    }
+    if let Some(critique) = &meta.critique {
+        parts.push(if critique.passed {
+            "critic: ok".to_string()
+        } else {
+            "critic: flagged".to_string()
+        });
+    }
+    (!parts.is_empty()).then(|| parts.join(" · "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn collects_tools_and_tokens_into_trace_meta() {
+        let mut provenance = Provenance::start();
+        provenance.record_response(&json!({"usage": {"input_tokens": 1200, "output_tokens": 300}}));
+        provenance.record_response(&json!({"usage": {"input_tokens": 300, "output_tokens": 34}}));
+        provenance.record_tool("workspace_read_file");
+        provenance.record_tool(&crate::mcp::namespaced_tool_name("github", "search_issues"));
+        provenance.record_tool("workspace_read_file");
+
+        let meta = provenance.apply(TraceMeta::score("q", "a", "chat").with_model("gpt-4o"));
+        assert_eq!(
+            meta.tools,
+            vec!["workspace_read_file", "github/search_issues"]
+        );
+        assert_eq!(
+            meta.tokens,
+            Some(TokenUsage {
+                input: 1500,
+                output: 334
+            })
+        );
+        let parsed = TraceMeta::parse(&meta.encode()).expect("round trip");
+        let line = summary(&parsed).expect("summary");
+        assert!(
+            line.starts_with("gpt-4o · workspace_read_file, github/search_issues · 1834 tokens · ")
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! Where model requests go: OpenAI (or a compatible gateway) or Azure
+//! OpenAI.
+//!
+//! OpenAI takes `{base_url}/{path}` with a bearer key.  An Azure OpenAI
+//! resource differs in three ways, all handled here so callers only name
+//! the path and model:
+//!
+//! - the key goes in an `api-key` header;
+//! - every URL carries `?api-version=`;
+//! - embeddings and audio go to `{endpoint}/openai/deployments/{name}/…`,
+//!   while the Responses API is `{endpoint}/openai/responses` with the
+//!   deployment name as `model`.
+//!
+//! Azure is picked by `AZURE_OPENAI_ENDPOINT` (with `AZURE_OPENAI_API_VERSION`
+//! and `AZURE_OPENAI_DEPLOYMENTS="gpt-4o=prod-4o,…"`) or by `/provider
+//! azure`, which is stored in Rice.  Models without a deployment entry use
+//! their own name as the deployment.
+
+use std::collections::BTreeMap;
+use std::sync::{OnceLock, RwLock};
+
+use anyhow::{Result, bail};
+use reqwest::RequestBuilder;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::constants::{DEFAULT_AZURE_API_VERSION, DEFAULT_OPENAI_BASE_URL};
+use crate::util::env_first;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Provider {
+    OpenAi { base_url: String },
+    Azure(AzureConfig),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AzureConfig {
+    /// Resource endpoint, e.g. `https://my-resource.openai.azure.com`.
+    pub endpoint: String,
+    pub api_version: String,
+    /// Model name → deployment name.
+    #[serde(default)]
+    pub deployments: BTreeMap<String, String>,
+}
+
+impl AzureConfig {
+    pub fn deployment<'a>(&'a self, model: &'a str) -> &'a str {
+        self.deployments
+            .get(model)
+            .map(String::as_str)
+            .unwrap_or(model)
+    }
+}
+
+impl Provider {
+    pub fn from_env() -> Self {
+        if let Some(endpoint) = env_first(&["AZURE_OPENAI_ENDPOINT"]) {
+            let deployments = env_first(&["AZURE_OPENAI_DEPLOYMENTS"])
+                .map(|raw| parse_deployments(raw.split(',')).unwrap_or_default())
+                .unwrap_or_default();
+            return Provider::Azure(AzureConfig {
+                endpoint: endpoint.trim_end_matches('/').to_string(),
+                api_version: env_first(&["AZURE_OPENAI_API_VERSION", "OPENAI_API_VERSION"])
+                    .unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string()),
+                deployments,
+            });
+        }
+        Provider::OpenAi {
+            base_url: env_first(&["OPENAI_BASE_URL", "OPENAI_API_BASE"])
+                .unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+        }
+    }
+
+    /// One line for `/provider` and `/status`.
+    pub fn label(&self) -> String {
+        match self {
+            Provider::OpenAi { base_url } => format!("OpenAI ({base_url})"),
+            Provider::Azure(azure) => format!(
+                "Azure OpenAI ({}, api-version {})",
+                azure.endpoint, azure.api_version
+            ),
+        }
+    }
+
+    /// URL for an API `path` (`responses`, `embeddings`,
+    /// `audio/transcriptions`) requested with `model`.
+    pub fn url(&self, path: &str, model: &str) -> String {
+        let path = path.trim_start_matches('/');
+        match self {
+            Provider::OpenAi { base_url } => format!("{base_url}/{path}"),
+            Provider::Azure(azure) if path == "responses" => format!(
+                "{}/openai/responses?api-version={}",
+                azure.endpoint, azure.api_version
+            ),
+            Provider::Azure(azure) => format!(
+                "{}/openai/deployments/{}/{path}?api-version={}",
+                azure.endpoint,
+                azure.deployment(model),
+                azure.api_version
+            ),
+        }
+    }
+
+    /// Attach the API key the way the provider expects it.
+    pub fn authorize(&self, request: RequestBuilder, key: &str) -> RequestBuilder {
+        match self {
+            Provider::OpenAi { .. } => request.bearer_auth(key),
+            Provider::Azure(_) => request.header("api-key", key),
+        }
+    }
+
+    /// Swap the model for its deployment name in a JSON body.
+    pub fn prepare_body(&self, body: &mut Value) {
+        let Provider::Azure(azure) = self else {
+            return;
+        };
+        if let Some(model) = body.get("model").and_then(Value::as_str) {
+            let deployment = azure.deployment(model).to_string();
+            body["model"] = Value::String(deployment);
+        }
+    }
+}
+
+/// `model=deployment` pairs.
+pub fn parse_deployments<'a>(
+    pairs: impl IntoIterator<Item = &'a str>,
+) -> Result<BTreeMap<String, String>> {
+    let mut deployments = BTreeMap::new();
+    for pair in pairs {
+        let pair = pair.trim();
+        if pair.is_empty() {
+            continue;
+        }
+        let Some((model, deployment)) = pair.split_once('=') else {
+            bail!("expected model=deployment, got '{pair}'");
+        };
+        deployments.insert(model.trim().to_string(), deployment.trim().to_string());
+    }
+    Ok(deployments)
+}
+
+fn provider_cell() -> &'static RwLock<Provider> {
+    static PROVIDER: OnceLock<RwLock<Provider>> = OnceLock::new();
+    PROVIDER.get_or_init(|| RwLock::new(Provider::from_env()))
+}
+
+/// The provider requests currently go to.
+pub fn current() -> Provider {
+    provider_cell()
+        .read()
+        .map(|provider| provider.clone())
+        .unwrap_or_else(|_| Provider::from_env())
+}
+
+/// Send every later request to `provider`.
+pub fn set(provider: Provider) {
+    if let Ok(mut guard) = provider_cell().write() {
+        *guard = provider;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn azure_uses_deployment_urls_and_names() {
+        let azure = Provider::Azure(AzureConfig {
+            endpoint: "https://acme.openai.azure.com".to_string(),
+            api_version: "2025-04-01-preview".to_string(),
+            deployments: parse_deployments(["gpt-4o=prod-4o", "text-embedding-3-small=embed"])
+                .expect("deployments"),
+        });
+        assert_eq!(
+            azure.url("responses", "gpt-4o"),
+            "https://acme.openai.azure.com/openai/responses?api-version=2025-04-01-preview"
+        );
+        assert_eq!(
+            azure.url("embeddings", "text-embedding-3-small"),
+            "https://acme.openai.azure.com/openai/deployments/embed/embeddings?api-version=2025-04-01-preview"
+        );
+        assert_eq!(
+            azure.url("audio/transcriptions", "whisper-1"),
+            "https://acme.openai.azure.com/openai/deployments/whisper-1/audio/transcriptions?api-version=2025-04-01-preview"
+        );
+        let mut body = json!({"model": "gpt-4o", "input": []});
+        azure.prepare_body(&mut body);
+        assert_eq!(body["model"], "prod-4o");
+
+        let openai = Provider::OpenAi {
+            base_url: "https://api.openai.com/v1".to_string(),
+        };
+        assert_eq!(
+            openai.url("/responses", "gpt-4o"),
+            "https://api.openai.com/v1/responses"
+        );
+    }
+}
@@ -1,27 +1,62 @@
 //! Rice SDK integration — state variables, memory traces, and focus.
 
+use std::collections::HashSet;
 use std::env;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use anyhow::{Context, Result, anyhow};
+use chrono::Local;
 use rice::Client;
 use rice::rice_core::config::{RiceConfig, StateConfig, StorageConfig};
 use rice::rice_state::proto::Trace;
 use serde_json::Value;
+use tokio::sync::Mutex;
 
+use crate::backend::{BoxFuture, MemoryBackend};
 use crate::constants::{
     ACTIVE_AGENT_VAR, APP_NAME, CONVERSATION_THREAD_VAR, CUSTOM_AGENTS_VAR, DEFAULT_RUN_ID,
-    SHARED_WORKSPACE_VAR,
+    PRUNED_TRACES_VAR, RECALL_OVERFETCH, SEMANTIC_FACTS_VAR, SESSIONS_VAR, SHARED_WORKSPACE_VAR,
 };
+use crate::facts::Fact;
+use crate::importance::{self, TraceMeta};
+use crate::provenance::{self, Provenance};
+use crate::redact::redact;
 use crate::util::{env_first, normalize_url};
 
+/// Session-wide `/incognito` switch: while on, traces, focus and the
+/// conversation thread are not written.
+static INCOGNITO: AtomicBool = AtomicBool::new(false);
+
+pub fn set_incognito(on: bool) {
+    INCOGNITO.store(on, Ordering::SeqCst);
+}
+
+pub fn incognito() -> bool {
+    INCOGNITO.load(Ordering::SeqCst)
+}
+
 /// Persistent store backed by the Rice State gRPC service.
 pub struct RiceStore {
-    client: Option<Client>,
+    backend: Option<Arc<dyn MemoryBackend>>,
     pub status: RiceStatus,
     run_id: String,
     /// When set, all memory operations target this shared workspace
     /// instead of the personal `run_id`.
     pub shared_run_id: Option<String>,
+    /// Identity attached to committed traces and variables so teammates
+    /// in a shared workspace can tell who did what.
+    pub user: String,
+    /// Child scope that traces and focus go to (spawned agent windows).
+    scope: Option<MemoryScope>,
+}
+
+/// A child memory scope, `<parent run id>.<child>`.
+#[derive(Clone, Debug)]
+pub struct MemoryScope {
+    pub run_id: String,
+    /// Recall the parent scope's traces as well as the child's.
+    pub inherit: bool,
 }
 
 /// Connection state of the Rice backend.
@@ -43,10 +78,12 @@ impl RiceStore {
     pub async fn connect() -> Self {
         let Some(config) = rice_config_from_env() else {
             return RiceStore {
-                client: None,
+                backend: None,
                 status: RiceStatus::Disabled("Rice env not configured".to_string()),
                 run_id: rice_run_id(),
                 shared_run_id: None,
+                user: rice_user(),
+                scope: None,
             };
         };
 
@@ -58,21 +95,47 @@ impl RiceStore {
                     RiceStatus::Disabled("Rice state module not enabled".to_string())
                 };
                 RiceStore {
-                    client: Some(client),
+                    backend: Some(Arc::new(RiceBackend(Mutex::new(client)))),
                     status,
                     run_id: rice_run_id(),
                     shared_run_id: None,
+                    user: rice_user(),
+                    scope: None,
                 }
             }
             Err(err) => RiceStore {
-                client: None,
+                backend: None,
                 status: RiceStatus::Disabled(format!("Client init failed: {err}")),
                 run_id: rice_run_id(),
                 shared_run_id: None,
+                user: rice_user(),
+                scope: None,
             },
         }
     }
 
+    /// A store on `backend` (the shared memory from [`crate::backend::Backends`]),
+    /// or a fresh Rice connection when there is none.
+    pub async fn open(backend: Option<Arc<dyn MemoryBackend>>) -> Self {
+        match backend {
+            Some(backend) => RiceStore {
+                backend: Some(backend),
+                status: RiceStatus::Connected,
+                run_id: rice_run_id(),
+                shared_run_id: None,
+                user: rice_user(),
+                scope: None,
+            },
+            None => Self::connect().await,
+        }
+    }
+
+    fn backend(&self) -> Result<&dyn MemoryBackend> {
+        self.backend
+            .as_deref()
+            .ok_or_else(|| anyhow!("Rice not connected"))
+    }
+
     pub fn status_label(&self) -> String {
         match &self.status {
             RiceStatus::Connected => "connected".to_string(),
@@ -101,25 +164,40 @@ impl RiceStore {
         self.shared_run_id = None;
     }
 
+    /// Commit traces and focus into `<active run id>.<child>` from now on.
+    /// Variables stay in the active run so coordination still works.
+    pub fn enter_scope(&mut self, child: &str, inherit: bool) {
+        self.scope = Some(MemoryScope {
+            run_id: format!("{}.{child}", self.active_run_id()),
+            inherit,
+        });
+    }
+
+    pub fn scope(&self) -> Option<&MemoryScope> {
+        self.scope.as_ref()
+    }
+
+    /// The run-id traces and focus go to: the child scope if one was
+    /// entered, else [`Self::active_run_id`].
+    pub fn memory_run_id(&self) -> String {
+        match &self.scope {
+            Some(scope) => scope.run_id.clone(),
+            None => self.active_run_id(),
+        }
+    }
+
     /// Persist the current shared workspace name into Rice (personal
     /// scope) so it can be restored on next launch.
     pub async fn save_shared_workspace(&mut self) -> Result<()> {
+        // Always the personal scope (the real personal run_id).
+        let run_id = self.run_id.clone();
         match &self.shared_run_id {
             Some(name) => {
-                // Save to personal scope (use the real personal run_id).
-                let client = self
-                    .client
-                    .as_mut()
-                    .ok_or_else(|| anyhow!("Rice not connected"))?;
-                let state = client
-                    .state
-                    .as_mut()
-                    .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
                 let value_json =
                     serde_json::to_string(&Value::String(name.clone())).context("serialize")?;
-                state
+                self.backend()?
                     .set_variable(
-                        self.run_id.clone(),
+                        run_id,
                         SHARED_WORKSPACE_VAR.to_string(),
                         value_json,
                         "share".to_string(),
@@ -128,16 +206,8 @@ impl RiceStore {
                     .context("save shared workspace")?;
             }
             None => {
-                let client = self
-                    .client
-                    .as_mut()
-                    .ok_or_else(|| anyhow!("Rice not connected"))?;
-                let state = client
-                    .state
-                    .as_mut()
-                    .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-                state
-                    .delete_variable(self.run_id.clone(), SHARED_WORKSPACE_VAR.to_string())
+                self.backend()?
+                    .delete_variable(run_id, SHARED_WORKSPACE_VAR.to_string())
                     .await
                     .context("clear shared workspace")?;
             }
@@ -147,121 +217,124 @@ impl RiceStore {
 
     /// Load a previously-saved shared workspace from Rice.
     pub async fn load_shared_workspace(&mut self) -> Result<Option<String>> {
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-        let variable = state
+        let value_json = self
+            .backend()?
             .get_variable(self.run_id.clone(), SHARED_WORKSPACE_VAR.to_string())
             .await
             .context("load shared workspace")?;
-        if variable.value_json.trim().is_empty() {
+        if value_json.trim().is_empty() {
             return Ok(None);
         }
-        match serde_json::from_str::<Value>(&variable.value_json) {
+        match serde_json::from_str::<Value>(&value_json) {
             Ok(Value::String(name)) => Ok(Some(name)),
             _ => Ok(None),
         }
     }
 
+    #[tracing::instrument(name = "rice.set_variable", skip_all, fields(var = %name))]
     pub async fn set_variable(&mut self, name: &str, value: Value, source: &str) -> Result<()> {
         let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
         let value_json = serde_json::to_string(&value).context("serialize value")?;
-        state
-            .set_variable(rid, name.to_string(), value_json, source.to_string())
+        let source = attributed(source, &self.user);
+        self.backend()?
+            .set_variable(rid, name.to_string(), value_json, source)
             .await
-            .context("set variable")?;
-        Ok(())
     }
 
+    #[tracing::instrument(name = "rice.get_variable", skip_all, fields(var = %name))]
     pub async fn get_variable(&mut self, name: &str) -> Result<Option<Value>> {
         let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-        let variable = state
-            .get_variable(rid, name.to_string())
-            .await
-            .context("get variable")?;
-        if variable.value_json.trim().is_empty() {
+        let value_json = self.backend()?.get_variable(rid, name.to_string()).await?;
+        if value_json.trim().is_empty() {
             return Ok(None);
         }
-        let value =
-            serde_json::from_str::<Value>(&variable.value_json).context("parse value_json")?;
+        let value = serde_json::from_str::<Value>(&value_json).context("parse value_json")?;
         Ok(Some(value))
     }
 
+    #[tracing::instrument(name = "rice.delete_variable", skip_all, fields(var = %name))]
     pub async fn delete_variable(&mut self, name: &str) -> Result<()> {
         let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-        state
-            .delete_variable(rid, name.to_string())
-            .await
-            .context("delete variable")?;
-        Ok(())
+        self.backend()?.delete_variable(rid, name.to_string()).await
     }
 
+    #[tracing::instrument(name = "rice.focus", skip_all)]
     pub async fn focus(&mut self, content: &str) -> Result<()> {
-        let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-        state
-            .focus(content.to_string(), rid)
-            .await
-            .context("focus")?;
-        Ok(())
+        if incognito() {
+            return Ok(());
+        }
+        let rid = self.memory_run_id();
+        self.backend()?.focus(rid, content.to_string()).await
     }
 
+    #[tracing::instrument(name = "rice.reminisce", skip_all, fields(limit = limit))]
     pub async fn reminisce(
         &mut self,
         embedding: Vec<f32>,
         limit: u64,
         query_text: &str,
     ) -> Result<Vec<Trace>> {
-        let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-        let response = state
-            .reminisce(embedding, limit, query_text.to_string(), rid)
-            .await
-            .context("reminisce")?;
-        Ok(response.traces)
+        let rid = self.memory_run_id();
+        // Over-fetch so re-ranking by importance and recency has room to
+        // promote lower-relevance but more valuable traces.
+        let fetch = if query_text.trim().is_empty() {
+            limit
+        } else {
+            limit.saturating_mul(RECALL_OVERFETCH)
+        };
+        let mut traces = self
+            .backend()?
+            .reminisce(rid, embedding.clone(), fetch, query_text.to_string())
+            .await?;
+        if self.scope.as_ref().is_some_and(|scope| scope.inherit) {
+            let parent = self
+                .backend()?
+                .reminisce(
+                    self.active_run_id(),
+                    embedding,
+                    fetch,
+                    query_text.to_string(),
+                )
+                .await?;
+            traces.extend(parent);
+        }
+
+        // A missing or unreadable prune list must not break recall.
+        let mut hidden = self.load_pruned_traces().await.unwrap_or_default();
+        let traces: Vec<Trace> = traces
+            .into_iter()
+            .filter(|trace| hidden.insert(importance::fingerprint(trace)))
+            .collect();
+        if query_text.trim().is_empty() {
+            return Ok(traces.into_iter().take(limit as usize).collect());
+        }
+        Ok(importance::rank(
+            traces,
+            limit as usize,
+            Local::now().fixed_offset(),
+        ))
+    }
+
+    /// Fingerprints of traces `/memory compact` removed from recall.
+    pub async fn load_pruned_traces(&mut self) -> Result<HashSet<String>> {
+        Ok(match self.get_variable(PRUNED_TRACES_VAR).await? {
+            Some(Value::Array(items)) => items
+                .into_iter()
+                .filter_map(|item| item.as_str().map(str::to_string))
+                .collect(),
+            _ => HashSet::new(),
+        })
+    }
+
+    /// Hide traces from recall.  Rice cannot delete traces, so they stay
+    /// stored but are filtered out of every `reminisce`.
+    pub async fn prune_traces(&mut self, fingerprints: &[String]) -> Result<()> {
+        let mut pruned: Vec<String> = self.load_pruned_traces().await?.into_iter().collect();
+        pruned.extend(fingerprints.iter().cloned());
+        pruned.sort();
+        pruned.dedup();
+        let value = Value::Array(pruned.into_iter().map(Value::String).collect());
+        self.set_variable(PRUNED_TRACES_VAR, value, "compact").await
     }
 
     pub async fn commit_trace(
@@ -272,26 +345,72 @@ impl RiceStore {
         embedding: Vec<f32>,
         agent_id: &str,
     ) -> Result<()> {
-        let rid = self.active_run_id();
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
+        if incognito() {
+            return Ok(());
+        }
+        let meta = TraceMeta::score(input, outcome, action);
+        self.commit_scored_trace(input, outcome, action, embedding, agent_id, &meta)
+            .await
+    }
+
+    /// [`Self::commit_trace`], noting the model that produced `outcome` and
+    /// the tools, tokens and time it took.
+    pub async fn commit_model_trace(
+        &mut self,
+        input: &str,
+        outcome: &str,
+        action: &str,
+        agent_id: &str,
+        model: &str,
+        provenance: &Provenance,
+    ) -> Result<()> {
+        if incognito() {
+            return Ok(());
+        }
+        let meta = provenance.apply(TraceMeta::score(input, outcome, action).with_model(model));
+        self.commit_scored_trace(input, outcome, action, Vec::new(), agent_id, &meta)
+            .await
+    }
+
+    /// Commit a trace with explicit importance metadata (used when merging
+    /// traces, which keep their original score and age).
+    #[tracing::instrument(name = "rice.commit_trace", skip_all)]
+    pub async fn commit_scored_trace(
+        &mut self,
+        input: &str,
+        outcome: &str,
+        action: &str,
+        embedding: Vec<f32>,
+        agent_id: &str,
+        meta: &TraceMeta,
+    ) -> Result<()> {
         let trace = Trace {
-            input: input.to_string(),
-            reasoning: String::new(),
-            action: action.to_string(),
-            outcome: outcome.to_string(),
-            agent_id: agent_id.to_string(),
+            input: redact(input),
+            reasoning: meta.encode(),
+            action: redact(action),
+            outcome: redact(outcome),
+            agent_id: attributed(agent_id, &self.user),
             embedding,
-            run_id: rid,
+            run_id: self.memory_run_id(),
         };
-        state.commit(trace).await.context("commit trace")?;
-        Ok(())
+        self.backend()?.commit(trace).await
+    }
+
+    /// Commit a trace into the parent of the current child scope (or the
+    /// active run when there is none), e.g. a sub-agent's final summary.
+    pub async fn promote_trace(
+        &mut self,
+        input: &str,
+        outcome: &str,
+        action: &str,
+        agent_id: &str,
+    ) -> Result<()> {
+        let scope = self.scope.take();
+        let committed = self
+            .commit_trace(input, outcome, action, Vec::new(), agent_id)
+            .await;
+        self.scope = scope;
+        committed
     }
 
     pub async fn subscribe_events_with<F>(
@@ -301,35 +420,19 @@ impl RiceStore {
         mut on_event: F,
     ) -> Result<()>
     where
-        F: FnMut(StateEvent),
+        F: FnMut(StateEvent) + Send,
     {
-        let client = self
-            .client
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice not connected"))?;
-        let state = client
-            .state
-            .as_mut()
-            .ok_or_else(|| anyhow!("Rice state module not enabled"))?;
-
-        let mut stream = state
-            .subscribe(run_id, event_types)
+        self.backend()?
+            .subscribe(run_id, event_types, &mut on_event)
             .await
-            .context("subscribe to state events")?;
-        while let Some(event) = stream.message().await.context("read state event")? {
-            on_event(StateEvent {
-                event_type: event.r#type,
-                run_id: event.run_id,
-                agent_id: event.agent_id,
-                payload: event.payload,
-            });
-        }
-        Ok(())
     }
 
     // ── Conversation thread ──────────────────────────────────────────
 
     pub async fn save_thread(&mut self, messages: &[Value]) -> Result<()> {
+        if incognito() {
+            return Ok(());
+        }
         self.set_variable(
             CONVERSATION_THREAD_VAR,
             Value::Array(messages.to_vec()),
@@ -349,6 +452,17 @@ impl RiceStore {
         self.delete_variable(CONVERSATION_THREAD_VAR).await
     }
 
+    // ── Named sessions ───────────────────────────────────────────────
+
+    pub async fn save_sessions(&mut self, sessions_json: Value) -> Result<()> {
+        self.set_variable(SESSIONS_VAR, sessions_json, "session")
+            .await
+    }
+
+    pub async fn load_sessions(&mut self) -> Result<Option<Value>> {
+        self.get_variable(SESSIONS_VAR).await
+    }
+
     // ── Agent persistence ────────────────────────────────────────────
 
     pub async fn save_custom_agents(&mut self, agents_json: Value) -> Result<()> {
@@ -371,6 +485,166 @@ impl RiceStore {
             _ => Ok(None),
         }
     }
+
+    // ── Semantic facts ───────────────────────────────────────────────
+
+    pub async fn load_facts(&mut self) -> Result<Vec<Fact>> {
+        match self.get_variable(SEMANTIC_FACTS_VAR).await? {
+            Some(value @ Value::Array(_)) => {
+                serde_json::from_value(value).context("parse semantic facts")
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    pub async fn save_facts(&mut self, facts: &[Fact]) -> Result<()> {
+        let value = serde_json::to_value(facts).context("serialize semantic facts")?;
+        self.set_variable(SEMANTIC_FACTS_VAR, value, "facts").await
+    }
+}
+
+// ── Rice State backend ───────────────────────────────────────────────
+
+/// The Rice State gRPC service (see [`MemoryBackend`]).
+pub struct RiceBackend(Mutex<Client>);
+
+fn state_disabled() -> anyhow::Error {
+    anyhow!("Rice state module not enabled")
+}
+
+impl MemoryBackend for RiceBackend {
+    fn set_variable<'a>(
+        &'a self,
+        run_id: String,
+        name: String,
+        value_json: String,
+        source: String,
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            state
+                .set_variable(run_id, name, value_json, source)
+                .await
+                .context("set variable")?;
+            Ok(())
+        })
+    }
+
+    fn get_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<String>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            let variable = state
+                .get_variable(run_id, name)
+                .await
+                .context("get variable")?;
+            Ok(variable.value_json)
+        })
+    }
+
+    fn delete_variable<'a>(&'a self, run_id: String, name: String) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            state
+                .delete_variable(run_id, name)
+                .await
+                .context("delete variable")?;
+            Ok(())
+        })
+    }
+
+    fn focus<'a>(&'a self, run_id: String, content: String) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            state.focus(content, run_id).await.context("focus")?;
+            Ok(())
+        })
+    }
+
+    fn reminisce<'a>(
+        &'a self,
+        run_id: String,
+        embedding: Vec<f32>,
+        limit: u64,
+        query: String,
+    ) -> BoxFuture<'a, Result<Vec<Trace>>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            let response = state
+                .reminisce(embedding, limit, query, run_id)
+                .await
+                .context("reminisce")?;
+            Ok(response.traces)
+        })
+    }
+
+    fn commit<'a>(&'a self, trace: Trace) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut client = self.0.lock().await;
+            let state = client.state.as_mut().ok_or_else(state_disabled)?;
+            state.commit(trace).await.context("commit trace")?;
+            Ok(())
+        })
+    }
+
+    fn subscribe<'a>(
+        &'a self,
+        run_id: String,
+        event_types: Vec<String>,
+        on_event: &'a mut (dyn FnMut(StateEvent) + Send),
+    ) -> BoxFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let mut stream = {
+                let mut client = self.0.lock().await;
+                let state = client.state.as_mut().ok_or_else(state_disabled)?;
+                state
+                    .subscribe(run_id, event_types)
+                    .await
+                    .context("subscribe to state events")?
+            };
+            while let Some(event) = stream.message().await.context("read state event")? {
+                on_event(StateEvent {
+                    event_type: event.r#type,
+                    run_id: event.run_id,
+                    agent_id: event.agent_id,
+                    payload: event.payload,
+                });
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Who this memini instance acts as: `MEMINI_USER`, else the OS user.
+fn rice_user() -> String {
+    ["MEMINI_USER", "USER", "USERNAME"]
+        .iter()
+        .find_map(|key| env::var(key).ok())
+        .map(|user| user.trim().replace('@', "_"))
+        .filter(|user| !user.is_empty())
+        .unwrap_or_else(|| "anonymous".to_string())
+}
+
+/// Tag an agent id or variable source with the acting user
+/// (`memini:chat` → `memini:chat@alice`).  Already-tagged ids are kept.
+fn attributed(id: &str, user: &str) -> String {
+    if id.contains('@') {
+        id.to_string()
+    } else {
+        format!("{id}@{user}")
+    }
+}
+
+/// Split an attributed agent id into `(agent, user)`.
+pub fn attribution(agent_id: &str) -> (&str, Option<&str>) {
+    match agent_id.rsplit_once('@') {
+        Some((agent, user)) if !user.is_empty() => (agent, Some(user)),
+        _ => (agent_id, None),
+    }
 }
 
 fn rice_run_id() -> String {
@@ -415,24 +689,95 @@ pub fn format_memories(traces: &[Trace]) -> String {
             continue;
         }
         let action = trace.action.trim();
-        if action.is_empty() {
-            lines.push(format!("- input: {input} | outcome: {outcome}"));
+        let mut line = if action.is_empty() {
+            format!("- input: {input} | outcome: {outcome}")
         } else {
-            lines.push(format!(
-                "- input: {input} | action: {action} | outcome: {outcome}"
-            ));
+            format!("- input: {input} | action: {action} | outcome: {outcome}")
+        };
+        // How the outcome was reached, so answers can cite it.
+        if let Some(via) =
+            TraceMeta::parse(&trace.reasoning).and_then(|meta| provenance::summary(&meta))
+        {
+            line.push_str(&format!(" | via: {via}"));
         }
+        lines.push(line);
     }
     lines.join("\n")
 }
 
-pub fn system_prompt(persona: &str, require_mcp: bool) -> String {
+/// Keep only episodic memories that share a content word with `query`,
+/// so unrelated traces are not injected just because they ranked top-N.
+pub fn relevant_memories(traces: Vec<Trace>, query: &str) -> Vec<Trace> {
+    let wanted = content_words(query);
+    if wanted.is_empty() {
+        return Vec::new();
+    }
+    traces
+        .into_iter()
+        .filter(|trace| {
+            let text = format!("{} {}", trace.input, trace.outcome);
+            content_words(&text)
+                .iter()
+                .any(|word| wanted.contains(word))
+        })
+        .collect()
+}
+
+fn content_words(text: &str) -> HashSet<String> {
+    const STOPWORDS: &[&str] = &[
+        "about", "could", "from", "have", "please", "should", "that", "there", "this", "what",
+        "when", "where", "which", "with", "would", "your",
+    ];
+    text.split(|ch: char| !ch.is_alphanumeric())
+        .filter(|word| word.chars().count() >= 4)
+        .map(str::to_lowercase)
+        .filter(|word| !STOPWORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// Main-chat system prompt for `agent_name`, honouring `system.md`
+/// overrides.
+pub fn system_prompt(agent_name: &str, persona: &str, require_mcp: bool) -> String {
     let now = chrono::Local::now()
         .format("%A, %B %e, %Y at %H:%M")
         .to_string();
-    crate::prompts::main_chat_system_prompt(persona, &now, require_mcp)
+    crate::prompts::system_override(agent_name, persona, &now)
+        .unwrap_or_else(|| crate::prompts::main_chat_system_prompt(persona, &now, require_mcp))
 }
 
 pub fn agent_id_for(agent_name: &str) -> String {
     format!("{APP_NAME}:{agent_name}")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn attributes_agent_ids_once() {
+        let tagged = attributed("memini:chat", "alice");
+        assert_eq!(tagged, "memini:chat@alice");
+        assert_eq!(attributed(&tagged, "bob"), tagged);
+        assert_eq!(attribution(&tagged), ("memini:chat", Some("alice")));
+        assert_eq!(attribution("memini:chat"), ("memini:chat", None));
+    }
+
+    #[test]
+    fn filters_unrelated_memories() {
+        let trace = |input: &str, outcome: &str| Trace {
+            input: input.to_string(),
+            outcome: outcome.to_string(),
+            ..Trace::default()
+        };
+        let kept = relevant_memories(
+            vec![
+                trace("set up the Postgres migrations", "Done with sqlx."),
+                trace("what's for lunch", "Tacos."),
+            ],
+            "Which postgres version do we run?",
+        );
+        assert_eq!(kept.len(), 1);
+        assert_eq!(kept[0].input, "set up the Postgres migrations");
+        assert!(relevant_memories(kept, "hi!").is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+//! Cost-aware model routing — which OpenAI model handles which kind of work.
+//!
+//! Routes are declared in the `[routing]` section of `memini.toml` and can
+//! be changed at runtime with `/route`.  Once changed, the route table is
+//! persisted in Rice and wins over the file until `/route reset`:
+//!
+//! ```toml
+//! [routing]
+//! chat = "gpt-5"
+//! code = "gpt-5"
+//! agent = "gpt-5-mini"
+//! daemon = "gpt-4o-mini"
+//! summary = "gpt-4o-mini"
+//! embedding = "text-embedding-3-small"
+//! ```
+//!
+//! Task kinds without a route use the active `/model`, except embeddings,
+//! which fall back to `OPENAI_EMBEDDING_MODEL`.  Recipes can pin their own
+//! model with `model:` front matter, which beats the `daemon` route.
+//!
+//! A `[fallback]` chain covers provider errors.  When a request fails with
+//! an unknown model, an overload or the content filter, the models after
+//! the requested one in the chain are tried in order (the whole chain when
+//! the requested model is not in it):
+//!
+//! ```toml
+//! [fallback]
+//! models = ["gpt-5", "gpt-5-mini", "gpt-4o-mini"]
+//! ```
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Kind of work an OpenAI request is made for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TaskKind {
+    /// User-facing chat turns.
+    Chat,
+    /// Chat turns that ask for code to be written or changed.
+    Code,
+    /// Spawned agent windows.
+    Agent,
+    /// Background daemon runs and heartbeats.
+    Daemon,
+    /// Summaries of tool output, results and memory.
+    Summary,
+    /// Embedding vectors for Rice traces.
+    Embedding,
+}
+
+impl TaskKind {
+    pub const ALL: [TaskKind; 6] = [
+        TaskKind::Chat,
+        TaskKind::Code,
+        TaskKind::Agent,
+        TaskKind::Daemon,
+        TaskKind::Summary,
+        TaskKind::Embedding,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            TaskKind::Chat => "chat",
+            TaskKind::Code => "code",
+            TaskKind::Agent => "agent",
+            TaskKind::Daemon => "daemon",
+            TaskKind::Summary => "summary",
+            TaskKind::Embedding => "embedding",
+        }
+    }
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "chat" => Some(TaskKind::Chat),
+            "code" | "edit" | "edits" => Some(TaskKind::Code),
+            "agent" | "agents" => Some(TaskKind::Agent),
+            "daemon" | "daemons" | "heartbeat" => Some(TaskKind::Daemon),
+            "summary" | "summaries" | "summarize" => Some(TaskKind::Summary),
+            "embedding" | "embeddings" | "embed" => Some(TaskKind::Embedding),
+            _ => None,
+        }
+    }
+
+    /// Classify a user chat message as `Code` or `Chat`.
+    pub fn for_message(message: &str) -> Self {
+        let lower = message.to_ascii_lowercase();
+        if lower.contains("```") {
+            return TaskKind::Code;
+        }
+        const CODE_VERBS: &[&str] = &[
+            "refactor",
+            "implement",
+            "fix the",
+            "fix this",
+            "write a function",
+            "write code",
+            "edit ",
+            "rename",
+            "add a test",
+            "unit test",
+            "compile error",
+            "stack trace",
+        ];
+        const CODE_FILES: &[&str] = &[
+            ".rs", ".py", ".ts", ".tsx", ".js", ".go", ".java", ".c", ".cpp", ".toml",
+        ];
+        let mentions_file = lower.split_whitespace().any(|word| {
+            CODE_FILES
+                .iter()
+                .any(|ext| word.trim_end_matches([',', '.', ':', ')']).ends_with(ext))
+        });
+        if mentions_file || CODE_VERBS.iter().any(|verb| lower.contains(verb)) {
+            TaskKind::Code
+        } else {
+            TaskKind::Chat
+        }
+    }
+}
+
+/// Model overrides per [`TaskKind`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModelRoutes {
+    routes: BTreeMap<TaskKind, String>,
+}
+
+/// The `[fallback]` section of `memini.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+pub struct FallbackChain {
+    #[serde(default)]
+    pub models: Vec<String>,
+}
+
+impl FallbackChain {
+    /// Models to try, in order, after `model` fails.
+    pub fn after(&self, model: &str) -> Vec<String> {
+        let rest = match self.models.iter().position(|m| m == model) {
+            Some(index) => &self.models[index + 1..],
+            None => &self.models[..],
+        };
+        rest.iter()
+            .filter(|m| m.as_str() != model && !m.trim().is_empty())
+            .cloned()
+            .collect()
+    }
+}
+
+impl ModelRoutes {
+    pub fn get(&self, task: TaskKind) -> Option<&str> {
+        self.routes.get(&task).map(String::as_str)
+    }
+
+    /// Route `task` to `model`, or clear the route with `None`.
+    pub fn set(&mut self, task: TaskKind, model: Option<String>) {
+        match model.filter(|model| !model.trim().is_empty()) {
+            Some(model) => {
+                self.routes.insert(task, model.trim().to_string());
+            }
+            None => {
+                self.routes.remove(&task);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn parses_routing_section() {
+        let routes = MeminiToml::parse(
+            "[policy]\nrules = []\n\n[routing]\nchat = \"gpt-5\"\ndaemon = \"gpt-4o-mini\"\n",
+        )
+        .expect("parse")
+        .routing;
+        assert_eq!(routes.get(TaskKind::Chat), Some("gpt-5"));
+        assert_eq!(routes.get(TaskKind::Daemon), Some("gpt-4o-mini"));
+        assert_eq!(routes.get(TaskKind::Code), None);
+
+        let mut routes = routes;
+        routes.set(TaskKind::Chat, Some(" o3 ".to_string()));
+        assert_eq!(routes.get(TaskKind::Chat), Some("o3"));
+        routes.set(TaskKind::Chat, None);
+        assert_eq!(routes.get(TaskKind::Chat), None);
+    }
+
+    #[test]
+    fn fallback_chain_continues_after_the_requested_model() {
+        let chain = MeminiToml::parse(
+            "[fallback]\nmodels = [\"gpt-5\", \"gpt-5-mini\", \"gpt-4o-mini\"]\n",
+        )
+        .expect("parse")
+        .fallback;
+        assert_eq!(chain.after("gpt-5"), vec!["gpt-5-mini", "gpt-4o-mini"]);
+        assert_eq!(chain.after("gpt-4o-mini"), Vec::<String>::new());
+        assert_eq!(chain.after("o3").len(), 3);
+        assert!(FallbackChain::default().after("gpt-5").is_empty());
+    }
+
+    #[test]
+    fn classifies_code_requests() {
+        assert_eq!(
+            TaskKind::for_message("refactor the parser in src/app/mod.rs"),
+            TaskKind::Code
+        );
+        assert_eq!(
+            TaskKind::for_message("why does main.rs panic?"),
+            TaskKind::Code
+        );
+        assert_eq!(
+            TaskKind::for_message("what's on my calendar today?"),
+            TaskKind::Chat
+        );
+    }
+}
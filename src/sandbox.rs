@@ -0,0 +1,487 @@
+//! Sandboxing for `workspace_run_command`.
+//!
+//! Configured by the `[sandbox]` section of `memini.toml`:
+//!
+//! ```toml
+//! [sandbox]
+//! scrub_env = true                      # pass only a minimal environment
+//! env_passthrough = ["CARGO_HOME"]      # extra variables to keep
+//! network = false                       # needs the bwrap or docker backend
+//! allowed_binaries = ["cargo", "git", "ls", "rg"]
+//! backend = "auto"                      # none | bwrap | docker | auto
+//! docker_image = "debian:stable-slim"
+//! shell = "powershell"                  # sh | cmd | powershell (default: sh, cmd on Windows)
+//! ```
+//!
+//! Without a `[sandbox]` section commands run exactly as before
+//! (`sh -lc` with the user's environment; `cmd /C` on Windows, see
+//! [`crate::platform`]).  The bwrap and docker backends are Unix-only.  Secrets named in a recipe's
+//! `env:` are added on top in every mode, docker included.  The binary allowlist inspects
+//! each pipeline segment's leading word; it is a guard rail, not a security
+//! boundary — use the `bwrap` or `docker` backend for real isolation.
+
+use std::env;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use portable_pty::CommandBuilder;
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::platform::Shell;
+
+/// Variables kept when `scrub_env` is on (plus `env_passthrough`).
+const BASE_ENV: &[&str] = &["PATH", "HOME", "LANG", "LC_ALL", "TERM", "USER", "TMPDIR"];
+/// Additional variables Windows programs need to start at all.
+const WINDOWS_BASE_ENV: &[&str] = &[
+    "SYSTEMROOT",
+    "WINDIR",
+    "COMSPEC",
+    "PATHEXT",
+    "USERPROFILE",
+    "USERNAME",
+    "TEMP",
+    "TMP",
+    "APPDATA",
+    "LOCALAPPDATA",
+    "PROGRAMDATA",
+];
+const DEFAULT_DOCKER_IMAGE: &str = "debian:stable-slim";
+
+/// Where the command is executed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Backend {
+    #[default]
+    None,
+    Bwrap,
+    Docker,
+    Auto,
+}
+
+/// `[sandbox]` settings.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    pub scrub_env: bool,
+    pub env_passthrough: Vec<String>,
+    pub network: bool,
+    pub allowed_binaries: Vec<String>,
+    pub backend: Backend,
+    pub docker_image: Option<String>,
+    /// Shell for the `none` backend; defaults to [`Shell::native`].
+    pub shell: Option<Shell>,
+}
+
+impl Default for SandboxConfig {
+    fn default() -> Self {
+        SandboxConfig {
+            scrub_env: false,
+            env_passthrough: Vec::new(),
+            network: true,
+            allowed_binaries: Vec::new(),
+            backend: Backend::None,
+            docker_image: None,
+            shell: None,
+        }
+    }
+}
+
+impl SandboxConfig {
+    /// One-line description for `/policy`.
+    pub fn summary(&self) -> String {
+        let binaries = if self.allowed_binaries.is_empty() {
+            "any".to_string()
+        } else {
+            self.allowed_binaries.join(",")
+        };
+        format!(
+            "backend={}, shell={}, scrub_env={}, network={}, binaries={}",
+            backend_label(self.backend),
+            self.shell.unwrap_or_else(Shell::native).label(),
+            self.scrub_env,
+            if self.network { "on" } else { "off" },
+            binaries
+        )
+    }
+
+    /// Reject commands that invoke binaries outside the allowlist.
+    pub fn check_command(&self, command: &str) -> Result<()> {
+        if self.allowed_binaries.is_empty() {
+            return Ok(());
+        }
+        for binary in command_binaries(command) {
+            let name = Path::new(&binary)
+                .file_name()
+                .and_then(|name| name.to_str())
+                .unwrap_or(&binary)
+                .to_string();
+            if !self.allowed_binaries.iter().any(|allowed| allowed == &name) {
+                bail!("Sandbox: binary '{name}' is not in allowed_binaries");
+            }
+        }
+        Ok(())
+    }
+
+    /// Resolve `auto` to a concrete backend based on what is installed.
+    fn resolved_backend(&self) -> Backend {
+        match self.backend {
+            Backend::Auto if cfg!(windows) => Backend::None,
+            Backend::Auto => {
+                if find_in_path("bwrap").is_some() {
+                    Backend::Bwrap
+                } else if find_in_path("docker").is_some() {
+                    Backend::Docker
+                } else {
+                    Backend::None
+                }
+            }
+            other => other,
+        }
+    }
+
+    /// Variable names are case-insensitive on Windows (`Path`, `PATH`).
+    fn env_allowed(&self, key: &str) -> bool {
+        if cfg!(windows) {
+            return BASE_ENV
+                .iter()
+                .chain(WINDOWS_BASE_ENV)
+                .any(|name| name.eq_ignore_ascii_case(key))
+                || self
+                    .env_passthrough
+                    .iter()
+                    .any(|name| name.eq_ignore_ascii_case(key));
+        }
+        BASE_ENV.contains(&key) || self.env_passthrough.iter().any(|name| name == key)
+    }
+}
+
+fn backend_label(backend: Backend) -> &'static str {
+    match backend {
+        Backend::None => "none",
+        Backend::Bwrap => "bwrap",
+        Backend::Docker => "docker",
+        Backend::Auto => "auto",
+    }
+}
+
+/// The `[sandbox]` section of `memini.toml`; an error while the file does
+/// not load, so commands are refused rather than run unsandboxed.
+pub fn load_config() -> Result<SandboxConfig> {
+    Ok(crate::config::checked()?.sandbox.clone())
+}
+
+/// Build the process for `command` according to the sandbox settings,
+/// with `extra_env` set on top.
+pub fn build_command(
+    config: &SandboxConfig,
+    command: &str,
+    workspace_root: &Path,
+    workdir: &Path,
+    extra_env: &[(String, String)],
+) -> Result<Command> {
+    config.check_command(command)?;
+    build(config, Some(command), workspace_root, workdir, extra_env)
+}
+
+/// Build a POSIX `sh` for shell sessions, to be spawned on a
+/// pseudo-terminal.  Check each command sent to it with
+/// [`SandboxConfig::check_command`].
+pub fn build_shell(
+    config: &SandboxConfig,
+    workspace_root: &Path,
+    workdir: &Path,
+    extra_env: &[(String, String)],
+) -> Result<CommandBuilder> {
+    if cfg!(windows) {
+        bail!("Shell sessions need a POSIX sh and are not supported on Windows");
+    }
+    let cmd = build(config, None, workspace_root, workdir, extra_env)?;
+    let cmd = cmd.as_std();
+    let mut shell = CommandBuilder::new(cmd.get_program());
+    shell.args(cmd.get_args());
+    if let Some(dir) = cmd.get_current_dir() {
+        shell.cwd(dir);
+    }
+    if config.scrub_env {
+        shell.env_clear();
+    }
+    for (key, value) in cmd.get_envs() {
+        match value {
+            Some(value) => shell.env(key, value),
+            None => shell.env_remove(key),
+        }
+    }
+    Ok(shell)
+}
+
+/// `sh -c command`, or an interactive shell when `command` is `None`.
+fn build(
+    config: &SandboxConfig,
+    command: Option<&str>,
+    workspace_root: &Path,
+    workdir: &Path,
+    extra_env: &[(String, String)],
+) -> Result<Command> {
+    let backend = config.resolved_backend();
+    if cfg!(windows) && matches!(backend, Backend::Bwrap | Backend::Docker) {
+        bail!(
+            "Sandbox: the {} backend is not supported on Windows",
+            backend_label(backend)
+        );
+    }
+    if !config.network && backend == Backend::None {
+        bail!("Sandbox: network = false requires the bwrap or docker backend (none available)");
+    }
+
+    let kept_env: Vec<(String, String)> = env::vars()
+        .filter(|(key, _)| !config.scrub_env || config.env_allowed(key))
+        .collect();
+
+    let mut cmd = match backend {
+        Backend::None | Backend::Auto => {
+            let mut cmd = match command {
+                Some(command) => config.shell.unwrap_or_else(Shell::native).command(command),
+                None => Command::new("sh"),
+            };
+            cmd.current_dir(workdir);
+            cmd
+        }
+        Backend::Bwrap => {
+            let mut cmd = Command::new("bwrap");
+            cmd.args(bwrap_args(config, workspace_root, workdir))
+                .arg("sh");
+            if let Some(command) = command {
+                cmd.arg("-c").arg(command);
+            }
+            cmd
+        }
+        Backend::Docker => {
+            let mut cmd = Command::new("docker");
+            cmd.args(docker_args(
+                config,
+                workspace_root,
+                workdir,
+                &kept_env,
+                extra_env,
+                command.is_none(),
+            ))
+            .arg("sh");
+            if let Some(command) = command {
+                cmd.arg("-c").arg(command);
+            }
+            cmd
+        }
+    };
+
+    if config.scrub_env {
+        cmd.env_clear();
+        cmd.envs(kept_env);
+    }
+    cmd.envs(extra_env.iter().cloned());
+    cmd.kill_on_drop(true);
+    Ok(cmd)
+}
+
+fn bwrap_args(config: &SandboxConfig, workspace_root: &Path, workdir: &Path) -> Vec<String> {
+    let root = workspace_root.display().to_string();
+    let mut args = vec![
+        "--ro-bind".to_string(),
+        "/".to_string(),
+        "/".to_string(),
+        "--bind".to_string(),
+        root.clone(),
+        root,
+        "--dev".to_string(),
+        "/dev".to_string(),
+        "--proc".to_string(),
+        "/proc".to_string(),
+        "--tmpfs".to_string(),
+        "/tmp".to_string(),
+        "--die-with-parent".to_string(),
+    ];
+    if !config.network {
+        args.push("--unshare-net".to_string());
+    }
+    args.push("--chdir".to_string());
+    args.push(workdir.display().to_string());
+    args
+}
+
+fn docker_args(
+    config: &SandboxConfig,
+    workspace_root: &Path,
+    workdir: &Path,
+    env: &[(String, String)],
+    extra_env: &[(String, String)],
+    interactive: bool,
+) -> Vec<String> {
+    let root = workspace_root.display().to_string();
+    let mut args = vec!["run".to_string(), "--rm".to_string()];
+    if interactive {
+        // Keep stdin open and give the shell session a terminal.
+        args.push("-i".to_string());
+        args.push("-t".to_string());
+    }
+    args.extend([
+        "-v".to_string(),
+        format!("{root}:{root}"),
+        "-w".to_string(),
+        workdir.display().to_string(),
+    ]);
+    if !config.network {
+        args.push("--network".to_string());
+        args.push("none".to_string());
+    }
+    if config.scrub_env {
+        for (key, _) in env {
+            if key != "PATH" && key != "HOME" {
+                args.push("-e".to_string());
+                args.push(key.clone());
+            }
+        }
+    }
+    // `-e NAME` copies the value from the docker client's environment.
+    for (key, _) in extra_env {
+        args.push("-e".to_string());
+        args.push(key.clone());
+    }
+    args.push(
+        config
+            .docker_image
+            .clone()
+            .unwrap_or_else(|| DEFAULT_DOCKER_IMAGE.to_string()),
+    );
+    args
+}
+
+/// Leading word of every pipeline/list segment in a shell command.
+fn command_binaries(command: &str) -> Vec<String> {
+    let normalized = command
+        .replace("&&", "\n")
+        .replace("||", "\n")
+        .replace("$(", "\n")
+        .replace(['|', ';', '&', '`', '(', ')'], "\n");
+    normalized
+        .lines()
+        .filter_map(|segment| {
+            segment
+                .split_whitespace()
+                .find(|word| !is_env_assignment(word))
+                .map(|word| word.trim_matches(|ch| ch == '"' || ch == '\'').to_string())
+        })
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+fn is_env_assignment(word: &str) -> bool {
+    match word.split_once('=') {
+        Some((key, _)) => {
+            !key.is_empty()
+                && key
+                    .chars()
+                    .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        }
+        None => false,
+    }
+}
+
+fn find_in_path(binary: &str) -> Option<PathBuf> {
+    let path = env::var_os("PATH")?;
+    env::split_paths(&path)
+        .map(|dir| dir.join(binary))
+        .find(|candidate| candidate.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::MeminiToml;
+
+    #[test]
+    fn extracts_binaries_from_compound_commands() {
+        assert_eq!(
+            command_binaries("FOO=1 cargo test && git status | head -5; echo $(whoami)"),
+            vec!["cargo", "git", "head", "echo", "whoami"]
+        );
+    }
+
+    #[test]
+    fn allowlist_rejects_unknown_binaries() {
+        let config = SandboxConfig {
+            allowed_binaries: vec!["cargo".to_string(), "git".to_string()],
+            ..SandboxConfig::default()
+        };
+        assert!(config.check_command("cargo build && git diff").is_ok());
+        assert!(config.check_command("cargo build; curl http://x").is_err());
+        assert!(config.check_command("/usr/bin/git log").is_ok());
+    }
+
+    #[test]
+    fn parses_sandbox_section() {
+        let config = MeminiToml::parse(
+            "[sandbox]\nscrub_env = true\nnetwork = false\nbackend = \"bwrap\"\n",
+        )
+        .expect("parse")
+        .sandbox;
+        assert!(config.scrub_env);
+        assert!(!config.network);
+        assert_eq!(config.backend, Backend::Bwrap);
+        assert!(config.env_allowed("PATH"));
+        assert!(!config.env_allowed("OPENAI_API_KEY"));
+
+        let args = bwrap_args(&config, Path::new("/ws"), Path::new("/ws/src"));
+        assert!(args.contains(&"--unshare-net".to_string()));
+        assert_eq!(args.last().map(String::as_str), Some("/ws/src"));
+    }
+
+    #[test]
+    fn shell_is_configurable() {
+        let config = MeminiToml::parse("[sandbox]\nshell = \"pwsh\"\n")
+            .expect("parse")
+            .sandbox;
+        assert_eq!(config.shell, Some(Shell::Powershell));
+        assert!(config.summary().contains("shell=powershell"));
+        let program = config
+            .shell
+            .map(|shell| shell.command("echo hi").as_std().get_program().to_owned());
+        let expected = if cfg!(windows) { "powershell" } else { "pwsh" };
+        assert_eq!(program.as_deref(), Some(std::ffi::OsStr::new(expected)));
+    }
+
+    #[test]
+    fn missing_section_keeps_legacy_behaviour() {
+        let config = MeminiToml::parse("[policy]\nrules = []\n")
+            .expect("parse")
+            .sandbox;
+        assert!(!config.scrub_env);
+        assert!(config.network);
+        assert_eq!(config.backend, Backend::None);
+    }
+
+    #[test]
+    fn recipe_env_survives_a_scrubbed_environment() {
+        let config = SandboxConfig {
+            scrub_env: true,
+            ..SandboxConfig::default()
+        };
+        let extra = [("GITHUB_TOKEN".to_string(), "ghp_test".to_string())];
+        let cmd = build_command(&config, "true", Path::new("/ws"), Path::new("/ws"), &extra)
+            .expect("build");
+        let envs: Vec<_> = cmd.as_std().get_envs().collect();
+        assert!(envs.contains(&(
+            std::ffi::OsStr::new("GITHUB_TOKEN"),
+            Some(std::ffi::OsStr::new("ghp_test"))
+        )));
+
+        let args = docker_args(
+            &config,
+            Path::new("/ws"),
+            Path::new("/ws"),
+            &[],
+            &extra,
+            false,
+        );
+        assert!(args.windows(2).any(|pair| pair == ["-e", "GITHUB_TOKEN"]));
+    }
+}
@@ -1,16 +1,16 @@
 //! Skill import/load support for `/skills import` and prompt injection.
 
-use std::env;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use anyhow::{Context, Result, anyhow, bail};
 use chrono::Utc;
-use directories::BaseDirs;
 use reqwest::header::{ACCEPT, USER_AGENT};
 use serde::{Deserialize, Serialize};
 use url::Url;
 
+use crate::util::memini_home;
+
 const GITHUB_API_BASE: &str = "https://api.github.com";
 const RAW_GITHUB_BASE: &str = "https://raw.githubusercontent.com";
 const REGISTRY_FILENAME: &str = "skills_registry.json";
@@ -378,9 +378,7 @@ fn parse_source(reference: &str) -> Result<SkillSource> {
 }
 
 fn github_client() -> Result<reqwest::Client> {
-    reqwest::Client::builder()
-        .build()
-        .context("Create HTTP client")
+    crate::http::client(crate::http::Interface::Skills).context("Create HTTP client")
 }
 
 async fn fetch_repo_info(
@@ -671,19 +669,7 @@ fn ensure_memini_skills_root() -> Result<()> {
     Ok(())
 }
 
-fn memini_home() -> PathBuf {
-    if let Ok(value) = env::var("MEMINI_HOME") {
-        if !value.trim().is_empty() {
-            return PathBuf::from(value);
-        }
-    }
-    if let Some(base_dirs) = BaseDirs::new() {
-        return base_dirs.home_dir().join("Memini");
-    }
-    PathBuf::from("Memini")
-}
-
-fn memini_skills_root() -> PathBuf {
+pub fn memini_skills_root() -> PathBuf {
     memini_home().join("skills")
 }
 
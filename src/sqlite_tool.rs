@@ -0,0 +1,247 @@
+//! `workspace_sqlite_query` — run SQL against a SQLite database file in
+//! the workspace and return the rows as JSON.
+//!
+//! Databases open read-only unless the call sets `write: true`, and such
+//! calls always wait for the user's approval (see
+//! [`crate::app::policy`]).  `ATTACH` is disabled so a query can't reach
+//! databases outside the workspace, each statement runs under a time
+//! limit, and results stop at a row count and a character budget.
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, bail};
+use rusqlite::types::{Value as SqlValue, ValueRef};
+use rusqlite::{Connection, OpenFlags, limits::Limit, params_from_iter};
+use serde_json::{Value, json};
+
+use crate::constants::{
+    SQLITE_DEFAULT_ROWS, SQLITE_MAX_RESULT_CHARS, SQLITE_MAX_ROWS, SQLITE_QUERY_TIMEOUT_SECS,
+};
+use crate::local_tools::{resolve_workspace_path, to_workspace_relative};
+
+pub const SQLITE_QUERY_TOOL: &str = "workspace_sqlite_query";
+
+/// File extensions the tool will open.
+const EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3", "db3"];
+
+pub fn tool_def() -> Value {
+    json!({
+        "type": "function",
+        "name": SQLITE_QUERY_TOOL,
+        "description": "Run one SQL statement against a SQLite database file (.db, .sqlite, .sqlite3, .db3) in the workspace and return rows as JSON. Read-only unless write is true, which needs the user's approval.",
+        "parameters": {
+            "type": "object",
+            "properties": {
+                "path": {
+                    "type": "string",
+                    "description": "Relative path to the database file."
+                },
+                "sql": {
+                    "type": "string",
+                    "description": "A single SQL statement. Use ? placeholders for params."
+                },
+                "params": {
+                    "type": "array",
+                    "description": "Values bound to the ? placeholders, in order.",
+                    "items": {}
+                },
+                "max_rows": {
+                    "type": "integer",
+                    "description": "Maximum rows to return (default 100, max 1000)."
+                },
+                "write": {
+                    "type": "boolean",
+                    "description": "Open the database for writing (default false). Needs approval."
+                }
+            },
+            "required": ["path", "sql"]
+        }
+    })
+}
+
+/// Whether a call asks to write, which always needs approval.
+pub fn wants_write(arguments: &Value) -> bool {
+    arguments.get("write").and_then(Value::as_bool) == Some(true)
+}
+
+/// Handle a `workspace_sqlite_query` call.  SQLite blocks, so the query
+/// runs off the async runtime.
+pub async fn handle_tool_call(arguments: &Value) -> Result<Value> {
+    let arguments = arguments.clone();
+    tokio::task::spawn_blocking(move || run_query(&arguments))
+        .await
+        .context("SQLite query task failed")?
+}
+
+fn run_query(args: &Value) -> Result<Value> {
+    let path_arg = args
+        .get("path")
+        .and_then(Value::as_str)
+        .filter(|path| !path.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("path is required"))?;
+    let sql = args
+        .get("sql")
+        .and_then(Value::as_str)
+        .filter(|sql| !sql.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("sql is required"))?;
+    let write = wants_write(args);
+    let max_rows = args
+        .get("max_rows")
+        .and_then(Value::as_u64)
+        .unwrap_or(SQLITE_DEFAULT_ROWS as u64)
+        .clamp(1, SQLITE_MAX_ROWS as u64) as usize;
+    let params: Vec<SqlValue> = args
+        .get("params")
+        .and_then(Value::as_array)
+        .map(|values| values.iter().map(to_sql_value).collect())
+        .transpose()?
+        .unwrap_or_default();
+
+    let (workspace_root, path) = resolve_workspace_path(path_arg)?;
+    check_extension(&path)?;
+    if !path.is_file() {
+        bail!("Database does not exist: {}", path.display());
+    }
+    let conn = open(&path, write)?;
+    let mut result = query(&conn, sql, &params, write, max_rows)?;
+    result["path"] = json!(to_workspace_relative(&path, &workspace_root));
+    Ok(result)
+}
+
+fn check_extension(path: &Path) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+    if !EXTENSIONS.contains(&extension.as_str()) {
+        bail!(
+            "Not a SQLite database file: {} (expected .{})",
+            path.display(),
+            EXTENSIONS.join(", .")
+        );
+    }
+    Ok(())
+}
+
+fn open(path: &Path, write: bool) -> Result<Connection> {
+    let mode = if write {
+        OpenFlags::SQLITE_OPEN_READ_WRITE
+    } else {
+        OpenFlags::SQLITE_OPEN_READ_ONLY
+    };
+    let conn = Connection::open_with_flags(path, mode | OpenFlags::SQLITE_OPEN_NO_MUTEX)
+        .with_context(|| format!("Open {}", path.display()))?;
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    conn.busy_timeout(Duration::from_secs(2))?;
+    let deadline = Instant::now() + Duration::from_secs(SQLITE_QUERY_TIMEOUT_SECS);
+    conn.progress_handler(10_000, Some(move || Instant::now() > deadline));
+    Ok(conn)
+}
+
+/// Run `sql` on `conn`: rows for a query, the change count otherwise.
+fn query(
+    conn: &Connection,
+    sql: &str,
+    params: &[SqlValue],
+    write: bool,
+    max_rows: usize,
+) -> Result<Value> {
+    let mut stmt = conn.prepare(sql).context("Prepare SQL")?;
+    if !write && !stmt.readonly() {
+        bail!("This statement changes the database; call again with write: true (needs approval).");
+    }
+    if stmt.column_count() == 0 {
+        let changed = stmt
+            .execute(params_from_iter(params))
+            .context("Execute SQL")?;
+        return Ok(json!({ "rows_changed": changed, "status": "ok" }));
+    }
+
+    let columns: Vec<String> = stmt.column_names().iter().map(|c| c.to_string()).collect();
+    let mut rows = stmt.query(params_from_iter(params)).context("Run query")?;
+    let mut out = Vec::new();
+    let mut chars = 0;
+    let mut truncated = false;
+    while let Some(row) = rows.next().context("Read row")? {
+        if out.len() >= max_rows {
+            truncated = true;
+            break;
+        }
+        let values: Vec<Value> = (0..columns.len())
+            .map(|idx| row.get_ref(idx).map(to_json))
+            .collect::<rusqlite::Result<_>>()?;
+        let row = Value::Array(values);
+        chars += row.to_string().len();
+        if chars > SQLITE_MAX_RESULT_CHARS {
+            truncated = true;
+            break;
+        }
+        out.push(row);
+    }
+    Ok(json!({
+        "columns": columns,
+        "rows": out,
+        "row_count": out.len(),
+        "truncated": truncated,
+    }))
+}
+
+fn to_sql_value(value: &Value) -> Result<SqlValue> {
+    Ok(match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(flag) => SqlValue::Integer(i64::from(*flag)),
+        Value::Number(number) => match number.as_i64() {
+            Some(int) => SqlValue::Integer(int),
+            None => SqlValue::Real(number.as_f64().unwrap_or_default()),
+        },
+        Value::String(text) => SqlValue::Text(text.clone()),
+        _ => bail!("params must be strings, numbers, booleans or null"),
+    })
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(int) => json!(int),
+        ValueRef::Real(real) => json!(real),
+        ValueRef::Text(text) => json!(String::from_utf8_lossy(text)),
+        ValueRef::Blob(blob) => json!(format!("<blob {} bytes>", blob.len())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_rows_caps_them_and_refuses_writes_without_flag() {
+        let conn = Connection::open_in_memory().expect("open");
+        conn.execute_batch(
+            "CREATE TABLE notes (id INTEGER, body TEXT, data BLOB);
+             INSERT INTO notes VALUES (1, 'alpha', x'0102'), (2, 'beta', NULL), (3, 'gamma', NULL);",
+        )
+        .expect("seed");
+
+        let result = query(
+            &conn,
+            "SELECT id, body, data FROM notes WHERE id >= ? ORDER BY id",
+            &[SqlValue::Integer(1)],
+            false,
+            2,
+        )
+        .expect("query");
+        assert_eq!(result["columns"], json!(["id", "body", "data"]));
+        assert_eq!(result["rows"][0], json!([1, "alpha", "<blob 2 bytes>"]));
+        assert_eq!(result["row_count"], 2);
+        assert_eq!(result["truncated"], true);
+
+        let err = query(&conn, "DELETE FROM notes", &[], false, 10).expect_err("read-only");
+        assert!(err.to_string().contains("write: true"));
+        let changed = query(&conn, "DELETE FROM notes WHERE id = 3", &[], true, 10).expect("write");
+        assert_eq!(changed["rows_changed"], 1);
+        assert!(check_extension(Path::new("data/app.DB")).is_ok());
+        assert!(check_extension(Path::new("notes.txt")).is_err());
+    }
+}
@@ -0,0 +1,303 @@
+//! Tracing spans, per-subsystem latency stats, and optional OTLP export.
+//!
+//! The OpenAI client, MCP calls, Rice operations, chat turns, agent windows
+//! and daemon runs open `tracing` spans named `<subsystem>.<operation>`
+//! (`openai.response`, `mcp.call_tool`, `rice.reminisce`, `daemon.run`, …).
+//! [`StatsLayer`] times every span from creation to close into an
+//! in-process registry that `/stats` renders as request counts and p50/p95
+//! latencies.
+//!
+//! Builds with the `otlp` feature also export the spans over OTLP (gRPC)
+//! when `OTEL_EXPORTER_OTLP_ENDPOINT` is set; the other standard `OTEL_*`
+//! variables (headers, timeout, `OTEL_SERVICE_NAME`) apply as usual.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use anyhow::Result;
+use tracing::Subscriber;
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+
+use crate::constants::STATS_SAMPLE_WINDOW;
+use crate::util::env_first;
+
+/// Install the global subscriber.  Call from inside the tokio runtime: the
+/// OTLP exporter spawns its batch task there.  Errors only concern OTLP;
+/// `/stats` works regardless.
+pub fn init() -> Result<()> {
+    let registry = tracing_subscriber::registry().with(StatsLayer);
+    if otlp_endpoint().is_none() {
+        let _ = registry.try_init();
+        return Ok(());
+    }
+    match otlp::layer() {
+        Ok(layer) => {
+            let _ = registry.with(layer).try_init();
+            Ok(())
+        }
+        Err(err) => {
+            let _ = registry.try_init();
+            Err(err)
+        }
+    }
+}
+
+/// Flush and stop the OTLP exporter, if one is running.
+pub fn shutdown() {
+    otlp::shutdown();
+}
+
+/// The configured OTLP endpoint.
+pub fn otlp_endpoint() -> Option<String> {
+    env_first(&[
+        "OTEL_EXPORTER_OTLP_TRACES_ENDPOINT",
+        "OTEL_EXPORTER_OTLP_ENDPOINT",
+    ])
+}
+
+/// Whether spans are being exported over OTLP.
+pub fn otlp_active() -> bool {
+    otlp::active()
+}
+
+#[cfg(not(feature = "otlp"))]
+mod otlp {
+    use anyhow::{Result, bail};
+    use tracing_subscriber::layer::Identity;
+
+    pub fn layer() -> Result<Identity> {
+        bail!("OTEL_EXPORTER_OTLP_ENDPOINT is set but this build lacks the `otlp` feature")
+    }
+
+    pub fn active() -> bool {
+        false
+    }
+
+    pub fn shutdown() {}
+}
+
+#[cfg(feature = "otlp")]
+mod otlp {
+    use std::sync::OnceLock;
+
+    use anyhow::{Context, Result};
+    use opentelemetry::KeyValue;
+    use opentelemetry::trace::TracerProvider as _;
+    use opentelemetry_sdk::trace::{Tracer, TracerProvider};
+    use tracing::Subscriber;
+    use tracing_opentelemetry::OpenTelemetryLayer;
+    use tracing_subscriber::registry::LookupSpan;
+
+    use crate::constants::APP_NAME;
+    use crate::util::env_first;
+
+    static PROVIDER: OnceLock<TracerProvider> = OnceLock::new();
+
+    pub fn layer<S>() -> Result<OpenTelemetryLayer<S, Tracer>>
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        let exporter = opentelemetry_otlp::SpanExporter::builder()
+            .with_tonic()
+            .build()
+            .context("build OTLP span exporter")?;
+        let service = env_first(&["OTEL_SERVICE_NAME"]).unwrap_or_else(|| APP_NAME.to_string());
+        let provider = TracerProvider::builder()
+            .with_batch_exporter(exporter, opentelemetry_sdk::runtime::Tokio)
+            .with_resource(opentelemetry_sdk::Resource::new([KeyValue::new(
+                "service.name",
+                service,
+            )]))
+            .build();
+        let tracer = provider.tracer(APP_NAME);
+        let _ = PROVIDER.set(provider);
+        Ok(tracing_opentelemetry::layer().with_tracer(tracer))
+    }
+
+    pub fn active() -> bool {
+        PROVIDER.get().is_some()
+    }
+
+    pub fn shutdown() {
+        if let Some(provider) = PROVIDER.get() {
+            let _ = provider.shutdown();
+        }
+    }
+}
+
+// ── Latency registry ─────────────────────────────────────────────────
+
+/// Recent durations for one span name.
+#[derive(Default)]
+struct Samples {
+    count: u64,
+    recent: VecDeque<Duration>,
+}
+
+fn samples() -> &'static Mutex<BTreeMap<&'static str, Samples>> {
+    static SAMPLES: OnceLock<Mutex<BTreeMap<&'static str, Samples>>> = OnceLock::new();
+    SAMPLES.get_or_init(|| Mutex::new(BTreeMap::new()))
+}
+
+fn record(name: &'static str, elapsed: Duration) {
+    if let Ok(mut all) = samples().lock() {
+        let entry = all.entry(name).or_default();
+        entry.count += 1;
+        if entry.recent.len() == STATS_SAMPLE_WINDOW {
+            entry.recent.pop_front();
+        }
+        entry.recent.push_back(elapsed);
+    }
+}
+
+/// Forget everything recorded so far (`/stats reset`).
+pub fn reset() {
+    if let Ok(mut all) = samples().lock() {
+        all.clear();
+    }
+}
+
+/// Request count and latency percentiles over the recent samples.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LatencyStats {
+    pub count: u64,
+    pub p50: Duration,
+    pub p95: Duration,
+}
+
+impl LatencyStats {
+    fn from_samples(count: u64, samples: &mut [Duration]) -> Self {
+        samples.sort_unstable();
+        Self {
+            count,
+            p50: percentile(samples, 50),
+            p95: percentile(samples, 95),
+        }
+    }
+}
+
+/// Nearest-rank percentile of sorted samples.
+fn percentile(sorted: &[Duration], pct: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// Stats for one subsystem (the span-name prefix) and its operations.
+#[derive(Clone, Debug)]
+pub struct SubsystemStats {
+    pub name: String,
+    pub total: LatencyStats,
+    pub operations: Vec<(String, LatencyStats)>,
+}
+
+/// Everything recorded so far, grouped by subsystem.
+pub fn snapshot() -> Vec<SubsystemStats> {
+    let Ok(all) = samples().lock() else {
+        return Vec::new();
+    };
+    let mut grouped: BTreeMap<&str, Vec<(&str, &Samples)>> = BTreeMap::new();
+    for (name, samples) in all.iter() {
+        let subsystem = name.split_once('.').map_or(*name, |(prefix, _)| prefix);
+        grouped.entry(subsystem).or_default().push((name, samples));
+    }
+    grouped
+        .into_iter()
+        .map(|(subsystem, ops)| {
+            let count = ops.iter().map(|(_, samples)| samples.count).sum();
+            let mut durations: Vec<Duration> = ops
+                .iter()
+                .flat_map(|(_, samples)| samples.recent.iter().copied())
+                .collect();
+            SubsystemStats {
+                name: subsystem.to_string(),
+                total: LatencyStats::from_samples(count, &mut durations),
+                operations: ops
+                    .into_iter()
+                    .map(|(name, samples)| {
+                        let mut recent: Vec<Duration> = samples.recent.iter().copied().collect();
+                        (
+                            name.to_string(),
+                            LatencyStats::from_samples(samples.count, &mut recent),
+                        )
+                    })
+                    .collect(),
+            }
+        })
+        .collect()
+}
+
+// ── Layer ────────────────────────────────────────────────────────────
+
+/// Times spans from creation to close into the latency registry.
+pub struct StatsLayer;
+
+struct Opened(Instant);
+
+impl<S> Layer<S> for StatsLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Opened(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let opened = span.extensions().get::<Opened>().map(|opened| opened.0);
+        if let Some(opened) = opened {
+            record(span.name(), opened.elapsed());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let mut samples: Vec<Duration> = (1..=20).rev().map(Duration::from_millis).collect();
+        let stats = LatencyStats::from_samples(20, &mut samples);
+        assert_eq!(stats.p50, Duration::from_millis(10));
+        assert_eq!(stats.p95, Duration::from_millis(19));
+        assert_eq!(percentile(&[], 95), Duration::ZERO);
+    }
+
+    #[test]
+    fn groups_spans_by_subsystem() {
+        let subscriber = tracing_subscriber::registry().with(StatsLayer);
+        tracing::subscriber::with_default(subscriber, || {
+            for _ in 0..3 {
+                let _span = tracing::info_span!("telemetrytest.call_tool").entered();
+            }
+            let _span = tracing::info_span!("telemetrytest.connect").entered();
+        });
+
+        let stats = snapshot();
+        let group = stats
+            .iter()
+            .find(|group| group.name == "telemetrytest")
+            .expect("subsystem recorded");
+        assert_eq!(group.total.count, 4);
+        assert_eq!(
+            group
+                .operations
+                .iter()
+                .map(|(name, stats)| (name.as_str(), stats.count))
+                .collect::<Vec<_>>(),
+            vec![("telemetrytest.call_tool", 3), ("telemetrytest.connect", 1)]
+        );
+    }
+}
@@ -0,0 +1,349 @@
+//! Self-update from GitHub releases (`/update`, `memini update`).
+//!
+//! The latest release of [`DEFAULT_UPDATE_REPO`] (or `MEMINI_UPDATE_REPO`)
+//! is compared with [`APP_VERSION`].  A release must carry a binary for
+//! this platform, named with the OS and architecture
+//! (`memini-x86_64-linux`, `memini-aarch64-macos`,
+//! `memini-x86_64-windows.exe`), plus its SHA-256: a `<asset>.sha256` file
+//! or a `SHA256SUMS` listing.  The download is verified before it replaces
+//! the running executable.  The old binary is kept beside it as
+//! `<exe>.old` until the new one answers `--version`, and is moved back if
+//! any step fails.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow, bail};
+use reqwest::header::{ACCEPT, USER_AGENT};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::constants::{APP_VERSION, DEFAULT_UPDATE_REPO};
+use crate::util::env_first;
+
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    /// Release notes (Markdown).
+    #[serde(default)]
+    pub body: Option<String>,
+    #[serde(default)]
+    pub html_url: Option<String>,
+    #[serde(default)]
+    assets: Vec<Asset>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String,
+}
+
+impl Release {
+    pub fn version(&self) -> &str {
+        self.tag_name.trim_start_matches('v')
+    }
+
+    pub fn is_newer(&self) -> bool {
+        is_newer(self.version(), APP_VERSION)
+    }
+}
+
+/// Outcome of [`run`].
+pub enum UpdateOutcome {
+    UpToDate,
+    /// `check_only` found a newer release.
+    Available(Release),
+    Installed {
+        release: Release,
+        path: PathBuf,
+    },
+}
+
+fn repo() -> String {
+    env_first(&["MEMINI_UPDATE_REPO"]).unwrap_or_else(|| DEFAULT_UPDATE_REPO.to_string())
+}
+
+fn client() -> Result<reqwest::Client> {
+    reqwest::Client::builder()
+        .build()
+        .context("build HTTP client")
+}
+
+pub async fn latest_release() -> Result<Release> {
+    let url = format!("https://api.github.com/repos/{}/releases/latest", repo());
+    client()?
+        .get(&url)
+        .header(USER_AGENT, "memini-update")
+        .header(ACCEPT, "application/vnd.github+json")
+        .send()
+        .await
+        .with_context(|| format!("GET {url}"))?
+        .error_for_status()
+        .context("fetch latest release")?
+        .json::<Release>()
+        .await
+        .context("parse release")
+}
+
+/// Check for a newer release and, unless `check_only`, install it.
+/// `progress` receives one line per step.
+pub async fn run(check_only: bool, progress: impl Fn(String)) -> Result<UpdateOutcome> {
+    progress(format!("Checking {} for releases…", repo()));
+    let release = latest_release().await?;
+    if !release.is_newer() {
+        return Ok(UpdateOutcome::UpToDate);
+    }
+    if check_only {
+        return Ok(UpdateOutcome::Available(release));
+    }
+    let asset = pick_asset(
+        &release.assets,
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+    )
+    .ok_or_else(|| {
+        anyhow!(
+            "release {} has no binary for {}-{}",
+            release.tag_name,
+            std::env::consts::ARCH,
+            std::env::consts::OS
+        )
+    })?
+    .clone();
+
+    progress(format!("Downloading {}…", asset.name));
+    let http = client()?;
+    let binary = download(&http, &asset.browser_download_url).await?;
+    let expected = expected_checksum(&http, &release.assets, &asset.name).await?;
+    let actual = sha256_hex(&binary);
+    if !actual.eq_ignore_ascii_case(&expected) {
+        bail!(
+            "checksum mismatch for {}: expected {expected}, got {actual}",
+            asset.name
+        );
+    }
+    progress("Checksum verified; replacing the executable…".to_string());
+
+    let exe = std::env::current_exe().context("locate current executable")?;
+    swap_executable(&exe, &binary, verify_binary)?;
+    Ok(UpdateOutcome::Installed { release, path: exe })
+}
+
+async fn download(http: &reqwest::Client, url: &str) -> Result<Vec<u8>> {
+    let bytes = http
+        .get(url)
+        .header(USER_AGENT, "memini-update")
+        .send()
+        .await
+        .with_context(|| format!("GET {url}"))?
+        .error_for_status()
+        .with_context(|| format!("download {url}"))?
+        .bytes()
+        .await
+        .context("read download")?;
+    Ok(bytes.to_vec())
+}
+
+/// SHA-256 for `name` from `<name>.sha256` or `SHA256SUMS`.
+async fn expected_checksum(http: &reqwest::Client, assets: &[Asset], name: &str) -> Result<String> {
+    let sidecar = format!("{name}.sha256");
+    let source = assets
+        .iter()
+        .find(|asset| asset.name == sidecar)
+        .or_else(|| assets.iter().find(|asset| asset.name == CHECKSUMS_ASSET))
+        .ok_or_else(|| {
+            anyhow!("release has no {sidecar} or {CHECKSUMS_ASSET}; refusing to install")
+        })?;
+    let text =
+        String::from_utf8_lossy(&download(http, &source.browser_download_url).await?).into_owned();
+    parse_checksum(&text, name).ok_or_else(|| anyhow!("no checksum for {name} in {}", source.name))
+}
+
+/// The hash for `name` in `sha256sum` output (`<hash>  <name>` lines), or
+/// a lone hash.
+fn parse_checksum(text: &str, name: &str) -> Option<String> {
+    let is_hash = |word: &str| word.len() == 64 && word.chars().all(|ch| ch.is_ascii_hexdigit());
+    let mut lone = None;
+    for line in text.lines() {
+        let mut words = line.split_whitespace();
+        let Some(hash) = words.next().filter(|word| is_hash(word)) else {
+            continue;
+        };
+        match words.next() {
+            Some(file) if file.trim_start_matches('*') == name => return Some(hash.to_string()),
+            Some(_) => {}
+            None => lone = Some(hash.to_string()),
+        }
+    }
+    lone
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// The binary for `os`/`arch`: the name mentions both, and it is not a
+/// checksum file.  macOS builds may say `darwin` or `apple`.
+fn pick_asset<'a>(assets: &'a [Asset], os: &str, arch: &str) -> Option<&'a Asset> {
+    let os_names: &[&str] = match os {
+        "macos" => &["macos", "darwin", "apple"],
+        "linux" => &["linux"],
+        "windows" => &["windows"],
+        _ => return None,
+    };
+    let arch_names: &[&str] = match arch {
+        "x86_64" => &["x86_64", "amd64"],
+        "aarch64" => &["aarch64", "arm64"],
+        _ => return None,
+    };
+    assets.iter().find(|asset| {
+        let name = asset.name.to_ascii_lowercase();
+        !name.ends_with(".sha256")
+            && name != CHECKSUMS_ASSET.to_ascii_lowercase()
+            && os_names.iter().any(|os| name.contains(os))
+            && arch_names.iter().any(|arch| name.contains(arch))
+    })
+}
+
+/// Replace `exe` with `binary`, keeping `<exe>.old` until `verify` accepts
+/// the new file, and restoring it on any failure.
+fn swap_executable(exe: &Path, binary: &[u8], verify: impl Fn(&Path) -> Result<()>) -> Result<()> {
+    let staged = exe.with_extension("new");
+    let backup = exe.with_extension("old");
+    fs::write(&staged, binary).with_context(|| format!("write {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))
+            .with_context(|| format!("chmod {}", staged.display()))?;
+    }
+    let _ = fs::remove_file(&backup);
+    // Renaming the running binary works on Windows too, unlike deleting it.
+    if let Err(err) = fs::rename(exe, &backup) {
+        let _ = fs::remove_file(&staged);
+        return Err(err).with_context(|| format!("move {} aside", exe.display()));
+    }
+    let installed = fs::rename(&staged, exe)
+        .with_context(|| format!("install {}", exe.display()))
+        .and_then(|()| verify(exe));
+    match installed {
+        Ok(()) => {
+            // Windows keeps the running image locked; it is removed next time.
+            let _ = fs::remove_file(&backup);
+            Ok(())
+        }
+        Err(err) => {
+            let _ = fs::remove_file(exe);
+            let _ = fs::remove_file(&staged);
+            fs::rename(&backup, exe).with_context(|| {
+                format!("restore {} after failed update ({err:#})", exe.display())
+            })?;
+            Err(err.context("update rolled back"))
+        }
+    }
+}
+
+/// The new binary must start and print a version.
+fn verify_binary(exe: &Path) -> Result<()> {
+    let output = Command::new(exe)
+        .arg("--version")
+        .output()
+        .with_context(|| format!("run {} --version", exe.display()))?;
+    if !output.status.success() || output.stdout.is_empty() {
+        bail!("new binary failed `--version`");
+    }
+    Ok(())
+}
+
+/// `a` is a later dotted version than `b` (numeric parts; pre-release
+/// suffixes are ignored).
+fn is_newer(a: &str, b: &str) -> bool {
+    let parts = |version: &str| -> Vec<u64> {
+        version
+            .split(['-', '+'])
+            .next()
+            .unwrap_or("")
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    let (a, b) = (parts(a), parts(b));
+    for index in 0..a.len().max(b.len()) {
+        let (x, y) = (
+            a.get(index).copied().unwrap_or(0),
+            b.get(index).copied().unwrap_or(0),
+        );
+        if x != y {
+            return x > y;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(name: &str) -> Asset {
+        Asset {
+            name: name.to_string(),
+            browser_download_url: format!("https://example.test/{name}"),
+        }
+    }
+
+    #[test]
+    fn versions_and_assets_are_matched() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(is_newer("1.0", "0.9.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("0.1.0-rc1", "0.1.0"));
+
+        let assets = vec![
+            asset("memini-x86_64-linux.sha256"),
+            asset("memini-x86_64-linux"),
+            asset("memini-arm64-darwin"),
+            asset("SHA256SUMS"),
+        ];
+        let pick = |os, arch| pick_asset(&assets, os, arch).map(|a| a.name.as_str());
+        assert_eq!(pick("linux", "x86_64"), Some("memini-x86_64-linux"));
+        assert_eq!(pick("macos", "aarch64"), Some("memini-arm64-darwin"));
+        assert_eq!(pick("windows", "x86_64"), None);
+    }
+
+    #[test]
+    fn checksums_parse_from_sums_files() {
+        let hash = "a".repeat(64);
+        let other = "b".repeat(64);
+        let sums = format!("{other}  memini-x86_64-windows.exe\n{hash} *memini-x86_64-linux\n");
+        assert_eq!(
+            parse_checksum(&sums, "memini-x86_64-linux"),
+            Some(hash.clone())
+        );
+        assert_eq!(parse_checksum(&format!("{hash}\n"), "anything"), Some(hash));
+        assert_eq!(parse_checksum("not a hash", "memini"), None);
+    }
+
+    #[test]
+    fn failed_verification_restores_the_old_binary() {
+        let dir = std::env::temp_dir().join(format!("memini-update-{}", std::process::id()));
+        fs::create_dir_all(&dir).expect("temp dir");
+        let exe = dir.join("memini");
+        fs::write(&exe, b"old").expect("write old");
+
+        let err = swap_executable(&exe, b"new", |_| bail!("broken")).unwrap_err();
+        assert!(format!("{err:#}").contains("rolled back"));
+        assert_eq!(fs::read(&exe).expect("read"), b"old");
+
+        swap_executable(&exe, b"new", |_| Ok(())).expect("swap");
+        assert_eq!(fs::read(&exe).expect("read"), b"new");
+        assert!(!exe.with_extension("old").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
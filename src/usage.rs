@@ -0,0 +1,118 @@
+//! Model token usage for the current day and the last provider error.
+//!
+//! Every Responses API attempt made through [`crate::openai::OpenAiClient`]
+//! is recorded here — answers add their `usage` block to today's totals,
+//! failures replace the last error.  `/status` reads both.  Totals cover
+//! this process only and start over at local midnight.
+
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::Result;
+use chrono::{DateTime, Local, NaiveDate};
+use serde_json::Value;
+
+/// Requests and tokens since local midnight.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DailyUsage {
+    pub requests: u64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProviderError {
+    pub at: DateTime<Local>,
+    pub model: String,
+    pub message: String,
+}
+
+#[derive(Default)]
+struct Ledger {
+    day: Option<NaiveDate>,
+    today: DailyUsage,
+    last_error: Option<ProviderError>,
+}
+
+impl Ledger {
+    fn add(&mut self, day: NaiveDate, response: &Value) {
+        if self.day != Some(day) {
+            self.day = Some(day);
+            self.today = DailyUsage::default();
+        }
+        let tokens = |key: &str| {
+            response
+                .pointer(&format!("/usage/{key}"))
+                .and_then(Value::as_u64)
+                .unwrap_or(0)
+        };
+        self.today.requests += 1;
+        self.today.input_tokens += tokens("input_tokens");
+        self.today.output_tokens += tokens("output_tokens");
+    }
+}
+
+fn ledger() -> &'static Mutex<Ledger> {
+    static LEDGER: OnceLock<Mutex<Ledger>> = OnceLock::new();
+    LEDGER.get_or_init(|| Mutex::new(Ledger::default()))
+}
+
+/// Record one model request against `model`.
+pub fn record(model: &str, result: &Result<Value>) {
+    let Ok(mut ledger) = ledger().lock() else {
+        return;
+    };
+    let now = Local::now();
+    match result {
+        Ok(response) => ledger.add(now.date_naive(), response),
+        Err(err) => {
+            ledger.last_error = Some(ProviderError {
+                at: now,
+                model: model.to_string(),
+                message: format!("{err:#}"),
+            });
+        }
+    }
+}
+
+pub fn today() -> DailyUsage {
+    let Ok(ledger) = ledger().lock() else {
+        return DailyUsage::default();
+    };
+    if ledger.day == Some(Local::now().date_naive()) {
+        ledger.today
+    } else {
+        DailyUsage::default()
+    }
+}
+
+pub fn last_error() -> Option<ProviderError> {
+    ledger()
+        .lock()
+        .ok()
+        .and_then(|ledger| ledger.last_error.clone())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn totals_start_over_on_a_new_day() {
+        let mut ledger = Ledger::default();
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).expect("date");
+        let response = json!({"usage": {"input_tokens": 1200, "output_tokens": 80}});
+        ledger.add(monday, &response);
+        ledger.add(monday, &json!({}));
+        assert_eq!(
+            ledger.today,
+            DailyUsage {
+                requests: 2,
+                input_tokens: 1200,
+                output_tokens: 80
+            }
+        );
+        ledger.add(monday.succ_opt().expect("tuesday"), &response);
+        assert_eq!(ledger.today.requests, 1);
+    }
+}
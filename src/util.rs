@@ -1,6 +1,9 @@
 //! Small utility helpers shared across the crate.
 
 use std::env;
+use std::path::PathBuf;
+
+use directories::BaseDirs;
 
 /// Return the first non-empty environment variable from `keys`, or `None`.
 pub fn env_first(keys: &[&str]) -> Option<String> {
@@ -14,6 +17,15 @@ pub fn env_first(keys: &[&str]) -> Option<String> {
     None
 }
 
+/// Compare two secrets without stopping at the first differing byte, so
+/// response timing does not reveal how much of a guess was right.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
 /// Normalise a URL by prepending `http://` or `https://` when the scheme is missing.
 pub fn normalize_url(raw: &str) -> String {
     if raw.contains("://") {
@@ -26,3 +38,26 @@ pub fn normalize_url(raw: &str) -> String {
     };
     format!("{scheme}://{raw}")
 }
+
+/// Locate `memini.toml`: `MEMINI_CONFIG`, then `./memini.toml`, then
+/// `$MEMINI_HOME/memini.toml` (default `~/Memini`). `None` if none exist.
+pub fn memini_config_path() -> Option<PathBuf> {
+    if let Some(path) = env_first(&["MEMINI_CONFIG"]) {
+        return Some(PathBuf::from(path.trim()));
+    }
+    let local = PathBuf::from("memini.toml");
+    if local.is_file() {
+        return Some(local);
+    }
+    let candidate = memini_home().join("memini.toml");
+    candidate.is_file().then_some(candidate)
+}
+
+/// `$MEMINI_HOME`, defaulting to `~/Memini` (`./Memini` without a home
+/// directory).
+pub fn memini_home() -> PathBuf {
+    env_first(&["MEMINI_HOME"])
+        .map(|value| PathBuf::from(value.trim()))
+        .or_else(|| BaseDirs::new().map(|dirs| dirs.home_dir().join("Memini")))
+        .unwrap_or_else(|| PathBuf::from("Memini"))
+}